@@ -0,0 +1,103 @@
+/**
+ * BioChain ID derivation
+ *
+ * Mirrors the `biochain_ids` contract crate so back-office systems can
+ * precompute and verify the IDs emitted by the contracts:
+ * - Purchase IDs (DatasetPurchased.purchase_id, PurchaseRecord.tx_hash)
+ * - Receipt hashes (DatasetPurchased.receipt_hash)
+ * - Payout IDs (ContributorRewarded.payout_id)
+ *
+ * Every ID is the SHA-256 of a fixed-length preimage: "BCID", the schema
+ * version, a kind byte, then the fields below (integers big-endian).
+ * Sequences come from DatasetMarketplace.get_purchase_count and
+ * RevenueSplitter.get_payout_count, or from the events themselves.
+ */
+
+import { createHash } from 'crypto'
+
+const MAGIC = Buffer.from('BCID', 'ascii')
+const SCHEMA_VERSION = 1
+const STRKEY_LEN = 56
+
+const KIND_PURCHASE = 1
+const KIND_PAYOUT = 2
+const KIND_RECEIPT = 3
+
+const sha256 = (data: Buffer): Buffer => createHash('sha256').update(data).digest()
+
+const header = (kind: number): Buffer => Buffer.concat([MAGIC, Buffer.from([SCHEMA_VERSION, kind])])
+
+const strkey = (address: string): Buffer => {
+  if (address.length !== STRKEY_LEN || !/^[GC][A-Z2-7]+$/.test(address)) {
+    throw new Error(`Invalid Stellar address: ${address}`)
+  }
+  return Buffer.from(address, 'ascii')
+}
+
+const u64 = (value: bigint): Buffer => {
+  const out = Buffer.alloc(8)
+  out.writeBigUInt64BE(value)
+  return out
+}
+
+const i128 = (value: bigint): Buffer => {
+  if (value < 0n) {
+    throw new Error(`Invalid amount: ${value}`)
+  }
+  const out = Buffer.alloc(16)
+  out.writeBigUInt64BE(value >> 64n)
+  out.writeBigUInt64BE(value & 0xffffffffffffffffn, 8)
+  return out
+}
+
+/**
+ * Key of a dataset in ID preimages: SHA-256 of the dataset ID bytes
+ */
+export const datasetKey = (datasetId: Buffer | string): Buffer =>
+  sha256(typeof datasetId === 'string' ? Buffer.from(datasetId, 'utf8') : datasetId)
+
+/**
+ * Purchase ID of the `sequence`-th purchase (0-based) of a dataset
+ */
+export const derivePurchaseId = (
+  datasetId: Buffer | string,
+  buyer: string,
+  sequence: bigint
+): Buffer =>
+  sha256(Buffer.concat([header(KIND_PURCHASE), datasetKey(datasetId), strkey(buyer), u64(sequence)]))
+
+/**
+ * Payout ID of a contributor's share in the `sequence`-th payout of a dataset
+ */
+export const derivePayoutId = (
+  datasetId: Buffer | string,
+  sequence: bigint,
+  recipient: string,
+  amount: bigint
+): Buffer =>
+  sha256(
+    Buffer.concat([
+      header(KIND_PAYOUT),
+      datasetKey(datasetId),
+      u64(sequence),
+      strkey(recipient),
+      i128(amount),
+    ])
+  )
+
+/**
+ * Receipt hash of a purchase, from the address that paid and the ledger timestamp
+ */
+export const deriveReceiptHash = (
+  purchaseId: Buffer,
+  payer: string,
+  amount: bigint,
+  timestamp: bigint
+): Buffer => {
+  if (purchaseId.length !== 32) {
+    throw new Error('Purchase ID must be 32 bytes')
+  }
+  return sha256(
+    Buffer.concat([header(KIND_RECEIPT), purchaseId, strkey(payer), i128(amount), u64(timestamp)])
+  )
+}
@@ -196,6 +196,7 @@ export const registerDatasetOnChain = async (params: {
  * Calls DatasetMarketplace.purchase_dataset() with:
  * - dataset_id (Bytes)
  * - buyer (Address)
+ * - promo_code (Option<Bytes>, always none here)
  * 
  * This will automatically trigger RevenueSplitter to distribute payouts.
  * 
@@ -235,6 +236,7 @@ export const purchaseDatasetOnChain = async (params: {
     const args = [
       xdr.ScVal.scvBytes(Buffer.from(datasetId, 'utf-8')), // dataset_id: Bytes
       addressToScVal(buyerAddress), // buyer: Address
+      xdr.ScVal.scvVoid(), // promo_code: Option<Bytes> (none)
     ]
 
     // Prepare transaction (args are already XDR ScVal)
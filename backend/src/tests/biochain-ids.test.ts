@@ -0,0 +1,41 @@
+/**
+ * Tests for BioChain ID derivation
+ *
+ * Vectors match contracts/biochain_ids/tests/fixtures.
+ */
+
+import { describe, it, expect } from 'vitest'
+import { derivePayoutId, derivePurchaseId, deriveReceiptHash } from '../lib/biochainIds.js'
+
+const ACCOUNT = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
+const PURCHASE_ID = 'd5b74befbbf05421aea24de36aa206f97da04815cd2f19bcfdfac4e5dc7ab57a'
+
+describe('BioChain IDs', () => {
+  it('should derive the fixture purchase ID', () => {
+    expect(derivePurchaseId('dataset_001', ACCOUNT, 0n).toString('hex')).toBe(PURCHASE_ID)
+  })
+
+  it('should derive the fixture payout ID', () => {
+    expect(derivePayoutId('dataset_001', 0n, ACCOUNT, 1_000_000n).toString('hex')).toBe(
+      'f935e285f49e481f0b0aa0201e049a98ccd02bc4353c8b8a93e83409383363f6'
+    )
+  })
+
+  it('should derive the fixture receipt hash', () => {
+    const purchaseId = Buffer.from(PURCHASE_ID, 'hex')
+    expect(deriveReceiptHash(purchaseId, ACCOUNT, 10_000_000n, 1_700_000_000n).toString('hex')).toBe(
+      'b9b7cae3b145a41338681cdb40101d5b318a9342632a7cb8a1bb5dbfcd8503b9'
+    )
+  })
+
+  it('should change the ID with the sequence', () => {
+    expect(derivePurchaseId('dataset_001', ACCOUNT, 1n)).not.toEqual(
+      derivePurchaseId('dataset_001', ACCOUNT, 0n)
+    )
+  })
+
+  it('should reject invalid addresses and amounts', () => {
+    expect(() => derivePurchaseId('dataset_001', 'S' + ACCOUNT.slice(1), 0n)).toThrow()
+    expect(() => derivePayoutId('dataset_001', 0n, ACCOUNT, -1n)).toThrow()
+  })
+})
@@ -0,0 +1,277 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, Bytes,
+    BytesN, Env, Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const SEQUENCE_KEY: Symbol = symbol_short!("SEQ");
+const OUTBOUND_KEY: Symbol = symbol_short!("OUTBOUND");
+const SIGNER_KEY: Symbol = symbol_short!("SIGNER");
+const INBOUND_KEY: Symbol = symbol_short!("INBOUND");
+
+/// Domain separator for outbound attestations
+const OUTBOUND_DOMAIN: Symbol = symbol_short!("BIO_OUT");
+
+/// Domain separator for inbound access proofs
+const INBOUND_DOMAIN: Symbol = symbol_short!("BIO_IN");
+
+/// Outbound attestation
+///
+/// Exportable record of a BIOCHAIN purchase that a relayer carries to
+/// other chains:
+/// - sequence: Monotonic sequence number (replay protection on the
+///   destination chain)
+/// - message_hash: SHA256 of the XDR-encoded tuple
+///   ("BIO_OUT", sequence, dataset_id, buyer, timestamp)
+/// - dataset_id / buyer: Purchase being attested
+/// - timestamp: Ledger timestamp of the purchase
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub sequence: u64,
+    pub message_hash: BytesN<32>,
+    pub dataset_id: Bytes,
+    pub buyer: Address,
+    pub timestamp: u64,
+}
+
+/// Inbound access proof accepted from another chain
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InboundProof {
+    pub source_chain: u32,
+    pub sequence: u64,
+    pub message_hash: BytesN<32>,
+    pub verified_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    AttestationNotFound = 3,
+    UnknownSourceChain = 4,
+    InboundReplay = 5,
+    InboundNotFound = 6,
+}
+
+#[contract]
+pub struct AccessBridge;
+
+#[contractimpl]
+impl AccessBridge {
+    /// Initialize the AccessBridge contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage trusted signers
+    /// * `marketplace` - DatasetMarketplace contract allowed to record purchases
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address, marketplace: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&SEQUENCE_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Set the ed25519 key trusted to sign access proofs from a source chain
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `source_chain` - Chain ID of the source chain
+    /// * `public_key` - Relayer / guardian ed25519 public key
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    pub fn set_trusted_signer(
+        env: Env,
+        source_chain: u32,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&(SIGNER_KEY, source_chain), &public_key);
+        Ok(())
+    }
+
+    /// Remove the trusted signer of a source chain
+    pub fn remove_trusted_signer(env: Env, source_chain: u32) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(SIGNER_KEY, source_chain));
+        Ok(())
+    }
+
+    /// Record an exportable attestation for a purchase
+    ///
+    /// Called by DatasetMarketplace after a successful purchase. Emits an
+    /// `Attest` event with the attestation for relayers to pick up.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `buyer` - Address of the buyer
+    ///
+    /// # Returns
+    /// * `Ok(Attestation)` with the new attestation
+    /// * `Err(Error::NotInitialized)` if no marketplace is configured
+    pub fn record_purchase(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<Attestation, Error> {
+        // ============================================
+        // 1. AUTHENTICATE MARKETPLACE
+        // ============================================
+        let storage = env.storage().instance();
+        let marketplace: Address = storage.get(&MARKETPLACE_KEY).ok_or(Error::NotInitialized)?;
+        marketplace.require_auth();
+
+        // ============================================
+        // 2. BUILD ATTESTATION
+        // ============================================
+        let sequence: u64 = storage.get(&SEQUENCE_KEY).unwrap_or(0);
+        let timestamp = env.ledger().timestamp();
+        let message = (
+            OUTBOUND_DOMAIN,
+            sequence,
+            dataset_id.clone(),
+            buyer.clone(),
+            timestamp,
+        )
+            .to_xdr(&env);
+        let message_hash: BytesN<32> = env.crypto().sha256(&message).into();
+
+        let attestation = Attestation {
+            sequence,
+            message_hash,
+            dataset_id,
+            buyer,
+            timestamp,
+        };
+
+        // ============================================
+        // 3. STORE AND EMIT
+        // ============================================
+        env.storage()
+            .persistent()
+            .set(&(OUTBOUND_KEY, sequence), &attestation);
+        storage.set(&SEQUENCE_KEY, &(sequence + 1));
+
+        env.events()
+            .publish((symbol_short!("Attest"), sequence), attestation.clone());
+
+        Ok(attestation)
+    }
+
+    /// Get an outbound attestation by sequence number
+    pub fn get_attestation(env: Env, sequence: u64) -> Result<Attestation, Error> {
+        env.storage()
+            .persistent()
+            .get(&(OUTBOUND_KEY, sequence))
+            .ok_or(Error::AttestationNotFound)
+    }
+
+    /// Get the next outbound sequence number
+    pub fn next_sequence(env: Env) -> u64 {
+        env.storage().instance().get(&SEQUENCE_KEY).unwrap_or(0)
+    }
+
+    /// Verify an access proof arriving from another chain
+    ///
+    /// The trusted signer of `source_chain` signs the SHA256 of the
+    /// XDR-encoded tuple ("BIO_IN", source_chain, sequence, payload).
+    /// Each (source_chain, sequence) pair is accepted once.
+    ///
+    /// The call traps if the signature does not verify.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `source_chain` - Chain ID the proof comes from
+    /// * `sequence` - Sequence number on the source chain
+    /// * `payload` - Opaque access proof payload
+    /// * `signature` - ed25519 signature over the message hash
+    ///
+    /// # Returns
+    /// * `Ok(InboundProof)` if the proof verifies
+    /// * `Err(Error)` if the chain is unknown or the proof was already used
+    pub fn verify_inbound(
+        env: Env,
+        source_chain: u32,
+        sequence: u64,
+        payload: Bytes,
+        signature: BytesN<64>,
+    ) -> Result<InboundProof, Error> {
+        let public_key: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&(SIGNER_KEY, source_chain))
+            .ok_or(Error::UnknownSourceChain)?;
+
+        let inbound_key = (INBOUND_KEY, source_chain, sequence);
+        if env.storage().persistent().has(&inbound_key) {
+            return Err(Error::InboundReplay);
+        }
+
+        let message_hash = Self::inbound_message_hash(env.clone(), source_chain, sequence, payload);
+        env.crypto()
+            .ed25519_verify(&public_key, &message_hash.clone().into(), &signature);
+
+        let proof = InboundProof {
+            source_chain,
+            sequence,
+            message_hash,
+            verified_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&inbound_key, &proof);
+
+        env.events().publish(
+            (symbol_short!("Inbound"), source_chain, sequence),
+            proof.clone(),
+        );
+
+        Ok(proof)
+    }
+
+    /// Get a previously verified inbound proof
+    pub fn get_inbound(env: Env, source_chain: u32, sequence: u64) -> Result<InboundProof, Error> {
+        env.storage()
+            .persistent()
+            .get(&(INBOUND_KEY, source_chain, sequence))
+            .ok_or(Error::InboundNotFound)
+    }
+
+    /// Compute the message hash a source-chain signer must sign
+    pub fn inbound_message_hash(
+        env: Env,
+        source_chain: u32,
+        sequence: u64,
+        payload: Bytes,
+    ) -> BytesN<32> {
+        let message = (INBOUND_DOMAIN, source_chain, sequence, payload).to_xdr(&env);
+        env.crypto().sha256(&message).into()
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(test)]
+
+use access_bridge::{AccessBridge, AccessBridgeClient, Error};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{testutils::Address as AddressTestUtils, Address, Bytes, BytesN, Env};
+
+/// Chain ID used for the simulated EVM partner chain
+const EVM_CHAIN: u32 = 8453;
+
+/// Helper: Create an initialized AccessBridge client
+fn create_bridge_client(env: &Env) -> AccessBridgeClient<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AccessBridge);
+    let client = AccessBridgeClient::new(env, &contract_id);
+    client.init(&Address::generate(env), &Address::generate(env));
+    client
+}
+
+/// Helper: Deterministic relayer key trusted for EVM_CHAIN
+fn relayer_key(env: &Env, client: &AccessBridgeClient) -> SigningKey {
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    client.set_trusted_signer(
+        &EVM_CHAIN,
+        &BytesN::from_array(env, &signing_key.verifying_key().to_bytes()),
+    );
+    signing_key
+}
+
+/// Helper: Sign an inbound proof with the given key
+fn sign_inbound(
+    env: &Env,
+    client: &AccessBridgeClient,
+    key: &SigningKey,
+    sequence: u64,
+    payload: &Bytes,
+) -> BytesN<64> {
+    let hash = client.inbound_message_hash(&EVM_CHAIN, &sequence, payload);
+    BytesN::from_array(env, &key.sign(&hash.to_array()).to_bytes())
+}
+
+#[test]
+fn test_record_purchase_assigns_sequences() {
+    let env = Env::default();
+    let client = create_bridge_client(&env);
+
+    // Arrange
+    let buyer = Address::generate(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+
+    // Act
+    let first = client.record_purchase(&dataset_id, &buyer);
+    let second = client.record_purchase(&dataset_id, &buyer);
+
+    // Assert
+    assert_eq!(first.sequence, 0);
+    assert_eq!(second.sequence, 1);
+    assert_ne!(first.message_hash, second.message_hash);
+    assert_eq!(client.get_attestation(&0), first);
+    assert_eq!(client.next_sequence(), 2);
+}
+
+#[test]
+fn test_get_missing_attestation_fails() {
+    let env = Env::default();
+    let client = create_bridge_client(&env);
+
+    assert_eq!(
+        client.try_get_attestation(&5),
+        Err(Ok(Error::AttestationNotFound))
+    );
+}
+
+#[test]
+fn test_verify_inbound_accepts_signed_proof_once() {
+    let env = Env::default();
+    let client = create_bridge_client(&env);
+    let key = relayer_key(&env, &client);
+
+    // Arrange
+    let payload = Bytes::from_slice(&env, b"access:dataset_001:0xabc");
+    let signature = sign_inbound(&env, &client, &key, 42, &payload);
+
+    // Act
+    let proof = client.verify_inbound(&EVM_CHAIN, &42, &payload, &signature);
+
+    // Assert
+    assert_eq!(proof.sequence, 42);
+    assert_eq!(client.get_inbound(&EVM_CHAIN, &42), proof);
+    assert_eq!(
+        client.try_verify_inbound(&EVM_CHAIN, &42, &payload, &signature),
+        Err(Ok(Error::InboundReplay))
+    );
+}
+
+#[test]
+fn test_verify_inbound_unknown_chain_fails() {
+    let env = Env::default();
+    let client = create_bridge_client(&env);
+
+    // Arrange
+    let payload = Bytes::from_slice(&env, b"access");
+    let signature = BytesN::from_array(&env, &[0u8; 64]);
+
+    // Act
+    let result = client.try_verify_inbound(&1, &0, &payload, &signature);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::UnknownSourceChain)));
+}
+
+#[test]
+fn test_verify_inbound_bad_signature_traps() {
+    let env = Env::default();
+    let client = create_bridge_client(&env);
+    let key = relayer_key(&env, &client);
+
+    // Arrange: signature over a different payload
+    let payload = Bytes::from_slice(&env, b"access:dataset_001");
+    let other = Bytes::from_slice(&env, b"access:dataset_002");
+    let signature = sign_inbound(&env, &client, &key, 1, &other);
+
+    // Act
+    let result = client.try_verify_inbound(&EVM_CHAIN, &1, &payload, &signature);
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(
+        client.try_get_inbound(&EVM_CHAIN, &1),
+        Err(Ok(Error::InboundNotFound))
+    );
+}
@@ -0,0 +1,264 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
+    IntoVal, Symbol, Vec,
+};
+pub use biochain_types::{Dataset, DatasetStatus};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const STUDY_REGISTRY_KEY: Symbol = symbol_short!("STUDY_REG");
+const COMPLIANCE_KEY: Symbol = symbol_short!("COMPLY");
+const LENDING_POOL_KEY: Symbol = symbol_short!("LENDING");
+
+/// Outcome of an access check
+///
+/// Checks run in order and the first failing one decides the status:
+/// - DatasetNotFound: The marketplace does not know the dataset
+/// - NoLicense: The buyer has not purchased the dataset
+/// - StudyUnavailable: A study in the dataset is no longer in the registry
+/// - NotAttested: The compliance contract no longer attests the buyer
+/// - Suspended: The lending pool suspended the buyer for a delinquent loan
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AccessStatus {
+    Granted,
+    DatasetNotFound,
+    NoLicense,
+    StudyUnavailable,
+    NotAttested,
+    Suspended,
+}
+
+/// Verdict returned by `check_access`
+///
+/// - granted: `true` only if status is Granted
+/// - unavailable_studies: Studies of the dataset missing from the registry
+/// - compliance_checked: `false` if no compliance contract is configured
+/// - checked_at: Ledger timestamp of the check
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccessVerdict {
+    pub buyer: Address,
+    pub dataset_id: Bytes,
+    pub granted: bool,
+    pub status: AccessStatus,
+    pub unavailable_studies: Vec<Bytes>,
+    pub compliance_checked: bool,
+    pub checked_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+}
+
+#[contract]
+pub struct AccessGateway;
+
+#[contractimpl]
+impl AccessGateway {
+    /// Initialize the AccessGateway contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to configure the compliance contract
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// * `study_registry` - Address of the StudyRegistry contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        marketplace: Address,
+        study_registry: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&STUDY_REGISTRY_KEY, &study_registry);
+        Ok(())
+    }
+
+    /// Set the compliance contract consulted by `check_access`
+    ///
+    /// The contract must expose `is_attested(subject: Address) -> bool`.
+    /// Until one is set, access checks skip the compliance step.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `compliance` - Address of the compliance contract
+    pub fn set_compliance(env: Env, compliance: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&COMPLIANCE_KEY, &compliance);
+        Ok(())
+    }
+
+    /// Get the compliance contract, if any
+    pub fn get_compliance(env: Env) -> Option<Address> {
+        env.storage().instance().get(&COMPLIANCE_KEY)
+    }
+
+    /// Set the lending pool consulted by `check_access`
+    ///
+    /// The contract must expose `is_suspended(buyer: Address) -> bool`;
+    /// buyers with a delinquent buy-now-pay-later loan are denied access.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `lending_pool` - Address of the LendingPool contract
+    pub fn set_lending_pool(env: Env, lending_pool: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&LENDING_POOL_KEY, &lending_pool);
+        Ok(())
+    }
+
+    /// Get the lending pool, if any
+    pub fn get_lending_pool(env: Env) -> Option<Address> {
+        env.storage().instance().get(&LENDING_POOL_KEY)
+    }
+
+    /// Check whether a buyer may receive a dataset's decrypted data
+    ///
+    /// Read-only, so off-chain data servers can simulate it before
+    /// releasing data. Flow:
+    /// 1. Load the dataset from the marketplace
+    /// 2. Require the buyer's purchase record (active license)
+    /// 3. Require every study of the dataset to still be in the registry
+    /// 4. Require the buyer's attestation, if a compliance contract is set
+    /// 5. Require the buyer not to be suspended, if a lending pool is set
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - Address requesting the data
+    /// * `dataset_id` - ID of the dataset
+    ///
+    /// # Returns
+    /// * `Ok(AccessVerdict)` with the first failing check, or Granted
+    /// * `Err(Error::NotInitialized)` if the gateway is not initialized
+    pub fn check_access(
+        env: Env,
+        buyer: Address,
+        dataset_id: Bytes,
+    ) -> Result<AccessVerdict, Error> {
+        let storage = env.storage().instance();
+        let marketplace: Address = storage.get(&MARKETPLACE_KEY).ok_or(Error::NotInitialized)?;
+        let study_registry: Address = storage
+            .get(&STUDY_REGISTRY_KEY)
+            .ok_or(Error::NotInitialized)?;
+        let compliance: Option<Address> = storage.get(&COMPLIANCE_KEY);
+
+        let mut verdict = AccessVerdict {
+            buyer: buyer.clone(),
+            dataset_id: dataset_id.clone(),
+            granted: false,
+            status: AccessStatus::Granted,
+            unavailable_studies: Vec::new(&env),
+            compliance_checked: compliance.is_some(),
+            checked_at: env.ledger().timestamp(),
+        };
+
+        // ============================================
+        // 1. LOAD DATASET
+        // ============================================
+        let dataset = match env.try_invoke_contract::<Dataset, soroban_sdk::Error>(
+            &marketplace,
+            &Symbol::new(&env, "get_dataset"),
+            (dataset_id.clone(),).into_val(&env),
+        ) {
+            Ok(Ok(dataset)) => dataset,
+            _ => return Ok(Self::deny(verdict, AccessStatus::DatasetNotFound)),
+        };
+
+        // ============================================
+        // 2. CHECK LICENSE
+        // ============================================
+        let purchase = env.try_invoke_contract::<soroban_sdk::Val, soroban_sdk::Error>(
+            &marketplace,
+            &Symbol::new(&env, "get_purchase"),
+            (dataset_id, buyer.clone()).into_val(&env),
+        );
+        if !matches!(purchase, Ok(Ok(_))) {
+            return Ok(Self::deny(verdict, AccessStatus::NoLicense));
+        }
+
+        // ============================================
+        // 3. CHECK STUDIES
+        // ============================================
+        for study_id in dataset.study_ids.iter() {
+            let registered = match BytesN::<32>::try_from(&study_id) {
+                Ok(study_hash) => env.invoke_contract::<bool>(
+                    &study_registry,
+                    &Symbol::new(&env, "dataset_exists"),
+                    (study_hash,).into_val(&env),
+                ),
+                Err(_) => false,
+            };
+            if !registered {
+                verdict.unavailable_studies.push_back(study_id);
+            }
+        }
+        if !verdict.unavailable_studies.is_empty() {
+            return Ok(Self::deny(verdict, AccessStatus::StudyUnavailable));
+        }
+
+        // ============================================
+        // 4. CHECK COMPLIANCE
+        // ============================================
+        if let Some(compliance) = compliance {
+            let attested: bool = env.invoke_contract(
+                &compliance,
+                &Symbol::new(&env, "is_attested"),
+                (buyer.clone(),).into_val(&env),
+            );
+            if !attested {
+                return Ok(Self::deny(verdict, AccessStatus::NotAttested));
+            }
+        }
+
+        // ============================================
+        // 5. CHECK CREDIT STANDING
+        // ============================================
+        if let Some(lending_pool) = storage.get::<_, Address>(&LENDING_POOL_KEY) {
+            let suspended: bool = env.invoke_contract(
+                &lending_pool,
+                &Symbol::new(&env, "is_suspended"),
+                (buyer,).into_val(&env),
+            );
+            if suspended {
+                return Ok(Self::deny(verdict, AccessStatus::Suspended));
+            }
+        }
+
+        verdict.granted = true;
+        Ok(verdict)
+    }
+
+    /// Mark a verdict as denied with the given status
+    fn deny(mut verdict: AccessVerdict, status: AccessStatus) -> AccessVerdict {
+        verdict.granted = false;
+        verdict.status = status;
+        verdict
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
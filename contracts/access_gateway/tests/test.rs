@@ -0,0 +1,210 @@
+#![cfg(test)]
+
+use access_gateway::{AccessGateway, AccessGatewayClient, AccessStatus, Error};
+use soroban_sdk::{
+    contract, contractimpl, testutils::Address as AddressTestUtils, token, vec, Address, Bytes,
+    BytesN, Env,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Minimal compliance stand-in
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn set_attested(env: Env, subject: Address, attested: bool) {
+        env.storage().instance().set(&subject, &attested);
+    }
+
+    pub fn is_attested(env: Env, subject: Address) -> bool {
+        env.storage().instance().get(&subject).unwrap_or(false)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    gateway: AccessGatewayClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+}
+
+/// Helper: Deploy the core contracts with one registered study and an
+/// initialized AccessGateway
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+
+    let gateway = AccessGatewayClient::new(&env, &env.register_contract(None, AccessGateway));
+    gateway.init(
+        &Address::generate(&env),
+        &marketplace.address,
+        &registry.address,
+    );
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        gateway,
+        marketplace,
+    }
+}
+
+/// Helper: Register a dataset over the given study and buy it as `buyer`
+fn buy_dataset(s: &Setup, dataset_id: &Bytes, study_id: [u8; 32], buyer: &Address) {
+    s.marketplace.register_dataset(
+        &Address::generate(&s.env),
+        dataset_id,
+        &vec![&s.env, Bytes::from_array(&s.env, &study_id)],
+        &PRICE,
+        &None,
+    );
+    s.usdc.mint(buyer, &PRICE);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        buyer,
+        &s.marketplace.address,
+        &PRICE,
+        &1_000,
+    );
+    s.marketplace.purchase_dataset(dataset_id, buyer, &None);
+}
+
+#[test]
+fn test_check_access_grants_licensed_buyer() {
+    let s = setup();
+    let buyer = Address::generate(&s.env);
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    buy_dataset(&s, &dataset_id, [1u8; 32], &buyer);
+
+    // Act
+    let verdict = s.gateway.check_access(&buyer, &dataset_id);
+
+    // Assert: no compliance contract is configured yet
+    assert!(verdict.granted);
+    assert_eq!(verdict.status, AccessStatus::Granted);
+    assert!(verdict.unavailable_studies.is_empty());
+    assert!(!verdict.compliance_checked);
+}
+
+#[test]
+fn test_check_access_denies_unknown_dataset_and_missing_license() {
+    let s = setup();
+    let buyer = Address::generate(&s.env);
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    buy_dataset(&s, &dataset_id, [1u8; 32], &buyer);
+
+    // Act
+    let unknown = s
+        .gateway
+        .check_access(&buyer, &Bytes::from_slice(&s.env, b"dataset_404"));
+    let unlicensed = s
+        .gateway
+        .check_access(&Address::generate(&s.env), &dataset_id);
+
+    // Assert
+    assert_eq!(
+        (unknown.granted, unknown.status),
+        (false, AccessStatus::DatasetNotFound)
+    );
+    assert_eq!(
+        (unlicensed.granted, unlicensed.status),
+        (false, AccessStatus::NoLicense)
+    );
+}
+
+#[test]
+fn test_check_access_reports_unavailable_studies() {
+    let s = setup();
+    let buyer = Address::generate(&s.env);
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_002");
+
+    // Arrange: the dataset's only study is not in the registry
+    buy_dataset(&s, &dataset_id, [9u8; 32], &buyer);
+
+    // Act
+    let verdict = s.gateway.check_access(&buyer, &dataset_id);
+
+    // Assert
+    assert!(!verdict.granted);
+    assert_eq!(verdict.status, AccessStatus::StudyUnavailable);
+    assert_eq!(
+        verdict.unavailable_studies,
+        vec![&s.env, Bytes::from_array(&s.env, &[9u8; 32])]
+    );
+}
+
+#[test]
+fn test_check_access_requires_attestation_once_compliance_is_set() {
+    let s = setup();
+    let buyer = Address::generate(&s.env);
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    buy_dataset(&s, &dataset_id, [1u8; 32], &buyer);
+
+    // Arrange
+    let compliance =
+        MockComplianceClient::new(&s.env, &s.env.register_contract(None, MockCompliance));
+    s.gateway.set_compliance(&compliance.address);
+
+    // Act
+    let before = s.gateway.check_access(&buyer, &dataset_id);
+    compliance.set_attested(&buyer, &true);
+    let after = s.gateway.check_access(&buyer, &dataset_id);
+
+    // Assert
+    assert_eq!(
+        (before.granted, before.status, before.compliance_checked),
+        (false, AccessStatus::NotAttested, true)
+    );
+    assert_eq!((after.granted, after.status), (true, AccessStatus::Granted));
+    assert_eq!(s.gateway.get_compliance(), Some(compliance.address));
+}
+
+#[test]
+fn test_init_twice_fails() {
+    let s = setup();
+
+    // Act & Assert
+    assert_eq!(
+        s.gateway.try_init(
+            &Address::generate(&s.env),
+            &s.marketplace.address,
+            &s.marketplace.address
+        ),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
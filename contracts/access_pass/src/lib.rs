@@ -0,0 +1,255 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, Env, String,
+};
+
+/// TTL policy for passes, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Storage keys
+///
+/// - Pass / TokenOf / Balance: Passes by token ID, the token ID of each
+///   holder's pass for a dataset and the number of passes each address
+///   holds, in persistent storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Marketplace,
+    TokenCount,
+    Pass(u64),
+    TokenOf(Bytes, Address),
+    Balance(Address),
+}
+
+/// Non-transferable token granting access to a purchased dataset
+///
+/// - token_id: Sequential ID of the token, starting at 1
+/// - holder: Buyer the pass is bound to
+/// - expires_at: Ledger timestamp access ends at (None if it never
+///   expires)
+/// - minted_at: Ledger timestamp of the latest purchase renewing the pass
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pass {
+    pub token_id: u64,
+    pub dataset_id: Bytes,
+    pub holder: Address,
+    pub expires_at: Option<u64>,
+    pub minted_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    PassNotFound = 3,
+    NonTransferable = 4,
+}
+
+/// Soulbound access passes for purchased datasets
+///
+/// The DatasetMarketplace mints a pass to the buyer on every successful
+/// purchase, encoding the dataset, the buyer and when access expires, and
+/// burns it when the purchase is refunded. Data-delivery gateways check
+/// `has_valid_pass` instead of reading marketplace storage. Passes follow
+/// a minimal NFT interface (`name`, `symbol`, `balance`, `owner_of`) but
+/// can never be transferred. One pass exists per dataset and holder;
+/// repeat purchases renew it.
+#[contract]
+pub struct AccessPass;
+
+#[contractimpl]
+impl AccessPass {
+    /// Initialize the AccessPass contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `marketplace` - Address of the DatasetMarketplace contract, the
+    ///   only minter
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, marketplace: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Marketplace) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Marketplace, &marketplace);
+        storage.set(&DataKey::TokenCount, &0u64);
+        Ok(())
+    }
+
+    /// Mint or renew a holder's pass for a dataset
+    ///
+    /// Requires the marketplace's authorization. A holder who already has a
+    /// pass for the dataset keeps its token ID with the new expiry.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `holder` - Buyer the pass is bound to
+    /// * `expires_at` - Ledger timestamp access ends at (None if it never
+    ///   expires)
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the token ID of the pass
+    pub fn mint(
+        env: Env,
+        dataset_id: Bytes,
+        holder: Address,
+        expires_at: Option<u64>,
+    ) -> Result<u64, Error> {
+        Self::require_marketplace(&env)?;
+        let token_of_key = DataKey::TokenOf(dataset_id.clone(), holder.clone());
+        let token_id = match env.storage().persistent().get::<_, u64>(&token_of_key) {
+            Some(token_id) => token_id,
+            None => {
+                let token_id: u64 = env
+                    .storage()
+                    .instance()
+                    .get::<_, u64>(&DataKey::TokenCount)
+                    .ok_or(Error::NotInitialized)?
+                    + 1;
+                env.storage().instance().set(&DataKey::TokenCount, &token_id);
+                Self::set_persistent(&env, &token_of_key, &token_id);
+                Self::set_persistent(
+                    &env,
+                    &DataKey::Balance(holder.clone()),
+                    &(Self::balance(env.clone(), holder.clone()) + 1),
+                );
+                token_id
+            }
+        };
+
+        let pass = Pass {
+            token_id,
+            dataset_id,
+            holder: holder.clone(),
+            expires_at,
+            minted_at: env.ledger().timestamp(),
+        };
+        Self::set_persistent(&env, &DataKey::Pass(token_id), &pass);
+
+        env.events()
+            .publish((symbol_short!("Mint"), holder, token_id), expires_at);
+        Ok(token_id)
+    }
+
+    /// Burn a holder's pass for a dataset
+    ///
+    /// Requires the marketplace's authorization.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if a pass was burned, `Ok(false)` if the holder had none
+    pub fn burn(env: Env, dataset_id: Bytes, holder: Address) -> Result<bool, Error> {
+        Self::require_marketplace(&env)?;
+        let token_of_key = DataKey::TokenOf(dataset_id, holder.clone());
+        let token_id = match env.storage().persistent().get::<_, u64>(&token_of_key) {
+            Some(token_id) => token_id,
+            None => return Ok(false),
+        };
+        let storage = env.storage().persistent();
+        storage.remove(&token_of_key);
+        storage.remove(&DataKey::Pass(token_id));
+        let balance = Self::balance(env.clone(), holder.clone()) - 1;
+        if balance == 0 {
+            storage.remove(&DataKey::Balance(holder.clone()));
+        } else {
+            Self::set_persistent(&env, &DataKey::Balance(holder.clone()), &balance);
+        }
+
+        env.events()
+            .publish((symbol_short!("Burn"), holder, token_id), ());
+        Ok(true)
+    }
+
+    /// Passes are soulbound, so transfers always fail
+    ///
+    /// # Returns
+    /// * `Err(Error::NonTransferable)`
+    pub fn transfer(_env: Env, _from: Address, _to: Address, _token_id: u64) -> Result<(), Error> {
+        Err(Error::NonTransferable)
+    }
+
+    /// Name of the token collection
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "BIOCHAIN Access Pass")
+    }
+
+    /// Symbol of the token collection
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "BIOPASS")
+    }
+
+    /// Number of passes an address holds, expired ones included
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(owner))
+            .unwrap_or(0)
+    }
+
+    /// Holder of a pass
+    pub fn owner_of(env: Env, token_id: u64) -> Result<Address, Error> {
+        Ok(Self::get_pass(env, token_id)?.holder)
+    }
+
+    /// Get a pass by token ID
+    pub fn get_pass(env: Env, token_id: u64) -> Result<Pass, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Pass(token_id))
+            .ok_or(Error::PassNotFound)
+    }
+
+    /// Get the token ID of a holder's pass for a dataset, if any
+    pub fn token_of(env: Env, dataset_id: Bytes, holder: Address) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TokenOf(dataset_id, holder))
+    }
+
+    /// Check whether a holder has an unexpired pass for a dataset
+    pub fn has_valid_pass(env: Env, dataset_id: Bytes, holder: Address) -> bool {
+        Self::token_of(env.clone(), dataset_id, holder)
+            .and_then(|token_id| Self::get_pass(env.clone(), token_id).ok())
+            .is_some_and(|pass| {
+                pass.expires_at
+                    .is_none_or(|expires_at| env.ledger().timestamp() < expires_at)
+            })
+    }
+
+    /// Get the number of passes ever minted (the latest token ID)
+    pub fn total_minted(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TokenCount)
+            .unwrap_or(0)
+    }
+
+    fn set_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        key: &DataKey,
+        value: &V,
+    ) {
+        let storage = env.storage().persistent();
+        storage.set(key, value);
+        storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn require_marketplace(env: &Env) -> Result<(), Error> {
+        let marketplace: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Marketplace)
+            .ok_or(Error::NotInitialized)?;
+        marketplace.require_auth();
+        Ok(())
+    }
+}
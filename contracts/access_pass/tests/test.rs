@@ -0,0 +1,182 @@
+#![cfg(test)]
+
+use access_pass::{AccessPass, AccessPassClient, Error};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Price of the dataset (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Access period of the dataset (30 days)
+const PERIOD: u64 = 30 * 86_400;
+
+struct Setup<'a> {
+    env: Env,
+    passes: AccessPassClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::Client<'a>,
+    treasury: Address,
+    dataset_id: Bytes,
+}
+
+/// Helper: Deploy the core contracts with an AccessPass, and list a dataset
+/// of one study with a 30-day access period
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+    splitter.init(&Address::generate(&env), &usdc_id, &treasury);
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let passes = AccessPassClient::new(&env, &env.register_contract(None, AccessPass));
+    passes.init(&marketplace.address);
+    marketplace.set_access_pass(&Some(passes.address.clone()));
+
+    let contributor = Address::generate(&env);
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    let dataset_id = Bytes::from_slice(&env, b"genomes");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from(study_hash)],
+        &PRICE,
+        &None,
+    );
+    marketplace.set_access_duration(&dataset_id, &Some(PERIOD));
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        passes,
+        marketplace,
+        splitter,
+        treasury,
+        dataset_id,
+    }
+}
+
+/// Helper: A researcher holding enough USDC for two purchases
+fn funded_buyer(s: &Setup) -> Address {
+    let buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.usdc.address).mint(&buyer, &(2 * PRICE));
+    s.usdc.approve(
+        &buyer,
+        &s.marketplace.address,
+        &(2 * PRICE),
+        &(s.env.ledger().sequence() + 100),
+    );
+    buyer
+}
+
+#[test]
+fn test_purchases_mint_and_renew_a_soulbound_pass() {
+    let s = setup();
+    let buyer = funded_buyer(&s);
+
+    // Act
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+
+    // Assert: the buyer holds pass 1 expiring with their access
+    assert_eq!(s.passes.token_of(&s.dataset_id, &buyer), Some(1));
+    let pass = s.passes.get_pass(&1);
+    assert_eq!((pass.dataset_id, pass.holder), (s.dataset_id.clone(), buyer.clone()));
+    assert_eq!(pass.expires_at, Some(1_000 + PERIOD));
+    assert_eq!(s.passes.owner_of(&1), buyer);
+    assert_eq!(s.passes.balance(&buyer), 1);
+    assert!(s.passes.has_valid_pass(&s.dataset_id, &buyer));
+    assert_eq!(
+        s.passes.try_transfer(&buyer, &Address::generate(&s.env), &1),
+        Err(Ok(Error::NonTransferable))
+    );
+
+    // Act & Assert: the pass lapses with access, and buying again renews
+    // the same token
+    s.env.ledger().set_timestamp(1_000 + PERIOD);
+    assert!(!s.passes.has_valid_pass(&s.dataset_id, &buyer));
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+    assert_eq!(s.passes.get_pass(&1).expires_at, Some(1_000 + 2 * PERIOD));
+    assert!(s.passes.has_valid_pass(&s.dataset_id, &buyer));
+    assert_eq!((s.passes.balance(&buyer), s.passes.total_minted()), (1, 1));
+}
+
+#[test]
+fn test_approved_refund_burns_the_pass() {
+    let s = setup();
+    s.splitter.set_payout_mode(&revenue_splitter::PayoutMode::Accrue);
+    s.splitter.set_authorized_caller(&s.marketplace.address);
+    let buyer = funded_buyer(&s);
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+    s.marketplace.set_refund_policy(&dataset_marketplace::RefundPolicy {
+        window: 7 * 86_400,
+        arbiter: Address::generate(&s.env),
+        refund_source: s.treasury.clone(),
+    });
+    s.usdc.approve(
+        &s.treasury,
+        &s.marketplace.address,
+        &PRICE,
+        &(s.env.ledger().sequence() + 100),
+    );
+
+    // Act
+    s.marketplace.request_refund(&s.dataset_id, &buyer);
+    s.marketplace.resolve_refund(&s.dataset_id, &buyer, &true);
+
+    // Assert
+    assert_eq!(s.passes.token_of(&s.dataset_id, &buyer), None);
+    assert_eq!(s.passes.try_owner_of(&1), Err(Ok(Error::PassNotFound)));
+    assert_eq!(s.passes.balance(&buyer), 0);
+    assert!(!s.passes.has_valid_pass(&s.dataset_id, &buyer));
+}
+
+#[test]
+fn test_only_the_marketplace_mints_passes() {
+    let s = setup();
+    let buyer = funded_buyer(&s);
+    s.marketplace.set_access_pass(&None);
+
+    // Act & Assert: without an AccessPass set, purchases mint nothing
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+    assert_eq!(s.marketplace.get_access_pass(), None);
+    assert_eq!(s.passes.total_minted(), 0);
+
+    // Minting requires the marketplace's authorization
+    s.env.set_auths(&[]);
+    assert!(s.passes.try_mint(&s.dataset_id, &buyer, &None).is_err());
+    assert_eq!(
+        s.passes.try_init(&s.marketplace.address),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
@@ -0,0 +1,311 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, Env, Symbol,
+    Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const WRITER_KEY: Symbol = symbol_short!("WRITER");
+const COUNT_KEY: Symbol = symbol_short!("COUNT");
+const RECORD_KEY: Symbol = symbol_short!("RECORD");
+const SOURCE_COUNT_KEY: Symbol = symbol_short!("SRC_CNT");
+const SOURCE_RECORD_KEY: Symbol = symbol_short!("SRC_REC");
+
+/// Actions appended by the core contracts
+///
+/// Actions are plain symbols so the registry, marketplace and splitter can
+/// append without sharing a type with this contract.
+/// - pause / unpause: Contract paused or resumed
+/// - upgrade: WASM replaced (data: new WASM hash)
+/// - migrate: Storage migration run
+/// - upgrader: UpgradeCoordinator set (subject: coordinator)
+/// - audit: AuditLog set (subject: audit log)
+/// - config: Dependency address set (subject: dependency)
+pub const ACTION_PAUSE: Symbol = symbol_short!("pause");
+pub const ACTION_UNPAUSE: Symbol = symbol_short!("unpause");
+pub const ACTION_UPGRADE: Symbol = symbol_short!("upgrade");
+pub const ACTION_MIGRATE: Symbol = symbol_short!("migrate");
+pub const ACTION_UPGRADER: Symbol = symbol_short!("upgrader");
+pub const ACTION_AUDIT: Symbol = symbol_short!("audit");
+pub const ACTION_CONFIG: Symbol = symbol_short!("config");
+
+/// TTL policy for records, in ledgers
+///
+/// Records are extended to ~30 days when written; the TtlKeeper or readers
+/// keep them alive afterwards.
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum number of records returned by a single page
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Audit record of one privileged action
+///
+/// - seq: Position in the global log (0-based)
+/// - source: Contract that performed the action
+/// - action: One of the ACTION_* symbols
+/// - subject: Address the action applied to, if any
+/// - data: Hash the action applied to (e.g. WASM hash), empty if none
+/// - ledger, timestamp: When the action was performed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub source: Address,
+    pub action: Symbol,
+    pub subject: Option<Address>,
+    pub data: Bytes,
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedWriter = 3,
+    RecordNotFound = 4,
+    InvalidRange = 5,
+}
+
+#[contract]
+pub struct AuditLog;
+
+#[contractimpl]
+impl AuditLog {
+    /// Initialize the AuditLog contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage writers
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Allow a contract (registry, marketplace, splitter) to append records
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `writer` - Address of the writing contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn add_writer(env: Env, writer: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&(WRITER_KEY, writer), &true);
+        Ok(())
+    }
+
+    /// Revoke a writer's permission to append records
+    ///
+    /// Records it already appended are kept.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `writer` - Address of the writing contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn remove_writer(env: Env, writer: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(WRITER_KEY, writer));
+        Ok(())
+    }
+
+    /// Check whether an address is an authorized writer
+    pub fn is_writer(env: Env, writer: Address) -> bool {
+        env.storage().instance().has(&(WRITER_KEY, writer))
+    }
+
+    /// Append a record of a privileged action
+    ///
+    /// Records are append-only: there is no entry point to edit or remove
+    /// them.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `source` - Authorized writing contract (must authorize the call)
+    /// * `action` - One of the ACTION_* symbols
+    /// * `subject` - Address the action applied to, if any
+    /// * `data` - Hash the action applied to, empty if none
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the sequence number of the record
+    /// * `Err(Error::UnauthorizedWriter)` if `source` is not a writer
+    pub fn append(
+        env: Env,
+        source: Address,
+        action: Symbol,
+        subject: Option<Address>,
+        data: Bytes,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE WRITER
+        // ============================================
+        source.require_auth();
+        let instance = env.storage().instance();
+        if !instance.has(&(WRITER_KEY, source.clone())) {
+            return Err(Error::UnauthorizedWriter);
+        }
+
+        // ============================================
+        // 2. STORE RECORD
+        // ============================================
+        let seq: u64 = instance.get(&COUNT_KEY).ok_or(Error::NotInitialized)?;
+        let record = AuditRecord {
+            seq,
+            source: source.clone(),
+            action: action.clone(),
+            subject,
+            data,
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        };
+        let persistent = env.storage().persistent();
+        Self::set_persistent(&env, &(RECORD_KEY, seq), &record);
+        instance.set(&COUNT_KEY, &(seq + 1));
+
+        // ============================================
+        // 3. INDEX BY SOURCE
+        // ============================================
+        let source_count_key = (SOURCE_COUNT_KEY, source.clone());
+        let source_count: u64 = persistent.get(&source_count_key).unwrap_or(0);
+        Self::set_persistent(
+            &env,
+            &(SOURCE_RECORD_KEY, source.clone(), source_count),
+            &seq,
+        );
+        Self::set_persistent(&env, &source_count_key, &(source_count + 1));
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events()
+            .publish((symbol_short!("Audit"), source, action), seq);
+
+        Ok(seq)
+    }
+
+    /// Get the number of records in the log
+    pub fn count(env: Env) -> u64 {
+        env.storage().instance().get(&COUNT_KEY).unwrap_or(0)
+    }
+
+    /// Get the number of records appended by a source
+    pub fn source_count(env: Env, source: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(SOURCE_COUNT_KEY, source))
+            .unwrap_or(0)
+    }
+
+    /// Get a record by sequence number
+    ///
+    /// # Returns
+    /// * `Ok(AuditRecord)` if found
+    /// * `Err(Error::RecordNotFound)` if not found
+    pub fn get_record(env: Env, seq: u64) -> Result<AuditRecord, Error> {
+        env.storage()
+            .persistent()
+            .get(&(RECORD_KEY, seq))
+            .ok_or(Error::RecordNotFound)
+    }
+
+    /// Get a page of the log, oldest first
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `start` - Sequence number of the first record
+    /// * `limit` - Maximum number of records (at most MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AuditRecord>)` (empty past the end of the log)
+    /// * `Err(Error::InvalidRange)` if `limit` exceeds MAX_PAGE_SIZE
+    pub fn get_records(env: Env, start: u64, limit: u32) -> Result<Vec<AuditRecord>, Error> {
+        if limit > MAX_PAGE_SIZE {
+            return Err(Error::InvalidRange);
+        }
+        let end = start
+            .saturating_add(limit as u64)
+            .min(Self::count(env.clone()));
+
+        let mut records = Vec::new(&env);
+        for seq in start..end {
+            records.push_back(Self::get_record(env.clone(), seq)?);
+        }
+        Ok(records)
+    }
+
+    /// Get a page of the records appended by one source, oldest first
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `source` - Contract whose records to read
+    /// * `start` - Index of the first record among this source's records
+    /// * `limit` - Maximum number of records (at most MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AuditRecord>)` (empty past the end of the source's records)
+    /// * `Err(Error::InvalidRange)` if `limit` exceeds MAX_PAGE_SIZE
+    pub fn get_records_by_source(
+        env: Env,
+        source: Address,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<AuditRecord>, Error> {
+        if limit > MAX_PAGE_SIZE {
+            return Err(Error::InvalidRange);
+        }
+        let persistent = env.storage().persistent();
+        let end = start
+            .saturating_add(limit as u64)
+            .min(Self::source_count(env.clone(), source.clone()));
+
+        let mut records = Vec::new(&env);
+        for index in start..end {
+            let seq: u64 = persistent
+                .get(&(SOURCE_RECORD_KEY, source.clone(), index))
+                .ok_or(Error::RecordNotFound)?;
+            records.push_back(Self::get_record(env.clone(), seq)?);
+        }
+        Ok(records)
+    }
+
+    /// Write a persistent entry and extend its TTL
+    fn set_persistent<K, V>(env: &Env, key: &K, value: &V)
+    where
+        K: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
+        V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
+    {
+        let persistent = env.storage().persistent();
+        persistent.set(key, value);
+        persistent.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
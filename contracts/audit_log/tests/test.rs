@@ -0,0 +1,158 @@
+#![cfg(test)]
+
+use audit_log::{
+    AuditLog, AuditLogClient, Error, ACTION_AUDIT, ACTION_CONFIG, ACTION_PAUSE, ACTION_UNPAUSE,
+    ACTION_UPGRADER, MAX_PAGE_SIZE,
+};
+use soroban_sdk::{symbol_short, testutils::Address as AddressTestUtils, Address, Bytes, Env};
+
+struct Setup<'a> {
+    env: Env,
+    log: AuditLogClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+}
+
+/// Helper: Deploy the core contracts and an AuditLog they are writers of
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    registry.init(&Address::generate(&env));
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    splitter.init(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    let log = AuditLogClient::new(&env, &env.register_contract(None, AuditLog));
+    log.init(&Address::generate(&env));
+    for writer in [&registry.address, &marketplace.address, &splitter.address] {
+        log.add_writer(writer);
+    }
+
+    Setup {
+        env,
+        log,
+        registry,
+        marketplace,
+        splitter,
+    }
+}
+
+#[test]
+fn test_core_contracts_append_privileged_actions() {
+    let s = setup();
+    let coordinator = Address::generate(&s.env);
+    let stats = Address::generate(&s.env);
+
+    // Act
+    s.registry.set_audit_log(&s.log.address);
+    s.registry.set_upgrade_coordinator(&coordinator);
+    s.registry.pause();
+    s.registry.unpause();
+    s.marketplace.set_audit_log(&s.log.address);
+    s.marketplace.set_stats_contract(&stats);
+
+    // Assert
+    let records = s.log.get_records(&0, &10);
+    assert_eq!(records.len(), 6);
+    let actions: std::vec::Vec<_> = records.iter().map(|r| r.action).collect();
+    assert_eq!(
+        actions,
+        [
+            ACTION_AUDIT,
+            ACTION_UPGRADER,
+            ACTION_PAUSE,
+            ACTION_UNPAUSE,
+            ACTION_AUDIT,
+            ACTION_CONFIG
+        ]
+    );
+    let upgrader = records.get(1).unwrap();
+    assert_eq!(upgrader.source, s.registry.address);
+    assert_eq!(upgrader.subject, Some(coordinator));
+    let config = records.get(5).unwrap();
+    assert_eq!(config.source, s.marketplace.address);
+    assert_eq!(config.subject, Some(stats));
+    assert_eq!(config.seq, 5);
+}
+
+#[test]
+fn test_records_are_paginated_by_source() {
+    let s = setup();
+    s.registry.set_audit_log(&s.log.address);
+    s.splitter.set_audit_log(&s.log.address);
+
+    // Act: interleave registry and splitter actions
+    s.splitter.set_insurance_pool(&Address::generate(&s.env));
+    s.registry.set_stats_contract(&Address::generate(&s.env));
+    s.splitter.set_stats_contract(&Address::generate(&s.env));
+
+    // Assert
+    assert_eq!(s.log.count(), 5);
+    assert_eq!(s.log.source_count(&s.splitter.address), 3);
+    let page = s.log.get_records_by_source(&s.splitter.address, &1, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!((page.get(0).unwrap().seq, page.get(1).unwrap().seq), (2, 4));
+    assert!(s.log.get_records(&5, &10).is_empty());
+    assert_eq!(
+        s.log.try_get_records(&0, &(MAX_PAGE_SIZE + 1)),
+        Err(Ok(Error::InvalidRange))
+    );
+}
+
+#[test]
+fn test_append_requires_writer_and_actions_survive_a_broken_log() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+
+    // Act & Assert: only writers can append
+    assert_eq!(
+        s.log.try_append(
+            &outsider,
+            &symbol_short!("pause"),
+            &None,
+            &Bytes::new(&s.env)
+        ),
+        Err(Ok(Error::UnauthorizedWriter))
+    );
+
+    // A contract that lost its writer role still performs its actions
+    s.log.remove_writer(&s.registry.address);
+    s.registry.set_audit_log(&s.log.address);
+    s.registry
+        .set_upgrade_coordinator(&Address::generate(&s.env));
+    s.registry.pause();
+    assert!(s.registry.is_paused());
+    assert_eq!(s.log.count(), 0);
+}
+
+#[test]
+fn test_append_stores_hash_data() {
+    let s = setup();
+    let hash = Bytes::from_array(&s.env, &[7u8; 32]);
+
+    // Act
+    let seq = s
+        .log
+        .append(&s.splitter.address, &symbol_short!("upgrade"), &None, &hash);
+
+    // Assert
+    assert_eq!(s.log.get_record(&seq).data, hash);
+    assert_eq!(s.log.try_get_record(&1), Err(Ok(Error::RecordNotFound)));
+}
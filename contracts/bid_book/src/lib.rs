@@ -0,0 +1,376 @@
+#![no_std]
+use biochain_pricing::math;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec,
+};
+pub use biochain_types::{Dataset, DatasetStatus};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const BID_COUNT_KEY: Symbol = symbol_short!("BID_CNT");
+const BID_KEY: Symbol = symbol_short!("BID");
+const BOOK_KEY: Symbol = symbol_short!("BOOK");
+
+/// TTL policy for bids and category books, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum open bids per category, so the book stays readable in one call
+pub const MAX_OPEN_BIDS: u32 = 100;
+
+/// Bid lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BidStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+/// Standing bid on a data category
+///
+/// A buyer's escrowed offer to buy any dataset meeting a specification:
+/// - category: Data category the bid is listed under (e.g. "cardio_img")
+/// - spec_hash: Hash of the off-chain specification (condition codes,
+///   modality, quality criteria)
+/// - price_per_study: Most the buyer pays per study in a matched dataset
+/// - remaining: Escrowed USDC not yet spent on matches
+/// - curator: Address that confirms a dataset meets the spec (may be the
+///   buyer)
+/// - expires_at: Ledger timestamp after which no more datasets match
+/// - matches: Datasets bought through the bid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bid {
+    pub bid_id: u64,
+    pub buyer: Address,
+    pub category: Symbol,
+    pub spec_hash: BytesN<32>,
+    pub price_per_study: i128,
+    pub remaining: i128,
+    pub curator: Address,
+    pub expires_at: u64,
+    pub matches: Vec<Bytes>,
+    pub status: BidStatus,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidExpiry = 4,
+    BidNotFound = 5,
+    BidNotOpen = 6,
+    BidExpired = 7,
+    DatasetNotFound = 8,
+    AlreadyMatched = 9,
+    PriceAboveBid = 10,
+    InsufficientEscrow = 11,
+    TooManyBids = 12,
+}
+
+/// Order book of standing bids on data categories
+///
+/// Bids escrow their budget here. A dataset listed in the DatasetMarketplace
+/// is matched against a bid once the bid's curator confirms it meets the
+/// spec; the match buys the dataset for the bid's buyer with
+/// `purchase_dataset_for`, so contributors are paid through the normal
+/// RevenueSplitter payout path. Matching cannot run inside the marketplace's
+/// own registration (contracts cannot be re-entered), so sellers or a keeper
+/// watching `DatasetRegistered` events submit the match right after.
+#[contract]
+pub struct BidBook;
+
+#[contractimpl]
+impl BidBook {
+    /// Initialize the BidBook contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Deployment admin
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// * `usdc_token` - Address of the USDC token contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        marketplace: Address,
+        usdc_token: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&BID_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Post a standing bid and escrow its budget
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - Researcher posting the bid (must authorize)
+    /// * `category` - Data category to list the bid under
+    /// * `spec_hash` - Hash of the off-chain data specification
+    /// * `price_per_study` - Most paid per study in a matched dataset
+    /// * `budget` - USDC escrowed for matches
+    /// * `curator` - Address that confirms matches
+    /// * `expires_at` - Ledger timestamp after which no more datasets match
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the bid ID
+    /// * `Err(Error)` if validation fails or the category book is full
+    #[allow(clippy::too_many_arguments)]
+    pub fn post_bid(
+        env: Env,
+        buyer: Address,
+        category: Symbol,
+        spec_hash: BytesN<32>,
+        price_per_study: i128,
+        budget: i128,
+        curator: Address,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        buyer.require_auth();
+        if price_per_study <= 0 || budget < price_per_study {
+            return Err(Error::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidExpiry);
+        }
+        let mut book = Self::get_open_bids(env.clone(), category.clone());
+        if book.len() >= MAX_OPEN_BIDS {
+            return Err(Error::TooManyBids);
+        }
+
+        // ============================================
+        // 2. ESCROW BUDGET
+        // ============================================
+        Self::token_client(&env)?.transfer(&buyer, &env.current_contract_address(), &budget);
+
+        // ============================================
+        // 3. STORE BID AND LIST IT
+        // ============================================
+        let storage = env.storage().instance();
+        let bid_id: u64 = storage.get(&BID_COUNT_KEY).ok_or(Error::NotInitialized)?;
+        storage.set(&BID_COUNT_KEY, &(bid_id + 1));
+
+        let bid = Bid {
+            bid_id,
+            buyer: buyer.clone(),
+            category: category.clone(),
+            spec_hash: spec_hash.clone(),
+            price_per_study,
+            remaining: budget,
+            curator,
+            expires_at,
+            matches: Vec::new(&env),
+            status: BidStatus::Open,
+        };
+        Self::save_bid(&env, &bid);
+        book.push_back(bid_id);
+        Self::save_book(&env, &category, &book);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("BidNew"), category, bid_id),
+            (buyer, spec_hash, price_per_study, budget),
+        );
+
+        Ok(bid_id)
+    }
+
+    /// Match a listed dataset against an open bid and settle it
+    ///
+    /// The curator confirms the dataset meets the bid's spec. The dataset
+    /// is bought at its listed price from the escrow, which must not exceed
+    /// `price_per_study` times its study count. A bid whose remaining
+    /// escrow cannot cover another study is filled and the rest refunded.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bid_id` - Bid to match
+    /// * `dataset_id` - ID of the dataset in the DatasetMarketplace
+    ///
+    /// # Returns
+    /// * `Ok(Bid)` with the updated bid
+    /// * `Err(Error::PriceAboveBid)` if the dataset costs more than the bid pays
+    /// * `Err(Error::InsufficientEscrow)` if the remaining escrow is too low
+    /// * `Err(Error)` if validation fails
+    pub fn match_dataset(env: Env, bid_id: u64, dataset_id: Bytes) -> Result<Bid, Error> {
+        // ============================================
+        // 1. VALIDATE BID AND DATASET
+        // ============================================
+        let mut bid = Self::load_open_bid(&env, bid_id)?;
+        bid.curator.require_auth();
+        if env.ledger().timestamp() > bid.expires_at {
+            return Err(Error::BidExpired);
+        }
+        if bid.matches.contains(&dataset_id) {
+            return Err(Error::AlreadyMatched);
+        }
+
+        let marketplace: Address = env
+            .storage()
+            .instance()
+            .get(&MARKETPLACE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        let dataset = match env.try_invoke_contract::<Dataset, soroban_sdk::Error>(
+            &marketplace,
+            &Symbol::new(&env, "get_dataset"),
+            (dataset_id.clone(),).into_val(&env),
+        ) {
+            Ok(Ok(dataset)) => dataset,
+            _ => return Err(Error::DatasetNotFound),
+        };
+        let price = dataset.price_usdc;
+        let max_price = math::checked_mul(bid.price_per_study, dataset.study_ids.len().into())
+            .map_err(|_| Error::InvalidAmount)?;
+        if price > max_price {
+            return Err(Error::PriceAboveBid);
+        }
+        if price > bid.remaining {
+            return Err(Error::InsufficientEscrow);
+        }
+
+        // ============================================
+        // 2. BUY THROUGH THE MARKETPLACE
+        // ============================================
+        let token = Self::token_client(&env)?;
+        let book = env.current_contract_address();
+        token.approve(&book, &marketplace, &price, &env.ledger().sequence());
+        let _: Dataset = env.invoke_contract(
+            &marketplace,
+            &Symbol::new(&env, "purchase_dataset_for"),
+            (dataset_id.clone(), bid.buyer.clone(), book.clone()).into_val(&env),
+        );
+
+        // ============================================
+        // 3. UPDATE BID
+        // ============================================
+        bid.remaining -= price;
+        bid.matches.push_back(dataset_id.clone());
+        if bid.remaining < bid.price_per_study {
+            if bid.remaining > 0 {
+                token.transfer(&book, &bid.buyer, &bid.remaining);
+                bid.remaining = 0;
+            }
+            bid.status = BidStatus::Filled;
+            Self::delist(&env, &bid);
+        }
+        Self::save_bid(&env, &bid);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("BidMatch"), bid.category.clone(), bid_id),
+            (dataset_id, price, bid.remaining),
+        );
+
+        Ok(bid)
+    }
+
+    /// Cancel an open bid and refund its remaining escrow to the buyer
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bid_id` - Bid to cancel
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the refunded amount
+    /// * `Err(Error)` if the bid is not open
+    pub fn cancel_bid(env: Env, bid_id: u64) -> Result<i128, Error> {
+        let mut bid = Self::load_open_bid(&env, bid_id)?;
+        bid.buyer.require_auth();
+
+        let refund = bid.remaining;
+        Self::token_client(&env)?.transfer(&env.current_contract_address(), &bid.buyer, &refund);
+        bid.remaining = 0;
+        bid.status = BidStatus::Cancelled;
+        Self::delist(&env, &bid);
+        Self::save_bid(&env, &bid);
+
+        env.events().publish(
+            (symbol_short!("BidCxl"), bid.category, bid_id),
+            refund,
+        );
+        Ok(refund)
+    }
+
+    /// Get a bid by ID
+    pub fn get_bid(env: Env, bid_id: u64) -> Result<Bid, Error> {
+        env.storage()
+            .persistent()
+            .get(&(BID_KEY, bid_id))
+            .ok_or(Error::BidNotFound)
+    }
+
+    /// Get the open bids listed under a category, oldest first
+    ///
+    /// Expired bids stay listed until their buyer cancels them.
+    pub fn get_open_bids(env: Env, category: Symbol) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(BOOK_KEY, category))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn load_open_bid(env: &Env, bid_id: u64) -> Result<Bid, Error> {
+        let bid = Self::get_bid(env.clone(), bid_id)?;
+        if bid.status != BidStatus::Open {
+            return Err(Error::BidNotOpen);
+        }
+        Ok(bid)
+    }
+
+    fn save_bid(env: &Env, bid: &Bid) {
+        let key = (BID_KEY, bid.bid_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, bid);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn save_book(env: &Env, category: &Symbol, book: &Vec<u64>) {
+        let key = (BOOK_KEY, category.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, book);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Remove a closed bid from its category book
+    fn delist(env: &Env, bid: &Bid) {
+        let mut book = Self::get_open_bids(env.clone(), bid.category.clone());
+        if let Some(index) = book.first_index_of(bid.bid_id) {
+            book.remove(index);
+            Self::save_book(env, &bid.category, &book);
+        }
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
@@ -0,0 +1,243 @@
+#![cfg(test)]
+
+use bid_book::{BidBook, BidBookClient, BidStatus, Error};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env, Symbol, Vec,
+};
+
+/// Price the test bids pay per study (50 USDC)
+const PER_STUDY: i128 = 50_0000000;
+
+/// Bid expiry used by the tests
+const EXPIRES_AT: u64 = 1_000_000;
+
+struct Setup<'a> {
+    env: Env,
+    book: BidBookClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    buyer: Address,
+    curator: Address,
+}
+
+/// Helper: Deploy the core contracts and a BidBook, with a funded buyer
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let book = BidBookClient::new(&env, &env.register_contract(None, BidBook));
+    book.init(&Address::generate(&env), &marketplace.address, &usdc_id);
+
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&buyer, &(10 * PER_STUDY));
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        curator: Address::generate(&env),
+        env,
+        book,
+        registry,
+        marketplace,
+        buyer,
+    }
+}
+
+/// Helper: Category used by the tests
+fn cardio() -> Symbol {
+    symbol_short!("cardio")
+}
+
+/// Helper: Post a cardiology bid with the given budget
+fn post_bid(s: &Setup, budget: i128) -> u64 {
+    s.book.post_bid(
+        &s.buyer,
+        &cardio(),
+        &BytesN::from_array(&s.env, &[7u8; 32]),
+        &PER_STUDY,
+        &budget,
+        &s.curator,
+        &EXPIRES_AT,
+    )
+}
+
+/// Helper: List a dataset of `studies` new studies (hashes from `seed`),
+/// each from its own contributor
+fn list_dataset(s: &Setup, id: &[u8], seed: u8, studies: u8, price: i128) -> (Bytes, Vec<Address>) {
+    let mut study_ids = Vec::new(&s.env);
+    let mut contributors = Vec::new(&s.env);
+    for i in 0..studies {
+        let contributor = Address::generate(&s.env);
+        let hash = [seed + i; 32];
+        let study_hash = BytesN::from_array(&s.env, &hash);
+        let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &contributor);
+        s.registry.register_study(
+            &study_hash,
+            &attestation,
+            &study_registry::ProofKind::Groth16,
+            &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &contributor),
+            &contributor,
+        );
+        study_ids.push_back(Bytes::from_array(&s.env, &hash));
+        contributors.push_back(contributor);
+    }
+    let dataset_id = Bytes::from_slice(&s.env, id);
+    s.marketplace.register_dataset(&Address::generate(&s.env), &dataset_id, &study_ids, &price, &None);
+    (dataset_id, contributors)
+}
+
+#[test]
+fn test_match_buys_dataset_through_payout_path() {
+    let s = setup();
+    let bid_id = post_bid(&s, 4 * PER_STUDY);
+    let (dataset_id, contributors) = list_dataset(&s, b"dataset_001", 1, 2, 2 * PER_STUDY);
+
+    // Act
+    let bid = s.book.match_dataset(&bid_id, &dataset_id);
+
+    // Assert: the buyer holds the purchase and contributors were paid
+    assert_eq!(
+        s.marketplace.get_purchase(&dataset_id, &s.buyer).buyer,
+        s.buyer
+    );
+    for contributor in contributors.iter() {
+        assert!(s.usdc.balance(&contributor) > 0);
+    }
+    assert_eq!(bid.remaining, 2 * PER_STUDY);
+    assert_eq!(bid.matches, vec![&s.env, dataset_id.clone()]);
+    assert_eq!(bid.status, BidStatus::Open);
+    assert_eq!(s.usdc.balance(&s.book.address), 2 * PER_STUDY);
+    assert_eq!(
+        s.book.try_match_dataset(&bid_id, &dataset_id),
+        Err(Ok(Error::AlreadyMatched))
+    );
+}
+
+#[test]
+fn test_filled_bid_refunds_rest_and_leaves_book() {
+    let s = setup();
+    let bid_id = post_bid(&s, 3 * PER_STUDY);
+    let other_id = post_bid(&s, PER_STUDY);
+    let (dataset_id, _) = list_dataset(&s, b"dataset_001", 1, 3, 2 * PER_STUDY + PER_STUDY / 2);
+
+    // Act: half a study's price is left, which is refunded
+    let bid = s.book.match_dataset(&bid_id, &dataset_id);
+
+    // Assert
+    assert_eq!((bid.status, bid.remaining), (BidStatus::Filled, 0));
+    assert_eq!(
+        s.usdc.balance(&s.buyer),
+        10 * PER_STUDY - 3 * PER_STUDY - PER_STUDY + PER_STUDY / 2
+    );
+    assert_eq!(s.book.get_open_bids(&cardio()), vec![&s.env, other_id]);
+    assert_eq!(
+        s.book.try_cancel_bid(&bid_id),
+        Err(Ok(Error::BidNotOpen))
+    );
+}
+
+#[test]
+fn test_match_rejects_overpriced_and_unknown_datasets() {
+    let s = setup();
+    let bid_id = post_bid(&s, 2 * PER_STUDY);
+    let (pricey, _) = list_dataset(&s, b"dataset_001", 1, 1, PER_STUDY + 1);
+    let (large, _) = list_dataset(&s, b"dataset_002", 2, 3, 3 * PER_STUDY);
+
+    // Act & Assert
+    assert_eq!(
+        s.book.try_match_dataset(&bid_id, &pricey),
+        Err(Ok(Error::PriceAboveBid))
+    );
+    assert_eq!(
+        s.book.try_match_dataset(&bid_id, &large),
+        Err(Ok(Error::InsufficientEscrow))
+    );
+    assert_eq!(
+        s.book.try_match_dataset(&bid_id, &Bytes::from_slice(&s.env, b"missing")),
+        Err(Ok(Error::DatasetNotFound))
+    );
+
+    // Expired bids no longer match
+    let (dataset_id, _) = list_dataset(&s, b"dataset_003", 5, 1, PER_STUDY);
+    s.env.ledger().set_timestamp(EXPIRES_AT + 1);
+    assert_eq!(
+        s.book.try_match_dataset(&bid_id, &dataset_id),
+        Err(Ok(Error::BidExpired))
+    );
+}
+
+#[test]
+fn test_cancel_refunds_escrow() {
+    let s = setup();
+    let bid_id = post_bid(&s, 2 * PER_STUDY);
+    assert_eq!(s.usdc.balance(&s.buyer), 8 * PER_STUDY);
+
+    // Act
+    let refund = s.book.cancel_bid(&bid_id);
+
+    // Assert
+    assert_eq!(refund, 2 * PER_STUDY);
+    assert_eq!(s.usdc.balance(&s.buyer), 10 * PER_STUDY);
+    assert_eq!(s.book.get_bid(&bid_id).status, BidStatus::Cancelled);
+    assert!(s.book.get_open_bids(&cardio()).is_empty());
+}
+
+#[test]
+fn test_post_bid_validates_inputs() {
+    let s = setup();
+    let spec_hash = BytesN::from_array(&s.env, &[7u8; 32]);
+
+    // Act & Assert: the budget must cover at least one study
+    assert_eq!(
+        s.book.try_post_bid(
+            &s.buyer,
+            &cardio(),
+            &spec_hash,
+            &PER_STUDY,
+            &(PER_STUDY - 1),
+            &s.curator,
+            &EXPIRES_AT,
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.book.try_post_bid(
+            &s.buyer,
+            &cardio(),
+            &spec_hash,
+            &PER_STUDY,
+            &PER_STUDY,
+            &s.curator,
+            &s.env.ledger().timestamp(),
+        ),
+        Err(Ok(Error::InvalidExpiry))
+    );
+    assert_eq!(
+        s.book.try_get_bid(&0),
+        Err(Ok(Error::BidNotFound))
+    );
+}
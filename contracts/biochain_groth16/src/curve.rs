@@ -0,0 +1,278 @@
+//! BN254 groups G1 (over Fq) and G2 (over Fq2, on the sextic twist)
+//!
+//! Both curves have the form y² = x³ + b, so the group law is written once
+//! over the `Field` trait. Scalar multiplication runs in Jacobian
+//! coordinates to avoid an inversion per step.
+
+use crate::field::{limbs_to_be, Field, Fq, Fq2, Fr};
+use crate::{Groth16Error, G1_LEN, G2_LEN};
+
+/// Coefficient b of G2's twist, 3 / (9 + u), in Montgomery form
+const TWIST_B: Fq2 = Fq2::new(
+    Fq([0x3bf938e377b802a8, 0x020b1b273633535d, 0x26b7edf049755260, 0x2514c6324384a86d]),
+    Fq([0x38e7ecccd1dcff67, 0x65f0b37d93ce0d3e, 0xd749d0dd22ac00aa, 0x0141b9ce4a688d4d]),
+);
+
+/// Point in affine coordinates
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Affine<F: Field> {
+    pub x: F,
+    pub y: F,
+    pub infinity: bool,
+}
+
+impl<F: Field> Affine<F> {
+    pub const IDENTITY: Self = Affine {
+        x: F::ZERO,
+        y: F::ZERO,
+        infinity: true,
+    };
+
+    pub fn new(x: F, y: F) -> Self {
+        Affine { x, y, infinity: false }
+    }
+
+    /// Check y² = x³ + b
+    fn is_on_curve(&self, b: &F) -> bool {
+        self.infinity || self.y.square() == self.x.square().mul(&self.x).add(b)
+    }
+
+    pub fn neg(&self) -> Self {
+        Affine {
+            x: self.x,
+            y: self.y.neg(),
+            infinity: self.infinity,
+        }
+    }
+
+    /// `scalar · self`, scalar as a big-endian 32-byte integer
+    pub fn mul(&self, scalar: &[u8; 32]) -> Self {
+        let mut acc = Jacobian::IDENTITY;
+        for byte in scalar {
+            for bit in (0..8).rev() {
+                acc = acc.double();
+                if (byte >> bit) & 1 == 1 {
+                    acc = acc.add_affine(self);
+                }
+            }
+        }
+        acc.to_affine()
+    }
+}
+
+/// Point (X / Z², Y / Z³) in Jacobian coordinates
+#[derive(Copy, Clone, Debug)]
+pub struct Jacobian<F: Field> {
+    x: F,
+    y: F,
+    z: F,
+}
+
+impl<F: Field> Jacobian<F> {
+    pub const IDENTITY: Self = Jacobian {
+        x: F::ONE,
+        y: F::ONE,
+        z: F::ZERO,
+    };
+
+    fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// `2 · self` (dbl-2009-l, a = 0)
+    pub fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let f = e.square();
+        let x = f.sub(&d.double());
+        let eight_c = c.double().double().double();
+        let y = e.mul(&d.sub(&x)).sub(&eight_c);
+        let z = self.y.mul(&self.z).double();
+        Jacobian { x, y, z }
+    }
+
+    /// `self + other` (madd-2007-bl)
+    pub fn add_affine(&self, other: &Affine<F>) -> Self {
+        if other.infinity {
+            return *self;
+        }
+        if self.is_identity() {
+            return Jacobian {
+                x: other.x,
+                y: other.y,
+                z: F::ONE,
+            };
+        }
+        let z1z1 = self.z.square();
+        let u2 = other.x.mul(&z1z1);
+        let s2 = other.y.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y).double();
+        if h.is_zero() {
+            return if r.is_zero() { self.double() } else { Self::IDENTITY };
+        }
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h.mul(&i);
+        let v = self.x.mul(&i);
+        let x = r.square().sub(&j).sub(&v.double());
+        let y = r.mul(&v.sub(&x)).sub(&self.y.mul(&j).double());
+        let z = self.z.add(&h).square().sub(&z1z1).sub(&hh);
+        Jacobian { x, y, z }
+    }
+
+    pub fn to_affine(self) -> Affine<F> {
+        match self.z.inverse() {
+            None => Affine::IDENTITY,
+            Some(z_inv) => {
+                let z_inv2 = z_inv.square();
+                Affine::new(self.x.mul(&z_inv2), self.y.mul(&z_inv2).mul(&z_inv))
+            }
+        }
+    }
+}
+
+/// Read a big-endian base field element from `bytes[offset..offset + 32]`
+fn read_fq(bytes: &[u8], offset: usize) -> Result<Fq, Groth16Error> {
+    let mut word = [0u8; 32];
+    word.copy_from_slice(&bytes[offset..offset + 32]);
+    Fq::from_be_bytes(&word).ok_or(Groth16Error::InvalidPoint)
+}
+
+/// Point of G1
+///
+/// Encoded as x || y, 32-byte big-endian coordinates; all zeros is the
+/// point at infinity.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct G1Point(pub(crate) Affine<Fq>);
+
+impl G1Point {
+    /// Generator (1, 2)
+    pub fn generator() -> Self {
+        G1Point(Affine::new(Fq::from_u64(1), Fq::from_u64(2)))
+    }
+
+    /// Parse an encoded point
+    ///
+    /// G1 has cofactor 1, so every point on the curve is in the group.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPoint)` if a coordinate is not
+    ///   canonical or the point is not on the curve
+    pub fn from_bytes(bytes: &[u8; G1_LEN]) -> Result<Self, Groth16Error> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G1Point(Affine::IDENTITY));
+        }
+        let point = Affine::new(read_fq(bytes, 0)?, read_fq(bytes, 32)?);
+        if !point.is_on_curve(&Fq::from_u64(3)) {
+            return Err(Groth16Error::InvalidPoint);
+        }
+        Ok(G1Point(point))
+    }
+
+    pub fn to_bytes(&self) -> [u8; G1_LEN] {
+        let mut bytes = [0u8; G1_LEN];
+        if !self.0.infinity {
+            bytes[..32].copy_from_slice(&self.0.x.to_be_bytes());
+            bytes[32..].copy_from_slice(&self.0.y.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// `scalar · self`, scalar as a big-endian 32-byte integer
+    pub fn mul(&self, scalar: &[u8; 32]) -> Self {
+        G1Point(self.0.mul(scalar))
+    }
+
+    pub fn neg(&self) -> Self {
+        G1Point(self.0.neg())
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0.infinity
+    }
+}
+
+/// Point of G2
+///
+/// Encoded as x.c1 || x.c0 || y.c1 || y.c0, 32-byte big-endian
+/// coefficients (the Ethereum precompile order); all zeros is the point
+/// at infinity.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct G2Point(pub(crate) Affine<Fq2>);
+
+impl G2Point {
+    /// Standard generator, in Montgomery form
+    pub fn generator() -> Self {
+        G2Point(Affine::new(
+            Fq2::new(
+                Fq([0x8e83b5d102bc2026, 0xdceb1935497b0172, 0xfbb8264797811adf, 0x19573841af96503b]),
+                Fq([0xafb4737da84c6140, 0x6043dd5a5802d8c4, 0x09e950fc52a02f86, 0x14fef0833aea7b6b]),
+            ),
+            Fq2::new(
+                Fq([0x619dfa9d886be9f6, 0xfe7fd297f59e9b78, 0xff9e1a62231b7dfe, 0x28fd7eebae9e4206]),
+                Fq([0x64095b56c71856ee, 0xdc57f922327d3cbb, 0x55f935be33351076, 0x0da4a0e693fd6482]),
+            ),
+        ))
+    }
+
+    /// Parse an encoded point
+    ///
+    /// The twist has a large cofactor, so points are also checked to lie
+    /// in the prime-order subgroup.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPoint)` if a coefficient is not
+    ///   canonical, or the point is not on the twist or not in G2
+    pub fn from_bytes(bytes: &[u8; G2_LEN]) -> Result<Self, Groth16Error> {
+        let point = Self::from_trusted_bytes(bytes)?;
+        if !point.0.mul(&limbs_to_be(&Fr::MODULUS)).infinity {
+            return Err(Groth16Error::InvalidPoint);
+        }
+        Ok(point)
+    }
+
+    /// Parse an encoded point that already passed `from_bytes`
+    ///
+    /// Skips the subgroup check, the bulk of the parsing cost, for points
+    /// validated when they were stored (such as a verifying key). Never use
+    /// it on proof points.
+    pub fn from_trusted_bytes(bytes: &[u8; G2_LEN]) -> Result<Self, Groth16Error> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G2Point(Affine::IDENTITY));
+        }
+        let x = Fq2::new(read_fq(bytes, 32)?, read_fq(bytes, 0)?);
+        let y = Fq2::new(read_fq(bytes, 96)?, read_fq(bytes, 64)?);
+        let point = Affine::new(x, y);
+        if !point.is_on_curve(&TWIST_B) {
+            return Err(Groth16Error::InvalidPoint);
+        }
+        Ok(G2Point(point))
+    }
+
+    pub fn to_bytes(&self) -> [u8; G2_LEN] {
+        let mut bytes = [0u8; G2_LEN];
+        if !self.0.infinity {
+            bytes[..32].copy_from_slice(&self.0.x.c1.to_be_bytes());
+            bytes[32..64].copy_from_slice(&self.0.x.c0.to_be_bytes());
+            bytes[64..96].copy_from_slice(&self.0.y.c1.to_be_bytes());
+            bytes[96..].copy_from_slice(&self.0.y.c0.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// `scalar · self`, scalar as a big-endian 32-byte integer
+    pub fn mul(&self, scalar: &[u8; 32]) -> Self {
+        G2Point(self.0.mul(scalar))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0.infinity
+    }
+}
@@ -0,0 +1,507 @@
+//! BN254 field arithmetic
+//!
+//! Fq and Fr are 254-bit prime fields in Montgomery form over four 64-bit
+//! limbs (least significant first). The pairing target group lives in the
+//! tower Fq2 = Fq[u]/(u² + 1), Fq6 = Fq2[v]/(v³ - ξ) with ξ = 9 + u, and
+//! Fq12 = Fq6[w]/(w² - v).
+
+/// Arithmetic shared by the base fields and their extensions, so curve
+/// formulas can be written once for G1 (over Fq) and G2 (over Fq2)
+pub trait Field: Copy + Eq {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn add(&self, other: &Self) -> Self;
+    fn sub(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn inverse(&self) -> Option<Self>;
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+}
+
+/// `a + b + carry`, returning the low limb and the carry
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// `a - b - borrow`, returning the low limb and the borrow (0 or 1)
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+/// `a + b * c + carry`, returning the low limb and the carry
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 * c as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Compare two little-endian limb arrays
+fn limbs_lt(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// Parse a big-endian 32-byte integer into little-endian limbs
+pub fn limbs_from_be(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[start..start + 8]);
+        *limb = u64::from_be_bytes(word);
+    }
+    limbs
+}
+
+/// Serialize little-endian limbs as a big-endian 32-byte integer
+pub fn limbs_to_be(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+/// Define a 254-bit prime field in Montgomery form
+///
+/// - modulus: The prime, little-endian limbs
+/// - r: 2^256 mod modulus (one in Montgomery form)
+/// - r2: 2^512 mod modulus (converts into Montgomery form)
+/// - inv: -modulus^-1 mod 2^64
+macro_rules! prime_field {
+    ($name:ident, modulus: $modulus:expr, r: $r:expr, r2: $r2:expr, inv: $inv:expr) => {
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub struct $name(pub(crate) [u64; 4]);
+
+        // Fr only needs the full conversion set for test proofs
+        #[allow(dead_code)]
+        impl $name {
+            pub const MODULUS: [u64; 4] = $modulus;
+            const R2: [u64; 4] = $r2;
+            const INV: u64 = $inv;
+
+            /// Parse a canonical big-endian encoding
+            ///
+            /// # Returns
+            /// * `None` if the value is not below the modulus
+            pub fn from_be_bytes(bytes: &[u8; 32]) -> Option<Self> {
+                let limbs = limbs_from_be(bytes);
+                if !limbs_lt(&limbs, &Self::MODULUS) {
+                    return None;
+                }
+                Some($name(limbs).mul(&$name(Self::R2)))
+            }
+
+            /// Canonical big-endian encoding
+            pub fn to_be_bytes(self) -> [u8; 32] {
+                limbs_to_be(&self.to_canonical())
+            }
+
+            pub fn from_u64(value: u64) -> Self {
+                $name([value, 0, 0, 0]).mul(&$name(Self::R2))
+            }
+
+            /// Leave Montgomery form
+            fn to_canonical(self) -> [u64; 4] {
+                self.mul(&$name([1, 0, 0, 0])).0
+            }
+
+            /// Subtract the modulus once if the value is not below it
+            fn reduce(limbs: [u64; 4]) -> Self {
+                if limbs_lt(&limbs, &Self::MODULUS) {
+                    return $name(limbs);
+                }
+                let mut out = [0u64; 4];
+                let mut borrow = 0;
+                for i in 0..4 {
+                    (out[i], borrow) = sbb(limbs[i], Self::MODULUS[i], borrow);
+                }
+                $name(out)
+            }
+
+            /// `self ^ exponent`, exponent as little-endian limbs
+            pub fn pow(&self, exponent: &[u64]) -> Self {
+                let mut result = Self::ONE;
+                for limb in exponent.iter().rev() {
+                    for bit in (0..64).rev() {
+                        result = result.square();
+                        if (limb >> bit) & 1 == 1 {
+                            result = result.mul(self);
+                        }
+                    }
+                }
+                result
+            }
+        }
+
+        impl Field for $name {
+            const ZERO: Self = $name([0; 4]);
+            const ONE: Self = $name($r);
+
+            fn add(&self, other: &Self) -> Self {
+                // Both operands are below 2^254, so the sum cannot overflow
+                let mut out = [0u64; 4];
+                let mut carry = 0;
+                for i in 0..4 {
+                    (out[i], carry) = adc(self.0[i], other.0[i], carry);
+                }
+                Self::reduce(out)
+            }
+
+            fn sub(&self, other: &Self) -> Self {
+                let mut out = [0u64; 4];
+                let mut borrow = 0;
+                for i in 0..4 {
+                    (out[i], borrow) = sbb(self.0[i], other.0[i], borrow);
+                }
+                if borrow != 0 {
+                    let mut carry = 0;
+                    for i in 0..4 {
+                        (out[i], carry) = adc(out[i], Self::MODULUS[i], carry);
+                    }
+                }
+                $name(out)
+            }
+
+            fn mul(&self, other: &Self) -> Self {
+                // CIOS Montgomery multiplication
+                let mut t = [0u64; 6];
+                for i in 0..4 {
+                    let mut carry = 0;
+                    for j in 0..4 {
+                        (t[j], carry) = mac(t[j], self.0[j], other.0[i], carry);
+                    }
+                    (t[4], t[5]) = adc(t[4], carry, 0);
+
+                    let m = t[0].wrapping_mul(Self::INV);
+                    let (_, mut carry) = mac(t[0], m, Self::MODULUS[0], 0);
+                    for j in 1..4 {
+                        (t[j - 1], carry) = mac(t[j], m, Self::MODULUS[j], carry);
+                    }
+                    (t[3], carry) = adc(t[4], carry, 0);
+                    t[4] = t[5] + carry;
+                }
+                Self::reduce([t[0], t[1], t[2], t[3]])
+            }
+
+            fn neg(&self) -> Self {
+                Self::ZERO.sub(self)
+            }
+
+            fn inverse(&self) -> Option<Self> {
+                if self.is_zero() {
+                    return None;
+                }
+                // Fermat: a^(m - 2)
+                let mut exponent = Self::MODULUS;
+                exponent[0] -= 2;
+                Some(self.pow(&exponent))
+            }
+        }
+    };
+}
+
+prime_field!(
+    Fq,
+    modulus: [0x3c208c16d87cfd47, 0x97816a916871ca8d, 0xb85045b68181585d, 0x30644e72e131a029],
+    r: [0xd35d438dc58f0d9d, 0x0a78eb28f5c70b3d, 0x666ea36f7879462c, 0x0e0a77c19a07df2f],
+    r2: [0xf32cfc5b538afa89, 0xb5e71911d44501fb, 0x47ab1eff0a417ff6, 0x06d89f71cab8351f],
+    inv: 0x87d20782e4866389
+);
+
+prime_field!(
+    Fr,
+    modulus: [0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029],
+    r: [0xac96341c4ffffffb, 0x36fc76959f60cd29, 0x666ea36f7879462e, 0x0e0a77c19a07df2f],
+    r2: [0x1bb8e645ae216da7, 0x53fe3ab1e35c59e3, 0x8c49833d53bb8085, 0x0216d0b17f4e44a5],
+    inv: 0xc2e1f593efffffff
+);
+
+/// Element c0 + c1·u of Fq2
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fq2 {
+    pub c0: Fq,
+    pub c1: Fq,
+}
+
+impl Fq2 {
+    pub const fn new(c0: Fq, c1: Fq) -> Self {
+        Fq2 { c0, c1 }
+    }
+
+    /// Multiply by ξ = 9 + u
+    fn mul_by_nonresidue(&self) -> Self {
+        let nine = |x: &Fq| {
+            let x2 = x.double();
+            let x8 = x2.double().double();
+            x8.add(x)
+        };
+        Fq2 {
+            c0: nine(&self.c0).sub(&self.c1),
+            c1: self.c0.add(&nine(&self.c1)),
+        }
+    }
+
+    pub fn mul_by_fq(&self, other: &Fq) -> Self {
+        Fq2 {
+            c0: self.c0.mul(other),
+            c1: self.c1.mul(other),
+        }
+    }
+
+    /// Raise to the power p (conjugation)
+    pub fn frobenius(&self) -> Self {
+        Fq2 {
+            c0: self.c0,
+            c1: self.c1.neg(),
+        }
+    }
+}
+
+impl Field for Fq2 {
+    const ZERO: Self = Fq2::new(Fq::ZERO, Fq::ZERO);
+    const ONE: Self = Fq2::new(Fq::ONE, Fq::ZERO);
+
+    fn add(&self, other: &Self) -> Self {
+        Fq2::new(self.c0.add(&other.c0), self.c1.add(&other.c1))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fq2::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1))
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        // Karatsuba with u² = -1
+        let v0 = self.c0.mul(&other.c0);
+        let v1 = self.c1.mul(&other.c1);
+        let cross = self.c0.add(&self.c1).mul(&other.c0.add(&other.c1));
+        Fq2::new(v0.sub(&v1), cross.sub(&v0).sub(&v1))
+    }
+
+    fn neg(&self) -> Self {
+        Fq2::new(self.c0.neg(), self.c1.neg())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square().add(&self.c1.square());
+        let inv = norm.inverse()?;
+        Some(Fq2::new(self.c0.mul(&inv), self.c1.mul(&inv).neg()))
+    }
+}
+
+/// Element c0 + c1·v + c2·v² of Fq6
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fq6 {
+    pub c0: Fq2,
+    pub c1: Fq2,
+    pub c2: Fq2,
+}
+
+impl Fq6 {
+    pub const fn new(c0: Fq2, c1: Fq2, c2: Fq2) -> Self {
+        Fq6 { c0, c1, c2 }
+    }
+
+    /// Multiply by v
+    fn mul_by_nonresidue(&self) -> Self {
+        Fq6::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+    }
+}
+
+impl Field for Fq6 {
+    const ZERO: Self = Fq6::new(Fq2::ZERO, Fq2::ZERO, Fq2::ZERO);
+    const ONE: Self = Fq6::new(Fq2::ONE, Fq2::ZERO, Fq2::ZERO);
+
+    fn add(&self, other: &Self) -> Self {
+        Fq6::new(
+            self.c0.add(&other.c0),
+            self.c1.add(&other.c1),
+            self.c2.add(&other.c2),
+        )
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fq6::new(
+            self.c0.sub(&other.c0),
+            self.c1.sub(&other.c1),
+            self.c2.sub(&other.c2),
+        )
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        // Karatsuba with v³ = ξ
+        let aa = self.c0.mul(&other.c0);
+        let bb = self.c1.mul(&other.c1);
+        let cc = self.c2.mul(&other.c2);
+        let c0 = self.c1.add(&self.c2)
+            .mul(&other.c1.add(&other.c2))
+            .sub(&bb)
+            .sub(&cc)
+            .mul_by_nonresidue()
+            .add(&aa);
+        let c1 = self.c0.add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&aa)
+            .sub(&bb)
+            .add(&cc.mul_by_nonresidue());
+        let c2 = self.c0.add(&self.c2)
+            .mul(&other.c0.add(&other.c2))
+            .sub(&aa)
+            .add(&bb)
+            .sub(&cc);
+        Fq6::new(c0, c1, c2)
+    }
+
+    fn neg(&self) -> Self {
+        Fq6::new(self.c0.neg(), self.c1.neg(), self.c2.neg())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let t0 = self.c0.square().sub(&self.c1.mul(&self.c2).mul_by_nonresidue());
+        let t1 = self.c2.square().mul_by_nonresidue().sub(&self.c0.mul(&self.c1));
+        let t2 = self.c1.square().sub(&self.c0.mul(&self.c2));
+        let norm = self.c0.mul(&t0).add(
+            &self.c2.mul(&t1).add(&self.c1.mul(&t2)).mul_by_nonresidue(),
+        );
+        let inv = norm.inverse()?;
+        Some(Fq6::new(t0.mul(&inv), t1.mul(&inv), t2.mul(&inv)))
+    }
+}
+
+/// ξ^(k·(p^i - 1) / 6) for i = 1, 2, 3 and k = 1..=5, in Montgomery form
+///
+/// The Frobenius map p^i sends w^k to this multiple of w^k.
+pub(crate) const FROBENIUS_COEFFS: [[Fq2; 5]; 3] = [
+    [
+        Fq2::new(Fq([0xaf9ba69633144907, 0xca6b1d7387afb78a, 0x11bded5ef08a2087, 0x02f34d751a1f3a7c]), Fq([0xa222ae234c492d72, 0xd00f02a4565de15b, 0xdc2ff3a253dfc926, 0x10a75716b3899551])),
+        Fq2::new(Fq([0xb5773b104563ab30, 0x347f91c8a9aa6454, 0x7a007127242e0991, 0x1956bcd8118214ec]), Fq([0x6e849f1ea0aa4757, 0xaa1c7b6d89f89141, 0xb6e713cdfae0ca3a, 0x26694fbb4e82ebc3])),
+        Fq2::new(Fq([0xe4bbdd0c2936b629, 0xbb30f162e133bacb, 0x31a9d1b6f9645366, 0x253570bea500f8dd]), Fq([0xa1d77ce45ffe77c7, 0x07affd117826d1db, 0x6d16bd27bb7edc6b, 0x2c87200285defecc])),
+        Fq2::new(Fq([0x7361d77f843abe92, 0xa5bb2bd3273411fb, 0x9c941f314b3e2399, 0x15df9cddbb9fd3ec]), Fq([0x5dddfd154bd8c949, 0x62cb29a5a4445b60, 0x37bc870a0c7dd2b9, 0x24830a9d3171f0fd])),
+        Fq2::new(Fq([0xc970692f41690fe7, 0xe240342127694b0b, 0x32bee66b83c459e8, 0x12aabced0ab08841]), Fq([0x0d485d2340aebfa9, 0x05193418ab2fcc57, 0xd3b0a40b8a4910f5, 0x2f21ebb535d2925a])),
+    ],
+    [
+        Fq2::new(Fq([0xca8d800500fa1bf2, 0xf0c5d61468b39769, 0x0e201271ad0d4418, 0x04290f65bad856e6]), Fq::ZERO),
+        Fq2::new(Fq([0x3350c88e13e80b9c, 0x7dce557cdb5e56b9, 0x6001b4b8b615564a, 0x2682e617020217e0]), Fq::ZERO),
+        Fq2::new(Fq([0x68c3488912edefaa, 0x8d087f6872aabf4f, 0x51e1a24709081231, 0x2259d6b14729c0fa]), Fq::ZERO),
+        Fq2::new(Fq([0x71930c11d782e155, 0xa6bb947cffbe3323, 0xaa303344d4741444, 0x2c3b3f0d26594943]), Fq::ZERO),
+        Fq2::new(Fq([0x08cfc388c494f1ab, 0x19b315148d1373d4, 0x584e90fdcb6c0213, 0x09e1685bdf2f8849]), Fq::ZERO),
+    ],
+    [
+        Fq2::new(Fq([0x365316184e46d97d, 0x0af7129ed4c96d9f, 0x659da72fca1009b5, 0x08116d8983a20d23]), Fq([0xb1df4af7c39c1939, 0x3d9f02878a73bf7f, 0x9b2220928caf0ae0, 0x26684515eff054a6])),
+        Fq2::new(Fq([0xc9af22f716ad6bad, 0xb311782a4aa662b2, 0x19eeaf64e248c7f4, 0x20273e77e3439f82]), Fq([0xacc02860f7ce93ac, 0x3933d5817ba76b4c, 0x69e6188b446c8467, 0x0a46036d4417cc55])),
+        Fq2::new(Fq([0x5764af0aaf46471e, 0xdc50792e873e0fc1, 0x86a673ff881d04f6, 0x0b2eddb43c30a74c]), Fq([0x9a490f32787e8580, 0x8fd16d7ff04af8b1, 0x4b39888ec6027bf2, 0x03dd2e705b52a15d])),
+        Fq2::new(Fq([0x448a93a57b6762df, 0xbfd62df528fdeadf, 0xd858f5d00e9bd47a, 0x06b03d4d3476ec58]), Fq([0x2b19daf4bcc936d1, 0xa1a54e7a56f4299f, 0xb533eee05adeaef1, 0x170c812b84dda0b2])),
+        Fq2::new(Fq([0xe0bc4b2275cf559f, 0xc238b945c154e60f, 0x803982a5929a7d5e, 0x15ce052df7e4a37e]), Fq([0x2d28efbdbf3799a7, 0x9b097e3c1ad60773, 0x982d4113af4a535b, 0x24e18991e3056063])),
+    ],
+];
+
+/// Element c0 + c1·w of Fq12
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fq12 {
+    pub c0: Fq6,
+    pub c1: Fq6,
+}
+
+impl Fq12 {
+    pub const fn new(c0: Fq6, c1: Fq6) -> Self {
+        Fq12 { c0, c1 }
+    }
+
+    /// Raise to the power p^6
+    pub fn conjugate(&self) -> Self {
+        Fq12::new(self.c0, self.c1.neg())
+    }
+
+    /// Raise to the power p^power, for power 1, 2 or 3
+    pub fn frobenius(&self, power: usize) -> Self {
+        let coeffs = &FROBENIUS_COEFFS[power - 1];
+        let map = |c: &Fq2| if power % 2 == 1 { c.frobenius() } else { *c };
+        // c0 holds w^0, w^2, w^4 and c1 holds w^1, w^3, w^5
+        Fq12::new(
+            Fq6::new(
+                map(&self.c0.c0),
+                map(&self.c0.c1).mul(&coeffs[1]),
+                map(&self.c0.c2).mul(&coeffs[3]),
+            ),
+            Fq6::new(
+                map(&self.c1.c0).mul(&coeffs[0]),
+                map(&self.c1.c1).mul(&coeffs[2]),
+                map(&self.c1.c2).mul(&coeffs[4]),
+            ),
+        )
+    }
+
+    /// `self ^ exponent`
+    pub fn pow_u64(&self, exponent: u64) -> Self {
+        let mut result = Self::ONE;
+        for bit in (0..64 - exponent.leading_zeros()).rev() {
+            result = result.square();
+            if (exponent >> bit) & 1 == 1 {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+}
+
+impl Field for Fq12 {
+    const ZERO: Self = Fq12::new(Fq6::ZERO, Fq6::ZERO);
+    const ONE: Self = Fq12::new(Fq6::ONE, Fq6::ZERO);
+
+    fn add(&self, other: &Self) -> Self {
+        Fq12::new(self.c0.add(&other.c0), self.c1.add(&other.c1))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fq12::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1))
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        // Karatsuba with w² = v
+        let v0 = self.c0.mul(&other.c0);
+        let v1 = self.c1.mul(&other.c1);
+        let cross = self.c0.add(&self.c1).mul(&other.c0.add(&other.c1));
+        Fq12::new(v0.add(&v1.mul_by_nonresidue()), cross.sub(&v0).sub(&v1))
+    }
+
+    fn square(&self) -> Self {
+        // (a + b·w)² = (a + b)(a + v·b) - ab - v·ab + 2ab·w
+        let ab = self.c0.mul(&self.c1);
+        let c0 = self.c0.add(&self.c1)
+            .mul(&self.c0.add(&self.c1.mul_by_nonresidue()))
+            .sub(&ab)
+            .sub(&ab.mul_by_nonresidue());
+        Fq12::new(c0, ab.double())
+    }
+
+    fn neg(&self) -> Self {
+        Fq12::new(self.c0.neg(), self.c1.neg())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square().sub(&self.c1.square().mul_by_nonresidue());
+        let inv = norm.inverse()?;
+        Some(Fq12::new(self.c0.mul(&inv), self.c1.mul(&inv).neg()))
+    }
+}
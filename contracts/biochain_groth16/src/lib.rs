@@ -0,0 +1,214 @@
+//! Groth16 verifier over BN254 for the BIOCHAIN contracts
+//!
+//! Soroban has no pairing host functions for BN254, so the curve
+//! arithmetic and the optimal ate pairing are implemented here over plain
+//! 64-bit limbs. The crate has no dependencies and no Soroban types, so
+//! contracts pass it raw bytes and the prover pipeline can use it to check
+//! proofs before submitting them. The arithmetic is checked against
+//! go-ethereum's EIP-196/197 precompile test vectors; it has not been
+//! audited.
+//!
+//! Encodings follow the Ethereum BN254 precompiles (and snarkjs exports):
+//! - G1 point: x || y (64 bytes)
+//! - G2 point: x.c1 || x.c0 || y.c1 || y.c0 (128 bytes)
+//! - Proof: A (G1) || B (G2) || C (G1) (256 bytes)
+//! - Verifying key: alpha (G1) || beta || gamma || delta (G2) || ic (G1 each)
+//! - Public signal: Scalar field element, 32 bytes big-endian
+//!
+//! All integers are big-endian and must be canonical (below the modulus);
+//! all zeros encodes the point at infinity.
+#![no_std]
+
+mod curve;
+mod field;
+mod pairing;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+
+pub use curve::{G1Point, G2Point};
+
+use field::Fr;
+
+/// Length of an encoded G1 point
+pub const G1_LEN: usize = 64;
+
+/// Length of an encoded G2 point
+pub const G2_LEN: usize = 128;
+
+/// Length of an encoded proof
+pub const PROOF_LEN: usize = G1_LEN + G2_LEN + G1_LEN;
+
+/// Length of an encoded public signal
+pub const SIGNAL_LEN: usize = 32;
+
+/// Error types for verifier operations
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Groth16Error {
+    InvalidLength,
+    InvalidPoint,
+    InvalidSignal,
+    SignalCountMismatch,
+}
+
+/// Groth16 proof
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Proof {
+    pub a: G1Point,
+    pub b: G2Point,
+    pub c: G1Point,
+}
+
+impl Proof {
+    /// Parse an encoded proof, validating each point
+    pub fn from_bytes(bytes: &[u8; PROOF_LEN]) -> Result<Self, Groth16Error> {
+        let mut a = [0u8; G1_LEN];
+        let mut b = [0u8; G2_LEN];
+        let mut c = [0u8; G1_LEN];
+        a.copy_from_slice(&bytes[..G1_LEN]);
+        b.copy_from_slice(&bytes[G1_LEN..G1_LEN + G2_LEN]);
+        c.copy_from_slice(&bytes[G1_LEN + G2_LEN..]);
+        Ok(Proof {
+            a: G1Point::from_bytes(&a)?,
+            b: G2Point::from_bytes(&b)?,
+            c: G1Point::from_bytes(&c)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; PROOF_LEN] {
+        let mut bytes = [0u8; PROOF_LEN];
+        bytes[..G1_LEN].copy_from_slice(&self.a.to_bytes());
+        bytes[G1_LEN..G1_LEN + G2_LEN].copy_from_slice(&self.b.to_bytes());
+        bytes[G1_LEN + G2_LEN..].copy_from_slice(&self.c.to_bytes());
+        bytes
+    }
+}
+
+/// Groth16 verifying key for a circuit with `N - 1` public signals
+///
+/// - alpha / beta / gamma / delta: Setup elements of the circuit
+/// - ic: Commitments to the public signals, the constant term first
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VerifyingKey<const N: usize> {
+    pub alpha: G1Point,
+    pub beta: G2Point,
+    pub gamma: G2Point,
+    pub delta: G2Point,
+    pub ic: [G1Point; N],
+}
+
+impl<const N: usize> VerifyingKey<N> {
+    /// Length of an encoded key
+    pub const ENCODED_LEN: usize = G1_LEN + 3 * G2_LEN + N * G1_LEN;
+
+    /// Parse an encoded key, validating each point
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidLength)` if the key does not have N
+    ///   commitments
+    /// * `Err(Groth16Error::InvalidPoint)` if a point is invalid
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        Self::parse(bytes, G2Point::from_bytes)
+    }
+
+    /// Parse an encoded key that already passed `from_bytes`
+    ///
+    /// Skips the G2 subgroup checks (see `G2Point::from_trusted_bytes`).
+    pub fn from_trusted_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        Self::parse(bytes, G2Point::from_trusted_bytes)
+    }
+
+    fn parse(
+        bytes: &[u8],
+        g2: fn(&[u8; G2_LEN]) -> Result<G2Point, Groth16Error>,
+    ) -> Result<Self, Groth16Error> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(Groth16Error::InvalidLength);
+        }
+        let g1_at = |offset: usize| {
+            let mut point = [0u8; G1_LEN];
+            point.copy_from_slice(&bytes[offset..offset + G1_LEN]);
+            G1Point::from_bytes(&point)
+        };
+        let g2_at = |offset: usize| {
+            let mut point = [0u8; G2_LEN];
+            point.copy_from_slice(&bytes[offset..offset + G2_LEN]);
+            g2(&point)
+        };
+
+        let ic_start = G1_LEN + 3 * G2_LEN;
+        let mut ic = [G1Point::generator(); N];
+        for (i, point) in ic.iter_mut().enumerate() {
+            *point = g1_at(ic_start + i * G1_LEN)?;
+        }
+        Ok(VerifyingKey {
+            alpha: g1_at(0)?,
+            beta: g2_at(G1_LEN)?,
+            gamma: g2_at(G1_LEN + G2_LEN)?,
+            delta: g2_at(G1_LEN + 2 * G2_LEN)?,
+            ic,
+        })
+    }
+
+    /// Encode the key into `out`, which must be ENCODED_LEN bytes long
+    pub fn write_bytes(&self, out: &mut [u8]) {
+        out[..G1_LEN].copy_from_slice(&self.alpha.to_bytes());
+        for (i, point) in [self.beta, self.gamma, self.delta].iter().enumerate() {
+            let offset = G1_LEN + i * G2_LEN;
+            out[offset..offset + G2_LEN].copy_from_slice(&point.to_bytes());
+        }
+        for (i, point) in self.ic.iter().enumerate() {
+            let offset = G1_LEN + 3 * G2_LEN + i * G1_LEN;
+            out[offset..offset + G1_LEN].copy_from_slice(&point.to_bytes());
+        }
+    }
+
+    /// Verify a proof for the given public signals
+    ///
+    /// Checks e(A, B) = e(alpha, beta) · e(L, gamma) · e(C, delta), where
+    /// L = ic[0] + Σ signals[i] · ic[i + 1].
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the proof is valid
+    /// * `Ok(false)` if it is not
+    /// * `Err(Groth16Error)` if the signals do not fit the key
+    pub fn verify(
+        &self,
+        proof: &Proof,
+        signals: &[[u8; SIGNAL_LEN]],
+    ) -> Result<bool, Groth16Error> {
+        if signals.len() + 1 != N {
+            return Err(Groth16Error::SignalCountMismatch);
+        }
+        if signals.iter().any(|signal| Fr::from_be_bytes(signal).is_none()) {
+            return Err(Groth16Error::InvalidSignal);
+        }
+
+        // Shared double-and-add over all signals
+        let mut acc = curve::Jacobian::IDENTITY;
+        for byte in 0..SIGNAL_LEN {
+            for bit in (0..8).rev() {
+                acc = acc.double();
+                for (signal, point) in signals.iter().zip(self.ic[1..].iter()) {
+                    if (signal[byte] >> bit) & 1 == 1 {
+                        acc = acc.add_affine(&point.0);
+                    }
+                }
+            }
+        }
+        let l = G1Point(acc.add_affine(&self.ic[0].0).to_affine());
+
+        Ok(pairing::pairing_check(&[
+            (proof.a.neg(), proof.b),
+            (self.alpha, self.beta),
+            (l, self.gamma),
+            (proof.c, self.delta),
+        ]))
+    }
+}
+
+/// Check e(p1, q1) = e(p2, q2)
+///
+/// Exposed so callers can sanity-check points against each other.
+pub fn pairings_equal(p1: &G1Point, q1: &G2Point, p2: &G1Point, q2: &G2Point) -> bool {
+    pairing::pairing_check(&[(p1.neg(), *q1), (*p2, *q2)])
+}
@@ -0,0 +1,217 @@
+//! Optimal ate pairing on BN254
+//!
+//! The Miller loop keeps the G2 points in homogeneous projective
+//! coordinates on the twist and maps each line into Fq12 through the
+//! untwisting (x, y) -> (x·w², y·w³). Lines are scaled by factors in Fq2
+//! to avoid inversions, and vertical lines are skipped: anything in a
+//! proper subfield is sent to one by the final exponentiation.
+
+use crate::curve::{Affine, G1Point, G2Point};
+use crate::field::{Field, Fq, Fq12, Fq2, Fq6, FROBENIUS_COEFFS};
+
+/// BN parameter u
+const BN_U: u64 = 4_965_661_367_192_848_881;
+
+/// 6u + 2, the optimal ate loop count
+const ATE_LOOP_COUNT: u128 = 6 * BN_U as u128 + 2;
+
+/// Bits of ATE_LOOP_COUNT below its leading one
+const ATE_LOOP_BITS: u32 = 127 - ATE_LOOP_COUNT.leading_zeros();
+
+/// ξ^((p² - 1) / 3), applied to x by the squared Frobenius map on the
+/// twist (which negates y)
+const FROBENIUS2_X: Fq = FROBENIUS_COEFFS[1][1].c0;
+
+/// Point (X / Z, Y / Z) of the twist in homogeneous projective coordinates
+#[derive(Copy, Clone)]
+struct Projective {
+    x: Fq2,
+    y: Fq2,
+    z: Fq2,
+}
+
+impl Projective {
+    const IDENTITY: Self = Projective {
+        x: Fq2::ZERO,
+        y: Fq2::ONE,
+        z: Fq2::ZERO,
+    };
+
+    fn from_affine(point: &Affine<Fq2>) -> Self {
+        if point.infinity {
+            return Self::IDENTITY;
+        }
+        Projective {
+            x: point.x,
+            y: point.y,
+            z: Fq2::ONE,
+        }
+    }
+}
+
+/// Line g0 + (h0 + h1·v)·w, evaluated at a G1 point
+fn line(g0: Fq2, h0: Fq2, h1: Fq2) -> Fq12 {
+    Fq12::new(
+        Fq6::new(g0, Fq2::ZERO, Fq2::ZERO),
+        Fq6::new(h0, h1, Fq2::ZERO),
+    )
+}
+
+/// Double `t` and return the tangent at `t` evaluated at `p`
+///
+/// With W = 3X², S = YZ, the tangent y_P - λ·x_P·w + (λ·x - y)·w³ is
+/// scaled by 2YZ².
+fn doubling_step(t: &mut Projective, p: &Affine<Fq>) -> Option<Fq12> {
+    if t.z.is_zero() || t.y.is_zero() {
+        *t = Projective::IDENTITY;
+        return None;
+    }
+    let xx = t.x.square();
+    let w = xx.double().add(&xx);
+    let yy = t.y.square();
+    let s = t.y.mul(&t.z);
+    let b = t.x.mul(&t.y).mul(&s);
+    let h = w.square().sub(&b.double().double().double());
+    let ss = s.square();
+
+    let tangent = line(
+        s.mul(&t.z).double().mul_by_fq(&p.y),
+        w.mul(&t.z).mul_by_fq(&p.x).neg(),
+        w.mul(&t.x).sub(&yy.mul(&t.z).double()),
+    );
+
+    let eight_yy_ss = yy.mul(&ss).double().double().double();
+    *t = Projective {
+        x: h.mul(&s).double(),
+        y: w.mul(&b.double().double().sub(&h)).sub(&eight_yy_ss),
+        z: ss.mul(&s).double().double().double(),
+    };
+    Some(tangent)
+}
+
+/// Add `q` to `t` and return the line through them evaluated at `p`
+///
+/// With θ = Y - y_Q·Z and μ = X - x_Q·Z (so λ = θ / μ), the line is
+/// evaluated through `q` and scaled by μ.
+fn addition_step(t: &mut Projective, q: &Affine<Fq2>, p: &Affine<Fq>) -> Option<Fq12> {
+    if q.infinity {
+        return None;
+    }
+    if t.z.is_zero() {
+        *t = Projective::from_affine(q);
+        return None;
+    }
+    let theta = t.y.sub(&q.y.mul(&t.z));
+    let mu = t.x.sub(&q.x.mul(&t.z));
+    if mu.is_zero() {
+        if theta.is_zero() {
+            return doubling_step(t, p);
+        }
+        *t = Projective::IDENTITY;
+        return None;
+    }
+
+    let chord = line(
+        mu.mul_by_fq(&p.y),
+        theta.mul_by_fq(&p.x).neg(),
+        theta.mul(&q.x).sub(&mu.mul(&q.y)),
+    );
+
+    let c = theta.square();
+    let d = mu.square();
+    let e = mu.mul(&d);
+    let f = t.z.mul(&c);
+    let g = t.x.mul(&d);
+    let h = e.add(&f).sub(&g.double());
+    *t = Projective {
+        x: mu.mul(&h),
+        y: theta.mul(&g.sub(&h)).sub(&e.mul(&t.y)),
+        z: t.z.mul(&e),
+    };
+    Some(chord)
+}
+
+/// Multiply a line into the Miller loop accumulator, skipping vertical ones
+fn accumulate(f: &mut Fq12, line: Option<Fq12>) {
+    if let Some(line) = line {
+        *f = f.mul(&line);
+    }
+}
+
+/// Product of the Miller loops of all pairs, sharing the squarings
+fn multi_miller_loop<const N: usize>(pairs: &[(G1Point, G2Point); N]) -> Fq12 {
+    let active = |p: &G1Point, q: &G2Point| !p.is_identity() && !q.is_identity();
+    let mut f = Fq12::ONE;
+    let mut ts = [Projective::IDENTITY; N];
+    for (t, (_, q)) in ts.iter_mut().zip(pairs.iter()) {
+        *t = Projective::from_affine(&q.0);
+    }
+
+    for bit in (0..ATE_LOOP_BITS).rev() {
+        f = f.square();
+        for (t, (p, q)) in ts.iter_mut().zip(pairs.iter()) {
+            if !active(p, q) {
+                continue;
+            }
+            accumulate(&mut f, doubling_step(t, &p.0));
+            if (ATE_LOOP_COUNT >> bit) & 1 == 1 {
+                accumulate(&mut f, addition_step(t, &q.0, &p.0));
+            }
+        }
+    }
+
+    let coeffs = &FROBENIUS_COEFFS[0];
+    for (t, (p, q)) in ts.iter_mut().zip(pairs.iter()) {
+        if !active(p, q) {
+            continue;
+        }
+        // π(Q) and -π²(Q)
+        let q1 = Affine::new(
+            q.0.x.frobenius().mul(&coeffs[1]),
+            q.0.y.frobenius().mul(&coeffs[2]),
+        );
+        let minus_q2 = Affine::new(q.0.x.mul_by_fq(&FROBENIUS2_X), q.0.y);
+        accumulate(&mut f, addition_step(t, &q1, &p.0));
+        accumulate(&mut f, addition_step(t, &minus_q2, &p.0));
+    }
+    f
+}
+
+/// `f ^ -u` for `f` in the cyclotomic subgroup (where inverting is
+/// conjugating)
+fn exp_by_neg_u(f: &Fq12) -> Fq12 {
+    f.pow_u64(BN_U).conjugate()
+}
+
+/// Raise a Miller loop output to a fixed multiple of (p^12 - 1) / r
+///
+/// The easy part is f^((p^6 - 1)(p² + 1)). The hard part follows
+/// Fuentes-Castañeda et al., "Faster hashing to G2", computing
+/// f^(2u(6u² + 3u + 1)(p^4 - p² + 1) / r); the extra factor is coprime
+/// to r, so a product of pairings is one exactly when this is.
+fn final_exponentiation(f: &Fq12) -> Option<Fq12> {
+    let f = f.conjugate().mul(&f.inverse()?);
+    let r = f.frobenius(2).mul(&f);
+
+    let y0 = exp_by_neg_u(&r);
+    let y1 = y0.square();
+    let y2 = y1.square();
+    let y3 = y2.mul(&y1);
+    let y4 = exp_by_neg_u(&y3);
+    let y5 = y4.square();
+    let y6 = exp_by_neg_u(&y5);
+    let y7 = y6.conjugate().mul(&y4);
+    let y8 = y7.mul(&y3.conjugate());
+    let y9 = y8.mul(&y1);
+    let y10 = y8.mul(&y4);
+    let y11 = y10.mul(&r);
+    let y13 = y9.frobenius(1).mul(&y11);
+    let y14 = y8.frobenius(2).mul(&y13);
+    let y15 = r.conjugate().mul(&y9);
+    Some(y15.frobenius(3).mul(&y14))
+}
+
+/// Check that the product of the pairings of `pairs` is one
+pub fn pairing_check<const N: usize>(pairs: &[(G1Point, G2Point); N]) -> bool {
+    final_exponentiation(&multi_miller_loop(pairs)) == Some(Fq12::ONE)
+}
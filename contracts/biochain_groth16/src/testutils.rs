@@ -0,0 +1,64 @@
+//! Test-only setup with a known trapdoor
+//!
+//! Contracts under test need proofs for public signals chosen at run time
+//! (generated addresses, per-test hashes). Knowing the setup's secret
+//! scalars lets this module produce a valid proof for any signals without
+//! a circuit. Never deploy `verifying_key()`: anyone can forge proofs for
+//! it.
+
+use crate::field::{Field, Fr};
+use crate::{G1Point, G2Point, Proof, VerifyingKey, SIGNAL_LEN};
+
+/// Secret setup scalars (alpha, beta, gamma, delta)
+const ALPHA: u64 = 0xa1fa;
+const BETA: u64 = 0xbe7a;
+const GAMMA: u64 = 0x9a33a;
+const DELTA: u64 = 0xde17a;
+
+/// Secret scalar of the constant term, then of each public signal
+const IC: [u64; 8] = [0x1c0, 0x1c1, 0x1c2, 0x1c3, 0x1c4, 0x1c5, 0x1c6, 0x1c7];
+
+/// Scalars of the proof's A and B
+const PROOF_A: u64 = 0xa;
+const PROOF_B: u64 = 0xb;
+
+fn scalar(value: u64) -> [u8; 32] {
+    Fr::from_u64(value).to_be_bytes()
+}
+
+/// Verifying key of the test setup, for `N - 1` public signals (N <= 8)
+pub fn verifying_key<const N: usize>() -> VerifyingKey<N> {
+    let g1 = G1Point::generator();
+    let g2 = G2Point::generator();
+    VerifyingKey {
+        alpha: g1.mul(&scalar(ALPHA)),
+        beta: g2.mul(&scalar(BETA)),
+        gamma: g2.mul(&scalar(GAMMA)),
+        delta: g2.mul(&scalar(DELTA)),
+        ic: core::array::from_fn(|i| g1.mul(&scalar(IC[i]))),
+    }
+}
+
+/// Proof for `signals` that verifies against `verifying_key()`
+///
+/// Picks C so that a·b = alpha·beta + l·gamma + c·delta, where l is the
+/// discrete log of the signals' commitment L.
+pub fn prove(signals: &[[u8; SIGNAL_LEN]]) -> Proof {
+    let mut l = Fr::from_u64(IC[0]);
+    for (signal, ic) in signals.iter().zip(IC[1..].iter()) {
+        let signal = Fr::from_be_bytes(signal).expect("signal is a scalar");
+        l = l.add(&signal.mul(&Fr::from_u64(*ic)));
+    }
+    let ab = Fr::from_u64(PROOF_A).mul(&Fr::from_u64(PROOF_B));
+    let alpha_beta = Fr::from_u64(ALPHA).mul(&Fr::from_u64(BETA));
+    let c = ab
+        .sub(&alpha_beta)
+        .sub(&l.mul(&Fr::from_u64(GAMMA)))
+        .mul(&Fr::from_u64(DELTA).inverse().expect("delta is not zero"));
+
+    Proof {
+        a: G1Point::generator().mul(&scalar(PROOF_A)),
+        b: G2Point::generator().mul(&scalar(PROOF_B)),
+        c: G1Point::generator().mul(&c.to_be_bytes()),
+    }
+}
@@ -0,0 +1,234 @@
+use biochain_groth16::{
+    pairings_equal, G1Point, G2Point, Groth16Error, Proof, VerifyingKey, G1_LEN, G2_LEN,
+    PROOF_LEN, SIGNAL_LEN,
+};
+
+/// Setup for two public signals, generated independently of this crate:
+/// alpha || beta || gamma || delta || ic[0..3]
+const VERIFYING_KEY: &str = include_str!("fixtures/verifying_key.hex");
+
+/// Proof for SIGNALS under VERIFYING_KEY
+const PROOF: &str = include_str!("fixtures/proof.hex");
+
+/// SHA-256 of biochain_zk_schema's public_inputs_v1 fixture, split into
+/// 128-bit halves
+const SIGNALS: &str = include_str!("fixtures/signals.hex");
+
+/// Point on the twist outside the prime-order subgroup
+const NON_SUBGROUP_G2: &str = "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010d1271953ed9ea0836846e70a1934187998c7f790cb4d7511b7f8da82de048a42869111d5381f072f8e2728fdb825a51aadd70e52c9830e9ab4b871c0531f1bb";
+
+/// Known-answer vectors from go-ethereum's EIP-196/197 precompile tests
+/// (core/vm/testdata/precompiles), in the same encodings as this crate
+///
+/// bn256ScalarMul "chfast1": point || scalar, and the product
+const MUL_INPUT: &str = "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb721611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb20400000000000000000000000000000000000000000000000011138ce750fa15c2";
+const MUL_OUTPUT: &str = "070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc";
+
+/// Twice the G1 generator, as listed with the EIP-196 test cases
+const G1_DOUBLE: &str = "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd315ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+
+/// bn256Pairing "jeff1" and "jeff2": two (G1, G2) pairs whose pairings
+/// multiply to one
+const PAIRING_INPUTS: [&str; 2] = [
+    "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f593034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf704bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a416782bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+    "2eca0c7238bf16e83e7a1e6c5d49540685ff51380f309842a98561558019fc0203d3260361bb8451de5ff5ecd17f010ff22f5c31cdf184e9020b06fa5997db841213d2149b006137fcfb23036606f848d638d576a120ca981b5b1a5f9300b3ee2276cf730cf493cd95d64677bbb75fc42db72513a4c1e387b476d056f80aa75f21ee6226d31426322afcda621464d0611d226783262e21bb3bc86b537e986237096df1f82dff337dd5972e32a8ad43e28a78a96a823ef1cd4debe12b6552ea5f06967a1237ebfeca9aaae0d6d0bab8e28c198c5a339ef8a2407e31cdac516db922160fa257a5fd5b280642ff47b65eca77e626cb685c84fa6d3b6882a283ddd1198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+];
+
+/// Helper: Decode a hex string
+fn hex(s: &str) -> Vec<u8> {
+    let s = s.trim().as_bytes();
+    let nibble = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+    s.chunks(2).map(|pair| nibble(pair[0]) << 4 | nibble(pair[1])).collect()
+}
+
+/// Helper: Scalar as a 32-byte big-endian integer
+fn scalar(value: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+fn fixture_key() -> VerifyingKey<3> {
+    VerifyingKey::from_bytes(&hex(VERIFYING_KEY)).unwrap()
+}
+
+fn fixture_proof() -> Proof {
+    Proof::from_bytes(hex(PROOF).as_slice().try_into().unwrap()).unwrap()
+}
+
+fn fixture_signals() -> [[u8; SIGNAL_LEN]; 2] {
+    let bytes = hex(SIGNALS);
+    core::array::from_fn(|i| bytes[i * SIGNAL_LEN..(i + 1) * SIGNAL_LEN].try_into().unwrap())
+}
+
+#[test]
+fn test_pairing_is_bilinear() {
+    let g1 = G1Point::generator();
+    let g2 = G2Point::generator();
+    assert!(pairings_equal(&g1.mul(&scalar(2)), &g2, &g1, &g2.mul(&scalar(2))));
+    assert!(pairings_equal(
+        &g1.mul(&scalar(6)),
+        &g2.mul(&scalar(5)),
+        &g1.mul(&scalar(10)),
+        &g2.mul(&scalar(3)),
+    ));
+    assert!(!pairings_equal(
+        &g1.mul(&scalar(6)),
+        &g2.mul(&scalar(5)),
+        &g1.mul(&scalar(10)),
+        &g2.mul(&scalar(4)),
+    ));
+}
+
+#[test]
+fn test_pairing_is_non_degenerate() {
+    let g1 = G1Point::generator();
+    let g2 = G2Point::generator();
+    assert!(!pairings_equal(&g1, &g2, &g1, &g2.mul(&scalar(2))));
+    assert!(!pairings_equal(&g1, &g2, &g1.neg(), &g2));
+}
+
+#[test]
+fn test_points_round_trip() {
+    let g1 = G1Point::generator().mul(&scalar(1234));
+    let g2 = G2Point::generator().mul(&scalar(5678));
+    assert_eq!(G1Point::from_bytes(&g1.to_bytes()), Ok(g1));
+    assert_eq!(G2Point::from_bytes(&g2.to_bytes()), Ok(g2));
+
+    let identity = G1Point::from_bytes(&[0u8; G1_LEN]).unwrap();
+    assert!(identity.is_identity());
+    assert_eq!(identity.to_bytes(), [0u8; G1_LEN]);
+    assert!(G2Point::from_bytes(&[0u8; G2_LEN]).unwrap().is_identity());
+
+    let proof = hex(PROOF);
+    assert_eq!(fixture_proof().to_bytes().as_slice(), proof.as_slice());
+}
+
+#[test]
+fn test_rejects_invalid_points() {
+    // (1, 3) is not on the curve
+    let mut off_curve = G1Point::generator().to_bytes();
+    off_curve[63] = 3;
+    assert_eq!(G1Point::from_bytes(&off_curve), Err(Groth16Error::InvalidPoint));
+
+    // x = p is not canonical
+    let mut non_canonical = [0u8; G1_LEN];
+    non_canonical[..32].copy_from_slice(&hex(
+        "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47",
+    ));
+    non_canonical[63] = 2;
+    assert_eq!(G1Point::from_bytes(&non_canonical), Err(Groth16Error::InvalidPoint));
+
+    let mut off_twist = G2Point::generator().to_bytes();
+    off_twist[127] ^= 1;
+    assert_eq!(G2Point::from_bytes(&off_twist), Err(Groth16Error::InvalidPoint));
+    assert_eq!(G2Point::from_trusted_bytes(&off_twist), Err(Groth16Error::InvalidPoint));
+
+    let non_subgroup: [u8; G2_LEN] = hex(NON_SUBGROUP_G2).try_into().unwrap();
+    assert_eq!(G2Point::from_bytes(&non_subgroup), Err(Groth16Error::InvalidPoint));
+    assert!(G2Point::from_trusted_bytes(&non_subgroup).is_ok());
+
+    let mut proof: [u8; PROOF_LEN] = fixture_proof().to_bytes();
+    proof[G1_LEN..G1_LEN + G2_LEN].copy_from_slice(&non_subgroup);
+    assert_eq!(Proof::from_bytes(&proof), Err(Groth16Error::InvalidPoint));
+}
+
+#[test]
+fn test_verify_accepts_fixture_proof() {
+    let signals = fixture_signals();
+    assert_eq!(fixture_key().verify(&fixture_proof(), &signals), Ok(true));
+}
+
+#[test]
+fn test_verify_rejects_tampered_proof_or_signals() {
+    let key = fixture_key();
+    let proof = fixture_proof();
+    let signals = fixture_signals();
+
+    let mut tampered = signals;
+    tampered[1][31] ^= 1;
+    assert_eq!(key.verify(&proof, &tampered), Ok(false));
+
+    let swapped = [signals[1], signals[0]];
+    assert_eq!(key.verify(&proof, &swapped), Ok(false));
+
+    let forged = Proof {
+        c: proof.c.neg(),
+        ..proof
+    };
+    assert_eq!(key.verify(&forged, &signals), Ok(false));
+}
+
+#[test]
+fn test_verify_rejects_malformed_signals() {
+    let key = fixture_key();
+    let proof = fixture_proof();
+    let signals = fixture_signals();
+
+    assert_eq!(
+        key.verify(&proof, &signals[..1]),
+        Err(Groth16Error::SignalCountMismatch)
+    );
+    assert_eq!(
+        key.verify(&proof, &[signals[0], [0xff; SIGNAL_LEN]]),
+        Err(Groth16Error::InvalidSignal)
+    );
+}
+
+#[test]
+fn test_verifying_key_round_trips() {
+    let bytes = hex(VERIFYING_KEY);
+    let key = fixture_key();
+    let mut encoded = [0u8; VerifyingKey::<3>::ENCODED_LEN];
+    key.write_bytes(&mut encoded);
+    assert_eq!(encoded.as_slice(), bytes.as_slice());
+    assert_eq!(VerifyingKey::<3>::from_trusted_bytes(&bytes), Ok(key));
+
+    // The commitment count is part of the key
+    assert_eq!(
+        VerifyingKey::<2>::from_bytes(&bytes),
+        Err(Groth16Error::InvalidLength)
+    );
+
+    // Subgroup checks are only skipped for trusted keys
+    let mut bad_delta = bytes.clone();
+    let delta = G1_LEN + 2 * G2_LEN;
+    bad_delta[delta..delta + G2_LEN].copy_from_slice(&hex(NON_SUBGROUP_G2));
+    assert_eq!(
+        VerifyingKey::<3>::from_bytes(&bad_delta),
+        Err(Groth16Error::InvalidPoint)
+    );
+    assert!(VerifyingKey::<3>::from_trusted_bytes(&bad_delta).is_ok());
+}
+
+#[test]
+fn test_scalar_multiplication_matches_reference_vectors() {
+    let g1 = G1Point::generator();
+    let double: [u8; G1_LEN] = hex(G1_DOUBLE).try_into().unwrap();
+    assert_eq!(g1.mul(&scalar(2)).to_bytes(), double);
+
+    let input = hex(MUL_INPUT);
+    let point = G1Point::from_bytes(input[..G1_LEN].try_into().unwrap()).unwrap();
+    let product: [u8; G1_LEN] = hex(MUL_OUTPUT).try_into().unwrap();
+    assert_eq!(point.mul(input[G1_LEN..].try_into().unwrap()).to_bytes(), product);
+}
+
+#[test]
+fn test_pairing_matches_reference_vectors() {
+    for input in PAIRING_INPUTS {
+        let bytes = hex(input);
+        let g1_at = |offset: usize| {
+            G1Point::from_bytes(bytes[offset..offset + G1_LEN].try_into().unwrap()).unwrap()
+        };
+        let g2_at = |offset: usize| {
+            G2Point::from_bytes(bytes[offset..offset + G2_LEN].try_into().unwrap()).unwrap()
+        };
+        let pair = G1_LEN + G2_LEN;
+        let (p1, q1) = (g1_at(0), g2_at(G1_LEN));
+        let (p2, q2) = (g1_at(pair), g2_at(pair + G1_LEN));
+
+        // e(p1, q1) · e(p2, q2) = 1, so e(-p1, q1) = e(p2, q2)
+        assert!(pairings_equal(&p1.neg(), &q1, &p2, &q2));
+        assert!(!pairings_equal(&p1, &q1, &p2, &q2));
+    }
+}
@@ -0,0 +1,285 @@
+//! Deterministic purchase, payout and receipt IDs, and payout Merkle leaves
+//!
+//! Every ID is the SHA-256 of a fixed-length canonical preimage built from
+//! fields that are public once a purchase lands (dataset, parties, amounts,
+//! per-dataset counters). DatasetMarketplace and RevenueSplitter hash these
+//! preimages on-chain, and back-office systems rebuild them from events to
+//! precompute and verify IDs during reconciliation. The crate has no
+//! dependencies and no Soroban types, and leaves hashing to the caller
+//! (`env.crypto().sha256` on-chain, any SHA-256 off-chain).
+//!
+//! Common header (6 bytes):
+//! - 4 bytes: MAGIC ("BCID")
+//! - 1 byte: SCHEMA_VERSION
+//! - 1 byte: kind (KIND_PURCHASE, KIND_PAYOUT, KIND_RECEIPT or
+//!   KIND_PAYOUT_LEAF)
+//!
+//! Fields after the header, integers big-endian, no padding:
+//! - Purchase: dataset_key (32), buyer strkey (56), sequence (u64)
+//! - Payout: dataset_key (32), sequence (u64), recipient strkey (56),
+//!   amount (i128)
+//! - Receipt: purchase_id (32), payer strkey (56), amount (i128),
+//!   timestamp (u64)
+//! - Payout leaf: dataset_key (32), recipient strkey (56), amount (i128)
+//!
+//! Payout leaves are hashed into a Merkle tree whose inner nodes are the
+//! SHA-256 of the two child hashes, smaller first. Leaf preimages are
+//! longer than the 64 bytes of an inner node, so the two cannot be
+//! confused.
+//!
+//! `dataset_key` is the SHA-256 of the dataset ID bytes, so preimages stay
+//! fixed-length whatever the ID. A new layout gets a new SCHEMA_VERSION.
+#![no_std]
+
+/// Prefix identifying BIOCHAIN ID preimages
+pub const MAGIC: [u8; 4] = *b"BCID";
+
+/// Layout version written by the encoders
+pub const SCHEMA_VERSION: u8 = 1;
+
+/// Preimage kinds, so IDs of different records never collide
+pub const KIND_PURCHASE: u8 = 1;
+pub const KIND_PAYOUT: u8 = 2;
+pub const KIND_RECEIPT: u8 = 3;
+pub const KIND_PAYOUT_LEAF: u8 = 4;
+
+/// Length of a Stellar strkey (account or contract address)
+pub const STRKEY_LEN: usize = 56;
+
+/// Length of the common header
+pub const HEADER_LEN: usize = 4 + 1 + 1;
+
+/// Length of each encoded preimage
+pub const PURCHASE_LEN: usize = HEADER_LEN + 32 + STRKEY_LEN + 8;
+pub const PAYOUT_LEN: usize = HEADER_LEN + 32 + 8 + STRKEY_LEN + 16;
+pub const RECEIPT_LEN: usize = HEADER_LEN + 32 + STRKEY_LEN + 16 + 8;
+pub const PAYOUT_LEAF_LEN: usize = HEADER_LEN + 32 + STRKEY_LEN + 16;
+
+/// Error types for ID operations
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IdError {
+    InvalidAddress,
+    InvalidAmount,
+}
+
+/// Fields of a purchase ID
+///
+/// - dataset_key: SHA-256 of the dataset ID
+/// - buyer: Strkey of the buyer holding the license
+/// - sequence: Number of earlier purchases of the dataset
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseId {
+    pub dataset_key: [u8; 32],
+    pub buyer: [u8; STRKEY_LEN],
+    pub sequence: u64,
+}
+
+impl PurchaseId {
+    /// Build purchase ID fields, validating the buyer strkey
+    pub fn new(
+        dataset_key: [u8; 32],
+        buyer: [u8; STRKEY_LEN],
+        sequence: u64,
+    ) -> Result<Self, IdError> {
+        if !is_strkey(&buyer) {
+            return Err(IdError::InvalidAddress);
+        }
+        Ok(Self {
+            dataset_key,
+            buyer,
+            sequence,
+        })
+    }
+
+    /// Serialize to the canonical preimage
+    pub fn encode(&self) -> [u8; PURCHASE_LEN] {
+        let mut out = [0u8; PURCHASE_LEN];
+        write_fields(
+            &mut out,
+            KIND_PURCHASE,
+            &[
+                &self.dataset_key[..],
+                &self.buyer[..],
+                &self.sequence.to_be_bytes()[..],
+            ],
+        );
+        out
+    }
+}
+
+/// Fields of a payout ID
+///
+/// - dataset_key: SHA-256 of the dataset ID
+/// - sequence: Number of earlier payouts for the dataset
+/// - recipient: Strkey of the contributor paid
+/// - amount: USDC transferred to the contributor
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PayoutId {
+    pub dataset_key: [u8; 32],
+    pub sequence: u64,
+    pub recipient: [u8; STRKEY_LEN],
+    pub amount: i128,
+}
+
+impl PayoutId {
+    /// Build payout ID fields, validating the recipient and amount
+    pub fn new(
+        dataset_key: [u8; 32],
+        sequence: u64,
+        recipient: [u8; STRKEY_LEN],
+        amount: i128,
+    ) -> Result<Self, IdError> {
+        if !is_strkey(&recipient) {
+            return Err(IdError::InvalidAddress);
+        }
+        if amount < 0 {
+            return Err(IdError::InvalidAmount);
+        }
+        Ok(Self {
+            dataset_key,
+            sequence,
+            recipient,
+            amount,
+        })
+    }
+
+    /// Serialize to the canonical preimage
+    pub fn encode(&self) -> [u8; PAYOUT_LEN] {
+        let mut out = [0u8; PAYOUT_LEN];
+        write_fields(
+            &mut out,
+            KIND_PAYOUT,
+            &[
+                &self.dataset_key[..],
+                &self.sequence.to_be_bytes()[..],
+                &self.recipient[..],
+                &self.amount.to_be_bytes()[..],
+            ],
+        );
+        out
+    }
+}
+
+/// Fields of a purchase receipt hash
+///
+/// - purchase_id: ID of the purchase (SHA-256 of its preimage)
+/// - payer: Strkey of the address the price was pulled from
+/// - amount: Price paid
+/// - timestamp: Ledger timestamp of the purchase
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub purchase_id: [u8; 32],
+    pub payer: [u8; STRKEY_LEN],
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+impl Receipt {
+    /// Build receipt fields, validating the payer and amount
+    pub fn new(
+        purchase_id: [u8; 32],
+        payer: [u8; STRKEY_LEN],
+        amount: i128,
+        timestamp: u64,
+    ) -> Result<Self, IdError> {
+        if !is_strkey(&payer) {
+            return Err(IdError::InvalidAddress);
+        }
+        if amount < 0 {
+            return Err(IdError::InvalidAmount);
+        }
+        Ok(Self {
+            purchase_id,
+            payer,
+            amount,
+            timestamp,
+        })
+    }
+
+    /// Serialize to the canonical preimage
+    pub fn encode(&self) -> [u8; RECEIPT_LEN] {
+        let mut out = [0u8; RECEIPT_LEN];
+        write_fields(
+            &mut out,
+            KIND_RECEIPT,
+            &[
+                &self.purchase_id[..],
+                &self.payer[..],
+                &self.amount.to_be_bytes()[..],
+                &self.timestamp.to_be_bytes()[..],
+            ],
+        );
+        out
+    }
+}
+
+/// Fields of a payout Merkle leaf
+///
+/// - dataset_key: SHA-256 of the dataset ID
+/// - recipient: Strkey of the contributor who can claim
+/// - amount: USDC the contributor can claim
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PayoutLeaf {
+    pub dataset_key: [u8; 32],
+    pub recipient: [u8; STRKEY_LEN],
+    pub amount: i128,
+}
+
+impl PayoutLeaf {
+    /// Build leaf fields, validating the recipient and amount
+    pub fn new(
+        dataset_key: [u8; 32],
+        recipient: [u8; STRKEY_LEN],
+        amount: i128,
+    ) -> Result<Self, IdError> {
+        if !is_strkey(&recipient) {
+            return Err(IdError::InvalidAddress);
+        }
+        if amount <= 0 {
+            return Err(IdError::InvalidAmount);
+        }
+        Ok(Self {
+            dataset_key,
+            recipient,
+            amount,
+        })
+    }
+
+    /// Serialize to the canonical preimage
+    pub fn encode(&self) -> [u8; PAYOUT_LEAF_LEN] {
+        let mut out = [0u8; PAYOUT_LEAF_LEN];
+        write_fields(
+            &mut out,
+            KIND_PAYOUT_LEAF,
+            &[
+                &self.dataset_key[..],
+                &self.recipient[..],
+                &self.amount.to_be_bytes()[..],
+            ],
+        );
+        out
+    }
+}
+
+/// Write the header followed by `fields` into `out`
+///
+/// `out` must be exactly as long as the header and fields together.
+fn write_fields(out: &mut [u8], kind: u8, fields: &[&[u8]]) {
+    out[..4].copy_from_slice(&MAGIC);
+    out[4] = SCHEMA_VERSION;
+    out[5] = kind;
+    let mut offset = HEADER_LEN;
+    for field in fields {
+        out[offset..offset + field.len()].copy_from_slice(field);
+        offset += field.len();
+    }
+    debug_assert_eq!(offset, out.len());
+}
+
+/// Check the shape of a Stellar account ("G") or contract ("C") strkey
+///
+/// Only the alphabet and prefix are checked; the checksum is left to the
+/// Stellar tooling that produced the address.
+fn is_strkey(bytes: &[u8; STRKEY_LEN]) -> bool {
+    matches!(bytes[0], b'G' | b'C') && bytes.iter().all(|c| matches!(c, b'A'..=b'Z' | b'2'..=b'7'))
+}
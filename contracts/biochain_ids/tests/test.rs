@@ -0,0 +1,116 @@
+use biochain_ids::{
+    IdError, PayoutId, PayoutLeaf, PurchaseId, Receipt, KIND_PAYOUT, KIND_PAYOUT_LEAF,
+    KIND_PURCHASE, KIND_RECEIPT, PAYOUT_LEAF_LEN, PAYOUT_LEN, PURCHASE_LEN, RECEIPT_LEN,
+};
+
+/// Preimages shared with back-office reconciliation (tests/fixtures/*.hex):
+/// dataset "dataset_001", the all-zero account strkey as every party,
+/// sequence 0, payout and payout leaf of 1_000_000, receipt of 10_000_000
+/// at 1_700_000_000
+const PURCHASE_V1: &str = include_str!("fixtures/purchase_v1.hex");
+const PAYOUT_V1: &str = include_str!("fixtures/payout_v1.hex");
+const RECEIPT_V1: &str = include_str!("fixtures/receipt_v1.hex");
+const PAYOUT_LEAF_V1: &str = include_str!("fixtures/payout_leaf_v1.hex");
+
+const ACCOUNT: &[u8; 56] = b"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+/// SHA-256("dataset_001")
+const DATASET_KEY: [u8; 32] = [
+    0x73, 0x18, 0x44, 0xdf, 0xcf, 0x2d, 0xc9, 0xd0, 0x05, 0x46, 0xe7, 0x63, 0x81, 0x77, 0x07, 0xd9,
+    0x48, 0x98, 0x36, 0x73, 0x5d, 0x74, 0x5a, 0xb3, 0x50, 0xd5, 0xd5, 0x27, 0x88, 0x53, 0x99, 0xad,
+];
+
+/// SHA-256 of the purchase fixture
+const PURCHASE_ID: [u8; 32] = [
+    0xd5, 0xb7, 0x4b, 0xef, 0xbb, 0xf0, 0x54, 0x21, 0xae, 0xa2, 0x4d, 0xe3, 0x6a, 0xa2, 0x06, 0xf9,
+    0x7d, 0xa0, 0x48, 0x15, 0xcd, 0x2f, 0x19, 0xbc, 0xfd, 0xfa, 0xc4, 0xe5, 0xdc, 0x7a, 0xb5, 0x7a,
+];
+
+/// Helper: Decode a hex fixture
+fn fixture_bytes<const N: usize>(fixture: &str) -> [u8; N] {
+    let hex = fixture.trim().as_bytes();
+    assert_eq!(hex.len(), 2 * N);
+    let nibble = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = nibble(hex[2 * i]) << 4 | nibble(hex[2 * i + 1]);
+    }
+    out
+}
+
+#[test]
+fn test_purchase_matches_fixture() {
+    let id = PurchaseId::new(DATASET_KEY, *ACCOUNT, 0).unwrap();
+    assert_eq!(id.encode(), fixture_bytes::<PURCHASE_LEN>(PURCHASE_V1));
+}
+
+#[test]
+fn test_payout_matches_fixture() {
+    let id = PayoutId::new(DATASET_KEY, 0, *ACCOUNT, 1_000_000).unwrap();
+    assert_eq!(id.encode(), fixture_bytes::<PAYOUT_LEN>(PAYOUT_V1));
+}
+
+#[test]
+fn test_receipt_matches_fixture() {
+    let receipt = Receipt::new(PURCHASE_ID, *ACCOUNT, 10_000_000, 1_700_000_000).unwrap();
+    assert_eq!(receipt.encode(), fixture_bytes::<RECEIPT_LEN>(RECEIPT_V1));
+}
+
+#[test]
+fn test_payout_leaf_matches_fixture() {
+    let leaf = PayoutLeaf::new(DATASET_KEY, *ACCOUNT, 1_000_000).unwrap();
+    assert_eq!(leaf.encode(), fixture_bytes::<PAYOUT_LEAF_LEN>(PAYOUT_LEAF_V1));
+    assert_eq!(leaf.encode()[5], KIND_PAYOUT_LEAF);
+
+    // Inner Merkle nodes hash 64 bytes, so a leaf can never pass for one
+    assert_ne!(PAYOUT_LEAF_LEN, 64);
+    assert_eq!(
+        PayoutLeaf::new(DATASET_KEY, *ACCOUNT, 0),
+        Err(IdError::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_kinds_keep_preimages_apart() {
+    let purchase = PurchaseId::new(DATASET_KEY, *ACCOUNT, 0).unwrap().encode();
+    let payout = PayoutId::new(DATASET_KEY, 0, *ACCOUNT, 0).unwrap().encode();
+    let receipt = Receipt::new(DATASET_KEY, *ACCOUNT, 0, 0).unwrap().encode();
+    assert_eq!(purchase[5], KIND_PURCHASE);
+    assert_eq!(payout[5], KIND_PAYOUT);
+    assert_eq!(receipt[5], KIND_RECEIPT);
+}
+
+#[test]
+fn test_sequence_changes_preimage() {
+    let first = PurchaseId::new(DATASET_KEY, *ACCOUNT, 0).unwrap().encode();
+    let second = PurchaseId::new(DATASET_KEY, *ACCOUNT, 1).unwrap().encode();
+    assert_ne!(first, second);
+    assert_eq!(second[PURCHASE_LEN - 8..], 1u64.to_be_bytes());
+}
+
+#[test]
+fn test_new_rejects_invalid_fields() {
+    let mut secret = *ACCOUNT;
+    secret[0] = b'S';
+    assert_eq!(
+        PurchaseId::new(DATASET_KEY, secret, 0),
+        Err(IdError::InvalidAddress)
+    );
+
+    // Lowercase is outside the strkey alphabet
+    let mut lowercase = *ACCOUNT;
+    lowercase[10] = b'a';
+    assert_eq!(
+        PayoutId::new(DATASET_KEY, 0, lowercase, 1),
+        Err(IdError::InvalidAddress)
+    );
+
+    assert_eq!(
+        PayoutId::new(DATASET_KEY, 0, *ACCOUNT, -1),
+        Err(IdError::InvalidAmount)
+    );
+    assert_eq!(
+        Receipt::new(PURCHASE_ID, *ACCOUNT, -1, 0),
+        Err(IdError::InvalidAmount)
+    );
+}
@@ -0,0 +1,110 @@
+//! Typed cross-contract interfaces of BIOCHAIN contracts
+//!
+//! Contracts that call each other depend on this crate instead of on the
+//! callee's crate, which would link the callee's contract code into the
+//! caller's WASM. Each interface declares the callee's functions with a
+//! `#[contractclient]` trait over the shared `biochain-types` data
+//! structures, so calls are checked at compile time and decoded into real
+//! types instead of raw `invoke_contract` values.
+//!
+//! Error enums mirror only the codes callers handle and are not exported
+//! into the caller's contract spec; any other code surfaces as
+//! `InvokeError::Contract` from the `try_` client methods. The same goes
+//! for the types of external contracts, such as price oracles.
+#![no_std]
+
+pub use biochain_types::{Dataset, StudyRecord};
+use soroban_sdk::{
+    contractclient, contracterror, contracttype, Address, Bytes, BytesN, Env, Symbol, Vec,
+};
+
+/// StudyRegistry errors handled by callers (codes 100-199)
+#[contracterror(export = false)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum StudyRegistryError {
+    StudyNotFound = 103,
+}
+
+/// StudyRegistry functions called by other contracts
+#[contractclient(name = "StudyRegistryClient")]
+pub trait StudyRegistryInterface {
+    /// Get a study record by dataset_hash
+    fn get_study(env: Env, dataset_hash: BytesN<32>) -> Result<StudyRecord, StudyRegistryError>;
+
+    /// Get the contributors of a study with their shares in basis points
+    fn get_study_contributors(
+        env: Env,
+        dataset_hash: BytesN<32>,
+    ) -> Result<Vec<(Address, u32)>, StudyRegistryError>;
+
+    /// Get the revoked studies among the given ones
+    fn get_revoked_studies(env: Env, dataset_hashes: Vec<BytesN<32>>) -> Vec<BytesN<32>>;
+}
+
+/// DatasetMarketplace errors handled by callers (codes 300-399)
+#[contracterror(export = false)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DatasetMarketplaceError {
+    DatasetNotFound = 300,
+}
+
+/// DatasetMarketplace functions called by other contracts
+#[contractclient(name = "DatasetMarketplaceClient")]
+pub trait DatasetMarketplaceInterface {
+    /// Get a dataset listing by ID
+    fn get_dataset(env: Env, dataset_id: Bytes) -> Result<Dataset, DatasetMarketplaceError>;
+
+    /// Pay out a purchase released from escrow
+    fn settle_escrow(env: Env, purchase_id: BytesN<32>) -> Result<(), DatasetMarketplaceError>;
+}
+
+/// CredentialRegistry functions called by other contracts
+#[contractclient(name = "CredentialRegistryClient")]
+pub trait CredentialRegistryInterface {
+    /// Whether an address holds an unexpired, unrevoked credential of a
+    /// type
+    fn is_verified(env: Env, address: Address, credential_type: Symbol) -> bool;
+}
+
+/// AccessPass functions called by other contracts
+#[contractclient(name = "AccessPassClient")]
+pub trait AccessPassInterface {
+    /// Mint or renew the holder's pass for a dataset, returning its token
+    /// ID
+    fn mint(env: Env, dataset_id: Bytes, holder: Address, expires_at: Option<u64>) -> u64;
+
+    /// Burn the holder's pass for a dataset, returning whether one existed
+    fn burn(env: Env, dataset_id: Bytes, holder: Address) -> bool;
+}
+
+/// Asset quoted by a SEP-40 price oracle (e.g. Reflector)
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Price reading of a SEP-40 price oracle
+///
+/// - price: Value of one whole asset in the oracle's base asset (USD),
+///   scaled by `10^decimals()`
+/// - timestamp: Time of the reading, in seconds
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// SEP-40 price oracle functions called by other contracts
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    /// Decimals of the quoted prices
+    fn decimals(env: Env) -> u32;
+
+    /// Most recent price of an asset, if the oracle quotes it
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
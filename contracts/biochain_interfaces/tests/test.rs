@@ -0,0 +1,78 @@
+#![cfg(test)]
+
+use biochain_interfaces::{StudyRecord, StudyRegistryClient, StudyRegistryError};
+use soroban_sdk::{testutils::Address as AddressTestUtils, Address, BytesN, Env, Vec};
+
+/// Helper: Deploy a StudyRegistry holding one registered study
+fn setup(env: &Env) -> (Address, Address, BytesN<32>) {
+    env.mock_all_auths();
+    let registry = study_registry::StudyRegistryClient::new(
+        env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+
+    let contributor = Address::generate(env);
+    let dataset_hash = BytesN::from_array(env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(env, &dataset_hash, &contributor);
+    let zk_proof = study_registry::testutils::zk_proof(env, &dataset_hash, &attestation, &contributor);
+    registry.register_study(
+        &dataset_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &zk_proof,
+        &contributor,
+    );
+    (registry.address, contributor, dataset_hash)
+}
+
+#[test]
+fn test_study_registry_client_decodes_registry_values() {
+    let env = Env::default();
+    let (registry, contributor, dataset_hash) = setup(&env);
+    let client = StudyRegistryClient::new(&env, &registry);
+
+    // Act
+    let study = client.get_study(&dataset_hash);
+    let contributors = client.get_study_contributors(&dataset_hash);
+
+    // Assert: decoded into the registry's own types
+    assert_eq!(
+        study,
+        StudyRecord {
+            dataset_hash: dataset_hash.clone(),
+            contributor: contributor.clone(),
+            timestamp: env.ledger().timestamp(),
+        }
+    );
+    assert_eq!(
+        contributors,
+        Vec::from_array(&env, [(contributor, study_registry::BPS_DENOMINATOR)])
+    );
+    assert!(client
+        .get_revoked_studies(&Vec::from_array(&env, [dataset_hash]))
+        .is_empty());
+}
+
+#[test]
+fn test_study_registry_client_maps_registry_errors() {
+    let env = Env::default();
+    let (registry, _, _) = setup(&env);
+    let client = StudyRegistryClient::new(&env, &registry);
+    let unknown = BytesN::from_array(&env, &[2u8; 32]);
+
+    // Act & Assert: mirrored codes decode to the same error
+    assert_eq!(
+        StudyRegistryError::StudyNotFound as u32,
+        study_registry::Error::StudyNotFound as u32
+    );
+    assert_eq!(
+        client.try_get_study(&unknown),
+        Err(Ok(StudyRegistryError::StudyNotFound))
+    );
+    assert_eq!(
+        client.try_get_study_contributors(&unknown),
+        Err(Ok(StudyRegistryError::StudyNotFound))
+    );
+}
@@ -0,0 +1,274 @@
+//! Pricing math shared by the BIOCHAIN contracts
+//!
+//! Pure functions over USDC amounts (i128, 7 decimal places) and basis
+//! points. Every operation uses checked arithmetic and returns
+//! `PricingError::Overflow` instead of wrapping.
+//!
+//! Rounding rules:
+//! - Discounts round down, so a buyer never gets more off than advertised
+//! - Fees round up, so the platform never collects less than its rate
+//! - Prorated amounts take an explicit `Rounding`, chosen by the caller
+//!   depending on who the remainder should favor
+//! - Fiat conversions round up, so a seller never receives less than the
+//!   fiat price
+//! - Decaying prices round up, so a price never falls faster than its
+//!   schedule
+//!
+//! The `math` module exposes the same checked arithmetic for contracts
+//! adding up payouts.
+#![no_std]
+
+pub mod math;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Rounding direction for divisions
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Error types for pricing operations
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PricingError {
+    InvalidAmount,
+    InvalidBps,
+    InvalidPeriod,
+    InvalidTiers,
+    Overflow,
+}
+
+/// Volume discount tier
+///
+/// - min_quantity: Smallest quantity the tier applies to
+/// - discount_bps: Discount on the gross price, in basis points
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VolumeTier {
+    pub min_quantity: u32,
+    pub discount_bps: u32,
+}
+
+/// Price quote
+///
+/// - gross: unit_price * quantity
+/// - discount: Volume discount taken off the gross price
+/// - net: Amount the buyer pays (gross - discount)
+/// - fee: Platform fee included in `net`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub unit_price: i128,
+    pub quantity: u32,
+    pub gross: i128,
+    pub discount: i128,
+    pub net: i128,
+    pub fee: i128,
+}
+
+/// Multiply an amount by a rate in basis points
+///
+/// # Arguments
+/// * `amount` - Non-negative amount
+/// * `bps` - Rate in basis points (at most BPS_DENOMINATOR)
+/// * `rounding` - Rounding direction of the division
+///
+/// # Returns
+/// * `Ok(i128)` with `amount * bps / BPS_DENOMINATOR`
+/// * `Err(PricingError)` if an input is out of range or the product overflows
+pub fn mul_bps(amount: i128, bps: u32, rounding: Rounding) -> Result<i128, PricingError> {
+    if bps > BPS_DENOMINATOR {
+        return Err(PricingError::InvalidBps);
+    }
+    mul_div(amount, bps as i128, BPS_DENOMINATOR as i128, rounding)
+}
+
+/// Apply a discount to a price
+///
+/// # Arguments
+/// * `price` - Non-negative price
+/// * `discount_bps` - Discount in basis points (at most BPS_DENOMINATOR)
+///
+/// # Returns
+/// * `Ok((net, discount))` with the discount rounded down
+/// * `Err(PricingError)` if an input is out of range
+pub fn apply_discount(price: i128, discount_bps: u32) -> Result<(i128, i128), PricingError> {
+    let discount = mul_bps(price, discount_bps, Rounding::Down)?;
+    Ok((price - discount, discount))
+}
+
+/// Find the discount of the highest tier a quantity reaches
+///
+/// # Arguments
+/// * `tiers` - Tiers sorted by strictly increasing `min_quantity`
+/// * `quantity` - Quantity being bought
+///
+/// # Returns
+/// * `Ok(u32)` with the discount in basis points (0 if no tier applies)
+/// * `Err(PricingError::InvalidTiers)` if tiers are unsorted or a discount
+///   exceeds 100%
+pub fn volume_discount_bps(tiers: &[VolumeTier], quantity: u32) -> Result<u32, PricingError> {
+    let mut discount_bps = 0;
+    let mut previous: Option<u32> = None;
+    for tier in tiers {
+        if tier.discount_bps > BPS_DENOMINATOR
+            || previous.is_some_and(|min| tier.min_quantity <= min)
+        {
+            return Err(PricingError::InvalidTiers);
+        }
+        if quantity >= tier.min_quantity {
+            discount_bps = tier.discount_bps;
+        }
+        previous = Some(tier.min_quantity);
+    }
+    Ok(discount_bps)
+}
+
+/// Prorate an amount over a period
+///
+/// # Arguments
+/// * `amount` - Non-negative amount for the full period
+/// * `elapsed` - Part of the period to charge or refund (capped at `period`)
+/// * `period` - Length of the full period (non-zero)
+/// * `rounding` - Rounding direction of the division
+///
+/// # Returns
+/// * `Ok(i128)` with `amount * elapsed / period`
+/// * `Err(PricingError)` if an input is out of range or the product overflows
+pub fn prorate(
+    amount: i128,
+    elapsed: u64,
+    period: u64,
+    rounding: Rounding,
+) -> Result<i128, PricingError> {
+    if period == 0 {
+        return Err(PricingError::InvalidPeriod);
+    }
+    mul_div(
+        amount,
+        elapsed.min(period) as i128,
+        period as i128,
+        rounding,
+    )
+}
+
+/// Price of a listing decaying from a start price to a floor
+///
+/// # Arguments
+/// * `start_price` - Price when the decay starts (at least `floor_price`)
+/// * `floor_price` - Non-negative price once the decay is over
+/// * `elapsed` - Time since the decay started (capped at `duration`)
+/// * `duration` - Length of the decay (non-zero)
+/// * `steps` - Number of equal price drops, or 0 for a linear decay
+///
+/// # Returns
+/// * `Ok(i128)` with the current price, rounded up
+/// * `Err(PricingError)` if an input is out of range
+pub fn decayed_price(
+    start_price: i128,
+    floor_price: i128,
+    elapsed: u64,
+    duration: u64,
+    steps: u32,
+) -> Result<i128, PricingError> {
+    if floor_price < 0 || start_price < floor_price {
+        return Err(PricingError::InvalidAmount);
+    }
+    if duration == 0 {
+        return Err(PricingError::InvalidPeriod);
+    }
+    let range = start_price - floor_price;
+    let drop = if steps == 0 {
+        prorate(range, elapsed, duration, Rounding::Down)?
+    } else {
+        // Steps completed so far, rounded down
+        let completed = (elapsed.min(duration) as u128 * steps as u128 / duration as u128) as i128;
+        mul_div(range, completed, steps as i128, Rounding::Down)?
+    };
+    Ok(start_price - drop)
+}
+
+/// Convert a price in USD cents into token units at an oracle price
+///
+/// # Arguments
+/// * `cents` - Non-negative price in USD cents
+/// * `price` - USD value of one whole token (positive), scaled by
+///   `10^price_decimals`
+/// * `price_decimals` - Decimals of `price`
+/// * `token_decimals` - Decimals of the token
+///
+/// # Returns
+/// * `Ok(i128)` with the token amount worth `cents`, rounded up
+/// * `Err(PricingError)` if an input is out of range or a product overflows
+pub fn usd_cents_to_token(
+    cents: i128,
+    price: i128,
+    price_decimals: u32,
+    token_decimals: u32,
+) -> Result<i128, PricingError> {
+    if price <= 0 {
+        return Err(PricingError::InvalidAmount);
+    }
+    let scale = price_decimals
+        .checked_add(token_decimals)
+        .and_then(|decimals| 10i128.checked_pow(decimals))
+        .ok_or(PricingError::Overflow)?;
+    let denominator = price.checked_mul(100).ok_or(PricingError::Overflow)?;
+    mul_div(cents, scale, denominator, Rounding::Up)
+}
+
+/// Quote a purchase of `quantity` units
+///
+/// # Arguments
+/// * `unit_price` - Price of one unit (positive)
+/// * `quantity` - Units bought (non-zero)
+/// * `tiers` - Volume discount tiers (see `volume_discount_bps`)
+/// * `fee_bps` - Platform fee on the net price, in basis points
+///
+/// # Returns
+/// * `Ok(Quote)` with the discount rounded down and the fee rounded up
+/// * `Err(PricingError)` if an input is out of range or a product overflows
+pub fn quote(
+    unit_price: i128,
+    quantity: u32,
+    tiers: &[VolumeTier],
+    fee_bps: u32,
+) -> Result<Quote, PricingError> {
+    if unit_price <= 0 || quantity == 0 {
+        return Err(PricingError::InvalidAmount);
+    }
+    let gross = unit_price
+        .checked_mul(quantity as i128)
+        .ok_or(PricingError::Overflow)?;
+    let (net, discount) = apply_discount(gross, volume_discount_bps(tiers, quantity)?)?;
+    let fee = mul_bps(net, fee_bps, Rounding::Up)?;
+
+    Ok(Quote {
+        unit_price,
+        quantity,
+        gross,
+        discount,
+        net,
+        fee,
+    })
+}
+
+/// Compute `amount * numerator / denominator` with the given rounding
+fn mul_div(
+    amount: i128,
+    numerator: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, PricingError> {
+    if amount < 0 {
+        return Err(PricingError::InvalidAmount);
+    }
+    let product = amount
+        .checked_mul(numerator)
+        .ok_or(PricingError::Overflow)?;
+    let quotient = product / denominator;
+    match rounding {
+        Rounding::Up if product % denominator != 0 => Ok(quotient + 1),
+        _ => Ok(quotient),
+    }
+}
@@ -0,0 +1,33 @@
+//! Checked arithmetic on USDC amounts
+//!
+//! Thin wrappers over the `checked_*` methods of i128 that report
+//! `PricingError::Overflow` instead of returning `None`, so contracts can
+//! propagate overflows with `?` instead of wrapping or panicking.
+
+use crate::PricingError;
+
+/// `a + b`, or `Err(PricingError::Overflow)`
+pub fn checked_add(a: i128, b: i128) -> Result<i128, PricingError> {
+    a.checked_add(b).ok_or(PricingError::Overflow)
+}
+
+/// `a - b`, or `Err(PricingError::Overflow)`
+pub fn checked_sub(a: i128, b: i128) -> Result<i128, PricingError> {
+    a.checked_sub(b).ok_or(PricingError::Overflow)
+}
+
+/// `a * b`, or `Err(PricingError::Overflow)`
+pub fn checked_mul(a: i128, b: i128) -> Result<i128, PricingError> {
+    a.checked_mul(b).ok_or(PricingError::Overflow)
+}
+
+/// `a / b` rounded toward zero, or `Err(PricingError::Overflow)` when
+/// dividing by zero or `i128::MIN / -1`
+pub fn checked_div(a: i128, b: i128) -> Result<i128, PricingError> {
+    a.checked_div(b).ok_or(PricingError::Overflow)
+}
+
+/// Sum of amounts, or `Err(PricingError::Overflow)`
+pub fn checked_sum<I: IntoIterator<Item = i128>>(amounts: I) -> Result<i128, PricingError> {
+    amounts.into_iter().try_fold(0, checked_add)
+}
@@ -0,0 +1,182 @@
+use biochain_pricing::{
+    apply_discount, decayed_price, math, mul_bps, prorate, quote, usd_cents_to_token, volume_discount_bps, PricingError, Rounding,
+    VolumeTier, BPS_DENOMINATOR,
+};
+
+/// 10 USDC with 7 decimal places
+const PRICE: i128 = 10_0000000;
+
+/// Tiers used by the tests: 5% off from 5 units, 10% off from 10
+const TIERS: [VolumeTier; 2] = [
+    VolumeTier {
+        min_quantity: 5,
+        discount_bps: 500,
+    },
+    VolumeTier {
+        min_quantity: 10,
+        discount_bps: 1_000,
+    },
+];
+
+#[test]
+fn test_mul_bps_rounding() {
+    // 3 * 3333 / 10000 = 0.9999
+    assert_eq!(mul_bps(3, 3_333, Rounding::Down), Ok(0));
+    assert_eq!(mul_bps(3, 3_333, Rounding::Up), Ok(1));
+    assert_eq!(mul_bps(PRICE, 2_500, Rounding::Up), Ok(2_5000000));
+    assert_eq!(mul_bps(PRICE, BPS_DENOMINATOR, Rounding::Down), Ok(PRICE));
+}
+
+#[test]
+fn test_mul_bps_rejects_invalid_inputs() {
+    assert_eq!(
+        mul_bps(PRICE, BPS_DENOMINATOR + 1, Rounding::Down),
+        Err(PricingError::InvalidBps)
+    );
+    assert_eq!(
+        mul_bps(-1, 100, Rounding::Down),
+        Err(PricingError::InvalidAmount)
+    );
+    assert_eq!(
+        mul_bps(i128::MAX, 2, Rounding::Down),
+        Err(PricingError::Overflow)
+    );
+}
+
+#[test]
+fn test_apply_discount_rounds_down() {
+    // 5% of 0.0000019 USDC is 0.000000095, rounded down to 0.0000000
+    assert_eq!(apply_discount(19, 500), Ok((19, 0)));
+    assert_eq!(apply_discount(PRICE, 500), Ok((9_5000000, 5000000)));
+}
+
+#[test]
+fn test_volume_discount_picks_highest_reached_tier() {
+    assert_eq!(volume_discount_bps(&TIERS, 1), Ok(0));
+    assert_eq!(volume_discount_bps(&TIERS, 5), Ok(500));
+    assert_eq!(volume_discount_bps(&TIERS, 9), Ok(500));
+    assert_eq!(volume_discount_bps(&TIERS, 50), Ok(1_000));
+    assert_eq!(volume_discount_bps(&[], 50), Ok(0));
+}
+
+#[test]
+fn test_volume_discount_rejects_invalid_tiers() {
+    let unsorted = [TIERS[1], TIERS[0]];
+    let too_large = [VolumeTier {
+        min_quantity: 1,
+        discount_bps: BPS_DENOMINATOR + 1,
+    }];
+
+    assert_eq!(
+        volume_discount_bps(&unsorted, 1),
+        Err(PricingError::InvalidTiers)
+    );
+    assert_eq!(
+        volume_discount_bps(&too_large, 1),
+        Err(PricingError::InvalidTiers)
+    );
+}
+
+#[test]
+fn test_prorate() {
+    // A third of a 30-day period, in ledger timestamps
+    let period = 30 * 86_400;
+    assert_eq!(
+        prorate(PRICE, period / 3, period, Rounding::Down),
+        Ok(3_3333333)
+    );
+    assert_eq!(
+        prorate(PRICE, period / 3, period, Rounding::Up),
+        Ok(3_3333334)
+    );
+    assert_eq!(
+        prorate(PRICE, 2 * period, period, Rounding::Down),
+        Ok(PRICE)
+    );
+    assert_eq!(
+        prorate(PRICE, 1, 0, Rounding::Down),
+        Err(PricingError::InvalidPeriod)
+    );
+}
+
+#[test]
+fn test_decayed_price() {
+    // From 10 to 4 USDC over 300 ledgers
+    assert_eq!(decayed_price(PRICE, 4_0000000, 0, 300, 0), Ok(PRICE));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 100, 300, 0), Ok(8_0000000));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 1, 3, 0), Ok(8_0000000));
+    assert_eq!(decayed_price(7, 0, 1, 3, 0), Ok(5));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 900, 300, 0), Ok(4_0000000));
+
+    // In 3 drops of 2 USDC, each at the end of its third of the period
+    assert_eq!(decayed_price(PRICE, 4_0000000, 99, 300, 3), Ok(PRICE));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 100, 300, 3), Ok(8_0000000));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 299, 300, 3), Ok(6_0000000));
+    assert_eq!(decayed_price(PRICE, 4_0000000, 300, 300, 3), Ok(4_0000000));
+
+    assert_eq!(
+        decayed_price(4_0000000, PRICE, 0, 300, 0),
+        Err(PricingError::InvalidAmount)
+    );
+    assert_eq!(decayed_price(PRICE, -1, 0, 300, 0), Err(PricingError::InvalidAmount));
+    assert_eq!(
+        decayed_price(PRICE, 4_0000000, 0, 0, 3),
+        Err(PricingError::InvalidPeriod)
+    );
+}
+
+#[test]
+fn test_usd_cents_to_token_rounds_up() {
+    // XLM at 0.12 USD with 14 oracle decimals, 7 token decimals
+    let price = 12 * 10i128.pow(12);
+    assert_eq!(usd_cents_to_token(2_500, price, 14, 7), Ok(208_3333334));
+    // USDC at par
+    assert_eq!(usd_cents_to_token(2_500, 10i128.pow(14), 14, 7), Ok(25_0000000));
+    assert_eq!(usd_cents_to_token(0, price, 14, 7), Ok(0));
+
+    assert_eq!(usd_cents_to_token(2_500, 0, 14, 7), Err(PricingError::InvalidAmount));
+    assert_eq!(usd_cents_to_token(-1, price, 14, 7), Err(PricingError::InvalidAmount));
+    assert_eq!(usd_cents_to_token(2_500, price, 30, 20), Err(PricingError::Overflow));
+}
+
+#[test]
+fn test_quote_applies_discount_then_fee() {
+    // Act
+    let quote = quote(PRICE, 10, &TIERS, 333).unwrap();
+
+    // Assert: 100 USDC gross, 10% off, 3.33% fee on 90 USDC rounded up
+    assert_eq!(quote.gross, 100_0000000);
+    assert_eq!(quote.discount, 10_0000000);
+    assert_eq!(quote.net, 90_0000000);
+    assert_eq!(quote.fee, 2_9970000);
+    assert_eq!(quote.gross - quote.discount, quote.net);
+}
+
+#[test]
+fn test_quote_rejects_invalid_inputs() {
+    assert_eq!(quote(0, 1, &TIERS, 0), Err(PricingError::InvalidAmount));
+    assert_eq!(quote(PRICE, 0, &TIERS, 0), Err(PricingError::InvalidAmount));
+    assert_eq!(quote(i128::MAX, 2, &TIERS, 0), Err(PricingError::Overflow));
+    assert_eq!(
+        quote(PRICE, 1, &TIERS, BPS_DENOMINATOR + 1),
+        Err(PricingError::InvalidBps)
+    );
+}
+
+#[test]
+fn test_math_reports_overflow() {
+    assert_eq!(math::checked_add(PRICE, PRICE), Ok(2 * PRICE));
+    assert_eq!(math::checked_sub(PRICE, 2 * PRICE), Ok(-PRICE));
+    assert_eq!(math::checked_mul(PRICE, 3), Ok(3 * PRICE));
+    assert_eq!(math::checked_div(PRICE, 3), Ok(3_3333333));
+    assert_eq!(math::checked_sum([PRICE, PRICE, 1]), Ok(2 * PRICE + 1));
+
+    assert_eq!(math::checked_add(i128::MAX, 1), Err(PricingError::Overflow));
+    assert_eq!(math::checked_sub(i128::MIN, 1), Err(PricingError::Overflow));
+    assert_eq!(math::checked_mul(i128::MAX, 2), Err(PricingError::Overflow));
+    assert_eq!(math::checked_div(PRICE, 0), Err(PricingError::Overflow));
+    assert_eq!(
+        math::checked_sum([i128::MAX, 1]),
+        Err(PricingError::Overflow)
+    );
+}
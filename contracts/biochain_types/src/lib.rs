@@ -0,0 +1,131 @@
+//! Data structures shared by BIOCHAIN contracts
+//!
+//! StudyRegistry, DatasetMarketplace and RevenueSplitter pass these types
+//! across contract calls, return them to clients and publish them in
+//! events. Each is defined once here and re-exported by the contract that
+//! owns it, so callers, tests and off-chain consumers decode the same
+//! layout the owner stores and emits.
+//!
+//! Types are exported into the spec of every contract that uses them.
+//! Changing a field changes the stored and emitted layout of each of those
+//! contracts: add a new type (and a migration in the owner) instead.
+#![no_std]
+
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Vec};
+
+/// StudyRecord struct, owned by StudyRegistry
+///
+/// Stores essential study information on-chain:
+/// - dataset_hash: Unique hash of the processed dataset
+/// - contributor: Address of the study contributor
+/// - timestamp: Ledger timestamp when the study was registered
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyRecord {
+    pub dataset_hash: BytesN<32>,
+    pub contributor: Address,
+    pub timestamp: u64,
+}
+
+/// Dataset structure, owned by DatasetMarketplace
+///
+/// Stores dataset information on-chain:
+/// - dataset_id: Unique identifier for the dataset (Bytes)
+/// - study_ids: List of study hashes included in this dataset
+/// - price_usdc: Price in USDC (i128, with 7 decimal places for Stellar)
+/// - owner: Address allowed to update the price and studies
+/// - status: Whether the listing can currently be purchased
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dataset {
+    pub dataset_id: Bytes,
+    pub study_ids: Vec<Bytes>,
+    pub price_usdc: i128,
+    pub owner: Address,
+    pub status: DatasetStatus,
+}
+
+/// Lifecycle status of a dataset listing
+///
+/// - Active: Listed and purchasable
+/// - Delisted: Withdrawn by its owner, who can relist it
+/// - Suspended: Withdrawn by the admin, who alone can relist it
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DatasetStatus {
+    Active,
+    Delisted,
+    Suspended,
+}
+
+/// PurchaseRecord structure, owned by DatasetMarketplace
+///
+/// Stores purchase information:
+/// - buyer: Address of the researcher who purchased
+/// - dataset_id: ID of the purchased dataset
+/// - tx_hash: Purchase ID, the SHA-256 of the `biochain_ids::PurchaseId`
+///   preimage (records from before purchase IDs hold a mock hash)
+/// - license_hash: Hash of the license document (the data use agreement)
+///   the buyer acknowledged and co-signed at purchase (None if the listing
+///   had no license bound)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseRecord {
+    pub buyer: Address,
+    pub dataset_id: Bytes,
+    pub tx_hash: Bytes,
+    pub license_hash: Option<Bytes>,
+}
+
+/// Breakdown of how a purchase amount would be paid out, returned by
+/// RevenueSplitter and embedded in DatasetMarketplace purchase previews
+///
+/// Per-contributor amounts apply to every contributor when all weights are
+/// equal and are zero otherwise; totals cover the whole purchase.
+/// `treasury_amount` includes the rounding remainder.
+/// - contributor_amount: Paid to each contributor, net of institutional royalty
+/// - institutional_amount: Paid to the institution per contributor
+/// - referral_amount: Paid to the referrer per contributor
+/// - contributor_amounts: Paid to each contributor by address, net of
+///   institutional royalty
+/// - network_amount: Total paid to the contributors' referral chains
+/// - insurance_amount: Total insurance premiums paid into the pool
+/// - treasury_amount: Total paid to the BioChain treasury
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutBreakdown {
+    pub amount: i128,
+    pub num_contributors: u32,
+    pub contributor_amount: i128,
+    pub institutional_amount: i128,
+    pub referral_amount: i128,
+    pub contributor_amounts: Vec<(Address, i128)>,
+    pub network_amount: i128,
+    pub insurance_amount: i128,
+    pub treasury_amount: i128,
+}
+
+/// Event data for DatasetMarketplace's DatasetRegistered event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetRegisteredEventData {
+    pub dataset_id: Bytes,
+    pub price_usdc: i128,
+    pub study_count: u32,
+}
+
+/// Event data for DatasetMarketplace's DatasetPurchased event
+///
+/// `purchase_id` and `receipt_hash` are the SHA-256 of the
+/// `biochain_ids::PurchaseId` and `biochain_ids::Receipt` preimages, so
+/// back-office systems can rebuild both from the event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetPurchasedEventData {
+    pub buyer: Address,
+    pub dataset_id: Bytes,
+    pub price_usdc: i128,
+    pub purchase_id: BytesN<32>,
+    pub sequence: u64,
+    pub receipt_hash: BytesN<32>,
+}
@@ -0,0 +1,64 @@
+#![cfg(test)]
+
+use biochain_types::{Dataset, DatasetPurchasedEventData, DatasetStatus, PurchaseRecord};
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, Address, Bytes, BytesN, Env, IntoVal, Map, Symbol,
+    TryFromVal, Val, Vec,
+};
+
+#[test]
+fn test_shared_types_round_trip_through_val() {
+    let env = Env::default();
+    let dataset = Dataset {
+        dataset_id: Bytes::from_slice(&env, b"dataset_1"),
+        study_ids: Vec::from_array(&env, [Bytes::from_array(&env, &[1u8; 32])]),
+        price_usdc: 10_0000000,
+        owner: Address::generate(&env),
+        status: DatasetStatus::Delisted,
+    };
+    let record = PurchaseRecord {
+        buyer: Address::generate(&env),
+        dataset_id: dataset.dataset_id.clone(),
+        tx_hash: Bytes::from_array(&env, &[2u8; 32]),
+        license_hash: None,
+    };
+
+    // Act
+    let dataset_val: Val = dataset.clone().into_val(&env);
+    let record_val: Val = record.clone().into_val(&env);
+
+    // Assert
+    assert_eq!(Dataset::try_from_val(&env, &dataset_val), Ok(dataset));
+    assert_eq!(PurchaseRecord::try_from_val(&env, &record_val), Ok(record));
+}
+
+#[test]
+fn test_event_payload_field_names_are_stable() {
+    let env = Env::default();
+    let event = DatasetPurchasedEventData {
+        buyer: Address::generate(&env),
+        dataset_id: Bytes::from_slice(&env, b"dataset_1"),
+        price_usdc: 10_0000000,
+        purchase_id: BytesN::from_array(&env, &[3u8; 32]),
+        sequence: 1,
+        receipt_hash: BytesN::from_array(&env, &[4u8; 32]),
+    };
+
+    // Act: off-chain consumers decode events as maps keyed by field name
+    let event_val: Val = event.into_val(&env);
+    let fields = Map::<Symbol, Val>::try_from_val(&env, &event_val).unwrap();
+
+    // Assert
+    let keys = [
+        "buyer",
+        "dataset_id",
+        "price_usdc",
+        "purchase_id",
+        "receipt_hash",
+        "sequence",
+    ];
+    assert_eq!(fields.len(), keys.len() as u32);
+    for key in keys {
+        assert!(fields.contains_key(Symbol::new(&env, key)), "missing {key}");
+    }
+}
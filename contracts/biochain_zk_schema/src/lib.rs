@@ -0,0 +1,163 @@
+//! Canonical ZK public inputs shared by the prover pipeline and StudyRegistry
+//!
+//! The off-chain prover commits to these bytes and the on-chain verifier
+//! rebuilds them from the registration call, so both sides must agree
+//! byte-for-byte. The crate has no dependencies and no Soroban types, so
+//! it can be built into the prover as well as into the contracts.
+//!
+//! Layout (ENCODED_LEN bytes, no padding):
+//! - 4 bytes: MAGIC ("BCZK")
+//! - 1 byte: SCHEMA_VERSION
+//! - 32 bytes: dataset_hash
+//! - 56 bytes: contributor, Stellar strkey in ASCII ("G..." or "C...")
+//! - 32 bytes: attestation_hash, SHA-256 of the TEE attestation
+//! - 4 bytes: policy_version, big-endian
+//!
+//! A new layout gets a new SCHEMA_VERSION; decoders reject versions they
+//! do not know instead of guessing.
+//!
+//! The circuit does not take the encoding itself as public input: it
+//! exposes the SHA-256 digest of the encoding as SIGNAL_COUNT public
+//! signals (see `signals`), so the proof commits to every field above.
+#![no_std]
+
+/// Prefix identifying BIOCHAIN public inputs
+pub const MAGIC: [u8; 4] = *b"BCZK";
+
+/// Layout version written by `encode`
+pub const SCHEMA_VERSION: u8 = 1;
+
+/// Length of a Stellar strkey (account or contract address)
+pub const STRKEY_LEN: usize = 56;
+
+/// Length of the encoded public inputs
+pub const ENCODED_LEN: usize = 4 + 1 + 32 + STRKEY_LEN + 32 + 4;
+
+/// Number of public signals the digest of the encoding is split into
+pub const SIGNAL_COUNT: usize = 2;
+
+/// Error types for schema operations
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SchemaError {
+    InvalidLength,
+    InvalidMagic,
+    UnsupportedVersion,
+    InvalidContributor,
+}
+
+/// Public inputs of a study validity proof
+///
+/// - dataset_hash: SHA-256 of the processed dataset
+/// - contributor: Strkey of the contributor registering the study
+/// - attestation_hash: SHA-256 of the TEE attestation
+/// - policy_version: Version of the data-handling policy the proof
+///   certifies (no PII, processed in TEE, ...)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PublicInputs {
+    pub dataset_hash: [u8; 32],
+    pub contributor: [u8; STRKEY_LEN],
+    pub attestation_hash: [u8; 32],
+    pub policy_version: u32,
+}
+
+impl PublicInputs {
+    /// Build public inputs, validating the contributor strkey
+    ///
+    /// # Returns
+    /// * `Ok(PublicInputs)` if the contributor is a well-formed strkey
+    /// * `Err(SchemaError::InvalidContributor)` otherwise
+    pub fn new(
+        dataset_hash: [u8; 32],
+        contributor: [u8; STRKEY_LEN],
+        attestation_hash: [u8; 32],
+        policy_version: u32,
+    ) -> Result<Self, SchemaError> {
+        if !is_strkey(&contributor) {
+            return Err(SchemaError::InvalidContributor);
+        }
+        Ok(Self {
+            dataset_hash,
+            contributor,
+            attestation_hash,
+            policy_version,
+        })
+    }
+
+    /// Serialize to the canonical layout
+    pub fn encode(&self) -> [u8; ENCODED_LEN] {
+        let mut out = [0u8; ENCODED_LEN];
+        let mut offset = 0;
+        for field in [
+            &MAGIC[..],
+            &[SCHEMA_VERSION][..],
+            &self.dataset_hash[..],
+            &self.contributor[..],
+            &self.attestation_hash[..],
+            &self.policy_version.to_be_bytes()[..],
+        ] {
+            out[offset..offset + field.len()].copy_from_slice(field);
+            offset += field.len();
+        }
+        out
+    }
+
+    /// Parse the canonical layout
+    ///
+    /// # Returns
+    /// * `Ok(PublicInputs)` if the bytes are a valid encoding
+    /// * `Err(SchemaError)` describing the first problem found
+    pub fn decode(bytes: &[u8]) -> Result<Self, SchemaError> {
+        if bytes.len() != ENCODED_LEN {
+            return Err(SchemaError::InvalidLength);
+        }
+        if bytes[..4] != MAGIC {
+            return Err(SchemaError::InvalidMagic);
+        }
+        if bytes[4] != SCHEMA_VERSION {
+            return Err(SchemaError::UnsupportedVersion);
+        }
+
+        let mut dataset_hash = [0u8; 32];
+        let mut contributor = [0u8; STRKEY_LEN];
+        let mut attestation_hash = [0u8; 32];
+        let mut policy_version = [0u8; 4];
+        let mut offset = 5;
+        for field in [
+            &mut dataset_hash[..],
+            &mut contributor[..],
+            &mut attestation_hash[..],
+            &mut policy_version[..],
+        ] {
+            let len = field.len();
+            field.copy_from_slice(&bytes[offset..offset + len]);
+            offset += len;
+        }
+
+        Self::new(
+            dataset_hash,
+            contributor,
+            attestation_hash,
+            u32::from_be_bytes(policy_version),
+        )
+    }
+}
+
+/// Check the shape of a Stellar account ("G") or contract ("C") strkey
+///
+/// Only the alphabet and prefix are checked; the checksum is left to the
+/// Stellar tooling that produced the address.
+fn is_strkey(bytes: &[u8; STRKEY_LEN]) -> bool {
+    matches!(bytes[0], b'G' | b'C') && bytes.iter().all(|c| matches!(c, b'A'..=b'Z' | b'2'..=b'7'))
+}
+
+/// Split the SHA-256 digest of an encoding into the circuit's public signals
+///
+/// A digest does not fit the BN254 scalar field, so it is split into its
+/// high and low 128 bits, each a 32-byte big-endian signal.
+pub fn signals(digest: &[u8; 32]) -> [[u8; 32]; SIGNAL_COUNT] {
+    let mut high = [0u8; 32];
+    let mut low = [0u8; 32];
+    high[16..].copy_from_slice(&digest[..16]);
+    low[16..].copy_from_slice(&digest[16..]);
+    [high, low]
+}
@@ -0,0 +1,104 @@
+use biochain_zk_schema::{signals, PublicInputs, SchemaError, ENCODED_LEN, SCHEMA_VERSION};
+
+/// Encoding shared with the prover pipeline (fixtures/public_inputs_v1.hex):
+/// dataset_hash [1; 32], the all-zero account strkey, SHA-256 of
+/// "mock_attestation" and policy version 1
+const FIXTURE_V1: &str = include_str!("fixtures/public_inputs_v1.hex");
+
+const CONTRIBUTOR: &[u8; 56] = b"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+/// SHA-256("mock_attestation")
+const ATTESTATION_HASH: [u8; 32] = [
+    0x13, 0x08, 0xf5, 0x68, 0xd5, 0x95, 0xe2, 0x02, 0xcd, 0x59, 0xb3, 0xa2, 0x76, 0xe3, 0xf9, 0x08,
+    0xe5, 0x70, 0xae, 0xa4, 0xc1, 0x90, 0x23, 0x2b, 0x6f, 0x8c, 0x65, 0x24, 0x90, 0x1d, 0x65, 0x0d,
+];
+
+/// Helper: Decode the hex fixture
+fn fixture_bytes() -> [u8; ENCODED_LEN] {
+    let hex = FIXTURE_V1.trim().as_bytes();
+    assert_eq!(hex.len(), 2 * ENCODED_LEN);
+    let nibble = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+    let mut out = [0u8; ENCODED_LEN];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = nibble(hex[2 * i]) << 4 | nibble(hex[2 * i + 1]);
+    }
+    out
+}
+
+fn fixture_inputs() -> PublicInputs {
+    PublicInputs::new([1u8; 32], *CONTRIBUTOR, ATTESTATION_HASH, 1).unwrap()
+}
+
+#[test]
+fn test_encode_matches_fixture() {
+    assert_eq!(fixture_inputs().encode(), fixture_bytes());
+}
+
+#[test]
+fn test_decode_fixture_round_trips() {
+    let decoded = PublicInputs::decode(&fixture_bytes()).unwrap();
+    assert_eq!(decoded, fixture_inputs());
+    assert_eq!(decoded.encode(), fixture_bytes());
+}
+
+#[test]
+fn test_policy_version_is_big_endian() {
+    let mut inputs = fixture_inputs();
+    inputs.policy_version = 0x0102_0304;
+    let encoded = inputs.encode();
+    assert_eq!(encoded[ENCODED_LEN - 4..], [1, 2, 3, 4]);
+    assert_eq!(PublicInputs::decode(&encoded), Ok(inputs));
+}
+
+#[test]
+fn test_decode_rejects_malformed_input() {
+    let bytes = fixture_bytes();
+    assert_eq!(
+        PublicInputs::decode(&bytes[..ENCODED_LEN - 1]),
+        Err(SchemaError::InvalidLength)
+    );
+
+    let mut bad_magic = bytes;
+    bad_magic[0] = b'X';
+    assert_eq!(
+        PublicInputs::decode(&bad_magic),
+        Err(SchemaError::InvalidMagic)
+    );
+
+    let mut bad_version = bytes;
+    bad_version[4] = SCHEMA_VERSION + 1;
+    assert_eq!(
+        PublicInputs::decode(&bad_version),
+        Err(SchemaError::UnsupportedVersion)
+    );
+
+    // Lowercase is outside the strkey alphabet
+    let mut bad_contributor = bytes;
+    bad_contributor[40] = b'a';
+    assert_eq!(
+        PublicInputs::decode(&bad_contributor),
+        Err(SchemaError::InvalidContributor)
+    );
+}
+
+#[test]
+fn test_new_rejects_non_address_prefix() {
+    let mut contributor = *CONTRIBUTOR;
+    contributor[0] = b'S';
+    assert_eq!(
+        PublicInputs::new([1u8; 32], contributor, ATTESTATION_HASH, 1),
+        Err(SchemaError::InvalidContributor)
+    );
+}
+
+#[test]
+fn test_signals_split_digest_in_halves() {
+    let digest: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+    let [high, low] = signals(&digest);
+
+    // Each half is right-aligned, so both stay below the scalar field modulus
+    assert_eq!(high[..16], [0u8; 16]);
+    assert_eq!(low[..16], [0u8; 16]);
+    assert_eq!(high[16..], digest[..16]);
+    assert_eq!(low[16..], digest[16..]);
+}
@@ -0,0 +1,335 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const GUARDIAN_KEY: Symbol = symbol_short!("GUARDIAN");
+const REPORTER_KEY: Symbol = symbol_short!("REPORTER");
+const THRESHOLDS_KEY: Symbol = symbol_short!("THRESH");
+const WINDOW_KEY: Symbol = symbol_short!("WINDOW");
+const TRIP_KEY: Symbol = symbol_short!("TRIP");
+
+/// Metric names reporters push observations to
+///
+/// - purchase: Number of dataset purchases (marketplace)
+/// - payout: USDC paid out to contributors (splitter)
+pub const METRIC_PURCHASE: Symbol = symbol_short!("purchase");
+pub const METRIC_PAYOUT: Symbol = symbol_short!("payout");
+
+/// Trip thresholds
+///
+/// Observations are summed over a sliding window of `window_seconds`; the
+/// breaker trips as soon as either sum exceeds its maximum.
+/// - max_payout_volume: USDC paid out per window (e.g. 10x normal volume)
+/// - max_purchases: Purchases per window
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Thresholds {
+    pub window_seconds: u64,
+    pub max_payout_volume: i128,
+    pub max_purchases: u32,
+}
+
+/// Observations of the current and previous window
+///
+/// The sliding sum weights the previous window by the share of it that
+/// still overlaps the sliding window, so bursts straddling a window
+/// boundary are not missed.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Window {
+    pub index: u64,
+    pub payout_volume: i128,
+    pub purchases: u32,
+    pub prev_payout_volume: i128,
+    pub prev_purchases: u32,
+}
+
+/// Why and when the breaker tripped
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trip {
+    pub metric: Symbol,
+    pub observed: i128,
+    pub threshold: i128,
+    pub tripped_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedReporter = 3,
+    UnknownMetric = 4,
+    InvalidAmount = 5,
+    InvalidThresholds = 6,
+    NotTripped = 7,
+}
+
+#[contract]
+pub struct CircuitBreaker;
+
+#[contractimpl]
+impl CircuitBreaker {
+    /// Initialize the CircuitBreaker contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage reporters and thresholds
+    /// * `guardian` - Address allowed to resume after a trip
+    /// * `thresholds` - Initial trip thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if called twice or the thresholds are invalid
+    pub fn init(
+        env: Env,
+        admin: Address,
+        guardian: Address,
+        thresholds: Thresholds,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        Self::validate_thresholds(&thresholds)?;
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&GUARDIAN_KEY, &guardian);
+        storage.set(&THRESHOLDS_KEY, &thresholds);
+        Ok(())
+    }
+
+    /// Allow a contract (marketplace, splitter) to push observations
+    pub fn add_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&(REPORTER_KEY, reporter), &true);
+        Ok(())
+    }
+
+    /// Revoke a reporter's permission to push observations
+    pub fn remove_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(REPORTER_KEY, reporter));
+        Ok(())
+    }
+
+    /// Replace the trip thresholds
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `thresholds` - New thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidThresholds)` if a field is zero or negative
+    pub fn set_thresholds(env: Env, thresholds: Thresholds) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::validate_thresholds(&thresholds)?;
+        env.storage().instance().set(&THRESHOLDS_KEY, &thresholds);
+        Ok(())
+    }
+
+    /// Get the trip thresholds
+    pub fn get_thresholds(env: Env) -> Result<Thresholds, Error> {
+        env.storage()
+            .instance()
+            .get(&THRESHOLDS_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Push an observation and trip the breaker if a threshold is exceeded
+    ///
+    /// Reporters call this after the observed action succeeded; once the
+    /// breaker is tripped, observations are ignored until a resume.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `metric` - One of the METRIC_* symbols
+    /// * `amount` - Number of purchases or USDC paid out
+    ///
+    /// # Returns
+    /// * `Ok(bool)` with whether the breaker is tripped
+    /// * `Err(Error)` if the reporter, metric or amount is invalid
+    pub fn record(
+        env: Env,
+        reporter: Address,
+        metric: Symbol,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        reporter.require_auth();
+        let storage = env.storage().instance();
+        if !storage.has(&(REPORTER_KEY, reporter)) {
+            return Err(Error::UnauthorizedReporter);
+        }
+        if metric != METRIC_PURCHASE && metric != METRIC_PAYOUT {
+            return Err(Error::UnknownMetric);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if storage.has(&TRIP_KEY) {
+            return Ok(true);
+        }
+
+        // ============================================
+        // 2. UPDATE WINDOW
+        // ============================================
+        let thresholds = Self::get_thresholds(env.clone())?;
+        let now = env.ledger().timestamp();
+        let mut window = Self::current_window(&env, &thresholds, now);
+        if metric == METRIC_PURCHASE {
+            window.purchases = window.purchases.saturating_add(amount as u32);
+        } else {
+            window.payout_volume = window.payout_volume.saturating_add(amount);
+        }
+        storage.set(&WINDOW_KEY, &window);
+
+        // ============================================
+        // 3. CHECK THRESHOLDS
+        // ============================================
+        let (observed, threshold) = if metric == METRIC_PURCHASE {
+            (
+                Self::sliding_sum(
+                    &thresholds,
+                    now,
+                    window.prev_purchases as i128,
+                    window.purchases as i128,
+                ),
+                thresholds.max_purchases as i128,
+            )
+        } else {
+            (
+                Self::sliding_sum(
+                    &thresholds,
+                    now,
+                    window.prev_payout_volume,
+                    window.payout_volume,
+                ),
+                thresholds.max_payout_volume,
+            )
+        };
+        if observed <= threshold {
+            return Ok(false);
+        }
+
+        // ============================================
+        // 4. TRIP AND ALERT
+        // ============================================
+        let trip = Trip {
+            metric: metric.clone(),
+            observed,
+            threshold,
+            tripped_at: now,
+        };
+        storage.set(&TRIP_KEY, &trip);
+        env.events().publish((symbol_short!("Alert"), metric), trip);
+        Ok(true)
+    }
+
+    /// Check whether the breaker is tripped
+    ///
+    /// The marketplace and splitter treat a tripped breaker as paused.
+    pub fn is_tripped(env: Env) -> bool {
+        env.storage().instance().has(&TRIP_KEY)
+    }
+
+    /// Get the details of the current trip, if any
+    pub fn get_trip(env: Env) -> Option<Trip> {
+        env.storage().instance().get(&TRIP_KEY)
+    }
+
+    /// Get the observations of the current window
+    pub fn get_window(env: Env) -> Window {
+        env.storage()
+            .instance()
+            .get(&WINDOW_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Resume the marketplace and splitter after a trip
+    ///
+    /// Clears the trip and the window observations, so the burst that
+    /// tripped the breaker does not trip it again right away.
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotTripped)` if the breaker is not tripped
+    pub fn resume(env: Env) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        let guardian: Address = storage.get(&GUARDIAN_KEY).ok_or(Error::NotInitialized)?;
+        guardian.require_auth();
+        if !storage.has(&TRIP_KEY) {
+            return Err(Error::NotTripped);
+        }
+        storage.remove(&TRIP_KEY);
+        storage.remove(&WINDOW_KEY);
+        env.events().publish((symbol_short!("Resumed"),), guardian);
+        Ok(())
+    }
+
+    /// Load the window, rolling it over if `now` is past its end
+    fn current_window(env: &Env, thresholds: &Thresholds, now: u64) -> Window {
+        let index = now / thresholds.window_seconds;
+        let window: Window = env
+            .storage()
+            .instance()
+            .get(&WINDOW_KEY)
+            .unwrap_or_default();
+
+        if window.index == index {
+            window
+        } else if window.index + 1 == index {
+            Window {
+                index,
+                prev_payout_volume: window.payout_volume,
+                prev_purchases: window.purchases,
+                ..Window::default()
+            }
+        } else {
+            Window {
+                index,
+                ..Window::default()
+            }
+        }
+    }
+
+    /// Estimate the sum over the sliding window ending at `now`
+    fn sliding_sum(thresholds: &Thresholds, now: u64, previous: i128, current: i128) -> i128 {
+        let elapsed = (now % thresholds.window_seconds) as i128;
+        let remaining = thresholds.window_seconds as i128 - elapsed;
+        current
+            .saturating_add(previous.saturating_mul(remaining) / thresholds.window_seconds as i128)
+    }
+
+    fn validate_thresholds(thresholds: &Thresholds) -> Result<(), Error> {
+        if thresholds.window_seconds == 0
+            || thresholds.max_payout_volume <= 0
+            || thresholds.max_purchases == 0
+        {
+            return Err(Error::InvalidThresholds);
+        }
+        Ok(())
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
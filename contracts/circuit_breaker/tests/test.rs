@@ -0,0 +1,228 @@
+#![cfg(test)]
+
+use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerClient, Error, Thresholds, METRIC_PAYOUT, METRIC_PURCHASE,
+};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+struct Setup<'a> {
+    env: Env,
+    breaker: CircuitBreakerClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+    dataset_id: Bytes,
+}
+
+/// Helper: Deploy the core contracts wired to a CircuitBreaker
+fn setup<'a>(thresholds: &Thresholds) -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let breaker = CircuitBreakerClient::new(&env, &env.register_contract(None, CircuitBreaker));
+    breaker.init(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        thresholds,
+    );
+    breaker.add_reporter(&marketplace.address);
+    breaker.add_reporter(&splitter.address);
+    marketplace.set_circuit_breaker(&breaker.address);
+    splitter.set_circuit_breaker(&breaker.address);
+
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from_array(&env, &[1u8; 32])],
+        &PRICE,
+        &None,
+    );
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        breaker,
+        marketplace,
+        splitter,
+        dataset_id,
+    }
+}
+
+/// Helper: Fund a fresh buyer and try to buy the test dataset
+fn try_buy(s: &Setup) -> Result<(), dataset_marketplace::Error> {
+    let buyer = Address::generate(&s.env);
+    s.usdc.mint(&buyer, &PRICE);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        &buyer,
+        &s.marketplace.address,
+        &PRICE,
+        &1_000,
+    );
+    match s.marketplace.try_purchase_dataset(&s.dataset_id, &buyer, &None) {
+        Ok(_) => Ok(()),
+        Err(Ok(e)) => Err(e),
+        Err(Err(e)) => panic!("unexpected invoke error: {e:?}"),
+    }
+}
+
+fn thresholds(max_payout_volume: i128, max_purchases: u32) -> Thresholds {
+    Thresholds {
+        window_seconds: 3_600,
+        max_payout_volume,
+        max_purchases,
+    }
+}
+
+#[test]
+fn test_purchase_burst_trips_and_pauses_marketplace_and_splitter() {
+    let s = setup(&thresholds(1_000 * PRICE, 2));
+
+    // Act: the third purchase in the window exceeds the threshold
+    try_buy(&s).unwrap();
+    try_buy(&s).unwrap();
+    try_buy(&s).unwrap();
+
+    // Assert: the tripping purchase went through, later ones are blocked
+    let trip = s.breaker.get_trip().unwrap();
+    assert_eq!(trip.metric, METRIC_PURCHASE);
+    assert_eq!((trip.observed, trip.threshold), (3, 2));
+    assert!(s.marketplace.is_paused());
+    assert!(s.splitter.is_paused());
+    assert_eq!(try_buy(&s), Err(dataset_marketplace::Error::Paused));
+}
+
+#[test]
+fn test_payout_volume_trips_breaker() {
+    let s = setup(&thresholds(PRICE, 100));
+
+    // Act: contributors receive the price minus the platform fee, so the
+    // second purchase pushes the payout volume past one price
+    try_buy(&s).unwrap();
+    assert!(!s.breaker.is_tripped());
+    try_buy(&s).unwrap();
+
+    // Assert
+    let trip = s.breaker.get_trip().unwrap();
+    assert_eq!(trip.metric, METRIC_PAYOUT);
+    assert!(trip.observed > PRICE);
+    assert_eq!(try_buy(&s), Err(dataset_marketplace::Error::Paused));
+}
+
+#[test]
+fn test_sliding_window_decays_previous_observations() {
+    let s = setup(&thresholds(1_000 * PRICE, 2));
+
+    // Arrange: two purchases at the very end of a window
+    s.env.ledger().with_mut(|l| l.timestamp = 3_600 * 300 - 1);
+    try_buy(&s).unwrap();
+    try_buy(&s).unwrap();
+
+    // Act: a purchase right after the boundary still counts the burst
+    s.env.ledger().with_mut(|l| l.timestamp = 3_600 * 300);
+    try_buy(&s).unwrap();
+    assert!(s.breaker.is_tripped());
+
+    // Half a window later the previous window only counts half
+    s.breaker.resume();
+    s.env.ledger().with_mut(|l| l.timestamp = 3_600 * 301);
+    try_buy(&s).unwrap();
+    try_buy(&s).unwrap();
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = 3_600 * 302 + 1_800);
+    try_buy(&s).unwrap();
+
+    // Assert: 2 * 1/2 + 1 = 2 purchases, not over the threshold
+    assert!(!s.breaker.is_tripped());
+    assert_eq!(s.breaker.get_window().prev_purchases, 2);
+}
+
+#[test]
+fn test_guardian_resume_reopens_marketplace() {
+    let s = setup(&thresholds(1_000 * PRICE, 1));
+    try_buy(&s).unwrap();
+    try_buy(&s).unwrap();
+    assert!(s.breaker.is_tripped());
+
+    // Act
+    s.breaker.resume();
+
+    // Assert: the window was cleared, so the next purchase goes through
+    assert!(!s.marketplace.is_paused());
+    try_buy(&s).unwrap();
+    assert_eq!(s.breaker.try_resume(), Err(Ok(Error::NotTripped)));
+}
+
+#[test]
+fn test_record_rejects_invalid_reports_and_thresholds() {
+    let s = setup(&thresholds(PRICE, 1));
+    let outsider = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        s.breaker.try_record(&outsider, &METRIC_PURCHASE, &1),
+        Err(Ok(Error::UnauthorizedReporter))
+    );
+    assert_eq!(
+        s.breaker.try_record(
+            &s.splitter.address,
+            &soroban_sdk::symbol_short!("sales"),
+            &1
+        ),
+        Err(Ok(Error::UnknownMetric))
+    );
+    assert_eq!(
+        s.breaker
+            .try_record(&s.splitter.address, &METRIC_PAYOUT, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.breaker.try_set_thresholds(&thresholds(PRICE, 0)),
+        Err(Ok(Error::InvalidThresholds))
+    );
+    assert_eq!(
+        s.breaker
+            .try_init(&outsider, &outsider, &thresholds(PRICE, 1)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
@@ -0,0 +1,251 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String,
+    Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MINTER_KEY: Symbol = symbol_short!("MINTER");
+const CREDIT_KEY: Symbol = symbol_short!("CREDIT");
+const SUPPLY_KEY: Symbol = symbol_short!("SUPPLY");
+
+/// TTL policy for credit records, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Credits minted for each study verified by the StudyRegistry
+pub const STUDY_CREDITS: i128 = 10;
+
+/// Credits minted each time a dataset containing the contributor's study is sold
+pub const SALE_CREDITS: i128 = 1;
+
+/// Credit record
+///
+/// - verified_studies: Studies the credit was minted for
+/// - sales: Sales the credit was minted for
+/// - balance: Credits held (derived from the counters)
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Credit {
+    pub verified_studies: u32,
+    pub sales: u32,
+    pub balance: i128,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedMinter = 3,
+    NonTransferable = 4,
+}
+
+/// Non-transferable (soulbound) credit for contributions
+///
+/// Minted by the StudyRegistry when a study is verified and by the
+/// RevenueSplitter on each sale. Credits can never move between
+/// addresses, so a balance is a citable record of the holder's own
+/// contributions; reputation and tier systems read it with `balance`.
+#[contract]
+pub struct ContributionCredit;
+
+#[contractimpl]
+impl ContributionCredit {
+    /// Initialize the ContributionCredit contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage minters
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Allow a contract (registry, splitter) to mint credits
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `minter` - Address of the minting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn add_minter(env: Env, minter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&(MINTER_KEY, minter), &true);
+        Ok(())
+    }
+
+    /// Revoke a contract's permission to mint credits
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `minter` - Address of the minting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn remove_minter(env: Env, minter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(MINTER_KEY, minter));
+        Ok(())
+    }
+
+    /// Check whether an address is an authorized minter
+    pub fn is_minter(env: Env, minter: Address) -> bool {
+        env.storage().instance().has(&(MINTER_KEY, minter))
+    }
+
+    /// Mint credit for a study verified by the StudyRegistry
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `minter` - Authorized minting contract (must authorize the call)
+    /// * `contributor` - Contributor whose study was verified
+    ///
+    /// # Returns
+    /// * `Ok(Credit)` with the updated record
+    /// * `Err(Error::UnauthorizedMinter)` if minter is not allowed
+    pub fn record_study_verified(
+        env: Env,
+        minter: Address,
+        contributor: Address,
+    ) -> Result<Credit, Error> {
+        Self::mint(&env, &minter, contributor, STUDY_CREDITS, |credit| {
+            credit.verified_studies = credit.verified_studies.saturating_add(1)
+        })
+    }
+
+    /// Mint credit for a sale of a dataset containing the contributor's study
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `minter` - Authorized minting contract (must authorize the call)
+    /// * `contributor` - Contributor whose study was sold
+    ///
+    /// # Returns
+    /// * `Ok(Credit)` with the updated record
+    /// * `Err(Error::UnauthorizedMinter)` if minter is not allowed
+    pub fn record_sale(env: Env, minter: Address, contributor: Address) -> Result<Credit, Error> {
+        Self::mint(&env, &minter, contributor, SALE_CREDITS, |credit| {
+            credit.sales = credit.sales.saturating_add(1)
+        })
+    }
+
+    /// Get the credit record of a contributor (all zero if none was minted)
+    pub fn get_credit(env: Env, contributor: Address) -> Credit {
+        env.storage()
+            .persistent()
+            .get(&(CREDIT_KEY, contributor))
+            .unwrap_or_default()
+    }
+
+    /// Get the credit balance of an address (SEP-41 `balance`)
+    pub fn balance(env: Env, id: Address) -> i128 {
+        Self::get_credit(env, id).balance
+    }
+
+    /// Get the credits minted to all contributors
+    pub fn total_supply(env: Env) -> i128 {
+        env.storage().instance().get(&SUPPLY_KEY).unwrap_or(0)
+    }
+
+    /// Credits are whole units
+    pub fn decimals(_env: Env) -> u32 {
+        0
+    }
+
+    /// Token name (SEP-41 `name`)
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "BioChain Contribution Credit")
+    }
+
+    /// Token symbol (SEP-41 `symbol`)
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "BCC")
+    }
+
+    /// Always fails: credits are bound to the contributor that earned them
+    ///
+    /// Exposed with the SEP-41 signature so wallets get a clear error
+    /// instead of a missing function.
+    pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) -> Result<(), Error> {
+        Err(Error::NonTransferable)
+    }
+
+    /// Always fails, like `transfer`
+    pub fn transfer_from(
+        _env: Env,
+        _spender: Address,
+        _from: Address,
+        _to: Address,
+        _amount: i128,
+    ) -> Result<(), Error> {
+        Err(Error::NonTransferable)
+    }
+
+    /// Always fails, like `transfer`
+    pub fn approve(
+        _env: Env,
+        _from: Address,
+        _spender: Address,
+        _amount: i128,
+        _expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        Err(Error::NonTransferable)
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    /// Authenticate the minter, mint `amount` credits and persist the record
+    fn mint(
+        env: &Env,
+        minter: &Address,
+        contributor: Address,
+        amount: i128,
+        count: impl FnOnce(&mut Credit),
+    ) -> Result<Credit, Error> {
+        minter.require_auth();
+        if !Self::is_minter(env.clone(), minter.clone()) {
+            return Err(Error::UnauthorizedMinter);
+        }
+
+        let mut credit = Self::get_credit(env.clone(), contributor.clone());
+        count(&mut credit);
+        credit.balance = credit.balance.saturating_add(amount);
+        let key = (CREDIT_KEY, contributor.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, &credit);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        env.storage().instance().set(
+            &SUPPLY_KEY,
+            &Self::total_supply(env.clone()).saturating_add(amount),
+        );
+
+        env.events().publish(
+            (symbol_short!("mint"), contributor),
+            (amount, credit.balance),
+        );
+        Ok(credit)
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(test)]
+
+use contribution_credit::{
+    ContributionCredit, ContributionCreditClient, Credit, Error, SALE_CREDITS, STUDY_CREDITS,
+};
+use soroban_sdk::{testutils::Address as AddressTestUtils, token, vec, Address, Bytes, BytesN, Env};
+
+struct Setup<'a> {
+    env: Env,
+    credit: ContributionCreditClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+}
+
+/// Helper: Deploy a ContributionCredit with the registry and splitter as minters
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let credit = ContributionCreditClient::new(&env, &env.register_contract(None, ContributionCredit));
+    credit.init(&Address::generate(&env));
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+
+    for minter in [&registry.address, &splitter.address] {
+        credit.add_minter(minter);
+    }
+    registry.set_credit_token(&credit.address);
+    splitter.set_credit_token(&credit.address);
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        credit,
+        registry,
+        splitter,
+    }
+}
+
+/// Helper: Register a study with hash `[seed; 32]`
+fn register_study(s: &Setup, seed: u8, contributor: &Address) {
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, contributor);
+    s.registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, contributor),
+        contributor,
+    );
+}
+
+#[test]
+fn test_registry_and_splitter_mint_credit() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+
+    // Act: two verified studies, then two sales through the marketplace
+    // (one shared with another contributor)
+    register_study(&s, 1, &contributor);
+    register_study(&s, 2, &contributor);
+    let marketplace = Address::generate(&s.env);
+    s.splitter.set_authorized_caller(&marketplace);
+    s.usdc.mint(&marketplace, &20_0000000);
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    s.splitter.payout_purchase(
+        &marketplace,
+        &dataset_id,
+        &vec![&s.env, contributor.clone()],
+        &10_0000000,
+    );
+    s.splitter.payout_purchase(
+        &marketplace,
+        &dataset_id,
+        &vec![&s.env, contributor.clone(), other.clone()],
+        &10_0000000,
+    );
+
+    // A self-funded payout is not a sale, so it mints no credit
+    let payer = Address::generate(&s.env);
+    s.usdc.mint(&payer, &10_0000000);
+    s.splitter
+        .payout_purchase(&payer, &dataset_id, &vec![&s.env, contributor.clone()], &10_0000000);
+
+    // Assert
+    assert_eq!(
+        s.credit.get_credit(&contributor),
+        Credit {
+            verified_studies: 2,
+            sales: 2,
+            balance: 2 * STUDY_CREDITS + 2 * SALE_CREDITS,
+        }
+    );
+    assert_eq!(s.credit.balance(&other), SALE_CREDITS);
+    assert_eq!(s.credit.balance(&Address::generate(&s.env)), 0);
+    assert_eq!(s.credit.total_supply(), 2 * STUDY_CREDITS + 3 * SALE_CREDITS);
+}
+
+#[test]
+fn test_credit_is_not_transferable() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    let recipient = Address::generate(&s.env);
+    register_study(&s, 1, &contributor);
+
+    // Act & Assert
+    assert_eq!(
+        s.credit.try_transfer(&contributor, &recipient, &1),
+        Err(Ok(Error::NonTransferable))
+    );
+    assert_eq!(
+        s.credit.try_approve(&contributor, &recipient, &1, &1_000),
+        Err(Ok(Error::NonTransferable))
+    );
+    assert_eq!(
+        s.credit.try_transfer_from(&recipient, &contributor, &recipient, &1),
+        Err(Ok(Error::NonTransferable))
+    );
+    assert_eq!(s.credit.balance(&contributor), STUDY_CREDITS);
+    assert_eq!(s.credit.balance(&recipient), 0);
+}
+
+#[test]
+fn test_only_minters_mint() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        s.credit
+            .try_record_sale(&Address::generate(&s.env), &contributor),
+        Err(Ok(Error::UnauthorizedMinter))
+    );
+
+    // A removed minter stops minting, but registrations still go through
+    s.credit.remove_minter(&s.registry.address);
+    assert!(!s.credit.is_minter(&s.registry.address));
+    register_study(&s, 1, &contributor);
+    assert_eq!(s.credit.balance(&contributor), 0);
+}
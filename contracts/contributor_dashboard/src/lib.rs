@@ -0,0 +1,173 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
+    IntoVal, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const TARGETS_KEY: Symbol = symbol_short!("TARGETS");
+
+/// Contracts the dashboard reads from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Targets {
+    pub registry: Address,
+    pub marketplace: Address,
+    pub splitter: Address,
+}
+
+/// ContributorEarnings as returned by RevenueSplitter.get_contributor_earnings()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributorEarnings {
+    pub sales: u32,
+    pub total_earned: i128,
+    pub claimable: i128,
+}
+
+/// Everything the contributor dashboard shows, in one call
+///
+/// - studies: Studies registered by the contributor (StudyRegistry)
+/// - datasets: Distinct datasets including any of those studies
+///   (DatasetMarketplace)
+/// - sales, total_earned, claimable: Running earnings (RevenueSplitter);
+///   `claimable` is what the contributor has been credited but not yet
+///   claimed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dashboard {
+    pub contributor: Address,
+    pub studies: Vec<BytesN<32>>,
+    pub datasets: Vec<Bytes>,
+    pub sales: u32,
+    pub total_earned: i128,
+    pub claimable: i128,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+}
+
+#[contract]
+pub struct ContributorDashboard;
+
+#[contractimpl]
+impl ContributorDashboard {
+    /// Initialize the ContributorDashboard contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to repoint the targets
+    /// * `targets` - Registry, marketplace and splitter to read from
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address, targets: Targets) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&TARGETS_KEY, &targets);
+        Ok(())
+    }
+
+    /// Repoint the dashboard at new contract addresses
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `targets` - Registry, marketplace and splitter to read from
+    pub fn set_targets(env: Env, targets: Targets) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&TARGETS_KEY, &targets);
+        Ok(())
+    }
+
+    /// Get the contracts the dashboard reads from
+    pub fn get_targets(env: Env) -> Result<Targets, Error> {
+        env.storage()
+            .instance()
+            .get(&TARGETS_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the dashboard of a contributor
+    ///
+    /// Read-only; meant to be simulated by the frontend. Flow:
+    /// 1. List the contributor's studies from StudyRegistry
+    /// 2. Collect the distinct datasets including them from DatasetMarketplace
+    /// 3. Read the running earnings from RevenueSplitter
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    ///
+    /// # Returns
+    /// * `Ok(Dashboard)` with the joined data
+    /// * `Err(Error::NotInitialized)` if the dashboard is not initialized
+    pub fn get_dashboard(env: Env, contributor: Address) -> Result<Dashboard, Error> {
+        let targets = Self::get_targets(env.clone())?;
+
+        // ============================================
+        // 1. LIST STUDIES
+        // ============================================
+        let studies: Vec<BytesN<32>> = env.invoke_contract(
+            &targets.registry,
+            &Symbol::new(&env, "get_contributor_studies"),
+            (contributor.clone(),).into_val(&env),
+        );
+
+        // ============================================
+        // 2. COLLECT DATASETS
+        // ============================================
+        let mut datasets = Vec::new(&env);
+        for study in studies.iter() {
+            let study_datasets: Vec<Bytes> = env.invoke_contract(
+                &targets.marketplace,
+                &Symbol::new(&env, "get_study_datasets"),
+                (Bytes::from(study),).into_val(&env),
+            );
+            for dataset_id in study_datasets.iter() {
+                if !datasets.contains(&dataset_id) {
+                    datasets.push_back(dataset_id);
+                }
+            }
+        }
+
+        // ============================================
+        // 3. READ EARNINGS
+        // ============================================
+        let earnings: ContributorEarnings = env.invoke_contract(
+            &targets.splitter,
+            &Symbol::new(&env, "get_contributor_earnings"),
+            (contributor.clone(),).into_val(&env),
+        );
+
+        Ok(Dashboard {
+            contributor,
+            studies,
+            datasets,
+            sales: earnings.sales,
+            total_earned: earnings.total_earned,
+            claimable: earnings.claimable,
+        })
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
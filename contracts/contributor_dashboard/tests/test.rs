@@ -0,0 +1,166 @@
+#![cfg(test)]
+
+use contributor_dashboard::{ContributorDashboard, ContributorDashboardClient, Error, Targets};
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+struct Setup<'a> {
+    env: Env,
+    dashboard: ContributorDashboardClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    targets: Targets,
+    usdc: token::StellarAssetClient<'a>,
+}
+
+/// Helper: Deploy the core contracts and an initialized ContributorDashboard
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let targets = Targets {
+        registry: registry.address.clone(),
+        marketplace: marketplace.address.clone(),
+        splitter: splitter.address.clone(),
+    };
+    let dashboard =
+        ContributorDashboardClient::new(&env, &env.register_contract(None, ContributorDashboard));
+    dashboard.init(&Address::generate(&env), &targets);
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        dashboard,
+        registry,
+        marketplace,
+        targets,
+    }
+}
+
+/// Helper: Register a study with the given seed for `contributor`
+fn register_study(s: &Setup, seed: u8, contributor: &Address) {
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, contributor);
+    s.registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, contributor),
+        contributor,
+    );
+}
+
+/// Helper: Buy a dataset as a fresh buyer
+fn buy_dataset(s: &Setup, dataset_id: &Bytes) {
+    let buyer = Address::generate(&s.env);
+    s.usdc.mint(&buyer, &PRICE);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        &buyer,
+        &s.marketplace.address,
+        &PRICE,
+        &1_000,
+    );
+    s.marketplace.purchase_dataset(dataset_id, &buyer, &None);
+}
+
+#[test]
+fn test_dashboard_joins_studies_datasets_and_earnings() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+
+    // Arrange: the contributor's second study is in both datasets
+    register_study(&s, 1, &contributor);
+    register_study(&s, 2, &contributor);
+    register_study(&s, 3, &other);
+    let first = Bytes::from_slice(&s.env, b"dataset_001");
+    let second = Bytes::from_slice(&s.env, b"dataset_002");
+    s.marketplace.register_dataset(
+        &Address::generate(&s.env),
+        &first,
+        &vec![
+            &s.env,
+            Bytes::from_array(&s.env, &[1u8; 32]),
+            Bytes::from_array(&s.env, &[2u8; 32]),
+        ],
+        &PRICE,
+        &None,
+    );
+    s.marketplace.register_dataset(
+        &Address::generate(&s.env),
+        &second,
+        &vec![
+            &s.env,
+            Bytes::from_array(&s.env, &[2u8; 32]),
+            Bytes::from_array(&s.env, &[3u8; 32]),
+        ],
+        &PRICE,
+        &None,
+    );
+
+    // Act: each dataset is sold once
+    buy_dataset(&s, &first);
+    buy_dataset(&s, &second);
+    let dashboard = s.dashboard.get_dashboard(&contributor);
+
+    // Assert: the contributor is paid once per purchase
+    assert_eq!(dashboard.studies.len(), 2);
+    assert_eq!(dashboard.datasets, vec![&s.env, first, second]);
+    assert_eq!(dashboard.sales, 2);
+    assert_eq!(
+        dashboard.total_earned,
+        token::Client::new(&s.env, &s.usdc.address).balance(&contributor)
+    );
+    assert_eq!(dashboard.claimable, 0);
+}
+
+#[test]
+fn test_dashboard_of_unknown_contributor_is_empty() {
+    let s = setup();
+
+    // Act
+    let dashboard = s.dashboard.get_dashboard(&Address::generate(&s.env));
+
+    // Assert
+    assert!(dashboard.studies.is_empty());
+    assert!(dashboard.datasets.is_empty());
+    assert_eq!((dashboard.sales, dashboard.total_earned), (0, 0));
+}
+
+#[test]
+fn test_init_twice_fails() {
+    let s = setup();
+
+    // Act & Assert
+    assert_eq!(
+        s.dashboard.try_init(&Address::generate(&s.env), &s.targets),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(s.dashboard.get_targets(), s.targets);
+}
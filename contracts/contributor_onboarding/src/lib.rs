@@ -0,0 +1,235 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const VERIFIERS_KEY: Symbol = symbol_short!("VERIFIERS");
+const BINDING_KEY: Symbol = symbol_short!("BINDING");
+
+/// TTL policy for bindings, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum number of approved verifiers
+pub const MAX_VERIFIERS: u32 = 20;
+
+/// Identity binding of a contributor address
+///
+/// - verifier: Approved verifier that issued the binding
+/// - attestation_hash: Hash of the verifier's organization or email
+///   attestation; the attestation itself stays off-chain
+/// - bound_at / expires_at: Ledger timestamps the binding is valid between
+/// - revoked: Whether the verifier revoked the binding
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Binding {
+    pub verifier: Address,
+    pub attestation_hash: BytesN<32>,
+    pub bound_at: u64,
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotVerifier = 3,
+    VerifierAlreadyApproved = 4,
+    TooManyVerifiers = 5,
+    InvalidExpiry = 6,
+    BindingNotFound = 7,
+    BindingRevoked = 8,
+}
+
+/// Onboarding of contributors before they register studies or get paid
+///
+/// Approved verifiers bind a contributor address to an organization or
+/// email attestation. The StudyRegistry checks `is_onboarded` at
+/// registration and the RevenueSplitter before a contributor's first
+/// payout. A binding stops counting once it expires, is revoked, or its
+/// verifier is removed.
+#[contract]
+pub struct ContributorOnboarding;
+
+#[contractimpl]
+impl ContributorOnboarding {
+    /// Initialize the ContributorOnboarding contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Governance address allowed to approve verifiers
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&VERIFIERS_KEY, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    /// Approve a verifier to issue bindings
+    pub fn add_verifier(env: Env, verifier: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut verifiers = Self::get_verifiers(env.clone());
+        if verifiers.contains(&verifier) {
+            return Err(Error::VerifierAlreadyApproved);
+        }
+        if verifiers.len() >= MAX_VERIFIERS {
+            return Err(Error::TooManyVerifiers);
+        }
+        verifiers.push_back(verifier.clone());
+        env.storage().instance().set(&VERIFIERS_KEY, &verifiers);
+
+        env.events()
+            .publish((symbol_short!("Verifier"), verifier), true);
+        Ok(())
+    }
+
+    /// Remove a verifier; the bindings it issued stop counting
+    pub fn remove_verifier(env: Env, verifier: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut verifiers = Self::get_verifiers(env.clone());
+        let index = verifiers
+            .first_index_of(&verifier)
+            .ok_or(Error::NotVerifier)?;
+        verifiers.remove(index);
+        env.storage().instance().set(&VERIFIERS_KEY, &verifiers);
+
+        env.events()
+            .publish((symbol_short!("Verifier"), verifier), false);
+        Ok(())
+    }
+
+    /// Bind a contributor address to a verified attestation
+    ///
+    /// Replaces any earlier binding of the contributor, so verifiers also
+    /// renew bindings with it.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `verifier` - Approved verifier issuing the binding (must authorize)
+    /// * `contributor` - Address being onboarded (must authorize)
+    /// * `attestation_hash` - Hash of the organization or email attestation
+    /// * `expires_at` - Ledger timestamp the binding expires at
+    ///
+    /// # Returns
+    /// * `Ok(Binding)` if successful
+    /// * `Err(Error::NotVerifier)` if the verifier is not approved
+    /// * `Err(Error::InvalidExpiry)` if `expires_at` is not in the future
+    pub fn bind(
+        env: Env,
+        verifier: Address,
+        contributor: Address,
+        attestation_hash: BytesN<32>,
+        expires_at: u64,
+    ) -> Result<Binding, Error> {
+        verifier.require_auth();
+        contributor.require_auth();
+        if !Self::get_verifiers(env.clone()).contains(&verifier) {
+            return Err(Error::NotVerifier);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidExpiry);
+        }
+
+        let binding = Binding {
+            verifier: verifier.clone(),
+            attestation_hash,
+            bound_at: now,
+            expires_at,
+            revoked: false,
+        };
+        Self::save_binding(&env, &contributor, &binding);
+
+        env.events().publish(
+            (symbol_short!("Bound"), contributor),
+            (verifier, expires_at),
+        );
+        Ok(binding)
+    }
+
+    /// Revoke a contributor's binding
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor whose binding is revoked
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if there is no binding or it is already revoked
+    pub fn revoke(env: Env, contributor: Address) -> Result<(), Error> {
+        let mut binding = Self::get_binding(env.clone(), contributor.clone())?;
+        binding.verifier.require_auth();
+        if binding.revoked {
+            return Err(Error::BindingRevoked);
+        }
+        binding.revoked = true;
+        Self::save_binding(&env, &contributor, &binding);
+
+        env.events()
+            .publish((symbol_short!("Unbound"), contributor), binding.verifier);
+        Ok(())
+    }
+
+    /// Check whether a contributor is onboarded
+    ///
+    /// # Returns
+    /// * `true` if the contributor holds an unexpired, unrevoked binding
+    ///   from a currently approved verifier
+    pub fn is_onboarded(env: Env, contributor: Address) -> bool {
+        match Self::get_binding(env.clone(), contributor) {
+            Ok(binding) => {
+                !binding.revoked
+                    && env.ledger().timestamp() < binding.expires_at
+                    && Self::get_verifiers(env).contains(&binding.verifier)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Get a contributor's binding
+    pub fn get_binding(env: Env, contributor: Address) -> Result<Binding, Error> {
+        env.storage()
+            .persistent()
+            .get(&(BINDING_KEY, contributor))
+            .ok_or(Error::BindingNotFound)
+    }
+
+    /// Get the approved verifiers
+    pub fn get_verifiers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&VERIFIERS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn save_binding(env: &Env, contributor: &Address, binding: &Binding) {
+        let key = (BINDING_KEY, contributor.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, binding);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+}
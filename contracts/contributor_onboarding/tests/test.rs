@@ -0,0 +1,195 @@
+#![cfg(test)]
+
+use contributor_onboarding::{ContributorOnboarding, ContributorOnboardingClient, Error};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Payout amount used by the tests (10 USDC)
+const AMOUNT: i128 = 10_0000000;
+
+/// Binding expiry used by the tests
+const EXPIRES_AT: u64 = 1_000;
+
+struct Setup<'a> {
+    env: Env,
+    onboarding: ContributorOnboardingClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::Client<'a>,
+    verifier: Address,
+    payer: Address,
+}
+
+/// Helper: Deploy the registry and splitter checking a ContributorOnboarding
+/// with one approved verifier, and fund a payer
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let onboarding = ContributorOnboardingClient::new(
+        &env,
+        &env.register_contract(None, ContributorOnboarding),
+    );
+    onboarding.init(&Address::generate(&env));
+    let verifier = Address::generate(&env);
+    onboarding.add_verifier(&verifier);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    registry.set_onboarding(&onboarding.address);
+    splitter.set_onboarding(&onboarding.address);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&payer, &(10 * AMOUNT));
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        onboarding,
+        registry,
+        splitter,
+        verifier,
+        payer,
+    }
+}
+
+/// Helper: Bind a contributor through the test verifier
+fn bind(s: &Setup, contributor: &Address) {
+    s.onboarding.bind(
+        &s.verifier,
+        contributor,
+        &BytesN::from_array(&s.env, &[7u8; 32]),
+        &EXPIRES_AT,
+    );
+}
+
+/// Helper: Pay a purchase to a single contributor
+fn try_pay(s: &Setup, contributor: &Address) -> bool {
+    s.splitter
+        .try_payout_purchase(
+            &s.payer,
+            &Bytes::from_slice(&s.env, b"dataset_001"),
+            &vec![&s.env, contributor.clone()],
+            &AMOUNT,
+        )
+        .is_ok()
+}
+
+#[test]
+fn test_registration_requires_onboarding() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    let study_hash = BytesN::from_array(&s.env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &contributor);
+    let zk_proof = study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &contributor);
+    let register = || s.registry.try_register_study(&study_hash, &attestation, &study_registry::ProofKind::Groth16, &zk_proof, &contributor);
+
+    // Act & Assert
+    assert_eq!(register(), Err(Ok(study_registry::Error::NotOnboarded)));
+    bind(&s, &contributor);
+    assert!(s.onboarding.is_onboarded(&contributor));
+    assert_eq!(register(), Ok(Ok(())));
+}
+
+#[test]
+fn test_first_payout_requires_onboarding() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+
+    // Act & Assert: nothing is paid before onboarding
+    assert_eq!(
+        s.splitter.try_payout_purchase(
+            &s.payer,
+            &Bytes::from_slice(&s.env, b"dataset_001"),
+            &vec![&s.env, contributor.clone()],
+            &AMOUNT,
+        ),
+        Err(Ok(revenue_splitter::Error::NotOnboarded))
+    );
+    bind(&s, &contributor);
+    assert!(try_pay(&s, &contributor));
+    assert_eq!(s.usdc.balance(&contributor), AMOUNT * 85 / 100);
+
+    // Later payouts are not held back by the binding
+    s.onboarding.revoke(&contributor);
+    assert!(!s.onboarding.is_onboarded(&contributor));
+    assert!(try_pay(&s, &contributor));
+}
+
+#[test]
+fn test_binding_lapses_on_expiry_and_verifier_removal() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    bind(&s, &contributor);
+
+    // Act & Assert: bindings expire
+    s.env.ledger().set_timestamp(EXPIRES_AT);
+    assert!(!s.onboarding.is_onboarded(&contributor));
+    assert_eq!(
+        s.onboarding.try_bind(
+            &s.verifier,
+            &contributor,
+            &BytesN::from_array(&s.env, &[7u8; 32]),
+            &EXPIRES_AT,
+        ),
+        Err(Ok(Error::InvalidExpiry))
+    );
+
+    // Renewed bindings stop counting once their verifier is removed
+    s.onboarding.bind(
+        &s.verifier,
+        &contributor,
+        &BytesN::from_array(&s.env, &[7u8; 32]),
+        &(2 * EXPIRES_AT),
+    );
+    assert!(s.onboarding.is_onboarded(&contributor));
+    s.onboarding.remove_verifier(&s.verifier);
+    assert!(!s.onboarding.is_onboarded(&contributor));
+    assert_eq!(
+        s.onboarding.try_bind(
+            &s.verifier,
+            &Address::generate(&s.env),
+            &BytesN::from_array(&s.env, &[7u8; 32]),
+            &(2 * EXPIRES_AT),
+        ),
+        Err(Ok(Error::NotVerifier))
+    );
+    assert_eq!(s.onboarding.get_binding(&contributor).bound_at, EXPIRES_AT);
+}
+
+#[test]
+fn test_revoke_needs_a_live_binding() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        s.onboarding.try_revoke(&contributor),
+        Err(Ok(Error::BindingNotFound))
+    );
+    bind(&s, &contributor);
+    s.onboarding.revoke(&contributor);
+    assert!(s.onboarding.get_binding(&contributor).revoked);
+    assert_eq!(
+        s.onboarding.try_revoke(&contributor),
+        Err(Ok(Error::BindingRevoked))
+    );
+    assert_eq!(
+        s.onboarding.try_add_verifier(&s.verifier),
+        Err(Ok(Error::VerifierAlreadyApproved))
+    );
+}
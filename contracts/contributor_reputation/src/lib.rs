@@ -0,0 +1,374 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const REPORTER_KEY: Symbol = symbol_short!("REPORTER");
+const REPUTATION_KEY: Symbol = symbol_short!("REPUT");
+
+/// Points awarded for each study verified by the StudyRegistry
+pub const STUDY_POINTS: u32 = 10;
+
+/// Points awarded each time a dataset containing the contributor's study is sold
+pub const SALE_POINTS: u32 = 2;
+
+/// Points awarded when a dispute is resolved in the contributor's favour
+pub const DISPUTE_WON_POINTS: u32 = 5;
+
+/// Points deducted when a dispute is resolved against the contributor
+pub const DISPUTE_LOST_PENALTY: u32 = 20;
+
+/// Points deducted for each revoked study
+pub const REVOCATION_PENALTY: u32 = 30;
+
+/// Minimum score for each reputation tier (index = tier)
+///
+/// Tier 0 is the default for every contributor. The marketplace uses tiers
+/// for badging and the splitter uses them for tiered shares.
+pub const TIER_THRESHOLDS: [u32; 4] = [0, 50, 200, 1000];
+
+/// Reputation record
+///
+/// Stores the raw counters the score is derived from:
+/// - verified_studies: Studies registered and verified in StudyRegistry
+/// - sales: Sales of datasets containing the contributor's studies
+/// - disputes_won / disputes_lost: Outcomes of resolved disputes
+/// - revocations: Studies revoked after registration
+/// - score: Derived score (never negative)
+/// - updated_at: Ledger timestamp of the last update
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reputation {
+    pub contributor: Address,
+    pub verified_studies: u32,
+    pub sales: u32,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    pub revocations: u32,
+    pub score: u32,
+    pub updated_at: u64,
+}
+
+/// Kind of reputation update, published in ReputationUpdated events
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReputationEvent {
+    StudyVerified,
+    Sale,
+    DisputeWon,
+    DisputeLost,
+    Revocation,
+}
+
+/// Event data for ReputationUpdated event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationUpdated {
+    pub contributor: Address,
+    pub kind: ReputationEvent,
+    pub score: u32,
+    pub tier: u32,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedReporter = 3,
+}
+
+#[contract]
+pub struct ContributorReputation;
+
+#[contractimpl]
+impl ContributorReputation {
+    /// Initialize the ContributorReputation contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage reporters
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Allow a contract (marketplace, splitter, registry, arbitration) to
+    /// report reputation events
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Address of the reporting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn add_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&(REPORTER_KEY, reporter), &true);
+        Ok(())
+    }
+
+    /// Revoke a reporter's permission to report reputation events
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Address of the reporting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn remove_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(REPORTER_KEY, reporter));
+        Ok(())
+    }
+
+    /// Check whether an address is an authorized reporter
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        env.storage().instance().has(&(REPORTER_KEY, reporter))
+    }
+
+    /// Record a study verified by the StudyRegistry
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `contributor` - Contributor whose study was verified
+    ///
+    /// # Returns
+    /// * `Ok(Reputation)` with the updated record
+    /// * `Err(Error::UnauthorizedReporter)` if reporter is not allowed
+    pub fn record_study_verified(
+        env: Env,
+        reporter: Address,
+        contributor: Address,
+    ) -> Result<Reputation, Error> {
+        Self::apply(&env, &reporter, contributor, ReputationEvent::StudyVerified)
+    }
+
+    /// Record a sale of a dataset containing one of the contributor's studies
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `contributor` - Contributor whose study was sold
+    ///
+    /// # Returns
+    /// * `Ok(Reputation)` with the updated record
+    /// * `Err(Error::UnauthorizedReporter)` if reporter is not allowed
+    pub fn record_sale(
+        env: Env,
+        reporter: Address,
+        contributor: Address,
+    ) -> Result<Reputation, Error> {
+        Self::apply(&env, &reporter, contributor, ReputationEvent::Sale)
+    }
+
+    /// Record the outcome of a resolved dispute
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `contributor` - Contributor the dispute was raised against
+    /// * `upheld` - `true` if the dispute was upheld against the contributor
+    ///
+    /// # Returns
+    /// * `Ok(Reputation)` with the updated record
+    /// * `Err(Error::UnauthorizedReporter)` if reporter is not allowed
+    pub fn record_dispute(
+        env: Env,
+        reporter: Address,
+        contributor: Address,
+        upheld: bool,
+    ) -> Result<Reputation, Error> {
+        let kind = if upheld {
+            ReputationEvent::DisputeLost
+        } else {
+            ReputationEvent::DisputeWon
+        };
+        Self::apply(&env, &reporter, contributor, kind)
+    }
+
+    /// Record a revoked study
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `contributor` - Contributor whose study was revoked
+    ///
+    /// # Returns
+    /// * `Ok(Reputation)` with the updated record
+    /// * `Err(Error::UnauthorizedReporter)` if reporter is not allowed
+    pub fn record_revocation(
+        env: Env,
+        reporter: Address,
+        contributor: Address,
+    ) -> Result<Reputation, Error> {
+        Self::apply(&env, &reporter, contributor, ReputationEvent::Revocation)
+    }
+
+    /// Get the reputation record for a contributor
+    ///
+    /// Contributors with no recorded activity get an all-zero record, so
+    /// callers never have to handle a missing entry.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor address
+    ///
+    /// # Returns
+    /// * `Reputation` record
+    pub fn get_reputation(env: Env, contributor: Address) -> Reputation {
+        Self::load(&env, contributor)
+    }
+
+    /// Get the reputation tier for a contributor (see `TIER_THRESHOLDS`)
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor address
+    ///
+    /// # Returns
+    /// * Tier index, 0 being the lowest
+    pub fn get_tier(env: Env, contributor: Address) -> u32 {
+        Self::tier_for(Self::load(&env, contributor).score)
+    }
+
+    /// Check whether a contributor meets a minimum reputation score
+    ///
+    /// Used by the marketplace for minimum-reputation listings.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor address
+    /// * `min_score` - Required score
+    ///
+    /// # Returns
+    /// * `true` if the contributor's score is at least `min_score`
+    pub fn meets_minimum(env: Env, contributor: Address, min_score: u32) -> bool {
+        Self::load(&env, contributor).score >= min_score
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn load(env: &Env, contributor: Address) -> Reputation {
+        env.storage()
+            .persistent()
+            .get(&(REPUTATION_KEY, contributor.clone()))
+            .unwrap_or(Reputation {
+                contributor,
+                verified_studies: 0,
+                sales: 0,
+                disputes_won: 0,
+                disputes_lost: 0,
+                revocations: 0,
+                score: 0,
+                updated_at: 0,
+            })
+    }
+
+    /// Apply a reputation event and persist the recomputed record
+    fn apply(
+        env: &Env,
+        reporter: &Address,
+        contributor: Address,
+        kind: ReputationEvent,
+    ) -> Result<Reputation, Error> {
+        // ============================================
+        // 1. AUTHENTICATE REPORTER
+        // ============================================
+        reporter.require_auth();
+        if !env
+            .storage()
+            .instance()
+            .has(&(REPORTER_KEY, reporter.clone()))
+        {
+            return Err(Error::UnauthorizedReporter);
+        }
+
+        // ============================================
+        // 2. UPDATE COUNTERS
+        // ============================================
+        let mut reputation = Self::load(env, contributor.clone());
+        match kind {
+            ReputationEvent::StudyVerified => {
+                reputation.verified_studies = reputation.verified_studies.saturating_add(1)
+            }
+            ReputationEvent::Sale => reputation.sales = reputation.sales.saturating_add(1),
+            ReputationEvent::DisputeWon => {
+                reputation.disputes_won = reputation.disputes_won.saturating_add(1)
+            }
+            ReputationEvent::DisputeLost => {
+                reputation.disputes_lost = reputation.disputes_lost.saturating_add(1)
+            }
+            ReputationEvent::Revocation => {
+                reputation.revocations = reputation.revocations.saturating_add(1)
+            }
+        }
+
+        // ============================================
+        // 3. RECOMPUTE SCORE AND STORE
+        // ============================================
+        reputation.score = Self::compute_score(&reputation);
+        reputation.updated_at = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&(REPUTATION_KEY, contributor.clone()), &reputation);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("RepUpdate"), contributor.clone()),
+            ReputationUpdated {
+                contributor,
+                kind,
+                score: reputation.score,
+                tier: Self::tier_for(reputation.score),
+            },
+        );
+
+        Ok(reputation)
+    }
+
+    /// Derive the score from the counters, flooring at zero
+    fn compute_score(reputation: &Reputation) -> u32 {
+        let gained = (reputation.verified_studies as u64) * (STUDY_POINTS as u64)
+            + (reputation.sales as u64) * (SALE_POINTS as u64)
+            + (reputation.disputes_won as u64) * (DISPUTE_WON_POINTS as u64);
+        let lost = (reputation.disputes_lost as u64) * (DISPUTE_LOST_PENALTY as u64)
+            + (reputation.revocations as u64) * (REVOCATION_PENALTY as u64);
+        gained.saturating_sub(lost).min(u32::MAX as u64) as u32
+    }
+
+    fn tier_for(score: u32) -> u32 {
+        let mut tier = 0;
+        for (i, threshold) in TIER_THRESHOLDS.iter().enumerate() {
+            if score >= *threshold {
+                tier = i as u32;
+            }
+        }
+        tier
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(test)]
+
+use contributor_reputation::{
+    ContributorReputation, ContributorReputationClient, Error, SALE_POINTS, STUDY_POINTS,
+};
+use soroban_sdk::{testutils::Address as AddressTestUtils, Address, Env};
+
+/// Helper: Create a test environment
+fn create_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+/// Helper: Create a test address
+fn create_address(env: &Env) -> Address {
+    Address::generate(env)
+}
+
+/// Helper: Create an initialized ContributorReputation client with one reporter
+fn create_reputation_client(env: &Env) -> (ContributorReputationClient<'_>, Address) {
+    let contract_id = env.register_contract(None, ContributorReputation);
+    let client = ContributorReputationClient::new(env, &contract_id);
+
+    let admin = create_address(env);
+    let reporter = create_address(env);
+    client.init(&admin);
+    client.add_reporter(&reporter);
+
+    (client, reporter)
+}
+
+#[test]
+fn test_new_contributor_has_zero_reputation() {
+    let env = create_env();
+    let (client, _) = create_reputation_client(&env);
+
+    // Arrange
+    let contributor = create_address(&env);
+
+    // Act
+    let reputation = client.get_reputation(&contributor);
+
+    // Assert
+    assert_eq!(reputation.score, 0);
+    assert_eq!(reputation.verified_studies, 0);
+    assert_eq!(client.get_tier(&contributor), 0);
+}
+
+#[test]
+fn test_studies_and_sales_increase_score() {
+    let env = create_env();
+    let (client, reporter) = create_reputation_client(&env);
+
+    // Arrange
+    let contributor = create_address(&env);
+
+    // Act
+    client.record_study_verified(&reporter, &contributor);
+    client.record_sale(&reporter, &contributor);
+    let reputation = client.record_sale(&reporter, &contributor);
+
+    // Assert
+    assert_eq!(reputation.verified_studies, 1);
+    assert_eq!(reputation.sales, 2);
+    assert_eq!(reputation.score, STUDY_POINTS + 2 * SALE_POINTS);
+    assert_eq!(client.get_reputation(&contributor), reputation);
+}
+
+#[test]
+fn test_penalties_floor_at_zero() {
+    let env = create_env();
+    let (client, reporter) = create_reputation_client(&env);
+
+    // Arrange
+    let contributor = create_address(&env);
+    client.record_study_verified(&reporter, &contributor);
+
+    // Act: a lost dispute outweighs one verified study
+    let reputation = client.record_dispute(&reporter, &contributor, &true);
+
+    // Assert
+    assert_eq!(reputation.disputes_lost, 1);
+    assert_eq!(reputation.score, 0);
+
+    // Revocations are counted even when the score is already zero
+    let reputation = client.record_revocation(&reporter, &contributor);
+    assert_eq!(reputation.revocations, 1);
+    assert_eq!(reputation.score, 0);
+}
+
+#[test]
+fn test_tier_and_minimum_threshold() {
+    let env = create_env();
+    let (client, reporter) = create_reputation_client(&env);
+
+    // Arrange
+    let contributor = create_address(&env);
+
+    // Act: 5 verified studies = 50 points = tier 1
+    for _ in 0..5 {
+        client.record_study_verified(&reporter, &contributor);
+    }
+
+    // Assert
+    assert_eq!(client.get_tier(&contributor), 1);
+    assert!(client.meets_minimum(&contributor, &50));
+    assert!(!client.meets_minimum(&contributor, &51));
+}
+
+#[test]
+fn test_unknown_reporter_rejected() {
+    let env = create_env();
+    let (client, _) = create_reputation_client(&env);
+
+    // Arrange
+    let stranger = create_address(&env);
+    let contributor = create_address(&env);
+
+    // Act
+    let result = client.try_record_sale(&stranger, &contributor);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::UnauthorizedReporter)));
+    assert_eq!(client.get_reputation(&contributor).sales, 0);
+}
+
+#[test]
+fn test_removed_reporter_rejected() {
+    let env = create_env();
+    let (client, reporter) = create_reputation_client(&env);
+
+    // Arrange
+    let contributor = create_address(&env);
+    client.remove_reporter(&reporter);
+
+    // Act
+    let result = client.try_record_study_verified(&reporter, &contributor);
+
+    // Assert
+    assert!(!client.is_reporter(&reporter));
+    assert_eq!(result, Err(Ok(Error::UnauthorizedReporter)));
+}
+
+#[test]
+fn test_init_twice_fails() {
+    let env = create_env();
+    let (client, _) = create_reputation_client(&env);
+
+    // Act
+    let result = client.try_init(&create_address(&env));
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+}
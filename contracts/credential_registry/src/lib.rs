@@ -0,0 +1,260 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec,
+};
+
+/// TTL policy for credentials, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum number of approved verifiers
+pub const MAX_VERIFIERS: u32 = 20;
+
+/// Storage keys
+///
+/// - Credential: Credentials by holder and type, in persistent storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Verifiers,
+    Credential(Address, Symbol),
+}
+
+/// Credential attesting what an address belongs to
+///
+/// - credential_type: Kind of credential (e.g. a verified research
+///   institution or an IRB-approved lab)
+/// - verifier: Approved verifier that issued the credential
+/// - attestation_hash: Hash of the verifier's evidence; the evidence
+///   itself stays off-chain
+/// - issued_at / expires_at: Ledger timestamps the credential is valid
+///   between
+/// - revoked: Whether the verifier revoked the credential
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Credential {
+    pub credential_type: Symbol,
+    pub verifier: Address,
+    pub attestation_hash: BytesN<32>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotVerifier = 3,
+    VerifierAlreadyApproved = 4,
+    TooManyVerifiers = 5,
+    InvalidExpiry = 6,
+    CredentialNotFound = 7,
+    CredentialRevoked = 8,
+}
+
+/// Registry of researcher credentials
+///
+/// Approved verifiers attest that an address belongs to a verified
+/// research institution, or holds another credential type, until an
+/// expiry. The DatasetMarketplace checks `is_verified` before selling a
+/// credential-gated dataset. A credential stops counting once it expires,
+/// is revoked, or its verifier is removed.
+#[contract]
+pub struct CredentialRegistry;
+
+#[contractimpl]
+impl CredentialRegistry {
+    /// Initialize the CredentialRegistry contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Governance address allowed to approve verifiers
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::Verifiers, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    /// Approve a verifier to issue credentials
+    pub fn add_verifier(env: Env, verifier: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut verifiers = Self::get_verifiers(env.clone());
+        if verifiers.contains(&verifier) {
+            return Err(Error::VerifierAlreadyApproved);
+        }
+        if verifiers.len() >= MAX_VERIFIERS {
+            return Err(Error::TooManyVerifiers);
+        }
+        verifiers.push_back(verifier.clone());
+        env.storage().instance().set(&DataKey::Verifiers, &verifiers);
+
+        env.events()
+            .publish((symbol_short!("Verifier"), verifier), true);
+        Ok(())
+    }
+
+    /// Remove a verifier; the credentials it issued stop counting
+    pub fn remove_verifier(env: Env, verifier: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut verifiers = Self::get_verifiers(env.clone());
+        let index = verifiers
+            .first_index_of(&verifier)
+            .ok_or(Error::NotVerifier)?;
+        verifiers.remove(index);
+        env.storage().instance().set(&DataKey::Verifiers, &verifiers);
+
+        env.events()
+            .publish((symbol_short!("Verifier"), verifier), false);
+        Ok(())
+    }
+
+    /// Issue a credential to an address
+    ///
+    /// Replaces any earlier credential of the same type, so verifiers also
+    /// renew credentials with it.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `verifier` - Approved verifier issuing the credential (must
+    ///   authorize)
+    /// * `holder` - Address receiving the credential (must authorize)
+    /// * `credential_type` - Kind of credential
+    /// * `attestation_hash` - Hash of the verifier's evidence
+    /// * `expires_at` - Ledger timestamp the credential expires at
+    ///
+    /// # Returns
+    /// * `Ok(Credential)` if successful
+    /// * `Err(Error::NotVerifier)` if the verifier is not approved
+    /// * `Err(Error::InvalidExpiry)` if `expires_at` is not in the future
+    pub fn issue(
+        env: Env,
+        verifier: Address,
+        holder: Address,
+        credential_type: Symbol,
+        attestation_hash: BytesN<32>,
+        expires_at: u64,
+    ) -> Result<Credential, Error> {
+        verifier.require_auth();
+        holder.require_auth();
+        if !Self::get_verifiers(env.clone()).contains(&verifier) {
+            return Err(Error::NotVerifier);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidExpiry);
+        }
+
+        let credential = Credential {
+            credential_type: credential_type.clone(),
+            verifier: verifier.clone(),
+            attestation_hash,
+            issued_at: now,
+            expires_at,
+            revoked: false,
+        };
+        Self::save_credential(&env, &holder, &credential);
+
+        env.events().publish(
+            (symbol_short!("Issued"), holder, credential_type),
+            (verifier, expires_at),
+        );
+        Ok(credential)
+    }
+
+    /// Revoke a holder's credential
+    ///
+    /// Requires the authorization of the verifier that issued it.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `holder` - Address whose credential is revoked
+    /// * `credential_type` - Kind of credential
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if there is no such credential or it is already
+    ///   revoked
+    pub fn revoke(env: Env, holder: Address, credential_type: Symbol) -> Result<(), Error> {
+        let mut credential = Self::get_credential(env.clone(), holder.clone(), credential_type.clone())?;
+        credential.verifier.require_auth();
+        if credential.revoked {
+            return Err(Error::CredentialRevoked);
+        }
+        credential.revoked = true;
+        Self::save_credential(&env, &holder, &credential);
+
+        env.events().publish(
+            (symbol_short!("Revoked"), holder, credential_type),
+            credential.verifier,
+        );
+        Ok(())
+    }
+
+    /// Check whether an address holds a credential
+    ///
+    /// # Returns
+    /// * `true` if the address holds an unexpired, unrevoked credential of
+    ///   the type from a currently approved verifier
+    pub fn is_verified(env: Env, address: Address, credential_type: Symbol) -> bool {
+        match Self::get_credential(env.clone(), address, credential_type) {
+            Ok(credential) => {
+                !credential.revoked
+                    && env.ledger().timestamp() < credential.expires_at
+                    && Self::get_verifiers(env).contains(&credential.verifier)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Get a holder's credential of a type
+    pub fn get_credential(
+        env: Env,
+        holder: Address,
+        credential_type: Symbol,
+    ) -> Result<Credential, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Credential(holder, credential_type))
+            .ok_or(Error::CredentialNotFound)
+    }
+
+    /// Get the approved verifiers
+    pub fn get_verifiers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Verifiers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn save_credential(env: &Env, holder: &Address, credential: &Credential) {
+        let key = DataKey::Credential(holder.clone(), credential.credential_type.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, credential);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+#![cfg(test)]
+
+use credential_registry::{CredentialRegistry, CredentialRegistryClient, Error};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env, InvokeError, Symbol,
+};
+
+/// Price of the gated dataset (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Credential expiry used by the tests
+const EXPIRES_AT: u64 = 1_000;
+
+struct Setup<'a> {
+    env: Env,
+    credentials: CredentialRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    verifier: Address,
+    dataset_id: Bytes,
+}
+
+/// Helper: Credential type the gated dataset requires
+fn institution() -> Symbol {
+    symbol_short!("inst")
+}
+
+/// Helper: Deploy the core contracts with a CredentialRegistry holding one
+/// approved verifier, and list a dataset only verified institutions can
+/// purchase
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let credentials = CredentialRegistryClient::new(
+        &env,
+        &env.register_contract(None, CredentialRegistry),
+    );
+    credentials.init(&Address::generate(&env));
+    let verifier = Address::generate(&env);
+    credentials.add_verifier(&verifier);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+    marketplace.set_credential_registry(&credentials.address);
+
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from_slice(&env, &[1u8; 32])],
+        &PRICE,
+        &None,
+    );
+    marketplace.set_access_policy(
+        &dataset_id,
+        &dataset_marketplace::AccessPolicy::CredentialGated(institution()),
+    );
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        credentials,
+        marketplace,
+        verifier,
+        dataset_id,
+    }
+}
+
+/// Helper: Issue the institution credential through the test verifier
+fn issue(s: &Setup, holder: &Address, expires_at: u64) {
+    s.credentials.issue(
+        &s.verifier,
+        holder,
+        &institution(),
+        &BytesN::from_array(&s.env, &[7u8; 32]),
+        &expires_at,
+    );
+}
+
+/// Helper: A researcher holding enough USDC for two purchases
+fn funded_buyer(s: &Setup) -> Address {
+    let buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.usdc.address).mint(&buyer, &(2 * PRICE));
+    s.usdc.approve(
+        &buyer,
+        &s.marketplace.address,
+        &(2 * PRICE),
+        &(s.env.ledger().sequence() + 100),
+    );
+    buyer
+}
+
+#[test]
+fn test_gated_purchase_requires_a_live_credential() {
+    let s = setup();
+    let buyer = funded_buyer(&s);
+    let not_eligible = Err(Err(InvokeError::Contract(
+        dataset_marketplace::ExtError::BuyerNotEligible as u32,
+    )));
+
+    // Act & Assert: only verified institutions can purchase
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&s.dataset_id, &buyer, &None),
+        not_eligible
+    );
+    issue(&s, &buyer, EXPIRES_AT);
+    assert!(s.credentials.is_verified(&buyer, &institution()));
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+    assert_eq!(s.usdc.balance(&buyer), PRICE);
+
+    // A revoked credential no longer qualifies
+    s.credentials.revoke(&buyer, &institution());
+    assert!(!s.credentials.is_verified(&buyer, &institution()));
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&s.dataset_id, &buyer, &None),
+        not_eligible
+    );
+}
+
+#[test]
+fn test_credentials_lapse_on_expiry_and_verifier_removal() {
+    let s = setup();
+    let holder = Address::generate(&s.env);
+    issue(&s, &holder, EXPIRES_AT);
+
+    // Act & Assert: credentials expire and only count for their type
+    assert!(!s.credentials.is_verified(&holder, &symbol_short!("irb")));
+    s.env.ledger().set_timestamp(EXPIRES_AT);
+    assert!(!s.credentials.is_verified(&holder, &institution()));
+    assert_eq!(
+        s.credentials.try_issue(
+            &s.verifier,
+            &holder,
+            &institution(),
+            &BytesN::from_array(&s.env, &[7u8; 32]),
+            &EXPIRES_AT,
+        ),
+        Err(Ok(Error::InvalidExpiry))
+    );
+
+    // Renewed credentials stop counting once their verifier is removed
+    issue(&s, &holder, 2 * EXPIRES_AT);
+    assert!(s.credentials.is_verified(&holder, &institution()));
+    s.credentials.remove_verifier(&s.verifier);
+    assert!(!s.credentials.is_verified(&holder, &institution()));
+    assert_eq!(
+        s.credentials.try_issue(
+            &s.verifier,
+            &Address::generate(&s.env),
+            &institution(),
+            &BytesN::from_array(&s.env, &[7u8; 32]),
+            &(2 * EXPIRES_AT),
+        ),
+        Err(Ok(Error::NotVerifier))
+    );
+    assert_eq!(
+        s.credentials.get_credential(&holder, &institution()).issued_at,
+        EXPIRES_AT
+    );
+}
+
+#[test]
+fn test_revoke_needs_a_live_credential() {
+    let s = setup();
+    let holder = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        s.credentials.try_revoke(&holder, &institution()),
+        Err(Ok(Error::CredentialNotFound))
+    );
+    issue(&s, &holder, EXPIRES_AT);
+    s.credentials.revoke(&holder, &institution());
+    assert!(s.credentials.get_credential(&holder, &institution()).revoked);
+    assert_eq!(
+        s.credentials.try_revoke(&holder, &institution()),
+        Err(Ok(Error::CredentialRevoked))
+    );
+    assert_eq!(
+        s.credentials.try_add_verifier(&s.verifier),
+        Err(Ok(Error::VerifierAlreadyApproved))
+    );
+    assert_eq!(
+        s.credentials.try_init(&Address::generate(&s.env)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
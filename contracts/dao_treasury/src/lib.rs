@@ -0,0 +1,867 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+    IntoVal, Symbol,
+};
+
+/// Storage keys
+const GOVERNANCE_KEY: Symbol = symbol_short!("GOV");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const TIMELOCK_KEY: Symbol = symbol_short!("TIMELOCK");
+const LEDGER_KEY: Symbol = symbol_short!("LEDGER");
+const BUDGET_KEY: Symbol = symbol_short!("BUDGET");
+const PROPOSAL_COUNT_KEY: Symbol = symbol_short!("PROP_CNT");
+const PROPOSAL_KEY: Symbol = symbol_short!("PROPOSAL");
+const STREAM_COUNT_KEY: Symbol = symbol_short!("STRM_CNT");
+const STREAM_KEY: Symbol = symbol_short!("STREAM");
+const HOLDING_KEY: Symbol = symbol_short!("HOLDING");
+const ADAPTER_KEY: Symbol = symbol_short!("ADAPTER");
+const REBALANCE_LIMIT_KEY: Symbol = symbol_short!("REBAL_LIM");
+const REBALANCE_USED_KEY: Symbol = symbol_short!("REBAL_USE");
+const KEEPER_KEY: Symbol = symbol_short!("KEEPER");
+
+/// Basis points in 100%
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Length of a rebalance period in seconds (one day)
+///
+/// A token's rebalance limit caps what all `rebalance` calls may sell
+/// within one period.
+pub const REBALANCE_PERIOD: u64 = 86_400;
+
+/// Source label for inflows picked up by `sync_inflows`
+///
+/// RevenueSplitter pays the platform share with a plain token transfer, so
+/// unaccounted balance is attributed to it.
+pub const SPLITTER_SOURCE: Symbol = symbol_short!("splitter");
+
+/// Treasury-wide accounting
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryLedger {
+    pub total_inflows: i128,
+    pub splitter_inflows: i128,
+    pub total_outflows: i128,
+    pub reserved_for_streams: i128,
+}
+
+/// Accounting for a settlement token other than USDC
+///
+/// The splitter pays the platform share in the token each sale settled
+/// in, so the treasury can hold several stablecoins. Spending is in USDC;
+/// other tokens are rebalanced into it.
+/// - inflows: Amount of the token received (booked by `sync_token_inflows`)
+/// - swapped_out: Amount of the token sold by `rebalance`
+/// - usdc_received: USDC obtained from those sales
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenHolding {
+    pub token: Address,
+    pub inflows: i128,
+    pub swapped_out: i128,
+    pub usdc_received: i128,
+}
+
+/// AMM adapter and price oracle used by `rebalance`
+///
+/// The adapter must expose:
+/// - `swap(from, token_in, token_out, amount_in, min_out) -> i128`: pull
+///   `amount_in` from `from` (allowance granted by the treasury) and send
+///   at least `min_out` of `token_out` back to it
+///
+/// The oracle, a separate contract so the venue cannot price its own
+/// trades, must expose:
+/// - `quote(token_in, token_out, amount_in) -> i128`: value of `amount_in`
+///   in `token_out`
+///
+/// - max_slippage_bps: Largest shortfall from the oracle quote a swap may have
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapAdapter {
+    pub adapter: Address,
+    pub oracle: Address,
+    pub max_slippage_bps: u32,
+}
+
+/// Budget category
+///
+/// - limit: Maximum that can be committed from the category
+/// - committed: Amount committed by executed proposals (paid or streaming)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Budget {
+    pub category: Symbol,
+    pub limit: i128,
+    pub committed: i128,
+}
+
+/// How an approved spend is disbursed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Disbursement {
+    /// Single transfer on execution
+    OneOff,
+    /// Linear stream between two ledger timestamps
+    Stream(u64, u64),
+}
+
+/// Proposal lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+/// Spending proposal
+///
+/// Proposals are queued by governance and become executable once the
+/// timelock has elapsed (`eta`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub category: Symbol,
+    pub recipient: Address,
+    pub amount: i128,
+    pub disbursement: Disbursement,
+    pub description_hash: BytesN<32>,
+    pub eta: u64,
+    pub status: ProposalStatus,
+}
+
+/// Streaming disbursement to a service provider
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub stream_id: u64,
+    pub proposal_id: u64,
+    pub recipient: Address,
+    pub total: i128,
+    pub withdrawn: i128,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    BudgetNotFound = 4,
+    BudgetExceeded = 5,
+    ProposalNotFound = 6,
+    ProposalNotQueued = 7,
+    TimelockNotElapsed = 8,
+    InsufficientFunds = 9,
+    InvalidStream = 10,
+    StreamNotFound = 11,
+    NothingToWithdraw = 12,
+    InvalidToken = 13,
+    AdapterNotSet = 14,
+    RebalanceNotAllowed = 15,
+    RebalanceLimitExceeded = 16,
+    SlippageExceeded = 17,
+    Unauthorized = 18,
+    InvalidOracle = 19,
+    Overflow = 20,
+}
+
+#[contract]
+pub struct DaoTreasury;
+
+#[contractimpl]
+impl DaoTreasury {
+    /// Initialize the DaoTreasury contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `governance` - Governance contract / multisig controlling spending
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `timelock` - Delay in seconds between queueing and executing a proposal
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        governance: Address,
+        usdc_token: Address,
+        timelock: u64,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&GOVERNANCE_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&GOVERNANCE_KEY, &governance);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&TIMELOCK_KEY, &timelock);
+        storage.set(&PROPOSAL_COUNT_KEY, &0u64);
+        storage.set(&STREAM_COUNT_KEY, &0u64);
+        storage.set(
+            &LEDGER_KEY,
+            &TreasuryLedger {
+                total_inflows: 0,
+                splitter_inflows: 0,
+                total_outflows: 0,
+                reserved_for_streams: 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Create or update a budget category
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `category` - Category name (e.g. "infra", "audits", "grants")
+    /// * `limit` - Maximum amount that can be committed from the category
+    ///
+    /// # Returns
+    /// * `Ok(Budget)` with the updated category
+    /// * `Err(Error::InvalidAmount)` if the limit is below what is committed
+    pub fn set_budget(env: Env, category: Symbol, limit: i128) -> Result<Budget, Error> {
+        Self::require_governance(&env)?;
+
+        let key = (BUDGET_KEY, category.clone());
+        let mut budget = env.storage().persistent().get(&key).unwrap_or(Budget {
+            category,
+            limit: 0,
+            committed: 0,
+        });
+        if limit < budget.committed {
+            return Err(Error::InvalidAmount);
+        }
+        budget.limit = limit;
+        env.storage().persistent().set(&key, &budget);
+        Ok(budget)
+    }
+
+    /// Deposit USDC into the treasury with explicit accounting
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `from` - Depositor (must authorize the transfer)
+    /// * `amount` - USDC amount
+    ///
+    /// # Returns
+    /// * `Ok(TreasuryLedger)` with updated totals
+    /// * `Err(Error::InvalidAmount)` if amount is not positive
+    pub fn deposit(env: Env, from: Address, amount: i128) -> Result<TreasuryLedger, Error> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::token_client(&env)?.transfer(&from, &env.current_contract_address(), &amount);
+
+        let mut ledger = Self::get_ledger(env.clone())?;
+        ledger.total_inflows += amount;
+        Self::save_ledger(&env, &ledger);
+
+        env.events()
+            .publish((symbol_short!("Inflow"), from), amount);
+
+        Ok(ledger)
+    }
+
+    /// Account for platform-share transfers received from RevenueSplitter
+    ///
+    /// Any USDC balance not explained by recorded inflows and outflows is
+    /// booked as a splitter inflow. Callable by anyone.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the newly accounted amount
+    pub fn sync_inflows(env: Env) -> Result<i128, Error> {
+        let balance = Self::token_client(&env)?.balance(&env.current_contract_address());
+        let mut ledger = Self::get_ledger(env.clone())?;
+
+        let unaccounted = balance - (ledger.total_inflows - ledger.total_outflows);
+        if unaccounted > 0 {
+            ledger.total_inflows += unaccounted;
+            ledger.splitter_inflows += unaccounted;
+            Self::save_ledger(&env, &ledger);
+
+            env.events()
+                .publish((symbol_short!("Inflow"), SPLITTER_SOURCE), unaccounted);
+        }
+
+        Ok(unaccounted.max(0))
+    }
+
+    /// Account for platform-share transfers received in another token
+    ///
+    /// Like `sync_inflows`, books any balance of `token` not explained by
+    /// recorded inflows and swaps. Callable by anyone.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Settlement token other than USDC
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the newly accounted amount
+    /// * `Err(Error::InvalidToken)` for USDC, which `sync_inflows` handles
+    pub fn sync_token_inflows(env: Env, token: Address) -> Result<i128, Error> {
+        if token == Self::usdc_token(&env)? {
+            return Err(Error::InvalidToken);
+        }
+        let mut holding = Self::get_token_holding(env.clone(), token.clone());
+        let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+
+        let unaccounted = balance - (holding.inflows - holding.swapped_out);
+        if unaccounted > 0 {
+            holding.inflows += unaccounted;
+            env.storage()
+                .persistent()
+                .set(&(HOLDING_KEY, token.clone()), &holding);
+
+            env.events()
+                .publish((symbol_short!("Inflow"), SPLITTER_SOURCE, token), unaccounted);
+        }
+
+        Ok(unaccounted.max(0))
+    }
+
+    /// Configure the AMM adapter and price oracle used by `rebalance`
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `adapter` - Adapter contract (see `SwapAdapter`)
+    /// * `oracle` - Oracle contract pricing the swaps (see `SwapAdapter`)
+    /// * `max_slippage_bps` - Largest shortfall from the oracle quote accepted
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidAmount)` if the slippage exceeds 100%
+    /// * `Err(Error::InvalidOracle)` if the oracle is the adapter itself
+    pub fn set_swap_adapter(
+        env: Env,
+        adapter: Address,
+        oracle: Address,
+        max_slippage_bps: u32,
+    ) -> Result<(), Error> {
+        Self::require_governance(&env)?;
+        if i128::from(max_slippage_bps) > BPS_DENOMINATOR {
+            return Err(Error::InvalidAmount);
+        }
+        if oracle == adapter {
+            return Err(Error::InvalidOracle);
+        }
+        env.storage().instance().set(
+            &ADAPTER_KEY,
+            &SwapAdapter {
+                adapter,
+                oracle,
+                max_slippage_bps,
+            },
+        );
+        Ok(())
+    }
+
+    /// Allow or revoke a keeper that may call `rebalance`
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `keeper` - Keeper address
+    /// * `allowed` - Whether the keeper may rebalance
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    pub fn set_keeper(env: Env, keeper: Address, allowed: bool) -> Result<(), Error> {
+        Self::require_governance(&env)?;
+        let key = (KEEPER_KEY, keeper);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Check whether an address is a registered keeper
+    pub fn is_keeper(env: Env, keeper: Address) -> bool {
+        env.storage().persistent().has(&(KEEPER_KEY, keeper))
+    }
+
+    /// Set the largest amount of a token rebalancing may sell per period
+    ///
+    /// The limit is cumulative: all `rebalance` calls within one
+    /// REBALANCE_PERIOD share it. Tokens without a limit cannot be
+    /// rebalanced; a limit of 0 disables rebalancing again.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Settlement token other than USDC
+    /// * `max_amount_in` - Largest amount sold per REBALANCE_PERIOD
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` for USDC or a negative limit
+    pub fn set_rebalance_limit(env: Env, token: Address, max_amount_in: i128) -> Result<(), Error> {
+        Self::require_governance(&env)?;
+        if token == Self::usdc_token(&env)? {
+            return Err(Error::InvalidToken);
+        }
+        if max_amount_in < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .persistent()
+            .set(&(REBALANCE_LIMIT_KEY, token), &max_amount_in);
+        Ok(())
+    }
+
+    /// Swap part of a token holding into USDC through the adapter
+    ///
+    /// Callable by governance or a registered keeper (see `set_keeper`).
+    /// Governance bounds the sales with the token's rebalance limit, shared
+    /// by all calls in a REBALANCE_PERIOD, and each swap with the maximum
+    /// slippage from the oracle's quote. The USDC received is booked as an
+    /// inflow and can be spent by proposals.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - Governance or a keeper (must authorize the call)
+    /// * `token` - Settlement token to sell
+    /// * `amount_in` - Amount of the token to sell
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the USDC received
+    /// * `Err(Error::Unauthorized)` if the caller is neither governance nor
+    ///   a keeper
+    /// * `Err(Error::Overflow)` if the oracle's quote or the booked totals
+    ///   overflow
+    /// * `Err(Error)` if the swap is outside the limits or falls short
+    pub fn rebalance(
+        env: Env,
+        caller: Address,
+        token: Address,
+        amount_in: i128,
+    ) -> Result<i128, Error> {
+        // ============================================
+        // 1. VALIDATE AGAINST LIMITS
+        // ============================================
+        Self::require_rebalancer(&env, &caller)?;
+        let usdc_token = Self::usdc_token(&env)?;
+        if token == usdc_token {
+            return Err(Error::InvalidToken);
+        }
+        let max_amount_in: i128 = env
+            .storage()
+            .persistent()
+            .get(&(REBALANCE_LIMIT_KEY, token.clone()))
+            .unwrap_or(0);
+        if max_amount_in == 0 {
+            return Err(Error::RebalanceNotAllowed);
+        }
+        if amount_in <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let period = env.ledger().timestamp() / REBALANCE_PERIOD;
+        let sold = Self::rebalanced_in_period(env.clone(), token.clone());
+        if amount_in > max_amount_in - sold {
+            return Err(Error::RebalanceLimitExceeded);
+        }
+
+        Self::sync_token_inflows(env.clone(), token.clone())?;
+        let mut holding = Self::get_token_holding(env.clone(), token.clone());
+        if amount_in > holding.inflows - holding.swapped_out {
+            return Err(Error::InsufficientFunds);
+        }
+
+        let swap: SwapAdapter = env
+            .storage()
+            .instance()
+            .get(&ADAPTER_KEY)
+            .ok_or(Error::AdapterNotSet)?;
+
+        // ============================================
+        // 2. SWAP THROUGH THE ADAPTER
+        // ============================================
+        let quote: i128 = env.invoke_contract(
+            &swap.oracle,
+            &symbol_short!("quote"),
+            (token.clone(), usdc_token.clone(), amount_in).into_val(&env),
+        );
+        let min_out = quote
+            .checked_mul(BPS_DENOMINATOR - i128::from(swap.max_slippage_bps))
+            .ok_or(Error::Overflow)?
+            / BPS_DENOMINATOR;
+
+        // Measure what arrived rather than trusting the adapter's answer
+        let treasury = env.current_contract_address();
+        let usdc = token::Client::new(&env, &usdc_token);
+        let before = usdc.balance(&treasury);
+        token::Client::new(&env, &token).approve(
+            &treasury,
+            &swap.adapter,
+            &amount_in,
+            &env.ledger().sequence(),
+        );
+        let _: i128 = env.invoke_contract(
+            &swap.adapter,
+            &symbol_short!("swap"),
+            (treasury.clone(), token.clone(), usdc_token, amount_in, min_out).into_val(&env),
+        );
+        let received = usdc.balance(&treasury) - before;
+        if received < min_out {
+            return Err(Error::SlippageExceeded);
+        }
+
+        // ============================================
+        // 3. UPDATE ACCOUNTING
+        // ============================================
+        holding.swapped_out = holding
+            .swapped_out
+            .checked_add(amount_in)
+            .ok_or(Error::Overflow)?;
+        holding.usdc_received = holding
+            .usdc_received
+            .checked_add(received)
+            .ok_or(Error::Overflow)?;
+        let sold = sold.checked_add(amount_in).ok_or(Error::Overflow)?;
+        let mut ledger = Self::get_ledger(env.clone())?;
+        ledger.total_inflows = ledger
+            .total_inflows
+            .checked_add(received)
+            .ok_or(Error::Overflow)?;
+
+        env.storage()
+            .persistent()
+            .set(&(HOLDING_KEY, token.clone()), &holding);
+        env.storage()
+            .persistent()
+            .set(&(REBALANCE_USED_KEY, token.clone()), &(period, sold));
+        Self::save_ledger(&env, &ledger);
+
+        env.events()
+            .publish((symbol_short!("Rebalance"), token), (amount_in, received));
+
+        Ok(received)
+    }
+
+    /// Queue a spending proposal
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `category` - Budget category to charge
+    /// * `recipient` - Service provider receiving the funds
+    /// * `amount` - Total USDC to disburse
+    /// * `disbursement` - One-off transfer or linear stream
+    /// * `description_hash` - Hash of the off-chain proposal text
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the proposal ID
+    /// * `Err(Error)` if validation fails
+    pub fn propose_spend(
+        env: Env,
+        category: Symbol,
+        recipient: Address,
+        amount: i128,
+        disbursement: Disbursement,
+        description_hash: BytesN<32>,
+    ) -> Result<u64, Error> {
+        Self::require_governance(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if let Disbursement::Stream(start, end) = disbursement {
+            if end <= start {
+                return Err(Error::InvalidStream);
+            }
+        }
+        let budget = Self::get_budget(env.clone(), category.clone())?;
+        if budget.committed + amount > budget.limit {
+            return Err(Error::BudgetExceeded);
+        }
+
+        let storage = env.storage().instance();
+        let proposal_id: u64 = storage.get(&PROPOSAL_COUNT_KEY).unwrap_or(0);
+        let timelock: u64 = storage.get(&TIMELOCK_KEY).unwrap_or(0);
+
+        let proposal = Proposal {
+            proposal_id,
+            category,
+            recipient,
+            amount,
+            disbursement,
+            description_hash,
+            eta: env.ledger().timestamp() + timelock,
+            status: ProposalStatus::Queued,
+        };
+        env.storage()
+            .persistent()
+            .set(&(PROPOSAL_KEY, proposal_id), &proposal);
+        storage.set(&PROPOSAL_COUNT_KEY, &(proposal_id + 1));
+
+        env.events()
+            .publish((symbol_short!("Proposed"), proposal_id), proposal.eta);
+
+        Ok(proposal_id)
+    }
+
+    /// Cancel a queued proposal
+    pub fn cancel_spend(env: Env, proposal_id: u64) -> Result<(), Error> {
+        Self::require_governance(&env)?;
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        if proposal.status != ProposalStatus::Queued {
+            return Err(Error::ProposalNotQueued);
+        }
+        proposal.status = ProposalStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&(PROPOSAL_KEY, proposal_id), &proposal);
+        Ok(())
+    }
+
+    /// Execute a queued proposal once its timelock has elapsed
+    ///
+    /// One-off proposals transfer immediately; stream proposals reserve the
+    /// amount and create a Stream the recipient withdraws from.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `proposal_id` - Proposal to execute
+    ///
+    /// # Returns
+    /// * `Ok(Option<u64>)` with the stream ID for stream proposals
+    /// * `Err(Error)` if the proposal cannot be executed yet
+    pub fn execute_spend(env: Env, proposal_id: u64) -> Result<Option<u64>, Error> {
+        // ============================================
+        // 1. VALIDATE PROPOSAL
+        // ============================================
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        if proposal.status != ProposalStatus::Queued {
+            return Err(Error::ProposalNotQueued);
+        }
+        if env.ledger().timestamp() < proposal.eta {
+            return Err(Error::TimelockNotElapsed);
+        }
+
+        let mut budget = Self::get_budget(env.clone(), proposal.category.clone())?;
+        if budget.committed + proposal.amount > budget.limit {
+            return Err(Error::BudgetExceeded);
+        }
+
+        let mut ledger = Self::get_ledger(env.clone())?;
+        let available = ledger.total_inflows - ledger.total_outflows - ledger.reserved_for_streams;
+        if proposal.amount > available {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // ============================================
+        // 2. DISBURSE
+        // ============================================
+        let stream_id = match proposal.disbursement {
+            Disbursement::OneOff => {
+                Self::token_client(&env)?.transfer(
+                    &env.current_contract_address(),
+                    &proposal.recipient,
+                    &proposal.amount,
+                );
+                ledger.total_outflows += proposal.amount;
+                None
+            }
+            Disbursement::Stream(start, end) => {
+                let storage = env.storage().instance();
+                let stream_id: u64 = storage.get(&STREAM_COUNT_KEY).unwrap_or(0);
+                let stream = Stream {
+                    stream_id,
+                    proposal_id,
+                    recipient: proposal.recipient.clone(),
+                    total: proposal.amount,
+                    withdrawn: 0,
+                    start,
+                    end,
+                };
+                env.storage()
+                    .persistent()
+                    .set(&(STREAM_KEY, stream_id), &stream);
+                storage.set(&STREAM_COUNT_KEY, &(stream_id + 1));
+                ledger.reserved_for_streams += proposal.amount;
+                Some(stream_id)
+            }
+        };
+
+        // ============================================
+        // 3. UPDATE ACCOUNTING
+        // ============================================
+        budget.committed += proposal.amount;
+        env.storage()
+            .persistent()
+            .set(&(BUDGET_KEY, budget.category.clone()), &budget);
+        Self::save_ledger(&env, &ledger);
+
+        proposal.status = ProposalStatus::Executed;
+        env.storage()
+            .persistent()
+            .set(&(PROPOSAL_KEY, proposal_id), &proposal);
+
+        env.events().publish(
+            (symbol_short!("Executed"), proposal_id),
+            (proposal.recipient, proposal.amount),
+        );
+
+        Ok(stream_id)
+    }
+
+    /// Withdraw the vested part of a stream
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `stream_id` - Stream to withdraw from
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the amount withdrawn
+    /// * `Err(Error)` if nothing has vested since the last withdrawal
+    pub fn withdraw_stream(env: Env, stream_id: u64) -> Result<i128, Error> {
+        let mut stream = Self::get_stream(env.clone(), stream_id)?;
+        stream.recipient.require_auth();
+
+        let amount = Self::vested(&env, &stream) - stream.withdrawn;
+        if amount <= 0 {
+            return Err(Error::NothingToWithdraw);
+        }
+
+        Self::token_client(&env)?.transfer(
+            &env.current_contract_address(),
+            &stream.recipient,
+            &amount,
+        );
+
+        stream.withdrawn += amount;
+        env.storage()
+            .persistent()
+            .set(&(STREAM_KEY, stream_id), &stream);
+
+        let mut ledger = Self::get_ledger(env.clone())?;
+        ledger.total_outflows += amount;
+        ledger.reserved_for_streams -= amount;
+        Self::save_ledger(&env, &ledger);
+
+        env.events()
+            .publish((symbol_short!("StreamOut"), stream_id), amount);
+
+        Ok(amount)
+    }
+
+    /// Get the amount of a stream that can be withdrawn now
+    pub fn withdrawable(env: Env, stream_id: u64) -> Result<i128, Error> {
+        let stream = Self::get_stream(env.clone(), stream_id)?;
+        Ok(Self::vested(&env, &stream) - stream.withdrawn)
+    }
+
+    /// Get the treasury-wide accounting
+    pub fn get_ledger(env: Env) -> Result<TreasuryLedger, Error> {
+        env.storage()
+            .instance()
+            .get(&LEDGER_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the accounting for a settlement token other than USDC
+    pub fn get_token_holding(env: Env, token: Address) -> TokenHolding {
+        env.storage()
+            .persistent()
+            .get(&(HOLDING_KEY, token.clone()))
+            .unwrap_or(TokenHolding {
+                token,
+                inflows: 0,
+                swapped_out: 0,
+                usdc_received: 0,
+            })
+    }
+
+    /// Get the amount of a token sold by `rebalance` in the current period
+    pub fn rebalanced_in_period(env: Env, token: Address) -> i128 {
+        let period = env.ledger().timestamp() / REBALANCE_PERIOD;
+        match env
+            .storage()
+            .persistent()
+            .get::<_, (u64, i128)>(&(REBALANCE_USED_KEY, token))
+        {
+            Some((used_period, sold)) if used_period == period => sold,
+            _ => 0,
+        }
+    }
+
+    /// Get a budget category
+    pub fn get_budget(env: Env, category: Symbol) -> Result<Budget, Error> {
+        env.storage()
+            .persistent()
+            .get(&(BUDGET_KEY, category))
+            .ok_or(Error::BudgetNotFound)
+    }
+
+    /// Get a proposal by ID
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&(PROPOSAL_KEY, proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    /// Get a stream by ID
+    pub fn get_stream(env: Env, stream_id: u64) -> Result<Stream, Error> {
+        env.storage()
+            .persistent()
+            .get(&(STREAM_KEY, stream_id))
+            .ok_or(Error::StreamNotFound)
+    }
+
+    fn vested(env: &Env, stream: &Stream) -> i128 {
+        let now = env.ledger().timestamp();
+        if now <= stream.start {
+            0
+        } else if now >= stream.end {
+            stream.total
+        } else {
+            stream.total * (now - stream.start) as i128 / (stream.end - stream.start) as i128
+        }
+    }
+
+    fn require_governance(env: &Env) -> Result<Address, Error> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&GOVERNANCE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        governance.require_auth();
+        Ok(governance)
+    }
+
+    /// Require the authorization of governance or a registered keeper
+    fn require_rebalancer(env: &Env, caller: &Address) -> Result<(), Error> {
+        caller.require_auth();
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&GOVERNANCE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        if *caller != governance && !Self::is_keeper(env.clone(), caller.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn save_ledger(env: &Env, ledger: &TreasuryLedger) {
+        env.storage().instance().set(&LEDGER_KEY, ledger);
+    }
+
+    fn usdc_token(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        Ok(token::Client::new(env, &Self::usdc_token(env)?))
+    }
+}
@@ -0,0 +1,459 @@
+#![cfg(test)]
+
+use dao_treasury::{
+    DaoTreasury, DaoTreasuryClient, Disbursement, Error, ProposalStatus, TokenHolding,
+    REBALANCE_PERIOD,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, Address, BytesN, Env,
+};
+
+/// 1 USDC with 7 decimal places
+const USDC: i128 = 1_0000000;
+
+/// Timelock used by the test treasury (one day)
+const TIMELOCK: u64 = 86_400;
+
+struct Setup<'a> {
+    env: Env,
+    treasury: DaoTreasuryClient<'a>,
+    usdc: token::Client<'a>,
+    usdc_admin: token::StellarAssetClient<'a>,
+    provider: Address,
+    keeper: Address,
+}
+
+/// Helper: Deploy USDC and an initialized treasury holding 1000 USDC that
+/// arrived as plain splitter transfers, with a 500 USDC "infra" budget and
+/// a registered rebalance keeper
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let usdc_admin = token::StellarAssetClient::new(&env, &usdc_id);
+
+    let treasury_id = env.register_contract(None, DaoTreasury);
+    let treasury = DaoTreasuryClient::new(&env, &treasury_id);
+    treasury.init(&Address::generate(&env), &usdc_id, &TIMELOCK);
+    treasury.set_budget(&symbol_short!("infra"), &(500 * USDC));
+    let keeper = Address::generate(&env);
+    treasury.set_keeper(&keeper, &true);
+
+    usdc_admin.mint(&treasury_id, &(1000 * USDC));
+    treasury.sync_inflows();
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        provider: Address::generate(&env),
+        keeper,
+        usdc_admin,
+        treasury,
+        env,
+    }
+}
+
+/// Helper: Queue a proposal from the "infra" budget
+fn propose(s: &Setup, amount: i128, disbursement: Disbursement) -> u64 {
+    s.treasury.propose_spend(
+        &symbol_short!("infra"),
+        &s.provider,
+        &amount,
+        &disbursement,
+        &BytesN::from_array(&s.env, &[1u8; 32]),
+    )
+}
+
+/// Helper: Advance the ledger clock
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+#[test]
+fn test_sync_inflows_accounts_splitter_transfers() {
+    let s = setup();
+
+    // Assert: setup's mint was booked as a splitter inflow
+    let ledger = s.treasury.get_ledger();
+    assert_eq!(ledger.total_inflows, 1000 * USDC);
+    assert_eq!(ledger.splitter_inflows, 1000 * USDC);
+
+    // A second sync with no new transfers books nothing
+    assert_eq!(s.treasury.sync_inflows(), 0);
+
+    // Explicit deposits are not double counted by sync
+    let donor = Address::generate(&s.env);
+    s.usdc_admin.mint(&donor, &(10 * USDC));
+    s.treasury.deposit(&donor, &(10 * USDC));
+    assert_eq!(s.treasury.sync_inflows(), 0);
+    assert_eq!(s.treasury.get_ledger().total_inflows, 1010 * USDC);
+}
+
+#[test]
+fn test_one_off_spend_respects_timelock() {
+    let s = setup();
+    let proposal_id = propose(&s, 100 * USDC, Disbursement::OneOff);
+
+    // Act: too early
+    assert_eq!(
+        s.treasury.try_execute_spend(&proposal_id),
+        Err(Ok(Error::TimelockNotElapsed))
+    );
+
+    advance(&s.env, TIMELOCK);
+    let stream_id = s.treasury.execute_spend(&proposal_id);
+
+    // Assert
+    assert_eq!(stream_id, None);
+    assert_eq!(s.usdc.balance(&s.provider), 100 * USDC);
+    assert_eq!(
+        s.treasury.get_proposal(&proposal_id).status,
+        ProposalStatus::Executed
+    );
+    assert_eq!(
+        s.treasury.get_budget(&symbol_short!("infra")).committed,
+        100 * USDC
+    );
+    assert_eq!(s.treasury.get_ledger().total_outflows, 100 * USDC);
+}
+
+#[test]
+fn test_budget_limit_enforced() {
+    let s = setup();
+
+    // Proposal larger than the category budget is rejected
+    assert_eq!(
+        s.treasury.try_propose_spend(
+            &symbol_short!("infra"),
+            &s.provider,
+            &(600 * USDC),
+            &Disbursement::OneOff,
+            &BytesN::from_array(&s.env, &[1u8; 32]),
+        ),
+        Err(Ok(Error::BudgetExceeded))
+    );
+
+    // Unknown category
+    assert_eq!(
+        s.treasury.try_propose_spend(
+            &symbol_short!("audits"),
+            &s.provider,
+            &USDC,
+            &Disbursement::OneOff,
+            &BytesN::from_array(&s.env, &[1u8; 32]),
+        ),
+        Err(Ok(Error::BudgetNotFound))
+    );
+}
+
+#[test]
+fn test_stream_vests_linearly() {
+    let s = setup();
+
+    // Arrange: 100 USDC streamed over 1000 seconds starting after the timelock
+    let start = s.env.ledger().timestamp() + TIMELOCK;
+    let proposal_id = propose(&s, 100 * USDC, Disbursement::Stream(start, start + 1000));
+    advance(&s.env, TIMELOCK);
+    let stream_id = s.treasury.execute_spend(&proposal_id).unwrap();
+    assert_eq!(s.treasury.get_ledger().reserved_for_streams, 100 * USDC);
+
+    // Act: a quarter of the way through
+    advance(&s.env, 250);
+    let first = s.treasury.withdraw_stream(&stream_id);
+
+    // Assert
+    assert_eq!(first, 25 * USDC);
+    assert_eq!(
+        s.treasury.try_withdraw_stream(&stream_id),
+        Err(Ok(Error::NothingToWithdraw))
+    );
+
+    // After the end everything is withdrawable
+    advance(&s.env, 10_000);
+    assert_eq!(s.treasury.withdrawable(&stream_id), 75 * USDC);
+    s.treasury.withdraw_stream(&stream_id);
+    assert_eq!(s.usdc.balance(&s.provider), 100 * USDC);
+
+    let ledger = s.treasury.get_ledger();
+    assert_eq!(ledger.reserved_for_streams, 0);
+    assert_eq!(ledger.total_outflows, 100 * USDC);
+}
+
+#[test]
+fn test_cancelled_proposal_cannot_execute() {
+    let s = setup();
+    let proposal_id = propose(&s, 10 * USDC, Disbursement::OneOff);
+
+    // Act
+    s.treasury.cancel_spend(&proposal_id);
+    advance(&s.env, TIMELOCK);
+
+    // Assert
+    assert_eq!(
+        s.treasury.try_execute_spend(&proposal_id),
+        Err(Ok(Error::ProposalNotQueued))
+    );
+}
+
+#[test]
+fn test_execute_fails_without_funds() {
+    let s = setup();
+
+    // Arrange: raise the budget above the treasury balance
+    s.treasury
+        .set_budget(&symbol_short!("infra"), &(5000 * USDC));
+    let proposal_id = propose(&s, 2000 * USDC, Disbursement::OneOff);
+    advance(&s.env, TIMELOCK);
+
+    // Act
+    let result = s.treasury.try_execute_spend(&proposal_id);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+}
+
+/// Price oracle quoting at a fixed rate
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_rate(env: Env, rate_bps: i128) {
+        env.storage().instance().set(&symbol_short!("RATE"), &rate_bps);
+    }
+
+    pub fn quote(env: Env, _token_in: Address, _token_out: Address, amount_in: i128) -> i128 {
+        let rate: i128 = env.storage().instance().get(&symbol_short!("RATE")).unwrap();
+        amount_in * rate / 10_000
+    }
+}
+
+/// Oracle quoting every swap at the largest representable amount (in its
+/// own module, as `MockOracle` exports `quote` too)
+mod max_oracle {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MaxOracle;
+
+    #[contractimpl]
+    impl MaxOracle {
+        pub fn quote(_env: Env, _token_in: Address, _token_out: Address, _amount_in: i128) -> i128 {
+            i128::MAX
+        }
+    }
+}
+
+/// Swap adapter pricing at its own fixed rate and filling a share of it
+#[contract]
+pub struct MockAdapter;
+
+#[contractimpl]
+impl MockAdapter {
+    pub fn configure(env: Env, rate_bps: i128, fill_bps: i128) {
+        env.storage().instance().set(&symbol_short!("RATE"), &rate_bps);
+        env.storage().instance().set(&symbol_short!("FILL"), &fill_bps);
+    }
+
+    pub fn swap(
+        env: Env,
+        from: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        _min_out: i128,
+    ) -> i128 {
+        let adapter = env.current_contract_address();
+        token::Client::new(&env, &token_in).transfer_from(&adapter, &from, &adapter, &amount_in);
+        let rate: i128 = env.storage().instance().get(&symbol_short!("RATE")).unwrap();
+        let fill: i128 = env.storage().instance().get(&symbol_short!("FILL")).unwrap();
+        let out = amount_in * rate / 10_000 * fill / 10_000;
+        token::Client::new(&env, &token_out).transfer(&adapter, &from, &out);
+        out
+    }
+}
+
+/// Helper: Give the treasury 200 units of a second stablecoin, an oracle
+/// pricing it at 0.99 USDC and an adapter trading at `rate_bps`, filling
+/// `fill_bps` of its own price
+fn setup_second_token<'a>(
+    s: &Setup,
+    rate_bps: i128,
+    fill_bps: i128,
+) -> (Address, token::Client<'a>) {
+    let token_id = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    token::StellarAssetClient::new(&s.env, &token_id).mint(&s.treasury.address, &(200 * USDC));
+
+    let adapter_id = s.env.register_contract(None, MockAdapter);
+    MockAdapterClient::new(&s.env, &adapter_id).configure(&rate_bps, &fill_bps);
+    s.usdc_admin.mint(&adapter_id, &(1000 * USDC));
+    let oracle_id = s.env.register_contract(None, MockOracle);
+    MockOracleClient::new(&s.env, &oracle_id).set_rate(&9_900);
+    assert_eq!(
+        s.treasury.try_set_swap_adapter(&adapter_id, &adapter_id, &100),
+        Err(Ok(Error::InvalidOracle))
+    );
+    s.treasury.set_swap_adapter(&adapter_id, &oracle_id, &100);
+    (token_id.clone(), token::Client::new(&s.env, &token_id))
+}
+
+#[test]
+fn test_token_inflows_are_tracked_per_token() {
+    let s = setup();
+    let (token_id, _) = setup_second_token(&s, 9_900, 10_000);
+
+    // Act
+    let booked = s.treasury.sync_token_inflows(&token_id);
+
+    // Assert: the USDC ledger is untouched
+    assert_eq!(booked, 200 * USDC);
+    assert_eq!(s.treasury.sync_token_inflows(&token_id), 0);
+    assert_eq!(
+        s.treasury.get_token_holding(&token_id),
+        TokenHolding {
+            token: token_id.clone(),
+            inflows: 200 * USDC,
+            swapped_out: 0,
+            usdc_received: 0,
+        }
+    );
+    assert_eq!(s.treasury.get_ledger().total_inflows, 1000 * USDC);
+    assert_eq!(
+        s.treasury.try_sync_token_inflows(&s.usdc.address),
+        Err(Ok(Error::InvalidToken))
+    );
+}
+
+#[test]
+fn test_rebalance_swaps_into_usdc_within_limits() {
+    let s = setup();
+    let (token_id, token) = setup_second_token(&s, 9_900, 10_000);
+
+    // Act & Assert: nothing can be sold before governance sets a limit
+    assert_eq!(
+        s.treasury.try_rebalance(&s.keeper, &token_id, &(50 * USDC)),
+        Err(Ok(Error::RebalanceNotAllowed))
+    );
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+    assert_eq!(
+        s.treasury.try_rebalance(&s.keeper, &token_id, &(101 * USDC)),
+        Err(Ok(Error::RebalanceLimitExceeded))
+    );
+
+    let received = s.treasury.rebalance(&s.keeper, &token_id, &(100 * USDC));
+
+    // Assert: the proceeds are spendable USDC
+    assert_eq!(received, 99 * USDC);
+    assert_eq!(token.balance(&s.treasury.address), 100 * USDC);
+    let holding = s.treasury.get_token_holding(&token_id);
+    assert_eq!((holding.swapped_out, holding.usdc_received), (100 * USDC, 99 * USDC));
+    assert_eq!(s.treasury.get_ledger().total_inflows, 1099 * USDC);
+    assert_eq!(s.usdc.balance(&s.treasury.address), 1099 * USDC);
+    assert_eq!(s.treasury.sync_inflows(), 0);
+}
+
+#[test]
+fn test_rebalance_rejects_excess_slippage() {
+    let s = setup();
+
+    // Arrange: the adapter fills 2% below the oracle, above the 1% limit
+    let (token_id, token) = setup_second_token(&s, 9_900, 9_800);
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+
+    // Act
+    let result = s.treasury.try_rebalance(&s.keeper, &token_id, &(100 * USDC));
+
+    // Assert: the swap is rolled back
+    assert_eq!(result, Err(Ok(Error::SlippageExceeded)));
+    assert_eq!(token.balance(&s.treasury.address), 200 * USDC);
+    assert_eq!(s.usdc.balance(&s.treasury.address), 1000 * USDC);
+    assert_eq!(s.treasury.get_token_holding(&token_id).swapped_out, 0);
+}
+
+#[test]
+fn test_rebalance_is_priced_by_the_oracle_not_the_adapter() {
+    let s = setup();
+
+    // Arrange: the adapter trades at half the oracle's price
+    let (token_id, token) = setup_second_token(&s, 5_000, 10_000);
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+
+    // Act
+    let result = s.treasury.try_rebalance(&s.keeper, &token_id, &(100 * USDC));
+
+    // Assert: the adapter's own price does not lower the floor
+    assert_eq!(result, Err(Ok(Error::SlippageExceeded)));
+    assert_eq!(token.balance(&s.treasury.address), 200 * USDC);
+}
+
+#[test]
+fn test_rebalance_rejects_a_quote_that_overflows() {
+    let s = setup();
+    let (token_id, token) = setup_second_token(&s, 9_900, 10_000);
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+
+    // Arrange: an oracle quoting so high the slippage floor overflows
+    let adapter_id = s.env.register_contract(None, MockAdapter);
+    MockAdapterClient::new(&s.env, &adapter_id).configure(&9_900, &10_000);
+    let oracle_id = s.env.register_contract(None, max_oracle::MaxOracle);
+    s.treasury.set_swap_adapter(&adapter_id, &oracle_id, &100);
+
+    // Act
+    let result = s.treasury.try_rebalance(&s.keeper, &token_id, &(100 * USDC));
+
+    // Assert: the sale fails with a typed error instead of trapping
+    assert_eq!(result, Err(Ok(Error::Overflow)));
+    assert_eq!(token.balance(&s.treasury.address), 200 * USDC);
+    assert_eq!(s.treasury.get_token_holding(&token_id).swapped_out, 0);
+}
+
+#[test]
+fn test_rebalance_requires_governance_or_a_keeper() {
+    let s = setup();
+    let (token_id, _) = setup_second_token(&s, 9_900, 10_000);
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+    let stranger = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        s.treasury.try_rebalance(&stranger, &token_id, &(10 * USDC)),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.treasury.set_keeper(&s.keeper, &false);
+    assert!(!s.treasury.is_keeper(&s.keeper));
+    assert_eq!(
+        s.treasury.try_rebalance(&s.keeper, &token_id, &(10 * USDC)),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(s.treasury.get_token_holding(&token_id).swapped_out, 0);
+}
+
+#[test]
+fn test_rebalance_limit_is_shared_within_a_period() {
+    let s = setup();
+    let (token_id, _) = setup_second_token(&s, 9_900, 10_000);
+    s.treasury.set_rebalance_limit(&token_id, &(100 * USDC));
+
+    // Act: two sales in the same period cannot exceed the limit together
+    s.treasury.rebalance(&s.keeper, &token_id, &(60 * USDC));
+    assert_eq!(
+        s.treasury.try_rebalance(&s.keeper, &token_id, &(50 * USDC)),
+        Err(Ok(Error::RebalanceLimitExceeded))
+    );
+    s.treasury.rebalance(&s.keeper, &token_id, &(40 * USDC));
+    assert_eq!(s.treasury.rebalanced_in_period(&token_id), 100 * USDC);
+
+    // Assert: the limit is available again in the next period
+    advance(&s.env, REBALANCE_PERIOD);
+    assert_eq!(s.treasury.rebalanced_in_period(&token_id), 0);
+    s.treasury.rebalance(&s.keeper, &token_id, &(50 * USDC));
+    assert_eq!(s.treasury.get_token_holding(&token_id).swapped_out, 150 * USDC);
+}
@@ -0,0 +1,477 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const REVENUE_SPLITTER_KEY: Symbol = symbol_short!("REV_SPLIT");
+const STUDY_REGISTRY_KEY: Symbol = symbol_short!("STUDY_REG");
+const BOUNTY_COUNT_KEY: Symbol = symbol_short!("BNTY_CNT");
+const BOUNTY_KEY: Symbol = symbol_short!("BOUNTY");
+const SUBMISSION_KEY: Symbol = symbol_short!("SUBMIT");
+
+/// Maximum number of studies that can be submitted to a single bounty
+pub const MAX_SUBMISSIONS: u32 = 200;
+
+/// Bounty lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BountyStatus {
+    Open,
+    Paid,
+    Cancelled,
+}
+
+/// Bounty structure
+///
+/// A researcher's escrowed request for data that does not exist yet:
+/// - spec_hash: Hash of the off-chain specification (condition codes,
+///   modality, minimum N)
+/// - reward: Escrowed USDC, paid through RevenueSplitter on settlement
+/// - min_studies: Number of confirmed studies required to settle
+/// - curator: Address that confirms submitted studies match the spec
+/// - deadline: Ledger timestamp after which the researcher can cancel
+/// - submissions: Study hashes submitted so far
+/// - confirmed: Number of submissions confirmed by the curator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bounty {
+    pub bounty_id: u64,
+    pub researcher: Address,
+    pub spec_hash: BytesN<32>,
+    pub reward: i128,
+    pub min_studies: u32,
+    pub curator: Address,
+    pub deadline: u64,
+    pub submissions: Vec<BytesN<32>>,
+    pub confirmed: u32,
+    pub status: BountyStatus,
+}
+
+/// Submission structure
+///
+/// A study registered in StudyRegistry and offered against a bounty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submission {
+    pub study_hash: BytesN<32>,
+    pub contributor: Address,
+    pub confirmed: bool,
+}
+
+/// StudyRecord as returned by StudyRegistry.get_study()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyRecord {
+    pub dataset_hash: BytesN<32>,
+    pub contributor: Address,
+    pub timestamp: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidMinStudies = 4,
+    InvalidDeadline = 5,
+    BountyNotFound = 6,
+    BountyNotOpen = 7,
+    BountyExpired = 8,
+    BountyNotExpired = 9,
+    NotStudyContributor = 10,
+    DuplicateSubmission = 11,
+    TooManySubmissions = 12,
+    SubmissionNotFound = 13,
+    AlreadyConfirmed = 14,
+    NotEnoughConfirmed = 15,
+}
+
+#[contract]
+pub struct DataBounty;
+
+#[contractimpl]
+impl DataBounty {
+    /// Initialize the DataBounty contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Deployment admin
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// * `study_registry` - Address of the StudyRegistry contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        revenue_splitter: Address,
+        study_registry: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&REVENUE_SPLITTER_KEY, &revenue_splitter);
+        storage.set(&STUDY_REGISTRY_KEY, &study_registry);
+        storage.set(&BOUNTY_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Post a bounty and escrow its reward
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `researcher` - Researcher posting the bounty (must authorize)
+    /// * `spec_hash` - Hash of the off-chain data specification
+    /// * `reward` - USDC reward escrowed in this contract
+    /// * `min_studies` - Confirmed studies required to settle
+    /// * `curator` - Address that confirms matches
+    /// * `deadline` - Ledger timestamp after which the bounty can be cancelled
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the bounty ID
+    /// * `Err(Error)` if validation fails
+    pub fn post_bounty(
+        env: Env,
+        researcher: Address,
+        spec_hash: BytesN<32>,
+        reward: i128,
+        min_studies: u32,
+        curator: Address,
+        deadline: u64,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        researcher.require_auth();
+        if reward <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if min_studies == 0 || min_studies > MAX_SUBMISSIONS {
+            return Err(Error::InvalidMinStudies);
+        }
+        if deadline <= env.ledger().timestamp() {
+            return Err(Error::InvalidDeadline);
+        }
+
+        // ============================================
+        // 2. ESCROW REWARD
+        // ============================================
+        Self::token_client(&env)?.transfer(&researcher, &env.current_contract_address(), &reward);
+
+        // ============================================
+        // 3. STORE BOUNTY
+        // ============================================
+        let storage = env.storage().instance();
+        let bounty_id: u64 = storage
+            .get(&BOUNTY_COUNT_KEY)
+            .ok_or(Error::NotInitialized)?;
+
+        let bounty = Bounty {
+            bounty_id,
+            researcher: researcher.clone(),
+            spec_hash: spec_hash.clone(),
+            reward,
+            min_studies,
+            curator,
+            deadline,
+            submissions: Vec::new(&env),
+            confirmed: 0,
+            status: BountyStatus::Open,
+        };
+        Self::save_bounty(&env, &bounty);
+        storage.set(&BOUNTY_COUNT_KEY, &(bounty_id + 1));
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("BountyNew"), bounty_id),
+            (researcher, spec_hash, reward, min_studies),
+        );
+
+        Ok(bounty_id)
+    }
+
+    /// Submit a registered study against a bounty
+    ///
+    /// The study must already be registered in StudyRegistry by the
+    /// submitting contributor.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bounty_id` - Bounty to fulfill
+    /// * `contributor` - Study contributor (must authorize)
+    /// * `study_hash` - Hash of the registered study
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if validation fails
+    pub fn submit_study(
+        env: Env,
+        bounty_id: u64,
+        contributor: Address,
+        study_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        contributor.require_auth();
+
+        let mut bounty = Self::load_open_bounty(&env, bounty_id)?;
+        if env.ledger().timestamp() > bounty.deadline {
+            return Err(Error::BountyExpired);
+        }
+        if bounty.submissions.len() >= MAX_SUBMISSIONS {
+            return Err(Error::TooManySubmissions);
+        }
+
+        let submission_key = (SUBMISSION_KEY, bounty_id, study_hash.clone());
+        if env.storage().persistent().has(&submission_key) {
+            return Err(Error::DuplicateSubmission);
+        }
+
+        // Verify the study is registered to this contributor
+        let study_registry: Address = env
+            .storage()
+            .instance()
+            .get(&STUDY_REGISTRY_KEY)
+            .ok_or(Error::NotInitialized)?;
+        let study: StudyRecord = env.invoke_contract(
+            &study_registry,
+            &symbol_short!("get_study"),
+            (study_hash.clone(),).into_val(&env),
+        );
+        if study.contributor != contributor {
+            return Err(Error::NotStudyContributor);
+        }
+
+        let submission = Submission {
+            study_hash: study_hash.clone(),
+            contributor: contributor.clone(),
+            confirmed: false,
+        };
+        env.storage().persistent().set(&submission_key, &submission);
+        bounty.submissions.push_back(study_hash.clone());
+        Self::save_bounty(&env, &bounty);
+
+        env.events().publish(
+            (symbol_short!("BountySub"), bounty_id),
+            (contributor, study_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Confirm that a submitted study matches the bounty specification
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bounty_id` - Bounty ID
+    /// * `study_hash` - Submitted study to confirm
+    ///
+    /// # Returns
+    /// * `Ok(u32)` with the number of confirmed studies
+    /// * `Err(Error)` if validation fails
+    pub fn confirm_match(env: Env, bounty_id: u64, study_hash: BytesN<32>) -> Result<u32, Error> {
+        let mut bounty = Self::load_open_bounty(&env, bounty_id)?;
+        bounty.curator.require_auth();
+
+        let submission_key = (SUBMISSION_KEY, bounty_id, study_hash.clone());
+        let mut submission: Submission = env
+            .storage()
+            .persistent()
+            .get(&submission_key)
+            .ok_or(Error::SubmissionNotFound)?;
+        if submission.confirmed {
+            return Err(Error::AlreadyConfirmed);
+        }
+
+        submission.confirmed = true;
+        env.storage().persistent().set(&submission_key, &submission);
+        bounty.confirmed += 1;
+        Self::save_bounty(&env, &bounty);
+
+        env.events()
+            .publish((symbol_short!("BountyOk"), bounty_id), study_hash);
+
+        Ok(bounty.confirmed)
+    }
+
+    /// Settle a bounty once enough studies are confirmed
+    ///
+    /// The escrowed reward is forwarded to RevenueSplitter and split among
+    /// the contributors of the confirmed studies.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bounty_id` - Bounty to settle
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Address>)` with the paid contributors
+    /// * `Err(Error)` if validation fails
+    pub fn settle(env: Env, bounty_id: u64) -> Result<Vec<Address>, Error> {
+        // ============================================
+        // 1. VALIDATE BOUNTY
+        // ============================================
+        let mut bounty = Self::load_open_bounty(&env, bounty_id)?;
+        if bounty.confirmed < bounty.min_studies {
+            return Err(Error::NotEnoughConfirmed);
+        }
+
+        // ============================================
+        // 2. COLLECT CONFIRMED CONTRIBUTORS
+        // ============================================
+        let mut contributors = Vec::new(&env);
+        for study_hash in bounty.submissions.iter() {
+            let submission: Submission = env
+                .storage()
+                .persistent()
+                .get(&(SUBMISSION_KEY, bounty_id, study_hash))
+                .ok_or(Error::SubmissionNotFound)?;
+            if submission.confirmed {
+                contributors.push_back(submission.contributor);
+            }
+        }
+
+        // ============================================
+        // 3. PAY THROUGH REVENUE SPLITTER
+        // ============================================
+        let revenue_splitter: Address = env
+            .storage()
+            .instance()
+            .get(&REVENUE_SPLITTER_KEY)
+            .ok_or(Error::NotInitialized)?;
+
+        // RevenueSplitter pulls the reward from this contract
+        let contract_address = env.current_contract_address();
+        env.authorize_as_current_contract(soroban_sdk::vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: Self::token_client(&env)?.address,
+                    fn_name: symbol_short!("transfer"),
+                    args: (contract_address.clone(), revenue_splitter.clone(), bounty.reward)
+                        .into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+
+        env.invoke_contract::<()>(
+            &revenue_splitter,
+            &Symbol::new(&env, "payout_amount"),
+            (
+                contract_address,
+                Self::payout_id(&env, bounty_id),
+                contributors.clone(),
+                bounty.reward,
+            )
+                .into_val(&env),
+        );
+
+        bounty.status = BountyStatus::Paid;
+        Self::save_bounty(&env, &bounty);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("BountyPay"), bounty_id),
+            (bounty.reward, contributors.len()),
+        );
+
+        Ok(contributors)
+    }
+
+    /// Cancel an unsettled bounty after its deadline and refund the researcher
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bounty_id` - Bounty to cancel
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the bounty is not open or not yet expired
+    pub fn cancel_bounty(env: Env, bounty_id: u64) -> Result<(), Error> {
+        let mut bounty = Self::load_open_bounty(&env, bounty_id)?;
+        bounty.researcher.require_auth();
+        if env.ledger().timestamp() <= bounty.deadline {
+            return Err(Error::BountyNotExpired);
+        }
+
+        Self::token_client(&env)?.transfer(
+            &env.current_contract_address(),
+            &bounty.researcher,
+            &bounty.reward,
+        );
+
+        bounty.status = BountyStatus::Cancelled;
+        Self::save_bounty(&env, &bounty);
+
+        env.events()
+            .publish((symbol_short!("BountyCxl"), bounty_id), bounty.reward);
+
+        Ok(())
+    }
+
+    /// Get a bounty by ID
+    pub fn get_bounty(env: Env, bounty_id: u64) -> Result<Bounty, Error> {
+        env.storage()
+            .persistent()
+            .get(&(BOUNTY_KEY, bounty_id))
+            .ok_or(Error::BountyNotFound)
+    }
+
+    /// Get a submission by bounty and study hash
+    pub fn get_submission(
+        env: Env,
+        bounty_id: u64,
+        study_hash: BytesN<32>,
+    ) -> Result<Submission, Error> {
+        env.storage()
+            .persistent()
+            .get(&(SUBMISSION_KEY, bounty_id, study_hash))
+            .ok_or(Error::SubmissionNotFound)
+    }
+
+    fn load_open_bounty(env: &Env, bounty_id: u64) -> Result<Bounty, Error> {
+        let bounty = Self::get_bounty(env.clone(), bounty_id)?;
+        if bounty.status != BountyStatus::Open {
+            return Err(Error::BountyNotOpen);
+        }
+        Ok(bounty)
+    }
+
+    fn save_bounty(env: &Env, bounty: &Bounty) {
+        env.storage()
+            .persistent()
+            .set(&(BOUNTY_KEY, bounty.bounty_id), bounty);
+    }
+
+    /// Payout ID used in RevenueSplitter events: "bounty" + big-endian ID
+    fn payout_id(env: &Env, bounty_id: u64) -> Bytes {
+        let mut payout_id = Bytes::from_slice(env, b"bounty");
+        payout_id.append(&Bytes::from_slice(env, &bounty_id.to_be_bytes()));
+        payout_id
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
@@ -0,0 +1,275 @@
+#![cfg(test)]
+
+use data_bounty::{BountyStatus, DataBounty, DataBountyClient, Error, StudyRecord};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, Address, Bytes, BytesN, Env, Vec,
+};
+
+/// 100 USDC with 7 decimal places
+const REWARD: i128 = 100_0000000;
+
+/// Deadline used by every test bounty
+const DEADLINE: u64 = 1_000;
+
+/// Minimal StudyRegistry stand-in
+#[contract]
+pub struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn register(env: Env, study_hash: BytesN<32>, contributor: Address) {
+        let record = StudyRecord {
+            dataset_hash: study_hash.clone(),
+            contributor,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&study_hash, &record);
+    }
+
+    pub fn get_study(env: Env, study_hash: BytesN<32>) -> StudyRecord {
+        env.storage().instance().get(&study_hash).unwrap()
+    }
+}
+
+/// Minimal RevenueSplitter stand-in pulling the amount and recording
+/// payout_amount calls
+#[contract]
+pub struct MockSplitter;
+
+#[contractimpl]
+impl MockSplitter {
+    pub fn init(env: Env, usdc_token: Address) {
+        env.storage().instance().set(&symbol_short!("USDC"), &usdc_token);
+    }
+
+    pub fn payout_amount(
+        env: Env,
+        payer: Address,
+        dataset_id: Bytes,
+        contributors: Vec<Address>,
+        amount: i128,
+    ) {
+        payer.require_auth();
+        let usdc_token: Address = env.storage().instance().get(&symbol_short!("USDC")).unwrap();
+        token::Client::new(&env, &usdc_token).transfer(&payer, &env.current_contract_address(), &amount);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("LAST"), &(dataset_id, contributors, amount));
+    }
+
+    pub fn last(env: Env) -> (Bytes, Vec<Address>, i128) {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("LAST"))
+            .unwrap()
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    bounty: DataBountyClient<'a>,
+    registry: MockRegistryClient<'a>,
+    splitter: MockSplitterClient<'a>,
+    usdc: token::Client<'a>,
+    researcher: Address,
+    curator: Address,
+}
+
+/// Helper: Deploy USDC, mocks and an initialized DataBounty; fund the researcher
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let researcher = Address::generate(&env);
+
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&researcher, &(10 * REWARD));
+
+    let registry_id = env.register_contract(None, MockRegistry);
+    let splitter_id = env.register_contract(None, MockSplitter);
+    MockSplitterClient::new(&env, &splitter_id).init(&usdc_id);
+    let bounty_id = env.register_contract(None, DataBounty);
+    let bounty = DataBountyClient::new(&env, &bounty_id);
+    bounty.init(&admin, &usdc_id, &splitter_id, &registry_id);
+
+    Setup {
+        registry: MockRegistryClient::new(&env, &registry_id),
+        splitter: MockSplitterClient::new(&env, &splitter_id),
+        usdc: token::Client::new(&env, &usdc_id),
+        curator: Address::generate(&env),
+        bounty,
+        researcher,
+        env,
+    }
+}
+
+/// Helper: Create a study hash
+fn study_hash(env: &Env, seed: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[seed; 32])
+}
+
+/// Helper: Post a bounty requiring `min_studies` confirmed studies
+fn post(s: &Setup, min_studies: u32) -> u64 {
+    s.bounty.post_bounty(
+        &s.researcher,
+        &study_hash(&s.env, 0xAA),
+        &REWARD,
+        &min_studies,
+        &s.curator,
+        &DEADLINE,
+    )
+}
+
+/// Helper: Register a study to a new contributor and submit it
+fn register_and_submit(s: &Setup, bounty_id: u64, seed: u8) -> (Address, BytesN<32>) {
+    let contributor = Address::generate(&s.env);
+    let hash = study_hash(&s.env, seed);
+    s.registry.register(&hash, &contributor);
+    s.bounty.submit_study(&bounty_id, &contributor, &hash);
+    (contributor, hash)
+}
+
+#[test]
+fn test_post_bounty_escrows_reward() {
+    let s = setup();
+
+    // Act
+    let bounty_id = post(&s, 2);
+
+    // Assert
+    let bounty = s.bounty.get_bounty(&bounty_id);
+    assert_eq!(bounty.reward, REWARD);
+    assert_eq!(bounty.status, BountyStatus::Open);
+    assert_eq!(s.usdc.balance(&s.bounty.address), REWARD);
+}
+
+#[test]
+fn test_full_bounty_flow_pays_through_splitter() {
+    let s = setup();
+    let bounty_id = post(&s, 2);
+
+    // Arrange
+    let (c1, h1) = register_and_submit(&s, bounty_id, 1);
+    let (c2, h2) = register_and_submit(&s, bounty_id, 2);
+    register_and_submit(&s, bounty_id, 3);
+
+    // Act
+    s.bounty.confirm_match(&bounty_id, &h1);
+    s.bounty.confirm_match(&bounty_id, &h2);
+    let paid = s.bounty.settle(&bounty_id);
+
+    // Assert: only confirmed contributors are paid
+    assert_eq!(paid.len(), 2);
+    assert_eq!(paid.get(0).unwrap(), c1);
+    assert_eq!(paid.get(1).unwrap(), c2);
+    assert_eq!(s.usdc.balance(&s.splitter.address), REWARD);
+
+    let (_, contributors, amount) = s.splitter.last();
+    assert_eq!(contributors, paid);
+    assert_eq!(amount, REWARD);
+    assert_eq!(s.bounty.get_bounty(&bounty_id).status, BountyStatus::Paid);
+}
+
+#[test]
+fn test_settle_requires_min_confirmed() {
+    let s = setup();
+    let bounty_id = post(&s, 2);
+
+    // Arrange
+    let (_, h1) = register_and_submit(&s, bounty_id, 1);
+    s.bounty.confirm_match(&bounty_id, &h1);
+
+    // Act
+    let result = s.bounty.try_settle(&bounty_id);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::NotEnoughConfirmed)));
+}
+
+#[test]
+fn test_submit_study_of_other_contributor_fails() {
+    let s = setup();
+    let bounty_id = post(&s, 1);
+
+    // Arrange
+    let owner = Address::generate(&s.env);
+    let impostor = Address::generate(&s.env);
+    let hash = study_hash(&s.env, 7);
+    s.registry.register(&hash, &owner);
+
+    // Act
+    let result = s.bounty.try_submit_study(&bounty_id, &impostor, &hash);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::NotStudyContributor)));
+}
+
+#[test]
+fn test_duplicate_submission_and_confirmation_fail() {
+    let s = setup();
+    let bounty_id = post(&s, 1);
+
+    // Arrange
+    let (contributor, hash) = register_and_submit(&s, bounty_id, 1);
+    s.bounty.confirm_match(&bounty_id, &hash);
+
+    // Assert
+    assert_eq!(
+        s.bounty.try_submit_study(&bounty_id, &contributor, &hash),
+        Err(Ok(Error::DuplicateSubmission))
+    );
+    assert_eq!(
+        s.bounty.try_confirm_match(&bounty_id, &hash),
+        Err(Ok(Error::AlreadyConfirmed))
+    );
+}
+
+#[test]
+fn test_cancel_after_deadline_refunds_researcher() {
+    let s = setup();
+    let bounty_id = post(&s, 1);
+
+    // Cannot cancel before the deadline
+    assert_eq!(
+        s.bounty.try_cancel_bounty(&bounty_id),
+        Err(Ok(Error::BountyNotExpired))
+    );
+
+    // Act
+    s.env.ledger().with_mut(|l| l.timestamp = DEADLINE + 1);
+    s.bounty.cancel_bounty(&bounty_id);
+
+    // Assert
+    assert_eq!(s.usdc.balance(&s.researcher), 10 * REWARD);
+    assert_eq!(
+        s.bounty.get_bounty(&bounty_id).status,
+        BountyStatus::Cancelled
+    );
+    assert_eq!(
+        s.bounty.try_settle(&bounty_id),
+        Err(Ok(Error::BountyNotOpen))
+    );
+}
+
+#[test]
+fn test_submit_after_deadline_fails() {
+    let s = setup();
+    let bounty_id = post(&s, 1);
+
+    // Arrange
+    let contributor = Address::generate(&s.env);
+    let hash = study_hash(&s.env, 9);
+    s.registry.register(&hash, &contributor);
+    s.env.ledger().with_mut(|l| l.timestamp = DEADLINE + 1);
+
+    // Act
+    let result = s.bounty.try_submit_study(&bounty_id, &contributor, &hash);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::BountyExpired)));
+}
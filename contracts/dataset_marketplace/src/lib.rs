@@ -1,73 +1,1015 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Env, Symbol, Map, Address, 
-    Bytes, BytesN, Vec, I128,
+    contract, contracterror, contractimpl, contractmeta, contracttype, panic_with_error, symbol_short, Env, Symbol, Map, Address, 
+    Bytes, BytesN, String, Vec, token, IntoVal, FromVal, TryFromVal, Val,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+};
+use biochain_pricing as pricing;
+use biochain_ids::{IdError, PurchaseId, Receipt, STRKEY_LEN};
+use biochain_interfaces::{
+    AccessPassClient, CredentialRegistryClient, OracleAsset, PriceOracleClient, StudyRegistryClient, StudyRegistryError,
+};
+pub use biochain_types::{
+    Dataset, DatasetPurchasedEventData, DatasetRegisteredEventData, DatasetStatus, PayoutBreakdown,
+    PurchaseRecord,
 };
 
-/// Storage keys
+/// Storage keys used before schema 3
+/// 
+/// Only read through `DataKey::legacy` so entries written by older code
+/// stay reachable until they are migrated.
 const DATASET_KEY: Symbol = symbol_short!("DATASET");
 const PURCHASE_KEY: Symbol = symbol_short!("PURCHASE");
 const REVENUE_SPLITTER_KEY: Symbol = symbol_short!("REV_SPLIT");
 const STUDY_REGISTRY_KEY: Symbol = symbol_short!("STUDY_REG");
+const STATS_KEY: Symbol = symbol_short!("STATS");
+const BRIDGE_KEY: Symbol = symbol_short!("BRIDGE");
+const ROYALTY_KEY: Symbol = symbol_short!("ROYALTY");
+const INBOX_KEY: Symbol = symbol_short!("INBOX");
+const UPGRADER_KEY: Symbol = symbol_short!("UPGRADER");
+const PAUSED_KEY: Symbol = symbol_short!("PAUSED");
+const SCHEMA_KEY: Symbol = symbol_short!("SCHEMA");
+const AUDIT_KEY: Symbol = symbol_short!("AUDIT");
+const BREAKER_KEY: Symbol = symbol_short!("BREAKER");
+const STUDY_DATASETS_KEY: Symbol = symbol_short!("STUDY_DS");
+const NS_REGISTRY_KEY: Symbol = symbol_short!("NS_REG");
+const NS_DATASET_KEY: Symbol = symbol_short!("NS_DS");
+const NS_PURCHASE_KEY: Symbol = symbol_short!("NS_PUR");
+const NS_INDEX_KEY: Symbol = symbol_short!("NS_INDEX");
+
+/// Interface version checked by the UpgradeCoordinator after an upgrade
+/// 
+/// - 2: Adds `purchase_dataset_at`
+/// - 3: Adds `on_study_revoked`
+/// - 4: Adds `init`, `get_admin` and `transfer_admin`
+/// - 5: `register_dataset` takes an owner; adds `update_price`,
+///   `add_studies` and `remove_studies`
+/// - 6: Adds `delist_dataset`, `relist_dataset` and `suspend_dataset`;
+///   datasets carry a status
+/// - 7: Adds `list_datasets` and `dataset_count`
+/// - 8: Adds `get_purchases_for_buyer` and `purchase_count`
+/// - 9: Adds `get_buyers_for_dataset` and `dataset_sales_count`
+/// - 10: Adds `bump_dataset`
+/// - 11: `register_dataset` takes an optional payment token; adds
+///   `add_payment_token`, `remove_payment_token`, `get_payment_tokens` and
+///   `get_dataset_token`
+/// - 12: Adds `set_price_oracle`, `get_price_oracle`, `set_price_mode`,
+///   `get_price_mode` and `quote_price`
+/// - 13: Adds `set_access_duration`, `get_access_duration`,
+///   `get_access_grant` and `has_access`
+/// - 14: Adds `register_bundle`, `get_bundle` and `purchase_bundle`
+/// - 15: `purchase_dataset` takes an optional promo code; adds
+///   `set_promo_code`, `remove_promo_code`, `get_promo_code`,
+///   `set_timed_discount`, `get_timed_discount`, `set_volume_tiers` and
+///   `get_volume_tiers`
+/// - 16: Adds `set_refund_policy`, `get_refund_policy`,
+///   `get_purchase_payment`, `request_refund` and `resolve_refund`
+/// - 17: Adds `set_escrow`, `get_escrow`, `get_escrowed_payout` and
+///   `settle_escrow`
+/// - 18: Adds `publish_version`, `get_version`, `get_latest_version`,
+///   `get_purchased_version`, `set_upgrade_price`, `get_upgrade_price`
+///   and `purchase_upgrade`
+/// - 19: Adds `set_metadata` and `get_metadata`
+/// - 20: Adds `find_datasets_by_tag` and `tag_dataset_count`
+/// - 21: Adds `set_access_policy`, `get_access_policy`, `set_allowlisted`,
+///   `is_allowlisted`, `is_eligible`, `set_credential_registry` and
+///   `get_credential_registry`
+/// - 22: Adds `set_access_pass` and `get_access_pass`
+/// - 23: Adds `set_license_terms` and `purchase_with_terms`
+/// - 24: Adds `set_transferable`, `is_transferable` and `transfer_license`
+/// - 25: Adds `set_price_decay`, `get_price_decay` and `get_current_price`
+/// - 26: Adds `set_query_price`, `get_query_price`, `purchase_queries`,
+///   `consume_query`, `get_query_credit`, `set_query_gateway` and
+///   `get_query_gateway`
+/// - 27: `register_dataset` and `update_price` accept a price of 0 for
+///   open-access listings; adds `is_open_access`
+/// - 28: Adds `set_fee_config` and `get_fee_config`
+/// - 29: Adds `emergency_pause`, `emergency_unpause` and
+///   `is_emergency_paused`
+/// - 30: `register_namespaced_dataset` accepts a price of 0 for
+///   open-access listings
+/// - 31: Adds `delist_namespaced_dataset` and `relist_namespaced_dataset`;
+///   `purchase_namespaced_dataset` checks the listing like
+///   `purchase_dataset` and takes the platform fee before the namespace fee
+/// - 32: `set_dataset_license` requires the listing owner instead of the
+///   UpgradeCoordinator
+/// - 33: `link_related_datasets` requires both listing owners instead of
+///   the UpgradeCoordinator; `unlink_related_datasets` takes the owner of
+///   either listing
+/// - 34: The emergency pause fails calls with `Error::Paused`; drops
+///   `ExtError::ContractPaused`
+/// - 35: `resolve_refund` refunds a purchase still in escrow through the
+///   PurchaseEscrow's `refund` instead of paying it out
+/// - 36: `resolve_refund` reclaims only what the splitter credited for the
+///   refunded purchase, through the payout sequence recorded with it
+/// - 37: `claim_revocation_refund` refunds a share of what the buyer paid
+///   rather than of the listed price, and nothing without a payment record
+/// - 38: Adds `set_reputation_contract` and `get_reputation_contract`
+/// - 39: `consume_query` holds query payments too small to split until
+///   later queries add up to a payout; adds `get_pending_query_payout`
+/// - 40: `transfer_license` moves the license between the buyer and
+///   listing indexes
+pub const INTERFACE_VERSION: u32 = 40;
+
+/// Minimum interface versions accepted for the contracts this one calls
+/// 
+/// Purchases are paid out through the splitter's `payout_weighted_in`,
+/// added with its interface 10, and refunds reclaimed with its
+/// `claw_back` by payout sequence, as of its interface 21. Revoked studies are looked up with the registry's
+/// `get_revoked_studies`, added with its interface 11, and contributor
+/// shares with its `get_study_contributors`, added with its interface 12.
+pub const MIN_SPLITTER_INTERFACE: u32 = 21;
+pub const MIN_REGISTRY_INTERFACE: u32 = 12;
+
+// Contract metadata embedded in the WASM custom section
+contractmeta!(key = "Description", val = "Lists datasets built from registered studies and records purchases");
+
+/// TTL policy for records kept alive by the TtlKeeper, in ledgers
+/// 
+/// Entries are extended to ~30 days once they drop below ~7 days.
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum studies one `register_dataset`, `register_namespaced_dataset`
+/// or `add_studies` call takes, so registration stays well within a
+/// transaction's resource limits; larger datasets are extended with
+/// `add_studies`
+pub const MAX_STUDIES_PER_CALL: u32 = 100;
+
+/// Width of a jurisdiction reporting epoch in seconds (30 days)
+/// 
+/// A sale falls in epoch `ledger timestamp / REPORT_EPOCH_SECONDS`.
+pub const REPORT_EPOCH_SECONDS: u64 = 2_592_000;
+
+/// Jurisdiction sales are counted under when the compliance registry has
+/// none attested for the buyer, so reports still add up to all sales
+pub const UNKNOWN_JURISDICTION: Symbol = symbol_short!("unknown");
+
+/// Maximum related datasets linked to one listing, so browse results stay
+/// bounded
+pub const MAX_RELATED_DATASETS: u32 = 10;
+
+/// Maximum tokens, besides the splitter's USDC token, listings can be
+/// priced in
+pub const MAX_PAYMENT_TOKENS: u32 = 10;
+
+/// Maximum datasets in one bundle, so a bundle purchase stays within a
+/// transaction's resource limits
+pub const MAX_BUNDLE_DATASETS: u32 = 10;
+
+/// Maximum volume discount tiers of one listing
+pub const MAX_VOLUME_TIERS: u32 = 5;
+
+/// Maximum platform fee, in basis points of each payment (20%)
+pub const MAX_PLATFORM_FEE_BPS: u32 = 2_000;
+
+/// Maximum length of a listing's title, in bytes
+pub const MAX_TITLE_LEN: u32 = 64;
+
+/// Maximum length of a listing's license identifier, in bytes
+pub const MAX_LICENSE_ID_LEN: u32 = 32;
+
+/// Maximum tags of one listing
+pub const MAX_METADATA_TAGS: u32 = 10;
+
+/// License ID of data-use terms a listing's owner sets by hash with
+/// `set_license_terms`, instead of binding a LicenseRegistry license
+pub const OWNER_TERMS_LICENSE: Symbol = symbol_short!("terms");
+
+/// Maximum age of an oracle price a purchase is settled at, in seconds
+/// 
+/// Three times the 5-minute resolution of Reflector feeds, so one missed
+/// update does not block purchases.
+pub const MAX_PRICE_AGE_SECONDS: u64 = 900;
+
+/// Maximum records returned by a single `list_datasets`,
+/// `get_purchases_for_buyer` or `get_buyers_for_dataset` call
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Storage schema version written by this code
+/// 
+/// - 1: Dataset and PurchaseRecord stored raw (no marker in storage)
+/// - 2: Dataset and PurchaseRecord stored wrapped in versioned enums
+/// - 3: All entries keyed by `DataKey` instead of symbol tuples
+/// - 4: Dataset and PurchaseRecord stored in persistent storage
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// Storage keys
+/// 
+/// Each record type has its own variant, so keys of different records
+/// cannot collide however their fields are chosen:
+/// - Dataset / Purchase: Records in persistent storage (instance storage
+///   before schema 4)
+/// - StudyDatasets / NsDataset / NsPurchase / NsIndex / PurchaseSeq: Records,
+///   indexes and per-dataset purchase counters in persistent storage
+/// - DatasetLicense / LicenseAck: License bound to a listing and buyers'
+///   acknowledgements of it, in persistent storage
+/// - JurisdictionReport: Per-jurisdiction, per-epoch sales counters in
+///   persistent storage
+/// - RelatedDatasets: Listings linked to a dataset, in persistent storage
+/// - DatasetReview / RevocationRefund: Datasets flagged by study
+///   revocations and the revoked studies each buyer was refunded for, in
+///   persistent storage
+/// - DatasetCount / DatasetIndex: Number of registered datasets and their
+///   IDs by registration order, in persistent storage
+/// - BuyerPurchaseCount / BuyerPurchase: Number of datasets each buyer
+///   purchased and their IDs by first purchase, in persistent storage
+/// - DatasetBuyerCount / DatasetBuyer: Number of distinct buyers of each
+///   dataset and their addresses by first purchase, in persistent storage
+/// - DatasetToken: Payment token of listings not priced in the splitter's
+///   USDC token, in persistent storage
+/// - PaymentTokens: Tokens listings can be priced in besides USDC, in
+///   instance storage
+/// - DatasetPriceMode: Price mode set by a listing's owner (fixed when
+///   absent), in persistent storage
+/// - AccessDuration / AccessGrant: Access period a listing's owner set
+///   and each buyer's access to the dataset, in persistent storage
+/// - Bundle: Datasets sold together at a bundle price, in persistent
+///   storage
+/// - PromoCode / TimedDiscount / VolumeTiers: Discounts a listing's owner
+///   offers, promo codes keyed by their SHA-256 hash, in persistent storage
+/// - PurchasePayment: Payment and refund state of each buyer's latest
+///   purchase of a dataset, in persistent storage
+/// - Keys added after this enum reached the 50 variants a contract type
+///   can have are in `ExtKey`
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Dataset(Bytes),
+    Purchase(Bytes, Address),
+    StudyDatasets(Bytes),
+    NsDataset(Symbol, Bytes),
+    NsPurchase(Symbol, Bytes, Address),
+    NsIndex(Symbol),
+    PurchaseSeq(Bytes),
+    DatasetLicense(Bytes),
+    LicenseAck(Bytes, Address),
+    JurisdictionReport(Symbol, u64),
+    RelatedDatasets(Bytes),
+    RevenueSplitter,
+    StudyRegistry,
+    Stats,
+    Bridge,
+    Royalty,
+    Inbox,
+    Upgrader,
+    Paused,
+    Schema,
+    AuditLog,
+    Breaker,
+    NsRegistry,
+    LicenseRegistry,
+    Compliance,
+    Guardians,
+    TokenAllowlist,
+    DatasetReview(Bytes),
+    RevocationRefund(Bytes, Address),
+    RevocationPolicy,
+    Admin,
+    DatasetCount,
+    DatasetIndex(u32),
+    BuyerPurchaseCount(Address),
+    BuyerPurchase(Address, u32),
+    DatasetBuyerCount(Bytes),
+    DatasetBuyer(Bytes, u32),
+    DatasetToken(Bytes),
+    PaymentTokens,
+    PriceOracle,
+    DatasetPriceMode(Bytes),
+    AccessDuration(Bytes),
+    AccessGrant(Bytes, Address),
+    Bundle(Bytes),
+    PromoCode(Bytes, BytesN<32>),
+    TimedDiscount(Bytes),
+    VolumeTiers(Bytes),
+    RefundPolicy,
+    PurchasePayment(Bytes, Address),
+}
 
-/// Dataset structure
+/// Storage keys added after `DataKey` reached the variant limit
 /// 
-/// Stores dataset information on-chain:
-/// - dataset_id: Unique identifier for the dataset (Bytes)
-/// - study_ids: List of study hashes included in this dataset
-/// - price_usdc: Price in USDC (i128, with 7 decimal places for Stellar)
+/// These keys have no pre-schema-3 form, so they are read directly rather
+/// than through the legacy fallback of the storage helpers:
+/// - Escrow: Escrow configuration in instance storage
+/// - EscrowedPayout: Payouts of purchases held in escrow, by purchase ID,
+///   in persistent storage
+/// - PayoutSequence: RevenueSplitter payout sequence of each paid-out
+///   purchase, by purchase ID, in persistent storage
+/// - LatestVersion / DatasetVersion: Number of a listing's latest version
+///   (1 when absent) and the study set of each version, in persistent
+///   storage
+/// - PurchasedVersion: Version of a listing each buyer last paid for (1
+///   when absent), in persistent storage
+/// - UpgradePrice: Price prior buyers pay for a listing's latest version,
+///   in persistent storage
+/// - Metadata: Descriptive metadata set by a listing's owner, in
+///   persistent storage
+/// - TagCount / TagDataset / TagPosition: Number of listings tagged with
+///   each metadata tag, their IDs by position and each listing's
+///   position, in persistent storage
+/// - AccessPolicy / Allowlisted: Buyers a listing's owner sells to (open
+///   when absent) and the allowlisted buyers, in persistent storage
+/// - CredentialRegistry: Registry credential-gated listings check buyers
+///   against, in instance storage
+/// - AccessPass: Contract minting buyers' access passes, in instance
+///   storage
+/// - Transferable: Whether a listing's owner lets buyers transfer their
+///   licenses, in persistent storage
+/// - PriceDecay: Dutch auction schedule of a listing's price, in
+///   persistent storage
+/// - QueryPrice / QueryCredit / QueryPayout: Per-query price of a
+///   listing, buyers' prepaid queries and payments of consumed queries not
+///   paid out yet, by listing and token, in persistent storage
+/// - QueryGateway: Gateway that reports consumed queries, in instance
+///   storage
+/// - Reputation: ContributorReputation contract dispute outcomes are
+///   reported to, in instance storage
+/// - BuyerPurchasePosition / DatasetBuyerPosition: Position of a listing
+///   in each buyer's purchase index and of a buyer in each listing's buyer
+///   index, in persistent storage (absent for entries indexed before
+///   interface version 40)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExtKey {
+    Escrow,
+    EscrowedPayout(BytesN<32>),
+    PayoutSequence(BytesN<32>),
+    LatestVersion(Bytes),
+    DatasetVersion(Bytes, u32),
+    PurchasedVersion(Bytes, Address),
+    UpgradePrice(Bytes),
+    Metadata(Bytes),
+    TagCount(Symbol),
+    TagDataset(Symbol, u32),
+    TagPosition(Symbol, Bytes),
+    AccessPolicy(Bytes),
+    Allowlisted(Bytes, Address),
+    CredentialRegistry,
+    AccessPass,
+    Transferable(Bytes),
+    PriceDecay(Bytes),
+    QueryPrice(Bytes),
+    QueryCredit(Bytes, Address),
+    QueryPayout(Bytes, Address),
+    QueryGateway,
+    FeeConfig,
+    EmergencyPaused,
+    Reputation,
+    BuyerPurchasePosition(Address, Bytes),
+    DatasetBuyerPosition(Bytes, Address),
+}
+
+impl DataKey {
+    /// Key the same entry was stored under before schema 3
+    fn legacy(&self, env: &Env) -> Val {
+        match self {
+            DataKey::Dataset(dataset_id) => (DATASET_KEY, dataset_id.clone()).into_val(env),
+            DataKey::Purchase(dataset_id, buyer) => {
+                (PURCHASE_KEY, dataset_id.clone(), buyer.clone()).into_val(env)
+            },
+            DataKey::StudyDatasets(study_id) => (STUDY_DATASETS_KEY, study_id.clone()).into_val(env),
+            DataKey::NsDataset(namespace_id, dataset_id) => {
+                (NS_DATASET_KEY, namespace_id.clone(), dataset_id.clone()).into_val(env)
+            },
+            DataKey::NsPurchase(namespace_id, dataset_id, buyer) => {
+                (NS_PURCHASE_KEY, namespace_id.clone(), dataset_id.clone(), buyer.clone()).into_val(env)
+            },
+            DataKey::NsIndex(namespace_id) => (NS_INDEX_KEY, namespace_id.clone()).into_val(env),
+            // Added with schema 3, so there is no older key
+            DataKey::PurchaseSeq(_)
+            | DataKey::DatasetLicense(_)
+            | DataKey::LicenseAck(_, _)
+            | DataKey::JurisdictionReport(_, _)
+            | DataKey::RelatedDatasets(_)
+            | DataKey::LicenseRegistry
+            | DataKey::Compliance
+            | DataKey::Guardians
+            | DataKey::TokenAllowlist
+            | DataKey::DatasetReview(_)
+            | DataKey::RevocationRefund(_, _)
+            | DataKey::RevocationPolicy
+            | DataKey::Admin
+            | DataKey::DatasetCount
+            | DataKey::DatasetIndex(_)
+            | DataKey::BuyerPurchaseCount(_)
+            | DataKey::BuyerPurchase(_, _)
+            | DataKey::DatasetBuyerCount(_)
+            | DataKey::DatasetBuyer(_, _)
+            | DataKey::DatasetToken(_)
+            | DataKey::PaymentTokens
+            | DataKey::PriceOracle
+            | DataKey::DatasetPriceMode(_)
+            | DataKey::AccessDuration(_)
+            | DataKey::AccessGrant(_, _)
+            | DataKey::Bundle(_)
+            | DataKey::PromoCode(_, _)
+            | DataKey::TimedDiscount(_)
+            | DataKey::VolumeTiers(_)
+            | DataKey::RefundPolicy
+            | DataKey::PurchasePayment(_, _) => self.into_val(env),
+            DataKey::RevenueSplitter => REVENUE_SPLITTER_KEY.into_val(env),
+            DataKey::StudyRegistry => STUDY_REGISTRY_KEY.into_val(env),
+            DataKey::Stats => STATS_KEY.into_val(env),
+            DataKey::Bridge => BRIDGE_KEY.into_val(env),
+            DataKey::Royalty => ROYALTY_KEY.into_val(env),
+            DataKey::Inbox => INBOX_KEY.into_val(env),
+            DataKey::Upgrader => UPGRADER_KEY.into_val(env),
+            DataKey::Paused => PAUSED_KEY.into_val(env),
+            DataKey::Schema => SCHEMA_KEY.into_val(env),
+            DataKey::AuditLog => AUDIT_KEY.into_val(env),
+            DataKey::Breaker => BREAKER_KEY.into_val(env),
+            DataKey::NsRegistry => NS_REGISTRY_KEY.into_val(env),
+        }
+    }
+}
+
+/// Configuration entries moved to `DataKey` by the schema 3 migration
+const CONFIG_KEYS: [DataKey; 12] = [
+    DataKey::RevenueSplitter,
+    DataKey::StudyRegistry,
+    DataKey::Stats,
+    DataKey::Bridge,
+    DataKey::Royalty,
+    DataKey::Inbox,
+    DataKey::Upgrader,
+    DataKey::Paused,
+    DataKey::Schema,
+    DataKey::AuditLog,
+    DataKey::Breaker,
+    DataKey::NsRegistry,
+];
+
+/// Dataset layout before listings had an owner
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Dataset {
+pub struct DatasetV1 {
     pub dataset_id: Bytes,
     pub study_ids: Vec<Bytes>,
-    pub price_usdc: I128,
+    pub price_usdc: i128,
 }
 
-/// PurchaseRecord structure
-/// 
-/// Stores purchase information:
-/// - buyer: Address of the researcher who purchased
-/// - dataset_id: ID of the purchased dataset
-/// - tx_hash: Transaction hash of the purchase
+impl DatasetV1 {
+    /// Upgrade to the current layout under `owner`
+    fn with_owner(self, owner: Address) -> Dataset {
+        Dataset {
+            dataset_id: self.dataset_id,
+            study_ids: self.study_ids,
+            price_usdc: self.price_usdc,
+            owner,
+            status: DatasetStatus::Active,
+        }
+    }
+}
+
+/// Dataset layout before listings had a status
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetV2 {
+    pub dataset_id: Bytes,
+    pub study_ids: Vec<Bytes>,
+    pub price_usdc: i128,
+    pub owner: Address,
+}
+
+impl From<DatasetV2> for Dataset {
+    fn from(dataset: DatasetV2) -> Self {
+        Dataset {
+            dataset_id: dataset.dataset_id,
+            study_ids: dataset.study_ids,
+            price_usdc: dataset.price_usdc,
+            owner: dataset.owner,
+            status: DatasetStatus::Active,
+        }
+    }
+}
+
+/// PurchaseRecord layout stored before licenses were bound to sales
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct PurchaseRecord {
+pub struct PurchaseRecordV1 {
     pub buyer: Address,
     pub dataset_id: Bytes,
     pub tx_hash: Bytes,
 }
 
-/// Error types for the contract
+impl From<PurchaseRecordV1> for PurchaseRecord {
+    fn from(record: PurchaseRecordV1) -> Self {
+        PurchaseRecord {
+            buyer: record.buyer,
+            dataset_id: record.dataset_id,
+            tx_hash: record.tx_hash,
+            license_hash: None,
+        }
+    }
+}
+
+/// License bound to a dataset listing
+/// 
+/// - license_id / version: License document in the LicenseRegistry
+/// - document_hash: Hash of that version, copied at binding time (published
+///   versions never change) and acknowledged by buyers before purchasing
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum Error {
-    DatasetNotFound,
-    DatasetAlreadyExists,
-    InvalidPrice,
-    PaymentFailed,
-    InvalidStudyIds,
-    RevenueSplitterNotSet,
-    StudyRegistryNotSet,
-    ContributorLookupFailed,
+pub struct DatasetLicense {
+    pub license_id: Symbol,
+    pub version: u32,
+    pub document_hash: BytesN<32>,
+}
+
+/// How licenses react when a study in the dataset is revoked
+/// 
+/// - license_grace: Seconds licenses of affected datasets stay active
+///   after a revocation (None leaves them untouched; erasures always end
+///   them at once)
+/// - refund_bps: Share of a buyer's prorated price refunded per revoked
+///   study, in basis points
+/// - refund_source: Address refunds are pulled from (e.g. the DaoTreasury),
+///   which approves this contract; required when `refund_bps` is set
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationPolicy {
+    pub license_grace: Option<u64>,
+    pub refund_bps: u32,
+    pub refund_source: Option<Address>,
+}
+
+/// Refunds of purchases disputed by their buyer, e.g. for a corrupt or
+/// mislabeled dataset
+/// 
+/// - window: Seconds after a purchase its buyer can request a refund
+/// - arbiter: Address resolving refund requests
+/// - refund_source: Address the part of a refund not reclaimed from
+///   contributors is pulled from (e.g. the DaoTreasury), which approves
+///   this contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefundPolicy {
+    pub window: u64,
+    pub arbiter: Address,
+    pub refund_source: Address,
+}
+
+/// Descriptive metadata of a dataset listing, so listings can be
+/// rendered without trusting off-chain servers
+/// 
+/// - title: Short title, at most `MAX_TITLE_LEN` bytes
+/// - description_hash: Hash of the full off-chain description
+/// - license_id: License identifier (e.g. an SPDX ID such as
+///   "CC-BY-4.0"), at most `MAX_LICENSE_ID_LEN` bytes
+/// - tags: Modality and disease tags, at most `MAX_METADATA_TAGS` and
+///   without duplicates
+/// - record_count: Number of records in the dataset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetMetadata {
+    pub title: String,
+    pub description_hash: BytesN<32>,
+    pub license_id: String,
+    pub tags: Vec<Symbol>,
+    pub record_count: u64,
+}
+
+/// Buyers a dataset listing can be sold to
+/// 
+/// - Open: Anyone
+/// - Allowlist: Buyers the owner added with `set_allowlisted`
+/// - CredentialGated: Buyers holding a credential of the given type in the
+///   CredentialRegistry (e.g. a verified research institution)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessPolicy {
+    Open,
+    Allowlist,
+    CredentialGated(Symbol),
+}
+
+/// Study set of one version of a dataset listing
+/// 
+/// - changelog_hash: Hash of the off-chain changelog from the previous
+///   version (all zeros for version 1)
+/// - published_at: Ledger timestamp the version was published at (the
+///   time version 2 was published, for version 1)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetVersion {
+    pub version: u32,
+    pub study_ids: Vec<Bytes>,
+    pub changelog_hash: BytesN<32>,
+    pub published_at: u64,
+}
+
+/// Escrow settlement of expensive purchases
+/// 
+/// - escrow: PurchaseEscrow contract holding the funds until the buyer
+///   confirms delivery or the lock period ends
+/// - min_price: Smallest price held in escrow; cheaper purchases are paid
+///   out at once
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowConfig {
+    pub escrow: Address,
+    pub min_price: i128,
+}
+
+/// Platform fee taken from every payment before the contributor split
+/// 
+/// - fee_bps: Fee in basis points of the payment (at most
+///   MAX_PLATFORM_FEE_BPS)
+/// - collector: Address the fee is sent to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub collector: Address,
+}
+
+/// Payout of a purchase held in escrow, made when the escrow releases it
+/// 
+/// - weights: Contributors of the dataset at purchase time, with their
+///   weights
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowedPayout {
+    pub dataset_id: Bytes,
+    pub token: Address,
+    pub weights: Vec<(Address, u32)>,
+    pub amount: i128,
+}
+
+/// State of a buyer's refund request
+/// 
+/// - NotRequested: No refund requested
+/// - Requested: Awaiting the arbiter; the license is suspended
+/// - Approved: Refunded; the license has ended
+/// - Rejected: Denied; the license is active again
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RefundStatus {
+    NotRequested,
+    Requested,
+    Approved,
+    Rejected,
+}
+
+/// Payment of a buyer's latest purchase of a dataset
+/// 
+/// Not recorded for bundle purchases, which cannot be refunded.
+/// - payer: Address the price was pulled from, which a refund returns to
+/// - token: Token the price was paid in
+/// - amount: Price paid
+/// - paid_at: Ledger timestamp of the purchase
+/// - refund: State of the buyer's refund request
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchasePayment {
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub paid_at: u64,
+    pub refund: RefundStatus,
 }
 
-/// Event data for DatasetRegistered event
+/// Dataset flagged for review after studies in it were revoked
+/// 
+/// Flagged datasets can no longer be purchased.
+/// - revoked_studies: Revoked studies of the dataset, in revocation order
+/// - licenses_end_at: Ledger timestamp existing licenses end at, if
+///   shortened
+/// - refund_bps: Refund rate of the policy at the latest revocation
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct DatasetRegisteredEventData {
+pub struct DatasetReview {
     pub dataset_id: Bytes,
-    pub price_usdc: I128,
-    pub study_count: u32,
+    pub revoked_studies: Vec<Bytes>,
+    pub flagged_at: u64,
+    pub licenses_end_at: Option<u64>,
+    pub refund_bps: u32,
 }
 
-/// Event data for DatasetPurchased event
+/// Access to a dataset granted by its purchase
+/// 
+/// Access gateways check it with `has_access` instead of trusting
+/// off-chain records of time-limited licenses.
+/// - granted_at: Ledger timestamp of the latest purchase
+/// - expires_at: Ledger timestamp access ends at (None if unlimited)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct DatasetPurchasedEventData {
+pub struct AccessGrant {
+    pub dataset_id: Bytes,
     pub buyer: Address,
+    pub granted_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+/// Datasets sold together at a single price
+/// 
+/// - dataset_ids: Listings included, each recorded as purchased
+/// - price: USDC paid for the whole bundle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bundle {
+    pub bundle_id: Bytes,
+    pub dataset_ids: Vec<Bytes>,
+    pub price: i128,
+}
+
+/// Discount unlocked by a promo code
+/// 
+/// - discount_bps: Discount on the listed price, in basis points
+/// - expires_at: Ledger timestamp the code stops working at (None if it
+///   does not expire)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromoCode {
+    pub discount_bps: u32,
+    pub expires_at: Option<u64>,
+}
+
+/// Discount applied to every purchase during a time window
+/// 
+/// - discount_bps: Discount on the listed price, in basis points
+/// - starts_at / ends_at: Ledger timestamps the window starts at
+///   (inclusive) and ends at (exclusive)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimedDiscount {
+    pub discount_bps: u32,
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+/// Discount for buyers who have purchased at least `min_purchases`
+/// datasets before (see `purchase_count`)
+/// 
+/// - discount_bps: Discount on the listed price, in basis points
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VolumeTier {
+    pub min_purchases: u32,
+    pub discount_bps: u32,
+}
+
+/// Sales of one jurisdiction in one reporting epoch
+/// 
+/// - jurisdiction: Buyer jurisdiction attested by the compliance registry
+/// - epoch: Reporting epoch (ledger timestamp / REPORT_EPOCH_SECONDS)
+/// - purchases: Number of purchases
+/// - volume: USDC paid for those purchases
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JurisdictionReport {
+    pub jurisdiction: Symbol,
+    pub epoch: u64,
+    pub purchases: u32,
+    pub volume: i128,
+}
+
+/// Dataset as returned by browse queries
+/// 
+/// - dataset: The listing
+/// - related: Datasets the listing is linked to, in linking order
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DatasetListing {
+    pub dataset: Dataset,
+    pub related: Vec<Bytes>,
+}
+
+/// LicenseTerms as returned by LicenseRegistry.get_license()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicenseTerms {
+    pub license_id: Symbol,
+    pub version: u32,
+    pub document_hash: BytesN<32>,
+    pub uri: String,
+    pub published_at: u64,
+}
+
+/// PayoutMode as returned by RevenueSplitter.get_payout_mode()
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SplitterPayoutMode {
+    Push,
+    Accrue,
+}
+
+/// Prepaid queries of a buyer on a pay-per-query listing
+/// 
+/// - token: Token the queries were paid in
+/// - remaining: Queries left to consume
+/// - paid: Payment held for the remaining queries, paid out as they are
+///   consumed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueryCredit {
+    pub token: Address,
+    pub remaining: u32,
+    pub paid: i128,
+}
+
+/// Read-only preview of a purchase for wallets to display
+/// 
+/// - price_usdc: Amount that would be pulled from the buyer
+/// - contributors: Distinct contributors that would be paid
+/// - payout: How RevenueSplitter would pay out the price, after the
+///   platform fee
+/// - balance / allowance: Buyer's USDC balance and allowance for this contract
+/// - already_purchased: Whether the buyer already holds a purchase record
+/// - can_purchase: Whether `purchase_dataset` would currently succeed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchasePreview {
     pub dataset_id: Bytes,
-    pub price_usdc: I128,
+    pub buyer: Address,
+    pub price_usdc: i128,
+    pub contributors: Vec<Address>,
+    pub payout: PayoutBreakdown,
+    pub balance: i128,
+    pub allowance: i128,
+    pub already_purchased: bool,
+    pub can_purchase: bool,
+}
+
+/// NamespaceConfig as returned by NamespaceRegistry.get_config()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamespaceConfig {
+    pub name: String,
+    pub treasury: Address,
+    pub fee_bps: u32,
+}
+
+/// How a listing's `price_usdc` is interpreted
+/// 
+/// - Fixed: Amount of the payment token (7 decimal places for USDC)
+/// - OracleUsd: Price in USD cents, converted into the payment token at
+///   purchase time with the price oracle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceMode {
+    Fixed,
+    OracleUsd,
+}
+
+/// How a decaying listing's price falls to its floor
+/// 
+/// - Linear: Continuously, ledger by ledger
+/// - Stepwise: In the given number of equal drops, each at the end of its
+///   share of the ledger range
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecayCurve {
+    Linear,
+    Stepwise(u32),
+}
+
+/// Dutch auction schedule of a listing's price
+/// 
+/// Prices are in the listing's units, as `price_usdc` (see `PriceMode`).
+/// The price is `start_price` until `start_ledger`, falls along `curve`
+/// to `floor_price` at `end_ledger` and stays at the floor after.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceDecay {
+    pub start_price: i128,
+    pub floor_price: i128,
+    pub start_ledger: u32,
+    pub end_ledger: u32,
+    pub curve: DecayCurve,
+}
+
+/// Versioned wrapper for stored datasets
+/// 
+/// New record layouts are added as new variants so that entries written by
+/// older code can still be decoded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionedDataset {
+    V1(DatasetV1),
+    V2(DatasetV2),
+    V3(Dataset),
+}
+
+/// Versioned wrapper for stored purchase records
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionedPurchase {
+    V1(PurchaseRecordV1),
+    V2(PurchaseRecord),
+}
+
+/// Configuration health of the contract
+/// 
+/// Returned by `health()` so deploy pipelines and monitoring can check the
+/// wiring in a single read:
+/// - splitter_set / registry_set: Whether the RevenueSplitter and
+///   StudyRegistry addresses are configured
+/// - token_set: Whether the RevenueSplitter reports a USDC token
+/// - upgrader_set / audit_log_set: Whether the corresponding contract
+///   address is configured
+/// - paused: Whether purchases are paused (including a tripped breaker)
+/// - schema_version: Schema version of the stored data
+/// - ready: Whether purchases can be served
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Health {
+    pub splitter_set: bool,
+    pub registry_set: bool,
+    pub token_set: bool,
+    pub upgrader_set: bool,
+    pub audit_log_set: bool,
+    pub paused: bool,
+    pub schema_version: u32,
+    pub ready: bool,
+}
+
+/// Error types for the contract
+/// 
+/// DatasetMarketplace owns codes 300-399; StudyRegistry (100-199) and
+/// RevenueSplitter (200-299) errors keep their own codes when a nested
+/// call fails, so callers can tell which contract rejected a purchase.
+/// Codes are stable: never renumber, only append.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    DatasetNotFound = 300,
+    DatasetAlreadyExists = 301,
+    InvalidPrice = 302,
+    PaymentFailed = 303,
+    InvalidStudyIds = 304,
+    RevenueSplitterNotSet = 305,
+    StudyRegistryNotSet = 306,
+    ContributorLookupFailed = 307,
+    Paused = 308,
+    Unauthorized = 309,
+    IncompatibleInterface = 310,
+    NamespaceRegistryNotSet = 311,
+    NamespaceNotFound = 312,
+    NotCurator = 313,
+    Overflow = 314,
+    InvalidAddress = 315,
+    LicenseRegistryNotSet = 316,
+    LicenseNotFound = 317,
+    LicenseNotAcknowledged = 318,
+    InvalidRelatedDataset = 319,
+    TooManyRelatedDatasets = 320,
+    TokenNotAccepted = 321,
+    InvalidRevocationPolicy = 322,
+    DatasetUnderReview = 323,
+    NoRefundDue = 324,
+    AlreadyInitialized = 325,
+    DatasetNotActive = 326,
+    DatasetAlreadyActive = 327,
+    StudyRevoked = 328,
+    StudyLookupFailed = 329,
+    TooManyPaymentTokens = 330,
+    PriceOracleNotSet = 331,
+    PriceUnavailable = 332,
+    StalePrice = 333,
+    InvalidAccessDuration = 334,
+    BundleNotFound = 335,
+    BundleAlreadyExists = 336,
+    InvalidBundle = 337,
+    InvalidDiscount = 338,
+    InvalidPromoCode = 339,
+    RefundsNotEnabled = 340,
+    RefundWindowClosed = 341,
+    RefundAlreadyRequested = 342,
+    RefundPending = 343,
+    RefundNotRequested = 344,
+    EscrowNotSet = 345,
+    EscrowedPayoutNotFound = 346,
+    VersionNotFound = 347,
+    NotPurchased = 348,
+    AlreadyUpToDate = 349,
+}
+
+/// Errors added after `Error` reached the 50 codes a contract error type
+/// can have
+/// 
+/// Functions that can fail with codes of both types return
+/// `soroban_sdk::Error`, which either converts into. Purchases, whose
+/// signatures predate these codes, raise them with `panic_with_error!`;
+/// their typed clients surface them as `InvokeError::Contract(code)`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ExtError {
+    InvalidMetadata = 350,
+    BuyerNotEligible = 351,
+    CredentialRegistryNotSet = 352,
+    RegistryLicenseBound = 353,
+    LicenseNotTransferable = 354,
+    AlreadyLicensed = 355,
+    InvalidPriceDecay = 356,
+    QueriesNotEnabled = 357,
+    InvalidQueryCount = 358,
+    NoQueriesLeft = 359,
+    AccrualRequired = 360,
+    InvalidFee = 361,
 }
 
 #[contract]
@@ -75,63 +1017,137 @@ pub struct DatasetMarketplace;
 
 #[contractimpl]
 impl DatasetMarketplace {
+    /// Initialize the DatasetMarketplace contract
+    /// 
+    /// Configuration setters fail with `Error::Unauthorized` until an
+    /// admin is set, so nobody can wire the contract to their own
+    /// splitter or registry before the deployer does.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to change the configuration
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if an admin is already set
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
+    /// Get the admin address
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` the current admin
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Admin).ok_or(Error::Unauthorized)
+    }
+
+    /// Hand the admin role to another address
+    /// 
+    /// Requires the current admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_admin` - Address taking over the configuration
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn transfer_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        Self::audit(&env, symbol_short!("admin"), Some(new_admin), None);
+        Ok(())
+    }
+
     /// Register a dataset in the marketplace
     /// 
     /// This function allows dataset owners to register their datasets for sale.
     /// 
     /// Requirements:
     /// - dataset_id must be unique (not already registered)
-    /// - study_ids must not be empty
-    /// - price_usdc must be positive
+    /// - study_ids must hold 1 to MAX_STUDIES_PER_CALL studies
+    /// - study_ids must not include studies revoked in the StudyRegistry,
+    ///   once a registry is set
+    /// - price_usdc must not be negative; a price of 0 lists the dataset
+    ///   as open-access (see `is_open_access`)
+    /// - payment_token, if set, must be one of `get_payment_tokens`
     /// 
     /// Storage:
-    /// - Key: ("DATASET", dataset_id)
-    /// - Value: Dataset { dataset_id, study_ids, price_usdc }
+    /// - Key: DataKey::Dataset(dataset_id)
+    /// - Value: Dataset { dataset_id, study_ids, price_usdc, owner, status }
+    /// - Key: DataKey::DatasetToken(dataset_id), if a payment token is set
     /// 
     /// Events:
     /// - Emits DatasetRegistered event
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
+    /// * `owner` - Address managing the listing (must authorize)
     /// * `dataset_id` - Unique identifier for the dataset (Bytes)
     /// * `study_ids` - Vector of study hashes (Vec<Bytes>)
-    /// * `price_usdc` - Price in USDC (i128, 7 decimal places)
+    /// * `price_usdc` - Price in units of the payment token (i128; USDC
+    ///   has 7 decimal places)
+    /// * `payment_token` - Token the listing is paid in (None for the
+    ///   splitter's USDC token)
     /// 
     /// # Returns
     /// * `Ok(())` if successful
+    /// * `Err(Error::StudyRevoked)` if a study was revoked
+    /// * `Err(Error::TokenNotAccepted)` if the payment token is not listed
     /// * `Err(Error)` if validation fails
     pub fn register_dataset(
         env: Env,
+        owner: Address,
         dataset_id: Bytes,
         study_ids: Vec<Bytes>,
-        price_usdc: I128,
+        price_usdc: i128,
+        payment_token: Option<Address>,
     ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        owner.require_auth();
+        
         // ============================================
         // 1. VALIDATE INPUTS
         // ============================================
         
         // Check that dataset_id is not empty
-        if dataset_id.len() == 0 {
+        if dataset_id.is_empty() {
             return Err(Error::DatasetNotFound);
         }
         
-        // Check that study_ids is not empty
-        if study_ids.len() == 0 {
+        // Check that study_ids is not empty and fits in one call
+        if study_ids.is_empty() || study_ids.len() > MAX_STUDIES_PER_CALL {
             return Err(Error::InvalidStudyIds);
         }
         
-        // Check that price is positive
-        if price_usdc <= I128::from(0) {
+        // Check that price is not negative (0 for open-access listings)
+        if price_usdc < 0 {
             return Err(Error::InvalidPrice);
         }
         
+        // Check that the payment token is listed
+        if let Some(token) = payment_token.as_ref() {
+            if !Self::get_payment_tokens(env.clone()).contains(token) {
+                return Err(Error::TokenNotAccepted);
+            }
+        }
+        
+        Self::require_no_revoked_studies(&env, &study_ids)?;
+        
         // ============================================
         // 2. CHECK UNIQUENESS (Prevent duplicates)
         // ============================================
-        let storage = env.storage().instance();
-        let storage_key = (DATASET_KEY, dataset_id.clone());
+        let storage_key = DataKey::Dataset(dataset_id.clone());
         
-        if storage.has(&storage_key) {
+        if Self::has_record(&env, &storage_key) {
             return Err(Error::DatasetAlreadyExists);
         }
         
@@ -142,361 +1158,5750 @@ impl DatasetMarketplace {
             dataset_id: dataset_id.clone(),
             study_ids: study_ids.clone(),
             price_usdc,
+            owner,
+            status: DatasetStatus::Active,
         };
         
-        storage.set(&storage_key, &dataset);
+        Self::set_persistent(&env, &storage_key, &VersionedDataset::V3(dataset));
+        if let Some(token) = payment_token {
+            Self::set_persistent(&env, &DataKey::DatasetToken(dataset_id.clone()), &token);
+        }
+        
+        // Index the dataset under each of its studies for dashboards
+        // (persistent, so the index does not grow the instance entry;
+        // entries under the legacy key are merged in on read)
+        Self::index_studies(&env, &dataset_id, &study_ids);
+        
+        // Append the dataset to the registration-order index for paging
+        let count = Self::dataset_count(env.clone());
+        Self::set_persistent(&env, &DataKey::DatasetIndex(count), &dataset_id);
+        Self::set_persistent(&env, &DataKey::DatasetCount, &(count + 1));
         
         // ============================================
         // 4. EMIT EVENT
         // ============================================
         env.events().publish(
-            (symbol_short!("DatasetRegistered"), dataset_id.clone()),
+            (Symbol::new(&env, "DatasetRegistered"), dataset_id.clone()),
             DatasetRegisteredEventData {
                 dataset_id: dataset_id.clone(),
                 price_usdc,
-                study_count: study_ids.len() as u32,
+                study_count: study_ids.len(),
             },
         );
         
         Ok(())
     }
 
-    /// Purchase a dataset
-    /// 
-    /// This function allows researchers to purchase access to a dataset.
-    /// 
-    /// Flow:
-    /// 1. Verify dataset exists
-    /// 2. Verify payment (mock or real USDC token contract)
-    /// 3. Store PurchaseRecord
-    /// 4. Emit DatasetPurchased event
+    /// Change the price of a dataset listing
     /// 
-    /// Payment:
-    /// - In production, this would use Soroban token interface
-    /// - For now, we use mock payment verification
+    /// Applies to purchases made after the change; existing purchase
+    /// records are untouched. A price of 0 makes the listing open-access.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `dataset_id` - ID of the dataset to purchase
-    /// * `buyer` - Address of the researcher purchasing
+    /// * `dataset_id` - ID of the dataset
+    /// * `price_usdc` - New price in USDC (i128, 7 decimal places)
     /// 
     /// # Returns
-    /// * `Ok(Dataset)` if successful (returns dataset info for RevenueSplitter)
-    /// * `Err(Error)` if validation fails
-    pub fn purchase_dataset(
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidPrice)` if the price is negative
+    pub fn update_price(
         env: Env,
         dataset_id: Bytes,
-        buyer: Address,
-    ) -> Result<Dataset, Error> {
-        // ============================================
-        // 1. LOAD DATASET
-        // ============================================
-        let storage = env.storage().instance();
-        let storage_key = (DATASET_KEY, dataset_id.clone());
-        
-        let dataset: Dataset = storage.get(&storage_key)
-            .ok_or(Error::DatasetNotFound)?;
+        price_usdc: i128,
+    ) -> Result<(), Error> {
+        let mut dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if price_usdc < 0 {
+            return Err(Error::InvalidPrice);
+        }
+        let old_price = dataset.price_usdc;
+        dataset.price_usdc = price_usdc;
+        Self::save_dataset(&env, dataset);
         
-        // ============================================
-        // 2. VERIFY PAYMENT
-        // ============================================
-        // In production, this would:
-        // 1. Get USDC token contract address from storage or env
-        // 2. Verify buyer has authorized payment
-        // 3. Transfer USDC from buyer to contract
-        // 4. Verify transfer succeeded
+        env.events().publish(
+            (symbol_short!("DsPrice"), dataset_id),
+            (old_price, price_usdc),
+        );
+        Ok(())
+    }
+
+    /// Set the descriptive metadata of a dataset listing
+    /// 
+    /// Replaces any metadata set before and moves the listing between the
+    /// tag indexes of `find_datasets_by_tag`. Requires the owner's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `metadata` - Title, description hash, license, tags and record
+    ///   count
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(ExtError::InvalidMetadata)` if the title is empty, a field is
+    ///   too long or a tag is repeated
+    pub fn set_metadata(
+        env: Env,
+        dataset_id: Bytes,
+        metadata: DatasetMetadata,
+    ) -> Result<(), soroban_sdk::Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        Self::require_valid_metadata(&metadata)?;
+        let old_tags = Self::get_metadata(env.clone(), dataset_id.clone())
+            .map_or(Vec::new(&env), |old| old.tags);
+        for tag in old_tags.iter() {
+            if !metadata.tags.contains(&tag) {
+                Self::unindex_tag(&env, &tag, &dataset_id);
+            }
+        }
+        for tag in metadata.tags.iter() {
+            if !old_tags.contains(&tag) {
+                Self::index_tag(&env, &tag, &dataset_id);
+            }
+        }
+        Self::set_ext_persistent(&env, &ExtKey::Metadata(dataset_id.clone()), &metadata);
         
-        // Mock payment verification for now
-        // TODO: Replace with real USDC token contract integration
-        if !Self::verify_payment_mock(&env, &buyer, &dataset.price_usdc) {
-            return Err(Error::PaymentFailed);
+        env.events().publish(
+            (symbol_short!("DsMeta"), dataset_id),
+            (metadata.title, metadata.description_hash),
+        );
+        Ok(())
+    }
+
+    /// Get the descriptive metadata of a dataset listing, if its owner set
+    /// any
+    pub fn get_metadata(env: Env, dataset_id: Bytes) -> Option<DatasetMetadata> {
+        env.storage().persistent().get(&ExtKey::Metadata(dataset_id))
+    }
+
+    /// Set which buyers a dataset listing can be sold to
+    /// 
+    /// Applies to every purchase path from now on; existing purchases are
+    /// untouched. Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `policy` - Open, allowlisted buyers only, or holders of a
+    ///   credential type
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn set_access_policy(env: Env, dataset_id: Bytes, policy: AccessPolicy) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = ExtKey::AccessPolicy(dataset_id.clone());
+        if policy == AccessPolicy::Open {
+            env.storage().persistent().remove(&key);
+        } else {
+            Self::set_ext_persistent(&env, &key, &policy);
         }
         
-        // ============================================
-        // 3. CHECK IF ALREADY PURCHASED
-        // ============================================
-        // Optional: Check if buyer already purchased this dataset
-        // For now, we allow multiple purchases (could be useful for analytics)
+        env.events().publish((symbol_short!("DsPolicy"), dataset_id), policy);
+        Ok(())
+    }
+
+    /// Get which buyers a dataset listing can be sold to
+    pub fn get_access_policy(env: Env, dataset_id: Bytes) -> AccessPolicy {
+        env.storage().persistent()
+            .get(&ExtKey::AccessPolicy(dataset_id))
+            .unwrap_or(AccessPolicy::Open)
+    }
+
+    /// Add buyers to or remove them from a listing's allowlist
+    /// 
+    /// The allowlist only restricts purchases while the listing's policy
+    /// is `AccessPolicy::Allowlist`. Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyers` - Buyers to add or remove
+    /// * `allowed` - Whether to add (true) or remove (false) them
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn set_allowlisted(
+        env: Env,
+        dataset_id: Bytes,
+        buyers: Vec<Address>,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        for buyer in buyers.iter() {
+            let key = ExtKey::Allowlisted(dataset_id.clone(), buyer);
+            if allowed {
+                Self::set_ext_persistent(&env, &key, &true);
+            } else {
+                env.storage().persistent().remove(&key);
+            }
+        }
         
-        // ============================================
-        // 4. CREATE PURCHASE RECORD
-        // ============================================
-        let timestamp = env.ledger().timestamp();
-        let tx_hash = Self::generate_tx_hash(&env, &dataset_id, &buyer, timestamp);
+        env.events().publish((symbol_short!("Allowlist"), dataset_id), (buyers, allowed));
+        Ok(())
+    }
+
+    /// Check if a buyer is on a listing's allowlist
+    pub fn is_allowlisted(env: Env, dataset_id: Bytes, buyer: Address) -> bool {
+        env.storage().persistent().has(&ExtKey::Allowlisted(dataset_id, buyer))
+    }
+
+    /// Check if a listing's access policy lets a buyer purchase it
+    pub fn is_eligible(env: Env, dataset_id: Bytes, buyer: Address) -> bool {
+        Self::eligibility(&env, &dataset_id, &buyer).is_ok()
+    }
+
+    /// Change how a dataset listing is priced
+    /// 
+    /// With `PriceMode::OracleUsd` the price is in USD cents and each
+    /// purchase pays its value in the payment token at the price oracle's
+    /// latest reading; `update_price` then changes the cents. Requires the
+    /// owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `price_mode` - How `price` is interpreted
+    /// * `price` - New price, in token units or USD cents
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidPrice)` if the price is not positive
+    pub fn set_price_mode(
+        env: Env,
+        dataset_id: Bytes,
+        price_mode: PriceMode,
+        price: i128,
+    ) -> Result<(), Error> {
+        let mut dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        dataset.price_usdc = price;
+        Self::save_dataset(&env, dataset);
+        Self::set_persistent(&env, &DataKey::DatasetPriceMode(dataset_id.clone()), &price_mode);
         
-        let purchase = PurchaseRecord {
-            buyer: buyer.clone(),
-            dataset_id: dataset_id.clone(),
-            tx_hash: tx_hash.clone(),
-        };
+        env.events().publish(
+            (symbol_short!("DsPriceMd"), dataset_id),
+            (price_mode, price),
+        );
+        Ok(())
+    }
+
+    /// Set or clear a Dutch auction schedule for a dataset listing
+    /// 
+    /// While set, the schedule's price replaces `price_usdc` and is checked
+    /// on read, so every purchase pays the price of its ledger without
+    /// repricing; discounts apply on top of it. Clearing the schedule
+    /// restores `price_usdc`. Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `decay` - Price schedule (None to clear it)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(ExtError::InvalidPriceDecay)` if the floor is not positive or
+    ///   above the start price, the ledger range is empty, or a stepwise
+    ///   curve has no steps
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn set_price_decay(
+        env: Env,
+        dataset_id: Bytes,
+        decay: Option<PriceDecay>,
+    ) -> Result<(), soroban_sdk::Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = ExtKey::PriceDecay(dataset_id.clone());
+        match &decay {
+            Some(decay) => {
+                if decay.floor_price <= 0
+                    || decay.start_price < decay.floor_price
+                    || decay.end_ledger <= decay.start_ledger
+                    || decay.curve == DecayCurve::Stepwise(0)
+                {
+                    return Err(ExtError::InvalidPriceDecay.into());
+                }
+                Self::set_ext_persistent(&env, &key, decay);
+            },
+            None => env.storage().persistent().remove(&key),
+        }
         
-        // Store purchase record
-        // Key: ("PURCHASE", dataset_id, buyer_address)
-        let purchase_key = (PURCHASE_KEY, dataset_id.clone(), buyer.clone());
-        storage.set(&purchase_key, &purchase);
+        env.events().publish(
+            (symbol_short!("DsDecay"), dataset_id),
+            decay,
+        );
+        Ok(())
+    }
+
+    /// Get the Dutch auction schedule of a dataset listing, if any
+    pub fn get_price_decay(env: Env, dataset_id: Bytes) -> Option<PriceDecay> {
+        env.storage().persistent().get(&ExtKey::PriceDecay(dataset_id))
+    }
+
+    /// Get a dataset's price at the current ledger, in the listing's units
+    /// 
+    /// The schedule's price for listings with a Dutch auction schedule,
+    /// `price_usdc` otherwise; before discounts and, for listings priced in
+    /// USD, before conversion (see `quote_price`).
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the current price
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn get_current_price(env: Env, dataset_id: Bytes) -> Result<i128, Error> {
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        Self::current_price(&env, &dataset)
+    }
+
+    /// Check whether a dataset is listed as open-access
+    /// 
+    /// Open-access listings have a price of 0. Acquiring one is free but
+    /// goes through `purchase_dataset` like a sale: the acquisition is
+    /// recorded, counted by `get_purchase_count` and announced to the
+    /// contributors, so popular open datasets can be rewarded later.
+    /// Discounts, price modes and Dutch auction schedules do not apply.
+    /// 
+    /// # Returns
+    /// * `true` if the dataset is listed at a price of 0
+    pub fn is_open_access(env: Env, dataset_id: Bytes) -> bool {
+        Self::load_dataset(&env, &dataset_id)
+            .is_some_and(|dataset| dataset.price_usdc == 0)
+    }
+
+    /// Add studies to a dataset listing
+    /// 
+    /// Studies already in the listing are skipped. Later purchases pay the
+    /// contributors of the added studies as well.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `study_ids` - Study hashes to add
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` the number of studies in the listing
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidStudyIds)` if `study_ids` is empty or holds more
+    ///   than MAX_STUDIES_PER_CALL studies
+    /// * `Err(Error::StudyRevoked)` if a study was revoked
+    pub fn add_studies(
+        env: Env,
+        dataset_id: Bytes,
+        study_ids: Vec<Bytes>,
+    ) -> Result<u32, Error> {
+        let mut dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if study_ids.is_empty() || study_ids.len() > MAX_STUDIES_PER_CALL {
+            return Err(Error::InvalidStudyIds);
+        }
+        Self::require_no_revoked_studies(&env, &study_ids)?;
+        for study_id in study_ids.iter() {
+            if !dataset.study_ids.contains(&study_id) {
+                dataset.study_ids.push_back(study_id);
+            }
+        }
+        Self::index_studies(&env, &dataset_id, &study_ids);
+        let study_count = dataset.study_ids.len();
+        Self::save_dataset(&env, dataset);
         
-        // ============================================
-        // 5. CALL REVENUE SPLITTER
-        // ============================================
-        // Get contributor addresses from StudyRegistry
-        let contributors = Self::get_contributors_from_studies(&env, &dataset.study_ids)?;
+        env.events().publish(
+            (symbol_short!("DsStudies"), dataset_id),
+            study_count,
+        );
+        Ok(study_count)
+    }
+
+    /// Remove studies from a dataset listing
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `study_ids` - Study hashes to remove
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` the number of studies left in the listing
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidStudyIds)` if `study_ids` is empty, names a
+    ///   study not in the listing, or would leave the listing empty
+    pub fn remove_studies(
+        env: Env,
+        dataset_id: Bytes,
+        study_ids: Vec<Bytes>,
+    ) -> Result<u32, Error> {
+        let mut dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if study_ids.is_empty() {
+            return Err(Error::InvalidStudyIds);
+        }
+        for study_id in study_ids.iter() {
+            let index = dataset.study_ids.first_index_of(&study_id)
+                .ok_or(Error::InvalidStudyIds)?;
+            dataset.study_ids.remove(index);
+        }
+        if dataset.study_ids.is_empty() {
+            return Err(Error::InvalidStudyIds);
+        }
         
-        // Call RevenueSplitter to distribute payouts
-        if contributors.len() > 0 {
-            let revenue_splitter: Address = storage.get(&REVENUE_SPLITTER_KEY)
-                .ok_or(Error::RevenueSplitterNotSet)?;
-            
-            // Call RevenueSplitter.payout_for_dataset()
-            // Using invoke_contract with proper Soroban SDK syntax
-            let _: Result<(), ()> = env.invoke_contract(
-                &revenue_splitter,
-                &symbol_short!("payout_for_dataset"),
-                soroban_sdk::vec![&env, 
-                    dataset_id.clone(),
-                    contributors.clone(),
-                ],
-            );
-            
-            // Note: If the call fails, the entire transaction will revert
-            // This ensures atomicity: purchase only succeeds if payouts succeed
+        let persistent = env.storage().persistent();
+        for study_id in study_ids.iter() {
+            let study_key = DataKey::StudyDatasets(study_id);
+            let mut study_datasets: Vec<Bytes> = persistent.get(&study_key)
+                .unwrap_or(Vec::new(&env));
+            if let Some(index) = study_datasets.first_index_of(&dataset_id) {
+                study_datasets.remove(index);
+                Self::set_persistent(&env, &study_key, &study_datasets);
+            }
         }
+        let study_count = dataset.study_ids.len();
+        Self::save_dataset(&env, dataset);
         
-        // ============================================
-        // 6. EMIT EVENT
-        // ============================================
         env.events().publish(
-            (symbol_short!("DatasetPurchased"), dataset_id.clone()),
-            DatasetPurchasedEventData {
-                buyer: buyer.clone(),
-                dataset_id: dataset_id.clone(),
-                price_usdc: dataset.price_usdc,
-            },
+            (symbol_short!("DsStudies"), dataset_id),
+            study_count,
         );
-        
-        Ok(dataset)
+        Ok(study_count)
+    }
+
+    /// Withdraw a dataset listing from sale
+    /// 
+    /// Existing purchase records and licenses are untouched. Requires the
+    /// owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::DatasetNotActive)` if the dataset is not active
+    pub fn delist_dataset(env: Env, dataset_id: Bytes) -> Result<(), Error> {
+        let dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if dataset.status != DatasetStatus::Active {
+            return Err(Error::DatasetNotActive);
+        }
+        Self::set_status(&env, dataset, DatasetStatus::Delisted);
+        Ok(())
+    }
+
+    /// Suspend a dataset listing, e.g. pending a takedown request
+    /// 
+    /// Requires the admin's authorization. Unlike a delisting, only the
+    /// admin can relist a suspended dataset.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::DatasetNotActive)` if the dataset is already suspended
+    pub fn suspend_dataset(env: Env, dataset_id: Bytes) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        if dataset.status == DatasetStatus::Suspended {
+            return Err(Error::DatasetNotActive);
+        }
+        Self::set_status(&env, dataset, DatasetStatus::Suspended);
+        Ok(())
+    }
+
+    /// Put a delisted or suspended dataset back on sale
+    /// 
+    /// Requires the owner's authorization for delisted datasets and the
+    /// admin's for suspended ones.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::DatasetAlreadyActive)` if the dataset is active
+    pub fn relist_dataset(env: Env, dataset_id: Bytes) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        match dataset.status {
+            DatasetStatus::Active => return Err(Error::DatasetAlreadyActive),
+            DatasetStatus::Delisted => dataset.owner.require_auth(),
+            DatasetStatus::Suspended => Self::require_admin(&env)?,
+        }
+        Self::set_status(&env, dataset, DatasetStatus::Active);
+        Ok(())
+    }
+
+    /// Purchase a dataset
+    /// 
+    /// This function allows researchers to purchase access to a dataset.
+    /// 
+    /// Flow:
+    /// 1. Verify dataset exists (and, for licensed listings, that the buyer
+    ///    co-signs the data use agreement hash)
+    /// 2. Resolve contributors from StudyRegistry
+    /// 3. Pull the dataset price from the buyer
+    /// 4. Store PurchaseRecord
+    /// 5. Forward the price to RevenueSplitter, which pays it out
+    /// 6. Emit DatasetPurchased event
+    /// 
+    /// Payment:
+    /// - Follows the SEP-41 allowance pattern: the buyer first calls
+    ///   `approve` on the listing's payment token (see `get_dataset_token`)
+    ///   with this contract as spender for at least the dataset price
+    /// - Exactly `price_usdc` is pulled with `transfer_from`, so nothing
+    ///   stays in this contract or needs to be pre-funded in RevenueSplitter
+    /// - For listings priced in USD (`PriceMode::OracleUsd`) the amount is
+    ///   converted at the price oracle's latest reading (see `quote_price`)
+    /// 
+    /// Discounts:
+    /// - The listing's promo code, timed discount and volume tier for the
+    ///   buyer are checked, and the largest one applies (they do not stack)
+    /// - A discounted purchase emits a Discount event with the listed and
+    ///   the paid price
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to purchase
+    /// * `buyer` - Address of the researcher purchasing
+    /// * `promo_code` - Promo code to redeem, if any
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if successful (returns dataset info for RevenueSplitter)
+    /// * `Err(Error::InvalidPromoCode)` if the promo code is unknown or
+    ///   expired
+    /// * `Err(Error::PaymentFailed)` if the allowance or balance is too low
+    /// * `Err(Error::PriceOracleNotSet)` if the listing is priced in USD and
+    ///   no oracle is set
+    /// * `Err(Error::PriceUnavailable)` if the oracle does not price the
+    ///   payment token
+    /// * `Err(Error::StalePrice)` if the oracle's latest price is older than
+    ///   `MAX_PRICE_AGE_SECONDS`
+    /// * `Err(Error)` if validation fails
+    pub fn purchase_dataset(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        promo_code: Option<Bytes>,
+    ) -> Result<Dataset, Error> {
+        Self::purchase_at(env, dataset_id, buyer.clone(), buyer, None, promo_code)
+    }
+
+    /// Purchase a dataset on behalf of a buyer
+    /// 
+    /// Same flow as `purchase_dataset`, but the price is pulled from `payer`
+    /// (e.g. the LendingPool fronting a buy-now-pay-later purchase) while
+    /// the purchase record, and so the license, goes to `buyer`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to purchase
+    /// * `buyer` - Address of the researcher receiving the license
+    /// * `payer` - Address the price is pulled from (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if successful
+    /// * `Err(Error::PaymentFailed)` if the payer's allowance or balance is too low
+    /// * `Err(Error)` if validation fails
+    pub fn purchase_dataset_for(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        payer: Address,
+    ) -> Result<Dataset, Error> {
+        Self::purchase_at(env, dataset_id, buyer, payer, None, None)
+    }
+
+    /// Purchase a dataset on behalf of a buyer at a price above the listing
+    /// 
+    /// Same flow as `purchase_dataset_for`, but `price` is pulled and paid
+    /// out instead of the listed price, e.g. the winning bid of a
+    /// SealedAuction. The listed price is the floor.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to purchase
+    /// * `buyer` - Address of the researcher receiving the license
+    /// * `payer` - Address the price is pulled from (must authorize)
+    /// * `price` - USDC to pay, at least the listed price
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if successful
+    /// * `Err(Error::InvalidPrice)` if `price` is below the listed price
+    /// * `Err(Error::PaymentFailed)` if the payer's allowance or balance is too low
+    /// * `Err(Error)` if validation fails
+    pub fn purchase_dataset_at(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        payer: Address,
+        price: i128,
+    ) -> Result<Dataset, Error> {
+        Self::purchase_at(env, dataset_id, buyer, payer, Some(price), None)
+    }
+
+    /// Accept a listing's license terms and purchase it in one call
+    /// 
+    /// Same flow as `purchase_dataset`, but the buyer passes the hash of
+    /// the terms they accept instead of calling `acknowledge_license`
+    /// first. The purchase has the buyer co-sign that hash and records it
+    /// on the PurchaseRecord, as evidence of the data-use agreement
+    /// version they accepted.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to purchase
+    /// * `buyer` - Address of the researcher purchasing
+    /// * `promo_code` - Promo code to redeem, if any
+    /// * `terms_hash` - Hash of the license terms being accepted
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if successful
+    /// * `Err(Error::LicenseNotFound)` if the listing has no license
+    /// * `Err(Error::LicenseNotAcknowledged)` if the hash is not the
+    ///   listing's current license
+    /// * `Err(Error)` if the purchase fails as in `purchase_dataset`
+    pub fn purchase_with_terms(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        promo_code: Option<Bytes>,
+        terms_hash: BytesN<32>,
+    ) -> Result<Dataset, Error> {
+        Self::store_license_ack(&env, &dataset_id, &buyer, terms_hash)?;
+        Self::purchase_at(env, dataset_id, buyer.clone(), buyer, None, promo_code)
+    }
+
+    /// Purchase a dataset at `price`, or at the discounted listed price if
+    /// `None`
+    fn purchase_at(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        payer: Address,
+        price: Option<i128>,
+        promo_code: Option<Bytes>,
+    ) -> Result<Dataset, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        
+        // ============================================
+        // 1. LOAD DATASET
+        // ============================================
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        Self::require_purchasable(&env, &dataset, &buyer)?;
+        // A purchase under dispute is settled before the buyer buys again
+        let payment_key = DataKey::PurchasePayment(dataset_id.clone(), buyer.clone());
+        if Self::get_persistent::<PurchasePayment>(&env, &payment_key)
+            .is_some_and(|payment| payment.refund == RefundStatus::Requested)
+        {
+            return Err(Error::RefundPending);
+        }
+        
+        // The buyer must have acknowledged the listing's current license
+        // and co-signs it as the purchase's data use agreement, even when
+        // someone else pays
+        let license_hash = Self::acknowledged_license(&env, &dataset_id, &buyer)?;
+        if let Some(agreement_hash) = license_hash.as_ref() {
+            buyer.require_auth_for_args((dataset_id.clone(), agreement_hash.clone()).into_val(&env));
+        }
+        
+        // ============================================
+        // 2. RESOLVE CONTRIBUTORS
+        // ============================================
+        // Get contributor addresses and weights from StudyRegistry
+        let weights = Self::get_contributor_weights(&env, &dataset.study_ids)?;
+        
+        // ============================================
+        // 3. COLLECT PAYMENT
+        // ============================================
+        // The payer authorizes the purchase itself; the allowance only
+        // covers the token transfer. An address authorizes a call once, so
+        // a buyer paying for a licensed listing is covered by the
+        // co-signature above.
+        if payer != buyer || license_hash.is_none() {
+            payer.require_auth();
+        }
+        
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        let listed_price = Self::listed_price(&env, &dataset, &payment_token)?;
+        let price = match price {
+            Some(price) if price < listed_price => return Err(Error::InvalidPrice),
+            Some(price) => price,
+            // Open-access listings are free
+            None if listed_price == 0 => 0,
+            None => {
+                let discount_bps = Self::discount_bps(&env, &dataset_id, &buyer, promo_code)?;
+                let (price, _) = pricing::apply_discount(listed_price, discount_bps)
+                    .map_err(|_| Error::Overflow)?;
+                if discount_bps > 0 {
+                    env.events().publish(
+                        (symbol_short!("Discount"), dataset_id.clone(), buyer.clone()),
+                        (listed_price, price, discount_bps),
+                    );
+                }
+                price
+            },
+        };
+        Self::require_payment_token(&env, &dataset_id, &payment_token)?;
+        
+        // Acquiring an open-access listing is free, so nothing is collected
+        // or paid out below; the acquisition is still recorded and
+        // announced like a sale
+        let paid = price > 0;
+        if paid {
+            Self::collect_payment(&env, &payment_token, &payer, price)?;
+        }
+        
+        // ============================================
+        // 4. CREATE PURCHASE RECORD
+        // ============================================
+        let (sequence, purchase_id) = Self::derive_purchase_id(&env, &dataset_id, &buyer)?;
+        let receipt_hash = Self::derive_receipt_hash(&env, &purchase_id, &payer, price)?;
+        Self::record_purchase(&env, &dataset_id, &buyer, &purchase_id, license_hash);
+        if paid {
+            Self::set_persistent(
+                &env,
+                &payment_key,
+                &PurchasePayment {
+                    payer: payer.clone(),
+                    token: payment_token.clone(),
+                    amount: price,
+                    paid_at: env.ledger().timestamp(),
+                    refund: RefundStatus::NotRequested,
+                },
+            );
+        }
+        
+        // ============================================
+        // 5. FORWARD PAYMENT TO REVENUE SPLITTER
+        // ============================================
+        // Expensive purchases are held in escrow and paid out by
+        // `settle_escrow` once released
+        match Self::get_escrow(env.clone()).filter(|config| price >= config.min_price) {
+            Some(config) => Self::hold_in_escrow(
+                &env,
+                &config.escrow,
+                &purchase_id,
+                &buyer,
+                EscrowedPayout {
+                    dataset_id: dataset_id.clone(),
+                    token: payment_token.clone(),
+                    weights: weights.clone(),
+                    amount: price,
+                },
+            ),
+            None if paid => {
+                let sequence = Self::forward_to_splitter(
+                    &env,
+                    &payment_token,
+                    &revenue_splitter,
+                    &dataset_id,
+                    &weights,
+                    price,
+                );
+                Self::record_payout_sequence(&env, &purchase_id, sequence);
+            },
+            None => {},
+        }
+        
+        // Note: If the call fails, the entire transaction will revert
+        // This ensures atomicity: purchase only succeeds if payouts succeed
+        
+        // ============================================
+        // 6. PUSH ANALYTICS
+        // ============================================
+        Self::push_stat(&env, symbol_short!("sales"), 1);
+        Self::push_stat(&env, symbol_short!("volume"), price);
+        Self::report_to_breaker(&env, symbol_short!("purchase"), 1);
+        Self::record_jurisdiction_sale(&env, &buyer, price)?;
+        
+        // ============================================
+        // 7. NOTIFY CONTRIBUTORS
+        // ============================================
+        for (contributor, _) in weights.iter() {
+            Self::notify(&env, &contributor, &dataset_id, price);
+        }
+        
+        // ============================================
+        // 8. RECORD CROSS-CHAIN ATTESTATION
+        // ============================================
+        // If an AccessBridge is configured, record an exportable attestation
+        // of this purchase for relayers to carry to other chains
+        if let Some(bridge) = Self::get_instance::<Address>(&env, &DataKey::Bridge) {
+            let _: Val = env.invoke_contract(
+                &bridge,
+                &Symbol::new(&env, "record_purchase"),
+                (dataset_id.clone(), buyer.clone()).into_val(&env),
+            );
+        }
+        
+        // ============================================
+        // 9. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (Symbol::new(&env, "DatasetPurchased"), dataset_id.clone()),
+            DatasetPurchasedEventData {
+                buyer: buyer.clone(),
+                dataset_id: dataset_id.clone(),
+                price_usdc: price,
+                purchase_id,
+                sequence,
+                receipt_hash,
+            },
+        );
+        
+        Ok(dataset)
+    }
+
+    /// Register a bundle of datasets sold together at one price
+    /// 
+    /// The price is in the splitter's USDC token, whatever the listings
+    /// are priced in, and is typically below the sum of their prices.
+    /// Requires the authorization of every included dataset's owner.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bundle_id` - Unique identifier for the bundle
+    /// * `dataset_ids` - Distinct listed datasets to include (2 to
+    ///   MAX_BUNDLE_DATASETS)
+    /// * `price` - USDC price of the bundle
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::BundleAlreadyExists)` if `bundle_id` is taken
+    /// * `Err(Error::InvalidBundle)` if the datasets are not 2 to
+    ///   MAX_BUNDLE_DATASETS distinct IDs
+    /// * `Err(Error::InvalidPrice)` if `price` is not positive
+    /// * `Err(Error::DatasetNotFound)` if a dataset is not listed
+    pub fn register_bundle(
+        env: Env,
+        bundle_id: Bytes,
+        dataset_ids: Vec<Bytes>,
+        price: i128,
+    ) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        
+        let bundle_key = DataKey::Bundle(bundle_id.clone());
+        if Self::has_persistent(&env, &bundle_key) {
+            return Err(Error::BundleAlreadyExists);
+        }
+        if dataset_ids.len() < 2 || dataset_ids.len() > MAX_BUNDLE_DATASETS {
+            return Err(Error::InvalidBundle);
+        }
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        
+        let mut owners: Vec<Address> = Vec::new(&env);
+        for (i, dataset_id) in dataset_ids.iter().enumerate() {
+            if dataset_ids.first_index_of(&dataset_id) != Some(i as u32) {
+                return Err(Error::InvalidBundle);
+            }
+            let dataset = Self::load_dataset(&env, &dataset_id)
+                .ok_or(Error::DatasetNotFound)?;
+            if !owners.contains(&dataset.owner) {
+                dataset.owner.require_auth();
+                owners.push_back(dataset.owner);
+            }
+        }
+        
+        Self::set_persistent(
+            &env,
+            &bundle_key,
+            &Bundle {
+                bundle_id: bundle_id.clone(),
+                dataset_ids: dataset_ids.clone(),
+                price,
+            },
+        );
+        
+        env.events().publish(
+            (symbol_short!("BundleReg"), bundle_id),
+            (dataset_ids, price),
+        );
+        Ok(())
+    }
+
+    /// Get a bundle by ID
+    /// 
+    /// # Returns
+    /// * `Ok(Bundle)` if found
+    /// * `Err(Error::BundleNotFound)` otherwise
+    pub fn get_bundle(env: Env, bundle_id: Bytes) -> Result<Bundle, Error> {
+        Self::get_persistent(&env, &DataKey::Bundle(bundle_id))
+            .ok_or(Error::BundleNotFound)
+    }
+
+    /// Purchase every dataset of a bundle at the bundle price
+    /// 
+    /// Records a purchase of each included dataset for the buyer, as
+    /// `purchase_dataset` would, and pays the price out once across the
+    /// union of their contributors, each study weighted once. Every
+    /// dataset must be active and its license acknowledged; if any check
+    /// fails, nothing is purchased.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bundle_id` - ID of the bundle to purchase
+    /// * `buyer` - Address of the researcher purchasing (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<BytesN<32>>)` with the purchase ID of each dataset, in
+    ///   bundle order
+    /// * `Err(Error::BundleNotFound)` if the bundle does not exist
+    /// * `Err(Error::PaymentFailed)` if the buyer's allowance or balance is too low
+    /// * `Err(Error)` if validation of an included dataset fails
+    pub fn purchase_bundle(
+        env: Env,
+        bundle_id: Bytes,
+        buyer: Address,
+    ) -> Result<Vec<BytesN<32>>, Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        buyer.require_auth();
+        
+        // ============================================
+        // 1. LOAD DATASETS
+        // ============================================
+        let bundle = Self::get_bundle(env.clone(), bundle_id.clone())?;
+        let mut license_hashes: Vec<Option<BytesN<32>>> = Vec::new(&env);
+        let mut study_ids: Vec<Bytes> = Vec::new(&env);
+        for dataset_id in bundle.dataset_ids.iter() {
+            let dataset = Self::load_dataset(&env, &dataset_id)
+                .ok_or(Error::DatasetNotFound)?;
+            if dataset.status != DatasetStatus::Active {
+                return Err(Error::DatasetNotActive);
+            }
+            if Self::get_dataset_review(env.clone(), dataset_id.clone()).is_some() {
+                return Err(Error::DatasetUnderReview);
+            }
+            Self::require_eligible(&env, &dataset_id, &buyer);
+            license_hashes.push_back(Self::acknowledged_license(&env, &dataset_id, &buyer)?);
+            for study_id in dataset.study_ids.iter() {
+                if !study_ids.contains(&study_id) {
+                    study_ids.push_back(study_id);
+                }
+            }
+        }
+        
+        // ============================================
+        // 2. RESOLVE CONTRIBUTORS AND COLLECT PAYMENT
+        // ============================================
+        let weights = Self::get_contributor_weights(&env, &study_ids)?;
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let usdc_token: Address = env.invoke_contract(
+            &revenue_splitter,
+            &Symbol::new(&env, "get_usdc_token"),
+            Vec::new(&env),
+        );
+        Self::collect_payment(&env, &usdc_token, &buyer, bundle.price)?;
+        
+        // ============================================
+        // 3. CREATE PURCHASE RECORDS
+        // ============================================
+        let mut purchase_ids = Vec::new(&env);
+        for (dataset_id, license_hash) in bundle.dataset_ids.iter().zip(license_hashes.iter()) {
+            let (_, purchase_id) = Self::derive_purchase_id(&env, &dataset_id, &buyer)?;
+            Self::record_purchase(&env, &dataset_id, &buyer, &purchase_id, license_hash);
+            purchase_ids.push_back(purchase_id);
+        }
+        
+        // ============================================
+        // 4. FORWARD PAYMENT TO REVENUE SPLITTER
+        // ============================================
+        Self::forward_to_splitter(
+            &env,
+            &usdc_token,
+            &revenue_splitter,
+            &bundle_id,
+            &weights,
+            bundle.price,
+        );
+        
+        // ============================================
+        // 5. PUSH ANALYTICS AND NOTIFY CONTRIBUTORS
+        // ============================================
+        Self::push_stat(&env, symbol_short!("sales"), bundle.dataset_ids.len().into());
+        Self::push_stat(&env, symbol_short!("volume"), bundle.price);
+        Self::report_to_breaker(&env, symbol_short!("purchase"), 1);
+        Self::record_jurisdiction_sale(&env, &buyer, bundle.price)?;
+        for (contributor, _) in weights.iter() {
+            Self::notify(&env, &contributor, &bundle_id, bundle.price);
+        }
+        
+        env.events().publish(
+            (symbol_short!("BundleBuy"), bundle_id),
+            (buyer, bundle.price, purchase_ids.clone()),
+        );
+        
+        Ok(purchase_ids)
+    }
+
+    /// Publish a new version of a dataset listing
+    /// 
+    /// Replaces the listing's studies; purchases made from now on pay the
+    /// contributors of the new set. The study set of every prior version
+    /// stays queryable with `get_version`, and prior buyers can move to
+    /// the new version with `purchase_upgrade`. Requires the owner's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `new_study_ids` - Studies of the new version
+    /// * `changelog_hash` - Hash of the off-chain changelog
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the number of the new version
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidStudyIds)` if there are no studies
+    /// * `Err(Error::StudyRevoked)` if a study has been revoked
+    pub fn publish_version(
+        env: Env,
+        dataset_id: Bytes,
+        new_study_ids: Vec<Bytes>,
+        changelog_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        Self::require_not_paused(&env)?;
+        let mut dataset = Self::load_owned_dataset(&env, &dataset_id)?;
+        if new_study_ids.is_empty() {
+            return Err(Error::InvalidStudyIds);
+        }
+        Self::require_no_revoked_studies(&env, &new_study_ids)?;
+        
+        // Listings registered before versioning get their current
+        // studies recorded as version 1
+        let now = env.ledger().timestamp();
+        let latest = Self::get_latest_version(env.clone(), dataset_id.clone());
+        if latest == 1 {
+            Self::set_ext_persistent(
+                &env,
+                &ExtKey::DatasetVersion(dataset_id.clone(), 1),
+                &DatasetVersion {
+                    version: 1,
+                    study_ids: dataset.study_ids.clone(),
+                    changelog_hash: BytesN::from_array(&env, &[0u8; 32]),
+                    published_at: now,
+                },
+            );
+        }
+        let version = latest.checked_add(1).ok_or(Error::Overflow)?;
+        Self::set_ext_persistent(
+            &env,
+            &ExtKey::DatasetVersion(dataset_id.clone(), version),
+            &DatasetVersion {
+                version,
+                study_ids: new_study_ids.clone(),
+                changelog_hash: changelog_hash.clone(),
+                published_at: now,
+            },
+        );
+        Self::set_ext_persistent(&env, &ExtKey::LatestVersion(dataset_id.clone()), &version);
+        
+        Self::index_studies(&env, &dataset_id, &new_study_ids);
+        dataset.study_ids = new_study_ids;
+        Self::save_dataset(&env, dataset);
+        
+        env.events().publish(
+            (symbol_short!("DsVersion"), dataset_id),
+            (version, changelog_hash),
+        );
+        Ok(version)
+    }
+
+    /// Get one version of a dataset listing
+    /// 
+    /// # Returns
+    /// * `Ok(DatasetVersion)` with the version's studies
+    /// * `Err(Error::VersionNotFound)` if no such version was published
+    pub fn get_version(env: Env, dataset_id: Bytes, version: u32) -> Result<DatasetVersion, Error> {
+        env.storage()
+            .persistent()
+            .get(&ExtKey::DatasetVersion(dataset_id, version))
+            .ok_or(Error::VersionNotFound)
+    }
+
+    /// Get the number of a dataset listing's latest version (1 if it was
+    /// never versioned)
+    pub fn get_latest_version(env: Env, dataset_id: Bytes) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&ExtKey::LatestVersion(dataset_id))
+            .unwrap_or(1)
+    }
+
+    /// Get the version of a dataset a buyer last paid for
+    /// 
+    /// # Returns
+    /// * `Some(u32)` with the version
+    /// * `None` if the buyer never purchased the dataset
+    pub fn get_purchased_version(env: Env, dataset_id: Bytes, buyer: Address) -> Option<u32> {
+        if !Self::has_record(&env, &DataKey::Purchase(dataset_id.clone(), buyer.clone())) {
+            return None;
+        }
+        let version = env.storage()
+            .persistent()
+            .get(&ExtKey::PurchasedVersion(dataset_id, buyer))
+            .unwrap_or(1);
+        Some(version)
+    }
+
+    /// Set or clear the reduced price prior buyers pay to upgrade to the
+    /// latest version
+    /// 
+    /// The price is in the listing's payment token. Without one, upgrades
+    /// cost the listed price. Requires the owner's authorization.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidPrice)` if the price is not positive
+    pub fn set_upgrade_price(env: Env, dataset_id: Bytes, price: Option<i128>) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = ExtKey::UpgradePrice(dataset_id.clone());
+        match price {
+            Some(price) if price <= 0 => return Err(Error::InvalidPrice),
+            Some(price) => Self::set_ext_persistent(&env, &key, &price),
+            None => env.storage().persistent().remove(&key),
+        }
+        
+        env.events().publish((symbol_short!("UpgPrice"), dataset_id), price);
+        Ok(())
+    }
+
+    /// Get the upgrade price of a dataset listing, if it offers one
+    pub fn get_upgrade_price(env: Env, dataset_id: Bytes) -> Option<i128> {
+        env.storage().persistent().get(&ExtKey::UpgradePrice(dataset_id))
+    }
+
+    /// Upgrade a prior purchase to the latest version of a dataset
+    /// 
+    /// Charges the listing's upgrade price (its listed price if it has
+    /// none) and pays it to the contributors
+    /// of the studies added since the buyer's version (to those of every
+    /// current study if none was added). Records a new purchase and
+    /// renews the buyer's access as `purchase_dataset` would.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the prior buyer (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the version the buyer now holds
+    /// * `Err(Error::NotPurchased)` if the buyer never purchased the dataset
+    /// * `Err(Error::AlreadyUpToDate)` if the buyer holds the latest version
+    /// * `Err(Error::PaymentFailed)` if the buyer's allowance or balance is too low
+    /// * `Err(Error)` if the dataset cannot be purchased
+    pub fn purchase_upgrade(env: Env, dataset_id: Bytes, buyer: Address) -> Result<u32, Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        buyer.require_auth();
+        
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        if dataset.status != DatasetStatus::Active {
+            return Err(Error::DatasetNotActive);
+        }
+        if Self::get_dataset_review(env.clone(), dataset_id.clone()).is_some() {
+            return Err(Error::DatasetUnderReview);
+        }
+        let held = Self::get_purchased_version(env.clone(), dataset_id.clone(), buyer.clone())
+            .ok_or(Error::NotPurchased)?;
+        Self::require_eligible(&env, &dataset_id, &buyer);
+        let latest = Self::get_latest_version(env.clone(), dataset_id.clone());
+        if held >= latest {
+            return Err(Error::AlreadyUpToDate);
+        }
+        let license_hash = Self::acknowledged_license(&env, &dataset_id, &buyer)?;
+        
+        // Only the studies the buyer does not hold yet earn the upgrade
+        let held_studies = Self::get_version(env.clone(), dataset_id.clone(), held)?.study_ids;
+        let mut added: Vec<Bytes> = Vec::new(&env);
+        for study_id in dataset.study_ids.iter() {
+            if !held_studies.contains(&study_id) {
+                added.push_back(study_id);
+            }
+        }
+        let weights = if added.is_empty() {
+            Self::get_contributor_weights(&env, &dataset.study_ids)?
+        } else {
+            Self::get_contributor_weights(&env, &added)?
+        };
+        
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        let price = match Self::get_upgrade_price(env.clone(), dataset_id.clone()) {
+            Some(price) => price,
+            None => Self::listed_price(&env, &dataset, &payment_token)?,
+        };
+        Self::require_payment_token(&env, &dataset_id, &payment_token)?;
+        Self::collect_payment(&env, &payment_token, &buyer, price)?;
+        
+        let (_, purchase_id) = Self::derive_purchase_id(&env, &dataset_id, &buyer)?;
+        Self::record_purchase(&env, &dataset_id, &buyer, &purchase_id, license_hash);
+        Self::forward_to_splitter(
+            &env,
+            &payment_token,
+            &revenue_splitter,
+            &dataset_id,
+            &weights,
+            price,
+        );
+        
+        Self::push_stat(&env, symbol_short!("volume"), price);
+        for (contributor, _) in weights.iter() {
+            Self::notify(&env, &contributor, &dataset_id, price);
+        }
+        
+        env.events().publish(
+            (symbol_short!("Upgrade"), dataset_id, buyer),
+            (held, latest, price, purchase_id),
+        );
+        Ok(latest)
+    }
+
+    /// Set or clear the per-query price of a dataset listing
+    /// 
+    /// A listing with a query price also sells metered access with
+    /// `purchase_queries`, alongside full purchases. Credits already bought
+    /// keep the price they were paid at. Requires the owner's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `price` - Price of one query in the listing's payment token (None
+    ///   to stop selling queries)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidPrice)` if the price is not positive
+    pub fn set_query_price(env: Env, dataset_id: Bytes, price: Option<i128>) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = ExtKey::QueryPrice(dataset_id.clone());
+        match price {
+            Some(price) if price <= 0 => return Err(Error::InvalidPrice),
+            Some(price) => Self::set_ext_persistent(&env, &key, &price),
+            None => env.storage().persistent().remove(&key),
+        }
+        
+        env.events().publish(
+            (symbol_short!("DsQuery"), dataset_id),
+            price,
+        );
+        Ok(())
+    }
+
+    /// Get the per-query price of a dataset listing, if it sells queries
+    pub fn get_query_price(env: Env, dataset_id: Bytes) -> Option<i128> {
+        env.storage().persistent().get(&ExtKey::QueryPrice(dataset_id))
+    }
+
+    /// Buy metered access to a dataset, paid per query
+    /// 
+    /// Pulls `n_queries` times the query price from the buyer, who must be
+    /// eligible under the listing's access policy and have acknowledged its
+    /// license, if any. The payment stays in this contract until the
+    /// queries are consumed (see `consume_query`), so contributors earn
+    /// only for queries actually run. Query sales need the RevenueSplitter
+    /// in `PayoutMode::Accrue`, so each query's small payout is credited
+    /// to contributors rather than transferred. Requires the buyer's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the researcher buying queries
+    /// * `n_queries` - Number of queries to buy
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the buyer's remaining queries
+    /// * `Err(ExtError::QueriesNotEnabled)` if the listing has no query
+    ///   price
+    /// * `Err(ExtError::InvalidQueryCount)` if `n_queries` is zero
+    /// * `Err(ExtError::AccrualRequired)` if the RevenueSplitter pays out
+    ///   in `PayoutMode::Push`
+    /// * `Err(Error::TokenNotAccepted)` if the buyer's remaining queries
+    ///   were paid in another token than the listing's current one
+    /// * `Err(Error::PaymentFailed)` if the allowance or balance is too low
+    pub fn purchase_queries(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        n_queries: u32,
+    ) -> Result<u32, soroban_sdk::Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        buyer.require_auth();
+        
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        if dataset.status != DatasetStatus::Active {
+            return Err(Error::DatasetNotActive.into());
+        }
+        if Self::get_dataset_review(env.clone(), dataset_id.clone()).is_some() {
+            return Err(Error::DatasetUnderReview.into());
+        }
+        let query_price = Self::get_query_price(env.clone(), dataset_id.clone())
+            .ok_or(ExtError::QueriesNotEnabled)?;
+        if n_queries == 0 {
+            return Err(ExtError::InvalidQueryCount.into());
+        }
+        Self::eligibility(&env, &dataset_id, &buyer)?;
+        Self::acknowledged_license(&env, &dataset_id, &buyer)?;
+        
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let mode: SplitterPayoutMode = env.invoke_contract(
+            &revenue_splitter,
+            &Symbol::new(&env, "get_payout_mode"),
+            Vec::new(&env),
+        );
+        if mode != SplitterPayoutMode::Accrue {
+            return Err(ExtError::AccrualRequired.into());
+        }
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        Self::require_payment_token(&env, &dataset_id, &payment_token)?;
+        
+        let key = ExtKey::QueryCredit(dataset_id.clone(), buyer.clone());
+        let mut credit = env.storage().persistent()
+            .get::<_, QueryCredit>(&key)
+            .filter(|credit| credit.remaining > 0)
+            .unwrap_or(QueryCredit { token: payment_token.clone(), remaining: 0, paid: 0 });
+        if credit.token != payment_token {
+            return Err(Error::TokenNotAccepted.into());
+        }
+        let total = pricing::math::checked_mul(query_price, n_queries as i128)
+            .map_err(|_| Error::Overflow)?;
+        Self::collect_payment(&env, &payment_token, &buyer, total)?;
+        credit.remaining = credit.remaining.checked_add(n_queries).ok_or(Error::Overflow)?;
+        credit.paid = pricing::math::checked_add(credit.paid, total)
+            .map_err(|_| Error::Overflow)?;
+        Self::set_ext_persistent(&env, &key, &credit);
+        
+        env.events().publish(
+            (symbol_short!("QueryBuy"), dataset_id, buyer),
+            (n_queries, total, credit.remaining),
+        );
+        Ok(credit.remaining)
+    }
+
+    /// Consume one of a buyer's prepaid queries
+    /// 
+    /// Called by the query gateway before it runs a query. The query's
+    /// share of the payment held for the buyer is paid out through the
+    /// RevenueSplitter to the contributors of the dataset's current
+    /// studies. A share too small for the splitter to split among them is
+    /// held, with those of earlier queries in the same token, until the
+    /// total can be paid out (see `get_pending_query_payout`). Requires
+    /// the gateway's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the researcher running the query
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the buyer's remaining queries
+    /// * `Err(Error::Unauthorized)` if no query gateway is set
+    /// * `Err(ExtError::NoQueriesLeft)` if the buyer has no queries left
+    pub fn consume_query(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<u32, soroban_sdk::Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        let gateway = Self::get_query_gateway(env.clone())
+            .ok_or(Error::Unauthorized)?;
+        gateway.require_auth();
+        
+        let key = ExtKey::QueryCredit(dataset_id.clone(), buyer.clone());
+        let mut credit = env.storage().persistent()
+            .get::<_, QueryCredit>(&key)
+            .filter(|credit| credit.remaining > 0)
+            .ok_or(ExtError::NoQueriesLeft)?;
+        // Queries bought at different prices are paid out at their
+        // average; the last query takes the rounding remainder
+        let amount = credit.paid / credit.remaining as i128;
+        credit.remaining -= 1;
+        credit.paid -= amount;
+        if credit.remaining == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            Self::set_ext_persistent(&env, &key, &credit);
+        }
+        
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        let weights = Self::get_contributor_weights(&env, &dataset.study_ids)?;
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payout_key = ExtKey::QueryPayout(dataset_id.clone(), credit.token.clone());
+        let pending = pricing::math::checked_add(
+            Self::get_pending_query_payout(env.clone(), dataset_id.clone(), credit.token.clone()),
+            amount,
+        ).map_err(|_| Error::Overflow)?;
+        let (_, net_amount) = Self::split_platform_fee(&env, pending);
+        let splittable = matches!(
+            env.try_invoke_contract::<PayoutBreakdown, soroban_sdk::Error>(
+                &revenue_splitter,
+                &Symbol::new(&env, "preview_weighted_payout"),
+                (dataset_id.clone(), weights.clone(), net_amount).into_val(&env),
+            ),
+            Ok(Ok(_))
+        );
+        if splittable {
+            env.storage().persistent().remove(&payout_key);
+            Self::forward_to_splitter(
+                &env,
+                &credit.token,
+                &revenue_splitter,
+                &dataset_id,
+                &weights,
+                pending,
+            );
+        } else {
+            Self::set_ext_persistent(&env, &payout_key, &pending);
+        }
+        
+        env.events().publish(
+            (symbol_short!("QueryUse"), dataset_id, buyer),
+            (credit.remaining, amount),
+        );
+        Ok(credit.remaining)
+    }
+
+    /// Get the payment of consumed queries on a dataset not paid out yet
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `token` - Token the queries were paid in
+    /// 
+    /// # Returns
+    /// * The amount held until later queries add up to a payout (0 if
+    ///   none)
+    pub fn get_pending_query_payout(env: Env, dataset_id: Bytes, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&ExtKey::QueryPayout(dataset_id, token))
+            .unwrap_or(0)
+    }
+
+    /// Get a buyer's prepaid queries on a dataset, if any are left
+    pub fn get_query_credit(env: Env, dataset_id: Bytes, buyer: Address) -> Option<QueryCredit> {
+        env.storage().persistent().get(&ExtKey::QueryCredit(dataset_id, buyer))
+    }
+
+    /// Allow or forbid buyers to transfer their licenses to a dataset
+    /// 
+    /// Licenses are not transferable unless the owner allows it. Requires
+    /// the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `transferable` - Whether `transfer_license` is allowed
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn set_transferable(env: Env, dataset_id: Bytes, transferable: bool) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = ExtKey::Transferable(dataset_id.clone());
+        if transferable {
+            Self::set_ext_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        
+        env.events().publish(
+            (symbol_short!("DsXfer"), dataset_id),
+            transferable,
+        );
+        Ok(())
+    }
+
+    /// Check whether licenses to a dataset can be transferred
+    pub fn is_transferable(env: Env, dataset_id: Bytes) -> bool {
+        env.storage()
+            .persistent()
+            .get(&ExtKey::Transferable(dataset_id))
+            .unwrap_or(false)
+    }
+
+    /// Transfer a license to a dataset to another researcher
+    /// 
+    /// Moves the purchase record, its entries in the buyer and listing
+    /// indexes, the access grant with its current expiry, the purchased
+    /// version and the access pass from `from` to `to`. The
+    /// recipient pays the resale royalty from the RoyaltyRegistry on the
+    /// listed price (see `quote_resale_royalty`), which is paid out through
+    /// the RevenueSplitter to the contributors of the dataset's current
+    /// studies. The recipient must be eligible under the listing's access
+    /// policy and have acknowledged its license, if any. Refunds cannot be
+    /// requested for transferred licenses. Requires both parties'
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `from` - Current license holder
+    /// * `to` - Researcher receiving the license
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the royalty paid (0 without a RoyaltyRegistry)
+    /// * `Err(ExtError::LicenseNotTransferable)` if the owner forbids
+    ///   transfers
+    /// * `Err(Error::NotPurchased)` if `from` has no active license
+    /// * `Err(ExtError::AlreadyLicensed)` if `to` already has an active
+    ///   license or is `from`
+    /// * `Err(ExtError::BuyerNotEligible)` if the access policy excludes
+    ///   `to`
+    /// * `Err(Error::PaymentFailed)` if `to` cannot pay the royalty
+    pub fn transfer_license(
+        env: Env,
+        dataset_id: Bytes,
+        from: Address,
+        to: Address,
+    ) -> Result<i128, soroban_sdk::Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        if from == to {
+            return Err(ExtError::AlreadyLicensed.into());
+        }
+        from.require_auth();
+        to.require_auth();
+        
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        if !Self::is_transferable(env.clone(), dataset_id.clone()) {
+            return Err(ExtError::LicenseNotTransferable.into());
+        }
+        if !Self::is_license_active(env.clone(), dataset_id.clone(), from.clone()) {
+            return Err(Error::NotPurchased.into());
+        }
+        if Self::is_license_active(env.clone(), dataset_id.clone(), to.clone()) {
+            return Err(ExtError::AlreadyLicensed.into());
+        }
+        Self::eligibility(&env, &dataset_id, &to)?;
+        let license_hash = Self::acknowledged_license(&env, &dataset_id, &to)?;
+        
+        // Collect the royalty before moving anything
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        let listed_price = Self::listed_price(&env, &dataset, &payment_token)?;
+        let royalty = Self::quote_resale_royalty(env.clone(), dataset_id.clone(), listed_price)?;
+        if royalty > 0 {
+            let weights = Self::get_contributor_weights(&env, &dataset.study_ids)?;
+            Self::collect_payment(&env, &payment_token, &to, royalty)?;
+            Self::forward_to_splitter(
+                &env,
+                &payment_token,
+                &revenue_splitter,
+                &dataset_id,
+                &weights,
+                royalty,
+            );
+        }
+        
+        // Move the license
+        let purchase = Self::load_purchase(&env, &dataset_id, &from)
+            .ok_or(Error::NotPurchased)?;
+        Self::remove_record(&env, &DataKey::Purchase(dataset_id.clone(), from.clone()));
+        Self::unindex_buyer_purchase(&env, &from, &dataset_id);
+        Self::unindex_dataset_buyer(&env, &dataset_id, &from);
+        Self::save_purchase(&env, &PurchaseRecord {
+            buyer: to.clone(),
+            dataset_id: dataset_id.clone(),
+            tx_hash: purchase.tx_hash.clone(),
+            license_hash: license_hash.map(Into::into),
+        });
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::PurchasePayment(dataset_id.clone(), from.clone()));
+        persistent.remove(&DataKey::PurchasePayment(dataset_id.clone(), to.clone()));
+        
+        let grant = Self::get_access_grant(env.clone(), dataset_id.clone(), from.clone());
+        persistent.remove(&DataKey::AccessGrant(dataset_id.clone(), from.clone()));
+        let expires_at = grant.as_ref().and_then(|grant| grant.expires_at);
+        let to_grant_key = DataKey::AccessGrant(dataset_id.clone(), to.clone());
+        match grant {
+            Some(grant) => Self::set_persistent(&env, &to_grant_key, &AccessGrant {
+                buyer: to.clone(),
+                granted_at: env.ledger().timestamp(),
+                ..grant
+            }),
+            None => persistent.remove(&to_grant_key),
+        }
+        
+        let from_version_key = ExtKey::PurchasedVersion(dataset_id.clone(), from.clone());
+        let to_version_key = ExtKey::PurchasedVersion(dataset_id.clone(), to.clone());
+        match persistent.get::<_, u32>(&from_version_key) {
+            Some(version) => {
+                persistent.remove(&from_version_key);
+                Self::set_ext_persistent(&env, &to_version_key, &version);
+            },
+            None => persistent.remove(&to_version_key),
+        }
+        
+        if let Some(access_pass) = Self::get_access_pass(env.clone()) {
+            let passes = AccessPassClient::new(&env, &access_pass);
+            passes.burn(&dataset_id, &from);
+            passes.mint(&dataset_id, &to, &expires_at);
+        }
+        
+        env.events().publish(
+            (symbol_short!("LicXfer"), dataset_id, from),
+            (to, royalty, purchase.tx_hash),
+        );
+        Ok(royalty)
+    }
+
+    /// Preview a purchase without changing any state
+    /// 
+    /// Walks the same steps as `purchase_dataset`: resolves contributors
+    /// from StudyRegistry, asks RevenueSplitter for the payout breakdown
+    /// (split, royalties and insurance premiums) and checks the buyer's
+    /// balance and allowance. No funds move and nothing is stored.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to preview
+    /// * `buyer` - Address of the researcher purchasing
+    /// 
+    /// # Returns
+    /// * `Ok(PurchasePreview)` describing the purchase
+    /// * `Err(Error)` if the dataset or a dependency is missing
+    pub fn simulate_purchase(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<PurchasePreview, Error> {
+        // ============================================
+        // 1. LOAD DATASET AND CONTRIBUTORS
+        // ============================================
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        let weights = Self::get_contributor_weights(&env, &dataset.study_ids)?;
+        let mut contributors = Vec::new(&env);
+        for (contributor, _) in weights.iter() {
+            contributors.push_back(contributor);
+        }
+        
+        // ============================================
+        // 2. PREVIEW PAYOUT
+        // ============================================
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        let listed_price = Self::listed_price(&env, &dataset, &payment_token)?;
+        let discount_bps = Self::discount_bps(&env, &dataset_id, &buyer, None)?;
+        let (price, _) = pricing::apply_discount(listed_price, discount_bps)
+            .map_err(|_| Error::Overflow)?;
+        let (_, net_amount) = Self::split_platform_fee(&env, price);
+        let payout: PayoutBreakdown = env.invoke_contract(
+            &revenue_splitter,
+            &Symbol::new(&env, "preview_weighted_payout"),
+            (dataset_id.clone(), weights, net_amount).into_val(&env),
+        );
+        
+        // ============================================
+        // 3. CHECK BUYER FUNDS
+        // ============================================
+        let token_client = token::Client::new(&env, &payment_token);
+        let balance = token_client.balance(&buyer);
+        let allowance = token_client.allowance(&buyer, &env.current_contract_address());
+        
+        let can_purchase = !Self::is_paused(env.clone())
+            && !Self::is_emergency_paused(env.clone())
+            && dataset.status == DatasetStatus::Active
+            && Self::get_dataset_review(env.clone(), dataset_id.clone()).is_none()
+            && Self::require_payment_token(&env, &dataset_id, &payment_token).is_ok()
+            && Self::acknowledged_license(&env, &dataset_id, &buyer).is_ok()
+            && Self::eligibility(&env, &dataset_id, &buyer).is_ok()
+            && balance >= price
+            && allowance >= price;
+        
+        Ok(PurchasePreview {
+            already_purchased: Self::load_purchase(&env, &dataset_id, &buyer).is_some(),
+            dataset_id,
+            buyer,
+            price_usdc: price,
+            contributors,
+            payout,
+            balance,
+            allowance,
+            can_purchase,
+        })
+    }
+
+    /// Get the number of datasets registered with `register_dataset`
+    /// 
+    /// Datasets listed before the index existed and namespaced datasets
+    /// are not counted.
+    pub fn dataset_count(env: Env) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::DatasetCount)
+            .unwrap_or(0)
+    }
+
+    /// Page through datasets in registration order
+    /// 
+    /// Delisted and suspended datasets are included; check their status.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `start` - Position of the first dataset (0-based)
+    /// * `limit` - Maximum number of datasets (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * Datasets in registration order (empty past the end)
+    pub fn list_datasets(env: Env, start: u32, limit: u32) -> Vec<Dataset> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::dataset_count(env.clone()));
+        let persistent = env.storage().persistent();
+        
+        let mut datasets = Vec::new(&env);
+        for position in start..end {
+            let dataset = persistent.get::<_, Bytes>(&DataKey::DatasetIndex(position))
+                .and_then(|dataset_id| Self::load_dataset(&env, &dataset_id));
+            if let Some(dataset) = dataset {
+                datasets.push_back(dataset);
+            }
+        }
+        datasets
+    }
+
+    /// Get the number of listings tagged with `tag`
+    pub fn tag_dataset_count(env: Env, tag: Symbol) -> u32 {
+        env.storage().persistent()
+            .get(&ExtKey::TagCount(tag))
+            .unwrap_or(0)
+    }
+
+    /// Page through the IDs of listings tagged with `tag`
+    /// 
+    /// Listings are in no particular order: removing a tag moves the last
+    /// listing into its place. Delisted and suspended datasets are
+    /// included; check their status.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `tag` - Metadata tag to search by
+    /// * `start` - Position of the first dataset (0-based)
+    /// * `limit` - Maximum number of datasets (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * Dataset IDs (empty past the end)
+    pub fn find_datasets_by_tag(env: Env, tag: Symbol, start: u32, limit: u32) -> Vec<Bytes> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::tag_dataset_count(env.clone(), tag.clone()));
+        let persistent = env.storage().persistent();
+        
+        let mut dataset_ids = Vec::new(&env);
+        for position in start..end {
+            if let Some(dataset_id) = persistent.get(&ExtKey::TagDataset(tag.clone(), position)) {
+                dataset_ids.push_back(dataset_id);
+            }
+        }
+        dataset_ids
+    }
+
+    /// Get a dataset by ID
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to retrieve
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if found
+    /// * `Err(Error::DatasetNotFound)` if not found
+    pub fn get_dataset(
+        env: Env,
+        dataset_id: Bytes,
+    ) -> Result<Dataset, Error> {
+        Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)
+    }
+
+    /// Check if a dataset exists
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset to check
+    /// 
+    /// # Returns
+    /// * `true` if dataset exists, `false` otherwise
+    pub fn dataset_exists(
+        env: Env,
+        dataset_id: Bytes,
+    ) -> bool {
+        Self::has_record(&env, &DataKey::Dataset(dataset_id))
+    }
+
+    /// Get the datasets that include a study
+    /// 
+    /// Datasets registered before the study index existed are not listed.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_id` - Hash of the study
+    /// 
+    /// # Returns
+    /// * Dataset IDs in registration order (empty if none)
+    pub fn get_study_datasets(
+        env: Env,
+        study_id: Bytes,
+    ) -> Vec<Bytes> {
+        Self::get_index(&env, &DataKey::StudyDatasets(study_id))
+    }
+
+    /// Browse the datasets that include a study
+    /// 
+    /// Like `get_study_datasets`, but returns each listing together with
+    /// its related datasets so frontends can cross-promote them.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_id` - Hash of the study
+    /// 
+    /// # Returns
+    /// * Listings in registration order (empty if none)
+    pub fn browse_study_datasets(
+        env: Env,
+        study_id: Bytes,
+    ) -> Vec<DatasetListing> {
+        let mut listings = Vec::new(&env);
+        for dataset_id in Self::get_study_datasets(env.clone(), study_id).iter() {
+            if let Some(dataset) = Self::load_dataset(&env, &dataset_id) {
+                listings.push_back(DatasetListing {
+                    dataset,
+                    related: Self::get_related_datasets(env.clone(), dataset_id),
+                });
+            }
+        }
+        listings
+    }
+
+    /// Get purchase record for a buyer and dataset
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the buyer
+    /// 
+    /// # Returns
+    /// * `Ok(PurchaseRecord)` if found
+    /// * `Err(Error::DatasetNotFound)` if not found
+    pub fn get_purchase(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<PurchaseRecord, Error> {
+        Self::load_purchase(&env, &dataset_id, &buyer)
+            .ok_or(Error::DatasetNotFound)
+    }
+
+    /// Get the number of datasets a buyer purchased
+    /// 
+    /// Repeat purchases of a dataset count once. Purchases made before the
+    /// buyer index existed and namespaced purchases are not counted.
+    pub fn purchase_count(env: Env, buyer: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::BuyerPurchaseCount(buyer))
+            .unwrap_or(0)
+    }
+
+    /// Page through a buyer's purchase records, oldest first
+    /// 
+    /// A dataset bought again appears once, at its first purchase, with
+    /// the latest purchase record. A license transferred away leaves the
+    /// index, and the buyer's latest purchase takes its position.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - Address of the buyer
+    /// * `start` - Position of the first purchase (0-based)
+    /// * `limit` - Maximum number of records (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * Purchase records in purchase order (empty past the end)
+    pub fn get_purchases_for_buyer(
+        env: Env,
+        buyer: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<PurchaseRecord> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::purchase_count(env.clone(), buyer.clone()));
+        let persistent = env.storage().persistent();
+        
+        let mut purchases = Vec::new(&env);
+        for position in start..end {
+            let purchase = persistent
+                .get::<_, Bytes>(&DataKey::BuyerPurchase(buyer.clone(), position))
+                .and_then(|dataset_id| Self::load_purchase(&env, &dataset_id, &buyer));
+            if let Some(purchase) = purchase {
+                purchases.push_back(purchase);
+            }
+        }
+        purchases
+    }
+
+    /// Get the number of distinct buyers of a dataset
+    /// 
+    /// Unlike `get_purchase_count`, repeat purchases by the same buyer
+    /// count once. Purchases made before the buyer index existed and
+    /// namespaced purchases are not counted.
+    pub fn dataset_sales_count(env: Env, dataset_id: Bytes) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::DatasetBuyerCount(dataset_id))
+            .unwrap_or(0)
+    }
+
+    /// Page through the buyers of a dataset, by first purchase
+    /// 
+    /// A buyer who transferred their license leaves the index, and the
+    /// latest buyer takes their position; the recipient is listed as a
+    /// new buyer.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `start` - Position of the first buyer (0-based)
+    /// * `limit` - Maximum number of buyers (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * Buyer addresses in purchase order (empty past the end)
+    pub fn get_buyers_for_dataset(
+        env: Env,
+        dataset_id: Bytes,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::dataset_sales_count(env.clone(), dataset_id.clone()));
+        let persistent = env.storage().persistent();
+        
+        let mut buyers = Vec::new(&env);
+        for position in start..end {
+            if let Some(buyer) = persistent.get(&DataKey::DatasetBuyer(dataset_id.clone(), position)) {
+                buyers.push_back(buyer);
+            }
+        }
+        buyers
+    }
+
+    /// Get the data use agreement a buyer signed for a purchase
+    /// 
+    /// Purchases of licensed listings carry the buyer's signature over
+    /// `(dataset_id, document_hash)` in their authorization payload; the
+    /// signed hash is kept on the PurchaseRecord for legal audits.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the buyer
+    /// 
+    /// # Returns
+    /// * `Ok(Some(hash))` the agreement document hash the buyer signed
+    /// * `Ok(None)` if the listing had no license bound at purchase
+    /// * `Err(Error::DatasetNotFound)` if there is no such purchase
+    pub fn get_signed_agreement(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<Option<BytesN<32>>, Error> {
+        let purchase = Self::get_purchase(env, dataset_id, buyer)?;
+        Ok(purchase.license_hash.and_then(|hash| hash.try_into().ok()))
+    }
+
+    /// Get the number of purchases made of a dataset
+    /// 
+    /// Counts shared and namespaced purchases alike. The next purchase of
+    /// the dataset uses this value as its sequence, so back-office systems
+    /// can precompute its purchase ID.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * Number of purchases so far (0 if never purchased)
+    pub fn get_purchase_count(env: Env, dataset_id: Bytes) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::PurchaseSeq(dataset_id))
+            .unwrap_or(0)
+    }
+
+    /// Register a dataset in a white-label namespace
+    /// 
+    /// Same requirements as `register_dataset` (a price of 0 lists the
+    /// dataset as open-access), but the dataset is scoped to the namespace: the same dataset_id may exist in several
+    /// namespaces and in the shared marketplace. Only curators of the
+    /// namespace (per NamespaceRegistry) can list datasets in it.
+    /// 
+    /// Storage:
+    /// - Key: DataKey::NsDataset(namespace_id, dataset_id) (persistent)
+    /// - Value: Dataset { dataset_id, study_ids, price_usdc, owner, status }
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Namespace to list the dataset in
+    /// * `curator` - Curator of the namespace (must authorize)
+    /// * `dataset_id` - Identifier of the dataset within the namespace
+    /// * `study_ids` - Vector of study hashes (Vec<Bytes>)
+    /// * `price_usdc` - Price in USDC (i128, 7 decimal places)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotCurator)` if `curator` is not a curator of the namespace
+    /// * `Err(Error)` if validation fails
+    pub fn register_namespaced_dataset(
+        env: Env,
+        namespace_id: Symbol,
+        curator: Address,
+        dataset_id: Bytes,
+        study_ids: Vec<Bytes>,
+        price_usdc: i128,
+    ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        curator.require_auth();
+        
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        if dataset_id.is_empty() {
+            return Err(Error::DatasetNotFound);
+        }
+        if study_ids.is_empty() || study_ids.len() > MAX_STUDIES_PER_CALL {
+            return Err(Error::InvalidStudyIds);
+        }
+        if price_usdc < 0 {
+            return Err(Error::InvalidPrice);
+        }
+        
+        // ============================================
+        // 2. CHECK CURATOR
+        // ============================================
+        Self::require_curator(&env, &namespace_id, &curator)?;
+        
+        // ============================================
+        // 3. CHECK UNIQUENESS AND STORE
+        // ============================================
+        let storage_key = DataKey::NsDataset(namespace_id.clone(), dataset_id.clone());
+        if Self::has_persistent(&env, &storage_key) {
+            return Err(Error::DatasetAlreadyExists);
+        }
+        
+        let dataset = Dataset {
+            dataset_id: dataset_id.clone(),
+            study_ids: study_ids.clone(),
+            price_usdc,
+            owner: curator.clone(),
+            status: DatasetStatus::Active,
+        };
+        Self::set_persistent(&env, &storage_key, &VersionedDataset::V3(dataset));
+        
+        let index_key = DataKey::NsIndex(namespace_id.clone());
+        let mut datasets: Vec<Bytes> = env.storage().persistent().get(&index_key)
+            .unwrap_or(Vec::new(&env));
+        datasets.push_back(dataset_id.clone());
+        Self::set_persistent(&env, &index_key, &datasets);
+        
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (Symbol::new(&env, "DatasetRegistered"), dataset_id.clone(), namespace_id),
+            DatasetRegisteredEventData {
+                dataset_id,
+                price_usdc,
+                study_count: study_ids.len(),
+            },
+        );
+        
+        Ok(())
+    }
+
+    /// Purchase a dataset listed in a white-label namespace
+    /// 
+    /// Follows `purchase_dataset` (same allowance pattern and listing
+    /// checks, with the dataset's access policy and review), except that
+    /// after the platform fee the namespace fee is sent to the namespace
+    /// treasury and only the rest of the price is forwarded to the shared
+    /// RevenueSplitter. Namespaced listings are not flagged by study
+    /// revocations, so one that includes a revoked study cannot be bought.
+    /// Namespaced purchases are not attested to the AccessBridge.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Namespace the dataset is listed in
+    /// * `dataset_id` - ID of the dataset within the namespace
+    /// * `buyer` - Address of the researcher purchasing
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if successful
+    /// * `Err(Error::NamespaceNotFound)` if the namespace does not exist
+    /// * `Err(Error::DatasetNotActive)` if the listing is delisted
+    /// * `Err(Error::DatasetUnderReview)` if the dataset is under review or
+    ///   includes a revoked study
+    /// * `Err(Error)` if validation or payment fails
+    pub fn purchase_namespaced_dataset(
+        env: Env,
+        namespace_id: Symbol,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<Dataset, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        
+        // ============================================
+        // 1. LOAD DATASET AND NAMESPACE
+        // ============================================
+        let dataset = Self::get_namespaced_dataset(
+            env.clone(),
+            namespace_id.clone(),
+            dataset_id.clone(),
+        )?;
+        let config = Self::namespace_config(&env, &namespace_id)?;
+        Self::require_purchasable(&env, &dataset, &buyer)?;
+        
+        // ============================================
+        // 2. RESOLVE CONTRIBUTORS
+        // ============================================
+        let study_registry: Address = Self::get_instance(&env, &DataKey::StudyRegistry)
+            .ok_or(Error::StudyRegistryNotSet)?;
+        let study_registry = StudyRegistryClient::new(&env, &study_registry);
+        if !Self::revoked_studies(&env, &study_registry, &dataset.study_ids).is_empty() {
+            return Err(Error::DatasetUnderReview);
+        }
+        let weights = Self::get_contributor_weights(&env, &dataset.study_ids)?;
+        
+        // ============================================
+        // 3. COLLECT PAYMENT
+        // ============================================
+        buyer.require_auth();
+        
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let usdc_token: Address = env.invoke_contract(
+            &revenue_splitter,
+            &Symbol::new(&env, "get_usdc_token"),
+            Vec::new(&env),
+        );
+        Self::require_accepted_token(&env, &usdc_token)?;
+        
+        // Open-access listings are acquired without payment, as in
+        // `purchase_dataset`
+        let paid = dataset.price_usdc > 0;
+        if paid {
+            Self::collect_payment(&env, &usdc_token, &buyer, dataset.price_usdc)?;
+        }
+        
+        // ============================================
+        // 4. CREATE PURCHASE RECORD
+        // ============================================
+        let (sequence, purchase_id) = Self::derive_purchase_id(&env, &dataset_id, &buyer)?;
+        let receipt_hash = Self::derive_receipt_hash(&env, &purchase_id, &buyer, dataset.price_usdc)?;
+        let purchase = PurchaseRecord {
+            buyer: buyer.clone(),
+            dataset_id: dataset_id.clone(),
+            tx_hash: purchase_id.clone().into(),
+            license_hash: None,
+        };
+        let purchase_key = DataKey::NsPurchase(namespace_id.clone(), dataset_id.clone(), buyer.clone());
+        Self::set_persistent(&env, &purchase_key, &VersionedPurchase::V2(purchase));
+        
+        // ============================================
+        // 5. PAY FEES AND FORWARD THE REST
+        // ============================================
+        // The platform fee comes off the full price, as for a direct
+        // purchase, and the namespace fee off what is left
+        if paid {
+            let amount = Self::pay_platform_fee(&env, &usdc_token, dataset.price_usdc);
+            let fee = pricing::mul_bps(amount, config.fee_bps, pricing::Rounding::Down)
+                .map_err(|_| Error::Overflow)?;
+            if fee > 0 {
+                token::Client::new(&env, &usdc_token).transfer(
+                    &env.current_contract_address(),
+                    &config.treasury,
+                    &fee,
+                );
+            }
+            Self::pay_out(
+                &env,
+                &usdc_token,
+                &revenue_splitter,
+                &dataset_id,
+                &weights,
+                amount - fee,
+            );
+        }
+        
+        // ============================================
+        // 6. PUSH ANALYTICS AND NOTIFY
+        // ============================================
+        Self::push_stat(&env, symbol_short!("sales"), 1);
+        Self::push_stat(&env, symbol_short!("volume"), dataset.price_usdc);
+        Self::report_to_breaker(&env, symbol_short!("purchase"), 1);
+        Self::record_jurisdiction_sale(&env, &buyer, dataset.price_usdc)?;
+        for (contributor, _) in weights.iter() {
+            Self::notify(&env, &contributor, &dataset_id, dataset.price_usdc);
+        }
+        
+        // ============================================
+        // 7. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (Symbol::new(&env, "DatasetPurchased"), dataset_id.clone(), namespace_id),
+            DatasetPurchasedEventData {
+                buyer,
+                dataset_id,
+                price_usdc: dataset.price_usdc,
+                purchase_id,
+                sequence,
+                receipt_hash,
+            },
+        );
+        
+        Ok(dataset)
+    }
+
+    /// Get a dataset listed in a namespace
+    /// 
+    /// # Returns
+    /// * `Ok(Dataset)` if found
+    /// * `Err(Error::DatasetNotFound)` if not found
+    pub fn get_namespaced_dataset(
+        env: Env,
+        namespace_id: Symbol,
+        dataset_id: Bytes,
+    ) -> Result<Dataset, Error> {
+        Self::get_persistent::<Val>(&env, &DataKey::NsDataset(namespace_id, dataset_id))
+            .and_then(|raw| Self::decode_dataset(&env, &raw))
+            .ok_or(Error::DatasetNotFound)
+    }
+
+    /// Withdraw a namespaced dataset listing from sale
+    /// 
+    /// Existing purchase records are untouched. Requires the authorization
+    /// of a curator of the namespace.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Namespace the dataset is listed in
+    /// * `curator` - Curator of the namespace (must authorize)
+    /// * `dataset_id` - ID of the dataset within the namespace
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotCurator)` if `curator` is not a curator of the namespace
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::DatasetNotActive)` if the dataset is not active
+    pub fn delist_namespaced_dataset(
+        env: Env,
+        namespace_id: Symbol,
+        curator: Address,
+        dataset_id: Bytes,
+    ) -> Result<(), Error> {
+        curator.require_auth();
+        Self::require_curator(&env, &namespace_id, &curator)?;
+        let dataset = Self::get_namespaced_dataset(env.clone(), namespace_id.clone(), dataset_id)?;
+        if dataset.status != DatasetStatus::Active {
+            return Err(Error::DatasetNotActive);
+        }
+        Self::set_namespaced_status(&env, &namespace_id, dataset, DatasetStatus::Delisted);
+        Ok(())
+    }
+
+    /// Put a delisted namespaced dataset back on sale
+    /// 
+    /// Requires the authorization of a curator of the namespace.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Namespace the dataset is listed in
+    /// * `curator` - Curator of the namespace (must authorize)
+    /// * `dataset_id` - ID of the dataset within the namespace
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotCurator)` if `curator` is not a curator of the namespace
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::DatasetAlreadyActive)` if the dataset is active
+    pub fn relist_namespaced_dataset(
+        env: Env,
+        namespace_id: Symbol,
+        curator: Address,
+        dataset_id: Bytes,
+    ) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        curator.require_auth();
+        Self::require_curator(&env, &namespace_id, &curator)?;
+        let dataset = Self::get_namespaced_dataset(env.clone(), namespace_id.clone(), dataset_id)?;
+        if dataset.status == DatasetStatus::Active {
+            return Err(Error::DatasetAlreadyActive);
+        }
+        Self::set_namespaced_status(&env, &namespace_id, dataset, DatasetStatus::Active);
+        Ok(())
+    }
+
+    /// Get the datasets listed in a namespace, in registration order
+    pub fn get_namespace_datasets(
+        env: Env,
+        namespace_id: Symbol,
+    ) -> Vec<Bytes> {
+        Self::get_index(&env, &DataKey::NsIndex(namespace_id))
+    }
+
+    /// Get the purchase record of a namespaced dataset
+    /// 
+    /// # Returns
+    /// * `Ok(PurchaseRecord)` if found
+    /// * `Err(Error::DatasetNotFound)` if not found
+    pub fn get_namespaced_purchase(
+        env: Env,
+        namespace_id: Symbol,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<PurchaseRecord, Error> {
+        Self::get_persistent::<Val>(&env, &DataKey::NsPurchase(namespace_id, dataset_id, buyer))
+            .and_then(|raw| Self::decode_purchase(&env, &raw))
+            .ok_or(Error::DatasetNotFound)
+    }
+
+    /// Set the compliance registry consulted for buyer jurisdictions
+    /// 
+    /// Once set, every purchase is added to the report of the buyer's
+    /// attested jurisdiction (see `get_jurisdiction_report`). Requires the
+    /// admin's authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `compliance` - Contract answering `get_jurisdiction(buyer)`
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_compliance_registry(
+        env: Env,
+        compliance: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::Compliance, &compliance);
+        Self::audit(&env, symbol_short!("config"), Some(compliance), None);
+        Ok(())
+    }
+
+    /// Get the sales of a jurisdiction in a reporting epoch
+    /// 
+    /// Only purchases made while a compliance registry was configured are
+    /// counted; buyers without an attested jurisdiction are reported under
+    /// `UNKNOWN_JURISDICTION`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `jurisdiction` - Jurisdiction code as attested by the registry
+    /// * `epoch` - Ledger timestamp / REPORT_EPOCH_SECONDS
+    /// 
+    /// # Returns
+    /// * Report for the jurisdiction and epoch (zero if there were no sales)
+    pub fn get_jurisdiction_report(
+        env: Env,
+        jurisdiction: Symbol,
+        epoch: u64,
+    ) -> JurisdictionReport {
+        env.storage().persistent()
+            .get(&DataKey::JurisdictionReport(jurisdiction.clone(), epoch))
+            .unwrap_or(JurisdictionReport {
+                jurisdiction,
+                epoch,
+                purchases: 0,
+                volume: 0,
+            })
+    }
+
+    /// Set the LicenseRegistry contract address
+    /// 
+    /// Requires the admin's authorization, and the UpgradeCoordinator's once
+    /// one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `license_registry` - Address of the LicenseRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_license_registry(
+        env: Env,
+        license_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::LicenseRegistry, &license_registry);
+        Self::audit(&env, symbol_short!("config"), Some(license_registry), None);
+        Ok(())
+    }
+
+    /// Bind a dataset listing to a license document version
+    /// 
+    /// Buyers must acknowledge the version's document hash before they can
+    /// purchase. Rebinding to a newer version leaves earlier purchase
+    /// records untouched, but buyers have to acknowledge the new document.
+    /// Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `license_id` - License in the LicenseRegistry
+    /// * `version` - Published version of the license
+    /// 
+    /// # Returns
+    /// * `Ok(DatasetLicense)` the license now bound to the listing
+    /// * `Err(Error::LicenseNotFound)` if the version is not published
+    /// * `Err(Error)` if the dataset or LicenseRegistry is missing
+    pub fn set_dataset_license(
+        env: Env,
+        dataset_id: Bytes,
+        license_id: Symbol,
+        version: u32,
+    ) -> Result<DatasetLicense, Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let license_registry: Address = Self::get_instance(&env, &DataKey::LicenseRegistry)
+            .ok_or(Error::LicenseRegistryNotSet)?;
+        
+        let terms = match env.try_invoke_contract::<LicenseTerms, soroban_sdk::Error>(
+            &license_registry,
+            &Symbol::new(&env, "get_license"),
+            (license_id.clone(), version).into_val(&env),
+        ) {
+            Ok(Ok(terms)) => terms,
+            _ => return Err(Error::LicenseNotFound),
+        };
+        let license = DatasetLicense {
+            license_id,
+            version,
+            document_hash: terms.document_hash,
+        };
+        Self::set_persistent(&env, &DataKey::DatasetLicense(dataset_id.clone()), &license);
+        
+        env.events().publish(
+            (symbol_short!("License"), dataset_id),
+            license.clone(),
+        );
+        Ok(license)
+    }
+
+    /// Get the license bound to a dataset listing, if any
+    pub fn get_dataset_license(env: Env, dataset_id: Bytes) -> Option<DatasetLicense> {
+        env.storage().persistent().get(&DataKey::DatasetLicense(dataset_id))
+    }
+
+    /// Acknowledge the license bound to a dataset listing
+    /// 
+    /// The buyer signs the document hash they accept; purchases check it
+    /// against the listing's current license and record it on the
+    /// PurchaseRecord.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `buyer` - Address of the researcher (must authorize)
+    /// * `document_hash` - Hash of the license document being accepted
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::LicenseNotFound)` if the listing has no license
+    /// * `Err(Error::LicenseNotAcknowledged)` if the hash is not the
+    ///   listing's current license
+    pub fn acknowledge_license(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        document_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        Self::store_license_ack(&env, &dataset_id, &buyer, document_hash)
+    }
+
+    /// Set the data-use terms of a dataset listing by their hash
+    /// 
+    /// For listings whose owner publishes their own agreement rather than
+    /// a LicenseRegistry license: the hash is bound as the next version of
+    /// license `OWNER_TERMS_LICENSE`, so buyers accept it like any bound
+    /// license (with `acknowledge_license` or `purchase_with_terms`) and
+    /// purchases record it on the PurchaseRecord. After a change, buyers
+    /// must accept the new hash; setting the current hash again is a no-op.
+    /// Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `terms_hash` - Hash of the terms document
+    /// 
+    /// # Returns
+    /// * `Ok(DatasetLicense)` the terms now bound to the listing
+    /// * `Err(ExtError::RegistryLicenseBound)` if a LicenseRegistry license
+    ///   is bound to the listing
+    /// * `Err(Error)` if the dataset is missing
+    pub fn set_license_terms(
+        env: Env,
+        dataset_id: Bytes,
+        terms_hash: BytesN<32>,
+    ) -> Result<DatasetLicense, soroban_sdk::Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let version = match Self::get_dataset_license(env.clone(), dataset_id.clone()) {
+            Some(license) if license.license_id != OWNER_TERMS_LICENSE => {
+                return Err(ExtError::RegistryLicenseBound.into());
+            },
+            Some(license) if license.document_hash == terms_hash => return Ok(license),
+            Some(license) => license.version + 1,
+            None => 1,
+        };
+        let license = DatasetLicense {
+            license_id: OWNER_TERMS_LICENSE,
+            version,
+            document_hash: terms_hash,
+        };
+        Self::set_persistent(&env, &DataKey::DatasetLicense(dataset_id.clone()), &license);
+        
+        env.events().publish(
+            (symbol_short!("License"), dataset_id),
+            license.clone(),
+        );
+        Ok(license)
+    }
+
+    /// Get the license hash a buyer acknowledged for a dataset, if any
+    pub fn get_license_ack(env: Env, dataset_id: Bytes, buyer: Address) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::LicenseAck(dataset_id, buyer))
+    }
+
+    /// Link two dataset listings as related
+    /// 
+    /// Links are reciprocal: each dataset is added to the other's related
+    /// list, and both sides are validated before either is written.
+    /// Linking already linked datasets is a no-op. Requires the
+    /// authorization of both owners, since the link shows on both listings.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `related_id` - ID of the dataset to link it to
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if either dataset is not listed
+    /// * `Err(Error::InvalidRelatedDataset)` if both IDs are the same
+    /// * `Err(Error::TooManyRelatedDatasets)` if either side already has
+    ///   `MAX_RELATED_DATASETS` links
+    pub fn link_related_datasets(
+        env: Env,
+        dataset_id: Bytes,
+        related_id: Bytes,
+    ) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        if dataset_id == related_id {
+            return Err(Error::InvalidRelatedDataset);
+        }
+        let (Some(dataset), Some(related_dataset)) = (
+            Self::load_dataset(&env, &dataset_id),
+            Self::load_dataset(&env, &related_id),
+        ) else {
+            return Err(Error::DatasetNotFound);
+        };
+        dataset.owner.require_auth();
+        if related_dataset.owner != dataset.owner {
+            related_dataset.owner.require_auth();
+        }
+        
+        let mut related = Self::get_related_datasets(env.clone(), dataset_id.clone());
+        let mut reverse = Self::get_related_datasets(env.clone(), related_id.clone());
+        if related.contains(&related_id) && reverse.contains(&dataset_id) {
+            return Ok(());
+        }
+        if related.len() >= MAX_RELATED_DATASETS || reverse.len() >= MAX_RELATED_DATASETS {
+            return Err(Error::TooManyRelatedDatasets);
+        }
+        related.push_back(related_id.clone());
+        reverse.push_back(dataset_id.clone());
+        Self::set_persistent(&env, &DataKey::RelatedDatasets(dataset_id.clone()), &related);
+        Self::set_persistent(&env, &DataKey::RelatedDatasets(related_id.clone()), &reverse);
+        
+        env.events().publish(
+            (symbol_short!("Related"), dataset_id),
+            (related_id, true),
+        );
+        Ok(())
+    }
+
+    /// Remove the link between two dataset listings
+    /// 
+    /// Removes both directions of the link. Either owner can withdraw from
+    /// it on their own.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `related_id` - ID of the linked dataset
+    /// * `owner` - Owner of either dataset (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if `owner` owns neither dataset
+    /// * `Err(Error::InvalidRelatedDataset)` if the datasets are not linked
+    pub fn unlink_related_datasets(
+        env: Env,
+        dataset_id: Bytes,
+        related_id: Bytes,
+        owner: Address,
+    ) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        owner.require_auth();
+        let is_owner = |id: &Bytes| {
+            Self::load_dataset(&env, id).is_some_and(|dataset| dataset.owner == owner)
+        };
+        if !is_owner(&dataset_id) && !is_owner(&related_id) {
+            return Err(Error::Unauthorized);
+        }
+        let mut related = Self::get_related_datasets(env.clone(), dataset_id.clone());
+        let mut reverse = Self::get_related_datasets(env.clone(), related_id.clone());
+        match (related.first_index_of(&related_id), reverse.first_index_of(&dataset_id)) {
+            (Some(index), Some(reverse_index)) => {
+                related.remove(index);
+                reverse.remove(reverse_index);
+            },
+            _ => return Err(Error::InvalidRelatedDataset),
+        }
+        Self::set_persistent(&env, &DataKey::RelatedDatasets(dataset_id.clone()), &related);
+        Self::set_persistent(&env, &DataKey::RelatedDatasets(related_id.clone()), &reverse);
+        
+        env.events().publish(
+            (symbol_short!("Related"), dataset_id),
+            (related_id, false),
+        );
+        Ok(())
+    }
+
+    /// Get the datasets linked to a listing, in linking order
+    pub fn get_related_datasets(env: Env, dataset_id: Bytes) -> Vec<Bytes> {
+        env.storage().persistent()
+            .get(&DataKey::RelatedDatasets(dataset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Flag the datasets containing a revoked study
+    /// 
+    /// Called by the StudyRegistry when a study is revoked or erased. Each
+    /// dataset listing the study is marked for review, which stops its
+    /// sales; under the revocation policy its licenses may be shortened
+    /// and its buyers become entitled to prorated refunds. This runs inside
+    /// the registry's call, so it must not call back into the registry.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_id` - Hash of the revoked study
+    /// * `erased` - Whether the study record was erased
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the number of datasets flagged
+    /// * `Err(Error::StudyRegistryNotSet)` if no registry is configured
+    pub fn on_study_revoked(
+        env: Env,
+        study_id: Bytes,
+        erased: bool,
+    ) -> Result<u32, Error> {
+        let registry: Address = Self::get_instance(&env, &DataKey::StudyRegistry)
+            .ok_or(Error::StudyRegistryNotSet)?;
+        registry.require_auth();
+        
+        let policy = Self::get_revocation_policy(env.clone());
+        let now = env.ledger().timestamp();
+        let licenses_end_at = if erased {
+            Some(now)
+        } else {
+            policy.license_grace.map(|grace| now.saturating_add(grace))
+        };
+        
+        let dataset_ids = Self::get_study_datasets(env.clone(), study_id.clone());
+        for dataset_id in dataset_ids.iter() {
+            let mut review = Self::get_dataset_review(env.clone(), dataset_id.clone())
+                .unwrap_or(DatasetReview {
+                    dataset_id: dataset_id.clone(),
+                    revoked_studies: Vec::new(&env),
+                    flagged_at: now,
+                    licenses_end_at: None,
+                    refund_bps: 0,
+                });
+            if !review.revoked_studies.contains(&study_id) {
+                review.revoked_studies.push_back(study_id.clone());
+            }
+            // A later revocation can only bring the end forward
+            review.licenses_end_at = match (review.licenses_end_at, licenses_end_at) {
+                (Some(current), Some(new)) => Some(current.min(new)),
+                (current, new) => current.or(new),
+            };
+            review.refund_bps = policy.refund_bps;
+            Self::set_persistent(&env, &DataKey::DatasetReview(dataset_id.clone()), &review);
+            
+            env.events().publish(
+                (symbol_short!("DsReview"), dataset_id),
+                (study_id.clone(), review.licenses_end_at),
+            );
+        }
+        
+        Ok(dataset_ids.len())
+    }
+
+    /// Get the review of a dataset flagged by study revocations, if any
+    pub fn get_dataset_review(env: Env, dataset_id: Bytes) -> Option<DatasetReview> {
+        env.storage().persistent()
+            .get(&DataKey::DatasetReview(dataset_id))
+    }
+
+    /// Check whether a buyer's license to a dataset is still active
+    /// 
+    /// # Returns
+    /// * `true` if the buyer purchased the dataset, has no refund of it
+    ///   requested or approved, and no revocation ended its licenses
+    pub fn is_license_active(env: Env, dataset_id: Bytes, buyer: Address) -> bool {
+        if Self::load_purchase(&env, &dataset_id, &buyer).is_none() {
+            return false;
+        }
+        if Self::get_purchase_payment(env.clone(), dataset_id.clone(), buyer)
+            .is_some_and(|payment| matches!(
+                payment.refund,
+                RefundStatus::Requested | RefundStatus::Approved
+            ))
+        {
+            return false;
+        }
+        match Self::get_dataset_review(env.clone(), dataset_id)
+            .and_then(|review| review.licenses_end_at)
+        {
+            Some(end) => env.ledger().timestamp() < end,
+            None => true,
+        }
+    }
+
+    /// Set how long a purchase grants access to a dataset
+    /// 
+    /// Applies to purchases made after the change; a buyer purchasing
+    /// again before their access ends has the period added to it. Requires
+    /// the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `duration` - Access period in seconds (None for unlimited access)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidAccessDuration)` if `duration` is zero
+    pub fn set_access_duration(
+        env: Env,
+        dataset_id: Bytes,
+        duration: Option<u64>,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        if duration == Some(0) {
+            return Err(Error::InvalidAccessDuration);
+        }
+        Self::set_persistent(&env, &DataKey::AccessDuration(dataset_id.clone()), &duration);
+        
+        env.events().publish(
+            (symbol_short!("DsAccess"), dataset_id),
+            duration,
+        );
+        Ok(())
+    }
+
+    /// Get the access period of a dataset in seconds (None if unlimited)
+    pub fn get_access_duration(env: Env, dataset_id: Bytes) -> Option<u64> {
+        Self::get_persistent::<Option<u64>>(&env, &DataKey::AccessDuration(dataset_id))
+            .flatten()
+    }
+
+    /// Get a buyer's access grant to a dataset, if they purchased it since
+    /// grants were recorded
+    pub fn get_access_grant(env: Env, dataset_id: Bytes, buyer: Address) -> Option<AccessGrant> {
+        Self::get_persistent(&env, &DataKey::AccessGrant(dataset_id, buyer))
+    }
+
+    /// Check whether a buyer currently has access to a dataset
+    /// 
+    /// # Returns
+    /// * `true` if the buyer's license is active (see `is_license_active`)
+    ///   and their access grant, if any, has not expired
+    pub fn has_access(env: Env, dataset_id: Bytes, buyer: Address) -> bool {
+        if !Self::is_license_active(env.clone(), dataset_id.clone(), buyer.clone()) {
+            return false;
+        }
+        match Self::get_access_grant(env.clone(), dataset_id, buyer)
+            .and_then(|grant| grant.expires_at)
+        {
+            Some(end) => env.ledger().timestamp() < end,
+            None => true,
+        }
+    }
+
+    /// Offer a discount to buyers redeeming a promo code
+    /// 
+    /// Only the code's SHA-256 hash is stored, so the code itself stays
+    /// off-chain until a buyer redeems it. Setting an existing code
+    /// replaces its terms. Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `code_hash` - SHA-256 hash of the promo code
+    /// * `promo` - Discount and expiry of the code
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidDiscount)` if the discount is not between 0
+    ///   and 100% exclusive
+    pub fn set_promo_code(
+        env: Env,
+        dataset_id: Bytes,
+        code_hash: BytesN<32>,
+        promo: PromoCode,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        Self::require_discount_bps(promo.discount_bps)?;
+        Self::set_persistent(&env, &DataKey::PromoCode(dataset_id.clone(), code_hash.clone()), &promo);
+        
+        env.events().publish(
+            (symbol_short!("PromoSet"), dataset_id, code_hash),
+            promo,
+        );
+        Ok(())
+    }
+
+    /// Withdraw a promo code
+    /// 
+    /// Requires the owner's authorization.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidPromoCode)` if the code is not offered
+    pub fn remove_promo_code(
+        env: Env,
+        dataset_id: Bytes,
+        code_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = DataKey::PromoCode(dataset_id.clone(), code_hash.clone());
+        if !Self::has_persistent(&env, &key) {
+            return Err(Error::InvalidPromoCode);
+        }
+        env.storage().persistent().remove(&key);
+        
+        env.events().publish((symbol_short!("PromoDel"), dataset_id, code_hash), ());
+        Ok(())
+    }
+
+    /// Get the terms of a promo code by its SHA-256 hash
+    pub fn get_promo_code(env: Env, dataset_id: Bytes, code_hash: BytesN<32>) -> Option<PromoCode> {
+        Self::get_persistent(&env, &DataKey::PromoCode(dataset_id, code_hash))
+    }
+
+    /// Set or clear a discount applied to every purchase in a time window
+    /// 
+    /// Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `discount` - Discount and window (None to end it)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidDiscount)` if the discount is not between 0
+    ///   and 100% exclusive or the window is empty
+    pub fn set_timed_discount(
+        env: Env,
+        dataset_id: Bytes,
+        discount: Option<TimedDiscount>,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        let key = DataKey::TimedDiscount(dataset_id.clone());
+        match discount.as_ref() {
+            Some(timed) => {
+                Self::require_discount_bps(timed.discount_bps)?;
+                if timed.starts_at >= timed.ends_at {
+                    return Err(Error::InvalidDiscount);
+                }
+                Self::set_persistent(&env, &key, timed);
+            },
+            None => env.storage().persistent().remove(&key),
+        }
+        
+        env.events().publish((symbol_short!("DsSale"), dataset_id), discount);
+        Ok(())
+    }
+
+    /// Get the timed discount of a dataset, if any
+    pub fn get_timed_discount(env: Env, dataset_id: Bytes) -> Option<TimedDiscount> {
+        Self::get_persistent(&env, &DataKey::TimedDiscount(dataset_id))
+    }
+
+    /// Set the volume discount tiers of a dataset
+    /// 
+    /// A buyer gets the discount of the highest tier whose `min_purchases`
+    /// their prior purchases reach. An empty list removes the tiers.
+    /// Requires the owner's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `tiers` - Tiers sorted by strictly increasing `min_purchases`
+    ///   (at most MAX_VOLUME_TIERS)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::InvalidDiscount)` if the tiers are unsorted, too many
+    ///   or a discount is not between 0 and 100% exclusive
+    pub fn set_volume_tiers(
+        env: Env,
+        dataset_id: Bytes,
+        tiers: Vec<VolumeTier>,
+    ) -> Result<(), Error> {
+        Self::load_owned_dataset(&env, &dataset_id)?;
+        if tiers.len() > MAX_VOLUME_TIERS {
+            return Err(Error::InvalidDiscount);
+        }
+        for tier in tiers.iter() {
+            Self::require_discount_bps(tier.discount_bps)?;
+        }
+        Self::volume_discount_bps(&tiers, 0)?;
+        Self::set_persistent(&env, &DataKey::VolumeTiers(dataset_id.clone()), &tiers);
+        
+        env.events().publish((symbol_short!("DsTiers"), dataset_id), tiers);
+        Ok(())
+    }
+
+    /// Get the volume discount tiers of a dataset
+    pub fn get_volume_tiers(env: Env, dataset_id: Bytes) -> Vec<VolumeTier> {
+        Self::get_persistent(&env, &DataKey::VolumeTiers(dataset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Claim the refund owed for revoked studies in a purchased dataset
+    /// 
+    /// The refund is the policy's `refund_bps` of what the buyer paid, in
+    /// the token they paid in, prorated by the studies revoked since the
+    /// buyer's last claim over the studies in the dataset. It is pulled
+    /// from the policy's refund source. Licenses with no payment on record,
+    /// such as free or transferred ones, are owed nothing.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `buyer` - Address of the buyer (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the refunded amount
+    /// * `Err(Error::NoRefundDue)` if nothing is owed, or the buyer paid
+    ///   nothing for their license
+    /// * `Err(Error::PaymentFailed)` if the refund source cannot cover it
+    /// * `Err(Error::Paused)` if the contract is paused
+    /// * `Err(Error)` if the buyer holds no purchase of the dataset
+    pub fn claim_revocation_refund(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Result<i128, Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        buyer.require_auth();
+        Self::load_purchase(&env, &dataset_id, &buyer)
+            .ok_or(Error::DatasetNotFound)?;
+        // A purchase refunded in full is owed nothing more
+        let payment = Self::get_purchase_payment(env.clone(), dataset_id.clone(), buyer.clone())
+            .filter(|payment| payment.refund != RefundStatus::Approved)
+            .ok_or(Error::NoRefundDue)?;
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        let review = Self::get_dataset_review(env.clone(), dataset_id.clone())
+            .ok_or(Error::NoRefundDue)?;
+        let refund_source = Self::get_revocation_policy(env.clone()).refund_source
+            .ok_or(Error::NoRefundDue)?;
+        
+        let refund_key = DataKey::RevocationRefund(dataset_id.clone(), buyer.clone());
+        let refunded: u32 = env.storage().persistent().get(&refund_key).unwrap_or(0);
+        let owed_studies = review.revoked_studies.len() - refunded;
+        if owed_studies == 0 || review.refund_bps == 0 {
+            return Err(Error::NoRefundDue);
+        }
+        // Refunds are paid in the token the listing was bought with
+        let payment_token = payment.token;
+        let amount = pricing::mul_bps(payment.amount, review.refund_bps, pricing::Rounding::Down)
+            .and_then(|share| pricing::prorate(
+                share,
+                owed_studies.into(),
+                dataset.study_ids.len().into(),
+                pricing::Rounding::Down,
+            ))
+            .map_err(|_| Error::Overflow)?;
+        if amount == 0 {
+            return Err(Error::NoRefundDue);
+        }
+        
+        let token_client = token::Client::new(&env, &payment_token);
+        let contract_address = env.current_contract_address();
+        if token_client.allowance(&refund_source, &contract_address) < amount
+            || token_client.balance(&refund_source) < amount
+        {
+            return Err(Error::PaymentFailed);
+        }
+        token_client.transfer_from(&contract_address, &refund_source, &buyer, &amount);
+        Self::set_persistent(&env, &refund_key, &review.revoked_studies.len());
+        
+        env.events().publish(
+            (symbol_short!("RevRefund"), dataset_id, buyer),
+            amount,
+        );
+        Ok(amount)
+    }
+
+    /// Get the revocation policy (no license change and no refunds if unset)
+    pub fn get_revocation_policy(env: Env) -> RevocationPolicy {
+        Self::get_instance(&env, &DataKey::RevocationPolicy)
+            .unwrap_or(RevocationPolicy {
+                license_grace: None,
+                refund_bps: 0,
+                refund_source: None,
+            })
+    }
+
+    /// Get the payment and refund state of a buyer's latest purchase
+    pub fn get_purchase_payment(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+    ) -> Option<PurchasePayment> {
+        Self::get_persistent(&env, &DataKey::PurchasePayment(dataset_id, buyer))
+    }
+
+    /// Request a refund of a purchase within the refund window
+    /// 
+    /// Suspends the buyer's license until the arbiter resolves the
+    /// request with `resolve_refund`. A purchase can be disputed once.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `buyer` - Address of the buyer (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::RefundsNotEnabled)` if no refund policy is set
+    /// * `Err(Error::NoRefundDue)` if the buyer has no refundable purchase
+    ///   of the dataset
+    /// * `Err(Error::RefundWindowClosed)` if the refund window has passed
+    /// * `Err(Error::RefundAlreadyRequested)` if the purchase was disputed
+    ///   before
+    pub fn request_refund(env: Env, dataset_id: Bytes, buyer: Address) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        buyer.require_auth();
+        let policy = Self::get_refund_policy(env.clone())
+            .ok_or(Error::RefundsNotEnabled)?;
+        
+        let payment_key = DataKey::PurchasePayment(dataset_id.clone(), buyer.clone());
+        let mut payment: PurchasePayment = Self::get_persistent(&env, &payment_key)
+            .ok_or(Error::NoRefundDue)?;
+        if payment.refund != RefundStatus::NotRequested {
+            return Err(Error::RefundAlreadyRequested);
+        }
+        if env.ledger().timestamp() >= payment.paid_at.saturating_add(policy.window) {
+            return Err(Error::RefundWindowClosed);
+        }
+        payment.refund = RefundStatus::Requested;
+        Self::set_persistent(&env, &payment_key, &payment);
+        
+        env.events().publish(
+            (symbol_short!("RefundReq"), dataset_id, buyer),
+            payment.amount,
+        );
+        Ok(())
+    }
+
+    /// Resolve a buyer's refund request
+    /// 
+    /// Requires the arbiter's authorization. An approved refund returns
+    /// the price to the payer in the token it was paid in and ends the
+    /// license. A purchase still held in escrow is refunded from its
+    /// deposit, and its payout is dropped so that it is never settled.
+    /// Otherwise RevenueSplitter hands back the contributors' shares they
+    /// have not claimed yet (recomputed from the dataset's current
+    /// studies), and the rest is pulled from the policy's refund source.
+    /// An approved refund also burns the buyer's access pass. A rejected
+    /// request reactivates the license. Either way the outcome is reported
+    /// to ContributorReputation, once set, for each contributor of the
+    /// dataset's studies.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `buyer` - Address of the buyer
+    /// * `approve` - Whether to refund the purchase
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the amount refunded (0 if rejected)
+    /// * `Err(Error::RefundsNotEnabled)` if no refund policy is set
+    /// * `Err(Error::RefundNotRequested)` if no refund request is pending
+    /// * `Err(Error::EscrowNotSet)` if the purchase is held in escrow but
+    ///   no escrow is configured
+    /// * `Err(Error::PaymentFailed)` if the refund source cannot cover its
+    ///   part
+    pub fn resolve_refund(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        approve: bool,
+    ) -> Result<i128, Error> {
+        Self::require_not_paused(&env)?;
+        Self::require_not_emergency_paused(&env)?;
+        let policy = Self::get_refund_policy(env.clone())
+            .ok_or(Error::RefundsNotEnabled)?;
+        policy.arbiter.require_auth();
+        
+        let payment_key = DataKey::PurchasePayment(dataset_id.clone(), buyer.clone());
+        let mut payment: PurchasePayment = Self::get_persistent(&env, &payment_key)
+            .filter(|payment: &PurchasePayment| payment.refund == RefundStatus::Requested)
+            .ok_or(Error::RefundNotRequested)?;
+        
+        let mut refunded = 0;
+        if approve {
+            // A purchase still in escrow is refunded from its deposit and
+            // never paid out; a settled one reclaims what its payout
+            // credited the contributors and they have not claimed first
+            let reclaimed: i128 = match Self::escrowed_purchase(&env, &dataset_id, &buyer) {
+                Some(purchase_id) => Self::refund_escrow(&env, &purchase_id, &payment.payer)?,
+                None => match Self::take_payout_sequence(&env, &dataset_id, &buyer) {
+                    Some(sequence) => {
+                        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+                            .ok_or(Error::RevenueSplitterNotSet)?;
+                        env.invoke_contract(
+                            &revenue_splitter,
+                            &Symbol::new(&env, "claw_back"),
+                            (
+                                payment.token.clone(),
+                                dataset_id.clone(),
+                                sequence,
+                                payment.payer.clone(),
+                            ).into_val(&env),
+                        )
+                    },
+                    None => 0,
+                },
+            };
+            
+            let shortfall = payment.amount - reclaimed;
+            if shortfall > 0 {
+                let token_client = token::Client::new(&env, &payment.token);
+                let contract_address = env.current_contract_address();
+                if token_client.allowance(&policy.refund_source, &contract_address) < shortfall
+                    || token_client.balance(&policy.refund_source) < shortfall
+                {
+                    return Err(Error::PaymentFailed);
+                }
+                token_client.transfer_from(
+                    &contract_address,
+                    &policy.refund_source,
+                    &payment.payer,
+                    &shortfall,
+                );
+            }
+            refunded = payment.amount;
+            if let Some(access_pass) = Self::get_access_pass(env.clone()) {
+                AccessPassClient::new(&env, &access_pass).burn(&dataset_id, &buyer);
+            }
+        }
+        payment.refund = if approve { RefundStatus::Approved } else { RefundStatus::Rejected };
+        Self::set_persistent(&env, &payment_key, &payment);
+        Self::report_dispute(&env, &dataset_id, approve);
+        
+        env.events().publish(
+            (symbol_short!("RefundRes"), dataset_id, buyer),
+            (approve, refunded),
+        );
+        Ok(refunded)
+    }
+
+    /// Pay out a purchase released from escrow
+    /// 
+    /// Called by the PurchaseEscrow after it returns the funds to this
+    /// contract, when the buyer confirms delivery or the lock period ends.
+    /// The payout goes to the contributors recorded at purchase time.
+    /// Requires the escrow's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `purchase_id` - ID of the escrowed purchase
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::EscrowNotSet)` if no escrow is configured
+    /// * `Err(Error::EscrowedPayoutNotFound)` if the purchase is not held
+    ///   in escrow
+    pub fn settle_escrow(env: Env, purchase_id: BytesN<32>) -> Result<(), Error> {
+        Self::require_not_emergency_paused(&env)?;
+        let config = Self::get_escrow(env.clone()).ok_or(Error::EscrowNotSet)?;
+        config.escrow.require_auth();
+        
+        let storage = env.storage().persistent();
+        let key = ExtKey::EscrowedPayout(purchase_id.clone());
+        let payout: EscrowedPayout = storage.get(&key).ok_or(Error::EscrowedPayoutNotFound)?;
+        storage.remove(&key);
+        
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let sequence = Self::forward_to_splitter(
+            &env,
+            &payout.token,
+            &revenue_splitter,
+            &payout.dataset_id,
+            &payout.weights,
+            payout.amount,
+        );
+        Self::record_payout_sequence(&env, &purchase_id, sequence);
+        
+        env.events().publish(
+            (symbol_short!("EscrowPay"), payout.dataset_id, purchase_id),
+            payout.amount,
+        );
+        Ok(())
+    }
+
+    /// Get the payout of a purchase held in escrow, if any
+    pub fn get_escrowed_payout(env: Env, purchase_id: BytesN<32>) -> Option<EscrowedPayout> {
+        env.storage().persistent().get(&ExtKey::EscrowedPayout(purchase_id))
+    }
+
+    /// Get the refund policy (None if purchases cannot be refunded)
+    pub fn get_refund_policy(env: Env) -> Option<RefundPolicy> {
+        Self::get_instance(&env, &DataKey::RefundPolicy)
+    }
+
+    /// Set the NamespaceRegistry contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_registry` - Address of the NamespaceRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_namespace_registry(
+        env: Env,
+        namespace_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::NsRegistry, &namespace_registry);
+        Self::audit(&env, symbol_short!("config"), Some(namespace_registry), None);
+        Ok(())
+    }
+
+    /// Load the NamespaceRegistry contract address
+    fn namespace_registry(env: &Env) -> Result<Address, Error> {
+        Self::get_instance(env, &DataKey::NsRegistry)
+            .ok_or(Error::NamespaceRegistryNotSet)
+    }
+
+    /// Check an address is a curator of a namespace, per NamespaceRegistry
+    fn require_curator(env: &Env, namespace_id: &Symbol, curator: &Address) -> Result<(), Error> {
+        let namespace_registry = Self::namespace_registry(env)?;
+        let is_curator: bool = env.invoke_contract(
+            &namespace_registry,
+            &Symbol::new(env, "is_curator"),
+            (namespace_id.clone(), curator.clone()).into_val(env),
+        );
+        if !is_curator {
+            return Err(Error::NotCurator);
+        }
+        Ok(())
+    }
+
+    /// Move a namespaced dataset to `status` and emit the transition
+    fn set_namespaced_status(
+        env: &Env,
+        namespace_id: &Symbol,
+        mut dataset: Dataset,
+        status: DatasetStatus,
+    ) {
+        let previous = dataset.status;
+        dataset.status = status;
+        let dataset_id = dataset.dataset_id.clone();
+        Self::set_persistent(
+            env,
+            &DataKey::NsDataset(namespace_id.clone(), dataset_id.clone()),
+            &VersionedDataset::V3(dataset),
+        );
+        
+        env.events().publish(
+            (symbol_short!("DsStatus"), dataset_id, namespace_id.clone()),
+            (previous, status),
+        );
+    }
+
+    /// Read the configuration of a namespace from NamespaceRegistry
+    fn namespace_config(
+        env: &Env,
+        namespace_id: &Symbol,
+    ) -> Result<NamespaceConfig, Error> {
+        let namespace_registry = Self::namespace_registry(env)?;
+        match env.try_invoke_contract::<NamespaceConfig, soroban_sdk::Error>(
+            &namespace_registry,
+            &Symbol::new(env, "get_config"),
+            (namespace_id.clone(),).into_val(env),
+        ) {
+            Ok(Ok(config)) => Ok(config),
+            _ => Err(Error::NamespaceNotFound),
+        }
+    }
+
+    /// Extend the TTL of a dataset record
+    /// 
+    /// Permissionless maintenance call so a listing nobody reads or buys
+    /// is not archived. Records not yet moved out of instance storage by
+    /// `migrate_datasets` are kept alive by extending the instance.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - Dataset to keep alive
+    /// 
+    /// # Returns
+    /// * `Ok(())` if the record's TTL was extended
+    /// * `Err(Error::DatasetNotFound)` if the dataset does not exist
+    pub fn bump_dataset(
+        env: Env,
+        dataset_id: Bytes,
+    ) -> Result<(), Error> {
+        if Self::extend_record_ttl(&env, &DataKey::Dataset(dataset_id)) {
+            Ok(())
+        } else {
+            Err(Error::DatasetNotFound)
+        }
+    }
+
+    /// Extend the TTL of dataset records
+    /// 
+    /// Permissionless so the TtlKeeper (or anyone) can keep records alive.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_ids` - Datasets to keep alive
+    /// 
+    /// # Returns
+    /// * Number of the given datasets that exist
+    pub fn extend_dataset_ttl(
+        env: Env,
+        dataset_ids: Vec<Bytes>,
+    ) -> u32 {
+        dataset_ids.iter()
+            .filter(|id| Self::extend_record_ttl(&env, &DataKey::Dataset(id.clone())))
+            .count() as u32
+    }
+
+    /// Extend the TTL of purchase records
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `purchases` - (dataset_id, buyer) pairs to keep alive
+    /// 
+    /// # Returns
+    /// * Number of the given purchases that exist
+    pub fn extend_purchase_ttl(
+        env: Env,
+        purchases: Vec<(Bytes, Address)>,
+    ) -> u32 {
+        purchases.iter()
+            .filter(|(dataset_id, buyer)| {
+                Self::extend_record_ttl(&env, &DataKey::Purchase(dataset_id.clone(), buyer.clone()))
+            })
+            .count() as u32
+    }
+
+    /// Get the storage schema version
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Schema version of the stored data (1 if no marker was ever written)
+    pub fn schema_version(env: Env) -> u32 {
+        Self::get_instance(&env, &DataKey::Schema).unwrap_or(1)
+    }
+
+    /// Rewrite legacy datasets in the current layout
+    /// 
+    /// Records are wrapped in `VersionedDataset` and moved from instance
+    /// storage (under their `DataKey` or symbol-tuple key) to
+    /// `DataKey::Dataset` in persistent storage. Storage cannot be enumerated
+    /// on-chain, so records are migrated in batches by ID. Records are
+    /// rewritten with the content they are already read as (listings from
+    /// before owners are owned by the admin), so anyone can call this.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_ids` - Datasets to migrate
+    /// 
+    /// # Returns
+    /// * Number of records that were rewritten
+    pub fn migrate_datasets(
+        env: Env,
+        dataset_ids: Vec<Bytes>,
+    ) -> u32 {
+        let storage = env.storage().instance();
+        let mut migrated = 0u32;
+        
+        for dataset_id in dataset_ids.iter() {
+            let storage_key = DataKey::Dataset(dataset_id);
+            let legacy_key = storage_key.legacy(&env);
+            let dataset = Self::get_instance::<Val>(&env, &storage_key)
+                .and_then(|raw| Self::decode_dataset(&env, &raw));
+            if let Some(dataset) = dataset {
+                Self::set_persistent(&env, &storage_key, &VersionedDataset::V3(dataset));
+                storage.remove(&storage_key);
+                storage.remove(&legacy_key);
+                migrated += 1;
+            }
+        }
+        
+        migrated
+    }
+
+    /// Rewrite legacy purchase records in the current layout
+    /// 
+    /// Same as `migrate_datasets`, moving records to `DataKey::Purchase` in
+    /// persistent storage.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `purchases` - (dataset_id, buyer) pairs to migrate
+    /// 
+    /// # Returns
+    /// * Number of records that were rewritten
+    pub fn migrate_purchases(
+        env: Env,
+        purchases: Vec<(Bytes, Address)>,
+    ) -> u32 {
+        let storage = env.storage().instance();
+        let mut migrated = 0u32;
+        
+        for (dataset_id, buyer) in purchases.iter() {
+            let purchase_key = DataKey::Purchase(dataset_id, buyer);
+            let legacy_key = purchase_key.legacy(&env);
+            let purchase = Self::get_instance::<Val>(&env, &purchase_key)
+                .and_then(|raw| Self::decode_purchase(&env, &raw));
+            if let Some(purchase) = purchase {
+                Self::set_persistent(&env, &purchase_key, &VersionedPurchase::V2(purchase));
+                storage.remove(&purchase_key);
+                storage.remove(&legacy_key);
+                migrated += 1;
+            }
+        }
+        
+        migrated
+    }
+
+    /// Pull the dataset price from the buyer
+    /// 
+    /// Checks the allowance and balance first so an underfunded purchase
+    /// fails with `PaymentFailed` instead of a token contract error.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_token` - Address of the listing's payment token contract
+    /// * `buyer` - Address of the buyer
+    /// * `amount` - Price to collect
+    /// 
+    /// # Returns
+    /// * `Ok(())` if `amount` was transferred to this contract
+    /// * `Err(Error::PaymentFailed)` otherwise
+    fn collect_payment(
+        env: &Env,
+        payment_token: &Address,
+        buyer: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::PaymentFailed);
+        }
+        
+        let token_client = token::Client::new(env, payment_token);
+        let contract_address = env.current_contract_address();
+        
+        if token_client.allowance(buyer, &contract_address) < amount
+            || token_client.balance(buyer) < amount
+        {
+            return Err(Error::PaymentFailed);
+        }
+        
+        token_client.transfer_from(&contract_address, buyer, &contract_address, &amount);
+        Ok(())
+    }
+
+    /// Forward a collected payment to RevenueSplitter for payout
+    /// 
+    /// The platform fee, if set, is sent to its collector first, and the
+    /// rest is paid out by `pay_out`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_token` - Address of the token the purchase was paid in
+    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `weights` - Distinct contributors to pay, with their weights
+    /// * `amount` - Amount of the token to pay out
+    fn forward_to_splitter(
+        env: &Env,
+        payment_token: &Address,
+        revenue_splitter: &Address,
+        dataset_id: &Bytes,
+        weights: &Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Option<u64> {
+        let amount = Self::pay_platform_fee(env, payment_token, amount);
+        Self::pay_out(env, payment_token, revenue_splitter, dataset_id, weights, amount)
+    }
+
+    /// Send the platform fee on a payment to its collector
+    /// 
+    /// # Returns
+    /// * The amount left once the fee, if any, is paid
+    fn pay_platform_fee(env: &Env, payment_token: &Address, amount: i128) -> i128 {
+        let (fee, amount) = Self::split_platform_fee(env, amount);
+        if let Some((collector, fee)) = fee {
+            token::Client::new(env, payment_token)
+                .transfer(&env.current_contract_address(), &collector, &fee);
+        }
+        amount
+    }
+
+    /// Have RevenueSplitter pay out an amount held by this contract
+    /// 
+    /// RevenueSplitter pulls the amount from this contract and pays it out
+    /// in the same token and call, so the nested transfer is authorized up
+    /// front.
+    /// 
+    /// # Returns
+    /// * The splitter's sequence of the payout, if anyone was paid
+    fn pay_out(
+        env: &Env,
+        payment_token: &Address,
+        revenue_splitter: &Address,
+        dataset_id: &Bytes,
+        weights: &Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Option<u64> {
+        let contract_address = env.current_contract_address();
+        env.authorize_as_current_contract(soroban_sdk::vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: payment_token.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: (
+                        contract_address.clone(),
+                        revenue_splitter.clone(),
+                        amount,
+                    ).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+        
+        env.invoke_contract(
+            revenue_splitter,
+            &Symbol::new(env, "payout_weighted_in"),
+            (
+                contract_address,
+                payment_token.clone(),
+                dataset_id.clone(),
+                weights.clone(),
+                amount,
+            ).into_val(env),
+        )
+    }
+
+    /// Split a payment into the platform fee, with its collector, and the
+    /// amount left for RevenueSplitter
+    /// 
+    /// The fee rounds down; no fee is returned when none is set or it
+    /// rounds to 0.
+    fn split_platform_fee(env: &Env, amount: i128) -> (Option<(Address, i128)>, i128) {
+        let Some(config) = Self::get_fee_config(env.clone()) else {
+            return (None, amount);
+        };
+        let fee = pricing::mul_bps(amount, config.fee_bps, pricing::Rounding::Down)
+            .unwrap_or_else(|_| panic_with_error!(env, Error::Overflow));
+        if fee == 0 {
+            return (None, amount);
+        }
+        (Some((config.collector, fee)), amount - fee)
+    }
+
+    /// ID of a buyer's latest purchase of a dataset, if it is still held in
+    /// escrow
+    fn escrowed_purchase(env: &Env, dataset_id: &Bytes, buyer: &Address) -> Option<BytesN<32>> {
+        let purchase = Self::load_purchase(env, dataset_id, buyer)?;
+        let purchase_id = BytesN::<32>::try_from(purchase.tx_hash).ok()?;
+        env.storage()
+            .persistent()
+            .has(&ExtKey::EscrowedPayout(purchase_id.clone()))
+            .then_some(purchase_id)
+    }
+
+    /// Record the splitter payout of a purchase, for `resolve_refund`
+    fn record_payout_sequence(env: &Env, purchase_id: &BytesN<32>, sequence: Option<u64>) {
+        if let Some(sequence) = sequence {
+            Self::set_ext_persistent(env, &ExtKey::PayoutSequence(purchase_id.clone()), &sequence);
+        }
+    }
+
+    /// Take the splitter payout sequence of a buyer's purchase, if any
+    /// 
+    /// Removes it, so a purchase's payout is reclaimed at most once.
+    fn take_payout_sequence(env: &Env, dataset_id: &Bytes, buyer: &Address) -> Option<u64> {
+        let purchase = Self::load_purchase(env, dataset_id, buyer)?;
+        let key = ExtKey::PayoutSequence(BytesN::<32>::try_from(purchase.tx_hash).ok()?);
+        let storage = env.storage().persistent();
+        let sequence = storage.get(&key)?;
+        storage.remove(&key);
+        Some(sequence)
+    }
+
+    /// Refund a purchase still held in escrow
+    /// 
+    /// Drops its payout, so `settle_escrow` can no longer pay it out, and
+    /// has the escrow return the deposit to the payer.
+    fn refund_escrow(env: &Env, purchase_id: &BytesN<32>, payer: &Address) -> Result<i128, Error> {
+        let config = Self::get_escrow(env.clone()).ok_or(Error::EscrowNotSet)?;
+        env.storage()
+            .persistent()
+            .remove(&ExtKey::EscrowedPayout(purchase_id.clone()));
+        Ok(env.invoke_contract(
+            &config.escrow,
+            &symbol_short!("refund"),
+            (purchase_id.clone(), payer.clone()).into_val(env),
+        ))
+    }
+
+    /// Move a purchase's payment into escrow
+    /// 
+    /// Transfers the amount to the escrow, which locks it for the buyer,
+    /// and records the payout `settle_escrow` makes on release.
+    fn hold_in_escrow(
+        env: &Env,
+        escrow: &Address,
+        purchase_id: &BytesN<32>,
+        buyer: &Address,
+        payout: EscrowedPayout,
+    ) {
+        token::Client::new(env, &payout.token)
+            .transfer(&env.current_contract_address(), escrow, &payout.amount);
+        let _: Val = env.invoke_contract(
+            escrow,
+            &symbol_short!("lock"),
+            (
+                purchase_id.clone(),
+                buyer.clone(),
+                payout.token.clone(),
+                payout.amount,
+            ).into_val(env),
+        );
+        Self::set_ext_persistent(env, &ExtKey::EscrowedPayout(purchase_id.clone()), &payout);
+    }
+
+    /// Set the RevenueSplitter contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::IncompatibleInterface)` if the contract reports an
+    ///   interface older than `MIN_SPLITTER_INTERFACE`
+    pub fn set_revenue_splitter(
+        env: Env,
+        revenue_splitter: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::require_interface(&env, &revenue_splitter, MIN_SPLITTER_INTERFACE)?;
+        
+        let storage = env.storage().instance();
+        storage.set(&DataKey::RevenueSplitter, &revenue_splitter);
+        Self::audit(&env, symbol_short!("config"), Some(revenue_splitter), None);
+        Ok(())
+    }
+
+    /// Set the StudyRegistry contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_registry` - Address of the StudyRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::IncompatibleInterface)` if the contract reports an
+    ///   interface older than `MIN_REGISTRY_INTERFACE`
+    pub fn set_study_registry(
+        env: Env,
+        study_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::require_interface(&env, &study_registry, MIN_REGISTRY_INTERFACE)?;
+        
+        let storage = env.storage().instance();
+        storage.set(&DataKey::StudyRegistry, &study_registry);
+        Self::audit(&env, symbol_short!("config"), Some(study_registry), None);
+        Ok(())
+    }
+
+    /// Check that a dependency implements at least `min` interface version
+    /// 
+    /// Contracts without `interface_version` (or not deployed at all) are
+    /// rejected, so a mismatched deployment fails at configuration time
+    /// instead of mis-executing on the first purchase.
+    fn require_interface(env: &Env, contract: &Address, min: u32) -> Result<(), Error> {
+        let version = env.try_invoke_contract::<u32, soroban_sdk::Error>(
+            contract,
+            &Symbol::new(env, "interface_version"),
+            Vec::new(env),
+        );
+        
+        match version {
+            Ok(Ok(version)) if version >= min => Ok(()),
+            _ => Err(Error::IncompatibleInterface),
+        }
+    }
+
+    /// Set the PlatformStats contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `stats` - Address of the PlatformStats contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_stats_contract(
+        env: Env,
+        stats: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Stats, &stats);
+        Self::audit(&env, symbol_short!("config"), Some(stats), None);
+        Ok(())
+    }
+
+    /// Set the NotificationInbox contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `inbox` - Address of the NotificationInbox contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_notification_inbox(
+        env: Env,
+        inbox: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Inbox, &inbox);
+        Self::audit(&env, symbol_short!("config"), Some(inbox), None);
+        Ok(())
+    }
+
+    /// Set the AccessBridge contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `bridge` - Address of the AccessBridge contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_bridge_adapter(
+        env: Env,
+        bridge: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Bridge, &bridge);
+        Self::audit(&env, symbol_short!("config"), Some(bridge), None);
+        Ok(())
+    }
+
+    /// Set the RoyaltyRegistry contract address
+    /// 
+    /// Royalty terms (resale, institutional, referral) are defined once in
+    /// the RoyaltyRegistry and shared with the RevenueSplitter.
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `royalty_registry` - Address of the RoyaltyRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_royalty_registry(
+        env: Env,
+        royalty_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Royalty, &royalty_registry);
+        Self::audit(&env, symbol_short!("config"), Some(royalty_registry), None);
+        Ok(())
+    }
+
+    /// Get the resale royalty of a dataset in basis points
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * Resale royalty from RoyaltyRegistry (0 if no registry is configured)
+    pub fn get_resale_royalty_bps(
+        env: Env,
+        dataset_id: Bytes,
+    ) -> u32 {
+        match Self::get_instance::<Address>(&env, &DataKey::Royalty) {
+            Some(royalty_registry) => {
+                let terms: Map<Symbol, Val> = env.invoke_contract(
+                    &royalty_registry,
+                    &Symbol::new(&env, "dataset_terms_or_default"),
+                    (dataset_id,).into_val(&env),
+                );
+                terms
+                    .get(Symbol::new(&env, "resale_bps"))
+                    .map(|bps| u32::from_val(&env, &bps))
+                    .unwrap_or(0)
+            },
+            None => 0,
+        }
+    }
+
+    /// Quote the resale royalty owed on a resale of a dataset
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// * `resale_price` - Price the dataset is resold for
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the royalty, rounded down
+    /// * `Err(Error::InvalidPrice)` if the resale price is not positive
+    pub fn quote_resale_royalty(
+        env: Env,
+        dataset_id: Bytes,
+        resale_price: i128,
+    ) -> Result<i128, Error> {
+        if resale_price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        
+        let royalty_bps = Self::get_resale_royalty_bps(env, dataset_id);
+        pricing::mul_bps(resale_price, royalty_bps, pricing::Rounding::Down)
+            .map_err(|_| Error::InvalidPrice)
+    }
+
+    /// Report a resolved refund request to ContributorReputation
+    /// 
+    /// The dispute counts for each contributor of the dataset's studies
+    /// that are not revoked. Best-effort like analytics: if no reputation
+    /// contract is configured or a lookup or call fails, the resolution
+    /// still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the disputed dataset
+    /// * `upheld` - Whether the refund was approved
+    fn report_dispute(env: &Env, dataset_id: &Bytes, upheld: bool) {
+        let Some(reputation) = Self::get_reputation_contract(env.clone()) else {
+            return;
+        };
+        let Some(dataset) = Self::load_dataset(env, dataset_id) else {
+            return;
+        };
+        let Ok(weights) = Self::get_contributor_weights(env, &dataset.study_ids) else {
+            return;
+        };
+        for (contributor, _) in weights.iter() {
+            let _ = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+                &reputation,
+                &Symbol::new(env, "record_dispute"),
+                (env.current_contract_address(), contributor, upheld).into_val(env),
+            );
+        }
+    }
+
+    /// Push an analytics increment to the PlatformStats contract
+    /// 
+    /// Analytics are best-effort: if no stats contract is configured or the
+    /// call fails, the purchase still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - PlatformStats metric symbol ("sales", "volume")
+    /// * `amount` - Increment to push
+    fn push_stat(
+        env: &Env,
+        metric: Symbol,
+        amount: i128,
+    ) {
+        if let Some(stats) = Self::get_instance::<Address>(env, &DataKey::Stats) {
+            let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &stats,
+                &symbol_short!("record"),
+                (env.current_contract_address(), metric, amount).into_val(env),
+            );
+        }
+    }
+
+    /// Set the UpgradeCoordinator contract address
+    /// 
+    /// Requires the admin's authorization. The first call sets the
+    /// coordinator; replacing it also requires the current coordinator's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `coordinator` - Address of the UpgradeCoordinator contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_upgrade_coordinator(
+        env: Env,
+        coordinator: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(current) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            current.require_auth();
+        }
+        storage.set(&DataKey::Upgrader, &coordinator);
+        Self::audit(&env, symbol_short!("upgrader"), Some(coordinator), None);
+        Ok(())
+    }
+
+    /// Pause registrations and purchases while an upgrade is applied
+    /// 
+    /// This is the UpgradeCoordinator's pause and blocks every
+    /// state-changing call. The admin's `emergency_pause` is kept apart so
+    /// that an upgrade finishing does not lift an incident halt, and the
+    /// other way round; both fail calls with `Error::Paused`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn pause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        Self::audit(&env, symbol_short!("pause"), None, None);
+        Ok(())
+    }
+
+    /// Resume registrations and purchases after an upgrade
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &false);
+        Self::audit(&env, symbol_short!("unpause"), None, None);
+        Ok(())
+    }
+
+    /// Halt purchases and payouts during an incident
+    /// 
+    /// Unlike `pause`, which the UpgradeCoordinator uses during upgrades,
+    /// the emergency pause is the admin's and leaves registrations, listing
+    /// management and reads available. While it is on, purchases of every
+    /// kind, query consumption, license transfers, escrow settlements and
+    /// refunds fail with `Error::Paused`, as they do under `pause`. Each
+    /// pause has its own flag and is lifted only by its own unpause.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn emergency_pause(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&ExtKey::EmergencyPaused, &true);
+        Self::audit(&env, symbol_short!("pause"), None, None);
+        Ok(())
+    }
+
+    /// Lift the emergency pause
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn emergency_unpause(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&ExtKey::EmergencyPaused);
+        Self::audit(&env, symbol_short!("unpause"), None, None);
+        Ok(())
+    }
+
+    /// Check whether the admin's emergency pause is on
+    pub fn is_emergency_paused(env: Env) -> bool {
+        env.storage().instance().get(&ExtKey::EmergencyPaused).unwrap_or(false)
+    }
+
+    /// Check whether the contract is paused
+    /// 
+    /// A tripped CircuitBreaker pauses the contract as well, until its
+    /// guardian resumes it, and so does a pause by the GuardianSet.
+    pub fn is_paused(env: Env) -> bool {
+        if Self::get_instance(&env, &DataKey::Paused).unwrap_or(false) {
+            return true;
+        }
+        if let Some(guardians) = Self::get_instance::<Address>(&env, &DataKey::Guardians) {
+            if let Ok(Ok(true)) = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &guardians,
+                &Symbol::new(&env, "is_paused"),
+                ().into_val(&env),
+            ) {
+                return true;
+            }
+        }
+        match Self::get_instance::<Address>(&env, &DataKey::Breaker) {
+            Some(breaker) => matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &breaker,
+                    &Symbol::new(&env, "is_tripped"),
+                    ().into_val(&env),
+                ),
+                Ok(Ok(true))
+            ),
+            None => false,
+        }
+    }
+
+    /// Get the configuration health of the contract
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Health snapshot; `ready` requires the splitter, the registry and
+    ///   a token behind the splitter, and no pause
+    pub fn health(env: Env) -> Health {
+        let revenue_splitter = Self::get_instance::<Address>(&env, &DataKey::RevenueSplitter);
+        let registry_set = Self::has_instance(&env, &DataKey::StudyRegistry);
+        let token_set = match &revenue_splitter {
+            Some(splitter) => matches!(
+                env.try_invoke_contract::<Address, soroban_sdk::Error>(
+                    splitter,
+                    &Symbol::new(&env, "get_usdc_token"),
+                    Vec::new(&env),
+                ),
+                Ok(Ok(_))
+            ),
+            None => false,
+        };
+        let paused = Self::is_paused(env.clone());
+        
+        Health {
+            splitter_set: revenue_splitter.is_some(),
+            registry_set,
+            token_set,
+            upgrader_set: Self::has_instance(&env, &DataKey::Upgrader),
+            audit_log_set: Self::has_instance(&env, &DataKey::AuditLog),
+            paused,
+            schema_version: Self::schema_version(env.clone()),
+            ready: revenue_splitter.is_some() && registry_set && token_set && !paused,
+        }
+    }
+
+    /// Replace the contract WASM
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_wasm_hash` - Hash of the uploaded WASM to switch to
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn upgrade(
+        env: Env,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        Self::audit(&env, symbol_short!("upgrade"), None, Some(new_wasm_hash.clone()));
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Run storage migrations for the current code
+    /// 
+    /// Called by the UpgradeCoordinator right after `upgrade`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the interface version after migration
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        Self::require_upgrader(&env)?;
+        
+        if Self::schema_version(env.clone()) < 2 {
+            Self::migrate_v1_to_v2(&env);
+        }
+        if Self::schema_version(env.clone()) < 3 {
+            Self::migrate_v2_to_v3(&env);
+        }
+        if Self::schema_version(env.clone()) < 4 {
+            Self::migrate_v3_to_v4(&env);
+        }
+        
+        Self::audit(&env, symbol_short!("migrate"), None, None);
+        Ok(INTERFACE_VERSION)
+    }
+
+    /// Get the crate version of the deployed code
+    pub fn version(env: Env) -> String {
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Get the interface version implemented by the current code
+    pub fn interface_version(_env: Env) -> u32 {
+        INTERFACE_VERSION
+    }
+
+    /// Migrate storage from schema 1 to schema 2
+    /// 
+    /// Datasets and purchases cannot be enumerated, so they keep decoding
+    /// through `load_dataset` / `load_purchase` until rewritten by
+    /// `migrate_datasets` / `migrate_purchases`.
+    fn migrate_v1_to_v2(env: &Env) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Schema, &2u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (1u32, 2u32),
+        );
+    }
+
+    /// Migrate storage from schema 2 to schema 3
+    /// 
+    /// Configuration entries are moved to their `DataKey`. Records cannot be
+    /// enumerated, so they stay readable under their legacy keys (see
+    /// `DataKey::legacy`) until rewritten by `migrate_datasets` /
+    /// `migrate_purchases`.
+    fn migrate_v2_to_v3(env: &Env) {
+        let storage = env.storage().instance();
+        for key in CONFIG_KEYS.iter() {
+            let legacy_key = key.legacy(env);
+            if let Some(value) = storage.get::<_, Val>(&legacy_key) {
+                if !storage.has(key) {
+                    storage.set(key, &value);
+                }
+                storage.remove(&legacy_key);
+            }
+        }
+        storage.set(&DataKey::Schema, &3u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (2u32, 3u32),
+        );
+    }
+
+    /// Migrate storage from schema 3 to schema 4
+    /// 
+    /// Records cannot be enumerated, so they stay readable from instance
+    /// storage (see `get_record`) until moved to persistent storage by
+    /// `migrate_datasets` / `migrate_purchases`.
+    fn migrate_v3_to_v4(env: &Env) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Schema, &4u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (3u32, 4u32),
+        );
+    }
+
+    /// Record a buyer's acknowledgement of the listing's current license
+    fn store_license_ack(
+        env: &Env,
+        dataset_id: &Bytes,
+        buyer: &Address,
+        document_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let license = Self::get_dataset_license(env.clone(), dataset_id.clone())
+            .ok_or(Error::LicenseNotFound)?;
+        if license.document_hash != document_hash {
+            return Err(Error::LicenseNotAcknowledged);
+        }
+        Self::set_persistent(
+            env,
+            &DataKey::LicenseAck(dataset_id.clone(), buyer.clone()),
+            &document_hash,
+        );
+        
+        env.events().publish(
+            (symbol_short!("LicAck"), dataset_id.clone(), buyer.clone()),
+            (license.license_id, license.version, document_hash),
+        );
+        Ok(())
+    }
+
+    /// Check the buyer acknowledged the listing's current license
+    /// 
+    /// # Returns
+    /// * `Ok(Some(hash))` the acknowledged document hash
+    /// * `Ok(None)` if the listing has no license bound
+    /// * `Err(Error::LicenseNotAcknowledged)` if the buyer has not
+    ///   acknowledged the current license version
+    fn acknowledged_license(
+        env: &Env,
+        dataset_id: &Bytes,
+        buyer: &Address,
+    ) -> Result<Option<BytesN<32>>, Error> {
+        let Some(license) = Self::get_dataset_license(env.clone(), dataset_id.clone()) else {
+            return Ok(None);
+        };
+        match Self::get_license_ack(env.clone(), dataset_id.clone(), buyer.clone()) {
+            Some(hash) if hash == license.document_hash => Ok(Some(hash)),
+            _ => Err(Error::LicenseNotAcknowledged),
+        }
+    }
+
+    /// Load a dataset and require its owner's authorization
+    fn load_owned_dataset(env: &Env, dataset_id: &Bytes) -> Result<Dataset, Error> {
+        Self::require_not_paused(env)?;
+        let dataset = Self::load_dataset(env, dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        dataset.owner.require_auth();
+        Ok(dataset)
+    }
+
+    /// Store a dataset in the current layout
+    fn save_dataset(env: &Env, dataset: Dataset) {
+        Self::set_persistent(
+            env,
+            &DataKey::Dataset(dataset.dataset_id.clone()),
+            &VersionedDataset::V3(dataset),
+        );
+    }
+
+    /// Move a dataset to `status` and emit the transition
+    fn set_status(env: &Env, mut dataset: Dataset, status: DatasetStatus) {
+        let previous = dataset.status;
+        dataset.status = status;
+        let dataset_id = dataset.dataset_id.clone();
+        Self::save_dataset(env, dataset);
+        
+        env.events().publish(
+            (symbol_short!("DsStatus"), dataset_id),
+            (previous, status),
+        );
+    }
+
+    /// Index a dataset under each of its studies
+    fn index_studies(env: &Env, dataset_id: &Bytes, study_ids: &Vec<Bytes>) {
+        let persistent = env.storage().persistent();
+        for study_id in study_ids.iter() {
+            let study_key = DataKey::StudyDatasets(study_id);
+            let mut study_datasets: Vec<Bytes> = persistent.get(&study_key)
+                .unwrap_or(Vec::new(env));
+            if !study_datasets.contains(dataset_id) {
+                study_datasets.push_back(dataset_id.clone());
+                Self::set_persistent(env, &study_key, &study_datasets);
+            }
+        }
+    }
+
+    /// Load a dataset written by any schema version
+    fn load_dataset(
+        env: &Env,
+        dataset_id: &Bytes,
+    ) -> Option<Dataset> {
+        let raw: Val = Self::get_record(env, &DataKey::Dataset(dataset_id.clone()))?;
+        Self::decode_dataset(env, &raw)
+    }
+
+    /// Decode a stored dataset in any record layout
+    /// 
+    /// Listings from before owners are owned by the admin, or by this
+    /// contract (so nobody can update them) if no admin is set.
+    fn decode_dataset(env: &Env, raw: &Val) -> Option<Dataset> {
+        let legacy = match VersionedDataset::try_from_val(env, raw) {
+            Ok(VersionedDataset::V3(dataset)) => return Some(dataset),
+            Ok(VersionedDataset::V2(dataset)) => return Some(dataset.into()),
+            Ok(VersionedDataset::V1(dataset)) => dataset,
+            // Schema 1 stored the record unwrapped
+            Err(_) => DatasetV1::try_from_val(env, raw).ok()?,
+        };
+        let owner = Self::get_instance(env, &DataKey::Admin)
+            .unwrap_or(env.current_contract_address());
+        Some(legacy.with_owner(owner))
+    }
+
+    /// Load a purchase record written by any schema version
+    fn load_purchase(
+        env: &Env,
+        dataset_id: &Bytes,
+        buyer: &Address,
+    ) -> Option<PurchaseRecord> {
+        let raw: Val = Self::get_record(
+            env,
+            &DataKey::Purchase(dataset_id.clone(), buyer.clone()),
+        )?;
+        Self::decode_purchase(env, &raw)
+    }
+
+    /// Decode a stored purchase record in any record layout
+    fn decode_purchase(env: &Env, raw: &Val) -> Option<PurchaseRecord> {
+        match VersionedPurchase::try_from_val(env, raw) {
+            Ok(VersionedPurchase::V1(purchase)) => Some(purchase.into()),
+            Ok(VersionedPurchase::V2(purchase)) => Some(purchase),
+            // Schema 1 stored the record unwrapped
+            Err(_) => PurchaseRecordV1::try_from_val(env, raw).ok().map(Into::into),
+        }
+    }
+
+    /// Read an instance entry, falling back to its pre-schema-3 key
+    fn get_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let storage = env.storage().instance();
+        storage.get(key).or_else(|| storage.get(&key.legacy(env)))
+    }
+
+    /// Check an instance entry under its `DataKey` or pre-schema-3 key
+    fn has_instance(env: &Env, key: &DataKey) -> bool {
+        let storage = env.storage().instance();
+        storage.has(key) || storage.has(&key.legacy(env))
+    }
+
+    /// Read a dataset or purchase record and extend its TTL
+    /// 
+    /// Falls back to instance storage for records written before schema 4
+    /// and not yet migrated.
+    fn get_record<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let storage = env.storage().persistent();
+        match storage.get(key) {
+            Some(value) => {
+                storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+                Some(value)
+            }
+            None => Self::get_instance(env, key),
+        }
+    }
+
+    /// Remove a dataset or purchase record wherever it is stored
+    fn remove_record(env: &Env, key: &DataKey) {
+        env.storage().persistent().remove(key);
+        let storage = env.storage().instance();
+        storage.remove(key);
+        storage.remove(&key.legacy(env));
+    }
+
+    /// Check a dataset or purchase record in persistent or instance storage
+    fn has_record(env: &Env, key: &DataKey) -> bool {
+        env.storage().persistent().has(key) || Self::has_instance(env, key)
+    }
+
+    /// Extend the TTL of a dataset or purchase record
+    /// 
+    /// # Returns
+    /// * `true` if the record exists
+    fn extend_record_ttl(env: &Env, key: &DataKey) -> bool {
+        let storage = env.storage().persistent();
+        if storage.has(key) {
+            storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            true
+        } else if Self::has_instance(env, key) {
+            env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Read a persistent entry, falling back to its pre-schema-3 key
+    fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let storage = env.storage().persistent();
+        storage.get(key).or_else(|| storage.get(&key.legacy(env)))
+    }
+
+    /// Check a persistent entry under its `DataKey` or pre-schema-3 key
+    fn has_persistent(env: &Env, key: &DataKey) -> bool {
+        let storage = env.storage().persistent();
+        storage.has(key) || storage.has(&key.legacy(env))
+    }
+
+    /// Read a persistent index of dataset IDs
+    /// 
+    /// Indexes are only appended to under their `DataKey`, so IDs still
+    /// listed under the pre-schema-3 key are returned first.
+    fn get_index(env: &Env, key: &DataKey) -> Vec<Bytes> {
+        let storage = env.storage().persistent();
+        let mut index: Vec<Bytes> = storage.get(&key.legacy(env))
+            .unwrap_or(Vec::new(env));
+        index.append(&storage.get(key).unwrap_or(Vec::new(env)));
+        index
+    }
+
+    /// Write a persistent entry under its `DataKey` and extend its TTL
+    fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        let storage = env.storage().persistent();
+        storage.set(key, value);
+        storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Check a listing's access policy lets a buyer purchase it
+    /// 
+    /// A CredentialRegistry that cannot answer counts as not verified.
+    fn eligibility(env: &Env, dataset_id: &Bytes, buyer: &Address) -> Result<(), ExtError> {
+        match Self::get_access_policy(env.clone(), dataset_id.clone()) {
+            AccessPolicy::Open => Ok(()),
+            AccessPolicy::Allowlist => {
+                if Self::is_allowlisted(env.clone(), dataset_id.clone(), buyer.clone()) {
+                    Ok(())
+                } else {
+                    Err(ExtError::BuyerNotEligible)
+                }
+            },
+            AccessPolicy::CredentialGated(credential_type) => {
+                let registry = Self::get_credential_registry(env.clone())
+                    .ok_or(ExtError::CredentialRegistryNotSet)?;
+                match CredentialRegistryClient::new(env, &registry).try_is_verified(buyer, &credential_type) {
+                    Ok(Ok(true)) => Ok(()),
+                    _ => Err(ExtError::BuyerNotEligible),
+                }
+            },
+        }
+    }
+
+    /// Check a listing can be sold to a buyer
+    /// 
+    /// Shared by direct and namespaced purchases: the listing must be
+    /// active, its dataset not under review, and its access policy must
+    /// let the buyer purchase it.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if the listing can be sold
+    /// * `Err(Error::DatasetNotActive)` if the listing is not active
+    /// * `Err(Error::DatasetUnderReview)` if the dataset is under review
+    fn require_purchasable(env: &Env, dataset: &Dataset, buyer: &Address) -> Result<(), Error> {
+        if dataset.status != DatasetStatus::Active {
+            return Err(Error::DatasetNotActive);
+        }
+        if Self::get_dataset_review(env.clone(), dataset.dataset_id.clone()).is_some() {
+            return Err(Error::DatasetUnderReview);
+        }
+        Self::require_eligible(env, &dataset.dataset_id, buyer);
+        Ok(())
+    }
+
+    /// Abort the purchase unless the listing's access policy lets the
+    /// buyer purchase it
+    fn require_eligible(env: &Env, dataset_id: &Bytes, buyer: &Address) {
+        if let Err(error) = Self::eligibility(env, dataset_id, buyer) {
+            panic_with_error!(env, error);
+        }
+    }
+
+    /// Append a listing to a tag's index
+    fn index_tag(env: &Env, tag: &Symbol, dataset_id: &Bytes) {
+        let count = Self::tag_dataset_count(env.clone(), tag.clone());
+        Self::set_ext_persistent(env, &ExtKey::TagDataset(tag.clone(), count), dataset_id);
+        Self::set_ext_persistent(env, &ExtKey::TagPosition(tag.clone(), dataset_id.clone()), &count);
+        Self::set_ext_persistent(env, &ExtKey::TagCount(tag.clone()), &(count + 1));
+    }
+
+    /// Remove a listing from a tag's index, moving the last listing into
+    /// its position
+    fn unindex_tag(env: &Env, tag: &Symbol, dataset_id: &Bytes) {
+        let persistent = env.storage().persistent();
+        let position_key = ExtKey::TagPosition(tag.clone(), dataset_id.clone());
+        let Some(position) = persistent.get::<_, u32>(&position_key) else {
+            return;
+        };
+        let last = Self::tag_dataset_count(env.clone(), tag.clone()) - 1;
+        if position != last {
+            if let Some(moved) = persistent.get::<_, Bytes>(&ExtKey::TagDataset(tag.clone(), last)) {
+                Self::set_ext_persistent(env, &ExtKey::TagDataset(tag.clone(), position), &moved);
+                Self::set_ext_persistent(env, &ExtKey::TagPosition(tag.clone(), moved), &position);
+            }
+        }
+        persistent.remove(&ExtKey::TagDataset(tag.clone(), last));
+        persistent.remove(&position_key);
+        Self::set_ext_persistent(env, &ExtKey::TagCount(tag.clone()), &last);
+    }
+
+    /// Check a listing's metadata fits the on-chain limits
+    fn require_valid_metadata(metadata: &DatasetMetadata) -> Result<(), ExtError> {
+        if metadata.title.is_empty()
+            || metadata.title.len() > MAX_TITLE_LEN
+            || metadata.license_id.len() > MAX_LICENSE_ID_LEN
+            || metadata.tags.len() > MAX_METADATA_TAGS
+        {
+            return Err(ExtError::InvalidMetadata);
+        }
+        for (index, tag) in metadata.tags.iter().enumerate() {
+            if metadata.tags.first_index_of(&tag) != Some(index as u32) {
+                return Err(ExtError::InvalidMetadata);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a persistent entry under its `ExtKey` and extend its TTL
+    fn set_ext_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &ExtKey, value: &V) {
+        let storage = env.storage().persistent();
+        storage.set(key, value);
+        storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Set the AuditLog contract address
+    /// 
+    /// Requires the admin's authorization, and the UpgradeCoordinator's
+    /// once one is set, so the audit trail cannot be redirected by anyone
+    /// else.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `audit_log` - Address of the AuditLog contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_audit_log(
+        env: Env,
+        audit_log: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::AuditLog, &audit_log);
+        Self::audit(&env, symbol_short!("audit"), Some(audit_log), None);
+        Ok(())
+    }
+
+    /// Set the CircuitBreaker contract address
+    /// 
+    /// The contract reports each purchase to the breaker and treats a
+    /// tripped breaker as a pause of registrations and purchases. Requires the
+    /// admin's authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `breaker` - Address of the CircuitBreaker contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_circuit_breaker(
+        env: Env,
+        breaker: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::Breaker, &breaker);
+        Self::audit(&env, symbol_short!("config"), Some(breaker), None);
+        Ok(())
+    }
+
+    /// Set the GuardianSet contract address
+    /// 
+    /// Any guardian of the set can then pause registrations and purchases;
+    /// resuming takes the set's M-of-N approval. Requires the
+    /// admin's authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardians` - Address of the GuardianSet contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_guardian_set(
+        env: Env,
+        guardians: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::Guardians, &guardians);
+        Self::audit(&env, symbol_short!("config"), Some(guardians), None);
+        Ok(())
+    }
+
+    /// Set the TokenAllowlist contract address
+    /// 
+    /// Purchases are then refused while the allowlist does not accept the
+    /// splitter's USDC token. Requires the admin's
+    /// authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `allowlist` - Address of the TokenAllowlist contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_token_allowlist(
+        env: Env,
+        allowlist: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::TokenAllowlist, &allowlist);
+        Self::audit(&env, symbol_short!("config"), Some(allowlist), None);
+        Ok(())
+    }
+
+    /// Accept a token for pricing listings, besides the splitter's USDC token
+    /// 
+    /// Owners can then register datasets priced in the token, e.g. XLM or
+    /// EURC; RevenueSplitter pays them out in the same token. Adding a
+    /// listed token again does nothing. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Address of the token contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::TooManyPaymentTokens)` if `MAX_PAYMENT_TOKENS` are
+    ///   listed
+    pub fn add_payment_token(env: Env, token: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut tokens = Self::get_payment_tokens(env.clone());
+        if tokens.contains(&token) {
+            return Ok(());
+        }
+        if tokens.len() >= MAX_PAYMENT_TOKENS {
+            return Err(Error::TooManyPaymentTokens);
+        }
+        tokens.push_back(token.clone());
+        env.storage().instance().set(&DataKey::PaymentTokens, &tokens);
+        Self::audit(&env, symbol_short!("config"), Some(token), None);
+        Ok(())
+    }
+
+    /// Stop accepting a token for listings
+    /// 
+    /// Listings priced in the token stay registered but cannot be
+    /// purchased until the token is added again. Requires the admin's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Address of the token contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::TokenNotAccepted)` if the token is not listed
+    pub fn remove_payment_token(env: Env, token: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut tokens = Self::get_payment_tokens(env.clone());
+        let index = tokens.first_index_of(&token).ok_or(Error::TokenNotAccepted)?;
+        tokens.remove(index);
+        env.storage().instance().set(&DataKey::PaymentTokens, &tokens);
+        Self::audit(&env, symbol_short!("config"), Some(token), None);
+        Ok(())
+    }
+
+    /// Get the tokens listings can be priced in besides the splitter's
+    /// USDC token
+    pub fn get_payment_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PaymentTokens)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the token a dataset is paid in
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` the listing's payment token, or the splitter's USDC
+    ///   token if none was set
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::RevenueSplitterNotSet)` if the listing is priced in
+    ///   USDC and no splitter is set
+    pub fn get_dataset_token(env: Env, dataset_id: Bytes) -> Result<Address, Error> {
+        if !Self::has_record(&env, &DataKey::Dataset(dataset_id.clone())) {
+            return Err(Error::DatasetNotFound);
+        }
+        if let Some(token) = Self::get_persistent(&env, &DataKey::DatasetToken(dataset_id)) {
+            return Ok(token);
+        }
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        Ok(env.invoke_contract(
+            &revenue_splitter,
+            &Symbol::new(&env, "get_usdc_token"),
+            Vec::new(&env),
+        ))
+    }
+
+    /// Set the price oracle listings priced in USD are converted with
+    /// 
+    /// The oracle must implement SEP-40 (e.g. Reflector) and quote each
+    /// payment token of those listings in USD. Requires the admin's
+    /// authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `oracle` - Address of the price oracle contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_price_oracle(
+        env: Env,
+        oracle: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        storage.set(&DataKey::PriceOracle, &oracle);
+        Self::audit(&env, symbol_short!("config"), Some(oracle), None);
+        Ok(())
+    }
+
+    /// Get the price oracle address, if set
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        Self::get_instance(&env, &DataKey::PriceOracle)
+    }
+
+    /// Set the CredentialRegistry credential-gated listings check buyers
+    /// against
+    /// 
+    /// Requires the admin's authorization, and the UpgradeCoordinator's
+    /// once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `registry` - Address of the CredentialRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_credential_registry(env: Env, registry: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        env.storage().instance().set(&ExtKey::CredentialRegistry, &registry);
+        Self::audit(&env, symbol_short!("config"), Some(registry), None);
+        Ok(())
+    }
+
+    /// Get the CredentialRegistry address, if set
+    pub fn get_credential_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ExtKey::CredentialRegistry)
+    }
+
+    /// Set or clear the AccessPass contract buyers get a pass from
+    /// 
+    /// Once set, every purchase mints or renews a soulbound pass for the
+    /// buyer carrying the access expiry, and an approved refund burns it.
+    /// Purchases made before it was set have no pass. Requires the admin's
+    /// authorization, and the UpgradeCoordinator's once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `access_pass` - Address of the AccessPass contract (None to stop
+    ///   minting passes)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_access_pass(env: Env, access_pass: Option<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        match &access_pass {
+            Some(access_pass) => env.storage().instance().set(&ExtKey::AccessPass, access_pass),
+            None => env.storage().instance().remove(&ExtKey::AccessPass),
+        }
+        Self::audit(&env, symbol_short!("config"), access_pass, None);
+        Ok(())
+    }
+
+    /// Get the AccessPass contract address, if set
+    pub fn get_access_pass(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ExtKey::AccessPass)
+    }
+
+    /// Set or clear the gateway allowed to report consumed queries
+    /// 
+    /// Requires the admin's authorization, and the UpgradeCoordinator's
+    /// once one is set.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `gateway` - Data-delivery gateway calling `consume_query` (None
+    ///   to stop query consumption)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_query_gateway(env: Env, gateway: Option<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        match &gateway {
+            Some(gateway) => env.storage().instance().set(&ExtKey::QueryGateway, gateway),
+            None => env.storage().instance().remove(&ExtKey::QueryGateway),
+        }
+        Self::audit(&env, symbol_short!("config"), gateway, None);
+        Ok(())
     }
 
-    /// Get a dataset by ID
+    /// Get the gateway allowed to report consumed queries, if set
+    pub fn get_query_gateway(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ExtKey::QueryGateway)
+    }
+
+    /// Set or clear the ContributorReputation contract
+    /// 
+    /// Once set, every resolved refund request is reported as a dispute
+    /// upheld against, or won by, the contributors of the dataset. This
+    /// contract must be added as a reporter in ContributorReputation.
+    /// Requires the admin's authorization, and the UpgradeCoordinator's
+    /// once one is set.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `dataset_id` - ID of the dataset to retrieve
+    /// * `reputation` - Address of the ContributorReputation contract
+    ///   (None to stop reporting)
     /// 
     /// # Returns
-    /// * `Ok(Dataset)` if found
-    /// * `Err(Error::DatasetNotFound)` if not found
-    pub fn get_dataset(
-        env: Env,
-        dataset_id: Bytes,
-    ) -> Result<Dataset, Error> {
-        let storage = env.storage().instance();
-        let storage_key = (DATASET_KEY, dataset_id);
-        
-        storage.get(&storage_key)
-            .ok_or(Error::DatasetNotFound)
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_reputation_contract(env: Env, reputation: Option<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        match &reputation {
+            Some(reputation) => env.storage().instance().set(&ExtKey::Reputation, reputation),
+            None => env.storage().instance().remove(&ExtKey::Reputation),
+        }
+        Self::audit(&env, symbol_short!("config"), reputation, None);
+        Ok(())
     }
 
-    /// Check if a dataset exists
+    /// Get the ContributorReputation contract address, if set
+    pub fn get_reputation_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ExtKey::Reputation)
+    }
+
+    /// Get how a dataset listing is priced (`PriceMode::Fixed` unless its
+    /// owner changed it)
+    pub fn get_price_mode(env: Env, dataset_id: Bytes) -> PriceMode {
+        Self::get_persistent(&env, &DataKey::DatasetPriceMode(dataset_id))
+            .unwrap_or(PriceMode::Fixed)
+    }
+
+    /// Get the amount of its payment token a dataset currently sells for
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `dataset_id` - ID of the dataset to check
+    /// * `dataset_id` - ID of the dataset
     /// 
     /// # Returns
-    /// * `true` if dataset exists, `false` otherwise
-    pub fn dataset_exists(
-        env: &Env,
-        dataset_id: &Bytes,
-    ) -> bool {
-        let storage = env.storage().instance();
-        let storage_key = (DATASET_KEY, dataset_id.clone());
-        storage.has(&storage_key)
+    /// * `Ok(i128)` the listed price, converted at the oracle's latest
+    ///   price for listings priced in USD
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    /// * `Err(Error::RevenueSplitterNotSet)` if no splitter is set
+    /// * `Err(Error)` if the oracle cannot price the listing (see
+    ///   `purchase_dataset`)
+    pub fn quote_price(env: Env, dataset_id: Bytes) -> Result<i128, Error> {
+        let dataset = Self::load_dataset(&env, &dataset_id)
+            .ok_or(Error::DatasetNotFound)?;
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)
+            .ok_or(Error::RevenueSplitterNotSet)?;
+        let payment_token = Self::dataset_token(&env, &dataset_id, &revenue_splitter);
+        Self::listed_price(&env, &dataset, &payment_token)
     }
 
-    /// Get purchase record for a buyer and dataset
+    /// Set or clear the escrow expensive purchases are held in
+    /// 
+    /// Applies to purchases made after the change; purchases already in
+    /// escrow are settled through the escrow that holds them, so clear it
+    /// only once they are released. Requires the admin's authorization,
+    /// and the UpgradeCoordinator's once one is set.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `dataset_id` - ID of the dataset
-    /// * `buyer` - Address of the buyer
+    /// * `config` - Escrow contract and minimum price (None to pay every
+    ///   purchase out at once)
     /// 
     /// # Returns
-    /// * `Ok(PurchaseRecord)` if found
-    /// * `Err(Error::DatasetNotFound)` if not found
-    pub fn get_purchase(
-        env: Env,
-        dataset_id: Bytes,
-        buyer: Address,
-    ) -> Result<PurchaseRecord, Error> {
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::InvalidPrice)` if the minimum price is not positive
+    pub fn set_escrow(env: Env, config: Option<EscrowConfig>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
         let storage = env.storage().instance();
-        let purchase_key = (PURCHASE_KEY, dataset_id, buyer);
-        
-        storage.get(&purchase_key)
-            .ok_or(Error::DatasetNotFound)
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        match config {
+            Some(config) => {
+                if config.min_price <= 0 {
+                    return Err(Error::InvalidPrice);
+                }
+                storage.set(&ExtKey::Escrow, &config);
+                Self::audit(&env, symbol_short!("config"), Some(config.escrow), None);
+            },
+            None => {
+                storage.remove(&ExtKey::Escrow);
+                Self::audit(&env, symbol_short!("config"), None, None);
+            },
+        }
+        Ok(())
+    }
+
+    /// Get the escrow configuration (None if purchases are paid out at
+    /// once)
+    pub fn get_escrow(env: Env) -> Option<EscrowConfig> {
+        env.storage().instance().get(&ExtKey::Escrow)
     }
 
-    /// Verify payment (mock implementation)
+    /// Set or clear the platform fee
     /// 
-    /// In production, this would:
-    /// 1. Get USDC token contract address
-    /// 2. Check buyer's balance
-    /// 3. Verify buyer has authorized payment
-    /// 4. Transfer USDC from buyer to contract
-    /// 5. Verify transfer succeeded
+    /// Applies to payments forwarded to RevenueSplitter after the change,
+    /// escrowed payments included once settled. The fee is sent to the
+    /// collector and only the rest is split between contributors and the
+    /// treasury. Requires the admin's authorization, and the
+    /// UpgradeCoordinator's once one is set.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `buyer` - Address of the buyer
-    /// * `amount` - Amount to verify
+    /// * `config` - Fee and collector (None to take no fee)
     /// 
     /// # Returns
-    /// * `true` if payment is valid (mock: always true for now)
-    /// * `false` otherwise
-    fn verify_payment_mock(
-        env: &Env,
-        buyer: &Address,
-        amount: &I128,
-    ) -> bool {
-        // Mock verification: In production, this would:
-        // 1. Get USDC token contract
-        // 2. Check balance
-        // 3. Transfer funds
-        // 4. Verify success
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(ExtError::InvalidFee)` if the fee is above
+    ///   MAX_PLATFORM_FEE_BPS
+    pub fn set_fee_config(env: Env, config: Option<FeeConfig>) -> Result<(), soroban_sdk::Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        match &config {
+            Some(config) => {
+                if config.fee_bps > MAX_PLATFORM_FEE_BPS {
+                    return Err(ExtError::InvalidFee.into());
+                }
+                env.storage().instance().set(&ExtKey::FeeConfig, config);
+            },
+            None => env.storage().instance().remove(&ExtKey::FeeConfig),
+        }
+        Self::audit(&env, symbol_short!("config"), config.as_ref().map(|config| config.collector.clone()), None);
         
-        // For now, just check that amount is positive
-        *amount > I128::from(0)
+        env.events().publish((symbol_short!("FeeCfg"),), config);
+        Ok(())
     }
 
-    /// Set the RevenueSplitter contract address
+    /// Get the platform fee (None if no fee is taken)
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&ExtKey::FeeConfig)
+    }
+
+    /// Set the refund window, arbiter and refund source
+    /// 
+    /// Applies to refunds requested after the change. Requires the admin's
+    /// authorization, and the UpgradeCoordinator's once one is set.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// * `policy` - Refund policy
     /// 
     /// # Returns
     /// * `Ok(())` if successful
-    pub fn set_revenue_splitter(
-        env: Env,
-        revenue_splitter: Address,
-    ) -> Result<(), Error> {
-        let storage = env.storage().instance();
-        storage.set(&REVENUE_SPLITTER_KEY, &revenue_splitter);
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_refund_policy(env: Env, policy: RefundPolicy) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        env.storage().instance().set(&DataKey::RefundPolicy, &policy);
+        Self::audit(&env, symbol_short!("config"), Some(policy.arbiter), None);
         Ok(())
     }
 
-    /// Set the StudyRegistry contract address
+    /// Set how licenses react when studies are revoked
+    /// 
+    /// Applies to revocations reported after the change. Requires the
+    /// admin's authorization, and the UpgradeCoordinator's once one is set.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `study_registry` - Address of the StudyRegistry contract
+    /// * `policy` - Revocation policy
     /// 
     /// # Returns
     /// * `Ok(())` if successful
-    pub fn set_study_registry(
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::InvalidRevocationPolicy)` if `refund_bps` exceeds
+    ///   10000 or is set without a refund source
+    pub fn set_revocation_policy(
         env: Env,
-        study_registry: Address,
+        policy: RevocationPolicy,
     ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
         let storage = env.storage().instance();
-        storage.set(&STUDY_REGISTRY_KEY, &study_registry);
+        if let Some(coordinator) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            coordinator.require_auth();
+        }
+        if policy.refund_bps > pricing::BPS_DENOMINATOR
+            || (policy.refund_bps > 0 && policy.refund_source.is_none())
+        {
+            return Err(Error::InvalidRevocationPolicy);
+        }
+        storage.set(&DataKey::RevocationPolicy, &policy);
+        Self::audit(&env, symbol_short!("config"), policy.refund_source, None);
+        Ok(())
+    }
+
+    /// Add a sale to the report of the buyer's jurisdiction
+    /// 
+    /// Skipped when no compliance registry is configured. A failing
+    /// jurisdiction lookup is reported as `UNKNOWN_JURISDICTION` rather than
+    /// blocking the purchase.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Overflow)` if the epoch volume overflows
+    fn record_jurisdiction_sale(
+        env: &Env,
+        buyer: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let Some(compliance) = Self::get_instance::<Address>(env, &DataKey::Compliance) else {
+            return Ok(());
+        };
+        let jurisdiction = match env.try_invoke_contract::<Option<Symbol>, soroban_sdk::Error>(
+            &compliance,
+            &Symbol::new(env, "get_jurisdiction"),
+            (buyer.clone(),).into_val(env),
+        ) {
+            Ok(Ok(Some(jurisdiction))) => jurisdiction,
+            _ => UNKNOWN_JURISDICTION,
+        };
+        
+        let epoch = env.ledger().timestamp() / REPORT_EPOCH_SECONDS;
+        let mut report = Self::get_jurisdiction_report(env.clone(), jurisdiction.clone(), epoch);
+        report.purchases = report.purchases.saturating_add(1);
+        report.volume = pricing::math::checked_add(report.volume, amount)
+            .map_err(|_| Error::Overflow)?;
+        Self::set_persistent(env, &DataKey::JurisdictionReport(jurisdiction, epoch), &report);
+        Ok(())
+    }
+
+    /// Report an observation to the CircuitBreaker contract
+    /// 
+    /// Reporting is best-effort like analytics: if no breaker is configured
+    /// or the call fails, the purchase still goes through. A trip only blocks
+    /// the calls that follow.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - CircuitBreaker metric symbol ("purchase")
+    /// * `amount` - Observed amount
+    fn report_to_breaker(
+        env: &Env,
+        metric: Symbol,
+        amount: i128,
+    ) {
+        if let Some(breaker) = Self::get_instance::<Address>(env, &DataKey::Breaker) {
+            let _ = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &breaker,
+                &symbol_short!("record"),
+                (env.current_contract_address(), metric, amount).into_val(env),
+            );
+        }
+    }
+
+    /// Append a record of a privileged action to the AuditLog contract
+    /// 
+    /// Auditing is best-effort like analytics: if no audit log is configured
+    /// or the call fails, the action still goes through, so a pause during
+    /// an incident can never be blocked by the log.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `action` - AuditLog action symbol ("pause", "upgrade", ...)
+    /// * `subject` - Address the action applied to, if any
+    /// * `data` - Hash the action applied to, if any
+    fn audit(
+        env: &Env,
+        action: Symbol,
+        subject: Option<Address>,
+        data: Option<BytesN<32>>,
+    ) {
+        if let Some(audit_log) = Self::get_instance::<Address>(env, &DataKey::AuditLog) {
+            let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+                &audit_log,
+                &symbol_short!("append"),
+                (
+                    env.current_contract_address(),
+                    action,
+                    subject,
+                    data.map(Bytes::from).unwrap_or(Bytes::new(env)),
+                ).into_val(env),
+            );
+        }
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(env, &DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require the UpgradeCoordinator's authorization
+    fn require_upgrader(env: &Env) -> Result<(), Error> {
+        let coordinator: Address = Self::get_instance(env, &DataKey::Upgrader)
+            .ok_or(Error::Unauthorized)?;
+        coordinator.require_auth();
+        Ok(())
+    }
+
+    /// Token a listing is paid in: its own, or the splitter's USDC token
+    fn dataset_token(env: &Env, dataset_id: &Bytes, revenue_splitter: &Address) -> Address {
+        Self::get_persistent(env, &DataKey::DatasetToken(dataset_id.clone()))
+            .unwrap_or_else(|| env.invoke_contract(
+                revenue_splitter,
+                &Symbol::new(env, "get_usdc_token"),
+                Vec::new(env),
+            ))
+    }
+
+    /// Store a buyer's purchase record and grant them access
+    fn record_purchase(
+        env: &Env,
+        dataset_id: &Bytes,
+        buyer: &Address,
+        purchase_id: &BytesN<32>,
+        license_hash: Option<BytesN<32>>,
+    ) {
+        Self::save_purchase(env, &PurchaseRecord {
+            buyer: buyer.clone(),
+            dataset_id: dataset_id.clone(),
+            tx_hash: purchase_id.clone().into(),
+            license_hash: license_hash.map(Into::into),
+        });
+        // Buyers of unversioned listings have version 1 without an entry
+        let version = Self::get_latest_version(env.clone(), dataset_id.clone());
+        if version > 1 {
+            Self::set_ext_persistent(
+                env,
+                &ExtKey::PurchasedVersion(dataset_id.clone(), buyer.clone()),
+                &version,
+            );
+        }
+        Self::grant_access(env, dataset_id, buyer);
+    }
+
+    /// Store a purchase record
+    /// 
+    /// A buyer's first record for a dataset also adds it to the buyer's
+    /// history and the buyer to the dataset's.
+    fn save_purchase(env: &Env, purchase: &PurchaseRecord) {
+        let (dataset_id, buyer) = (&purchase.dataset_id, &purchase.buyer);
+        // Key: DataKey::Purchase(dataset_id, buyer_address)
+        let purchase_key = DataKey::Purchase(dataset_id.clone(), buyer.clone());
+        if !Self::has_record(env, &purchase_key) {
+            let count = Self::purchase_count(env.clone(), buyer.clone());
+            Self::set_persistent(env, &DataKey::BuyerPurchase(buyer.clone(), count), dataset_id);
+            Self::set_ext_persistent(
+                env,
+                &ExtKey::BuyerPurchasePosition(buyer.clone(), dataset_id.clone()),
+                &count,
+            );
+            Self::set_persistent(env, &DataKey::BuyerPurchaseCount(buyer.clone()), &(count + 1));
+            let sales = Self::dataset_sales_count(env.clone(), dataset_id.clone());
+            Self::set_persistent(env, &DataKey::DatasetBuyer(dataset_id.clone(), sales), buyer);
+            Self::set_ext_persistent(
+                env,
+                &ExtKey::DatasetBuyerPosition(dataset_id.clone(), buyer.clone()),
+                &sales,
+            );
+            Self::set_persistent(env, &DataKey::DatasetBuyerCount(dataset_id.clone()), &(sales + 1));
+        }
+        Self::set_persistent(env, &purchase_key, &VersionedPurchase::V2(purchase.clone()));
+    }
+
+    /// Remove a listing from a buyer's purchase index, moving the buyer's
+    /// last purchase into its position
+    /// 
+    /// Purchases indexed before positions were recorded are looked up by
+    /// walking the buyer's index.
+    fn unindex_buyer_purchase(env: &Env, buyer: &Address, dataset_id: &Bytes) {
+        let persistent = env.storage().persistent();
+        let count = Self::purchase_count(env.clone(), buyer.clone());
+        let position_key = ExtKey::BuyerPurchasePosition(buyer.clone(), dataset_id.clone());
+        let position = persistent.get::<_, u32>(&position_key).or_else(|| {
+            (0..count).find(|position| {
+                persistent.get::<_, Bytes>(&DataKey::BuyerPurchase(buyer.clone(), *position)).as_ref()
+                    == Some(dataset_id)
+            })
+        });
+        let Some(position) = position else {
+            return;
+        };
+        let last = count - 1;
+        if position != last {
+            if let Some(moved) = persistent.get::<_, Bytes>(&DataKey::BuyerPurchase(buyer.clone(), last)) {
+                Self::set_persistent(env, &DataKey::BuyerPurchase(buyer.clone(), position), &moved);
+                Self::set_ext_persistent(env, &ExtKey::BuyerPurchasePosition(buyer.clone(), moved), &position);
+            }
+        }
+        persistent.remove(&DataKey::BuyerPurchase(buyer.clone(), last));
+        persistent.remove(&position_key);
+        Self::set_persistent(env, &DataKey::BuyerPurchaseCount(buyer.clone()), &last);
+    }
+
+    /// Remove a buyer from a listing's buyer index, moving the listing's
+    /// last buyer into its position
+    /// 
+    /// Buyers indexed before positions were recorded are looked up by
+    /// walking the listing's index.
+    fn unindex_dataset_buyer(env: &Env, dataset_id: &Bytes, buyer: &Address) {
+        let persistent = env.storage().persistent();
+        let count = Self::dataset_sales_count(env.clone(), dataset_id.clone());
+        let position_key = ExtKey::DatasetBuyerPosition(dataset_id.clone(), buyer.clone());
+        let position = persistent.get::<_, u32>(&position_key).or_else(|| {
+            (0..count).find(|position| {
+                persistent.get::<_, Address>(&DataKey::DatasetBuyer(dataset_id.clone(), *position)).as_ref()
+                    == Some(buyer)
+            })
+        });
+        let Some(position) = position else {
+            return;
+        };
+        let last = count - 1;
+        if position != last {
+            if let Some(moved) = persistent.get::<_, Address>(&DataKey::DatasetBuyer(dataset_id.clone(), last)) {
+                Self::set_persistent(env, &DataKey::DatasetBuyer(dataset_id.clone(), position), &moved);
+                Self::set_ext_persistent(env, &ExtKey::DatasetBuyerPosition(dataset_id.clone(), moved), &position);
+            }
+        }
+        persistent.remove(&DataKey::DatasetBuyer(dataset_id.clone(), last));
+        persistent.remove(&position_key);
+        Self::set_persistent(env, &DataKey::DatasetBuyerCount(dataset_id.clone()), &last);
+    }
+
+    /// Grant a buyer access to a dataset for its access period
+    /// 
+    /// The period runs from the purchase, or from the end of the buyer's
+    /// current access if it has not expired yet. The buyer's access pass
+    /// is minted or renewed with the same expiry when an AccessPass
+    /// contract is set.
+    fn grant_access(env: &Env, dataset_id: &Bytes, buyer: &Address) {
+        let now = env.ledger().timestamp();
+        let expires_at = Self::get_access_duration(env.clone(), dataset_id.clone()).map(|duration| {
+            let start = Self::get_access_grant(env.clone(), dataset_id.clone(), buyer.clone())
+                .and_then(|grant| grant.expires_at)
+                .map_or(now, |end| end.max(now));
+            start.saturating_add(duration)
+        });
+        let grant = AccessGrant {
+            dataset_id: dataset_id.clone(),
+            buyer: buyer.clone(),
+            granted_at: now,
+            expires_at,
+        };
+        Self::set_persistent(
+            env,
+            &DataKey::AccessGrant(dataset_id.clone(), buyer.clone()),
+            &grant,
+        );
+        if let Some(access_pass) = Self::get_access_pass(env.clone()) {
+            AccessPassClient::new(env, &access_pass).mint(dataset_id, buyer, &expires_at);
+        }
+        
+        env.events().publish(
+            (symbol_short!("AccessGrt"), dataset_id.clone(), buyer.clone()),
+            expires_at,
+        );
+    }
+
+    /// Price of a listing at the current ledger, in the listing's units
+    /// 
+    /// Follows the listing's Dutch auction schedule, if any; open-access
+    /// listings stay free.
+    fn current_price(env: &Env, dataset: &Dataset) -> Result<i128, Error> {
+        if dataset.price_usdc == 0 {
+            return Ok(0);
+        }
+        let Some(decay) = Self::get_price_decay(env.clone(), dataset.dataset_id.clone()) else {
+            return Ok(dataset.price_usdc);
+        };
+        let steps = match decay.curve {
+            DecayCurve::Linear => 0,
+            DecayCurve::Stepwise(steps) => steps,
+        };
+        pricing::decayed_price(
+            decay.start_price,
+            decay.floor_price,
+            env.ledger().sequence().saturating_sub(decay.start_ledger) as u64,
+            (decay.end_ledger - decay.start_ledger) as u64,
+            steps,
+        )
+        .map_err(|_| Error::Overflow)
+    }
+
+    /// Price of a listing in its payment token
+    /// 
+    /// Starts from the listing's current price (see `current_price`).
+    /// Listings priced in USD are converted at the oracle's latest price
+    /// for the token, rounded up so the listing never sells for less than
+    /// its USD price.
+    fn listed_price(env: &Env, dataset: &Dataset, token: &Address) -> Result<i128, Error> {
+        let price = Self::current_price(env, dataset)?;
+        if price == 0
+            || Self::get_price_mode(env.clone(), dataset.dataset_id.clone()) == PriceMode::Fixed
+        {
+            return Ok(price);
+        }
+        let oracle: Address = Self::get_instance(env, &DataKey::PriceOracle)
+            .ok_or(Error::PriceOracleNotSet)?;
+        let oracle = PriceOracleClient::new(env, &oracle);
+        let reading = oracle
+            .lastprice(&OracleAsset::Stellar(token.clone()))
+            .ok_or(Error::PriceUnavailable)?;
+        if reading.timestamp.saturating_add(MAX_PRICE_AGE_SECONDS) < env.ledger().timestamp() {
+            return Err(Error::StalePrice);
+        }
+        pricing::usd_cents_to_token(
+            price,
+            reading.price,
+            oracle.decimals(),
+            token::Client::new(env, token).decimals(),
+        )
+        .map_err(|error| match error {
+            pricing::PricingError::Overflow => Error::Overflow,
+            _ => Error::PriceUnavailable,
+        })
+    }
+
+    /// Largest discount a buyer gets on a listing, in basis points
+    /// 
+    /// Considers the redeemed promo code, the timed discount and the
+    /// buyer's volume tier; 0 if none applies.
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the discount
+    /// * `Err(Error::InvalidPromoCode)` if the promo code is unknown or
+    ///   expired
+    fn discount_bps(
+        env: &Env,
+        dataset_id: &Bytes,
+        buyer: &Address,
+        promo_code: Option<Bytes>,
+    ) -> Result<u32, Error> {
+        let now = env.ledger().timestamp();
+        let mut discount_bps = 0;
+        
+        if let Some(code) = promo_code {
+            let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+            let promo: PromoCode = Self::get_persistent(env, &DataKey::PromoCode(dataset_id.clone(), code_hash))
+                .ok_or(Error::InvalidPromoCode)?;
+            if promo.expires_at.is_some_and(|end| now >= end) {
+                return Err(Error::InvalidPromoCode);
+            }
+            discount_bps = promo.discount_bps;
+        }
+        
+        if let Some(timed) = Self::get_timed_discount(env.clone(), dataset_id.clone()) {
+            if timed.starts_at <= now && now < timed.ends_at {
+                discount_bps = discount_bps.max(timed.discount_bps);
+            }
+        }
+        
+        let tiers = Self::get_volume_tiers(env.clone(), dataset_id.clone());
+        if !tiers.is_empty() {
+            let purchases = Self::purchase_count(env.clone(), buyer.clone());
+            discount_bps = discount_bps.max(Self::volume_discount_bps(&tiers, purchases)?);
+        }
+        Ok(discount_bps)
+    }
+
+    /// Discount of the highest volume tier `purchases` reaches
+    fn volume_discount_bps(tiers: &Vec<VolumeTier>, purchases: u32) -> Result<u32, Error> {
+        let mut bounded = [pricing::VolumeTier { min_quantity: 0, discount_bps: 0 };
+            MAX_VOLUME_TIERS as usize];
+        let count = tiers.len().min(MAX_VOLUME_TIERS) as usize;
+        for (slot, tier) in bounded.iter_mut().zip(tiers.iter()) {
+            *slot = pricing::VolumeTier {
+                min_quantity: tier.min_purchases,
+                discount_bps: tier.discount_bps,
+            };
+        }
+        pricing::volume_discount_bps(&bounded[..count], purchases)
+            .map_err(|_| Error::InvalidDiscount)
+    }
+
+    /// Reject discounts that are zero or would make a listing free
+    fn require_discount_bps(discount_bps: u32) -> Result<(), Error> {
+        if discount_bps == 0 || discount_bps >= pricing::BPS_DENOMINATOR {
+            return Err(Error::InvalidDiscount);
+        }
+        Ok(())
+    }
+
+    /// Reject purchases in a token that is no longer accepted
+    /// 
+    /// A listing's own payment token must still be listed in
+    /// `get_payment_tokens`, and every token must pass the TokenAllowlist.
+    fn require_payment_token(env: &Env, dataset_id: &Bytes, token: &Address) -> Result<(), Error> {
+        if Self::has_persistent(env, &DataKey::DatasetToken(dataset_id.clone()))
+            && !Self::get_payment_tokens(env.clone()).contains(token)
+        {
+            return Err(Error::TokenNotAccepted);
+        }
+        Self::require_accepted_token(env, token)
+    }
+
+    /// Reject purchases in a token the TokenAllowlist does not accept
+    /// 
+    /// Without an allowlist configured every token is accepted; a failing
+    /// lookup counts as not accepted.
+    fn require_accepted_token(env: &Env, token: &Address) -> Result<(), Error> {
+        if let Some(allowlist) = Self::get_instance::<Address>(env, &DataKey::TokenAllowlist) {
+            let accepted = matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &allowlist,
+                    &Symbol::new(env, "is_accepted"),
+                    (token.clone(),).into_val(env),
+                ),
+                Ok(Ok(true))
+            );
+            if !accepted {
+                return Err(Error::TokenNotAccepted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject state-changing calls while paused
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+        Ok(())
+    }
+
+    /// Reject purchases and payouts while the emergency pause is on
+    fn require_not_emergency_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_emergency_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
         Ok(())
     }
 
-    /// Get contributor addresses from study IDs
+    /// Append a "sale" notification to a contributor's inbox
+    /// 
+    /// Notifications are best-effort: if no inbox is configured or the call
+    /// fails, the purchase still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor whose study was sold
+    /// * `dataset_id` - ID of the dataset sold
+    /// * `amount` - Sale price in USDC
+    fn notify(
+        env: &Env,
+        contributor: &Address,
+        dataset_id: &Bytes,
+        amount: i128,
+    ) {
+        if let Some(inbox) = Self::get_instance::<Address>(env, &DataKey::Inbox) {
+            let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+                &inbox,
+                &symbol_short!("append"),
+                (
+                    env.current_contract_address(),
+                    contributor.clone(),
+                    symbol_short!("sale"),
+                    dataset_id.clone(),
+                    amount,
+                ).into_val(env),
+            );
+        }
+    }
+
+    /// Get contributor weights from study IDs
     /// 
-    /// This function queries the StudyRegistry contract to get the contributor
-    /// address for each study hash in the dataset.
+    /// This function queries the StudyRegistry contract to get the
+    /// contributors of each study hash in the dataset. Every study carries
+    /// the same weight, split among its contributors by their shares in
+    /// basis points (a single contributor holds all of it), and a
+    /// contributor's shares add up across the dataset; RevenueSplitter pays
+    /// the sale out in proportion. Revoked studies earn nothing, even where
+    /// the marketplace was not notified of the revocation.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
     /// * `study_ids` - Vector of study hashes (Bytes)
     /// 
     /// # Returns
-    /// * `Ok(Vec<Address>)` with contributor addresses
-    /// * `Err(Error)` if lookup fails
-    fn get_contributors_from_studies(
+    /// * `Ok(Vec<(Address, u32)>)` with distinct contributors and weights
+    /// * `Err(Error::StudyRegistryNotSet)` if no registry is configured
+    /// * `Err(Error::StudyLookupFailed)` if a lookup fails other than for a
+    ///   study not registered
+    fn get_contributor_weights(
         env: &Env,
         study_ids: &Vec<Bytes>,
-    ) -> Result<Vec<Address>, Error> {
-        let storage = env.storage().instance();
-        let study_registry: Address = storage.get(&STUDY_REGISTRY_KEY)
+    ) -> Result<Vec<(Address, u32)>, Error> {
+        let study_registry: Address = Self::get_instance(env, &DataKey::StudyRegistry)
             .ok_or(Error::StudyRegistryNotSet)?;
+        let study_registry = StudyRegistryClient::new(env, &study_registry);
         
-        let mut contributors = Vec::new(env);
+        let mut weights: Vec<(Address, u32)> = Vec::new(env);
+        let revoked = Self::revoked_studies(env, &study_registry, study_ids);
         
         for study_id in study_ids.iter() {
             // Convert Bytes to BytesN<32> for StudyRegistry lookup
             // Note: This assumes study_id is exactly 32 bytes (SHA256 hash)
-            if study_id.len() != 32 {
-                // Skip invalid study IDs (could also return error)
+            if study_id.len() != 32 || revoked.contains(&study_id) {
+                // Skip invalid study IDs (could also return error) and
+                // revoked studies
                 continue;
             }
             
             // Create BytesN<32> from Bytes
             let mut hash_bytes = [0u8; 32];
-            for i in 0..32 {
-                hash_bytes[i] = study_id.get(i).unwrap_or(0);
-            }
+            study_id.copy_into_slice(&mut hash_bytes);
             let study_hash = BytesN::from_array(env, &hash_bytes);
             
-            // Call StudyRegistry.get_study() to get contributor address
-            // Returns: (dataset_hash: BytesN<32>, contributor: Address, timestamp: u64)
-            let study_result: Result<(BytesN<32>, Address, u64), ()> = env.invoke_contract(
-                &study_registry,
-                &symbol_short!("get_study"),
-                soroban_sdk::vec![env, study_hash],
-            );
+            // Call StudyRegistry.get_study_contributors() to get the
+            // contributors with their shares of the study
+            let contributors = match study_registry.try_get_study_contributors(&study_hash) {
+                Ok(Ok(contributors)) => contributors,
+                // If study not found, skip it
+                // This allows datasets with some studies not yet registered
+                Err(Ok(StudyRegistryError::StudyNotFound)) => continue,
+                // Any other failure must not silently drop contributors
+                // from the payout
+                _ => return Err(Error::StudyLookupFailed),
+            };
             
-            match study_result {
-                Ok((_, contributor, _)) => {
-                    contributors.push_back(contributor);
-                },
-                Err(_) => {
-                    // If study not found, skip it
-                    // This allows datasets with some studies not yet registered
-                    continue;
+            // A contributor is paid once per purchase, weighted by their
+            // shares of the studies the dataset includes
+            for (contributor, share) in contributors.iter() {
+                match weights.iter().position(|(address, _)| address == contributor) {
+                    Some(index) => {
+                        let (address, weight) = weights.get_unchecked(index as u32);
+                        weights.set(index as u32, (address, weight + share));
+                    },
+                    None => weights.push_back((contributor, share)),
                 }
             }
         }
         
-        // Return contributors (can be empty if no studies found)
-        Ok(contributors)
+        // Return weights (can be empty if no studies found)
+        Ok(weights)
     }
 
-    /// Generate a transaction hash for purchase record
+    /// Get the studies among `study_ids` revoked in the StudyRegistry
     /// 
-    /// In production, this would use the actual transaction hash from the ledger.
-    /// For now, we generate a mock hash based on dataset_id, buyer, and timestamp.
+    /// One registry call for the whole list; IDs that are not 32-byte
+    /// hashes cannot be registry studies and are never reported.
+    fn revoked_studies(
+        env: &Env,
+        study_registry: &StudyRegistryClient,
+        study_ids: &Vec<Bytes>,
+    ) -> Vec<Bytes> {
+        let mut hashes: Vec<BytesN<32>> = Vec::new(env);
+        for study_id in study_ids.iter() {
+            if let Ok(hash) = BytesN::<32>::try_from(study_id) {
+                hashes.push_back(hash);
+            }
+        }
+        let mut study_ids = Vec::new(env);
+        for hash in study_registry.get_revoked_studies(&hashes).iter() {
+            study_ids.push_back(Bytes::from(hash));
+        }
+        study_ids
+    }
+
+    /// Reject study lists with studies revoked in the StudyRegistry
     /// 
-    /// # Arguments
-    /// * `env` - The Soroban environment
-    /// * `dataset_id` - ID of the dataset
-    /// * `buyer` - Address of the buyer
-    /// * `timestamp` - Ledger timestamp
+    /// Skipped until a registry is set, like the contributor lookups that
+    /// tolerate studies not registered yet.
+    fn require_no_revoked_studies(env: &Env, study_ids: &Vec<Bytes>) -> Result<(), Error> {
+        if let Some(study_registry) = Self::get_instance::<Address>(env, &DataKey::StudyRegistry) {
+            let study_registry = StudyRegistryClient::new(env, &study_registry);
+            if !Self::revoked_studies(env, &study_registry, study_ids).is_empty() {
+                return Err(Error::StudyRevoked);
+            }
+        }
+        Ok(())
+    }
+
+    /// Derive the ID of a new purchase and take its sequence
+    /// 
+    /// The sequence counts earlier purchases of the dataset, shared and
+    /// namespaced listings alike, and `get_purchase_count` exposes the
+    /// next one so back-office systems can precompute IDs.
     /// 
     /// # Returns
-    /// * `Bytes` representing the transaction hash
-    fn generate_tx_hash(
+    /// * `Ok((sequence, purchase_id))` with the SHA-256 of the
+    ///   `biochain_ids::PurchaseId` preimage
+    /// * `Err(Error::InvalidAddress)` if the buyer has no strkey form
+    fn derive_purchase_id(
         env: &Env,
         dataset_id: &Bytes,
         buyer: &Address,
-        timestamp: u64,
-    ) -> Bytes {
-        // Mock hash generation
-        // In production, this would use env.ledger().sequence() or actual tx hash
-        // For now, we create a simple mock hash by combining the inputs
-        let mut hash_input = Bytes::new(env);
-        hash_input.append(dataset_id);
-        
-        // Append timestamp as bytes
-        let timestamp_bytes = Bytes::from_slice(env, &timestamp.to_be_bytes());
-        hash_input.append(&timestamp_bytes);
-        
-        // Append a simple identifier for buyer (in production, use proper address serialization)
-        let buyer_id = Bytes::from_slice(env, b"buyer");
-        hash_input.append(&buyer_id);
-        
-        // Return the combined bytes as mock hash
-        // In production, this would be a proper cryptographic hash (SHA256) of the transaction
-        hash_input
+    ) -> Result<(u64, BytesN<32>), Error> {
+        let storage = env.storage().persistent();
+        let key = DataKey::PurchaseSeq(dataset_id.clone());
+        let sequence: u64 = storage.get(&key).unwrap_or(0);
+        Self::set_persistent(env, &key, &(sequence + 1));
+        
+        let preimage = PurchaseId::new(
+            env.crypto().sha256(dataset_id).to_array(),
+            Self::strkey(buyer)?,
+            sequence,
+        ).map_err(|_| Error::InvalidAddress)?.encode();
+        Ok((sequence, env.crypto().sha256(&Bytes::from_array(env, &preimage)).into()))
+    }
+
+    /// Derive the receipt hash of a purchase
+    /// 
+    /// # Returns
+    /// * `Ok(BytesN<32>)` SHA-256 of the `biochain_ids::Receipt` preimage
+    /// * `Err(Error::InvalidAddress)` if the payer has no strkey form
+    /// * `Err(Error::InvalidPrice)` if the price is negative
+    fn derive_receipt_hash(
+        env: &Env,
+        purchase_id: &BytesN<32>,
+        payer: &Address,
+        amount: i128,
+    ) -> Result<BytesN<32>, Error> {
+        let preimage = Receipt::new(
+            purchase_id.to_array(),
+            Self::strkey(payer)?,
+            amount,
+            env.ledger().timestamp(),
+        ).map_err(|err| match err {
+            IdError::InvalidAddress => Error::InvalidAddress,
+            IdError::InvalidAmount => Error::InvalidPrice,
+        })?.encode();
+        Ok(env.crypto().sha256(&Bytes::from_array(env, &preimage)).into())
+    }
+
+    /// Copy an address into its fixed-length strkey form
+    fn strkey(address: &Address) -> Result<[u8; STRKEY_LEN], Error> {
+        let strkey = address.to_string();
+        if strkey.len() as usize != STRKEY_LEN {
+            return Err(Error::InvalidAddress);
+        }
+        let mut bytes = [0u8; STRKEY_LEN];
+        strkey.copy_into_slice(&mut bytes);
+        Ok(bytes)
     }
 }
@@ -0,0 +1,311 @@
+#![cfg(test)]
+
+//! Resource budget benchmarks for the hot paths
+//!
+//! Measures CPU instructions and memory with the host budget, plus the
+//! serialized size of stored entries, across input sizes. Each size has a
+//! regression budget with headroom over the measured cost; raise a budget
+//! only together with the change that makes the path more expensive.
+
+use dataset_marketplace::{DatasetMarketplace, DatasetMarketplaceClient, MAX_STUDIES_PER_CALL};
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec,
+};
+
+/// Network limits per transaction (CPU instructions / memory bytes)
+const TX_CPU_LIMIT: u64 = 100_000_000;
+const TX_MEM_LIMIT: u64 = 41_943_040;
+
+/// Network limit for a single contract data entry (bytes)
+///
+/// A dataset entry costs ~40 bytes per study, so the largest budgeted
+/// dataset must stay well under a quarter of the limit.
+const MAX_ENTRY_SIZE: u32 = 65_536;
+
+/// Regression budgets for purchase_dataset (contributors, max cpu, max mem)
+///
+/// Purchase cost includes the registry lookups (one per study plus one
+/// revocation check for the whole dataset), the listing's price mode and schedule,
+/// discounts and access policy, the payment pull and the RevenueSplitter payout (including
+/// lifetime and annual earnings records, one payout ID hash per
+/// contributor and the running payout totals), so it grows with the
+/// number of contributors. Contributors beyond the splitter's
+/// MAX_CONTRIBUTORS_PER_BATCH are queued instead of paid in the purchase.
+/// A buyer's first purchase of a dataset also writes the buyer's purchase
+/// history and the dataset's buyer index, with each entry's position so a
+/// transferred license can leave them. Every purchase writes the
+/// buyer's access grant, payment record and the splitter's payout
+/// sequence, after checking the payment record for a pending refund, and
+/// the purchase record is a persistent entry whose TTL is extended on
+/// write.
+/// Every ledger entry in the footprint, such as the registry's verifying
+/// key and proof verifier, adds to the storage snapshot taken per
+/// contract frame, and each
+/// study record carries the key version that verified it. The test host
+/// also snapshots the temporary auth nonce entry each contributor's
+/// registration left, which on the network is outside the footprint.
+const PURCHASE_BUDGETS: [(u32, u64, u64); 5] = [
+    (1, 2_550_000, 500_000),
+    (5, 6_150_000, 1_230_000),
+    (10, 12_300_000, 2_560_000),
+    (20, 31_000_000, 6_550_000),
+    (40, 70_000_000, 14_000_000),
+];
+
+/// Regression budgets for register_dataset (studies, max cpu, max mem)
+///
+/// Registration asks the registry whether any of the studies was revoked
+/// and writes one study-to-datasets index entry per study, and its fixed
+/// cost includes the registration-order index entry and count,
+/// the persistent dataset entry and its TTL extension, the instance and
+/// pre-schema-3 key lookups for the dataset and the pause flags. Like
+/// purchases, it pays for snapshotting the registrations' auth nonces and
+/// the registry's configuration, proof verifier included.
+/// Larger datasets are listed MAX_STUDIES_PER_CALL studies at a time.
+const REGISTER_BUDGETS: [(u32, u64, u64); 3] = [
+    (1, 320_000, 52_000),
+    (40, 6_100_000, 1_650_000),
+    (MAX_STUDIES_PER_CALL, 25_000_000, 8_100_000),
+];
+
+/// StudyRegistry and ProofVerifier built for the network (`make bench`
+/// builds both first), so `bench_register_study` meters the contracts' own
+/// instructions and not only their host calls
+const STUDY_REGISTRY_WASM: &[u8] =
+    include_bytes!("../../study_registry/target/wasm32v1-none/release/study_registry.wasm");
+const PROOF_VERIFIER_WASM: &[u8] =
+    include_bytes!("../../proof_verifier/target/wasm32v1-none/release/proof_verifier.wasm");
+
+/// Measured cost of one call
+#[derive(Debug)]
+struct Cost {
+    cpu: u64,
+    mem: u64,
+}
+
+struct Bench<'a> {
+    env: Env,
+    marketplace: DatasetMarketplaceClient<'a>,
+    study_registry: study_registry::StudyRegistryClient<'a>,
+    usdc: token::Client<'a>,
+}
+
+/// Helper: Deploy the three core contracts wired together
+fn setup<'a>() -> Bench<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Setup is not measured; only the calls wrapped in `measure` are
+    env.budget().reset_unlimited();
+
+    let marketplace =
+        DatasetMarketplaceClient::new(&env, &env.register_contract(None, DatasetMarketplace));
+    marketplace.init(&Address::generate(&env));
+    let study_registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&study_registry);
+    study_registry::testutils::install_attestation_root(&study_registry);
+    let revenue_splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    revenue_splitter.init(&Address::generate(&env), &usdc, &Address::generate(&env));
+
+    marketplace.set_study_registry(&study_registry.address);
+    marketplace.set_revenue_splitter(&revenue_splitter.address);
+
+    Bench {
+        usdc: token::Client::new(&env, &usdc),
+        env,
+        marketplace,
+        study_registry,
+    }
+}
+
+/// Helper: Register `count` studies from distinct contributors, numbering
+/// study hashes from `first`
+fn register_studies(bench: &Bench, first: u32, count: u32) -> Vec<Bytes> {
+    let mut study_ids = Vec::new(&bench.env);
+    for i in first..first + count {
+        let mut hash = [0u8; 32];
+        hash[0..4].copy_from_slice(&i.to_be_bytes());
+        let study_hash = BytesN::from_array(&bench.env, &hash);
+        let contributor = Address::generate(&bench.env);
+        let attestation = study_registry::testutils::attestation(&bench.env, &study_hash, &contributor);
+        bench.study_registry.register_study(
+            &study_hash,
+            &attestation,
+            &study_registry::ProofKind::Groth16,
+            &study_registry::testutils::zk_proof(&bench.env, &study_hash, &attestation, &contributor),
+            &contributor,
+        );
+        study_ids.push_back(Bytes::from_array(&bench.env, &hash));
+    }
+    study_ids
+}
+
+/// Helper: Measure a single contract call with an unlimited budget
+fn measure(env: &Env, call: impl FnOnce()) -> Cost {
+    env.budget().reset_unlimited();
+    call();
+    Cost {
+        cpu: env.budget().cpu_instruction_cost(),
+        mem: env.budget().memory_bytes_cost(),
+    }
+}
+
+#[test]
+fn bench_purchase_dataset_by_contributor_count() {
+    for (contributors, max_cpu, max_mem) in PURCHASE_BUDGETS {
+        let bench = setup();
+        let study_ids = register_studies(&bench, 0, contributors);
+        let dataset_id = Bytes::from_slice(&bench.env, b"bench_dataset");
+        let price = 10_0000000 * contributors as i128;
+        bench
+            .marketplace
+            .register_dataset(&Address::generate(&bench.env), &dataset_id, &study_ids, &price, &None);
+
+        let buyer = Address::generate(&bench.env);
+        token::StellarAssetClient::new(&bench.env, &bench.usdc.address).mint(&buyer, &price);
+        bench.usdc.approve(
+            &buyer,
+            &bench.marketplace.address,
+            &price,
+            &(bench.env.ledger().sequence() + 100),
+        );
+
+        // Act
+        let cost = measure(&bench.env, || {
+            bench.marketplace.purchase_dataset(&dataset_id, &buyer, &None);
+        });
+
+        // Assert: the buyer got access
+        assert!(bench.marketplace.has_access(&dataset_id, &buyer));
+        assert!(
+            cost.cpu <= max_cpu,
+            "purchase cpu regressed for {contributors} contributors: {cost:?}"
+        );
+        assert!(
+            cost.mem <= max_mem,
+            "purchase mem regressed for {contributors} contributors: {cost:?}"
+        );
+        assert!(cost.cpu <= TX_CPU_LIMIT && cost.mem <= TX_MEM_LIMIT);
+    }
+}
+
+#[test]
+fn bench_register_dataset_by_study_count() {
+    for (studies, max_cpu, max_mem) in REGISTER_BUDGETS {
+        let bench = setup();
+        let study_ids = register_studies(&bench, 0, studies);
+        let dataset_id = Bytes::from_slice(&bench.env, b"bench_dataset");
+
+        // Act
+        let cost = measure(&bench.env, || {
+            bench
+                .marketplace
+                .register_dataset(&Address::generate(&bench.env), &dataset_id, &study_ids, &10_0000000, &None);
+        });
+        let entry_size = bench
+            .marketplace
+            .get_dataset(&dataset_id)
+            .to_xdr(&bench.env)
+            .len();
+
+        // Assert
+        assert_eq!(
+            bench.marketplace.get_dataset(&dataset_id).study_ids.len(),
+            studies
+        );
+        assert!(
+            cost.cpu <= max_cpu,
+            "register cpu regressed for {studies} studies: {cost:?}"
+        );
+        assert!(
+            cost.mem <= max_mem,
+            "register mem regressed for {studies} studies: {cost:?}"
+        );
+        assert!(
+            entry_size < MAX_ENTRY_SIZE / 4,
+            "dataset entry is approaching the entry size limit: {entry_size} bytes"
+        );
+    }
+}
+
+#[test]
+fn bench_register_study() {
+    // Arrange: a registry run from WASM with its proof verifier, so every
+    // instruction of the calls is metered
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract_wasm(None, STUDY_REGISTRY_WASM),
+    );
+    study_registry::testutils::ensure_admin(&registry);
+    let vk = study_registry::testutils::verifying_key(&env);
+    let key_cost = measure(&env, || {
+        registry.set_verifying_key(&study_registry::testutils::CIRCUIT, &vk);
+    });
+    registry.set_study_circuit(&study_registry::testutils::CIRCUIT);
+    study_registry::testutils::install_attestation_root(&registry);
+    let verifier = proof_verifier::ProofVerifierClient::new(
+        &env,
+        &env.register_contract_wasm(None, PROOF_VERIFIER_WASM),
+    );
+    verifier.init(&Address::generate(&env), &Address::generate(&env));
+    registry.set_proof_verifier(&verifier.address);
+
+    // Populate the registry with 20 studies; each proof is approved as the
+    // verification service does once it checks out off-chain
+    let mut registrations = std::vec::Vec::new();
+    for i in 0..=20u8 {
+        let study_hash = BytesN::from_array(&env, &[i; 32]);
+        let contributor = Address::generate(&env);
+        let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+        let zk_proof = study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor);
+        let signals = study_registry::testutils::signals(&env, &study_hash, &attestation, &contributor);
+        verifier.approve(&verifier.proof_digest(&vk, &zk_proof, &signals));
+        registrations.push((study_hash, attestation, zk_proof, contributor));
+    }
+    let ((study_hash, attestation, zk_proof, contributor), populated) =
+        registrations.split_last().unwrap();
+    for (study_hash, attestation, zk_proof, contributor) in populated {
+        registry.register_study(study_hash, attestation, &study_registry::ProofKind::Groth16, zk_proof, contributor);
+    }
+
+    // Act: cost of one more registration (the proof is built outside the
+    // measured call)
+    let cost = measure(&env, || {
+        registry.register_study(study_hash, attestation, &study_registry::ProofKind::Groth16, zk_proof, contributor);
+    });
+
+    // Assert: includes running the registry's WASM, the pre-schema-3 key
+    // lookups for the study and the registry configuration, the P-256
+    // attestation signature check, loading the current verifying key,
+    // hashing the public inputs and the proof verifier's approval lookup,
+    // plus rewriting the current chunk of the registration-order index.
+    // The pairing itself is checked off-chain: in WASM it takes about
+    // 3.8 billion instructions, 38 times the transaction limit
+    assert!(
+        cost.cpu <= 18_800_000,
+        "register_study cpu regressed: {cost:?}"
+    );
+    assert!(
+        cost.mem <= 5_350_000,
+        "register_study mem regressed: {cost:?}"
+    );
+    assert!(cost.cpu <= TX_CPU_LIMIT && cost.mem <= TX_MEM_LIMIT);
+    // Installing a key checks that its points are on their curves, which
+    // must fit in a transaction too
+    assert!(
+        key_cost.cpu <= TX_CPU_LIMIT && key_cost.mem <= TX_MEM_LIMIT,
+        "set_verifying_key does not fit in a transaction: {key_cost:?}"
+    );
+}
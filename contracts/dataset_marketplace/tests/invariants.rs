@@ -0,0 +1,374 @@
+#![cfg(test)]
+
+//! Property-based invariants across the marketplace, registry and splitter
+//!
+//! Random operation sequences are replayed against the three core contracts
+//! wired together, and a small model tracks what should have happened. After
+//! every operation:
+//!
+//! - Funds are conserved: everything buyers paid equals what contributors
+//!   and the treasury received, and neither the marketplace nor the splitter
+//!   keeps a balance between calls
+//! - Every successful purchase has a record pointing at an existing dataset,
+//!   and failed purchases leave no record behind
+//! - Each contributor is paid once per purchase, in proportion to how many
+//!   of their studies the dataset includes
+//! - No funds move without a matching successful purchase
+
+use dataset_marketplace::{DatasetMarketplace, DatasetMarketplaceClient};
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, token, Address, Bytes, BytesN, Env, Vec,
+};
+use std::collections::BTreeMap;
+
+/// One USDC (7 decimals)
+const USDC: i128 = 10_000_000;
+
+/// Contributor part of each contributor's share of the price
+const CONTRIBUTOR_PERCENT: i128 = 85;
+
+const CONTRIBUTORS: usize = 4;
+const BUYERS: usize = 3;
+
+#[derive(Clone, Debug)]
+enum Op {
+    /// Register a study owned by contributor `contributor`
+    RegisterStudy { contributor: usize },
+    /// Register a dataset from the studies at the given indexes
+    RegisterDataset {
+        studies: std::vec::Vec<usize>,
+        price: i128,
+    },
+    /// Purchase the dataset at `dataset` as buyer `buyer`
+    Purchase { dataset: usize, buyer: usize },
+    /// Purchase a dataset that was never registered
+    PurchaseUnknown { buyer: usize },
+    /// Mint USDC to buyer `buyer`
+    Fund { buyer: usize, amount: i128 },
+    /// Buyer `buyer` sets the marketplace allowance to `amount`
+    Approve { buyer: usize, amount: i128 },
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..CONTRIBUTORS).prop_map(|contributor| Op::RegisterStudy { contributor }),
+        (prop::collection::vec(0usize..16, 1..5), 1i128..100 * USDC)
+            .prop_map(|(studies, price)| Op::RegisterDataset { studies, price }),
+        (0usize..8, 0..BUYERS).prop_map(|(dataset, buyer)| Op::Purchase { dataset, buyer }),
+        (0..BUYERS).prop_map(|buyer| Op::PurchaseUnknown { buyer }),
+        (0..BUYERS, 0i128..200 * USDC).prop_map(|(buyer, amount)| Op::Fund { buyer, amount }),
+        (0..BUYERS, 0i128..200 * USDC).prop_map(|(buyer, amount)| Op::Approve { buyer, amount }),
+    ]
+}
+
+struct World<'a> {
+    env: Env,
+    marketplace: DatasetMarketplaceClient<'a>,
+    study_registry: study_registry::StudyRegistryClient<'a>,
+    splitter: Address,
+    usdc: token::Client<'a>,
+    treasury: Address,
+    contributors: std::vec::Vec<Address>,
+    buyers: std::vec::Vec<Address>,
+}
+
+/// Model of the state the contracts should be in
+#[derive(Default)]
+struct Model {
+    /// (study id, contributor index) per registered study
+    studies: std::vec::Vec<(Bytes, usize)>,
+    /// (dataset id, weight per distinct contributor index) per registered dataset
+    datasets: std::vec::Vec<(Bytes, BTreeMap<usize, i128>)>,
+    /// (dataset id, buyer index) per successful purchase
+    purchases: std::vec::Vec<(Bytes, usize)>,
+    /// Total USDC minted to buyers
+    minted: i128,
+    /// Expected balance per buyer
+    balances: [i128; BUYERS],
+    /// Expected marketplace allowance per buyer
+    allowances: [i128; BUYERS],
+    /// Expected balance per contributor
+    earned: [i128; CONTRIBUTORS],
+    /// Expected treasury balance
+    treasury: i128,
+}
+
+/// Helper: Deploy the three core contracts wired together
+fn setup<'a>() -> World<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let marketplace =
+        DatasetMarketplaceClient::new(&env, &env.register_contract(None, DatasetMarketplace));
+    marketplace.init(&Address::generate(&env));
+    let study_registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&study_registry);
+    study_registry::testutils::install_attestation_root(&study_registry);
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+    splitter.init(&Address::generate(&env), &usdc, &treasury);
+
+    marketplace.set_study_registry(&study_registry.address);
+    marketplace.set_revenue_splitter(&splitter.address);
+
+    let contributors = (0..CONTRIBUTORS).map(|_| Address::generate(&env)).collect();
+    let buyers = (0..BUYERS).map(|_| Address::generate(&env)).collect();
+
+    World {
+        marketplace,
+        study_registry,
+        splitter: splitter.address,
+        usdc: token::Client::new(&env, &usdc),
+        treasury,
+        contributors,
+        buyers,
+        env,
+    }
+}
+
+/// Helper: Apply one operation to both the contracts and the model
+fn apply(world: &World, model: &mut Model, op: &Op) {
+    let env = &world.env;
+    match op {
+        Op::RegisterStudy { contributor } => {
+            let mut hash = [0u8; 32];
+            hash[0..4].copy_from_slice(&(model.studies.len() as u32).to_be_bytes());
+            let study_hash = BytesN::from_array(env, &hash);
+            let account = &world.contributors[*contributor];
+            let attestation = study_registry::testutils::attestation(env, &study_hash, account);
+            world.study_registry.register_study(
+                &study_hash,
+                &attestation,
+                &study_registry::ProofKind::Groth16,
+                &study_registry::testutils::zk_proof(env, &study_hash, &attestation, account),
+                account,
+            );
+            model
+                .studies
+                .push((Bytes::from_array(env, &hash), *contributor));
+        }
+        Op::RegisterDataset { studies, price } => {
+            // Indexes past the registered studies pick unknown study IDs,
+            // which have no contributor to pay
+            let mut study_ids = Vec::new(env);
+            let mut owners = BTreeMap::new();
+            for &i in studies {
+                match model.studies.get(i) {
+                    Some((id, owner)) => {
+                        study_ids.push_back(id.clone());
+                        *owners.entry(*owner).or_insert(0) += 1;
+                    }
+                    None => {
+                        let mut hash = [0xffu8; 32];
+                        hash[0] = i as u8;
+                        study_ids.push_back(Bytes::from_array(env, &hash));
+                    }
+                }
+            }
+            let mut id = *b"dataset_00";
+            id[8..].copy_from_slice(format!("{:02}", model.datasets.len()).as_bytes());
+            let dataset_id = Bytes::from_slice(env, &id);
+            world
+                .marketplace
+                .register_dataset(&Address::generate(env), &dataset_id, &study_ids, price, &None);
+            model.datasets.push((dataset_id, owners));
+        }
+        Op::Purchase { dataset, buyer } => {
+            let Some((dataset_id, owners)) = model.datasets.get(*dataset) else {
+                return;
+            };
+            let price = world.marketplace.get_dataset(dataset_id).price_usdc;
+            let result = world
+                .marketplace
+                .try_purchase_dataset(dataset_id, &world.buyers[*buyer], &None);
+
+            // Each distinct contributor gets a share of the price weighted by
+            // their studies; the rounding remainder (or everything, without
+            // contributors) goes to the treasury
+            let total_weight: i128 = owners.values().sum();
+            let shares: std::vec::Vec<(usize, i128, i128)> = owners
+                .iter()
+                .map(|(&owner, &weight)| {
+                    let share = price * weight / total_weight;
+                    (owner, share, share * CONTRIBUTOR_PERCENT / 100)
+                })
+                .collect();
+            let payable = model.balances[*buyer] >= price && model.allowances[*buyer] >= price;
+            let splittable = shares
+                .iter()
+                .all(|&(_, share, user_amount)| user_amount > 0 && share - user_amount > 0);
+            if !payable || !splittable {
+                // The whole purchase reverts
+                assert!(result.is_err(), "unpayable purchase must fail");
+                return;
+            }
+            assert!(result.is_ok(), "payable purchase must succeed: {result:?}");
+
+            model.balances[*buyer] -= price;
+            model.allowances[*buyer] -= price;
+            for &(owner, _, user_amount) in &shares {
+                model.earned[owner] += user_amount;
+                model.treasury -= user_amount;
+            }
+            model.treasury += price;
+            model.purchases.push((dataset_id.clone(), *buyer));
+        }
+        Op::PurchaseUnknown { buyer } => {
+            let dataset_id = Bytes::from_slice(env, b"never_registered");
+            let result = world
+                .marketplace
+                .try_purchase_dataset(&dataset_id, &world.buyers[*buyer], &None);
+            assert!(result.is_err(), "unknown dataset purchase must fail");
+        }
+        Op::Fund { buyer, amount } => {
+            token::StellarAssetClient::new(env, &world.usdc.address)
+                .mint(&world.buyers[*buyer], amount);
+            model.minted += amount;
+            model.balances[*buyer] += amount;
+        }
+        Op::Approve { buyer, amount } => {
+            world.usdc.approve(
+                &world.buyers[*buyer],
+                &world.marketplace.address,
+                amount,
+                &(env.ledger().sequence() + 100),
+            );
+            model.allowances[*buyer] = *amount;
+        }
+    }
+}
+
+/// Helper: Check every invariant against the current state
+fn check_invariants(world: &World, model: &Model) {
+    let paid: i128 = world
+        .contributors
+        .iter()
+        .map(|contributor| world.usdc.balance(contributor))
+        .sum();
+    let treasury = world.usdc.balance(&world.treasury);
+    let unspent: i128 = world
+        .buyers
+        .iter()
+        .map(|buyer| world.usdc.balance(buyer))
+        .sum();
+
+    // Conservation of funds, with nothing held in between
+    assert_eq!(world.usdc.balance(&world.marketplace.address), 0);
+    assert_eq!(world.usdc.balance(&world.splitter), 0);
+    assert_eq!(model.minted, unspent + paid + treasury);
+
+    // Buyers pay exactly the price, and only for successful purchases
+    for (i, buyer) in world.buyers.iter().enumerate() {
+        assert_eq!(world.usdc.balance(buyer), model.balances[i]);
+        assert_eq!(
+            world.usdc.allowance(buyer, &world.marketplace.address),
+            model.allowances[i]
+        );
+    }
+
+    // Each contributor paid exactly once per purchase, and only for
+    // successful purchases
+    for (i, contributor) in world.contributors.iter().enumerate() {
+        assert_eq!(world.usdc.balance(contributor), model.earned[i]);
+    }
+    assert_eq!(treasury, model.treasury);
+
+    // Purchase records are never orphaned
+    for (dataset_id, buyer) in &model.purchases {
+        let record = world
+            .marketplace
+            .get_purchase(dataset_id, &world.buyers[*buyer]);
+        assert_eq!(&record.dataset_id, dataset_id);
+        assert!(world.marketplace.dataset_exists(&record.dataset_id));
+    }
+
+    // Buyers never hold a record for a purchase that did not succeed
+    for (dataset_id, _) in &model.datasets {
+        for (i, buyer) in world.buyers.iter().enumerate() {
+            let recorded = world
+                .marketplace
+                .try_get_purchase(dataset_id, buyer)
+                .is_ok();
+            let expected = model
+                .purchases
+                .iter()
+                .any(|(id, b)| id == dataset_id && *b == i);
+            assert_eq!(recorded, expected);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn invariants_hold_under_random_operations(
+        ops in prop::collection::vec(op_strategy(), 1..12)
+    ) {
+        let world = setup();
+        let mut model = Model::default();
+        for op in &ops {
+            apply(&world, &mut model, op);
+            check_invariants(&world, &model);
+        }
+    }
+}
+
+#[test]
+fn purchase_pays_repeated_contributor_once_by_weight() {
+    // Arrange: one contributor owns two of the dataset's three studies
+    let world = setup();
+    let mut model = Model::default();
+    let ops = [
+        Op::RegisterStudy { contributor: 0 },
+        Op::RegisterStudy { contributor: 0 },
+        Op::RegisterStudy { contributor: 1 },
+        Op::RegisterDataset {
+            studies: std::vec![0, 1, 2],
+            price: 30 * USDC,
+        },
+        Op::Fund {
+            buyer: 0,
+            amount: 30 * USDC,
+        },
+        Op::Approve {
+            buyer: 0,
+            amount: 30 * USDC,
+        },
+    ];
+    for op in &ops {
+        apply(&world, &mut model, op);
+    }
+
+    // Act
+    apply(
+        &world,
+        &mut model,
+        &Op::Purchase {
+            dataset: 0,
+            buyer: 0,
+        },
+    );
+
+    // Assert: the two distinct contributors split the 30 USDC price 2:1
+    let user_amount = |share: i128| share * CONTRIBUTOR_PERCENT / 100;
+    assert_eq!(world.usdc.balance(&world.contributors[0]), user_amount(20 * USDC));
+    assert_eq!(world.usdc.balance(&world.contributors[1]), user_amount(10 * USDC));
+    assert_eq!(
+        world.usdc.balance(&world.treasury),
+        30 * USDC - user_amount(30 * USDC)
+    );
+    check_invariants(&world, &model);
+}
@@ -0,0 +1,287 @@
+#![no_std]
+use biochain_interfaces::DatasetMarketplaceClient;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+};
+
+/// TTL policy for deposits, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Longest lock period the admin can set (about 30 days of 5-second
+/// ledgers), so deposits always expire before their TTL
+pub const MAX_LOCK_LEDGERS: u32 = 518_400;
+
+/// Storage keys
+///
+/// - Deposit: Locked purchase payments in persistent storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Marketplace,
+    LockLedgers,
+    Deposit(BytesN<32>),
+}
+
+/// Payment of a marketplace purchase held until delivery is confirmed
+///
+/// - purchase_id: Marketplace purchase the payment is for
+/// - buyer: Researcher who can confirm delivery
+/// - release_ledger: Ledger sequence the payment can be released at
+///   without the buyer's confirmation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deposit {
+    pub purchase_id: BytesN<32>,
+    pub buyer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub release_ledger: u32,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidLockPeriod = 4,
+    DepositExists = 5,
+    DepositNotFound = 6,
+    StillLocked = 7,
+}
+
+/// Escrow of expensive marketplace purchases
+///
+/// The DatasetMarketplace moves the payment of a purchase priced at or
+/// above its escrow threshold here instead of paying contributors at once.
+/// The payment stays locked for a number of ledgers; the buyer can confirm
+/// delivery to release it early, and anyone can release it once the lock
+/// period is over. Released funds go back to the marketplace, which pays
+/// them out through the RevenueSplitter to the contributors recorded at
+/// purchase time. A refund the marketplace approves while the payment is
+/// locked returns it to the payer instead.
+#[contract]
+pub struct PurchaseEscrow;
+
+#[contractimpl]
+impl PurchaseEscrow {
+    /// Initialize the PurchaseEscrow contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to change the lock period
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// * `lock_ledgers` - Ledgers a payment stays locked without the
+    ///   buyer's confirmation
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    /// * `Err(Error::InvalidLockPeriod)` if the lock period is zero or
+    ///   above `MAX_LOCK_LEDGERS`
+    pub fn init(
+        env: Env,
+        admin: Address,
+        marketplace: Address,
+        lock_ledgers: u32,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        Self::require_lock_ledgers(lock_ledgers)?;
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::Marketplace, &marketplace);
+        storage.set(&DataKey::LockLedgers, &lock_ledgers);
+        Ok(())
+    }
+
+    /// Set the lock period of new deposits
+    ///
+    /// Deposits already locked keep their release ledger. Requires the
+    /// admin's authorization.
+    pub fn set_lock_ledgers(env: Env, lock_ledgers: u32) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(&env, &DataKey::Admin)?;
+        admin.require_auth();
+        Self::require_lock_ledgers(lock_ledgers)?;
+        env.storage().instance().set(&DataKey::LockLedgers, &lock_ledgers);
+        Ok(())
+    }
+
+    /// Lock the payment of a purchase
+    ///
+    /// Called by the marketplace after transferring the payment here.
+    /// Requires the marketplace's authorization.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `purchase_id` - ID of the marketplace purchase
+    /// * `buyer` - Researcher who made the purchase
+    /// * `token` - Token the payment was made in
+    /// * `amount` - Amount transferred here
+    ///
+    /// # Returns
+    /// * `Ok(u32)` with the ledger sequence the payment is released at
+    /// * `Err(Error::DepositExists)` if the purchase is already locked
+    /// * `Err(Error::InvalidAmount)` if the amount is not positive
+    pub fn lock(
+        env: Env,
+        purchase_id: BytesN<32>,
+        buyer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<u32, Error> {
+        let marketplace: Address = Self::get_instance(&env, &DataKey::Marketplace)?;
+        marketplace.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let key = DataKey::Deposit(purchase_id.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::DepositExists);
+        }
+
+        let lock_ledgers: u32 = Self::get_instance(&env, &DataKey::LockLedgers)?;
+        let release_ledger = env.ledger().sequence() + lock_ledgers;
+        let deposit = Deposit {
+            purchase_id: purchase_id.clone(),
+            buyer,
+            token,
+            amount,
+            release_ledger,
+        };
+        let storage = env.storage().persistent();
+        storage.set(&key, &deposit);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        env.events().publish(
+            (symbol_short!("Locked"), purchase_id),
+            (deposit.buyer, amount, release_ledger),
+        );
+        Ok(release_ledger)
+    }
+
+    /// Confirm delivery of a purchase and release its payment early
+    ///
+    /// Requires the buyer's authorization.
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the amount released
+    /// * `Err(Error::DepositNotFound)` if the purchase is not locked
+    pub fn confirm(env: Env, purchase_id: BytesN<32>) -> Result<i128, Error> {
+        let deposit = Self::load_deposit(&env, &purchase_id)?;
+        deposit.buyer.require_auth();
+        Self::release_deposit(&env, deposit)
+    }
+
+    /// Release the payment of a purchase whose lock period is over
+    ///
+    /// Anyone can call this, so contributors are paid even if the buyer
+    /// never confirms.
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the amount released
+    /// * `Err(Error::DepositNotFound)` if the purchase is not locked
+    /// * `Err(Error::StillLocked)` before the release ledger
+    pub fn release(env: Env, purchase_id: BytesN<32>) -> Result<i128, Error> {
+        let deposit = Self::load_deposit(&env, &purchase_id)?;
+        if env.ledger().sequence() < deposit.release_ledger {
+            return Err(Error::StillLocked);
+        }
+        Self::release_deposit(&env, deposit)
+    }
+
+    /// Return the payment of a refunded purchase to its payer
+    ///
+    /// Called by the marketplace when it approves a refund of a purchase
+    /// that is still locked, so the payment is never released to the
+    /// contributors. Requires the marketplace's authorization.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `purchase_id` - ID of the marketplace purchase
+    /// * `to` - Address the purchase was paid from
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the amount refunded
+    /// * `Err(Error::DepositNotFound)` if the purchase is not locked
+    pub fn refund(env: Env, purchase_id: BytesN<32>, to: Address) -> Result<i128, Error> {
+        let marketplace: Address = Self::get_instance(&env, &DataKey::Marketplace)?;
+        marketplace.require_auth();
+        let deposit = Self::load_deposit(&env, &purchase_id)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Deposit(purchase_id.clone()));
+
+        token::Client::new(&env, &deposit.token).transfer(
+            &env.current_contract_address(),
+            &to,
+            &deposit.amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("Refunded"), purchase_id),
+            (to, deposit.amount),
+        );
+        Ok(deposit.amount)
+    }
+
+    /// Get the locked payment of a purchase, if any
+    pub fn get_deposit(env: Env, purchase_id: BytesN<32>) -> Option<Deposit> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Deposit(purchase_id))
+    }
+
+    /// Get the lock period of new deposits, in ledgers
+    pub fn get_lock_ledgers(env: Env) -> Result<u32, Error> {
+        Self::get_instance(&env, &DataKey::LockLedgers)
+    }
+
+    /// Return a deposit to the marketplace and have it paid out
+    fn release_deposit(env: &Env, deposit: Deposit) -> Result<i128, Error> {
+        let marketplace: Address = Self::get_instance(env, &DataKey::Marketplace)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Deposit(deposit.purchase_id.clone()));
+
+        token::Client::new(env, &deposit.token).transfer(
+            &env.current_contract_address(),
+            &marketplace,
+            &deposit.amount,
+        );
+        DatasetMarketplaceClient::new(env, &marketplace).settle_escrow(&deposit.purchase_id);
+
+        env.events().publish(
+            (symbol_short!("Released"), deposit.purchase_id),
+            (deposit.buyer, deposit.amount),
+        );
+        Ok(deposit.amount)
+    }
+
+    fn load_deposit(env: &Env, purchase_id: &BytesN<32>) -> Result<Deposit, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Deposit(purchase_id.clone()))
+            .ok_or(Error::DepositNotFound)
+    }
+
+    fn require_lock_ledgers(lock_ledgers: u32) -> Result<(), Error> {
+        if lock_ledgers == 0 || lock_ledgers > MAX_LOCK_LEDGERS {
+            return Err(Error::InvalidLockPeriod);
+        }
+        Ok(())
+    }
+
+    fn get_instance<V: soroban_sdk::TryFromVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        key: &DataKey,
+    ) -> Result<V, Error> {
+        env.storage().instance().get(key).ok_or(Error::NotInitialized)
+    }
+}
@@ -0,0 +1,223 @@
+#![cfg(test)]
+
+use escrow::{Error, PurchaseEscrow, PurchaseEscrowClient};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Price of the expensive dataset (500 USDC)
+const PRICE: i128 = 500_0000000;
+
+/// Ledgers a payment stays locked
+const LOCK_LEDGERS: u32 = 1_000;
+
+struct Setup<'a> {
+    env: Env,
+    escrow: PurchaseEscrowClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    contributor: Address,
+}
+
+/// Helper: Deploy the core contracts with an escrow for purchases of 100
+/// USDC or more, and list a dataset of one study
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let escrow = PurchaseEscrowClient::new(&env, &env.register_contract(None, PurchaseEscrow));
+    escrow.init(&Address::generate(&env), &marketplace.address, &LOCK_LEDGERS);
+    marketplace.set_escrow(&Some(dataset_marketplace::EscrowConfig {
+        escrow: escrow.address.clone(),
+        min_price: 100_0000000,
+    }));
+
+    let contributor = Address::generate(&env);
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &Bytes::from_slice(&env, b"genomes"),
+        &vec![&env, Bytes::from(study_hash)],
+        &PRICE,
+        &None,
+    );
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        escrow,
+        marketplace,
+        contributor,
+    }
+}
+
+/// Helper: Purchase a dataset as a new funded buyer, returning the buyer
+/// and the purchase ID
+fn purchase(s: &Setup, dataset_id: &Bytes, price: i128) -> (Address, BytesN<32>) {
+    let buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.usdc.address).mint(&buyer, &price);
+    s.usdc.approve(
+        &buyer,
+        &s.marketplace.address,
+        &price,
+        &(s.env.ledger().sequence() + 100),
+    );
+    s.marketplace.purchase_dataset(dataset_id, &buyer, &None);
+    let purchase_id = s.marketplace.get_purchase(dataset_id, &buyer).tx_hash;
+    (buyer, BytesN::try_from(purchase_id).unwrap())
+}
+
+#[test]
+fn test_buyer_confirmation_releases_payment_to_contributors() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"genomes");
+
+    // Act
+    let (buyer, purchase_id) = purchase(&s, &dataset_id, PRICE);
+
+    // Assert: the payment is locked and nobody is paid yet
+    let deposit = s.escrow.get_deposit(&purchase_id).unwrap();
+    assert_eq!((deposit.buyer, deposit.amount), (buyer, PRICE));
+    assert_eq!(deposit.release_ledger, 100 + LOCK_LEDGERS);
+    assert_eq!(s.usdc.balance(&s.escrow.address), PRICE);
+    assert_eq!(s.usdc.balance(&s.contributor), 0);
+    assert!(s.marketplace.get_escrowed_payout(&purchase_id).is_some());
+
+    // Act: the buyer confirms delivery before the lock period ends
+    assert_eq!(s.escrow.confirm(&purchase_id), PRICE);
+
+    // Assert: the contributor is paid their 85% and nothing stays behind
+    assert_eq!(s.usdc.balance(&s.contributor), 425_0000000);
+    assert_eq!(s.usdc.balance(&s.escrow.address), 0);
+    assert_eq!(s.usdc.balance(&s.marketplace.address), 0);
+    assert_eq!(s.escrow.get_deposit(&purchase_id), None);
+    assert_eq!(s.marketplace.get_escrowed_payout(&purchase_id), None);
+    assert_eq!(s.escrow.try_confirm(&purchase_id), Err(Ok(Error::DepositNotFound)));
+}
+
+#[test]
+fn test_payment_is_released_to_anyone_after_the_lock_period() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"genomes");
+    let (_, purchase_id) = purchase(&s, &dataset_id, PRICE);
+
+    // Act & Assert: the payment stays locked until the release ledger
+    s.env.ledger().set_sequence_number(100 + LOCK_LEDGERS - 1);
+    assert_eq!(s.escrow.try_release(&purchase_id), Err(Ok(Error::StillLocked)));
+    s.env.ledger().set_sequence_number(100 + LOCK_LEDGERS);
+    assert_eq!(s.escrow.release(&purchase_id), PRICE);
+    assert_eq!(s.usdc.balance(&s.contributor), 425_0000000);
+    assert_eq!(s.escrow.try_release(&purchase_id), Err(Ok(Error::DepositNotFound)));
+}
+
+#[test]
+fn test_refund_of_an_escrowed_purchase_is_never_paid_out() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"genomes");
+    let refund_source = Address::generate(&s.env);
+    s.marketplace.set_refund_policy(&dataset_marketplace::RefundPolicy {
+        window: 86_400,
+        arbiter: Address::generate(&s.env),
+        refund_source: refund_source.clone(),
+    });
+    let (buyer, purchase_id) = purchase(&s, &dataset_id, PRICE);
+
+    // Act
+    s.marketplace.request_refund(&dataset_id, &buyer);
+    assert_eq!(s.marketplace.resolve_refund(&dataset_id, &buyer, &true), PRICE);
+
+    // Assert: the buyer gets the deposit back, and nothing else moved
+    assert_eq!(s.usdc.balance(&buyer), PRICE);
+    assert_eq!(s.usdc.balance(&s.escrow.address), 0);
+    assert_eq!(s.escrow.get_deposit(&purchase_id), None);
+    assert_eq!(s.marketplace.get_escrowed_payout(&purchase_id), None);
+
+    // Settling the purchase afterwards pays nobody
+    assert_eq!(
+        s.marketplace.try_settle_escrow(&purchase_id),
+        Err(Ok(dataset_marketplace::Error::EscrowedPayoutNotFound))
+    );
+    s.env.ledger().set_sequence_number(100 + LOCK_LEDGERS);
+    assert_eq!(s.escrow.try_release(&purchase_id), Err(Ok(Error::DepositNotFound)));
+    assert_eq!(s.usdc.balance(&s.contributor), 0);
+    assert_eq!(s.usdc.balance(&s.marketplace.address), 0);
+    assert_eq!(
+        s.escrow.try_refund(&purchase_id, &buyer),
+        Err(Ok(Error::DepositNotFound))
+    );
+}
+
+#[test]
+fn test_cheaper_purchases_are_paid_out_at_once() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"genomes");
+    s.marketplace.update_price(&dataset_id, &50_0000000);
+
+    // Act
+    let (_, purchase_id) = purchase(&s, &dataset_id, 50_0000000);
+
+    // Assert: below the threshold nothing goes through the escrow
+    assert_eq!(s.usdc.balance(&s.contributor), 42_5000000);
+    assert_eq!(s.escrow.get_deposit(&purchase_id), None);
+    assert_eq!(s.usdc.balance(&s.escrow.address), 0);
+}
+
+#[test]
+fn test_lock_period_is_validated() {
+    let s = setup();
+
+    // Act & Assert
+    assert_eq!(s.escrow.get_lock_ledgers(), LOCK_LEDGERS);
+    assert_eq!(s.escrow.try_set_lock_ledgers(&0), Err(Ok(Error::InvalidLockPeriod)));
+    assert_eq!(
+        s.escrow.try_set_lock_ledgers(&(escrow::MAX_LOCK_LEDGERS + 1)),
+        Err(Ok(Error::InvalidLockPeriod))
+    );
+    s.escrow.set_lock_ledgers(&10);
+    assert_eq!(s.escrow.get_lock_ledgers(), 10);
+    assert_eq!(
+        s.escrow.try_init(&Address::generate(&s.env), &s.marketplace.address, &10),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(
+        s.escrow.try_lock(
+            &BytesN::from_array(&s.env, &[9u8; 32]),
+            &Address::generate(&s.env),
+            &s.usdc.address,
+            &0
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
@@ -0,0 +1,441 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes, Env,
+    IntoVal, Symbol, Vec,
+};
+pub use biochain_types::{Dataset, DatasetStatus};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const GRANT_COUNT_KEY: Symbol = symbol_short!("GRANT_CNT");
+const GRANT_KEY: Symbol = symbol_short!("GRANT");
+const ELIGIBLE_KEY: Symbol = symbol_short!("ELIGIBLE");
+const DATASET_TAG_KEY: Symbol = symbol_short!("DS_TAGS");
+const SPEND_KEY: Symbol = symbol_short!("SPEND");
+
+/// Grant structure
+///
+/// A sponsor's budget earmarked for one research area:
+/// - grant_id: Sequential identifier
+/// - sponsor: Address that funded the grant and manages eligibility
+/// - tag: Research area / condition code the budget is earmarked for
+/// - deposited: Total USDC deposited by the sponsor
+/// - spent: Total USDC drawn for purchases
+/// - withdrawn: USDC returned to the sponsor on close
+/// - spend_count: Number of spend records
+/// - open: Whether researchers can still draw from the grant
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grant {
+    pub grant_id: u64,
+    pub sponsor: Address,
+    pub tag: Symbol,
+    pub deposited: i128,
+    pub spent: i128,
+    pub withdrawn: i128,
+    pub spend_count: u32,
+    pub open: bool,
+}
+
+/// SpendRecord structure
+///
+/// One draw against a grant to pay for a marketplace purchase
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendRecord {
+    pub grant_id: u64,
+    pub researcher: Address,
+    pub dataset_id: Bytes,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    GrantNotFound = 3,
+    GrantClosed = 4,
+    InvalidAmount = 5,
+    ResearcherNotEligible = 6,
+    EarmarkMismatch = 7,
+    InsufficientBudget = 8,
+    SpendNotFound = 9,
+}
+
+#[contract]
+pub struct GrantPool;
+
+#[contractimpl]
+impl GrantPool {
+    /// Initialize the GrantPool contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to tag datasets with research areas
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        marketplace: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&GRANT_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Tag a marketplace dataset with the research areas it covers
+    ///
+    /// Grants can only pay for datasets tagged with their earmark.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - Marketplace dataset ID
+    /// * `tags` - Research area / condition code tags
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    pub fn set_dataset_tags(env: Env, dataset_id: Bytes, tags: Vec<Symbol>) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage()
+            .persistent()
+            .set(&(DATASET_TAG_KEY, dataset_id), &tags);
+        Ok(())
+    }
+
+    /// Get the research area tags of a dataset
+    pub fn get_dataset_tags(env: Env, dataset_id: Bytes) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&(DATASET_TAG_KEY, dataset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Create a grant and deposit its initial budget
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `sponsor` - Funder (must authorize the USDC transfer)
+    /// * `tag` - Research area / condition code the budget is earmarked for
+    /// * `amount` - Initial USDC deposit
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the new grant ID
+    /// * `Err(Error)` if validation fails
+    pub fn create_grant(
+        env: Env,
+        sponsor: Address,
+        tag: Symbol,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        sponsor.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let storage = env.storage().instance();
+        let grant_id: u64 = storage.get(&GRANT_COUNT_KEY).ok_or(Error::NotInitialized)?;
+
+        Self::token_client(&env)?.transfer(&sponsor, &env.current_contract_address(), &amount);
+
+        let grant = Grant {
+            grant_id,
+            sponsor: sponsor.clone(),
+            tag: tag.clone(),
+            deposited: amount,
+            spent: 0,
+            withdrawn: 0,
+            spend_count: 0,
+            open: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&(GRANT_KEY, grant_id), &grant);
+        storage.set(&GRANT_COUNT_KEY, &(grant_id + 1));
+
+        env.events().publish(
+            (symbol_short!("GrantNew"), grant_id),
+            (sponsor, tag, amount),
+        );
+
+        Ok(grant_id)
+    }
+
+    /// Add funds to an open grant
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `grant_id` - Grant to top up
+    /// * `amount` - USDC to deposit (transferred from the sponsor)
+    ///
+    /// # Returns
+    /// * `Ok(Grant)` with the updated grant
+    /// * `Err(Error)` if validation fails
+    pub fn deposit(env: Env, grant_id: u64, amount: i128) -> Result<Grant, Error> {
+        let mut grant = Self::load_grant(&env, grant_id)?;
+        grant.sponsor.require_auth();
+        if !grant.open {
+            return Err(Error::GrantClosed);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::token_client(&env)?.transfer(
+            &grant.sponsor,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        grant.deposited += amount;
+        env.storage()
+            .persistent()
+            .set(&(GRANT_KEY, grant_id), &grant);
+
+        env.events()
+            .publish((symbol_short!("GrantDep"), grant_id), amount);
+
+        Ok(grant)
+    }
+
+    /// Mark a researcher as eligible to draw from a grant
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `grant_id` - Grant ID
+    /// * `researcher` - Researcher address
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::GrantNotFound)` if the grant does not exist
+    pub fn add_researcher(env: Env, grant_id: u64, researcher: Address) -> Result<(), Error> {
+        let grant = Self::load_grant(&env, grant_id)?;
+        grant.sponsor.require_auth();
+        env.storage()
+            .persistent()
+            .set(&(ELIGIBLE_KEY, grant_id, researcher), &true);
+        Ok(())
+    }
+
+    /// Remove a researcher's eligibility for a grant
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `grant_id` - Grant ID
+    /// * `researcher` - Researcher address
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::GrantNotFound)` if the grant does not exist
+    pub fn remove_researcher(env: Env, grant_id: u64, researcher: Address) -> Result<(), Error> {
+        let grant = Self::load_grant(&env, grant_id)?;
+        grant.sponsor.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&(ELIGIBLE_KEY, grant_id, researcher));
+        Ok(())
+    }
+
+    /// Check whether a researcher may draw from a grant
+    pub fn is_eligible(env: Env, grant_id: u64, researcher: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(ELIGIBLE_KEY, grant_id, researcher))
+    }
+
+    /// Pay for a marketplace purchase out of a grant
+    ///
+    /// Flow:
+    /// 1. Verify researcher eligibility and that the dataset matches the earmark
+    /// 2. Look up the dataset price in DatasetMarketplace
+    /// 3. Send the price to the researcher, from whom the marketplace pulls it
+    /// 4. Record the purchase for the researcher in DatasetMarketplace
+    /// 5. Store a SpendRecord and update grant accounting
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `grant_id` - Grant to draw from
+    /// * `researcher` - Eligible researcher (must authorize the call)
+    /// * `dataset_id` - Marketplace dataset to purchase
+    ///
+    /// # Returns
+    /// * `Ok(SpendRecord)` describing the draw
+    /// * `Err(Error)` if validation fails
+    pub fn fund_purchase(
+        env: Env,
+        grant_id: u64,
+        researcher: Address,
+        dataset_id: Bytes,
+    ) -> Result<SpendRecord, Error> {
+        researcher.require_auth();
+
+        // ============================================
+        // 1. VALIDATE GRANT, RESEARCHER AND EARMARK
+        // ============================================
+        let mut grant = Self::load_grant(&env, grant_id)?;
+        if !grant.open {
+            return Err(Error::GrantClosed);
+        }
+        if !Self::is_eligible(env.clone(), grant_id, researcher.clone()) {
+            return Err(Error::ResearcherNotEligible);
+        }
+        if !Self::get_dataset_tags(env.clone(), dataset_id.clone()).contains(&grant.tag) {
+            return Err(Error::EarmarkMismatch);
+        }
+
+        // ============================================
+        // 2. LOOK UP PRICE
+        // ============================================
+        let storage = env.storage().instance();
+        let marketplace: Address = storage.get(&MARKETPLACE_KEY).ok_or(Error::NotInitialized)?;
+
+        let dataset: Dataset = env.invoke_contract(
+            &marketplace,
+            &Symbol::new(&env, "get_dataset"),
+            (dataset_id.clone(),).into_val(&env),
+        );
+        let amount = dataset.price_usdc;
+        if amount > grant.deposited - grant.spent - grant.withdrawn {
+            return Err(Error::InsufficientBudget);
+        }
+
+        // ============================================
+        // 3. PAY AND RECORD PURCHASE
+        // ============================================
+        // The marketplace collects the price from the buyer's allowance, so
+        // the researcher must have approved it for at least `amount`
+        Self::token_client(&env)?.transfer(&env.current_contract_address(), &researcher, &amount);
+
+        let _: Dataset = env.invoke_contract(
+            &marketplace,
+            &Symbol::new(&env, "purchase_dataset"),
+            (dataset_id.clone(), researcher.clone(), None::<Bytes>).into_val(&env),
+        );
+
+        // ============================================
+        // 4. UPDATE ACCOUNTING
+        // ============================================
+        let record = SpendRecord {
+            grant_id,
+            researcher: researcher.clone(),
+            dataset_id: dataset_id.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(SPEND_KEY, grant_id, grant.spend_count), &record);
+
+        grant.spent += amount;
+        grant.spend_count += 1;
+        env.storage()
+            .persistent()
+            .set(&(GRANT_KEY, grant_id), &grant);
+
+        env.events().publish(
+            (symbol_short!("GrantSpnd"), grant_id, researcher),
+            record.clone(),
+        );
+
+        Ok(record)
+    }
+
+    /// Close a grant and return the unspent budget to the sponsor
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `grant_id` - Grant to close
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the amount returned to the sponsor
+    /// * `Err(Error)` if the grant is missing or already closed
+    pub fn close_grant(env: Env, grant_id: u64) -> Result<i128, Error> {
+        let mut grant = Self::load_grant(&env, grant_id)?;
+        grant.sponsor.require_auth();
+        if !grant.open {
+            return Err(Error::GrantClosed);
+        }
+
+        let remaining = grant.deposited - grant.spent - grant.withdrawn;
+        if remaining > 0 {
+            Self::token_client(&env)?.transfer(
+                &env.current_contract_address(),
+                &grant.sponsor,
+                &remaining,
+            );
+        }
+
+        grant.withdrawn += remaining;
+        grant.open = false;
+        env.storage()
+            .persistent()
+            .set(&(GRANT_KEY, grant_id), &grant);
+
+        env.events()
+            .publish((symbol_short!("GrantCls"), grant_id), remaining);
+
+        Ok(remaining)
+    }
+
+    /// Get a grant by ID
+    pub fn get_grant(env: Env, grant_id: u64) -> Result<Grant, Error> {
+        Self::load_grant(&env, grant_id)
+    }
+
+    /// Get the unspent budget of a grant
+    pub fn remaining_budget(env: Env, grant_id: u64) -> Result<i128, Error> {
+        let grant = Self::load_grant(&env, grant_id)?;
+        Ok(grant.deposited - grant.spent - grant.withdrawn)
+    }
+
+    /// Get a spend record by grant and index (0..grant.spend_count)
+    pub fn get_spend(env: Env, grant_id: u64, index: u32) -> Result<SpendRecord, Error> {
+        env.storage()
+            .persistent()
+            .get(&(SPEND_KEY, grant_id, index))
+            .ok_or(Error::SpendNotFound)
+    }
+
+    /// Get the number of grants created so far
+    pub fn grant_count(env: Env) -> u64 {
+        env.storage().instance().get(&GRANT_COUNT_KEY).unwrap_or(0)
+    }
+
+    fn load_grant(env: &Env, grant_id: u64) -> Result<Grant, Error> {
+        env.storage()
+            .persistent()
+            .get(&(GRANT_KEY, grant_id))
+            .ok_or(Error::GrantNotFound)
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
@@ -0,0 +1,243 @@
+#![cfg(test)]
+
+use grant_pool::{Error, GrantPool, GrantPoolClient, DatasetStatus, Dataset};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, testutils::Address as AddressTestUtils, token, vec,
+    Address, Bytes, Env, Vec,
+};
+
+/// Price every dataset in the mock marketplace sells for (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Minimal DatasetMarketplace stand-in exposing get_dataset/purchase_dataset
+///
+/// Like the real marketplace, purchases pull the price from the buyer's
+/// allowance.
+#[contract]
+pub struct MockMarketplace;
+
+#[contractimpl]
+impl MockMarketplace {
+    pub fn set_usdc_token(env: Env, usdc_token: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("USDC"), &usdc_token);
+    }
+
+    pub fn get_dataset(env: Env, dataset_id: Bytes) -> Dataset {
+        Dataset {
+            dataset_id,
+            study_ids: Vec::new(&env),
+            price_usdc: PRICE,
+            owner: env.current_contract_address(),
+            status: DatasetStatus::Active,
+        }
+    }
+
+    pub fn purchase_dataset(
+        env: Env,
+        dataset_id: Bytes,
+        buyer: Address,
+        _promo_code: Option<Bytes>,
+    ) -> Dataset {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("USDC"))
+            .unwrap();
+        let marketplace = env.current_contract_address();
+        token::Client::new(&env, &usdc_token).transfer_from(
+            &marketplace,
+            &buyer,
+            &marketplace,
+            &PRICE,
+        );
+        env.storage()
+            .instance()
+            .set(&(dataset_id.clone(), buyer), &true);
+        Self::get_dataset(env, dataset_id)
+    }
+
+    pub fn has_purchase(env: Env, dataset_id: Bytes, buyer: Address) -> bool {
+        env.storage().instance().has(&(dataset_id, buyer))
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    pool: GrantPoolClient<'a>,
+    marketplace: MockMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    sponsor: Address,
+    researcher: Address,
+}
+
+/// Helper: Deploy USDC, the mock marketplace and an initialized GrantPool,
+/// fund a sponsor and tag "dataset_001" as a cardiology dataset
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let sponsor = Address::generate(&env);
+    let researcher = Address::generate(&env);
+
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&sponsor, &(100 * PRICE));
+
+    let marketplace_id = env.register_contract(None, MockMarketplace);
+    let marketplace = MockMarketplaceClient::new(&env, &marketplace_id);
+    marketplace.set_usdc_token(&usdc_id);
+    token::Client::new(&env, &usdc_id).approve(
+        &researcher,
+        &marketplace_id,
+        &(100 * PRICE),
+        &(env.ledger().sequence() + 100),
+    );
+    let pool_id = env.register_contract(None, GrantPool);
+    let pool = GrantPoolClient::new(&env, &pool_id);
+    pool.init(&admin, &usdc_id, &marketplace_id);
+    pool.set_dataset_tags(
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &vec![&env, symbol_short!("cardio")],
+    );
+
+    Setup {
+        marketplace,
+        usdc: token::Client::new(&env, &usdc_id),
+        pool,
+        sponsor,
+        researcher,
+        env,
+    }
+}
+
+#[test]
+fn test_create_grant_and_deposit() {
+    let s = setup();
+
+    // Act
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("cardio"), &(5 * PRICE));
+    let grant = s.pool.deposit(&grant_id, &(3 * PRICE));
+
+    // Assert
+    assert_eq!(grant_id, 0);
+    assert_eq!(grant.deposited, 8 * PRICE);
+    assert_eq!(s.pool.remaining_budget(&grant_id), 8 * PRICE);
+    assert_eq!(s.usdc.balance(&s.pool.address), 8 * PRICE);
+    assert_eq!(s.pool.grant_count(), 1);
+}
+
+#[test]
+fn test_fund_purchase_pays_and_records_spend() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Arrange
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("cardio"), &(5 * PRICE));
+    s.pool.add_researcher(&grant_id, &s.researcher);
+
+    // Act
+    let record = s.pool.fund_purchase(&grant_id, &s.researcher, &dataset_id);
+
+    // Assert
+    assert_eq!(record.amount, PRICE);
+    assert_eq!(s.usdc.balance(&s.marketplace.address), PRICE);
+    assert_eq!(s.usdc.balance(&s.researcher), 0);
+    assert!(s.marketplace.has_purchase(&dataset_id, &s.researcher));
+
+    let grant = s.pool.get_grant(&grant_id);
+    assert_eq!(grant.spent, PRICE);
+    assert_eq!(grant.spend_count, 1);
+    assert_eq!(s.pool.get_spend(&grant_id, &0), record);
+    assert_eq!(s.pool.remaining_budget(&grant_id), 4 * PRICE);
+}
+
+#[test]
+fn test_fund_purchase_rejects_ineligible_researcher() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("cardio"), &(5 * PRICE));
+
+    // Act
+    let result = s
+        .pool
+        .try_fund_purchase(&grant_id, &s.researcher, &dataset_id);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::ResearcherNotEligible)));
+}
+
+#[test]
+fn test_fund_purchase_rejects_earmark_mismatch() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Arrange: grant earmarked for oncology, dataset tagged cardio
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("onco"), &(5 * PRICE));
+    s.pool.add_researcher(&grant_id, &s.researcher);
+
+    // Act
+    let result = s
+        .pool
+        .try_fund_purchase(&grant_id, &s.researcher, &dataset_id);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::EarmarkMismatch)));
+}
+
+#[test]
+fn test_fund_purchase_rejects_insufficient_budget() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Arrange: budget smaller than the dataset price
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("cardio"), &(PRICE / 2));
+    s.pool.add_researcher(&grant_id, &s.researcher);
+
+    // Act
+    let result = s
+        .pool
+        .try_fund_purchase(&grant_id, &s.researcher, &dataset_id);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::InsufficientBudget)));
+}
+
+#[test]
+fn test_close_grant_returns_unspent_budget() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Arrange
+    let grant_id = s
+        .pool
+        .create_grant(&s.sponsor, &symbol_short!("cardio"), &(5 * PRICE));
+    s.pool.add_researcher(&grant_id, &s.researcher);
+    s.pool.fund_purchase(&grant_id, &s.researcher, &dataset_id);
+
+    // Act
+    let returned = s.pool.close_grant(&grant_id);
+
+    // Assert
+    assert_eq!(returned, 4 * PRICE);
+    assert_eq!(s.usdc.balance(&s.sponsor), 99 * PRICE);
+    assert_eq!(s.pool.remaining_budget(&grant_id), 0);
+    assert_eq!(
+        s.pool
+            .try_fund_purchase(&grant_id, &s.researcher, &dataset_id),
+        Err(Ok(Error::GrantClosed))
+    );
+}
@@ -0,0 +1,327 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec,
+};
+
+/// Storage keys
+const GUARDIANS_KEY: Symbol = symbol_short!("GUARDIANS");
+const THRESHOLD_KEY: Symbol = symbol_short!("THRESHOLD");
+const EPOCH_KEY: Symbol = symbol_short!("EPOCH");
+const PAUSE_KEY: Symbol = symbol_short!("PAUSE");
+const PAUSE_COUNT_KEY: Symbol = symbol_short!("PAUSE_CNT");
+const PROPOSAL_COUNT_KEY: Symbol = symbol_short!("PROP_CNT");
+const PROPOSAL_KEY: Symbol = symbol_short!("PROPOSAL");
+
+/// Maximum number of guardians
+pub const MAX_GUARDIANS: u32 = 20;
+
+/// Action that needs M-of-N guardian approvals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GuardianAction {
+    Unpause,
+    AddGuardian(Address),
+    RemoveGuardian(Address),
+    SetThreshold(u32),
+}
+
+/// Guardian proposal
+///
+/// Executed as soon as `approvals` reaches the threshold. Proposals belong
+/// to the guardian set of their `epoch`; once the set or threshold changes
+/// they can no longer be approved. Unpause proposals also belong to the
+/// pause numbered `pause_epoch`, so approvals gathered against an earlier
+/// pause cannot lift a later one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub action: GuardianAction,
+    pub approvals: Vec<Address>,
+    pub epoch: u32,
+    pub pause_epoch: u32,
+    pub executed: bool,
+}
+
+/// Current pause
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pause {
+    pub paused_by: Address,
+    pub paused_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotGuardian = 3,
+    InvalidGuardianSet = 4,
+    InvalidThreshold = 5,
+    AlreadyPaused = 6,
+    NotPaused = 7,
+    ProposalNotFound = 8,
+    ProposalClosed = 9,
+    AlreadyApproved = 10,
+}
+
+/// Emergency pause shared by the marketplace, splitter and registry
+///
+/// Any single guardian can pause at once, so an incident needs one key.
+/// Unpausing and changes to the guardian set need M-of-N approvals, so no
+/// single key can hold the platform paused or take over the set.
+#[contract]
+pub struct GuardianSet;
+
+#[contractimpl]
+impl GuardianSet {
+    /// Initialize the GuardianSet contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardians` - Distinct guardian addresses
+    /// * `threshold` - Approvals (M) needed for unpausing and set changes
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if called twice or the set is invalid
+    pub fn init(env: Env, guardians: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&GUARDIANS_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        if guardians.is_empty() || guardians.len() > MAX_GUARDIANS {
+            return Err(Error::InvalidGuardianSet);
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardians.first_index_of(&guardian) != Some(i as u32) {
+                return Err(Error::InvalidGuardianSet);
+            }
+        }
+        if threshold == 0 || threshold > guardians.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        storage.set(&GUARDIANS_KEY, &guardians);
+        storage.set(&THRESHOLD_KEY, &threshold);
+        storage.set(&EPOCH_KEY, &0u32);
+        storage.set(&PROPOSAL_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Pause the contracts watching this set
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardian` - Guardian pausing (must authorize)
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotGuardian)` if the caller is not a guardian
+    /// * `Err(Error::AlreadyPaused)` if already paused
+    pub fn pause(env: Env, guardian: Address) -> Result<(), Error> {
+        Self::require_guardian(&env, &guardian)?;
+        let storage = env.storage().instance();
+        if storage.has(&PAUSE_KEY) {
+            return Err(Error::AlreadyPaused);
+        }
+        let pause = Pause {
+            paused_by: guardian.clone(),
+            paused_at: env.ledger().timestamp(),
+        };
+        storage.set(&PAUSE_KEY, &pause);
+        let pause_count: u32 = storage.get(&PAUSE_COUNT_KEY).unwrap_or(0);
+        storage.set(&PAUSE_COUNT_KEY, &(pause_count + 1));
+
+        env.events()
+            .publish((symbol_short!("Paused"), guardian), pause.paused_at);
+        Ok(())
+    }
+
+    /// Propose an action, counting the proposer's approval
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardian` - Proposing guardian (must authorize)
+    /// * `action` - Action to take once enough guardians approve
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the proposal ID (executed already if M is 1)
+    /// * `Err(Error)` if the caller is not a guardian or the action is
+    ///   invalid for the current set
+    pub fn propose(env: Env, guardian: Address, action: GuardianAction) -> Result<u64, Error> {
+        Self::require_guardian(&env, &guardian)?;
+        Self::validate_action(&env, &action)?;
+
+        let storage = env.storage().instance();
+        let proposal_id: u64 = storage
+            .get(&PROPOSAL_COUNT_KEY)
+            .ok_or(Error::NotInitialized)?;
+        storage.set(&PROPOSAL_COUNT_KEY, &(proposal_id + 1));
+
+        let proposal = Proposal {
+            proposal_id,
+            action,
+            approvals: Vec::from_array(&env, [guardian.clone()]),
+            epoch: storage.get(&EPOCH_KEY).unwrap_or(0),
+            pause_epoch: storage.get(&PAUSE_COUNT_KEY).unwrap_or(0),
+            executed: false,
+        };
+        env.events()
+            .publish((symbol_short!("Proposed"), proposal_id), guardian);
+        Self::execute_if_approved(&env, proposal)?;
+
+        Ok(proposal_id)
+    }
+
+    /// Approve a proposal, executing it once the threshold is met
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardian` - Approving guardian (must authorize)
+    /// * `proposal_id` - Proposal to approve
+    ///
+    /// # Returns
+    /// * `Ok(bool)` whether the proposal was executed
+    /// * `Err(Error::ProposalClosed)` if executed, from an earlier set or
+    ///   an unpause proposal from an earlier pause
+    /// * `Err(Error)` if the caller is not a guardian or already approved
+    pub fn approve(env: Env, guardian: Address, proposal_id: u64) -> Result<bool, Error> {
+        Self::require_guardian(&env, &guardian)?;
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        let storage = env.storage().instance();
+        let epoch: u32 = storage.get(&EPOCH_KEY).unwrap_or(0);
+        let pause_epoch: u32 = storage.get(&PAUSE_COUNT_KEY).unwrap_or(0);
+        let stale_unpause =
+            proposal.action == GuardianAction::Unpause && proposal.pause_epoch != pause_epoch;
+        if proposal.executed || proposal.epoch != epoch || stale_unpause {
+            return Err(Error::ProposalClosed);
+        }
+        if proposal.approvals.contains(&guardian) {
+            return Err(Error::AlreadyApproved);
+        }
+        proposal.approvals.push_back(guardian.clone());
+
+        env.events()
+            .publish((symbol_short!("Approved"), proposal_id), guardian);
+        Self::execute_if_approved(&env, proposal)
+    }
+
+    /// Check whether the contracts watching this set are paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&PAUSE_KEY)
+    }
+
+    /// Get the current pause, if any
+    pub fn get_pause(env: Env) -> Option<Pause> {
+        env.storage().instance().get(&PAUSE_KEY)
+    }
+
+    /// Get the guardians
+    pub fn get_guardians(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&GUARDIANS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the number of approvals (M) actions need
+    pub fn get_threshold(env: Env) -> u32 {
+        env.storage().instance().get(&THRESHOLD_KEY).unwrap_or(0)
+    }
+
+    /// Get a proposal by ID
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&(PROPOSAL_KEY, proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    /// Store a proposal, executing it first if it has enough approvals
+    fn execute_if_approved(env: &Env, mut proposal: Proposal) -> Result<bool, Error> {
+        let storage = env.storage().instance();
+        let threshold: u32 = storage.get(&THRESHOLD_KEY).ok_or(Error::NotInitialized)?;
+        if proposal.approvals.len() >= threshold {
+            // The set may have changed since the action was proposed
+            Self::validate_action(env, &proposal.action)?;
+            let mut guardians = Self::get_guardians(env.clone());
+            match proposal.action.clone() {
+                GuardianAction::Unpause => storage.remove(&PAUSE_KEY),
+                GuardianAction::AddGuardian(guardian) => {
+                    guardians.push_back(guardian);
+                    storage.set(&GUARDIANS_KEY, &guardians);
+                }
+                GuardianAction::RemoveGuardian(guardian) => {
+                    if let Some(index) = guardians.first_index_of(&guardian) {
+                        guardians.remove(index);
+                    }
+                    storage.set(&GUARDIANS_KEY, &guardians);
+                }
+                GuardianAction::SetThreshold(threshold) => storage.set(&THRESHOLD_KEY, &threshold),
+            }
+            // Any change to the set closes the proposals made under it
+            if proposal.action != GuardianAction::Unpause {
+                let epoch: u32 = storage.get(&EPOCH_KEY).unwrap_or(0);
+                storage.set(&EPOCH_KEY, &(epoch + 1));
+            }
+            proposal.executed = true;
+
+            env.events().publish(
+                (symbol_short!("Executed"), proposal.proposal_id),
+                proposal.action.clone(),
+            );
+        }
+        env.storage()
+            .persistent()
+            .set(&(PROPOSAL_KEY, proposal.proposal_id), &proposal);
+        Ok(proposal.executed)
+    }
+
+    /// Check an action against the current set
+    fn validate_action(env: &Env, action: &GuardianAction) -> Result<(), Error> {
+        let guardians = Self::get_guardians(env.clone());
+        let threshold = Self::get_threshold(env.clone());
+        match action {
+            GuardianAction::Unpause => {
+                if !Self::is_paused(env.clone()) {
+                    return Err(Error::NotPaused);
+                }
+            }
+            GuardianAction::AddGuardian(guardian) => {
+                if guardians.contains(guardian) || guardians.len() >= MAX_GUARDIANS {
+                    return Err(Error::InvalidGuardianSet);
+                }
+            }
+            GuardianAction::RemoveGuardian(guardian) => {
+                if !guardians.contains(guardian) {
+                    return Err(Error::NotGuardian);
+                }
+                if guardians.len() - 1 < threshold {
+                    return Err(Error::InvalidThreshold);
+                }
+            }
+            GuardianAction::SetThreshold(threshold) => {
+                if *threshold == 0 || *threshold > guardians.len() {
+                    return Err(Error::InvalidThreshold);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn require_guardian(env: &Env, guardian: &Address) -> Result<(), Error> {
+        guardian.require_auth();
+        let guardians: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&GUARDIANS_KEY)
+            .ok_or(Error::NotInitialized)?;
+        if !guardians.contains(guardian) {
+            return Err(Error::NotGuardian);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+#![cfg(test)]
+
+use guardian_set::{Error, GuardianAction, GuardianSet, GuardianSetClient};
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, vec, Address, Bytes, BytesN, Env, Vec,
+};
+
+struct Setup<'a> {
+    env: Env,
+    guardians: GuardianSetClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    members: Vec<Address>,
+}
+
+/// Helper: Deploy the core contracts watching a 2-of-3 GuardianSet
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let members = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let guardians = GuardianSetClient::new(&env, &env.register_contract(None, GuardianSet));
+    guardians.init(&members, &2);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    registry.set_guardian_set(&guardians.address);
+    marketplace.set_guardian_set(&guardians.address);
+    splitter.set_guardian_set(&guardians.address);
+
+    Setup {
+        env,
+        guardians,
+        registry,
+        marketplace,
+        splitter,
+        members,
+    }
+}
+
+/// Helper: Register a study with hash `[seed; 32]`
+fn try_register(s: &Setup, seed: u8) -> bool {
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let contributor = Address::generate(&s.env);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &contributor);
+    s.registry
+        .try_register_study(
+            &study_hash,
+            &attestation,
+            &study_registry::ProofKind::Groth16,
+            &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &contributor),
+            &contributor,
+        )
+        .is_ok()
+}
+
+#[test]
+fn test_single_guardian_pauses_all_contracts() {
+    let s = setup();
+    assert!(try_register(&s, 1));
+
+    // Act
+    s.guardians.pause(&s.members.get(2).unwrap());
+
+    // Assert
+    assert!(s.guardians.is_paused());
+    assert!(s.registry.is_paused());
+    assert!(s.marketplace.is_paused());
+    assert!(s.splitter.is_paused());
+    let study_hash = BytesN::from_array(&s.env, &[2u8; 32]);
+    let contributor = Address::generate(&s.env);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &contributor);
+    assert_eq!(
+        s.registry.try_register_study(
+            &study_hash,
+            &attestation,
+            &study_registry::ProofKind::Groth16,
+            &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &contributor),
+            &contributor,
+        ),
+        Err(Ok(study_registry::Error::Paused))
+    );
+    assert_eq!(
+        s.splitter.try_payout_purchase(
+            &Address::generate(&s.env),
+            &Bytes::from_slice(&s.env, b"dataset_001"),
+            &vec![&s.env, Address::generate(&s.env)],
+            &100,
+        ),
+        Err(Ok(revenue_splitter::Error::Paused))
+    );
+    assert_eq!(
+        s.guardians.get_pause().unwrap().paused_by,
+        s.members.get(2).unwrap()
+    );
+    assert_eq!(
+        s.guardians.try_pause(&s.members.get(0).unwrap()),
+        Err(Ok(Error::AlreadyPaused))
+    );
+}
+
+#[test]
+fn test_unpause_needs_threshold_approvals() {
+    let s = setup();
+    let first = s.members.get(0).unwrap();
+    s.guardians.pause(&first);
+
+    // Act: the proposer's approval alone is not enough
+    let proposal_id = s.guardians.propose(&first, &GuardianAction::Unpause);
+    assert!(s.marketplace.is_paused());
+    assert_eq!(
+        s.guardians.try_approve(&first, &proposal_id),
+        Err(Ok(Error::AlreadyApproved))
+    );
+    let executed = s.guardians.approve(&s.members.get(1).unwrap(), &proposal_id);
+
+    // Assert
+    assert!(executed);
+    assert!(!s.registry.is_paused());
+    assert!(!s.marketplace.is_paused());
+    assert!(!s.splitter.is_paused());
+    assert!(try_register(&s, 1));
+    assert_eq!(
+        s.guardians
+            .try_approve(&s.members.get(2).unwrap(), &proposal_id),
+        Err(Ok(Error::ProposalClosed))
+    );
+}
+
+#[test]
+fn test_unpause_approvals_do_not_carry_over_to_a_later_pause() {
+    let s = setup();
+    let (first, second, third) = (
+        s.members.get(0).unwrap(),
+        s.members.get(1).unwrap(),
+        s.members.get(2).unwrap(),
+    );
+    s.guardians.pause(&first);
+
+    // Arrange: two unpause proposals, only the second of which executes
+    let stale_id = s.guardians.propose(&first, &GuardianAction::Unpause);
+    let unpause_id = s.guardians.propose(&second, &GuardianAction::Unpause);
+    assert!(s.guardians.approve(&third, &unpause_id));
+    assert!(!s.guardians.is_paused());
+
+    // Act: pause again, then approve the leftover proposal
+    s.guardians.pause(&third);
+    let result = s.guardians.try_approve(&second, &stale_id);
+
+    // Assert: the earlier pause's approval cannot lift the new pause
+    assert_eq!(result, Err(Ok(Error::ProposalClosed)));
+    assert!(s.guardians.is_paused());
+    assert!(s.marketplace.is_paused());
+    let proposal_id = s.guardians.propose(&first, &GuardianAction::Unpause);
+    assert!(s.guardians.approve(&second, &proposal_id));
+    assert!(!s.guardians.is_paused());
+}
+
+#[test]
+fn test_non_guardian_is_rejected() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(s.guardians.try_pause(&outsider), Err(Ok(Error::NotGuardian)));
+    assert_eq!(
+        s.guardians.try_propose(&outsider, &GuardianAction::SetThreshold(1)),
+        Err(Ok(Error::NotGuardian))
+    );
+    assert_eq!(
+        s.guardians
+            .try_propose(&s.members.get(0).unwrap(), &GuardianAction::Unpause),
+        Err(Ok(Error::NotPaused))
+    );
+    assert!(!s.registry.is_paused());
+}
+
+#[test]
+fn test_set_changes_need_threshold_and_close_stale_proposals() {
+    let s = setup();
+    let (first, second, third) = (
+        s.members.get(0).unwrap(),
+        s.members.get(1).unwrap(),
+        s.members.get(2).unwrap(),
+    );
+    let newcomer = Address::generate(&s.env);
+    let remove_id = s
+        .guardians
+        .propose(&first, &GuardianAction::RemoveGuardian(third.clone()));
+    let add_id = s
+        .guardians
+        .propose(&second, &GuardianAction::AddGuardian(newcomer.clone()));
+
+    // Act
+    assert!(s.guardians.approve(&second, &remove_id));
+
+    // Assert: the removed guardian lost its powers and older proposals closed
+    assert_eq!(s.guardians.get_guardians(), vec![&s.env, first.clone(), second.clone()]);
+    assert_eq!(s.guardians.try_pause(&third), Err(Ok(Error::NotGuardian)));
+    assert_eq!(
+        s.guardians.try_approve(&first, &add_id),
+        Err(Ok(Error::ProposalClosed))
+    );
+
+    // The set cannot shrink below the threshold
+    assert_eq!(
+        s.guardians
+            .try_propose(&first, &GuardianAction::RemoveGuardian(second.clone())),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    assert_eq!(
+        s.guardians.try_propose(&first, &GuardianAction::SetThreshold(3)),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    let threshold_id = s.guardians.propose(&first, &GuardianAction::SetThreshold(1));
+    assert!(s.guardians.approve(&second, &threshold_id));
+    assert_eq!(s.guardians.get_threshold(), 1);
+}
+
+#[test]
+fn test_init_validates_guardian_set() {
+    let env = Env::default();
+    let guardians = GuardianSetClient::new(&env, &env.register_contract(None, GuardianSet));
+    let member = Address::generate(&env);
+
+    // Act & Assert
+    assert_eq!(
+        guardians.try_init(&vec![&env, member.clone(), member.clone()], &1),
+        Err(Ok(Error::InvalidGuardianSet))
+    );
+    assert_eq!(
+        guardians.try_init(&vec![&env, member.clone()], &2),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    assert_eq!(
+        guardians.try_init(&Vec::new(&env), &1),
+        Err(Ok(Error::InvalidGuardianSet))
+    );
+    guardians.init(&vec![&env, member.clone()], &1);
+    assert_eq!(
+        guardians.try_init(&vec![&env, member], &1),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
@@ -0,0 +1,427 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes, Env,
+    IntoVal, Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const ARBITER_KEY: Symbol = symbol_short!("ARBITER");
+const CLAIM_COUNT_KEY: Symbol = symbol_short!("CLAIM_CNT");
+const CLAIM_KEY: Symbol = symbol_short!("CLAIM");
+const SELLER_KEY: Symbol = symbol_short!("SELLER");
+const RESERVES_KEY: Symbol = symbol_short!("RESERVES");
+const RELAY_KEY: Symbol = symbol_short!("RELAY");
+
+/// Premium charged on a seller's platform share with no upheld claims
+/// (basis points of the platform share)
+pub const BASE_PREMIUM_BPS: u32 = 1_000;
+
+/// Premium increase per upheld claim against a seller (basis points)
+pub const CLAIM_SURCHARGE_BPS: u32 = 500;
+
+/// Maximum premium (basis points); the whole platform share
+pub const MAX_PREMIUM_BPS: u32 = 10_000;
+
+/// Claim lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    Pending,
+    Paid,
+    Rejected,
+}
+
+/// Claim structure
+///
+/// A buyer's claim that a purchased dataset is materially defective:
+/// - buyer: Researcher filing the claim (and receiving the payout)
+/// - seller: Contributor/owner the claim is raised against
+/// - dataset_id: Dataset the claim concerns
+/// - evidence_hash: Hash of the off-chain evidence bundle
+/// - amount: Amount claimed in USDC
+/// - status: Pending until the arbiter resolves it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claim {
+    pub claim_id: u64,
+    pub buyer: Address,
+    pub seller: Address,
+    pub dataset_id: Bytes,
+    pub evidence_hash: Bytes,
+    pub amount: i128,
+    pub status: ClaimStatus,
+    pub filed_at: u64,
+}
+
+/// Per-seller insurance record
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SellerRecord {
+    pub premiums_paid: i128,
+    pub claims_paid: i128,
+    pub upheld_claims: u32,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    ClaimNotFound = 4,
+    ClaimAlreadyResolved = 5,
+    InsufficientReserves = 6,
+    RelayerNotAllowed = 7,
+    InvalidNonce = 8,
+}
+
+#[contract]
+pub struct InsurancePool;
+
+#[contractimpl]
+impl InsurancePool {
+    /// Initialize the InsurancePool contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to replace the arbiter
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `arbiter` - Arbitration contract that validates claims
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        arbiter: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&ARBITER_KEY, &arbiter);
+        storage.set(&CLAIM_COUNT_KEY, &0u64);
+        storage.set(&RESERVES_KEY, &0i128);
+        Ok(())
+    }
+
+    /// Replace the arbitration contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbiter` - New arbitration contract address
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    pub fn set_arbiter(env: Env, arbiter: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        let admin: Address = storage.get(&ADMIN_KEY).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        storage.set(&ARBITER_KEY, &arbiter);
+        Ok(())
+    }
+
+    /// Get the premium rate for a seller
+    ///
+    /// The rate is applied to the platform share of each payout for the
+    /// seller's studies and grows with every upheld claim against them.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `seller` - Seller address
+    ///
+    /// # Returns
+    /// * Premium in basis points of the platform share
+    pub fn premium_bps(env: Env, seller: Address) -> u32 {
+        let record = Self::load_seller(&env, &seller);
+        BASE_PREMIUM_BPS
+            .saturating_add(record.upheld_claims.saturating_mul(CLAIM_SURCHARGE_BPS))
+            .min(MAX_PREMIUM_BPS)
+    }
+
+    /// Pay a premium into the pool
+    ///
+    /// Called by RevenueSplitter with the slice of the platform share
+    /// diverted for a seller.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is transferred from (must authorize)
+    /// * `seller` - Seller the premium is charged for
+    /// * `amount` - Premium amount in USDC
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidAmount)` if amount is not positive
+    pub fn deposit_premium(
+        env: Env,
+        payer: Address,
+        seller: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::token_client(&env)?.transfer(&payer, &env.current_contract_address(), &amount);
+
+        let mut record = Self::load_seller(&env, &seller);
+        record.premiums_paid += amount;
+        env.storage()
+            .persistent()
+            .set(&(SELLER_KEY, seller.clone()), &record);
+        Self::adjust_reserves(&env, amount);
+
+        env.events()
+            .publish((symbol_short!("Premium"), seller), amount);
+
+        Ok(())
+    }
+
+    /// File a claim about a materially defective dataset
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - Researcher filing the claim (must authorize)
+    /// * `seller` - Seller the claim is raised against
+    /// * `dataset_id` - Dataset the claim concerns
+    /// * `evidence_hash` - Hash of the off-chain evidence bundle
+    /// * `amount` - Amount claimed in USDC
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the claim ID
+    /// * `Err(Error)` if validation fails
+    pub fn file_claim(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        dataset_id: Bytes,
+        evidence_hash: Bytes,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        buyer.require_auth();
+        Self::open_claim(&env, buyer, seller, dataset_id, evidence_hash, amount)
+    }
+
+    /// File a claim through a fee-sponsoring relayer
+    ///
+    /// The buyer signs an auth entry for this call and an allowlisted
+    /// relayer submits it, paying the fees. The nonce is consumed in the
+    /// RelayerRegistry so the signed call cannot be replayed.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `relayer` - Allowlisted relayer submitting the call (must authorize)
+    /// * `nonce` - Buyer's next RelayerRegistry nonce
+    /// * `buyer` - Researcher filing the claim (must authorize)
+    /// * `seller` - Seller the claim is raised against
+    /// * `dataset_id` - Dataset the claim concerns
+    /// * `evidence_hash` - Hash of the off-chain evidence bundle
+    /// * `amount` - Amount claimed in USDC
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the claim ID
+    /// * `Err(Error)` if the relay is rejected or validation fails
+    #[allow(clippy::too_many_arguments)]
+    pub fn file_claim_sponsored(
+        env: Env,
+        relayer: Address,
+        nonce: u64,
+        buyer: Address,
+        seller: Address,
+        dataset_id: Bytes,
+        evidence_hash: Bytes,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        relayer.require_auth();
+        buyer.require_auth();
+        Self::use_relay_nonce(&env, &relayer, &buyer, nonce)?;
+        Self::open_claim(&env, buyer, seller, dataset_id, evidence_hash, amount)
+    }
+
+    /// Resolve a pending claim
+    ///
+    /// Only the arbitration contract can resolve claims. Approved claims are
+    /// paid to the buyer from the pool and count against the seller's
+    /// premium rate.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `claim_id` - Claim to resolve
+    /// * `approved` - Arbitration outcome
+    ///
+    /// # Returns
+    /// * `Ok(Claim)` with the resolved claim
+    /// * `Err(Error)` if the claim is missing, resolved, or underfunded
+    pub fn resolve_claim(env: Env, claim_id: u64, approved: bool) -> Result<Claim, Error> {
+        let arbiter: Address = env
+            .storage()
+            .instance()
+            .get(&ARBITER_KEY)
+            .ok_or(Error::NotInitialized)?;
+        arbiter.require_auth();
+
+        let mut claim = Self::get_claim(env.clone(), claim_id)?;
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::ClaimAlreadyResolved);
+        }
+
+        if approved {
+            if claim.amount > Self::get_reserves(env.clone()) {
+                return Err(Error::InsufficientReserves);
+            }
+            Self::token_client(&env)?.transfer(
+                &env.current_contract_address(),
+                &claim.buyer,
+                &claim.amount,
+            );
+            Self::adjust_reserves(&env, -claim.amount);
+
+            let mut record = Self::load_seller(&env, &claim.seller);
+            record.claims_paid += claim.amount;
+            record.upheld_claims = record.upheld_claims.saturating_add(1);
+            env.storage()
+                .persistent()
+                .set(&(SELLER_KEY, claim.seller.clone()), &record);
+
+            claim.status = ClaimStatus::Paid;
+        } else {
+            claim.status = ClaimStatus::Rejected;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(CLAIM_KEY, claim_id), &claim);
+
+        env.events()
+            .publish((symbol_short!("ClaimRes"), claim_id), claim.status);
+
+        Ok(claim)
+    }
+
+    /// Get a claim by ID
+    pub fn get_claim(env: Env, claim_id: u64) -> Result<Claim, Error> {
+        env.storage()
+            .persistent()
+            .get(&(CLAIM_KEY, claim_id))
+            .ok_or(Error::ClaimNotFound)
+    }
+
+    /// Set the RelayerRegistry contract (admin only)
+    pub fn set_relayer_registry(env: Env, relayer_registry: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        let admin: Address = storage.get(&ADMIN_KEY).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        storage.set(&RELAY_KEY, &relayer_registry);
+        Ok(())
+    }
+
+    /// Get the insurance record of a seller
+    pub fn get_seller(env: Env, seller: Address) -> SellerRecord {
+        Self::load_seller(&env, &seller)
+    }
+
+    /// Get the USDC reserves available to pay claims
+    pub fn get_reserves(env: Env) -> i128 {
+        env.storage().instance().get(&RESERVES_KEY).unwrap_or(0)
+    }
+
+    /// Store a new pending claim
+    fn open_claim(
+        env: &Env,
+        buyer: Address,
+        seller: Address,
+        dataset_id: Bytes,
+        evidence_hash: Bytes,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let storage = env.storage().instance();
+        let claim_id: u64 = storage.get(&CLAIM_COUNT_KEY).ok_or(Error::NotInitialized)?;
+
+        let claim = Claim {
+            claim_id,
+            buyer: buyer.clone(),
+            seller,
+            dataset_id,
+            evidence_hash,
+            amount,
+            status: ClaimStatus::Pending,
+            filed_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(CLAIM_KEY, claim_id), &claim);
+        storage.set(&CLAIM_COUNT_KEY, &(claim_id + 1));
+
+        env.events()
+            .publish((symbol_short!("ClaimNew"), claim_id), claim);
+
+        Ok(claim_id)
+    }
+
+    /// Consume the signer's nonce for a sponsored call in the RelayerRegistry
+    fn use_relay_nonce(
+        env: &Env,
+        relayer: &Address,
+        signer: &Address,
+        nonce: u64,
+    ) -> Result<(), Error> {
+        let relayer_registry: Address = env
+            .storage()
+            .instance()
+            .get(&RELAY_KEY)
+            .ok_or(Error::RelayerNotAllowed)?;
+
+        match env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &relayer_registry,
+            &Symbol::new(env, "use_nonce"),
+            (relayer.clone(), signer.clone(), nonce).into_val(env),
+        ) {
+            Ok(Ok(())) => Ok(()),
+            // RelayerRegistry Error::InvalidNonce
+            Err(Ok(error)) if error == soroban_sdk::Error::from_contract_error(4) => {
+                Err(Error::InvalidNonce)
+            }
+            _ => Err(Error::RelayerNotAllowed),
+        }
+    }
+
+    fn load_seller(env: &Env, seller: &Address) -> SellerRecord {
+        env.storage()
+            .persistent()
+            .get(&(SELLER_KEY, seller.clone()))
+            .unwrap_or(SellerRecord {
+                premiums_paid: 0,
+                claims_paid: 0,
+                upheld_claims: 0,
+            })
+    }
+
+    fn adjust_reserves(env: &Env, delta: i128) {
+        let storage = env.storage().instance();
+        let reserves: i128 = storage.get(&RESERVES_KEY).unwrap_or(0);
+        storage.set(&RESERVES_KEY, &(reserves + delta));
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(test)]
+
+use insurance_pool::{
+    ClaimStatus, Error, InsurancePool, InsurancePoolClient, BASE_PREMIUM_BPS, CLAIM_SURCHARGE_BPS,
+};
+use soroban_sdk::{testutils::Address as AddressTestUtils, token, Address, Bytes, Env};
+
+/// 10 USDC with 7 decimal places
+const TEN_USDC: i128 = 10_0000000;
+
+struct Setup<'a> {
+    env: Env,
+    pool: InsurancePoolClient<'a>,
+    usdc: token::Client<'a>,
+    payer: Address,
+    seller: Address,
+    buyer: Address,
+}
+
+/// Helper: Deploy USDC and an initialized InsurancePool, fund the payer
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let payer = Address::generate(&env);
+
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&payer, &(100 * TEN_USDC));
+
+    let pool_id = env.register_contract(None, InsurancePool);
+    let pool = InsurancePoolClient::new(&env, &pool_id);
+    pool.init(&admin, &usdc_id, &arbiter);
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        seller: Address::generate(&env),
+        buyer: Address::generate(&env),
+        pool,
+        payer,
+        env,
+    }
+}
+
+/// Helper: File a claim for `amount` from the buyer against the seller
+fn file_claim(s: &Setup, amount: i128) -> u64 {
+    s.pool.file_claim(
+        &s.buyer,
+        &s.seller,
+        &Bytes::from_slice(&s.env, b"dataset_001"),
+        &Bytes::from_slice(&s.env, b"evidence"),
+        &amount,
+    )
+}
+
+#[test]
+fn test_deposit_premium_increases_reserves() {
+    let s = setup();
+
+    // Act
+    s.pool.deposit_premium(&s.payer, &s.seller, &TEN_USDC);
+
+    // Assert
+    assert_eq!(s.pool.get_reserves(), TEN_USDC);
+    assert_eq!(s.usdc.balance(&s.pool.address), TEN_USDC);
+    assert_eq!(s.pool.get_seller(&s.seller).premiums_paid, TEN_USDC);
+}
+
+#[test]
+fn test_approved_claim_pays_buyer_and_raises_premium() {
+    let s = setup();
+
+    // Arrange
+    s.pool.deposit_premium(&s.payer, &s.seller, &(5 * TEN_USDC));
+    assert_eq!(s.pool.premium_bps(&s.seller), BASE_PREMIUM_BPS);
+    let claim_id = file_claim(&s, TEN_USDC);
+
+    // Act
+    let claim = s.pool.resolve_claim(&claim_id, &true);
+
+    // Assert
+    assert_eq!(claim.status, ClaimStatus::Paid);
+    assert_eq!(s.usdc.balance(&s.buyer), TEN_USDC);
+    assert_eq!(s.pool.get_reserves(), 4 * TEN_USDC);
+    assert_eq!(s.pool.get_seller(&s.seller).upheld_claims, 1);
+    assert_eq!(
+        s.pool.premium_bps(&s.seller),
+        BASE_PREMIUM_BPS + CLAIM_SURCHARGE_BPS
+    );
+}
+
+#[test]
+fn test_rejected_claim_pays_nothing() {
+    let s = setup();
+
+    // Arrange
+    s.pool.deposit_premium(&s.payer, &s.seller, &(5 * TEN_USDC));
+    let claim_id = file_claim(&s, TEN_USDC);
+
+    // Act
+    let claim = s.pool.resolve_claim(&claim_id, &false);
+
+    // Assert
+    assert_eq!(claim.status, ClaimStatus::Rejected);
+    assert_eq!(s.usdc.balance(&s.buyer), 0);
+    assert_eq!(s.pool.premium_bps(&s.seller), BASE_PREMIUM_BPS);
+    assert_eq!(
+        s.pool.try_resolve_claim(&claim_id, &true),
+        Err(Ok(Error::ClaimAlreadyResolved))
+    );
+}
+
+#[test]
+fn test_claim_exceeding_reserves_fails() {
+    let s = setup();
+
+    // Arrange
+    s.pool.deposit_premium(&s.payer, &s.seller, &TEN_USDC);
+    let claim_id = file_claim(&s, 2 * TEN_USDC);
+
+    // Act
+    let result = s.pool.try_resolve_claim(&claim_id, &true);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::InsufficientReserves)));
+    assert_eq!(s.pool.get_claim(&claim_id).status, ClaimStatus::Pending);
+}
+
+#[test]
+fn test_premium_is_capped() {
+    let s = setup();
+
+    // Arrange: many upheld claims
+    s.pool
+        .deposit_premium(&s.payer, &s.seller, &(50 * TEN_USDC));
+    for _ in 0..30 {
+        let claim_id = file_claim(&s, 1);
+        s.pool.resolve_claim(&claim_id, &true);
+    }
+
+    // Assert
+    assert_eq!(s.pool.premium_bps(&s.seller), 10_000);
+}
+
+#[test]
+fn test_invalid_amounts_fail() {
+    let s = setup();
+
+    assert_eq!(
+        s.pool.try_deposit_premium(&s.payer, &s.seller, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.pool.try_file_claim(
+            &s.buyer,
+            &s.seller,
+            &Bytes::from_slice(&s.env, b"dataset_001"),
+            &Bytes::new(&s.env),
+            &-1,
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
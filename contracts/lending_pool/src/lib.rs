@@ -0,0 +1,468 @@
+#![no_std]
+use biochain_pricing as pricing;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes, Env,
+    IntoVal, Symbol,
+};
+pub use biochain_types::{Dataset, DatasetStatus};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const COMPLIANCE_KEY: Symbol = symbol_short!("COMPLY");
+const POLICY_KEY: Symbol = symbol_short!("POLICY");
+const LOAN_COUNT_KEY: Symbol = symbol_short!("LOAN_CNT");
+const LOAN_KEY: Symbol = symbol_short!("LOAN");
+const ACCOUNT_KEY: Symbol = symbol_short!("ACCOUNT");
+
+/// TTL policy for loans and credit accounts, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Credit policy of the pool
+///
+/// - base_limit: Credit line of an attested buyer without history
+/// - repaid_bonus: Credit added per loan repaid in full
+/// - max_limit: Cap on any credit line
+/// - interest_bps: Flat interest charged on the principal of each loan
+/// - term_seconds: Time until a loan is due
+/// - grace_seconds: Time after the due date before it can be marked
+///   delinquent
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditPolicy {
+    pub base_limit: i128,
+    pub repaid_bonus: i128,
+    pub max_limit: i128,
+    pub interest_bps: u32,
+    pub term_seconds: u64,
+    pub grace_seconds: u64,
+}
+
+/// Loan status
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoanStatus {
+    Active,
+    Repaid,
+    Delinquent,
+}
+
+/// Loan fronting one dataset purchase
+///
+/// - principal: Dataset price paid by the pool
+/// - interest: Interest owed on top of the principal
+/// - repaid: Amount repaid so far
+/// - due_at: Ledger timestamp the loan must be repaid by
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loan {
+    pub loan_id: u64,
+    pub buyer: Address,
+    pub dataset_id: Bytes,
+    pub principal: i128,
+    pub interest: i128,
+    pub repaid: i128,
+    pub due_at: u64,
+    pub status: LoanStatus,
+}
+
+/// Credit history of a buyer
+///
+/// - outstanding: Principal and interest still owed across loans
+/// - active_loans: Loans not yet repaid (including delinquent ones)
+/// - loans_repaid: Loans repaid in full
+/// - delinquent_loans: Delinquent loans not yet repaid; while positive the
+///   buyer gets no credit and access is suspended
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CreditAccount {
+    pub outstanding: i128,
+    pub active_loans: u32,
+    pub loans_repaid: u32,
+    pub delinquent_loans: u32,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidPolicy = 3,
+    InvalidAmount = 4,
+    NotAttested = 5,
+    AccountSuspended = 6,
+    CreditLimitExceeded = 7,
+    InsufficientLiquidity = 8,
+    DatasetNotFound = 9,
+    LoanNotFound = 10,
+    LoanClosed = 11,
+    NotDue = 12,
+}
+
+#[contract]
+pub struct LendingPool;
+
+#[contractimpl]
+impl LendingPool {
+    /// Initialize the LendingPool contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to set the policy and withdraw liquidity
+    /// * `marketplace` - DatasetMarketplace the pool buys from
+    /// * `usdc_token` - USDC token lent and repaid
+    /// * `compliance` - Contract answering `is_attested(buyer)`
+    /// * `policy` - Initial credit policy
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if called twice or the policy is invalid
+    pub fn init(
+        env: Env,
+        admin: Address,
+        marketplace: Address,
+        usdc_token: Address,
+        compliance: Address,
+        policy: CreditPolicy,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        Self::validate_policy(&policy)?;
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&COMPLIANCE_KEY, &compliance);
+        storage.set(&POLICY_KEY, &policy);
+        storage.set(&LOAN_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Replace the credit policy
+    ///
+    /// Existing loans keep their interest and due date.
+    pub fn set_policy(env: Env, policy: CreditPolicy) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::validate_policy(&policy)?;
+        env.storage().instance().set(&POLICY_KEY, &policy);
+        Ok(())
+    }
+
+    /// Get the credit policy
+    pub fn get_policy(env: Env) -> Result<CreditPolicy, Error> {
+        env.storage()
+            .instance()
+            .get(&POLICY_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Add USDC liquidity to the pool
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `from` - Address providing the USDC (must authorize)
+    /// * `amount` - USDC to add
+    pub fn fund(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::token(&env)?.transfer(&from, &env.current_contract_address(), &amount);
+        env.events()
+            .publish((symbol_short!("Funded"), from), amount);
+        Ok(())
+    }
+
+    /// Withdraw idle USDC liquidity (admin only)
+    pub fn withdraw(env: Env, to: Address, amount: i128) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let token = Self::token(&env)?;
+        if token.balance(&env.current_contract_address()) < amount {
+            return Err(Error::InsufficientLiquidity);
+        }
+        token.transfer(&env.current_contract_address(), &to, &amount);
+        Ok(())
+    }
+
+    /// Get the credit line of a buyer
+    ///
+    /// Zero for buyers without a compliance attestation or with a
+    /// delinquent loan; otherwise the base limit plus a bonus per loan
+    /// repaid in full, capped at the policy maximum.
+    pub fn credit_limit(env: Env, buyer: Address) -> Result<i128, Error> {
+        let policy = Self::get_policy(env.clone())?;
+        let account = Self::get_account(env.clone(), buyer.clone());
+        if account.delinquent_loans > 0 || !Self::is_attested(&env, &buyer)? {
+            return Ok(0);
+        }
+        let limit = policy.base_limit.saturating_add(
+            policy
+                .repaid_bonus
+                .saturating_mul(account.loans_repaid as i128),
+        );
+        Ok(limit.min(policy.max_limit))
+    }
+
+    /// Get the credit a buyer can still draw
+    pub fn available_credit(env: Env, buyer: Address) -> Result<i128, Error> {
+        let limit = Self::credit_limit(env.clone(), buyer.clone())?;
+        let outstanding = Self::get_account(env, buyer).outstanding;
+        Ok((limit - outstanding).max(0))
+    }
+
+    /// Buy a dataset now and repay the pool later
+    ///
+    /// Flow:
+    /// 1. Check the buyer is attested, not suspended and within its credit
+    /// 2. Pay the dataset price to the marketplace as the payer of record
+    /// 3. Open a loan for the price plus flat interest, due after the term
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - Address of the researcher purchasing (must authorize)
+    /// * `dataset_id` - ID of the dataset to purchase
+    ///
+    /// # Returns
+    /// * `Ok(Loan)` with the new loan
+    /// * `Err(Error)` if the buyer gets no credit or the pool lacks liquidity
+    pub fn buy_now_pay_later(env: Env, buyer: Address, dataset_id: Bytes) -> Result<Loan, Error> {
+        buyer.require_auth();
+        let storage = env.storage().instance();
+        let marketplace: Address = storage.get(&MARKETPLACE_KEY).ok_or(Error::NotInitialized)?;
+        let policy = Self::get_policy(env.clone())?;
+
+        // ============================================
+        // 1. CHECK CREDIT
+        // ============================================
+        let mut account = Self::get_account(env.clone(), buyer.clone());
+        if account.delinquent_loans > 0 {
+            return Err(Error::AccountSuspended);
+        }
+        if !Self::is_attested(&env, &buyer)? {
+            return Err(Error::NotAttested);
+        }
+
+        let dataset = match env.try_invoke_contract::<Dataset, soroban_sdk::Error>(
+            &marketplace,
+            &Symbol::new(&env, "get_dataset"),
+            (dataset_id.clone(),).into_val(&env),
+        ) {
+            Ok(Ok(dataset)) => dataset,
+            _ => return Err(Error::DatasetNotFound),
+        };
+        let principal = dataset.price_usdc;
+        let interest = pricing::mul_bps(principal, policy.interest_bps, pricing::Rounding::Up)
+            .map_err(|_| Error::InvalidAmount)?;
+        let owed = principal + interest;
+
+        if account.outstanding + owed > Self::credit_limit(env.clone(), buyer.clone())? {
+            return Err(Error::CreditLimitExceeded);
+        }
+
+        // ============================================
+        // 2. PAY THE MARKETPLACE
+        // ============================================
+        let token = Self::token(&env)?;
+        let pool = env.current_contract_address();
+        if token.balance(&pool) < principal {
+            return Err(Error::InsufficientLiquidity);
+        }
+        token.approve(&pool, &marketplace, &principal, &env.ledger().sequence());
+        let _: Dataset = env.invoke_contract(
+            &marketplace,
+            &Symbol::new(&env, "purchase_dataset_for"),
+            (dataset_id.clone(), buyer.clone(), pool).into_val(&env),
+        );
+
+        // ============================================
+        // 3. OPEN LOAN
+        // ============================================
+        let loan_id: u64 = storage.get(&LOAN_COUNT_KEY).unwrap_or(0);
+        storage.set(&LOAN_COUNT_KEY, &(loan_id + 1));
+        let loan = Loan {
+            loan_id,
+            buyer: buyer.clone(),
+            dataset_id,
+            principal,
+            interest,
+            repaid: 0,
+            due_at: env.ledger().timestamp() + policy.term_seconds,
+            status: LoanStatus::Active,
+        };
+        Self::save_loan(&env, &loan);
+
+        account.outstanding += owed;
+        account.active_loans += 1;
+        Self::save_account(&env, &buyer, &account);
+
+        env.events()
+            .publish((symbol_short!("LoanOpen"), buyer), (loan_id, owed));
+        Ok(loan)
+    }
+
+    /// Repay part or all of a loan
+    ///
+    /// Repaying a delinquent loan in full lifts the suspension once the
+    /// buyer has no other delinquent loan.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `loan_id` - Loan to repay
+    /// * `from` - Address paying (must authorize)
+    /// * `amount` - USDC to repay (at most the amount still owed)
+    ///
+    /// # Returns
+    /// * `Ok(Loan)` with the updated loan
+    /// * `Err(Error)` if the loan is closed or the amount is invalid
+    pub fn repay(env: Env, loan_id: u64, from: Address, amount: i128) -> Result<Loan, Error> {
+        from.require_auth();
+        let mut loan = Self::get_loan(env.clone(), loan_id)?;
+        if loan.status == LoanStatus::Repaid {
+            return Err(Error::LoanClosed);
+        }
+        let remaining = loan.principal + loan.interest - loan.repaid;
+        if amount <= 0 || amount > remaining {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::token(&env)?.transfer(&from, &env.current_contract_address(), &amount);
+
+        let mut account = Self::get_account(env.clone(), loan.buyer.clone());
+        loan.repaid += amount;
+        account.outstanding -= amount;
+        if amount == remaining {
+            if loan.status == LoanStatus::Delinquent {
+                account.delinquent_loans -= 1;
+            }
+            loan.status = LoanStatus::Repaid;
+            account.active_loans -= 1;
+            account.loans_repaid += 1;
+        }
+        Self::save_loan(&env, &loan);
+        Self::save_account(&env, &loan.buyer, &account);
+
+        env.events().publish(
+            (symbol_short!("Repaid"), loan.buyer.clone()),
+            (loan_id, amount),
+        );
+        Ok(loan)
+    }
+
+    /// Mark an overdue loan as delinquent
+    ///
+    /// Permissionless once the due date plus the grace period has passed.
+    /// The buyer's credit is frozen and `is_suspended` reports `true` (the
+    /// AccessGateway denies access) until the loan is repaid.
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotDue)` if the grace period has not passed
+    pub fn mark_delinquent(env: Env, loan_id: u64) -> Result<(), Error> {
+        let mut loan = Self::get_loan(env.clone(), loan_id)?;
+        if loan.status != LoanStatus::Active {
+            return Err(Error::LoanClosed);
+        }
+        let policy = Self::get_policy(env.clone())?;
+        if env.ledger().timestamp() <= loan.due_at + policy.grace_seconds {
+            return Err(Error::NotDue);
+        }
+
+        loan.status = LoanStatus::Delinquent;
+        Self::save_loan(&env, &loan);
+        let mut account = Self::get_account(env.clone(), loan.buyer.clone());
+        account.delinquent_loans += 1;
+        Self::save_account(&env, &loan.buyer, &account);
+
+        env.events()
+            .publish((symbol_short!("Delinqnt"), loan.buyer), loan_id);
+        Ok(())
+    }
+
+    /// Check whether a buyer's access is suspended for delinquency
+    pub fn is_suspended(env: Env, buyer: Address) -> bool {
+        Self::get_account(env, buyer).delinquent_loans > 0
+    }
+
+    /// Get a loan by ID
+    pub fn get_loan(env: Env, loan_id: u64) -> Result<Loan, Error> {
+        env.storage()
+            .persistent()
+            .get(&(LOAN_KEY, loan_id))
+            .ok_or(Error::LoanNotFound)
+    }
+
+    /// Get the credit account of a buyer (empty if it never borrowed)
+    pub fn get_account(env: Env, buyer: Address) -> CreditAccount {
+        env.storage()
+            .persistent()
+            .get(&(ACCOUNT_KEY, buyer))
+            .unwrap_or_default()
+    }
+
+    fn save_loan(env: &Env, loan: &Loan) {
+        let persistent = env.storage().persistent();
+        let key = (LOAN_KEY, loan.loan_id);
+        persistent.set(&key, loan);
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn save_account(env: &Env, buyer: &Address, account: &CreditAccount) {
+        let persistent = env.storage().persistent();
+        let key = (ACCOUNT_KEY, buyer.clone());
+        persistent.set(&key, account);
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn is_attested(env: &Env, buyer: &Address) -> Result<bool, Error> {
+        let compliance: Address = env
+            .storage()
+            .instance()
+            .get(&COMPLIANCE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(env.invoke_contract(
+            &compliance,
+            &Symbol::new(env, "is_attested"),
+            (buyer.clone(),).into_val(env),
+        ))
+    }
+
+    fn token(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+
+    fn validate_policy(policy: &CreditPolicy) -> Result<(), Error> {
+        if policy.base_limit < 0
+            || policy.repaid_bonus < 0
+            || policy.max_limit < policy.base_limit
+            || policy.interest_bps > pricing::BPS_DENOMINATOR
+            || policy.term_seconds == 0
+        {
+            return Err(Error::InvalidPolicy);
+        }
+        Ok(())
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
@@ -0,0 +1,262 @@
+#![cfg(test)]
+
+use access_gateway::{AccessGateway, AccessGatewayClient, AccessStatus};
+use lending_pool::{CreditPolicy, Error, LendingPool, LendingPoolClient, LoanStatus};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Interest charged on PRICE at the test policy's 5%
+const INTEREST: i128 = PRICE * 5 / 100;
+
+/// Loan term and grace period of the test policy
+const TERM: u64 = 30 * 24 * 3_600;
+const GRACE: u64 = 7 * 24 * 3_600;
+
+/// Minimal compliance stand-in
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn set_attested(env: Env, subject: Address, attested: bool) {
+        env.storage().instance().set(&subject, &attested);
+    }
+
+    pub fn is_attested(env: Env, subject: Address) -> bool {
+        env.storage().instance().get(&subject).unwrap_or(false)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    pool: LendingPoolClient<'a>,
+    compliance: MockComplianceClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    gateway: AccessGatewayClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+    buyer: Address,
+}
+
+/// Helper: Deploy the core contracts with two datasets, a funded
+/// LendingPool and an AccessGateway consulting it
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    for dataset_id in [b"dataset_001", b"dataset_002"] {
+        marketplace.register_dataset(
+            &Address::generate(&env),
+            &Bytes::from_slice(&env, dataset_id),
+            &vec![&env, Bytes::from_array(&env, &[1u8; 32])],
+            &PRICE,
+            &None,
+        );
+    }
+
+    let compliance = MockComplianceClient::new(&env, &env.register_contract(None, MockCompliance));
+    let pool = LendingPoolClient::new(&env, &env.register_contract(None, LendingPool));
+    pool.init(
+        &Address::generate(&env),
+        &marketplace.address,
+        &usdc_id,
+        &compliance.address,
+        &CreditPolicy {
+            base_limit: PRICE + PRICE / 2,
+            repaid_bonus: PRICE,
+            max_limit: 5 * PRICE,
+            interest_bps: 500,
+            term_seconds: TERM,
+            grace_seconds: GRACE,
+        },
+    );
+    let usdc = token::StellarAssetClient::new(&env, &usdc_id);
+    let lender = Address::generate(&env);
+    usdc.mint(&lender, &(100 * PRICE));
+    pool.fund(&lender, &(100 * PRICE));
+
+    let gateway = AccessGatewayClient::new(&env, &env.register_contract(None, AccessGateway));
+    gateway.init(
+        &Address::generate(&env),
+        &marketplace.address,
+        &registry.address,
+    );
+    gateway.set_lending_pool(&pool.address);
+
+    let buyer = Address::generate(&env);
+    compliance.set_attested(&buyer, &true);
+
+    Setup {
+        env,
+        pool,
+        compliance,
+        marketplace,
+        gateway,
+        usdc,
+        buyer,
+    }
+}
+
+fn dataset(s: &Setup, id: &[u8]) -> Bytes {
+    Bytes::from_slice(&s.env, id)
+}
+
+#[test]
+fn test_pool_fronts_purchase_and_opens_loan() {
+    let s = setup();
+    let dataset_id = dataset(&s, b"dataset_001");
+
+    // Act
+    let loan = s.pool.buy_now_pay_later(&s.buyer, &dataset_id);
+
+    // Assert: the buyer holds the license, the pool paid the price
+    assert_eq!(
+        s.marketplace.get_purchase(&dataset_id, &s.buyer).buyer,
+        s.buyer
+    );
+    let usdc = token::Client::new(&s.env, &s.usdc.address);
+    assert_eq!(usdc.balance(&s.pool.address), 99 * PRICE);
+    assert_eq!((loan.principal, loan.interest), (PRICE, INTEREST));
+    assert_eq!(loan.due_at, s.env.ledger().timestamp() + TERM);
+    assert_eq!(s.pool.get_account(&s.buyer).outstanding, PRICE + INTEREST);
+    assert_eq!(s.pool.available_credit(&s.buyer), PRICE / 2 - INTEREST);
+}
+
+#[test]
+fn test_credit_depends_on_attestation_and_history() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+
+    // Act & Assert: unattested buyers get no credit
+    assert_eq!(s.pool.credit_limit(&outsider), 0);
+    assert_eq!(
+        s.pool
+            .try_buy_now_pay_later(&outsider, &dataset(&s, b"dataset_001")),
+        Err(Ok(Error::NotAttested))
+    );
+
+    // A second loan exceeds the base limit
+    let loan = s
+        .pool
+        .buy_now_pay_later(&s.buyer, &dataset(&s, b"dataset_001"));
+    assert_eq!(
+        s.pool
+            .try_buy_now_pay_later(&s.buyer, &dataset(&s, b"dataset_002")),
+        Err(Ok(Error::CreditLimitExceeded))
+    );
+
+    // Repaying in full raises the limit by the history bonus
+    s.usdc.mint(&s.buyer, &(PRICE + INTEREST));
+    s.pool.repay(&loan.loan_id, &s.buyer, &(PRICE + INTEREST));
+    assert_eq!(s.pool.credit_limit(&s.buyer), 2 * PRICE + PRICE / 2);
+    s.pool
+        .buy_now_pay_later(&s.buyer, &dataset(&s, b"dataset_002"));
+
+    // Losing the attestation freezes new credit
+    s.compliance.set_attested(&s.buyer, &false);
+    assert_eq!(s.pool.available_credit(&s.buyer), 0);
+}
+
+#[test]
+fn test_delinquency_suspends_access_until_repaid() {
+    let s = setup();
+    let dataset_id = dataset(&s, b"dataset_001");
+    let loan = s.pool.buy_now_pay_later(&s.buyer, &dataset_id);
+    assert!(s.gateway.check_access(&s.buyer, &dataset_id).granted);
+
+    // Act & Assert: not delinquent before the grace period ends
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = loan.due_at + GRACE);
+    assert_eq!(
+        s.pool.try_mark_delinquent(&loan.loan_id),
+        Err(Ok(Error::NotDue))
+    );
+
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = loan.due_at + GRACE + 1);
+    s.pool.mark_delinquent(&loan.loan_id);
+    assert!(s.pool.is_suspended(&s.buyer));
+    assert_eq!(
+        s.gateway.check_access(&s.buyer, &dataset_id).status,
+        AccessStatus::Suspended
+    );
+    assert_eq!(
+        s.pool
+            .try_buy_now_pay_later(&s.buyer, &dataset(&s, b"dataset_002")),
+        Err(Ok(Error::AccountSuspended))
+    );
+
+    // Repaying the delinquent loan restores access
+    s.usdc.mint(&s.buyer, &(PRICE + INTEREST));
+    s.pool.repay(&loan.loan_id, &s.buyer, &INTEREST);
+    assert!(s.pool.is_suspended(&s.buyer));
+    let repaid = s.pool.repay(&loan.loan_id, &s.buyer, &PRICE);
+    assert_eq!(repaid.status, LoanStatus::Repaid);
+    assert!(!s.pool.is_suspended(&s.buyer));
+    assert!(s.gateway.check_access(&s.buyer, &dataset_id).granted);
+}
+
+#[test]
+fn test_repay_rejects_overpayment_and_closed_loans() {
+    let s = setup();
+    let loan = s
+        .pool
+        .buy_now_pay_later(&s.buyer, &dataset(&s, b"dataset_001"));
+    s.usdc.mint(&s.buyer, &(2 * PRICE));
+
+    // Act & Assert
+    assert_eq!(
+        s.pool
+            .try_repay(&loan.loan_id, &s.buyer, &(PRICE + INTEREST + 1)),
+        Err(Ok(Error::InvalidAmount))
+    );
+    s.pool.repay(&loan.loan_id, &s.buyer, &(PRICE + INTEREST));
+    assert_eq!(
+        s.pool.try_repay(&loan.loan_id, &s.buyer, &1),
+        Err(Ok(Error::LoanClosed))
+    );
+    assert_eq!(
+        s.pool.try_mark_delinquent(&loan.loan_id),
+        Err(Ok(Error::LoanClosed))
+    );
+    assert_eq!(s.pool.get_account(&s.buyer).outstanding, 0);
+}
@@ -0,0 +1,178 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    String, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const LICENSE_KEY: Symbol = symbol_short!("LICENSE");
+const LATEST_KEY: Symbol = symbol_short!("LATEST");
+const LICENSES_KEY: Symbol = symbol_short!("LIC_LIST");
+
+/// TTL policy for license documents, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// LicenseTerms structure
+///
+/// One published version of a license document. Versions are never
+/// changed once published; updating a license publishes a new version.
+/// - license_id: License the document belongs to (e.g. "research")
+/// - version: Version number, starting at 1
+/// - document_hash: SHA-256 of the exact license text
+/// - uri: Where the license text can be retrieved
+/// - published_at: Ledger timestamp of publication
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicenseTerms {
+    pub license_id: Symbol,
+    pub version: u32,
+    pub document_hash: BytesN<32>,
+    pub uri: String,
+    pub published_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    LicenseNotFound = 3,
+    DocumentUnchanged = 4,
+}
+
+/// Versioned registry of license documents bound to dataset sales
+///
+/// DatasetMarketplace listings reference a (license_id, version) pair and
+/// buyers acknowledge that version's document hash before purchasing, so
+/// each sale is bound to the exact license text.
+#[contract]
+pub struct LicenseRegistry;
+
+#[contractimpl]
+impl LicenseRegistry {
+    /// Initialize the LicenseRegistry contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to publish license documents
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Publish a new version of a license document
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `license_id` - License to publish a version of (created if new)
+    /// * `document_hash` - SHA-256 of the license text
+    /// * `uri` - Where the license text can be retrieved
+    ///
+    /// # Returns
+    /// * `Ok(u32)` the new version number
+    /// * `Err(Error::DocumentUnchanged)` if the hash equals the latest version's
+    pub fn publish_license(
+        env: Env,
+        license_id: Symbol,
+        document_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+
+        let latest = Self::get_latest_version(env.clone(), license_id.clone());
+        if latest == 0 {
+            let mut licenses = Self::get_licenses(env.clone());
+            licenses.push_back(license_id.clone());
+            env.storage().instance().set(&LICENSES_KEY, &licenses);
+        } else if Self::get_license(env.clone(), license_id.clone(), latest)?.document_hash
+            == document_hash
+        {
+            return Err(Error::DocumentUnchanged);
+        }
+
+        let version = latest + 1;
+        let terms = LicenseTerms {
+            license_id: license_id.clone(),
+            version,
+            document_hash: document_hash.clone(),
+            uri,
+            published_at: env.ledger().timestamp(),
+        };
+        let key = (LICENSE_KEY, license_id.clone(), version);
+        let storage = env.storage().persistent();
+        storage.set(&key, &terms);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        env.storage()
+            .instance()
+            .set(&(LATEST_KEY, license_id.clone()), &version);
+
+        env.events().publish(
+            (symbol_short!("LicPub"), license_id),
+            (version, document_hash),
+        );
+        Ok(version)
+    }
+
+    /// Get one version of a license document
+    ///
+    /// Read by DatasetMarketplace when a listing is bound to a license.
+    ///
+    /// # Returns
+    /// * `Ok(LicenseTerms)` if found
+    /// * `Err(Error::LicenseNotFound)` if the license or version is unknown
+    pub fn get_license(env: Env, license_id: Symbol, version: u32) -> Result<LicenseTerms, Error> {
+        env.storage()
+            .persistent()
+            .get(&(LICENSE_KEY, license_id, version))
+            .ok_or(Error::LicenseNotFound)
+    }
+
+    /// Get the latest version of a license document
+    ///
+    /// # Returns
+    /// * `Ok(LicenseTerms)` if the license has been published
+    /// * `Err(Error::LicenseNotFound)` otherwise
+    pub fn get_latest_license(env: Env, license_id: Symbol) -> Result<LicenseTerms, Error> {
+        let version = Self::get_latest_version(env.clone(), license_id.clone());
+        Self::get_license(env, license_id, version)
+    }
+
+    /// Get the latest version number of a license (0 if never published)
+    pub fn get_latest_version(env: Env, license_id: Symbol) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(LATEST_KEY, license_id))
+            .unwrap_or(0)
+    }
+
+    /// Get all license IDs in order of first publication
+    pub fn get_licenses(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&LICENSES_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
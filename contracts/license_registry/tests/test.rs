@@ -0,0 +1,359 @@
+#![cfg(test)]
+
+use dataset_marketplace::{DatasetMarketplaceClient, Error as MarketplaceError};
+use license_registry::{Error, LicenseRegistry, LicenseRegistryClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as AddressTestUtils, AuthorizedFunction, MockAuth, MockAuthInvoke},
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+struct Setup<'a> {
+    env: Env,
+    licenses: LicenseRegistryClient<'a>,
+    marketplace: DatasetMarketplaceClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+}
+
+/// Helper: Deploy the core contracts, a LicenseRegistry and one listed
+/// dataset ("dataset_001")
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let licenses = LicenseRegistryClient::new(&env, &env.register_contract(None, LicenseRegistry));
+    licenses.init(&Address::generate(&env));
+    marketplace.set_license_registry(&licenses.address);
+
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &vec![&env, Bytes::from_array(&env, &[1u8; 32])],
+        &PRICE,
+        &None,
+    );
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        licenses,
+        marketplace,
+    }
+}
+
+/// Helper: Publish a license version whose document hash is `[seed; 32]`
+fn publish(s: &Setup, seed: u8) -> u32 {
+    s.licenses.publish_license(
+        &symbol_short!("research"),
+        &BytesN::from_array(&s.env, &[seed; 32]),
+        &String::from_str(&s.env, "ipfs://license"),
+    )
+}
+
+/// Helper: Fund a fresh buyer for one purchase
+fn funded_buyer(s: &Setup) -> Address {
+    let buyer = Address::generate(&s.env);
+    s.usdc.mint(&buyer, &PRICE);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        &buyer,
+        &s.marketplace.address,
+        &PRICE,
+        &1_000,
+    );
+    buyer
+}
+
+#[test]
+fn test_publishing_creates_new_versions() {
+    let s = setup();
+    let license_id = symbol_short!("research");
+
+    // Act
+    let first = publish(&s, 1);
+    let second = publish(&s, 2);
+
+    // Assert: earlier versions keep their document
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(s.licenses.get_latest_version(&license_id), 2);
+    assert_eq!(
+        s.licenses.get_license(&license_id, &1).document_hash,
+        BytesN::from_array(&s.env, &[1u8; 32])
+    );
+    assert_eq!(
+        s.licenses.get_latest_license(&license_id).document_hash,
+        BytesN::from_array(&s.env, &[2u8; 32])
+    );
+    assert_eq!(s.licenses.get_licenses(), vec![&s.env, license_id]);
+}
+
+#[test]
+fn test_publish_rejects_unchanged_document_and_unknown_versions() {
+    let s = setup();
+    let license_id = symbol_short!("research");
+    publish(&s, 1);
+
+    // Act & Assert
+    assert_eq!(
+        s.licenses.try_publish_license(
+            &license_id,
+            &BytesN::from_array(&s.env, &[1u8; 32]),
+            &String::from_str(&s.env, "ipfs://license"),
+        ),
+        Err(Ok(Error::DocumentUnchanged))
+    );
+    assert_eq!(
+        s.licenses.try_get_license(&license_id, &2),
+        Err(Ok(Error::LicenseNotFound))
+    );
+    assert_eq!(
+        s.licenses.try_get_latest_license(&symbol_short!("other")),
+        Err(Ok(Error::LicenseNotFound))
+    );
+}
+
+#[test]
+fn test_purchase_records_acknowledged_license() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let version = publish(&s, 1);
+    s.marketplace
+        .set_dataset_license(&dataset_id, &symbol_short!("research"), &version);
+    let document_hash = BytesN::from_array(&s.env, &[1u8; 32]);
+
+    // Act
+    let buyer = funded_buyer(&s);
+    s.marketplace
+        .acknowledge_license(&dataset_id, &buyer, &document_hash);
+    s.marketplace.purchase_dataset(&dataset_id, &buyer, &None);
+
+    // Assert: the buyer co-signed the agreement in the purchase's auth payload
+    let cosigned = s.env.auths().into_iter().any(|(address, invocation)| {
+        address == buyer
+            && invocation.function
+                == AuthorizedFunction::Contract((
+                    s.marketplace.address.clone(),
+                    Symbol::new(&s.env, "purchase_dataset"),
+                    (dataset_id.clone(), document_hash.clone()).into_val(&s.env),
+                ))
+    });
+    assert!(cosigned);
+    assert_eq!(
+        s.marketplace.get_purchase(&dataset_id, &buyer).license_hash,
+        Some(document_hash.clone().into())
+    );
+    assert_eq!(
+        s.marketplace.get_signed_agreement(&dataset_id, &buyer),
+        Some(document_hash)
+    );
+    assert_eq!(
+        s.marketplace
+            .try_get_signed_agreement(&dataset_id, &Address::generate(&s.env)),
+        Err(Ok(MarketplaceError::DatasetNotFound))
+    );
+}
+
+#[test]
+fn test_purchase_requires_current_license_acknowledgement() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let license_id = symbol_short!("research");
+    s.marketplace
+        .set_dataset_license(&dataset_id, &license_id, &publish(&s, 1));
+    let buyer = funded_buyer(&s);
+
+    // Without acknowledgement
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&dataset_id, &buyer, &None),
+        Err(Ok(MarketplaceError::LicenseNotAcknowledged))
+    );
+    assert!(
+        !s.marketplace
+            .simulate_purchase(&dataset_id, &buyer)
+            .can_purchase
+    );
+
+    // Acknowledging a different document is rejected
+    assert_eq!(
+        s.marketplace.try_acknowledge_license(
+            &dataset_id,
+            &buyer,
+            &BytesN::from_array(&s.env, &[9u8; 32]),
+        ),
+        Err(Ok(MarketplaceError::LicenseNotAcknowledged))
+    );
+
+    // An acknowledgement of version 1 does not cover version 2
+    s.marketplace
+        .acknowledge_license(&dataset_id, &buyer, &BytesN::from_array(&s.env, &[1u8; 32]));
+    s.marketplace
+        .set_dataset_license(&dataset_id, &license_id, &publish(&s, 2));
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&dataset_id, &buyer, &None),
+        Err(Ok(MarketplaceError::LicenseNotAcknowledged))
+    );
+}
+
+#[test]
+fn test_binding_requires_published_version() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Act & Assert
+    assert_eq!(
+        s.marketplace
+            .try_set_dataset_license(&dataset_id, &symbol_short!("research"), &1),
+        Err(Ok(MarketplaceError::LicenseNotFound))
+    );
+    assert_eq!(s.marketplace.get_dataset_license(&dataset_id), None);
+
+    // Datasets without a license sell as before
+    let buyer = funded_buyer(&s);
+    s.marketplace.purchase_dataset(&dataset_id, &buyer, &None);
+    assert_eq!(
+        s.marketplace.get_purchase(&dataset_id, &buyer).license_hash,
+        None
+    );
+    assert_eq!(s.marketplace.get_signed_agreement(&dataset_id, &buyer), None);
+}
+
+#[test]
+fn test_only_the_owner_binds_a_license() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let license_id = symbol_short!("research");
+    s.marketplace
+        .set_dataset_license(&dataset_id, &license_id, &publish(&s, 1));
+    assert_eq!(
+        s.env.auths()[0].0,
+        s.marketplace.get_dataset(&dataset_id).owner
+    );
+    let version = publish(&s, 2);
+    let invoke = MockAuthInvoke {
+        contract: &s.marketplace.address,
+        fn_name: "set_dataset_license",
+        args: (dataset_id.clone(), license_id.clone(), version).into_val(&s.env),
+        sub_invokes: &[],
+    };
+
+    // Act & Assert: a stranger cannot rebind the listing's license
+    s.env.mock_auths(&[MockAuth {
+        address: &Address::generate(&s.env),
+        invoke: &invoke,
+    }]);
+    assert!(s
+        .marketplace
+        .try_set_dataset_license(&dataset_id, &license_id, &version)
+        .is_err());
+    assert_eq!(
+        s.marketplace.get_dataset_license(&dataset_id).unwrap().version,
+        1
+    );
+}
+
+#[test]
+fn test_purchase_with_owner_terms_records_the_accepted_hash() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let terms_v1 = BytesN::from_array(&s.env, &[3u8; 32]);
+    let terms_v2 = BytesN::from_array(&s.env, &[4u8; 32]);
+
+    // Act: the owner publishes their own terms, without the registry
+    let license = s.marketplace.set_license_terms(&dataset_id, &terms_v1);
+    assert_eq!(
+        s.env.auths()[0].0,
+        s.marketplace.get_dataset(&dataset_id).owner
+    );
+    assert_eq!(
+        (license.license_id, license.version),
+        (dataset_marketplace::OWNER_TERMS_LICENSE, 1)
+    );
+    let buyer = funded_buyer(&s);
+    assert_eq!(
+        s.marketplace.try_purchase_with_terms(&dataset_id, &buyer, &None, &terms_v2),
+        Err(Ok(MarketplaceError::LicenseNotAcknowledged))
+    );
+    s.marketplace
+        .purchase_with_terms(&dataset_id, &buyer, &None, &terms_v1);
+
+    // Assert: the accepted hash is the purchase's evidence
+    assert_eq!(
+        s.marketplace.get_purchase(&dataset_id, &buyer).license_hash,
+        Some(terms_v1.clone().into())
+    );
+    assert_eq!(
+        s.marketplace.get_license_ack(&dataset_id, &buyer),
+        Some(terms_v1.clone())
+    );
+
+    // New terms are a new version buyers must accept again; resetting
+    // the current hash changes nothing
+    assert_eq!(
+        s.marketplace.set_license_terms(&dataset_id, &terms_v2).version,
+        2
+    );
+    assert_eq!(
+        s.marketplace.set_license_terms(&dataset_id, &terms_v2).version,
+        2
+    );
+    assert_eq!(
+        s.marketplace.try_purchase_with_terms(&dataset_id, &funded_buyer(&s), &None, &terms_v1),
+        Err(Ok(MarketplaceError::LicenseNotAcknowledged))
+    );
+}
+
+#[test]
+fn test_owner_terms_do_not_replace_a_registry_license() {
+    let s = setup();
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    s.marketplace
+        .set_dataset_license(&dataset_id, &symbol_short!("research"), &publish(&s, 1));
+
+    // Act & Assert
+    assert_eq!(
+        s.marketplace
+            .try_set_license_terms(&dataset_id, &BytesN::from_array(&s.env, &[3u8; 32])),
+        Err(Ok(dataset_marketplace::ExtError::RegistryLicenseBound.into()))
+    );
+    assert_eq!(
+        s.marketplace
+            .get_dataset_license(&dataset_id)
+            .unwrap()
+            .license_id,
+        symbol_short!("research")
+    );
+}
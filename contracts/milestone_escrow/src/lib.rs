@@ -0,0 +1,448 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const REVENUE_SPLITTER_KEY: Symbol = symbol_short!("REV_SPLIT");
+const STUDY_REGISTRY_KEY: Symbol = symbol_short!("STUDY_REG");
+const PROGRAM_COUNT_KEY: Symbol = symbol_short!("PROG_CNT");
+const PROGRAM_KEY: Symbol = symbol_short!("PROGRAM");
+const SUBMISSION_KEY: Symbol = symbol_short!("SUBMIT");
+
+/// TTL policy for programs and submissions, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum number of studies enrolled in a single program
+pub const MAX_STUDIES: u32 = 200;
+
+/// Maximum number of milestones in a single program
+pub const MAX_MILESTONES: u32 = 12;
+
+/// Milestone lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+    Missed,
+}
+
+/// One stage of a program
+///
+/// - target_studies: Studies that must be enrolled in the program (in
+///   total, counting earlier stages) to reach the milestone
+/// - amount: Tranche released when the milestone is reached
+/// - deadline: Ledger timestamp by which the target must be reached
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub target_studies: u32,
+    pub amount: i128,
+    pub deadline: u64,
+    pub status: MilestoneStatus,
+}
+
+/// Milestone-based payment schedule for a prospective study
+///
+/// A sponsor's escrowed budget for data that is still being collected:
+/// - spec_hash: Hash of the off-chain study protocol
+/// - budget: USDC escrowed in this contract (sum of the tranches)
+/// - milestones: Stages, settled strictly in order
+/// - next_milestone: Index of the first milestone not yet settled
+/// - studies: Hashes of the enrolled studies, in enrollment order
+/// - paid_studies: Studies whose contributors were paid by a tranche
+/// - released / refunded: Tranches paid out and returned to the sponsor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Program {
+    pub program_id: u64,
+    pub sponsor: Address,
+    pub spec_hash: BytesN<32>,
+    pub budget: i128,
+    pub milestones: Vec<Milestone>,
+    pub next_milestone: u32,
+    pub studies: Vec<BytesN<32>>,
+    pub paid_studies: u32,
+    pub released: i128,
+    pub refunded: i128,
+}
+
+/// StudyRecord as returned by StudyRegistry.get_study()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyRecord {
+    pub dataset_hash: BytesN<32>,
+    pub contributor: Address,
+    pub timestamp: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidMilestones = 4,
+    ProgramNotFound = 5,
+    ProgramClosed = 6,
+    NotStudyContributor = 7,
+    DuplicateStudy = 8,
+    TooManyStudies = 9,
+    MilestonePending = 10,
+}
+
+/// Staged payments for sponsor-commissioned data collection
+///
+/// The sponsor escrows the full budget up front. Contributors enroll
+/// studies they registered in the StudyRegistry, and each milestone
+/// reached releases its tranche through the RevenueSplitter to the
+/// contributors of the studies enrolled since the last tranche. A
+/// milestone missed by its deadline refunds its tranche to the sponsor;
+/// its studies are then paid by the next milestone reached.
+#[contract]
+pub struct MilestoneEscrow;
+
+#[contractimpl]
+impl MilestoneEscrow {
+    /// Initialize the MilestoneEscrow contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Deployment admin
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// * `study_registry` - Address of the StudyRegistry contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        revenue_splitter: Address,
+        study_registry: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&REVENUE_SPLITTER_KEY, &revenue_splitter);
+        storage.set(&STUDY_REGISTRY_KEY, &study_registry);
+        storage.set(&PROGRAM_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Create a program and escrow its full budget
+    ///
+    /// Milestone targets must strictly increase and deadlines must be in
+    /// the future and not decrease. The budget is the sum of the tranches.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `sponsor` - Sponsor commissioning the study (must authorize)
+    /// * `spec_hash` - Hash of the off-chain study protocol
+    /// * `milestones` - Stages of the program (status is ignored)
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the program ID
+    /// * `Err(Error)` if validation fails
+    pub fn create_program(
+        env: Env,
+        sponsor: Address,
+        spec_hash: BytesN<32>,
+        milestones: Vec<Milestone>,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE MILESTONES
+        // ============================================
+        sponsor.require_auth();
+        if milestones.is_empty() || milestones.len() > MAX_MILESTONES {
+            return Err(Error::InvalidMilestones);
+        }
+
+        let mut budget: i128 = 0;
+        let mut last_target = 0;
+        let mut last_deadline = env.ledger().timestamp();
+        let mut pending = Vec::new(&env);
+        for milestone in milestones.iter() {
+            if milestone.target_studies <= last_target
+                || milestone.target_studies > MAX_STUDIES
+                || milestone.deadline <= env.ledger().timestamp()
+                || milestone.deadline < last_deadline
+            {
+                return Err(Error::InvalidMilestones);
+            }
+            if milestone.amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            budget = budget
+                .checked_add(milestone.amount)
+                .ok_or(Error::InvalidAmount)?;
+            last_target = milestone.target_studies;
+            last_deadline = milestone.deadline;
+            pending.push_back(Milestone {
+                status: MilestoneStatus::Pending,
+                ..milestone
+            });
+        }
+
+        // ============================================
+        // 2. ESCROW BUDGET
+        // ============================================
+        Self::token_client(&env)?.transfer(&sponsor, &env.current_contract_address(), &budget);
+
+        // ============================================
+        // 3. STORE PROGRAM
+        // ============================================
+        let storage = env.storage().instance();
+        let program_id: u64 = storage
+            .get(&PROGRAM_COUNT_KEY)
+            .ok_or(Error::NotInitialized)?;
+        storage.set(&PROGRAM_COUNT_KEY, &(program_id + 1));
+
+        let program = Program {
+            program_id,
+            sponsor: sponsor.clone(),
+            spec_hash: spec_hash.clone(),
+            budget,
+            milestones: pending,
+            next_milestone: 0,
+            studies: Vec::new(&env),
+            paid_studies: 0,
+            released: 0,
+            refunded: 0,
+        };
+        Self::save_program(&env, &program);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("ProgNew"), program_id),
+            (sponsor, spec_hash, budget, program.milestones.len()),
+        );
+
+        Ok(program_id)
+    }
+
+    /// Enroll a registered study in a program
+    ///
+    /// The study must already be registered in StudyRegistry by the
+    /// enrolling contributor. Enrollment closes once every milestone is
+    /// settled.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `program_id` - Program to enroll in
+    /// * `contributor` - Study contributor (must authorize)
+    /// * `study_hash` - Hash of the registered study
+    ///
+    /// # Returns
+    /// * `Ok(u32)` with the number of enrolled studies
+    /// * `Err(Error)` if validation fails
+    pub fn enroll_study(
+        env: Env,
+        program_id: u64,
+        contributor: Address,
+        study_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        contributor.require_auth();
+
+        let mut program = Self::load_open_program(&env, program_id)?;
+        if program.studies.len() >= MAX_STUDIES {
+            return Err(Error::TooManyStudies);
+        }
+        let submission_key = (SUBMISSION_KEY, program_id, study_hash.clone());
+        if env.storage().persistent().has(&submission_key) {
+            return Err(Error::DuplicateStudy);
+        }
+
+        // Verify the study is registered to this contributor
+        let study_registry: Address = env
+            .storage()
+            .instance()
+            .get(&STUDY_REGISTRY_KEY)
+            .ok_or(Error::NotInitialized)?;
+        let study: StudyRecord = env.invoke_contract(
+            &study_registry,
+            &symbol_short!("get_study"),
+            (study_hash.clone(),).into_val(&env),
+        );
+        if study.contributor != contributor {
+            return Err(Error::NotStudyContributor);
+        }
+
+        let storage = env.storage().persistent();
+        storage.set(&submission_key, &contributor);
+        storage.extend_ttl(&submission_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        program.studies.push_back(study_hash.clone());
+        Self::save_program(&env, &program);
+
+        env.events().publish(
+            (symbol_short!("ProgEnrol"), program_id),
+            (contributor, study_hash),
+        );
+
+        Ok(program.studies.len())
+    }
+
+    /// Settle the next milestone of a program
+    ///
+    /// Anyone can call this. If the enrollment target is reached, the
+    /// tranche is paid through RevenueSplitter to the contributors of the
+    /// studies enrolled since the last tranche. If the deadline passed
+    /// without reaching it, the tranche is refunded to the sponsor.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `program_id` - Program to settle
+    ///
+    /// # Returns
+    /// * `Ok(MilestoneStatus)` with the settled milestone's new status
+    /// * `Err(Error::MilestonePending)` if the target is not reached yet
+    ///   and the deadline has not passed
+    /// * `Err(Error)` if the program is closed
+    pub fn settle_milestone(env: Env, program_id: u64) -> Result<MilestoneStatus, Error> {
+        let mut program = Self::load_open_program(&env, program_id)?;
+        let index = program.next_milestone;
+        let mut milestone = program
+            .milestones
+            .get(index)
+            .ok_or(Error::ProgramClosed)?;
+        let enrolled = program.studies.len();
+
+        // A tranche needs at least one study it has not paid for, even when
+        // enrollment overshot an earlier target
+        if enrolled >= milestone.target_studies && enrolled > program.paid_studies {
+            // ============================================
+            // REACHED: PAY THROUGH REVENUE SPLITTER
+            // ============================================
+            let mut contributors = Vec::new(&env);
+            for study_hash in program.studies.slice(program.paid_studies..enrolled).iter() {
+                let contributor: Address = env
+                    .storage()
+                    .persistent()
+                    .get(&(SUBMISSION_KEY, program_id, study_hash))
+                    .ok_or(Error::NotStudyContributor)?;
+                contributors.push_back(contributor);
+            }
+
+            let revenue_splitter: Address = env
+                .storage()
+                .instance()
+                .get(&REVENUE_SPLITTER_KEY)
+                .ok_or(Error::NotInitialized)?;
+            // RevenueSplitter pulls the tranche from this contract
+            let contract_address = env.current_contract_address();
+            env.authorize_as_current_contract(soroban_sdk::vec![
+                &env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: Self::token_client(&env)?.address,
+                        fn_name: symbol_short!("transfer"),
+                        args: (contract_address.clone(), revenue_splitter.clone(), milestone.amount)
+                            .into_val(&env),
+                    },
+                    sub_invocations: Vec::new(&env),
+                }),
+            ]);
+            env.invoke_contract::<()>(
+                &revenue_splitter,
+                &Symbol::new(&env, "payout_amount"),
+                (
+                    contract_address,
+                    Self::payout_id(&env, program_id, index),
+                    contributors,
+                    milestone.amount,
+                )
+                    .into_val(&env),
+            );
+
+            milestone.status = MilestoneStatus::Released;
+            program.paid_studies = enrolled;
+            program.released += milestone.amount;
+        } else if env.ledger().timestamp() > milestone.deadline {
+            // ============================================
+            // MISSED: REFUND THE TRANCHE
+            // ============================================
+            Self::token_client(&env)?.transfer(
+                &env.current_contract_address(),
+                &program.sponsor,
+                &milestone.amount,
+            );
+            milestone.status = MilestoneStatus::Missed;
+            program.refunded += milestone.amount;
+        } else {
+            return Err(Error::MilestonePending);
+        }
+
+        program.milestones.set(index, milestone.clone());
+        program.next_milestone = index + 1;
+        Self::save_program(&env, &program);
+
+        env.events().publish(
+            (symbol_short!("ProgMile"), program_id, index),
+            (milestone.status, milestone.amount),
+        );
+
+        Ok(milestone.status)
+    }
+
+    /// Get a program by ID
+    pub fn get_program(env: Env, program_id: u64) -> Result<Program, Error> {
+        env.storage()
+            .persistent()
+            .get(&(PROGRAM_KEY, program_id))
+            .ok_or(Error::ProgramNotFound)
+    }
+
+    /// Get the USDC still escrowed for a program's unsettled milestones
+    pub fn get_escrowed(env: Env, program_id: u64) -> Result<i128, Error> {
+        let program = Self::get_program(env, program_id)?;
+        Ok(program.budget - program.released - program.refunded)
+    }
+
+    fn load_open_program(env: &Env, program_id: u64) -> Result<Program, Error> {
+        let program = Self::get_program(env.clone(), program_id)?;
+        if program.next_milestone >= program.milestones.len() {
+            return Err(Error::ProgramClosed);
+        }
+        Ok(program)
+    }
+
+    fn save_program(env: &Env, program: &Program) {
+        let key = (PROGRAM_KEY, program.program_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, program);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Payout ID used in RevenueSplitter events: "milestone" + big-endian
+    /// program ID + big-endian milestone index
+    fn payout_id(env: &Env, program_id: u64, index: u32) -> Bytes {
+        let mut payout_id = Bytes::from_slice(env, b"milestone");
+        payout_id.append(&Bytes::from_slice(env, &program_id.to_be_bytes()));
+        payout_id.append(&Bytes::from_slice(env, &index.to_be_bytes()));
+        payout_id
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
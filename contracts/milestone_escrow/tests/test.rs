@@ -0,0 +1,215 @@
+#![cfg(test)]
+
+use milestone_escrow::{
+    Error, Milestone, MilestoneEscrow, MilestoneEscrowClient, MilestoneStatus,
+};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, BytesN, Env, Vec,
+};
+
+/// Tranche paid per milestone (100 USDC)
+const TRANCHE: i128 = 100_0000000;
+
+/// Milestone deadlines used by the tests
+const FIRST_DEADLINE: u64 = 1_000;
+const SECOND_DEADLINE: u64 = 2_000;
+
+struct Setup<'a> {
+    env: Env,
+    escrow: MilestoneEscrowClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    usdc: token::Client<'a>,
+    sponsor: Address,
+}
+
+/// Helper: Deploy the registry, splitter and a MilestoneEscrow; fund the sponsor
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+
+    let escrow = MilestoneEscrowClient::new(&env, &env.register_contract(None, MilestoneEscrow));
+    escrow.init(
+        &Address::generate(&env),
+        &usdc_id,
+        &splitter.address,
+        &registry.address,
+    );
+
+    let sponsor = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&sponsor, &(10 * TRANCHE));
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        escrow,
+        registry,
+        sponsor,
+    }
+}
+
+/// Helper: A milestone still to be settled
+fn milestone(target_studies: u32, deadline: u64) -> Milestone {
+    Milestone {
+        target_studies,
+        amount: TRANCHE,
+        deadline,
+        status: MilestoneStatus::Pending,
+    }
+}
+
+/// Helper: Create a two-stage program (2 then 3 studies)
+fn create_program(s: &Setup) -> u64 {
+    s.escrow.create_program(
+        &s.sponsor,
+        &BytesN::from_array(&s.env, &[9u8; 32]),
+        &vec![
+            &s.env,
+            milestone(2, FIRST_DEADLINE),
+            milestone(3, SECOND_DEADLINE),
+        ],
+    )
+}
+
+/// Helper: Register a study with hash `[seed; 32]` and enroll it
+fn enroll(s: &Setup, program_id: u64, seed: u8) -> Address {
+    let contributor = Address::generate(&s.env);
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &contributor);
+    s.registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    s.escrow.enroll_study(&program_id, &contributor, &study_hash);
+    contributor
+}
+
+#[test]
+fn test_reached_milestones_release_tranches_through_splitter() {
+    let s = setup();
+    let program_id = create_program(&s);
+    assert_eq!(s.usdc.balance(&s.escrow.address), 2 * TRANCHE);
+
+    // Act: first stage pays the first two contributors
+    let first: Vec<Address> = vec![&s.env, enroll(&s, program_id, 1), enroll(&s, program_id, 2)];
+    assert_eq!(s.escrow.settle_milestone(&program_id), MilestoneStatus::Released);
+    let third = enroll(&s, program_id, 3);
+    assert_eq!(s.escrow.settle_milestone(&program_id), MilestoneStatus::Released);
+
+    // Assert: 85% of each share goes to the contributor
+    for contributor in first.iter() {
+        assert_eq!(s.usdc.balance(&contributor), TRANCHE / 2 * 85 / 100);
+    }
+    assert_eq!(s.usdc.balance(&third), TRANCHE * 85 / 100);
+    let program = s.escrow.get_program(&program_id);
+    assert_eq!((program.released, program.refunded), (2 * TRANCHE, 0));
+    assert_eq!(s.escrow.get_escrowed(&program_id), 0);
+    assert_eq!(
+        s.escrow.try_settle_milestone(&program_id),
+        Err(Ok(Error::ProgramClosed))
+    );
+}
+
+#[test]
+fn test_missed_milestone_refunds_tranche() {
+    let s = setup();
+    let program_id = create_program(&s);
+    let early = enroll(&s, program_id, 1);
+
+    // Act: the first target is not reached in time
+    assert_eq!(
+        s.escrow.try_settle_milestone(&program_id),
+        Err(Ok(Error::MilestonePending))
+    );
+    s.env.ledger().set_timestamp(FIRST_DEADLINE + 1);
+    assert_eq!(s.escrow.settle_milestone(&program_id), MilestoneStatus::Missed);
+
+    // Assert: the sponsor got the tranche back
+    assert_eq!(s.usdc.balance(&s.sponsor), 9 * TRANCHE);
+
+    // The studies of the missed stage are paid by the next one
+    enroll(&s, program_id, 2);
+    enroll(&s, program_id, 3);
+    assert_eq!(s.escrow.settle_milestone(&program_id), MilestoneStatus::Released);
+    assert_eq!(s.usdc.balance(&early), TRANCHE / 3 * 85 / 100);
+    let program = s.escrow.get_program(&program_id);
+    assert_eq!((program.released, program.refunded), (TRANCHE, TRANCHE));
+    assert_eq!(program.milestones.get(0).unwrap().status, MilestoneStatus::Missed);
+}
+
+#[test]
+fn test_enroll_requires_own_unique_study() {
+    let s = setup();
+    let program_id = create_program(&s);
+    let contributor = enroll(&s, program_id, 1);
+    let study_hash = BytesN::from_array(&s.env, &[1u8; 32]);
+
+    // Act & Assert
+    assert_eq!(
+        s.escrow
+            .try_enroll_study(&program_id, &contributor, &study_hash),
+        Err(Ok(Error::DuplicateStudy))
+    );
+    let other_program = create_program(&s);
+    assert_eq!(
+        s.escrow
+            .try_enroll_study(&other_program, &Address::generate(&s.env), &study_hash),
+        Err(Ok(Error::NotStudyContributor))
+    );
+}
+
+#[test]
+fn test_create_program_validates_milestones() {
+    let s = setup();
+    let spec_hash = BytesN::from_array(&s.env, &[9u8; 32]);
+
+    // Act & Assert: targets must increase
+    assert_eq!(
+        s.escrow.try_create_program(
+            &s.sponsor,
+            &spec_hash,
+            &vec![&s.env, milestone(2, FIRST_DEADLINE), milestone(2, SECOND_DEADLINE)],
+        ),
+        Err(Ok(Error::InvalidMilestones))
+    );
+    // Deadlines must not go backwards
+    assert_eq!(
+        s.escrow.try_create_program(
+            &s.sponsor,
+            &spec_hash,
+            &vec![&s.env, milestone(1, SECOND_DEADLINE), milestone(2, FIRST_DEADLINE)],
+        ),
+        Err(Ok(Error::InvalidMilestones))
+    );
+    assert_eq!(
+        s.escrow
+            .try_create_program(&s.sponsor, &spec_hash, &Vec::new(&s.env)),
+        Err(Ok(Error::InvalidMilestones))
+    );
+    let mut free = milestone(1, FIRST_DEADLINE);
+    free.amount = 0;
+    assert_eq!(
+        s.escrow
+            .try_create_program(&s.sponsor, &spec_hash, &vec![&s.env, free]),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(s.usdc.balance(&s.sponsor), 10 * TRANCHE);
+}
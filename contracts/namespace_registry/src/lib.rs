@@ -0,0 +1,230 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String,
+    Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const NAMESPACE_KEY: Symbol = symbol_short!("NS");
+const NAMESPACES_KEY: Symbol = symbol_short!("NS_LIST");
+const CURATOR_KEY: Symbol = symbol_short!("CURATOR");
+
+/// Basis point denominator
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Maximum fee (basis points) a namespace may take on its sales
+pub const MAX_FEE_BPS: u32 = 5_000;
+
+/// Per-namespace configuration, set by the namespace owner
+///
+/// - name: Display name of the white-label marketplace
+/// - treasury: Address receiving the namespace fee
+/// - fee_bps: Fee taken by the namespace on each sale, before the shared
+///   RevenueSplitter pays out the rest
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamespaceConfig {
+    pub name: String,
+    pub treasury: Address,
+    pub fee_bps: u32,
+}
+
+/// Namespace structure
+///
+/// - namespace_id: Short identifier datasets are scoped by (e.g. "hospnet")
+/// - owner: Address allowed to configure the namespace and its curators
+/// - config: Current configuration
+/// - created_at: Ledger timestamp of creation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Namespace {
+    pub namespace_id: Symbol,
+    pub owner: Address,
+    pub config: NamespaceConfig,
+    pub created_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NamespaceExists = 3,
+    NamespaceNotFound = 4,
+    FeeTooHigh = 5,
+}
+
+#[contract]
+pub struct NamespaceRegistry;
+
+#[contractimpl]
+impl NamespaceRegistry {
+    /// Initialize the NamespaceRegistry contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to create namespaces
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Create a namespace for a white-label marketplace
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Unique identifier of the namespace
+    /// * `owner` - Address allowed to configure the namespace
+    /// * `config` - Initial configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the ID is taken or the fee is too high
+    pub fn create_namespace(
+        env: Env,
+        namespace_id: Symbol,
+        owner: Address,
+        config: NamespaceConfig,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::validate_config(&config)?;
+
+        let storage = env.storage().instance();
+        let key = (NAMESPACE_KEY, namespace_id.clone());
+        if storage.has(&key) {
+            return Err(Error::NamespaceExists);
+        }
+
+        let namespace = Namespace {
+            namespace_id: namespace_id.clone(),
+            owner: owner.clone(),
+            config,
+            created_at: env.ledger().timestamp(),
+        };
+        storage.set(&key, &namespace);
+
+        let mut namespaces: Vec<Symbol> = storage.get(&NAMESPACES_KEY).unwrap_or(Vec::new(&env));
+        namespaces.push_back(namespace_id.clone());
+        storage.set(&NAMESPACES_KEY, &namespaces);
+
+        env.events()
+            .publish((symbol_short!("NsCreated"), namespace_id), owner);
+        Ok(())
+    }
+
+    /// Replace the configuration of a namespace
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `namespace_id` - Namespace to configure (owner must authorize)
+    /// * `config` - New configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the namespace is unknown or the fee is too high
+    pub fn set_config(
+        env: Env,
+        namespace_id: Symbol,
+        config: NamespaceConfig,
+    ) -> Result<(), Error> {
+        let mut namespace = Self::require_owner(&env, &namespace_id)?;
+        Self::validate_config(&config)?;
+
+        namespace.config = config;
+        env.storage()
+            .instance()
+            .set(&(NAMESPACE_KEY, namespace_id.clone()), &namespace);
+
+        env.events()
+            .publish((symbol_short!("NsConfig"), namespace_id), namespace.config);
+        Ok(())
+    }
+
+    /// Allow an address to list datasets in a namespace
+    pub fn add_curator(env: Env, namespace_id: Symbol, curator: Address) -> Result<(), Error> {
+        Self::require_owner(&env, &namespace_id)?;
+        env.storage()
+            .instance()
+            .set(&(CURATOR_KEY, namespace_id, curator), &true);
+        Ok(())
+    }
+
+    /// Revoke a curator; datasets it already listed stay listed
+    pub fn remove_curator(env: Env, namespace_id: Symbol, curator: Address) -> Result<(), Error> {
+        Self::require_owner(&env, &namespace_id)?;
+        env.storage()
+            .instance()
+            .remove(&(CURATOR_KEY, namespace_id, curator));
+        Ok(())
+    }
+
+    /// Check whether an address is a curator of a namespace
+    pub fn is_curator(env: Env, namespace_id: Symbol, curator: Address) -> bool {
+        env.storage()
+            .instance()
+            .has(&(CURATOR_KEY, namespace_id, curator))
+    }
+
+    /// Get a namespace by ID
+    ///
+    /// # Returns
+    /// * `Ok(Namespace)` if found
+    /// * `Err(Error::NamespaceNotFound)` if not found
+    pub fn get_namespace(env: Env, namespace_id: Symbol) -> Result<Namespace, Error> {
+        env.storage()
+            .instance()
+            .get(&(NAMESPACE_KEY, namespace_id))
+            .ok_or(Error::NamespaceNotFound)
+    }
+
+    /// Get the configuration of a namespace
+    ///
+    /// Read by DatasetMarketplace on every namespaced sale.
+    pub fn get_config(env: Env, namespace_id: Symbol) -> Result<NamespaceConfig, Error> {
+        Ok(Self::get_namespace(env, namespace_id)?.config)
+    }
+
+    /// Get all namespace IDs in creation order
+    pub fn get_namespaces(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&NAMESPACES_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn validate_config(config: &NamespaceConfig) -> Result<(), Error> {
+        if config.fee_bps > MAX_FEE_BPS {
+            return Err(Error::FeeTooHigh);
+        }
+        Ok(())
+    }
+
+    /// Require the namespace owner's authorization
+    fn require_owner(env: &Env, namespace_id: &Symbol) -> Result<Namespace, Error> {
+        let namespace = Self::get_namespace(env.clone(), namespace_id.clone())?;
+        namespace.owner.require_auth();
+        Ok(namespace)
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
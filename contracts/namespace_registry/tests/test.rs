@@ -0,0 +1,376 @@
+#![cfg(test)]
+
+use namespace_registry::{
+    Error, NamespaceConfig, NamespaceRegistry, NamespaceRegistryClient, MAX_FEE_BPS,
+};
+use soroban_sdk::{
+    symbol_short, testutils::Address as AddressTestUtils, token, vec, Address, Bytes, BytesN, Env,
+    String, Symbol,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+struct Setup<'a> {
+    env: Env,
+    namespaces: NamespaceRegistryClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+    contributor: Address,
+}
+
+/// Helper: Deploy the core contracts, a NamespaceRegistry and one study
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let namespaces =
+        NamespaceRegistryClient::new(&env, &env.register_contract(None, NamespaceRegistry));
+    namespaces.init(&Address::generate(&env));
+    marketplace.set_namespace_registry(&namespaces.address);
+
+    let contributor = Address::generate(&env);
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        namespaces,
+        registry,
+        marketplace,
+        contributor,
+    }
+}
+
+/// Helper: Create a namespace with one curator; returns (owner, curator)
+fn create_namespace(
+    s: &Setup,
+    namespace_id: &Symbol,
+    config: &NamespaceConfig,
+) -> (Address, Address) {
+    let owner = Address::generate(&s.env);
+    let curator = Address::generate(&s.env);
+    s.namespaces.create_namespace(namespace_id, &owner, config);
+    s.namespaces.add_curator(namespace_id, &curator);
+    (owner, curator)
+}
+
+fn config(s: &Setup, treasury: &Address, fee_bps: u32) -> NamespaceConfig {
+    NamespaceConfig {
+        name: String::from_str(&s.env, "Hospital Network"),
+        treasury: treasury.clone(),
+        fee_bps,
+    }
+}
+
+/// Helper: List the test study as `dataset_id` in a namespace
+fn list_dataset(
+    s: &Setup,
+    namespace_id: &Symbol,
+    curator: &Address,
+    dataset_id: &Bytes,
+    price: i128,
+) {
+    s.marketplace.register_namespaced_dataset(
+        namespace_id,
+        curator,
+        dataset_id,
+        &vec![&s.env, Bytes::from_array(&s.env, &[1u8; 32])],
+        &price,
+    );
+}
+
+/// Helper: Fund a fresh buyer and buy a namespaced dataset
+fn buy(s: &Setup, namespace_id: &Symbol, dataset_id: &Bytes, price: i128) -> Address {
+    let buyer = Address::generate(&s.env);
+    s.usdc.mint(&buyer, &price);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        &buyer,
+        &s.marketplace.address,
+        &price,
+        &1_000,
+    );
+    s.marketplace
+        .purchase_namespaced_dataset(namespace_id, dataset_id, &buyer);
+    buyer
+}
+
+#[test]
+fn test_namespaced_sale_pays_namespace_fee_and_shared_splitter() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let treasury = Address::generate(&s.env);
+    let (_, curator) = create_namespace(&s, &namespace_id, &config(&s, &treasury, 1_000));
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    list_dataset(&s, &namespace_id, &curator, &dataset_id, PRICE);
+
+    // Act
+    let buyer = buy(&s, &namespace_id, &dataset_id, PRICE);
+
+    // Assert: 10% to the namespace treasury, the rest paid out as usual
+    let usdc = token::Client::new(&s.env, &s.usdc.address);
+    assert_eq!(usdc.balance(&treasury), PRICE / 10);
+    assert_eq!(
+        usdc.balance(&s.contributor),
+        (PRICE - PRICE / 10) * 85 / 100
+    );
+    assert_eq!(usdc.balance(&s.marketplace.address), 0);
+    assert_eq!(
+        s.marketplace
+            .get_namespaced_purchase(&namespace_id, &dataset_id, &buyer)
+            .buyer,
+        buyer
+    );
+    assert_eq!(
+        s.marketplace.get_namespace_datasets(&namespace_id),
+        vec![&s.env, dataset_id.clone()]
+    );
+    assert!(!s.marketplace.dataset_exists(&dataset_id));
+}
+
+#[test]
+fn test_open_access_namespaced_dataset_is_acquired_for_free() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let treasury = Address::generate(&s.env);
+    let (_, curator) = create_namespace(&s, &namespace_id, &config(&s, &treasury, 1_000));
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_open");
+    list_dataset(&s, &namespace_id, &curator, &dataset_id, 0);
+
+    // Act: a buyer without funds or allowance acquires it
+    let buyer = Address::generate(&s.env);
+    s.marketplace
+        .purchase_namespaced_dataset(&namespace_id, &dataset_id, &buyer);
+
+    // Assert: the acquisition is recorded and nothing moves
+    let usdc = token::Client::new(&s.env, &s.usdc.address);
+    assert_eq!(usdc.balance(&treasury), 0);
+    assert_eq!(usdc.balance(&s.contributor), 0);
+    assert_eq!(
+        s.marketplace
+            .get_namespaced_purchase(&namespace_id, &dataset_id, &buyer)
+            .buyer,
+        buyer
+    );
+}
+
+#[test]
+fn test_namespaced_sale_takes_the_platform_fee_first() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let treasury = Address::generate(&s.env);
+    let collector = Address::generate(&s.env);
+    let (_, curator) = create_namespace(&s, &namespace_id, &config(&s, &treasury, 1_000));
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    list_dataset(&s, &namespace_id, &curator, &dataset_id, PRICE);
+    s.marketplace.set_fee_config(&Some(dataset_marketplace::FeeConfig {
+        fee_bps: 500,
+        collector: collector.clone(),
+    }));
+
+    // Act
+    buy(&s, &namespace_id, &dataset_id, PRICE);
+
+    // Assert: 5% of the price to the platform, then 10% of the rest to
+    // the namespace treasury
+    let usdc = token::Client::new(&s.env, &s.usdc.address);
+    let net = PRICE - PRICE / 20;
+    assert_eq!(usdc.balance(&collector), PRICE / 20);
+    assert_eq!(usdc.balance(&treasury), net / 10);
+    assert_eq!(usdc.balance(&s.contributor), (net - net / 10) * 85 / 100);
+    assert_eq!(usdc.balance(&s.marketplace.address), 0);
+}
+
+#[test]
+fn test_namespaced_purchases_check_the_listing() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let (_, curator) = create_namespace(
+        &s,
+        &namespace_id,
+        &config(&s, &Address::generate(&s.env), 0),
+    );
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    list_dataset(&s, &namespace_id, &curator, &dataset_id, PRICE);
+
+    // Act & Assert: a delisted listing cannot be bought until relisted
+    assert_eq!(
+        s.marketplace.try_delist_namespaced_dataset(
+            &namespace_id,
+            &Address::generate(&s.env),
+            &dataset_id
+        ),
+        Err(Ok(dataset_marketplace::Error::NotCurator))
+    );
+    s.marketplace
+        .delist_namespaced_dataset(&namespace_id, &curator, &dataset_id);
+    assert_eq!(
+        s.marketplace
+            .try_purchase_namespaced_dataset(&namespace_id, &dataset_id, &Address::generate(&s.env)),
+        Err(Ok(dataset_marketplace::Error::DatasetNotActive))
+    );
+    s.marketplace
+        .relist_namespaced_dataset(&namespace_id, &curator, &dataset_id);
+    buy(&s, &namespace_id, &dataset_id, PRICE);
+
+    // The dataset's access policy applies in the namespace too
+    let owner = Address::generate(&s.env);
+    s.marketplace.register_dataset(
+        &owner,
+        &dataset_id,
+        &vec![&s.env, Bytes::from_array(&s.env, &[1u8; 32])],
+        &PRICE,
+        &None,
+    );
+    s.marketplace
+        .set_access_policy(&dataset_id, &dataset_marketplace::AccessPolicy::Allowlist);
+    assert_eq!(
+        s.marketplace
+            .try_purchase_namespaced_dataset(&namespace_id, &dataset_id, &Address::generate(&s.env)),
+        Err(Err(soroban_sdk::InvokeError::Contract(
+            dataset_marketplace::ExtError::BuyerNotEligible as u32
+        )))
+    );
+    s.marketplace
+        .set_access_policy(&dataset_id, &dataset_marketplace::AccessPolicy::Open);
+
+    // A listing with a revoked study is not sold
+    s.registry.revoke_study(
+        &s.contributor,
+        &BytesN::from_array(&s.env, &[1u8; 32]),
+        &study_registry::RevocationReason::ConsentWithdrawn,
+        &false,
+    );
+    assert_eq!(
+        s.marketplace
+            .try_purchase_namespaced_dataset(&namespace_id, &dataset_id, &Address::generate(&s.env)),
+        Err(Ok(dataset_marketplace::Error::DatasetUnderReview))
+    );
+}
+
+#[test]
+fn test_same_dataset_id_is_scoped_per_namespace() {
+    let s = setup();
+    let treasury = Address::generate(&s.env);
+    let first = symbol_short!("hospnet");
+    let second = symbol_short!("clinics");
+    let (_, first_curator) = create_namespace(&s, &first, &config(&s, &treasury, 0));
+    let (_, second_curator) = create_namespace(&s, &second, &config(&s, &treasury, 0));
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+
+    // Act
+    list_dataset(&s, &first, &first_curator, &dataset_id, PRICE);
+    list_dataset(&s, &second, &second_curator, &dataset_id, 2 * PRICE);
+
+    // Assert
+    assert_eq!(
+        s.marketplace
+            .get_namespaced_dataset(&first, &dataset_id)
+            .price_usdc,
+        PRICE
+    );
+    assert_eq!(
+        s.marketplace
+            .get_namespaced_dataset(&second, &dataset_id)
+            .price_usdc,
+        2 * PRICE
+    );
+    assert_eq!(s.namespaces.get_namespaces(), vec![&s.env, first, second]);
+}
+
+#[test]
+fn test_only_curators_can_list_datasets() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let (_, curator) = create_namespace(
+        &s,
+        &namespace_id,
+        &config(&s, &Address::generate(&s.env), 0),
+    );
+    let dataset_id = Bytes::from_slice(&s.env, b"dataset_001");
+    let studies = vec![&s.env, Bytes::from_array(&s.env, &[1u8; 32])];
+
+    // Act & Assert
+    assert_eq!(
+        s.marketplace.try_register_namespaced_dataset(
+            &namespace_id,
+            &Address::generate(&s.env),
+            &dataset_id,
+            &studies,
+            &PRICE
+        ),
+        Err(Ok(dataset_marketplace::Error::NotCurator))
+    );
+    s.namespaces.remove_curator(&namespace_id, &curator);
+    assert_eq!(
+        s.marketplace.try_register_namespaced_dataset(
+            &namespace_id,
+            &curator,
+            &dataset_id,
+            &studies,
+            &PRICE
+        ),
+        Err(Ok(dataset_marketplace::Error::NotCurator))
+    );
+}
+
+#[test]
+fn test_namespace_configuration_is_validated() {
+    let s = setup();
+    let namespace_id = symbol_short!("hospnet");
+    let treasury = Address::generate(&s.env);
+    create_namespace(&s, &namespace_id, &config(&s, &treasury, 500));
+
+    // Act & Assert
+    assert_eq!(
+        s.namespaces.try_create_namespace(
+            &namespace_id,
+            &Address::generate(&s.env),
+            &config(&s, &treasury, 0)
+        ),
+        Err(Ok(Error::NamespaceExists))
+    );
+    assert_eq!(
+        s.namespaces
+            .try_set_config(&namespace_id, &config(&s, &treasury, MAX_FEE_BPS + 1)),
+        Err(Ok(Error::FeeTooHigh))
+    );
+    s.namespaces
+        .set_config(&namespace_id, &config(&s, &treasury, MAX_FEE_BPS));
+    assert_eq!(s.namespaces.get_config(&namespace_id).fee_bps, MAX_FEE_BPS);
+    assert_eq!(
+        s.namespaces.try_get_namespace(&symbol_short!("unknown")),
+        Err(Ok(Error::NamespaceNotFound))
+    );
+}
@@ -0,0 +1,338 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, Env, Symbol,
+    Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const SENDER_KEY: Symbol = symbol_short!("SENDER");
+const INBOX_KEY: Symbol = symbol_short!("INBOX");
+const NOTE_KEY: Symbol = symbol_short!("NOTE");
+
+/// Notification kinds senders can append
+///
+/// Kinds are plain symbols so core contracts can notify without sharing a
+/// type with this contract.
+/// - sale: A dataset containing one of your studies was sold (marketplace)
+/// - payout: A payout was sent or is claimable (splitter)
+/// - consent: A consent you granted is about to expire
+pub const KIND_SALE: Symbol = symbol_short!("sale");
+pub const KIND_PAYOUT: Symbol = symbol_short!("payout");
+pub const KIND_CONSENT: Symbol = symbol_short!("consent");
+
+/// Maximum notifications kept per address; older ones are dropped first
+pub const MAX_INBOX_SIZE: u64 = 100;
+
+/// Maximum notifications returned by a single `list` call
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Compact notification
+///
+/// - id: Per-recipient sequence number
+/// - kind: One of the KIND_* symbols
+/// - sender: Contract that appended the notification
+/// - reference: Dataset ID / study hash the notification refers to
+/// - amount: USDC amount involved (0 if not applicable)
+/// - created_at: Ledger timestamp
+/// - acked: Whether the recipient acknowledged it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notification {
+    pub id: u64,
+    pub kind: Symbol,
+    pub sender: Address,
+    pub reference: Bytes,
+    pub amount: i128,
+    pub created_at: u64,
+    pub acked: bool,
+}
+
+/// Per-address inbox bookkeeping
+///
+/// Live notifications have ids in `first_id..next_id`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InboxMeta {
+    pub first_id: u64,
+    pub next_id: u64,
+    pub unread: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedSender = 3,
+    UnknownKind = 4,
+    NotificationNotFound = 5,
+}
+
+#[contract]
+pub struct NotificationInbox;
+
+#[contractimpl]
+impl NotificationInbox {
+    /// Initialize the NotificationInbox contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage senders
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Allow a contract (marketplace, splitter, consent service) to append
+    /// notifications
+    pub fn add_sender(env: Env, sender: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&(SENDER_KEY, sender), &true);
+        Ok(())
+    }
+
+    /// Revoke a sender's permission to append notifications
+    pub fn remove_sender(env: Env, sender: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(SENDER_KEY, sender));
+        Ok(())
+    }
+
+    /// Append a notification to an address's inbox
+    ///
+    /// When the inbox is full the oldest notification is dropped.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `sender` - Authorized sender contract (must authorize the call)
+    /// * `recipient` - Address the notification is for
+    /// * `kind` - One of the KIND_* symbols
+    /// * `reference` - Dataset ID / study hash the notification refers to
+    /// * `amount` - USDC amount involved (0 if not applicable)
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the notification ID
+    /// * `Err(Error)` if the sender or kind is invalid
+    pub fn append(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        kind: Symbol,
+        reference: Bytes,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE SENDER AND KIND
+        // ============================================
+        sender.require_auth();
+        if !env.storage().instance().has(&(SENDER_KEY, sender.clone())) {
+            return Err(Error::UnauthorizedSender);
+        }
+        if kind != KIND_SALE && kind != KIND_PAYOUT && kind != KIND_CONSENT {
+            return Err(Error::UnknownKind);
+        }
+
+        // ============================================
+        // 2. STORE NOTIFICATION
+        // ============================================
+        let mut meta = Self::get_meta(env.clone(), recipient.clone());
+        let id = meta.next_id;
+        let notification = Notification {
+            id,
+            kind: kind.clone(),
+            sender,
+            reference,
+            amount,
+            created_at: env.ledger().timestamp(),
+            acked: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&(NOTE_KEY, recipient.clone(), id), &notification);
+        meta.next_id += 1;
+        meta.unread += 1;
+
+        // ============================================
+        // 3. DROP OLDEST IF FULL
+        // ============================================
+        if meta.next_id - meta.first_id > MAX_INBOX_SIZE {
+            Self::drop_front(&env, &recipient, &mut meta);
+        }
+        Self::save_meta(&env, &recipient, &meta);
+
+        env.events()
+            .publish((symbol_short!("Notify"), recipient, kind), id);
+
+        Ok(id)
+    }
+
+    /// List live notifications starting at `start_id`
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `recipient` - Inbox owner
+    /// * `start_id` - First notification ID to return (clamped to the oldest)
+    /// * `limit` - Maximum number of notifications (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// * Notifications ordered by ID
+    pub fn list(env: Env, recipient: Address, start_id: u64, limit: u32) -> Vec<Notification> {
+        let meta = Self::get_meta(env.clone(), recipient.clone());
+        let mut notifications = Vec::new(&env);
+        let limit = limit.min(MAX_PAGE_SIZE);
+
+        let mut id = start_id.max(meta.first_id);
+        while id < meta.next_id && notifications.len() < limit {
+            if let Some(notification) = env.storage().persistent().get::<_, Notification>(&(
+                NOTE_KEY,
+                recipient.clone(),
+                id,
+            )) {
+                notifications.push_back(notification);
+            }
+            id += 1;
+        }
+        notifications
+    }
+
+    /// Get the inbox bookkeeping (live range and unread count)
+    pub fn get_meta(env: Env, recipient: Address) -> InboxMeta {
+        env.storage()
+            .persistent()
+            .get(&(INBOX_KEY, recipient))
+            .unwrap_or(InboxMeta {
+                first_id: 0,
+                next_id: 0,
+                unread: 0,
+            })
+    }
+
+    /// Get the number of unacknowledged notifications
+    pub fn unread_count(env: Env, recipient: Address) -> u64 {
+        Self::get_meta(env, recipient).unread
+    }
+
+    /// Acknowledge a single notification
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `recipient` - Inbox owner (must authorize)
+    /// * `id` - Notification to acknowledge
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotificationNotFound)` if the notification is gone
+    pub fn ack(env: Env, recipient: Address, id: u64) -> Result<(), Error> {
+        recipient.require_auth();
+
+        let key = (NOTE_KEY, recipient.clone(), id);
+        let mut notification: Notification = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NotificationNotFound)?;
+        if !notification.acked {
+            notification.acked = true;
+            env.storage().persistent().set(&key, &notification);
+
+            let mut meta = Self::get_meta(env.clone(), recipient.clone());
+            meta.unread -= 1;
+            Self::save_meta(&env, &recipient, &meta);
+        }
+        Ok(())
+    }
+
+    /// Acknowledge every live notification with an ID below `up_to_id`
+    ///
+    /// # Returns
+    /// * Number of notifications newly acknowledged
+    pub fn ack_all(env: Env, recipient: Address, up_to_id: u64) -> u32 {
+        recipient.require_auth();
+
+        let mut meta = Self::get_meta(env.clone(), recipient.clone());
+        let mut acked = 0u32;
+        let end = up_to_id.min(meta.next_id);
+        for id in meta.first_id..end {
+            let key = (NOTE_KEY, recipient.clone(), id);
+            if let Some(mut notification) = env.storage().persistent().get::<_, Notification>(&key)
+            {
+                if !notification.acked {
+                    notification.acked = true;
+                    env.storage().persistent().set(&key, &notification);
+                    acked += 1;
+                }
+            }
+        }
+        meta.unread -= acked as u64;
+        Self::save_meta(&env, &recipient, &meta);
+        acked
+    }
+
+    /// Delete acknowledged notifications from the front of the inbox
+    ///
+    /// Stops at the first unacknowledged notification or at `up_to_id`.
+    ///
+    /// # Returns
+    /// * Number of notifications deleted
+    pub fn prune(env: Env, recipient: Address, up_to_id: u64) -> u32 {
+        recipient.require_auth();
+
+        let mut meta = Self::get_meta(env.clone(), recipient.clone());
+        let mut pruned = 0u32;
+        while meta.first_id < up_to_id.min(meta.next_id) {
+            let key = (NOTE_KEY, recipient.clone(), meta.first_id);
+            let acked = env
+                .storage()
+                .persistent()
+                .get::<_, Notification>(&key)
+                .map(|n| n.acked)
+                .unwrap_or(true);
+            if !acked {
+                break;
+            }
+            env.storage().persistent().remove(&key);
+            meta.first_id += 1;
+            pruned += 1;
+        }
+        Self::save_meta(&env, &recipient, &meta);
+        pruned
+    }
+
+    fn drop_front(env: &Env, recipient: &Address, meta: &mut InboxMeta) {
+        let key = (NOTE_KEY, recipient.clone(), meta.first_id);
+        if let Some(notification) = env.storage().persistent().get::<_, Notification>(&key) {
+            if !notification.acked {
+                meta.unread -= 1;
+            }
+            env.storage().persistent().remove(&key);
+        }
+        meta.first_id += 1;
+    }
+
+    fn save_meta(env: &Env, recipient: &Address, meta: &InboxMeta) {
+        env.storage()
+            .persistent()
+            .set(&(INBOX_KEY, recipient.clone()), meta);
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
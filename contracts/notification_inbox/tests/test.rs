@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+use notification_inbox::{
+    Error, NotificationInbox, NotificationInboxClient, KIND_PAYOUT, KIND_SALE, MAX_INBOX_SIZE,
+};
+use soroban_sdk::{symbol_short, testutils::Address as AddressTestUtils, Address, Bytes, Env};
+
+/// Helper: Create an initialized NotificationInbox client with one sender
+fn create_inbox_client(env: &Env) -> (NotificationInboxClient<'_>, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, NotificationInbox);
+    let client = NotificationInboxClient::new(env, &contract_id);
+    let sender = Address::generate(env);
+    client.init(&Address::generate(env));
+    client.add_sender(&sender);
+    (client, sender)
+}
+
+/// Helper: Append a sale notification
+fn notify_sale(env: &Env, client: &NotificationInboxClient, sender: &Address, to: &Address) -> u64 {
+    client.append(
+        sender,
+        to,
+        &KIND_SALE,
+        &Bytes::from_slice(env, b"dataset_001"),
+        &8_5000000,
+    )
+}
+
+#[test]
+fn test_append_and_list() {
+    let env = Env::default();
+    let (client, sender) = create_inbox_client(&env);
+    let contributor = Address::generate(&env);
+
+    // Act
+    let first = notify_sale(&env, &client, &sender, &contributor);
+    let second = client.append(
+        &sender,
+        &contributor,
+        &KIND_PAYOUT,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &8_5000000,
+    );
+
+    // Assert
+    assert_eq!((first, second), (0, 1));
+    assert_eq!(client.unread_count(&contributor), 2);
+
+    let notifications = client.list(&contributor, &0, &10);
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications.get(0).unwrap().kind, KIND_SALE);
+    assert_eq!(notifications.get(1).unwrap().kind, KIND_PAYOUT);
+    assert_eq!(client.list(&contributor, &1, &10).len(), 1);
+}
+
+#[test]
+fn test_ack_and_ack_all() {
+    let env = Env::default();
+    let (client, sender) = create_inbox_client(&env);
+    let contributor = Address::generate(&env);
+    for _ in 0..3 {
+        notify_sale(&env, &client, &sender, &contributor);
+    }
+
+    // Act
+    client.ack(&contributor, &1);
+    client.ack(&contributor, &1);
+
+    // Assert: acknowledging twice counts once
+    assert_eq!(client.unread_count(&contributor), 2);
+    assert_eq!(client.ack_all(&contributor, &3), 2);
+    assert_eq!(client.unread_count(&contributor), 0);
+    assert!(client.list(&contributor, &0, &10).iter().all(|n| n.acked));
+}
+
+#[test]
+fn test_prune_stops_at_unread() {
+    let env = Env::default();
+    let (client, sender) = create_inbox_client(&env);
+    let contributor = Address::generate(&env);
+    for _ in 0..3 {
+        notify_sale(&env, &client, &sender, &contributor);
+    }
+
+    // Arrange: ack 0 and 2, leave 1 unread
+    client.ack(&contributor, &0);
+    client.ack(&contributor, &2);
+
+    // Act
+    let pruned = client.prune(&contributor, &3);
+
+    // Assert
+    assert_eq!(pruned, 1);
+    assert_eq!(client.get_meta(&contributor).first_id, 1);
+    assert_eq!(client.list(&contributor, &0, &10).len(), 2);
+    assert_eq!(
+        client.try_ack(&contributor, &0),
+        Err(Ok(Error::NotificationNotFound))
+    );
+}
+
+#[test]
+fn test_full_inbox_drops_oldest() {
+    let env = Env::default();
+    let (client, sender) = create_inbox_client(&env);
+    let contributor = Address::generate(&env);
+
+    // Act
+    for _ in 0..(MAX_INBOX_SIZE + 5) {
+        notify_sale(&env, &client, &sender, &contributor);
+    }
+
+    // Assert
+    let meta = client.get_meta(&contributor);
+    assert_eq!(meta.first_id, 5);
+    assert_eq!(meta.next_id, MAX_INBOX_SIZE + 5);
+    assert_eq!(meta.unread, MAX_INBOX_SIZE);
+}
+
+#[test]
+fn test_unauthorized_sender_and_unknown_kind_fail() {
+    let env = Env::default();
+    let (client, sender) = create_inbox_client(&env);
+    let contributor = Address::generate(&env);
+    let reference = Bytes::from_slice(&env, b"dataset_001");
+
+    assert_eq!(
+        client.try_append(
+            &Address::generate(&env),
+            &contributor,
+            &KIND_SALE,
+            &reference,
+            &0
+        ),
+        Err(Ok(Error::UnauthorizedSender))
+    );
+    assert_eq!(
+        client.try_append(
+            &sender,
+            &contributor,
+            &symbol_short!("spam"),
+            &reference,
+            &0
+        ),
+        Err(Ok(Error::UnknownKind))
+    );
+}
@@ -0,0 +1,284 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const REPORTER_KEY: Symbol = symbol_short!("REPORTER");
+const TOTAL_KEY: Symbol = symbol_short!("TOTAL");
+const BUCKET_KEY: Symbol = symbol_short!("BUCKET");
+
+/// Metric names reporters push increments to
+///
+/// Metrics are plain symbols so the marketplace, splitter and registry can
+/// report without sharing a type with this contract.
+/// - sales: Number of dataset purchases (marketplace)
+/// - volume: USDC paid for datasets (marketplace)
+/// - payouts: USDC paid out to contributors (splitter)
+/// - regs: Number of studies registered (registry)
+pub const METRIC_SALES: Symbol = symbol_short!("sales");
+pub const METRIC_VOLUME: Symbol = symbol_short!("volume");
+pub const METRIC_PAYOUTS: Symbol = symbol_short!("payouts");
+pub const METRIC_REGS: Symbol = symbol_short!("regs");
+
+/// Width of a time bucket in seconds (one day)
+pub const BUCKET_SECONDS: u64 = 86_400;
+
+/// Maximum number of buckets returned by a single range query
+pub const MAX_RANGE_BUCKETS: u64 = 366;
+
+/// Aggregate for a single bucket
+///
+/// - bucket: Bucket index (ledger timestamp / BUCKET_SECONDS)
+/// - count: Number of increments pushed in this bucket
+/// - sum: Sum of the pushed amounts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BucketStat {
+    pub bucket: u64,
+    pub count: u32,
+    pub sum: i128,
+}
+
+/// Event data for StatRecorded event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatRecorded {
+    pub reporter: Address,
+    pub metric: Symbol,
+    pub amount: i128,
+    pub bucket: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    UnauthorizedReporter = 3,
+    UnknownMetric = 4,
+    InvalidAmount = 5,
+    InvalidRange = 6,
+}
+
+#[contract]
+pub struct PlatformStats;
+
+#[contractimpl]
+impl PlatformStats {
+    /// Initialize the PlatformStats contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage reporters
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Allow a contract (marketplace, splitter, registry) to push increments
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Address of the reporting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn add_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&(REPORTER_KEY, reporter), &true);
+        Ok(())
+    }
+
+    /// Revoke a reporter's permission to push increments
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Address of the reporting contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no admin is set
+    pub fn remove_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&(REPORTER_KEY, reporter));
+        Ok(())
+    }
+
+    /// Check whether an address is an authorized reporter
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        env.storage().instance().has(&(REPORTER_KEY, reporter))
+    }
+
+    /// Push an increment for a metric
+    ///
+    /// The amount is added to the all-time total and to the bucket of the
+    /// current ledger timestamp.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reporter` - Authorized reporting contract (must authorize the call)
+    /// * `metric` - One of the METRIC_* symbols
+    /// * `amount` - Increment (1 for counters, USDC amount for volumes)
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the reporter, metric or amount is invalid
+    pub fn record(env: Env, reporter: Address, metric: Symbol, amount: i128) -> Result<(), Error> {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        reporter.require_auth();
+        if !env
+            .storage()
+            .instance()
+            .has(&(REPORTER_KEY, reporter.clone()))
+        {
+            return Err(Error::UnauthorizedReporter);
+        }
+        if !Self::is_known_metric(&metric) {
+            return Err(Error::UnknownMetric);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // ============================================
+        // 2. UPDATE ALL-TIME TOTAL
+        // ============================================
+        let instance = env.storage().instance();
+        let total_key = (TOTAL_KEY, metric.clone());
+        let total: i128 = instance.get(&total_key).unwrap_or(0);
+        instance.set(&total_key, &total.saturating_add(amount));
+
+        // ============================================
+        // 3. UPDATE TIME BUCKET
+        // ============================================
+        let bucket = env.ledger().timestamp() / BUCKET_SECONDS;
+        let mut stat = Self::load_bucket(&env, &metric, bucket);
+        stat.count = stat.count.saturating_add(1);
+        stat.sum = stat.sum.saturating_add(amount);
+        env.storage()
+            .persistent()
+            .set(&(BUCKET_KEY, metric.clone(), bucket), &stat);
+
+        // ============================================
+        // 4. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("StatRec"), metric.clone()),
+            StatRecorded {
+                reporter,
+                metric,
+                amount,
+                bucket,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get the all-time total for a metric
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - One of the METRIC_* symbols
+    ///
+    /// # Returns
+    /// * Sum of all increments pushed for the metric (0 if none)
+    pub fn get_total(env: Env, metric: Symbol) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(TOTAL_KEY, metric))
+            .unwrap_or(0)
+    }
+
+    /// Get the aggregate for a single bucket
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - One of the METRIC_* symbols
+    /// * `bucket` - Bucket index (timestamp / BUCKET_SECONDS)
+    ///
+    /// # Returns
+    /// * `BucketStat` (zeroed if nothing was recorded in the bucket)
+    pub fn get_bucket(env: Env, metric: Symbol, bucket: u64) -> BucketStat {
+        Self::load_bucket(&env, &metric, bucket)
+    }
+
+    /// Get the aggregates for an inclusive range of buckets
+    ///
+    /// Empty buckets are included as zeroed entries so the result can be
+    /// plotted directly.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - One of the METRIC_* symbols
+    /// * `from_bucket` - First bucket index
+    /// * `to_bucket` - Last bucket index (inclusive)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<BucketStat>)` ordered by bucket
+    /// * `Err(Error::InvalidRange)` if the range is reversed or too large
+    pub fn get_range(
+        env: Env,
+        metric: Symbol,
+        from_bucket: u64,
+        to_bucket: u64,
+    ) -> Result<Vec<BucketStat>, Error> {
+        if to_bucket < from_bucket || to_bucket - from_bucket >= MAX_RANGE_BUCKETS {
+            return Err(Error::InvalidRange);
+        }
+
+        let mut stats = Vec::new(&env);
+        for bucket in from_bucket..=to_bucket {
+            stats.push_back(Self::load_bucket(&env, &metric, bucket));
+        }
+        Ok(stats)
+    }
+
+    /// Get the bucket index for the current ledger timestamp
+    pub fn current_bucket(env: Env) -> u64 {
+        env.ledger().timestamp() / BUCKET_SECONDS
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn is_known_metric(metric: &Symbol) -> bool {
+        *metric == METRIC_SALES
+            || *metric == METRIC_VOLUME
+            || *metric == METRIC_PAYOUTS
+            || *metric == METRIC_REGS
+    }
+
+    fn load_bucket(env: &Env, metric: &Symbol, bucket: u64) -> BucketStat {
+        env.storage()
+            .persistent()
+            .get(&(BUCKET_KEY, metric.clone(), bucket))
+            .unwrap_or(BucketStat {
+                bucket,
+                count: 0,
+                sum: 0,
+            })
+    }
+}
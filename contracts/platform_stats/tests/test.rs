@@ -0,0 +1,135 @@
+#![cfg(test)]
+
+use platform_stats::{
+    Error, PlatformStats, PlatformStatsClient, BUCKET_SECONDS, METRIC_PAYOUTS, METRIC_SALES,
+    METRIC_VOLUME,
+};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as AddressTestUtils, Ledger},
+    Address, Env,
+};
+
+/// Helper: Create a test environment
+fn create_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+/// Helper: Create a test address
+fn create_address(env: &Env) -> Address {
+    Address::generate(env)
+}
+
+/// Helper: Create an initialized PlatformStats client with one reporter
+fn create_stats_client(env: &Env) -> (PlatformStatsClient<'_>, Address) {
+    let contract_id = env.register_contract(None, PlatformStats);
+    let client = PlatformStatsClient::new(env, &contract_id);
+
+    let admin = create_address(env);
+    let reporter = create_address(env);
+    client.init(&admin);
+    client.add_reporter(&reporter);
+
+    (client, reporter)
+}
+
+/// Helper: Move the ledger clock to the given bucket
+fn set_bucket(env: &Env, bucket: u64) {
+    env.ledger().with_mut(|l| l.timestamp = bucket * BUCKET_SECONDS + 1);
+}
+
+#[test]
+fn test_record_updates_total_and_bucket() {
+    let env = create_env();
+    let (client, reporter) = create_stats_client(&env);
+    set_bucket(&env, 100);
+
+    // Act
+    client.record(&reporter, &METRIC_SALES, &1);
+    client.record(&reporter, &METRIC_SALES, &1);
+    client.record(&reporter, &METRIC_VOLUME, &10_0000000);
+
+    // Assert
+    assert_eq!(client.get_total(&METRIC_SALES), 2);
+    assert_eq!(client.get_total(&METRIC_VOLUME), 10_0000000);
+    assert_eq!(client.get_total(&METRIC_PAYOUTS), 0);
+
+    let bucket = client.get_bucket(&METRIC_SALES, &100);
+    assert_eq!(bucket.count, 2);
+    assert_eq!(bucket.sum, 2);
+    assert_eq!(client.current_bucket(), 100);
+}
+
+#[test]
+fn test_get_range_includes_empty_buckets() {
+    let env = create_env();
+    let (client, reporter) = create_stats_client(&env);
+
+    // Arrange: activity on day 10 and day 12, nothing on day 11
+    set_bucket(&env, 10);
+    client.record(&reporter, &METRIC_VOLUME, &5);
+    set_bucket(&env, 12);
+    client.record(&reporter, &METRIC_VOLUME, &7);
+
+    // Act
+    let range = client.get_range(&METRIC_VOLUME, &10, &12);
+
+    // Assert
+    assert_eq!(range.len(), 3);
+    assert_eq!(range.get(0).unwrap().sum, 5);
+    assert_eq!(range.get(1).unwrap().sum, 0);
+    assert_eq!(range.get(1).unwrap().bucket, 11);
+    assert_eq!(range.get(2).unwrap().sum, 7);
+    assert_eq!(client.get_total(&METRIC_VOLUME), 12);
+}
+
+#[test]
+fn test_invalid_range_fails() {
+    let env = create_env();
+    let (client, _) = create_stats_client(&env);
+
+    // Reversed range
+    assert_eq!(
+        client.try_get_range(&METRIC_SALES, &5, &4),
+        Err(Ok(Error::InvalidRange))
+    );
+
+    // Range too large
+    assert_eq!(
+        client.try_get_range(&METRIC_SALES, &0, &1000),
+        Err(Ok(Error::InvalidRange))
+    );
+}
+
+#[test]
+fn test_unknown_metric_and_bad_amount_fail() {
+    let env = create_env();
+    let (client, reporter) = create_stats_client(&env);
+
+    assert_eq!(
+        client.try_record(&reporter, &symbol_short!("bogus"), &1),
+        Err(Ok(Error::UnknownMetric))
+    );
+    assert_eq!(
+        client.try_record(&reporter, &METRIC_SALES, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_unauthorized_reporter_fails() {
+    let env = create_env();
+    let (client, _) = create_stats_client(&env);
+
+    // Arrange
+    let stranger = create_address(&env);
+
+    // Act
+    let result = client.try_record(&stranger, &METRIC_SALES, &1);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::UnauthorizedReporter)));
+    assert_eq!(client.get_total(&METRIC_SALES), 0);
+}
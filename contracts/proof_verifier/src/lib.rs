@@ -0,0 +1,150 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN,
+    Env, Vec,
+};
+
+/// Ledgers an approval stays valid (about a day of 5-second ledgers)
+pub const APPROVAL_TTL: u32 = 17_280;
+
+/// Storage keys
+///
+/// - Approval: Digests of approved proofs in temporary storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Service,
+    Approval(BytesN<32>),
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    NotApproved = 3,
+}
+
+/// Groth16 proofs verified off-chain
+///
+/// Checking a Groth16 proof over BN254 takes a pairing that costs about 38
+/// times a transaction's instruction limit in WASM, and the network has no
+/// host function for it. A verification service instead checks each proof
+/// off-chain, against the verifying key and public signals StudyRegistry
+/// passes, and approves the digest of all three here; `verify` accepts
+/// only approved proofs. Keys and proofs are public, so anyone can re-run
+/// the check and the admin can replace a service that approves invalid
+/// proofs. Approvals expire after APPROVAL_TTL ledgers.
+#[contract]
+pub struct ProofVerifier;
+
+#[contractimpl]
+impl ProofVerifier {
+    /// Initialize the ProofVerifier contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to replace the service
+    /// * `service` - Address of the off-chain verification service
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address, service: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::Service, &service);
+        Ok(())
+    }
+
+    /// Replace the verification service
+    ///
+    /// Approvals already made stay valid until they expire. Requires the
+    /// admin's authorization.
+    pub fn set_service(env: Env, service: Address) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(&env, &DataKey::Admin)?;
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Service, &service);
+        Ok(())
+    }
+
+    /// Get the verification service
+    pub fn get_service(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Service)
+    }
+
+    /// Approve a proof the service verified off-chain
+    ///
+    /// Requires the service's authorization.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `digest` - `proof_digest` of the key, proof and signals
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInitialized)` if no service is set
+    pub fn approve(env: Env, digest: BytesN<32>) -> Result<(), Error> {
+        let service: Address = Self::get_instance(&env, &DataKey::Service)?;
+        service.require_auth();
+        let storage = env.storage().temporary();
+        let key = DataKey::Approval(digest.clone());
+        storage.set(&key, &true);
+        storage.extend_ttl(&key, APPROVAL_TTL, APPROVAL_TTL);
+
+        env.events().publish((symbol_short!("Approved"),), digest);
+        Ok(())
+    }
+
+    /// Check that a proof was approved
+    ///
+    /// Called by StudyRegistry for every Groth16 registration.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `vk` - Encoded verifying key the proof is checked against
+    /// * `proof` - Encoded proof, A || B || C
+    /// * `signals` - Public signals of the proof
+    ///
+    /// # Returns
+    /// * `Ok(())` if the proof was approved
+    /// * `Err(Error::NotApproved)` otherwise
+    pub fn verify(env: Env, vk: Bytes, proof: Bytes, signals: Vec<BytesN<32>>) -> Result<(), Error> {
+        let digest = Self::proof_digest(env.clone(), vk, proof, signals);
+        if !Self::is_approved(env, digest) {
+            return Err(Error::NotApproved);
+        }
+        Ok(())
+    }
+
+    /// Whether a proof digest is approved and the approval has not expired
+    pub fn is_approved(env: Env, digest: BytesN<32>) -> bool {
+        env.storage().temporary().has(&DataKey::Approval(digest))
+    }
+
+    /// Digest the service approves for a proof
+    ///
+    /// SHA-256 of the key, the proof and the signals, concatenated.
+    pub fn proof_digest(env: Env, vk: Bytes, proof: Bytes, signals: Vec<BytesN<32>>) -> BytesN<32> {
+        let mut preimage = vk;
+        preimage.append(&proof);
+        for signal in signals.iter() {
+            preimage.append(&signal.into());
+        }
+        env.crypto().sha256(&preimage).into()
+    }
+
+    fn get_instance<V: soroban_sdk::TryFromVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        key: &DataKey,
+    ) -> Result<V, Error> {
+        env.storage().instance().get(key).ok_or(Error::NotInitialized)
+    }
+}
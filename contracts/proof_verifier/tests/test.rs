@@ -0,0 +1,184 @@
+#![cfg(test)]
+
+use biochain_groth16::{Proof, VerifyingKey, PROOF_LEN};
+use biochain_zk_schema::SIGNAL_COUNT;
+use proof_verifier::{Error, ProofVerifier, ProofVerifierClient, APPROVAL_TTL};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    Address, Bytes, BytesN, Env, Vec,
+};
+use study_registry::{testutils, ProofKind, StudyRegistry, StudyRegistryClient};
+
+struct Setup<'a> {
+    env: Env,
+    verifier: ProofVerifierClient<'a>,
+    registry: StudyRegistryClient<'a>,
+}
+
+/// Helper: Deploy a StudyRegistry that checks Groth16 proofs with a
+/// ProofVerifier
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let verifier = ProofVerifierClient::new(&env, &env.register_contract(None, ProofVerifier));
+    verifier.init(&Address::generate(&env), &Address::generate(&env));
+    let registry = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    testutils::install_verifying_key(&registry);
+    testutils::install_attestation_root(&registry);
+    registry.set_proof_verifier(&verifier.address);
+
+    Setup { env, verifier, registry }
+}
+
+/// A registration's arguments
+struct Registration {
+    dataset_hash: BytesN<32>,
+    attestation: Bytes,
+    zk_proof: Bytes,
+    contributor: Address,
+}
+
+/// Helper: Build a registration with a valid proof
+fn registration(env: &Env, seed: u8) -> Registration {
+    let dataset_hash = BytesN::from_array(env, &[seed; 32]);
+    let contributor = Address::generate(env);
+    let attestation = testutils::attestation(env, &dataset_hash, &contributor);
+    let zk_proof = testutils::zk_proof(env, &dataset_hash, &attestation, &contributor);
+    Registration { dataset_hash, attestation, zk_proof, contributor }
+}
+
+/// Helper: Check a registration's proof off-chain, as the service does,
+/// and approve it if it verifies
+///
+/// # Returns
+/// * Whether the proof verified
+fn review(setup: &Setup, registration: &Registration) -> bool {
+    let env = &setup.env;
+    let vk = setup.registry.get_verifying_key(&testutils::CIRCUIT, &None).unwrap();
+    let signals = testutils::signals(
+        env,
+        &registration.dataset_hash,
+        &registration.attestation,
+        &registration.contributor,
+    );
+
+    let mut key_bytes = std::vec![0u8; vk.len() as usize];
+    vk.copy_into_slice(&mut key_bytes);
+    let key = VerifyingKey::<{ SIGNAL_COUNT + 1 }>::from_bytes(&key_bytes).unwrap();
+    let mut proof_bytes = [0u8; PROOF_LEN];
+    registration.zk_proof.copy_into_slice(&mut proof_bytes);
+    let signal_bytes: std::vec::Vec<[u8; 32]> = signals.iter().map(|signal| signal.to_array()).collect();
+    let verified = Proof::from_bytes(&proof_bytes)
+        .is_ok_and(|proof| key.verify(&proof, &signal_bytes) == Ok(true));
+
+    if verified {
+        let digest = setup.verifier.proof_digest(&vk, &registration.zk_proof, &signals);
+        setup.verifier.approve(&digest);
+    }
+    verified
+}
+
+fn register(setup: &Setup, registration: &Registration) -> Result<(), study_registry::Error> {
+    match setup.registry.try_register_study(
+        &registration.dataset_hash,
+        &registration.attestation,
+        &ProofKind::Groth16,
+        &registration.zk_proof,
+        &registration.contributor,
+    ) {
+        Ok(Ok(())) => Ok(()),
+        Err(Ok(error)) => Err(error),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_registry_accepts_only_proofs_the_service_approved() {
+    let setup = setup();
+    let valid = registration(&setup.env, 1);
+    let mut forged = registration(&setup.env, 2);
+    forged.zk_proof.set(63, forged.zk_proof.get(63).unwrap() ^ 1);
+
+    // A valid proof is rejected until the service approves it
+    assert_eq!(register(&setup, &valid), Err(study_registry::Error::InvalidZKProof));
+    assert!(review(&setup, &valid));
+
+    // Act
+    assert_eq!(register(&setup, &valid), Ok(()));
+
+    // Assert: the forged proof fails the off-chain check and is never
+    // approved
+    assert!(setup.registry.dataset_exists(&valid.dataset_hash));
+    assert!(!review(&setup, &forged));
+    assert_eq!(register(&setup, &forged), Err(study_registry::Error::InvalidZKProof));
+}
+
+#[test]
+fn test_approvals_cover_one_key_and_expire() {
+    let setup = setup();
+    let env = &setup.env;
+    let registration = registration(env, 1);
+    let vk = setup.registry.get_verifying_key(&testutils::CIRCUIT, &None).unwrap();
+    let signals = testutils::signals(
+        env,
+        &registration.dataset_hash,
+        &registration.attestation,
+        &registration.contributor,
+    );
+    assert!(review(&setup, &registration));
+
+    // Act & Assert: the approval does not carry over to another key
+    assert_eq!(setup.verifier.try_verify(&vk, &registration.zk_proof, &signals), Ok(Ok(())));
+    let mut other_key = vk.clone();
+    other_key.set(0, other_key.get(0).unwrap() ^ 1);
+    assert_eq!(
+        setup.verifier.try_verify(&other_key, &registration.zk_proof, &signals),
+        Err(Ok(Error::NotApproved))
+    );
+
+    // Approvals lapse after APPROVAL_TTL ledgers; the contracts themselves
+    // stay live
+    let digest = setup.verifier.proof_digest(&vk, &registration.zk_proof, &signals);
+    for contract in [&setup.verifier.address, &setup.registry.address] {
+        env.as_contract(contract, || {
+            env.storage().instance().extend_ttl(APPROVAL_TTL * 2, APPROVAL_TTL * 2);
+        });
+    }
+    env.ledger().with_mut(|ledger| ledger.sequence_number += APPROVAL_TTL + 1);
+    assert!(!setup.verifier.is_approved(&digest));
+    assert_eq!(register(&setup, &registration), Err(study_registry::Error::InvalidZKProof));
+}
+
+#[test]
+fn test_service_and_admin_gate_approvals() {
+    let env = Env::default();
+    let verifier = ProofVerifierClient::new(&env, &env.register_contract(None, ProofVerifier));
+    let admin = Address::generate(&env);
+    let service = Address::generate(&env);
+    let digest = BytesN::from_array(&env, &[7u8; 32]);
+    assert_eq!(verifier.try_approve(&digest), Err(Ok(Error::NotInitialized)));
+    verifier.init(&admin, &service);
+    assert_eq!(
+        verifier.try_init(&admin, &service),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    // Act & Assert: nobody else approves or replaces the service
+    assert!(verifier.try_approve(&digest).is_err());
+    assert!(verifier.try_set_service(&Address::generate(&env)).is_err());
+    assert!(!verifier.is_approved(&digest));
+
+    env.mock_all_auths();
+    verifier.approve(&digest);
+    assert_eq!(env.auths()[0].0, service);
+    assert!(verifier.is_approved(&digest));
+    let replacement = Address::generate(&env);
+    verifier.set_service(&replacement);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(verifier.get_service(), replacement);
+    assert_eq!(
+        verifier.try_verify(&Bytes::new(&env), &Bytes::new(&env), &Vec::new(&env)),
+        Err(Ok(Error::NotApproved))
+    );
+}
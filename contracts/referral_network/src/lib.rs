@@ -0,0 +1,238 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
+const REFERRER_KEY: Symbol = symbol_short!("REFERRER");
+const REFERRALS_KEY: Symbol = symbol_short!("REFERRALS");
+
+/// Basis point denominator
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Maximum number of upline levels paid on a payout
+pub const MAX_LEVELS: u32 = 2;
+
+/// Maximum carve-out (basis points of the platform share) routed to a chain
+pub const MAX_CARVE_OUT_BPS: u32 = 2_000;
+
+/// TTL policy for referral links, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Referral payout configuration
+///
+/// - carve_out_bps: Share of the platform payout routed to the referral
+///   chain (at most MAX_CARVE_OUT_BPS)
+/// - level_weights: Split of the carve-out per level, direct referrer
+///   first (at most MAX_LEVELS entries summing to at most BPS_DENOMINATOR;
+///   the unassigned rest stays with the treasury)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralConfig {
+    pub carve_out_bps: u32,
+    pub level_weights: Vec<u32>,
+}
+
+/// Share of a contributor's platform payout owed to one referrer
+///
+/// - referrer: Upline address to pay
+/// - level: 1 for the direct referrer, 2 for its referrer
+/// - share_bps: Basis points of the platform share (carve-out x weight)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralShare {
+    pub referrer: Address,
+    pub level: u32,
+    pub share_bps: u32,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidConfig = 3,
+    SelfReferral = 4,
+    AlreadyReferred = 5,
+    ReferralCycle = 6,
+}
+
+#[contract]
+pub struct ReferralNetwork;
+
+#[contractimpl]
+impl ReferralNetwork {
+    /// Initialize the ReferralNetwork contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to change the configuration
+    /// * `config` - Initial carve-out and level weights
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if called twice or the configuration is invalid
+    pub fn init(env: Env, admin: Address, config: ReferralConfig) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        Self::validate_config(&config)?;
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&CONFIG_KEY, &config);
+        Ok(())
+    }
+
+    /// Replace the carve-out and level weights
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidConfig)` if a cap is exceeded
+    pub fn set_config(env: Env, config: ReferralConfig) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::validate_config(&config)?;
+        env.storage().instance().set(&CONFIG_KEY, &config);
+        Ok(())
+    }
+
+    /// Get the carve-out and level weights
+    pub fn get_config(env: Env) -> Result<ReferralConfig, Error> {
+        env.storage()
+            .instance()
+            .get(&CONFIG_KEY)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Record who referred an address
+    ///
+    /// Each address can be referred once. Links that would close a loop
+    /// (the referee is already in the referrer's upline) are rejected, so
+    /// walking the upline always terminates.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `referee` - Address joining through the referral (must authorize)
+    /// * `referrer` - Address that referred it
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the link is a self-referral, duplicate or cycle
+    pub fn register_referral(env: Env, referee: Address, referrer: Address) -> Result<(), Error> {
+        referee.require_auth();
+        if referee == referrer {
+            return Err(Error::SelfReferral);
+        }
+
+        let storage = env.storage().persistent();
+        let referee_key = (REFERRER_KEY, referee.clone());
+        if storage.has(&referee_key) {
+            return Err(Error::AlreadyReferred);
+        }
+
+        // ============================================
+        // ANTI-CYCLE CHECK
+        // ============================================
+        // The upline is acyclic, so this walk ends at a root
+        let mut current = referrer.clone();
+        while let Some(upline) = storage.get::<_, Address>(&(REFERRER_KEY, current)) {
+            if upline == referee {
+                return Err(Error::ReferralCycle);
+            }
+            current = upline;
+        }
+
+        storage.set(&referee_key, &referrer);
+        storage.extend_ttl(&referee_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        let referrals_key = (REFERRALS_KEY, referrer.clone());
+        let referrals: u32 = storage.get(&referrals_key).unwrap_or(0);
+        storage.set(&referrals_key, &(referrals + 1));
+        storage.extend_ttl(&referrals_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        env.events()
+            .publish((symbol_short!("Referred"), referrer), referee);
+        Ok(())
+    }
+
+    /// Get the direct referrer of an address
+    pub fn get_referrer(env: Env, referee: Address) -> Option<Address> {
+        env.storage().persistent().get(&(REFERRER_KEY, referee))
+    }
+
+    /// Get the number of addresses directly referred by an address
+    pub fn referral_count(env: Env, referrer: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(REFERRALS_KEY, referrer))
+            .unwrap_or(0)
+    }
+
+    /// Get the referral shares owed on a contributor's platform payout
+    ///
+    /// Called by RevenueSplitter on every payout. Walks at most
+    /// MAX_LEVELS levels up the contributor's upline; levels without a
+    /// referrer or weight are skipped.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor being paid
+    ///
+    /// # Returns
+    /// * Shares, direct referrer first (empty if the contributor was not
+    ///   referred)
+    pub fn get_payout_chain(env: Env, contributor: Address) -> Vec<ReferralShare> {
+        let mut shares = Vec::new(&env);
+        let config = match Self::get_config(env.clone()) {
+            Ok(config) => config,
+            Err(_) => return shares,
+        };
+
+        let mut current = contributor;
+        for (index, weight) in config.level_weights.iter().enumerate() {
+            let referrer = match Self::get_referrer(env.clone(), current) {
+                Some(referrer) => referrer,
+                None => break,
+            };
+            let share_bps = config.carve_out_bps * weight / BPS_DENOMINATOR;
+            if share_bps > 0 {
+                shares.push_back(ReferralShare {
+                    referrer: referrer.clone(),
+                    level: index as u32 + 1,
+                    share_bps,
+                });
+            }
+            current = referrer;
+        }
+        shares
+    }
+
+    fn validate_config(config: &ReferralConfig) -> Result<(), Error> {
+        if config.carve_out_bps > MAX_CARVE_OUT_BPS || config.level_weights.len() > MAX_LEVELS {
+            return Err(Error::InvalidConfig);
+        }
+        let total = config
+            .level_weights
+            .iter()
+            .fold(0u32, |total, weight| total.saturating_add(weight));
+        if total > BPS_DENOMINATOR {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
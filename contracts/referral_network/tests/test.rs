@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+use referral_network::{
+    Error, ReferralConfig, ReferralNetwork, ReferralNetworkClient, MAX_CARVE_OUT_BPS,
+};
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Dataset price used by the tests (10 USDC)
+const PRICE: i128 = 10_0000000;
+
+/// Platform share of PRICE for a single contributor (15%)
+const PLATFORM_AMOUNT: i128 = PRICE * 15 / 100;
+
+struct Setup<'a> {
+    env: Env,
+    network: ReferralNetworkClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::StellarAssetClient<'a>,
+    treasury: Address,
+    contributor: Address,
+    dataset_id: Bytes,
+}
+
+/// Helper: Deploy the core contracts wired to a ReferralNetwork paying
+/// 20% of the platform share, split 70/30 over two levels
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+    splitter.init(&Address::generate(&env), &usdc_id, &treasury);
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let network = ReferralNetworkClient::new(&env, &env.register_contract(None, ReferralNetwork));
+    network.init(
+        &Address::generate(&env),
+        &ReferralConfig {
+            carve_out_bps: MAX_CARVE_OUT_BPS,
+            level_weights: vec![&env, 7_000, 3_000],
+        },
+    );
+    splitter.set_referral_network(&network.address);
+
+    let contributor = Address::generate(&env);
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from_array(&env, &[1u8; 32])],
+        &PRICE,
+        &None,
+    );
+
+    Setup {
+        usdc: token::StellarAssetClient::new(&env, &usdc_id),
+        env,
+        network,
+        marketplace,
+        splitter,
+        treasury,
+        contributor,
+        dataset_id,
+    }
+}
+
+/// Helper: Fund a fresh buyer and buy the test dataset
+fn buy(s: &Setup) {
+    let buyer = Address::generate(&s.env);
+    s.usdc.mint(&buyer, &PRICE);
+    token::Client::new(&s.env, &s.usdc.address).approve(
+        &buyer,
+        &s.marketplace.address,
+        &PRICE,
+        &1_000,
+    );
+    s.marketplace.purchase_dataset(&s.dataset_id, &buyer, &None);
+}
+
+fn balance(s: &Setup, address: &Address) -> i128 {
+    token::Client::new(&s.env, &s.usdc.address).balance(address)
+}
+
+#[test]
+fn test_payout_routes_carve_out_across_two_levels() {
+    let s = setup();
+    let direct = Address::generate(&s.env);
+    let upline = Address::generate(&s.env);
+    let beyond = Address::generate(&s.env);
+    s.network.register_referral(&upline, &beyond);
+    s.network.register_referral(&direct, &upline);
+    s.network.register_referral(&s.contributor, &direct);
+
+    // Act
+    let preview =
+        s.splitter
+            .preview_payout(&s.dataset_id, &vec![&s.env, s.contributor.clone()], &PRICE);
+    buy(&s);
+
+    // Assert: 20% of the platform share, 70% / 30%, nothing past level 2
+    let carve_out = PLATFORM_AMOUNT * 20 / 100;
+    assert_eq!(balance(&s, &direct), carve_out * 70 / 100);
+    assert_eq!(balance(&s, &upline), carve_out * 30 / 100);
+    assert_eq!(balance(&s, &beyond), 0);
+    assert_eq!(balance(&s, &s.treasury), PLATFORM_AMOUNT - carve_out);
+    assert_eq!(preview.network_amount, carve_out);
+    assert_eq!(preview.treasury_amount, PLATFORM_AMOUNT - carve_out);
+}
+
+#[test]
+fn test_missing_levels_stay_with_treasury() {
+    let s = setup();
+    let direct = Address::generate(&s.env);
+    s.network.register_referral(&s.contributor, &direct);
+
+    // Act
+    buy(&s);
+
+    // Assert: only the level 1 share is paid out
+    let level_one = PLATFORM_AMOUNT * 20 / 100 * 70 / 100;
+    assert_eq!(balance(&s, &direct), level_one);
+    assert_eq!(balance(&s, &s.treasury), PLATFORM_AMOUNT - level_one);
+    assert_eq!(s.network.referral_count(&direct), 1);
+}
+
+#[test]
+fn test_register_referral_rejects_cycles() {
+    let s = setup();
+    let a = Address::generate(&s.env);
+    let b = Address::generate(&s.env);
+    let c = Address::generate(&s.env);
+    s.network.register_referral(&b, &a);
+    s.network.register_referral(&c, &b);
+
+    // Act & Assert
+    assert_eq!(
+        s.network.try_register_referral(&a, &c),
+        Err(Ok(Error::ReferralCycle))
+    );
+    assert_eq!(
+        s.network.try_register_referral(&a, &a),
+        Err(Ok(Error::SelfReferral))
+    );
+    assert_eq!(
+        s.network.try_register_referral(&c, &a),
+        Err(Ok(Error::AlreadyReferred))
+    );
+    assert_eq!(s.network.get_referrer(&a), None);
+}
+
+#[test]
+fn test_config_is_capped() {
+    let s = setup();
+
+    // Act & Assert
+    for config in [
+        ReferralConfig {
+            carve_out_bps: MAX_CARVE_OUT_BPS + 1,
+            level_weights: vec![&s.env, 10_000],
+        },
+        ReferralConfig {
+            carve_out_bps: 1_000,
+            level_weights: vec![&s.env, 5_000, 3_000, 2_000],
+        },
+        ReferralConfig {
+            carve_out_bps: 1_000,
+            level_weights: vec![&s.env, 6_000, 5_000],
+        },
+    ] {
+        assert_eq!(
+            s.network.try_set_config(&config),
+            Err(Ok(Error::InvalidConfig))
+        );
+    }
+}
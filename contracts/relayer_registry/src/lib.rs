@@ -0,0 +1,150 @@
+#![no_std]
+use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env, Symbol, Vec};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const RELAYER_KEY: Symbol = symbol_short!("RELAYER");
+const RELAYERS_KEY: Symbol = symbol_short!("RELAYERS");
+const NONCE_KEY: Symbol = symbol_short!("NONCE");
+
+/// TTL policy for signer nonces, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    RelayerNotAllowed = 3,
+    InvalidNonce = 4,
+}
+
+/// Allowlist of fee-sponsoring relayers and per-signer replay nonces
+///
+/// A sponsored call is submitted (and its fees paid) by a relayer, while
+/// the contributor only signs a Soroban auth entry for the target call.
+/// Target contracts (StudyRegistry, InsurancePool) consume the signer's
+/// next nonce here before acting, so each signed call runs at most once
+/// and only through an allowlisted relayer.
+#[contract]
+pub struct RelayerRegistry;
+
+#[contractimpl]
+impl RelayerRegistry {
+    /// Initialize the RelayerRegistry contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage the relayer allowlist
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Allow an address to submit sponsored calls
+    pub fn add_relayer(env: Env, relayer: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if !storage.has(&(RELAYER_KEY, relayer.clone())) {
+            storage.set(&(RELAYER_KEY, relayer.clone()), &true);
+            let mut relayers = Self::get_relayers(env.clone());
+            relayers.push_back(relayer.clone());
+            storage.set(&RELAYERS_KEY, &relayers);
+        }
+        env.events().publish((symbol_short!("RelayAdd"),), relayer);
+        Ok(())
+    }
+
+    /// Revoke a relayer; signed calls it has not submitted yet can still
+    /// be submitted by another allowlisted relayer
+    pub fn remove_relayer(env: Env, relayer: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.remove(&(RELAYER_KEY, relayer.clone()));
+        let mut relayers = Self::get_relayers(env.clone());
+        if let Some(index) = relayers.first_index_of(&relayer) {
+            relayers.remove(index);
+            storage.set(&RELAYERS_KEY, &relayers);
+        }
+        env.events().publish((symbol_short!("RelayDel"),), relayer);
+        Ok(())
+    }
+
+    /// Check whether an address is an allowlisted relayer
+    pub fn is_relayer(env: Env, relayer: Address) -> bool {
+        env.storage().instance().has(&(RELAYER_KEY, relayer))
+    }
+
+    /// Get all allowlisted relayers
+    pub fn get_relayers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&RELAYERS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the nonce the next sponsored call of a signer must carry
+    pub fn next_nonce(env: Env, signer: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(NONCE_KEY, signer))
+            .unwrap_or(0)
+    }
+
+    /// Consume a signer's nonce for a sponsored call
+    ///
+    /// Called by the target contract. The signer's authorization covers
+    /// the relayer and nonce, so a nonce can only be burned by a call the
+    /// signer actually signed.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `relayer` - Allowlisted relayer submitting the call (must authorize)
+    /// * `signer` - Contributor the call acts for (must authorize)
+    /// * `nonce` - Must equal `next_nonce(signer)`
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the relayer is not allowlisted or the nonce is stale
+    pub fn use_nonce(env: Env, relayer: Address, signer: Address, nonce: u64) -> Result<(), Error> {
+        if !Self::is_relayer(env.clone(), relayer.clone()) {
+            return Err(Error::RelayerNotAllowed);
+        }
+        relayer.require_auth();
+        signer.require_auth();
+
+        if nonce != Self::next_nonce(env.clone(), signer.clone()) {
+            return Err(Error::InvalidNonce);
+        }
+        let key = (NONCE_KEY, signer.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, &(nonce + 1));
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        env.events()
+            .publish((symbol_short!("Relayed"), signer), (relayer, nonce));
+        Ok(())
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
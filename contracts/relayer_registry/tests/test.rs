@@ -0,0 +1,187 @@
+#![cfg(test)]
+
+use insurance_pool::{InsurancePool, InsurancePoolClient};
+use relayer_registry::{Error, RelayerRegistry, RelayerRegistryClient};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, AuthorizedFunction},
+    Address, Bytes, BytesN, Env, IntoVal, Symbol,
+};
+use study_registry::{StudyRegistry, StudyRegistryClient};
+
+struct Setup<'a> {
+    env: Env,
+    relay: RelayerRegistryClient<'a>,
+    registry: StudyRegistryClient<'a>,
+    insurance: InsurancePoolClient<'a>,
+    relayer: Address,
+    contributor: Address,
+}
+
+/// Helper: Deploy a RelayerRegistry with one relayer, wired to a
+/// StudyRegistry and an InsurancePool
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let relay = RelayerRegistryClient::new(&env, &env.register_contract(None, RelayerRegistry));
+    relay.init(&Address::generate(&env));
+    let relayer = Address::generate(&env);
+    relay.add_relayer(&relayer);
+
+    let registry = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    registry.set_relayer_registry(&relay.address);
+
+    let insurance = InsurancePoolClient::new(&env, &env.register_contract(None, InsurancePool));
+    insurance.init(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+    insurance.set_relayer_registry(&relay.address);
+
+    Setup {
+        contributor: Address::generate(&env),
+        env,
+        relay,
+        registry,
+        insurance,
+        relayer,
+    }
+}
+
+/// Helper: Register a study through the relayer
+fn register(
+    s: &Setup,
+    relayer: &Address,
+    nonce: u64,
+    seed: u8,
+) -> Result<(), Result<study_registry::Error, soroban_sdk::InvokeError>> {
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &s.contributor);
+    s.registry
+        .try_register_study_sponsored(
+            relayer,
+            &nonce,
+            &study_hash,
+            &attestation,
+            &study_registry::ProofKind::Groth16,
+            &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &s.contributor),
+            &s.contributor,
+        )
+        .map(|_| ())
+}
+
+#[test]
+fn test_sponsored_registration_is_signed_by_contributor() {
+    let s = setup();
+
+    // Act
+    assert_eq!(register(&s, &s.relayer, 0, 1), Ok(()));
+
+    // Assert: the contributor signed the registration and the relayer
+    // authorized it as the submitting account
+    let study_hash = BytesN::from_array(&s.env, &[1u8; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, &s.contributor);
+    let zk_proof =
+        study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, &s.contributor);
+    let auths = s.env.auths();
+    let signed = |address: &Address| {
+        auths.iter().any(|(signer, invocation)| {
+            signer == address
+                && invocation.function
+                    == AuthorizedFunction::Contract((
+                        s.registry.address.clone(),
+                        Symbol::new(&s.env, "register_study_sponsored"),
+                        (
+                            s.relayer.clone(),
+                            0u64,
+                            study_hash.clone(),
+                            attestation.clone(),
+                            study_registry::ProofKind::Groth16,
+                            zk_proof.clone(),
+                            s.contributor.clone(),
+                        )
+                            .into_val(&s.env),
+                    ))
+        })
+    };
+    assert!(signed(&s.contributor));
+    assert!(signed(&s.relayer));
+
+    let study = s
+        .registry
+        .get_study(&BytesN::from_array(&s.env, &[1u8; 32]));
+    assert_eq!(study.contributor, s.contributor);
+    assert_eq!(s.relay.next_nonce(&s.contributor), 1);
+}
+
+#[test]
+fn test_replayed_nonce_is_rejected() {
+    let s = setup();
+    register(&s, &s.relayer, 0, 1).unwrap();
+
+    // Act & Assert: the same signed nonce cannot run twice, even for a
+    // different study
+    assert_eq!(
+        register(&s, &s.relayer, 0, 2),
+        Err(Ok(study_registry::Error::InvalidNonce))
+    );
+    assert_eq!(
+        register(&s, &s.relayer, 2, 2),
+        Err(Ok(study_registry::Error::InvalidNonce))
+    );
+    assert_eq!(register(&s, &s.relayer, 1, 2), Ok(()));
+    assert_eq!(
+        s.relay.try_use_nonce(&s.relayer, &s.contributor, &1),
+        Err(Ok(Error::InvalidNonce))
+    );
+}
+
+#[test]
+fn test_only_allowlisted_relayers_can_submit() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+
+    // Act & Assert
+    assert_eq!(
+        register(&s, &stranger, 0, 1),
+        Err(Ok(study_registry::Error::RelayerNotAllowed))
+    );
+
+    s.relay.remove_relayer(&s.relayer);
+    assert!(s.relay.get_relayers().is_empty());
+    assert_eq!(
+        register(&s, &s.relayer, 0, 1),
+        Err(Ok(study_registry::Error::RelayerNotAllowed))
+    );
+
+    // Nothing was consumed by the rejected calls
+    assert_eq!(s.relay.next_nonce(&s.contributor), 0);
+}
+
+#[test]
+fn test_sponsored_claim_shares_the_nonce_sequence() {
+    let s = setup();
+    let seller = Address::generate(&s.env);
+
+    // Act
+    let claim_id = s.insurance.file_claim_sponsored(
+        &s.relayer,
+        &0,
+        &s.contributor,
+        &seller,
+        &Bytes::from_slice(&s.env, b"dataset_001"),
+        &Bytes::from_slice(&s.env, b"evidence"),
+        &10_0000000,
+    );
+
+    // Assert
+    assert_eq!(s.insurance.get_claim(&claim_id).buyer, s.contributor);
+    assert_eq!(
+        register(&s, &s.relayer, 0, 1),
+        Err(Ok(study_registry::Error::InvalidNonce))
+    );
+    assert_eq!(register(&s, &s.relayer, 1, 1), Ok(()));
+}
@@ -1,31 +1,429 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Env, Symbol, Address, 
-    Bytes, Vec, I128, token,
+    contract, contracterror, contractimpl, contractmeta, contracttype, symbol_short, Env, Symbol, Address, 
+    Bytes, BytesN, Map, String, Vec, token, IntoVal, TryFromVal, Val,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
 };
+use biochain_pricing::{math, PricingError, BPS_DENOMINATOR};
+use biochain_ids::{IdError, PayoutId, PayoutLeaf, STRKEY_LEN};
+pub use biochain_types::PayoutBreakdown;
 
-/// Storage keys
+/// Storage keys used before schema 3
+/// 
+/// Only read through `DataKey::legacy` so entries written by older code
+/// stay reachable.
 const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
 const TREASURY_KEY: Symbol = symbol_short!("TREASURY");
+const STATS_KEY: Symbol = symbol_short!("STATS");
+const INSURANCE_KEY: Symbol = symbol_short!("INSURANCE");
+const ROYALTY_KEY: Symbol = symbol_short!("ROYALTY");
+const INBOX_KEY: Symbol = symbol_short!("INBOX");
+const UPGRADER_KEY: Symbol = symbol_short!("UPGRADER");
+const PAUSED_KEY: Symbol = symbol_short!("PAUSED");
+const SCHEMA_KEY: Symbol = symbol_short!("SCHEMA");
+const AUDIT_KEY: Symbol = symbol_short!("AUDIT");
+const BREAKER_KEY: Symbol = symbol_short!("BREAKER");
+const REFERRAL_NET_KEY: Symbol = symbol_short!("REF_NET");
+const EARNINGS_KEY: Symbol = symbol_short!("EARNINGS");
+
+/// Interface version checked by the UpgradeCoordinator after an upgrade
+/// 
+/// - 2: Adds `payout_weighted`
+/// - 3: `payout_for_dataset` takes the purchase amount and divides it
+///   instead of paying a fixed reward per contributor
+/// - 4: Adds `set_split` and `get_split`
+/// - 5: Adds `set_payout_mode`, `get_payout_mode` and `claim`
+/// - 6: Adds `get_pending_balance`, `get_lifetime_earnings`,
+///   `get_total_distributed` and `get_platform_accrued`
+/// - 7: Adds `payout_batch` and `claim_many`
+/// - 8: Adds `init_with_caller`, `set_authorized_caller` and
+///   `get_authorized_caller`; `payout_for_dataset` requires the
+///   authorized caller
+/// - 9: `init` and `init_with_caller` take the admin and cannot be called
+///   twice; adds `get_admin`, `propose_admin`, `accept_admin` and
+///   `get_pending_admin`;
+///   `set_split`, `set_payout_mode` and `set_authorized_caller` require
+///   the admin instead of the UpgradeCoordinator
+/// - 10: Adds `payout_weighted_in`, `claim_in`, `get_earnings_in` and
+///   `get_payout_totals_in`
+/// - 11: Adds `claw_back`
+/// - 12: `payout_for_dataset` takes each contributor's study count and
+///   pays by weight instead of in equal shares
+/// - 13: Adds `set_payout_limits` and `get_payout_limits`
+/// - 14: Adds `emergency_pause`, `emergency_unpause` and
+///   `is_emergency_paused`
+/// - 15: The payout limits also apply to `payout_batch` and
+///   `commit_payout_root`
+/// - 16: `set_circuit_breaker`, `set_audit_log`, `set_guardian_set`,
+///   `set_token_allowlist`, `set_onboarding`, `set_credit_token` and
+///   `set_queue_tip` require the admin; only payouts by the authorized caller notify contributors,
+///   push stats and mint contribution credit
+/// - 17: Merkle payout commitments are keyed by payer and root;
+///   `claim_from_root`, `get_payout_root` and `is_root_claimed` take the
+///   payer
+/// - 18: `AnnualEarnings` reports credited rewards as `withheld` and no
+///   longer has `donated`
+/// - 19: `ContributorEarnings` no longer has `held`
+/// - 20: The emergency pause fails calls with `Error::Paused`; drops
+///   `Error::ContractPaused`
+/// - 21: `payout_weighted_in` returns the payout's sequence; `claw_back`
+///   takes that sequence instead of the weights and amount, and reclaims
+///   only what the payout credited
+/// - 22: Adds `set_reputation_contract`
+pub const INTERFACE_VERSION: u32 = 22;
+
+// Contract metadata embedded in the WASM custom section
+contractmeta!(key = "Description", val = "Splits dataset sale revenue between contributors and the BioChain treasury");
+
+/// Storage schema version written by this code
+/// 
+/// - 1: Configuration addresses only; no per-payout records are stored
+/// - 2: Per-contributor ContributorEarnings in persistent storage (missing
+///   entries read as zero, so no data needs transforming)
+/// - 3: All entries keyed by `DataKey` instead of symbol tuples
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Storage keys
+/// 
+/// Each record type has its own variant, so keys of different records
+/// cannot collide:
+/// - Earnings: Per-contributor earnings in persistent storage
+/// - TokenEarnings / TokenTotals: Per-contributor earnings and payout
+///   totals in tokens other than the configured USDC token, in persistent
+///   storage
+/// - AnnualEarnings: Per-contributor, per-calendar-year earnings in
+///   persistent storage
+/// - PayoutSeq: Per-dataset payout counter in persistent storage
+/// - TreasuryReceived: Per-token treasury share paid, in persistent storage
+/// - Totals: PayoutTotals across all payouts, in persistent storage
+/// - PayoutRoot / RootClaim: Merkle payout commitments by payer and root,
+///   and the contributors that claimed from them, in persistent storage
+/// - QueuedPayout: Deferred payouts by queue position, in persistent storage
+/// - QueuedToken: Token of a deferred payout not paid in the configured
+///   USDC token, by queue position, in persistent storage
+/// - QueuedSelfFunded: Marks a deferred payout not made by the
+///   authorized caller, by queue position, in persistent storage
+/// - PayoutCredits: Rewards the authorized caller's payouts credited, by
+///   dataset and payout sequence, in persistent storage
+/// - Claims: Number of claims per contributor and token, in persistent
+///   storage
+/// - CreditToken / Reputation: ContributionCredit and ContributorReputation
+///   addresses in instance storage
+/// - Split: Contributor and platform basis points in instance storage
+/// - PayoutMode: How contributor rewards are paid, in instance storage
+/// - PayoutLimits: Minimum transfer and per-purchase reward cap, in
+///   instance storage
+/// - EmergencyPaused: Whether the admin halted payouts and claims, in
+///   instance storage
+/// - AuthorizedCaller: Contract allowed to call `payout_for_dataset`
+///   (the DatasetMarketplace), in instance storage
+/// - Admin / PendingAdmin: Current admin and the admin proposed to take
+///   over, in instance storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Earnings(Address),
+    AnnualEarnings(Address, u32),
+    PayoutSeq(Bytes),
+    TreasuryReceived(Address),
+    Totals,
+    PayoutRoot(Address, BytesN<32>),
+    RootClaim(Address, BytesN<32>, Address),
+    QueuedPayout(u64),
+    UsdcToken,
+    Treasury,
+    Stats,
+    Insurance,
+    Royalty,
+    Inbox,
+    Upgrader,
+    Paused,
+    Schema,
+    AuditLog,
+    Breaker,
+    ReferralNetwork,
+    CreditToken,
+    QueueHead,
+    QueueTail,
+    QueueTip,
+    TipPool,
+    Guardians,
+    TokenAllowlist,
+    Onboarding,
+    Split,
+    PayoutMode,
+    AuthorizedCaller,
+    Admin,
+    PendingAdmin,
+    TokenEarnings(Address, Address),
+    TokenTotals(Address),
+    QueuedToken(u64),
+    PayoutLimits,
+    EmergencyPaused,
+    QueuedSelfFunded(u64),
+    PayoutCredits(Bytes, u64),
+    Claims(Address, Address),
+    Reputation,
+}
+
+impl DataKey {
+    /// Key the same entry was stored under before schema 3
+    fn legacy(&self, env: &Env) -> Val {
+        match self {
+            DataKey::Earnings(contributor) => (EARNINGS_KEY, contributor.clone()).into_val(env),
+            // Added with schema 3, so there is no older key
+            DataKey::PayoutSeq(_)
+            | DataKey::AnnualEarnings(_, _)
+            | DataKey::TreasuryReceived(_)
+            | DataKey::Totals
+            | DataKey::PayoutRoot(_, _)
+            | DataKey::RootClaim(_, _, _)
+            | DataKey::QueuedPayout(_)
+            | DataKey::CreditToken
+            | DataKey::QueueHead
+            | DataKey::QueueTail
+            | DataKey::QueueTip
+            | DataKey::TipPool
+            | DataKey::Guardians
+            | DataKey::TokenAllowlist
+            | DataKey::Onboarding
+            | DataKey::Split
+            | DataKey::PayoutMode
+            | DataKey::AuthorizedCaller
+            | DataKey::Admin
+            | DataKey::PendingAdmin
+            | DataKey::TokenEarnings(_, _)
+            | DataKey::TokenTotals(_)
+            | DataKey::QueuedToken(_)
+            | DataKey::PayoutLimits
+            | DataKey::EmergencyPaused
+            | DataKey::QueuedSelfFunded(_)
+            | DataKey::PayoutCredits(_, _)
+            | DataKey::Claims(_, _)
+            | DataKey::Reputation => self.into_val(env),
+            DataKey::UsdcToken => USDC_TOKEN_KEY.into_val(env),
+            DataKey::Treasury => TREASURY_KEY.into_val(env),
+            DataKey::Stats => STATS_KEY.into_val(env),
+            DataKey::Insurance => INSURANCE_KEY.into_val(env),
+            DataKey::Royalty => ROYALTY_KEY.into_val(env),
+            DataKey::Inbox => INBOX_KEY.into_val(env),
+            DataKey::Upgrader => UPGRADER_KEY.into_val(env),
+            DataKey::Paused => PAUSED_KEY.into_val(env),
+            DataKey::Schema => SCHEMA_KEY.into_val(env),
+            DataKey::AuditLog => AUDIT_KEY.into_val(env),
+            DataKey::Breaker => BREAKER_KEY.into_val(env),
+            DataKey::ReferralNetwork => REFERRAL_NET_KEY.into_val(env),
+        }
+    }
+}
+
+/// Configuration entries moved to `DataKey` by the schema 3 migration
+const CONFIG_KEYS: [DataKey; 12] = [
+    DataKey::UsdcToken,
+    DataKey::Treasury,
+    DataKey::Stats,
+    DataKey::Insurance,
+    DataKey::Royalty,
+    DataKey::Inbox,
+    DataKey::Upgrader,
+    DataKey::Paused,
+    DataKey::Schema,
+    DataKey::AuditLog,
+    DataKey::Breaker,
+    DataKey::ReferralNetwork,
+];
+
+/// TTL policy for persistent records, in ledgers
+/// 
+/// Entries are extended to ~30 days once they drop below ~7 days.
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Contributors paid per transaction
+/// 
+/// Keeps a purchase with 40 contributors, whose registry lookups the
+/// marketplace pays for too, at about two thirds of the network limits
+/// (see the marketplace budget benchmarks); larger sets are finished by
+/// `process_next`.
+pub const MAX_CONTRIBUTORS_PER_BATCH: u32 = 20;
+
+/// Contributor share in basis points until `set_split` is called (85%)
+pub const DEFAULT_CONTRIBUTOR_BPS: u32 = 8_500;
+
+/// Platform share in basis points until `set_split` is called (15%)
+pub const DEFAULT_PLATFORM_BPS: u32 = 1_500;
+
+// The two shares must cover the whole reward
+const _: () = assert!(DEFAULT_CONTRIBUTOR_BPS + DEFAULT_PLATFORM_BPS == BPS_DENOMINATOR);
+
+/// Royalty terms as returned by RoyaltyRegistry.dataset_terms_or_default()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyTerms {
+    pub resale_bps: u32,
+    pub institutional_bps: u32,
+    pub institution: Option<Address>,
+    pub referral_bps: u32,
+    pub referrer: Option<Address>,
+    pub updated_at: u64,
+}
+
+/// Referral share as returned by ReferralNetwork.get_payout_chain()
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralShare {
+    pub referrer: Address,
+    pub level: u32,
+    pub share_bps: u32,
+}
+
+/// Running earnings of a contributor
+/// 
+/// - sales: Payouts received (one per purchase of a dataset including them)
+/// - total_earned: USDC paid or credited to the contributor, net of
+///   institutional royalty
+/// - claimable: USDC credited in `PayoutMode::Accrue`, or below the
+///   minimum payout, but not yet claimed
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContributorEarnings {
+    pub sales: u32,
+    pub total_earned: i128,
+    pub claimable: i128,
+}
+
+/// Reward a payout by the authorized caller credited to a contributor
+/// 
+/// Kept so that `claw_back` reclaims exactly what a refunded purchase
+/// credited.
+/// - amount: Reward credited, net of institutional royalty
+/// - claims: Claims the contributor had made in the payout's token when
+///   it was credited; any later claim withdrew the reward
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutCredit {
+    pub contributor: Address,
+    pub amount: i128,
+    pub claims: u32,
+}
+
+/// Running totals across all payouts, for frontends and auditors
+/// 
+/// - total_distributed: USDC paid or credited to contributors, net of
+///   institutional royalty
+/// - platform_accrued: Platform share of every payout (before referral
+///   shares and insurance premiums are carved out of it), plus rounding
+///   remainders paid to the treasury
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PayoutTotals {
+    pub total_distributed: i128,
+    pub platform_accrued: i128,
+}
+
+/// How contributor rewards are paid
+/// 
+/// - Push: Transferred to each contributor within the payout
+/// - Accrue: Credited to the contributor's `claimable` balance, held by
+///   this contract until the contributor calls `claim`; royalties, referral
+///   shares, insurance premiums and the treasury share are still transferred
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PayoutMode {
+    Push,
+    Accrue,
+}
 
-/// Base reward per contributor per purchase
-/// 10 USDC with 7 decimal places (Stellar standard)
-const BASE_REWARD: I128 = I128::from(10_0000000);
+/// Limits applied to contributor rewards
+/// 
+/// - min_payout: Smallest reward transferred in `PayoutMode::Push`;
+///   smaller rewards are credited to the contributor's claimable balance
+///   instead, so dust is not transferred (0 transfers every reward)
+/// - max_reward_per_purchase: Most of a purchase's amount shared among its
+///   contributors; the rest goes to the treasury (None for no cap)
+/// 
+/// Both are in units of the token a payout is made in.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PayoutLimits {
+    pub min_payout: i128,
+    pub max_reward_per_purchase: Option<i128>,
+}
 
-/// Contributor split percentage (85%)
-const CONTRIBUTOR_PERCENT: I128 = I128::from(85);
+/// Earnings of a contributor in one calendar year (UTC), for tax filing
+/// 
+/// - year: Calendar year of the ledger timestamps of the payouts
+/// - sales: Payouts received in the year
+/// - gross: Contributor share before the institutional royalty
+/// - earned: USDC paid or credited to the contributor (gross minus
+///   institutional royalty)
+/// - withheld: Part of `earned` credited to the claimable balance instead
+///   of transferred, in `PayoutMode::Accrue` or below the minimum payout
+/// 
+/// Amounts are counted in the year they are paid or credited; claiming a
+/// withheld amount later does not move it to another year.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnnualEarnings {
+    pub year: u32,
+    pub sales: u32,
+    pub gross: i128,
+    pub earned: i128,
+    pub withheld: i128,
+}
 
-/// Platform split percentage (15%)
-const PLATFORM_PERCENT: I128 = I128::from(15);
+/// Merkle payout commitment
+/// 
+/// For contributor sets too large to pay in one transaction, only the
+/// Merkle root of their `biochain_ids::PayoutLeaf` entries is stored and
+/// contributors claim their leaf with a proof.
+/// - contributor_total: Contributor share held for claims
+/// - claimed: Amount claimed so far
+/// - claims: Number of contributors that claimed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutCommitment {
+    pub dataset_id: Bytes,
+    pub root: BytesN<32>,
+    pub contributor_total: i128,
+    pub claimed: i128,
+    pub claims: u32,
+}
 
 /// Event data for ContributorRewarded event
+/// 
+/// `payout_id` is the SHA-256 of the `biochain_ids::PayoutId` preimage for
+/// the dataset, the payout sequence, the contributor and the amount they
+/// received, so back-office systems can rebuild it from the event.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ContributorRewarded {
     pub dataset_id: Bytes,
     pub contributor: Address,
-    pub user_amount: I128,
-    pub platform_amount: I128,
+    pub user_amount: i128,
+    pub platform_amount: i128,
+    pub payout_id: BytesN<32>,
+    pub sequence: u64,
+}
+
+/// Payout waiting in the queue for `process_next`
+/// 
+/// - remaining: Contributors still to be paid, in order, with their share
+///   (split by the configured split in effect when paid), held by this
+///   contract until paid
+/// - sequence: Payout sequence shared by every contributor of the payout
+/// - num_contributors: Size of the whole contributor set
+/// - total_user_amount / total_platform_amount: Paid so far
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedPayout {
+    pub dataset_id: Bytes,
+    pub remaining: Vec<(Address, i128)>,
+    pub sequence: u64,
+    pub num_contributors: u32,
+    pub total_user_amount: i128,
+    pub total_platform_amount: i128,
 }
 
 /// Event data for DatasetPayoutCompleted event
@@ -34,20 +432,70 @@ pub struct ContributorRewarded {
 pub struct DatasetPayoutCompleted {
     pub dataset_id: Bytes,
     pub num_contributors: u32,
-    pub total_user_amount: I128,
-    pub total_platform_amount: I128,
+    pub total_user_amount: i128,
+    pub total_platform_amount: i128,
 }
 
-/// Error types for the contract
+/// Configuration health of the contract
+/// 
+/// Returned by `health()` so deploy pipelines and monitoring can check the
+/// wiring in a single read:
+/// - token_set / treasury_set: Whether `init` configured them
+/// - upgrader_set / audit_log_set: Whether the corresponding contract
+///   address is configured
+/// - paused: Whether payouts are paused (including a tripped breaker and
+///   the emergency pause)
+/// - schema_version: Schema version of the stored data
+/// - ready: Whether payouts can be served
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Health {
+    pub token_set: bool,
+    pub treasury_set: bool,
+    pub upgrader_set: bool,
+    pub audit_log_set: bool,
+    pub paused: bool,
+    pub schema_version: u32,
+    pub ready: bool,
+}
+
+/// Error types for the contract
+/// 
+/// RevenueSplitter owns codes 200-299 so errors stay distinguishable when
+/// they surface through DatasetMarketplace calls.
+/// Codes are stable: never renumber, only append.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum Error {
-    NotInitialized,
-    InvalidContributors,
-    InvalidAmount,
-    TransferFailed,
-    TreasuryNotSet,
-    TokenNotSet,
+    NotInitialized = 200,
+    InvalidContributors = 201,
+    InvalidAmount = 202,
+    TransferFailed = 203,
+    TreasuryNotSet = 204,
+    TokenNotSet = 205,
+    Paused = 206,
+    Unauthorized = 207,
+    Overflow = 208,
+    RootAlreadyCommitted = 209,
+    RootNotFound = 210,
+    AlreadyClaimed = 211,
+    InvalidProof = 212,
+    QueueEmpty = 213,
+    TokenNotAccepted = 214,
+    NotOnboarded = 215,
+    InvalidSplit = 216,
+    NothingToClaim = 217,
+    BatchTooLarge = 218,
+    AlreadyInitialized = 219,
+    NoPendingAdmin = 220,
+    InvalidLimit = 221,
+}
+
+impl From<PricingError> for Error {
+    fn from(_: PricingError) -> Self {
+        Error::Overflow
+    }
 }
 
 #[contract]
@@ -58,186 +506,3070 @@ impl RevenueSplitter {
     /// Initialize the RevenueSplitter contract
     /// 
     /// This function must be called once after deployment to configure:
+    /// - Admin allowed to change the payout configuration
     /// - USDC token contract address
     /// - BioChain treasury address
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
+    /// * `admin` - Address of the admin
     /// * `usdc_token` - Address of the USDC token contract
     /// * `treasury` - Address of the BioChain treasury
     /// 
     /// # Returns
     /// * `Ok(())` if successful
-    /// * `Err(Error)` if initialization fails
+    /// * `Err(Error::AlreadyInitialized)` if the contract was initialized
+    ///   before, including by code from before admins were stored
     pub fn init(
         env: Env,
+        admin: Address,
         usdc_token: Address,
         treasury: Address,
     ) -> Result<(), Error> {
         let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin)
+            || Self::has_instance(&env, &DataKey::UsdcToken)
+            || Self::has_instance(&env, &DataKey::Treasury)
+        {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
         
         // Store USDC token address
-        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&DataKey::UsdcToken, &usdc_token);
         
         // Store treasury address
-        storage.set(&TREASURY_KEY, &treasury);
+        storage.set(&DataKey::Treasury, &treasury);
         
         Ok(())
     }
 
+    /// Initialize the RevenueSplitter with the contract allowed to call
+    /// `payout_for_dataset`
+    /// 
+    /// Same as `init`, and stores `authorized_caller` (normally the
+    /// DatasetMarketplace) as `set_authorized_caller` would.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address of the admin
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `treasury` - Address of the BioChain treasury
+    /// * `authorized_caller` - Address allowed to call `payout_for_dataset`
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if the contract was initialized
+    ///   before
+    pub fn init_with_caller(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        treasury: Address,
+        authorized_caller: Address,
+    ) -> Result<(), Error> {
+        Self::init(env.clone(), admin, usdc_token, treasury)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::AuthorizedCaller, &authorized_caller);
+        Ok(())
+    }
+
     /// Payout rewards for a dataset purchase
     /// 
-    /// This function is called by DatasetMarketplace after a successful purchase.
-    /// For each contributor in the dataset:
-    /// - Calculates fixed reward of 10 USDC per contributor
-    /// - Splits 85% to contributor, 15% to platform treasury
+    /// This function is called by DatasetMarketplace after a successful
+    /// purchase and pays out of this contract's balance, so it requires the
+    /// authorization of the authorized caller (see `set_authorized_caller`).
+    /// The caller transfers the purchase `amount` to this contract first;
+    /// it is divided among the contributors in the dataset by weight, the
+    /// number of studies each supplied:
+    /// - Each contributor's share is `amount * weight / total weight`, split
+    ///   between contributor and platform treasury by
+    ///   the configured split (85% / 15% by default, see `set_split`)
+    /// - The rounding remainder goes to the treasury, so no more than
+    ///   `amount` is ever paid out
     /// - Transfers USDC tokens
     /// - Emits events for notifications
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
     /// * `dataset_id` - ID of the purchased dataset
-    /// * `contributors` - Vector of contributor addresses (one per study in dataset)
+    /// * `weights` - Distinct contributors in the dataset with the number of
+    ///   studies each supplied
+    /// * `amount` - USDC paid for the purchase
     /// 
     /// # Returns
     /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no authorized caller is set
+    /// * `Err(Error::InvalidContributors)` if there are no contributors or a
+    ///   weight is zero
+    /// * `Err(Error::InvalidAmount)` if a share is too small to split
     /// * `Err(Error)` if validation or transfer fails
     pub fn payout_for_dataset(
         env: Env,
         dataset_id: Bytes,
-        contributors: Vec<Address>,
+        weights: Vec<(Address, u32)>,
+        amount: i128,
     ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        
         // ============================================
         // 1. VALIDATE INPUTS
         // ============================================
         
-        if contributors.len() == 0 {
+        if weights.is_empty() {
             return Err(Error::InvalidContributors);
         }
+        Self::require_onboarded(&env, weights.iter().map(|(contributor, _)| contributor))?;
         
         // ============================================
         // 2. LOAD CONFIGURATION
         // ============================================
-        let storage = env.storage().instance();
         
-        let usdc_token: Address = storage.get(&USDC_TOKEN_KEY)
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
             .ok_or(Error::TokenNotSet)?;
+        Self::require_accepted_token(&env, &usdc_token)?;
         
-        let treasury: Address = storage.get(&TREASURY_KEY)
+        let treasury: Address = Self::get_instance(&env, &DataKey::Treasury)
             .ok_or(Error::TreasuryNotSet)?;
         
+        // Only the authorized caller may spend this contract's balance
+        let caller: Address = Self::get_instance(&env, &DataKey::AuthorizedCaller)
+            .ok_or(Error::Unauthorized)?;
+        caller.require_auth();
+        
         // ============================================
         // 3. CALCULATE AMOUNTS
         // ============================================
-        // Base reward: 10 USDC per contributor
-        // Split: 85% contributor, 15% platform
+        // Shares of the purchase amount by studies supplied, split by the
+        // configured contributor / platform basis points
+        let (shares, remainder) = Self::split_weighted(&env, amount, &weights)?;
         
-        // Calculate user amount (85% of BASE_REWARD)
-        let user_amount = (BASE_REWARD * CONTRIBUTOR_PERCENT) / I128::from(100);
+        // ============================================
+        // 4. PAY CONTRIBUTORS
+        // ============================================
+        if remainder > 0 {
+            token::Client::new(&env, &usdc_token)
+                .transfer(&env.current_contract_address(), &treasury, &remainder);
+            Self::record_treasury(&env, &usdc_token, remainder)?;
+            Self::record_totals(&env, &usdc_token, 0, remainder)?;
+        }
+        Self::pay_contributors(&env, &usdc_token, &treasury, &dataset_id, &shares, false)?;
         
-        // Calculate platform amount (15% of BASE_REWARD)
-        let platform_amount = BASE_REWARD - user_amount;
+        Ok(())
+    }
+
+    /// Payout a purchase from the price paid by the buyer
+    /// 
+    /// Same as `payout_weighted` with every contributor weighted 1, so the
+    /// amount is divided equally among them.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is pulled from
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `contributors` - Vector of distinct contributor addresses in the dataset
+    /// * `amount` - USDC paid for the purchase
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if validation or transfer fails
+    pub fn payout_purchase(
+        env: Env,
+        payer: Address,
+        dataset_id: Bytes,
+        contributors: Vec<Address>,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut weights = Vec::new(&env);
+        for contributor in contributors.iter() {
+            weights.push_back((contributor, 1u32));
+        }
+        Self::payout_weighted(env, payer, dataset_id, weights, amount)
+    }
+
+    /// Payout a purchase by contributor weight
+    /// 
+    /// Called by DatasetMarketplace after it has collected the quoted price,
+    /// with each contributor's weight in the dataset. The `payer` (the
+    /// marketplace) authorizes the transfer of `amount` USDC into this
+    /// contract, which then pays it out in the same call, so no pre-funded
+    /// balance is involved:
+    /// - Each contributor's share is `amount * weight / total weight`, split
+    ///   by the configured split (royalties and insurance premiums
+    ///   apply as in `payout_for_dataset`); with a `max_reward_per_purchase`
+    ///   set, shares are computed on at most that amount
+    /// - The rounding remainder, or the whole amount when no contributor
+    ///   could be resolved, goes to the treasury
+    /// - Contributors beyond `MAX_CONTRIBUTORS_PER_BATCH` are queued, with
+    ///   their shares, for `process_next`
+    /// - Only a payout by the authorized caller is a sale: it notifies the
+    ///   contributors, pushes the payout to PlatformStats and mints
+    ///   contribution credit. Anyone else can fund a payout (self-funded),
+    ///   which pays the contributors without any of these
+    /// - The rewards a sale credits instead of transferring are recorded
+    ///   under the payout's sequence, for `claw_back`
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is pulled from
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `weights` - Distinct contributors in the dataset with their weights
+    /// * `amount` - USDC paid for the purchase
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidContributors)` if a weight is zero
+    /// * `Err(Error)` if validation or transfer fails
+    pub fn payout_weighted(
+        env: Env,
+        payer: Address,
+        dataset_id: Bytes,
+        weights: Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Result<(), Error> {
+        Self::payout_weighted_with(env, payer, None, dataset_id, weights, amount)?;
+        Ok(())
+    }
+
+    /// Payout a purchase by contributor weight in the token it was paid in
+    /// 
+    /// Same as `payout_weighted`, but `amount` is pulled and paid out in
+    /// `token`, e.g. for listings priced in XLM or EURC. Payouts in a token
+    /// other than the configured USDC token are tracked per token (see
+    /// `get_earnings_in` and `get_payout_totals_in`); no insurance premium
+    /// is carved out of them, and in `PayoutMode::Accrue` contributors
+    /// withdraw them with `claim_in`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Address the tokens are pulled from
+    /// * `token` - Token the purchase was paid in
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `weights` - Distinct contributors in the dataset with their weights
+    /// * `amount` - Amount of `token` paid for the purchase
+    /// 
+    /// # Returns
+    /// * `Ok(Some(u64))` with the payout's sequence for the dataset, which
+    ///   identifies it to `claw_back`
+    /// * `Ok(None)` if there were no contributors to pay
+    /// * `Err(Error::TokenNotAccepted)` if the TokenAllowlist refuses `token`
+    /// * `Err(Error)` if validation or transfer fails
+    pub fn payout_weighted_in(
+        env: Env,
+        payer: Address,
+        token: Address,
+        dataset_id: Bytes,
+        weights: Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Result<Option<u64>, Error> {
+        Self::payout_weighted_with(env, payer, Some(token), dataset_id, weights, amount)
+    }
+
+    /// Payout a purchase by weight in `token`, or in the configured USDC
+    /// token if `None`
+    /// 
+    /// # Returns
+    /// * `Ok(Option<u64>)` with the payout's sequence, if anyone was paid
+    fn payout_weighted_with(
+        env: Env,
+        payer: Address,
+        token: Option<Address>,
+        dataset_id: Bytes,
+        weights: Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Result<Option<u64>, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        payer.require_auth();
+        let self_funded = !Self::is_authorized_caller(&env, &payer);
         
-        // Validate amounts
-        if user_amount <= I128::from(0) || platform_amount <= I128::from(0) {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
+        Self::require_onboarded(&env, weights.iter().map(|(contributor, _)| contributor))?;
         
         // ============================================
-        // 4. INITIALIZE TOKEN CLIENT
+        // 2. LOAD CONFIGURATION
         // ============================================
-        let token_client = token::Client::new(&env, &usdc_token);
-        let contract_address = env.current_contract_address();
+        
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        let token = token.unwrap_or(usdc_token);
+        Self::require_accepted_token(&env, &token)?;
+        
+        let treasury: Address = Self::get_instance(&env, &DataKey::Treasury)
+            .ok_or(Error::TreasuryNotSet)?;
         
         // ============================================
-        // 5. PROCESS EACH CONTRIBUTOR
+        // 3. CALCULATE AMOUNTS
         // ============================================
-        let mut total_user_amount = I128::from(0);
-        let mut total_platform_amount = I128::from(0);
+        let (shares, remainder) = Self::split_weighted(&env, amount, &weights)?;
         
-        for contributor in contributors.iter() {
-            // Transfer user amount to contributor
-            token_client.transfer(
-                &contract_address,
-                contributor,
-                &user_amount,
-            );
-            
-            // Transfer platform amount to treasury
-            // Note: We transfer platform_amount for each contributor
-            // This ensures proper accounting per contributor
-            token_client.transfer(
-                &contract_address,
-                &treasury,
-                &platform_amount,
-            );
-            
-            // Accumulate totals
-            total_user_amount = total_user_amount + user_amount;
-            total_platform_amount = total_platform_amount + platform_amount;
-            
-            // ============================================
-            // 6. EMIT PER-CONTRIBUTOR EVENT
-            // ============================================
-            env.events().publish(
-                (
-                    symbol_short!("ContributorRewarded"),
-                    dataset_id.clone(),
-                    contributor.clone(),
-                ),
-                ContributorRewarded {
-                    dataset_id: dataset_id.clone(),
-                    contributor: contributor.clone(),
-                    user_amount,
-                    platform_amount,
-                },
-            );
+        // ============================================
+        // 4. COLLECT PAYMENT
+        // ============================================
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&payer, &env.current_contract_address(), &amount);
+        
+        // ============================================
+        // 5. DISTRIBUTE
+        // ============================================
+        if remainder > 0 {
+            token_client.transfer(&env.current_contract_address(), &treasury, &remainder);
+            Self::record_treasury(&env, &token, remainder)?;
+            Self::record_totals(&env, &token, 0, remainder)?;
+        }
+        if shares.is_empty() {
+            return Ok(None);
+        }
+        let sequence = Self::pay_contributors(&env, &token, &treasury, &dataset_id, &shares, self_funded)?;
+        Ok(Some(sequence))
+    }
+
+    /// Payout explicit amounts to many contributors in one transaction
+    /// 
+    /// Lets an operator settle a dataset's contributors with amounts
+    /// computed off-chain. The `payer` authorizes the transfer of the sum
+    /// of `amounts` into this contract, and each amount is paid out as that
+    /// contributor's share: split by the configured split, with royalties,
+    /// referral shares and insurance premiums applied as in
+    /// `payout_weighted`.
+    /// 
+    /// The payout limits apply as in `payout_weighted`: with a
+    /// `max_reward_per_purchase` set, amounts adding up to more are scaled
+    /// down in proportion and the excess goes to the treasury, and rewards
+    /// below `min_payout` are credited instead of transferred. Unless the
+    /// `payer` is the authorized caller, the payout is self-funded and, as
+    /// in `payout_weighted`, neither notifies nor mints credit.
+    /// 
+    /// The batch is all or nothing: an invalid entry rejects it before any
+    /// USDC moves, and a failing transfer reverts the whole payout.
+    /// Contributors beyond `MAX_CONTRIBUTORS_PER_BATCH` are queued for
+    /// `process_next`. In `PayoutMode::Accrue` no contributor transfer is
+    /// made, so one contributor cannot block the others; they withdraw with
+    /// `claim` or `claim_many`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is pulled from
+    /// * `dataset_id` - ID of the dataset (selects royalty terms)
+    /// * `contributors` - Distinct contributors to pay
+    /// * `amounts` - Share of each contributor, in the same order
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidContributors)` if the lists are empty, differ in
+    ///   length or repeat a contributor
+    /// * `Err(Error::InvalidAmount)` if an amount, once capped, is too small
+    ///   to split
+    pub fn payout_batch(
+        env: Env,
+        payer: Address,
+        dataset_id: Bytes,
+        contributors: Vec<Address>,
+        amounts: Vec<i128>,
+    ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        payer.require_auth();
+        let self_funded = !Self::is_authorized_caller(&env, &payer);
+        
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        if contributors.is_empty() || contributors.len() != amounts.len() {
+            return Err(Error::InvalidContributors);
+        }
+        let contributor_bps = Self::contributor_bps(&env);
+        let mut seen = Map::new(&env);
+        let mut shares = Vec::new(&env);
+        let mut total: i128 = 0;
+        for (contributor, share) in contributors.iter().zip(amounts.iter()) {
+            if seen.contains_key(contributor.clone()) {
+                return Err(Error::InvalidContributors);
+            }
+            seen.set(contributor.clone(), ());
+            let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+            if user_amount <= 0 || share - user_amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            total = math::checked_add(total, share)?;
+            shares.push_back((contributor, share));
         }
+        Self::require_onboarded(&env, contributors.iter())?;
+        let (shares, remainder) = Self::cap_shares(&env, shares, total)?;
         
         // ============================================
-        // 7. EMIT AGGREGATE DATASET EVENT
+        // 2. LOAD CONFIGURATION
         // ============================================
-        env.events().publish(
-            (
-                symbol_short!("DatasetPayoutCompleted"),
-                dataset_id.clone(),
-            ),
-            DatasetPayoutCompleted {
-                dataset_id: dataset_id.clone(),
-                num_contributors: contributors.len() as u32,
-                total_user_amount,
-                total_platform_amount,
-            },
-        );
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        Self::require_accepted_token(&env, &usdc_token)?;
+        
+        let treasury: Address = Self::get_instance(&env, &DataKey::Treasury)
+            .ok_or(Error::TreasuryNotSet)?;
+        
+        // ============================================
+        // 3. COLLECT PAYMENT AND DISTRIBUTE
+        // ============================================
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&payer, &env.current_contract_address(), &total);
+        if remainder > 0 {
+            token_client.transfer(&env.current_contract_address(), &treasury, &remainder);
+            Self::record_treasury(&env, &usdc_token, remainder)?;
+            Self::record_totals(&env, &usdc_token, 0, remainder)?;
+        }
+        Self::pay_contributors(&env, &usdc_token, &treasury, &dataset_id, &shares, self_funded)?;
         
         Ok(())
     }
 
-    /// Get the configured USDC token address
+    /// Payout an explicit amount among contributors
+    /// 
+    /// Used for rewards that are not tied to a purchase, such as DataBounty
+    /// rewards and MilestoneEscrow tranches. The `payer` authorizes the
+    /// transfer of `total_amount` USDC into this contract, which pays it
+    /// out in the same call, as `payout_purchase` would: the amount is
+    /// divided equally among the contributors and each share is split
+    /// between contributor and platform by the configured split (85% / 15%
+    /// by default), with the payout limits applied.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is pulled from
+    /// * `payout_id` - Identifier used in events (e.g. bounty ID)
+    /// * `contributors` - Vector of contributor addresses
+    /// * `total_amount` - Total USDC to distribute
     /// 
     /// # Returns
-    /// * `Ok(Address)` if configured
-    /// * `Err(Error::TokenNotSet)` if not initialized
-    pub fn get_usdc_token(env: Env) -> Result<Address, Error> {
-        let storage = env.storage().instance();
-        storage.get(&USDC_TOKEN_KEY)
-            .ok_or(Error::TokenNotSet)
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidContributors)` if `contributors` is empty
+    /// * `Err(Error)` if validation or transfer fails
+    pub fn payout_amount(
+        env: Env,
+        payer: Address,
+        payout_id: Bytes,
+        contributors: Vec<Address>,
+        total_amount: i128,
+    ) -> Result<(), Error> {
+        if contributors.is_empty() {
+            return Err(Error::InvalidContributors);
+        }
+        let mut weights = Vec::new(&env);
+        for contributor in contributors.iter() {
+            weights.push_back((contributor, 1u32));
+        }
+        Self::payout_weighted_with(env, payer, None, payout_id, weights, total_amount)?;
+        Ok(())
     }
 
-    /// Get the configured treasury address
+    /// Commit a purchase payout as a Merkle root of contributor amounts
+    /// 
+    /// Used instead of `payout_purchase` when the contributor set is too
+    /// large to pay, or even record, one by one. The marketplace or gateway
+    /// builds the tree off-chain: each leaf is the SHA-256 of a
+    /// `biochain_ids::PayoutLeaf` preimage (dataset, contributor, amount)
+    /// and inner nodes hash their two children, smaller first. The `payer`
+    /// authorizes the transfer of `amount`; the platform share of the
+    /// configured split goes to the treasury now and the contributor share
+    /// is held for `claim_from_root`. Leaves
+    /// should add up to that share; claims never exceed it. With a
+    /// `max_reward_per_purchase` set, the share is computed on at most that
+    /// amount and the excess goes to the treasury. Royalties, referrals,
+    /// insurance premiums and `min_payout` do not apply in this mode, since
+    /// contributors pull their own amounts. Commitments are keyed by payer
+    /// and root, so committing someone else's root first neither blocks
+    /// their commitment nor takes over its claims.
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
+    /// * `payer` - Address the USDC is pulled from
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `root` - Merkle root of the payout leaves
+    /// * `amount` - USDC paid for the purchase
     /// 
     /// # Returns
-    /// * `Ok(Address)` if configured
-    /// * `Err(Error::TreasuryNotSet)` if not initialized
-    pub fn get_treasury(env: Env) -> Result<Address, Error> {
-        let storage = env.storage().instance();
-        storage.get(&TREASURY_KEY)
-            .ok_or(Error::TreasuryNotSet)
+    /// * `Ok(PayoutCommitment)` with the share held for claims
+    /// * `Err(Error::RootAlreadyCommitted)` if the payer already committed
+    ///   the root
+    /// * `Err(Error)` if validation or transfer fails
+    pub fn commit_payout_root(
+        env: Env,
+        payer: Address,
+        dataset_id: Bytes,
+        root: BytesN<32>,
+        amount: i128,
+    ) -> Result<PayoutCommitment, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        payer.require_auth();
+        
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        let key = DataKey::PayoutRoot(payer.clone(), root.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::RootAlreadyCommitted);
+        }
+        let shared = Self::get_payout_limits(env.clone())
+            .max_reward_per_purchase
+            .map_or(amount, |max_reward| amount.min(max_reward));
+        let (contributor_total, _, _) = Self::split_amount(&env, shared, 1)?;
+        let treasury_amount = math::checked_sub(amount, contributor_total)?;
+        
+        // ============================================
+        // 2. COLLECT PAYMENT AND PAY TREASURY
+        // ============================================
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        Self::require_accepted_token(&env, &usdc_token)?;
+        let treasury: Address = Self::get_instance(&env, &DataKey::Treasury)
+            .ok_or(Error::TreasuryNotSet)?;
+        
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&payer, &env.current_contract_address(), &amount);
+        token_client.transfer(&env.current_contract_address(), &treasury, &treasury_amount);
+        Self::record_treasury(&env, &usdc_token, treasury_amount)?;
+        Self::record_totals(&env, &usdc_token, 0, treasury_amount)?;
+        
+        // ============================================
+        // 3. STORE COMMITMENT
+        // ============================================
+        let commitment = PayoutCommitment {
+            dataset_id: dataset_id.clone(),
+            root: root.clone(),
+            contributor_total,
+            claimed: 0,
+            claims: 0,
+        };
+        env.storage().persistent().set(&key, &commitment);
+        env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        
+        env.events().publish(
+            (Symbol::new(&env, "RootCommitted"), dataset_id),
+            (payer, root, contributor_total),
+        );
+        
+        Ok(commitment)
+    }
+
+    /// Claim a contributor's amount from a Merkle payout commitment
+    /// 
+    /// Anyone can submit the claim; the amount always goes to the
+    /// contributor in the leaf. Each contributor claims once per
+    /// commitment.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payer` - Payer that committed the root
+    /// * `root` - Committed Merkle root
+    /// * `contributor` - Contributor in the leaf
+    /// * `amount` - Amount in the leaf
+    /// * `proof` - Sibling hashes from the leaf up to the root
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyClaimed)` if the contributor already claimed
+    /// * `Err(Error::InvalidProof)` if the leaf is not in the tree
+    /// * `Err(Error)` if the root is unknown or the amount exceeds what is left
+    pub fn claim_from_root(
+        env: Env,
+        payer: Address,
+        root: BytesN<32>,
+        contributor: Address,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        
+        // ============================================
+        // 1. VERIFY CLAIM
+        // ============================================
+        let mut commitment = Self::get_payout_root(env.clone(), payer.clone(), root.clone())?;
+        let claim_key = DataKey::RootClaim(payer.clone(), root.clone(), contributor.clone());
+        if env.storage().persistent().has(&claim_key) {
+            return Err(Error::AlreadyClaimed);
+        }
+        
+        let dataset_key = env.crypto().sha256(&commitment.dataset_id).to_array();
+        let mut node = Self::leaf_hash(&env, dataset_key, &contributor, amount)?;
+        for sibling in proof.iter() {
+            let mut pair = Bytes::new(&env);
+            if node.to_array() <= sibling.to_array() {
+                pair.append(&node.into());
+                pair.append(&sibling.into());
+            } else {
+                pair.append(&sibling.into());
+                pair.append(&node.into());
+            }
+            node = env.crypto().sha256(&pair).into();
+        }
+        if node != root {
+            return Err(Error::InvalidProof);
+        }
+        
+        let claimed = math::checked_add(commitment.claimed, amount)?;
+        if claimed > commitment.contributor_total {
+            return Err(Error::InvalidAmount);
+        }
+        Self::require_onboarded(&env, core::iter::once(contributor.clone()))?;
+        
+        // ============================================
+        // 2. PAY CONTRIBUTOR
+        // ============================================
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        token::Client::new(&env, &usdc_token)
+            .transfer(&env.current_contract_address(), &contributor, &amount);
+        Self::record_earnings(&env, &usdc_token, &contributor, amount, amount, false)?;
+        Self::record_totals(&env, &usdc_token, amount, 0)?;
+        
+        // ============================================
+        // 3. RECORD CLAIM
+        // ============================================
+        let storage = env.storage().persistent();
+        storage.set(&claim_key, &amount);
+        storage.extend_ttl(&claim_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        
+        commitment.claimed = claimed;
+        commitment.claims = commitment.claims.saturating_add(1);
+        let key = DataKey::PayoutRoot(payer, root.clone());
+        storage.set(&key, &commitment);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        
+        env.events().publish(
+            (symbol_short!("RootClaim"), root, contributor),
+            amount,
+        );
+        
+        Ok(())
+    }
+
+    /// Get a Merkle payout commitment
+    /// 
+    /// # Returns
+    /// * `Ok(PayoutCommitment)` if the payer committed the root
+    /// * `Err(Error::RootNotFound)` otherwise
+    pub fn get_payout_root(
+        env: Env,
+        payer: Address,
+        root: BytesN<32>,
+    ) -> Result<PayoutCommitment, Error> {
+        env.storage().persistent()
+            .get(&DataKey::PayoutRoot(payer, root))
+            .ok_or(Error::RootNotFound)
+    }
+
+    /// Check whether a contributor claimed from a Merkle payout commitment
+    pub fn is_root_claimed(
+        env: Env,
+        payer: Address,
+        root: BytesN<32>,
+        contributor: Address,
+    ) -> bool {
+        env.storage().persistent().has(&DataKey::RootClaim(payer, root, contributor))
+    }
+
+    /// Hash a `biochain_ids::PayoutLeaf` preimage
+    /// 
+    /// # Returns
+    /// * `Ok(BytesN<32>)` SHA-256 of the preimage
+    /// * `Err(Error::InvalidContributors)` if the contributor has no strkey form
+    /// * `Err(Error::InvalidAmount)` if the amount is not positive
+    fn leaf_hash(
+        env: &Env,
+        dataset_key: [u8; 32],
+        contributor: &Address,
+        amount: i128,
+    ) -> Result<BytesN<32>, Error> {
+        let strkey = contributor.to_string();
+        if strkey.len() as usize != STRKEY_LEN {
+            return Err(Error::InvalidContributors);
+        }
+        let mut recipient_strkey = [0u8; STRKEY_LEN];
+        strkey.copy_into_slice(&mut recipient_strkey);
+        
+        let preimage = PayoutLeaf::new(dataset_key, recipient_strkey, amount)
+            .map_err(|err| match err {
+                IdError::InvalidAddress => Error::InvalidContributors,
+                IdError::InvalidAmount => Error::InvalidAmount,
+            })?
+            .encode();
+        Ok(env.crypto().sha256(&Bytes::from_array(env, &preimage)).into())
+    }
+
+    /// Preview how `payout_purchase` would pay out an amount
+    /// 
+    /// Read-only: applies the current split, royalty terms and insurance
+    /// premiums without moving funds.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset (selects royalty terms)
+    /// * `contributors` - Vector of distinct contributor addresses in the dataset
+    /// * `amount` - USDC that would be paid
+    /// 
+    /// # Returns
+    /// * `Ok(PayoutBreakdown)` with the amounts each party would receive
+    /// * `Err(Error::InvalidAmount)` if the amount cannot be split
+    pub fn preview_payout(
+        env: Env,
+        dataset_id: Bytes,
+        contributors: Vec<Address>,
+        amount: i128,
+    ) -> Result<PayoutBreakdown, Error> {
+        let mut weights = Vec::new(&env);
+        for contributor in contributors.iter() {
+            weights.push_back((contributor, 1u32));
+        }
+        Self::preview_weighted_payout(env, dataset_id, weights, amount)
+    }
+
+    /// Preview how `payout_weighted` would pay out an amount
+    /// 
+    /// Read-only: applies the current split, royalty terms and insurance
+    /// premiums without moving funds.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset (selects royalty terms)
+    /// * `weights` - Distinct contributors in the dataset with their weights
+    /// * `amount` - USDC that would be paid
+    /// 
+    /// # Returns
+    /// * `Ok(PayoutBreakdown)` with the amounts each party would receive
+    /// * `Err(Error::InvalidContributors)` if a weight is zero
+    /// * `Err(Error::InvalidAmount)` if the amount cannot be split
+    pub fn preview_weighted_payout(
+        env: Env,
+        dataset_id: Bytes,
+        weights: Vec<(Address, u32)>,
+        amount: i128,
+    ) -> Result<PayoutBreakdown, Error> {
+        let (shares, remainder) = Self::split_weighted(&env, amount, &weights)?;
+        let royalties = Self::load_royalty_terms(&env, &dataset_id);
+        
+        let mut per_contributor: Option<(i128, i128, i128)> = None;
+        let mut equal_shares = true;
+        let mut contributor_amounts = Vec::new(&env);
+        let mut network_amount = 0;
+        let mut insurance_amount = 0;
+        let mut treasury_amount = remainder;
+        let contributor_bps = Self::contributor_bps(&env);
+        for (contributor, share) in shares.iter() {
+            let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+            let platform_amount = math::checked_sub(share, user_amount)?;
+            let institutional_amount = Self::apply_bps(user_amount, royalties.institutional_bps)?;
+            let referral_amount = Self::apply_bps(platform_amount, royalties.referral_bps)?;
+            let contributor_amount = math::checked_sub(user_amount, institutional_amount)?;
+            let platform_net = math::checked_sub(platform_amount, referral_amount)?;
+            
+            let contributor_network_amount = math::checked_sum(
+                Self::referral_chain(&env, &contributor, platform_net)?
+                    .iter()
+                    .map(|(_, amount)| amount),
+            )?;
+            let premium_amount = Self::insurance_premium(
+                &env,
+                &contributor,
+                math::checked_sub(platform_net, contributor_network_amount)?,
+            )?;
+            network_amount = math::checked_add(network_amount, contributor_network_amount)?;
+            insurance_amount = math::checked_add(insurance_amount, premium_amount)?;
+            treasury_amount = math::checked_add(
+                treasury_amount,
+                math::checked_sub(
+                    platform_net,
+                    math::checked_add(contributor_network_amount, premium_amount)?,
+                )?,
+            )?;
+            
+            let amounts = (contributor_amount, institutional_amount, referral_amount);
+            equal_shares &= per_contributor.is_none_or(|first| first == amounts);
+            per_contributor.get_or_insert(amounts);
+            contributor_amounts.push_back((contributor, contributor_amount));
+        }
+        
+        let (contributor_amount, institutional_amount, referral_amount) = match per_contributor {
+            Some(amounts) if equal_shares => amounts,
+            _ => (0, 0, 0),
+        };
+        Ok(PayoutBreakdown {
+            amount,
+            num_contributors: shares.len(),
+            contributor_amount,
+            institutional_amount,
+            referral_amount,
+            contributor_amounts,
+            network_amount,
+            insurance_amount,
+            treasury_amount,
+        })
+    }
+
+    /// Split an amount into equal contributor shares
+    /// 
+    /// # Returns
+    /// * `Ok((user_amount, platform_amount, remainder))` per contributor, with
+    ///   the remainder (everything, without contributors) left for the treasury
+    /// * `Err(Error::InvalidAmount)` if the amount is not positive or a share
+    ///   is too small to split
+    fn split_amount(
+        env: &Env,
+        amount: i128,
+        num_contributors: u32,
+    ) -> Result<(i128, i128, i128), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if num_contributors == 0 {
+            return Ok((0, 0, amount));
+        }
+        
+        let share = math::checked_div(amount, num_contributors as i128)?;
+        let contributor_bps = Self::contributor_bps(env);
+        let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+        let platform_amount = math::checked_sub(share, user_amount)?;
+        
+        if user_amount <= 0 || platform_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let distributed = math::checked_mul(share, num_contributors as i128)?;
+        Ok((user_amount, platform_amount, math::checked_sub(amount, distributed)?))
+    }
+
+    /// Split an amount into contributor shares by weight
+    /// 
+    /// Only up to the configured `max_reward_per_purchase` is shared; the
+    /// excess is added to the remainder.
+    /// 
+    /// # Returns
+    /// * `Ok((shares, remainder))` with each contributor's share and the
+    ///   remainder (everything, without contributors) left for the treasury
+    /// * `Err(Error::InvalidContributors)` if a weight is zero
+    /// * `Err(Error::InvalidAmount)` if the amount is not positive or a share
+    ///   is too small to split
+    fn split_weighted(
+        env: &Env,
+        amount: i128,
+        weights: &Vec<(Address, u32)>,
+    ) -> Result<(Vec<(Address, i128)>, i128), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut total_weight: i128 = 0;
+        for (_, weight) in weights.iter() {
+            if weight == 0 {
+                return Err(Error::InvalidContributors);
+            }
+            total_weight = math::checked_add(total_weight, weight as i128)?;
+        }
+        
+        let shared = Self::get_payout_limits(env.clone())
+            .max_reward_per_purchase
+            .map_or(amount, |max_reward| amount.min(max_reward));
+        let mut shares = Vec::new(env);
+        let mut distributed = 0;
+        let contributor_bps = Self::contributor_bps(env);
+        for (contributor, weight) in weights.iter() {
+            let share = math::checked_div(math::checked_mul(shared, weight as i128)?, total_weight)?;
+            let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+            if user_amount <= 0 || share - user_amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            distributed = math::checked_add(distributed, share)?;
+            shares.push_back((contributor, share));
+        }
+        Ok((shares, math::checked_sub(amount, distributed)?))
+    }
+
+    /// Scale explicit shares down to the configured `max_reward_per_purchase`
+    /// 
+    /// Shares adding up to no more than the cap are returned unchanged.
+    /// 
+    /// # Returns
+    /// * `Ok((shares, remainder))` with the shares, in proportion to the
+    ///   given ones, and the excess left for the treasury
+    /// * `Err(Error::InvalidAmount)` if a scaled share is too small to split
+    fn cap_shares(
+        env: &Env,
+        shares: Vec<(Address, i128)>,
+        total: i128,
+    ) -> Result<(Vec<(Address, i128)>, i128), Error> {
+        let Some(max_reward) = Self::get_payout_limits(env.clone())
+            .max_reward_per_purchase
+            .filter(|max_reward| *max_reward < total)
+        else {
+            return Ok((shares, 0));
+        };
+        let mut capped = Vec::new(env);
+        let mut distributed = 0;
+        let contributor_bps = Self::contributor_bps(env);
+        for (contributor, share) in shares.iter() {
+            let share = math::checked_div(math::checked_mul(share, max_reward)?, total)?;
+            let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+            if user_amount <= 0 || share - user_amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            distributed = math::checked_add(distributed, share)?;
+            capped.push_back((contributor, share));
+        }
+        Ok((capped, math::checked_sub(total, distributed)?))
+    }
+
+    /// Pay a payout's contributors, queueing those beyond one batch
+    /// 
+    /// Up to `MAX_CONTRIBUTORS_PER_BATCH` contributors are paid now; the
+    /// rest wait in the payout queue, with the tokens to pay them, until
+    /// `process_next` reaches them. This contract must hold the sum of the
+    /// shares. A `self_funded` payout, one not made by the authorized
+    /// caller, stays so when queued.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Overflow)` if an amount overflows
+    fn pay_contributors(
+        env: &Env,
+        token: &Address,
+        treasury: &Address,
+        dataset_id: &Bytes,
+        shares: &Vec<(Address, i128)>,
+        self_funded: bool,
+    ) -> Result<u64, Error> {
+        // Every contributor of the payout shares the payout sequence, even
+        // when paid in a later batch
+        let mut payout = QueuedPayout {
+            dataset_id: dataset_id.clone(),
+            remaining: shares.clone(),
+            sequence: Self::next_payout_seq(env, dataset_id),
+            num_contributors: shares.len(),
+            total_user_amount: 0,
+            total_platform_amount: 0,
+        };
+        Self::pay_next_batch(
+            env,
+            token,
+            treasury,
+            &mut payout,
+            MAX_CONTRIBUTORS_PER_BATCH,
+            self_funded,
+        )?;
+        
+        if payout.remaining.is_empty() {
+            Self::complete_payout(env, &payout);
+        } else {
+            Self::enqueue_payout(env, token, &payout, self_funded);
+        }
+        Ok(payout.sequence)
+    }
+
+    /// Pay the next `batch_size` contributors of a payout
+    /// 
+    /// Transfers each contributor's reward and the platform share in
+    /// `token`, pays royalties and (for USDC payouts) insurance premiums
+    /// and emits the per-contributor events. Unless the payout is
+    /// `self_funded`, it also notifies contributors, mints their sale
+    /// credit and pushes the payout to PlatformStats. Paid contributors are
+    /// removed from `payout.remaining` and added to its totals.
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the number of contributors paid
+    /// * `Err(Error::Overflow)` if an amount overflows
+    fn pay_next_batch(
+        env: &Env,
+        token: &Address,
+        treasury: &Address,
+        payout: &mut QueuedPayout,
+        batch_size: u32,
+        self_funded: bool,
+    ) -> Result<u32, Error> {
+        // ============================================
+        // 1. INITIALIZE TOKEN CLIENT AND ROYALTIES
+        // ============================================
+        let token_client = token::Client::new(env, token);
+        let contract_address = env.current_contract_address();
+        let dataset_id = &payout.dataset_id;
+        let sequence = payout.sequence;
+        let batch = payout.remaining.len().min(batch_size);
+        let shares = payout.remaining.slice(0..batch);
+        let royalties = Self::load_royalty_terms(env, dataset_id);
+        
+        let dataset_key = env.crypto().sha256(dataset_id).to_array();
+        
+        // ============================================
+        // 2. PROCESS EACH CONTRIBUTOR
+        // ============================================
+        let mut total_user_amount = 0;
+        let mut total_contributor_amount = 0;
+        let mut total_platform_amount = 0;
+        let mut total_treasury_amount = 0;
+        let mut credits = Vec::new(env);
+        let contributor_bps = Self::contributor_bps(env);
+        let accrue = Self::get_payout_mode(env.clone()) == PayoutMode::Accrue;
+        let min_payout = Self::get_payout_limits(env.clone()).min_payout;
+        // Insurance premiums are paid into the pool in USDC only
+        let insured = Self::is_default_token(env, token);
+        
+        for (contributor, share) in shares.iter() {
+            // Institutional royalties come out of the contributor share and
+            // referral shares out of the platform share
+            let user_amount = math::checked_mul(share, contributor_bps)? / BPS_DENOMINATOR as i128;
+            let platform_amount = math::checked_sub(share, user_amount)?;
+            let institutional_amount = Self::apply_bps(user_amount, royalties.institutional_bps)?;
+            let referral_amount = Self::apply_bps(platform_amount, royalties.referral_bps)?;
+            let contributor_amount = math::checked_sub(user_amount, institutional_amount)?;
+            let platform_net = math::checked_sub(platform_amount, referral_amount)?;
+            
+            // Transfer user amount (net of institutional royalty) to
+            // contributor, or credit it for `claim`; rewards below the
+            // minimum payout are always credited
+            let credited = accrue || contributor_amount < min_payout;
+            if !credited {
+                token_client.transfer(&contract_address, &contributor, &contributor_amount);
+            }
+            Self::record_earnings(env, token, &contributor, user_amount, contributor_amount, credited)?;
+            if !self_funded {
+                Self::notify(env, &contributor, dataset_id, contributor_amount);
+                Self::mint_credit(env, &contributor);
+                Self::report_sale(env, &contributor);
+                // A sale's credited rewards are all a refund can reclaim
+                if credited {
+                    credits.push_back(PayoutCredit {
+                        contributor: contributor.clone(),
+                        amount: contributor_amount,
+                        claims: Self::claim_count(env, &contributor, token),
+                    });
+                }
+            }
+            
+            // Pay royalties defined in RoyaltyRegistry
+            if let Some(institution) = royalties.institution.as_ref() {
+                if institutional_amount > 0 {
+                    token_client.transfer(&contract_address, institution, &institutional_amount);
+                }
+            }
+            if let Some(referrer) = royalties.referrer.as_ref() {
+                if referral_amount > 0 {
+                    token_client.transfer(&contract_address, referrer, &referral_amount);
+                }
+            }
+            
+            // Route the referral carve-out up the contributor's referral chain
+            let mut network_amount = 0;
+            for (referrer, amount) in Self::referral_chain(env, &contributor, platform_net)?.iter() {
+                token_client.transfer(&contract_address, &referrer, &amount);
+                network_amount = math::checked_add(network_amount, amount)?;
+            }
+            
+            // Divert the contributor's insurance premium from the platform share
+            let premium_amount = if insured {
+                Self::pay_insurance_premium(
+                    env,
+                    token,
+                    &contributor,
+                    math::checked_sub(platform_net, network_amount)?,
+                )?
+            } else {
+                0
+            };
+            
+            // Transfer the rest of the platform amount to treasury
+            // Note: We transfer platform_amount for each contributor
+            // This ensures proper accounting per contributor
+            let treasury_amount =
+                math::checked_sub(platform_net, math::checked_add(network_amount, premium_amount)?)?;
+            token_client.transfer(&contract_address, treasury, &treasury_amount);
+            
+            // Accumulate totals
+            total_user_amount = math::checked_add(total_user_amount, user_amount)?;
+            total_contributor_amount = math::checked_add(total_contributor_amount, contributor_amount)?;
+            total_platform_amount = math::checked_add(total_platform_amount, platform_amount)?;
+            total_treasury_amount = math::checked_add(total_treasury_amount, treasury_amount)?;
+            
+            // ============================================
+            // 3. EMIT PER-CONTRIBUTOR EVENT
+            // ============================================
+            env.events().publish(
+                (
+                    Symbol::new(env, "ContributorRewarded"),
+                    dataset_id.clone(),
+                    contributor.clone(),
+                ),
+                ContributorRewarded {
+                    dataset_id: dataset_id.clone(),
+                    contributor: contributor.clone(),
+                    user_amount,
+                    platform_amount,
+                    payout_id: Self::derive_payout_id(
+                        env,
+                        dataset_key,
+                        sequence,
+                        &contributor,
+                        contributor_amount,
+                    )?,
+                    sequence,
+                },
+            );
+        }
+        
+        // ============================================
+        // 4. PUSH ANALYTICS
+        // ============================================
+        Self::record_treasury(env, token, total_treasury_amount)?;
+        Self::record_totals(env, token, total_contributor_amount, total_platform_amount)?;
+        if !self_funded {
+            Self::push_stat(env, symbol_short!("payouts"), total_user_amount);
+        }
+        Self::report_to_breaker(env, symbol_short!("payout"), total_user_amount);
+        if !credits.is_empty() {
+            Self::record_credits(env, dataset_id, sequence, credits);
+        }
+        
+        payout.remaining = payout.remaining.slice(batch..);
+        payout.total_user_amount = math::checked_add(payout.total_user_amount, total_user_amount)?;
+        payout.total_platform_amount =
+            math::checked_add(payout.total_platform_amount, total_platform_amount)?;
+        Ok(batch)
+    }
+
+    /// Emit the aggregate event of a fully paid payout
+    fn complete_payout(env: &Env, payout: &QueuedPayout) {
+        env.events().publish(
+            (
+                Symbol::new(env, "DatasetPayoutCompleted"),
+                payout.dataset_id.clone(),
+            ),
+            DatasetPayoutCompleted {
+                dataset_id: payout.dataset_id.clone(),
+                num_contributors: payout.num_contributors,
+                total_user_amount: payout.total_user_amount,
+                total_platform_amount: payout.total_platform_amount,
+            },
+        );
+    }
+
+    /// Append a partly paid payout to the queue
+    /// 
+    /// The token is only stored for payouts not made in the configured USDC
+    /// token, and a marker only for self-funded payouts.
+    fn enqueue_payout(env: &Env, token: &Address, payout: &QueuedPayout, self_funded: bool) {
+        let tail: u64 = Self::get_instance(env, &DataKey::QueueTail).unwrap_or(0);
+        let key = DataKey::QueuedPayout(tail);
+        env.storage().persistent().set(&key, payout);
+        env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        if !Self::is_default_token(env, token) {
+            let token_key = DataKey::QueuedToken(tail);
+            env.storage().persistent().set(&token_key, token);
+            env.storage().persistent().extend_ttl(&token_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+        if self_funded {
+            let self_funded_key = DataKey::QueuedSelfFunded(tail);
+            env.storage().persistent().set(&self_funded_key, &true);
+            env.storage().persistent().extend_ttl(&self_funded_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+        env.storage().instance().set(&DataKey::QueueTail, &(tail + 1));
+        
+        env.events().publish(
+            (Symbol::new(env, "PayoutQueued"), payout.dataset_id.clone()),
+            (tail, payout.remaining.len()),
+        );
+    }
+
+    /// Pay the next batch of the oldest queued payout
+    /// 
+    /// Callable by anyone. Pays up to `batch_size` contributors (at most
+    /// `MAX_CONTRIBUTORS_PER_BATCH`) of the payout at the head of the
+    /// queue, which leaves the queue once all its contributors are paid.
+    /// When a tip is configured, `caller` receives it per contributor paid,
+    /// as long as the tip pool lasts.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - Address receiving the tip
+    /// * `batch_size` - Maximum number of contributors to pay
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the number of contributors paid
+    /// * `Err(Error::QueueEmpty)` if no payout is queued
+    /// * `Err(Error::InvalidAmount)` if `batch_size` is zero
+    pub fn process_next(env: Env, caller: Address, batch_size: u32) -> Result<u32, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        if batch_size == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        
+        // ============================================
+        // 1. LOAD HEAD OF THE QUEUE
+        // ============================================
+        let head: u64 = Self::get_instance(&env, &DataKey::QueueHead).unwrap_or(0);
+        let key = DataKey::QueuedPayout(head);
+        let mut payout: QueuedPayout = env.storage().persistent()
+            .get(&key)
+            .ok_or(Error::QueueEmpty)?;
+        
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        let treasury: Address = Self::get_instance(&env, &DataKey::Treasury)
+            .ok_or(Error::TreasuryNotSet)?;
+        let token_key = DataKey::QueuedToken(head);
+        let token: Address = env.storage().persistent()
+            .get(&token_key)
+            .unwrap_or_else(|| usdc_token.clone());
+        let self_funded_key = DataKey::QueuedSelfFunded(head);
+        let self_funded = env.storage().persistent().has(&self_funded_key);
+        
+        // ============================================
+        // 2. PAY NEXT BATCH
+        // ============================================
+        let paid = Self::pay_next_batch(
+            &env,
+            &token,
+            &treasury,
+            &mut payout,
+            batch_size.min(MAX_CONTRIBUTORS_PER_BATCH),
+            self_funded,
+        )?;
+        
+        if payout.remaining.is_empty() {
+            env.storage().persistent().remove(&key);
+            env.storage().persistent().remove(&token_key);
+            env.storage().persistent().remove(&self_funded_key);
+            env.storage().instance().set(&DataKey::QueueHead, &(head + 1));
+            Self::complete_payout(&env, &payout);
+        } else {
+            env.storage().persistent().set(&key, &payout);
+            env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+        
+        // ============================================
+        // 3. TIP CALLER
+        // ============================================
+        let tip_per_contributor: i128 = Self::get_instance(&env, &DataKey::QueueTip).unwrap_or(0);
+        let pool: i128 = Self::get_instance(&env, &DataKey::TipPool).unwrap_or(0);
+        let tip = math::checked_mul(tip_per_contributor, paid as i128)?.min(pool);
+        if tip > 0 {
+            token::Client::new(&env, &usdc_token)
+                .transfer(&env.current_contract_address(), &caller, &tip);
+            env.storage().instance().set(&DataKey::TipPool, &(pool - tip));
+        }
+        
+        Ok(paid)
+    }
+
+    /// Get the number of payouts waiting in the queue
+    pub fn get_queue_length(env: Env) -> u64 {
+        let head: u64 = Self::get_instance(&env, &DataKey::QueueHead).unwrap_or(0);
+        let tail: u64 = Self::get_instance(&env, &DataKey::QueueTail).unwrap_or(0);
+        tail - head
+    }
+
+    /// Get the payout `process_next` would work on, if any
+    pub fn peek_payout_queue(env: Env) -> Option<QueuedPayout> {
+        let head: u64 = Self::get_instance(&env, &DataKey::QueueHead).unwrap_or(0);
+        env.storage().persistent().get(&DataKey::QueuedPayout(head))
+    }
+
+    /// Set the tip paid per contributor processed by `process_next`
+    /// 
+    /// Tips are paid from the pool funded with `fund_queue_tips`.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `tip_per_contributor` - USDC paid per contributor (0 disables tips)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    /// * `Err(Error::InvalidAmount)` if the tip is negative
+    pub fn set_queue_tip(
+        env: Env,
+        tip_per_contributor: i128,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if tip_per_contributor < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        storage.set(&DataKey::QueueTip, &tip_per_contributor);
+        Self::audit(&env, symbol_short!("config"), None, None);
+        Ok(())
+    }
+
+    /// Set how each contributor share is split between the contributor and
+    /// the platform treasury
+    /// 
+    /// Applies to every payout paid from then on, including the remaining
+    /// contributors of queued payouts. Emits a `SplitUpdated` event.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor_bps` - Contributor share in basis points
+    /// * `platform_bps` - Platform share in basis points
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    /// * `Err(Error::InvalidSplit)` if a share is zero or they do not add up
+    ///   to 10_000 basis points
+    pub fn set_split(
+        env: Env,
+        contributor_bps: u32,
+        platform_bps: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if contributor_bps == 0
+            || platform_bps == 0
+            || contributor_bps.checked_add(platform_bps) != Some(BPS_DENOMINATOR)
+        {
+            return Err(Error::InvalidSplit);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Split, &(contributor_bps, platform_bps));
+        
+        env.events().publish(
+            (Symbol::new(&env, "SplitUpdated"),),
+            (contributor_bps, platform_bps),
+        );
+        Self::audit(&env, symbol_short!("config"), None, None);
+        Ok(())
+    }
+
+    /// Get the contributor and platform shares in basis points
+    /// 
+    /// # Returns
+    /// * `(contributor_bps, platform_bps)`, 85% / 15% until `set_split` is
+    ///   called
+    pub fn get_split(env: Env) -> (u32, u32) {
+        env.storage()
+            .instance()
+            .get(&DataKey::Split)
+            .unwrap_or((DEFAULT_CONTRIBUTOR_BPS, DEFAULT_PLATFORM_BPS))
+    }
+
+    /// Set how contributor rewards are paid
+    /// 
+    /// In `PayoutMode::Accrue` payouts credit each contributor's claimable
+    /// balance instead of transferring to them, so a purchase does not
+    /// depend on every contributor transfer succeeding; contributors
+    /// withdraw with `claim`. Balances credited earlier stay claimable after
+    /// switching back to `PayoutMode::Push`. Requires the admin's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `mode` - Payout mode for payouts from now on
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_payout_mode(env: Env, mode: PayoutMode) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::PayoutMode, &mode);
+        Self::audit(&env, symbol_short!("config"), None, None);
+        Ok(())
+    }
+
+    /// Get how contributor rewards are paid (`PayoutMode::Push` by default)
+    pub fn get_payout_mode(env: Env) -> PayoutMode {
+        env.storage()
+            .instance()
+            .get(&DataKey::PayoutMode)
+            .unwrap_or(PayoutMode::Push)
+    }
+
+    /// Set the minimum payout and the per-purchase reward cap
+    /// 
+    /// Applies to every payout paid from then on, including the remaining
+    /// contributors of queued payouts. Emits a `LimitsUpdated` event.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `min_payout` - Smallest reward transferred at once (0 for no
+    ///   minimum)
+    /// * `max_reward_per_purchase` - Most of a purchase's amount shared
+    ///   among its contributors (None for no cap)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    /// * `Err(Error::InvalidLimit)` if the minimum is negative or the cap
+    ///   is not positive
+    pub fn set_payout_limits(
+        env: Env,
+        min_payout: i128,
+        max_reward_per_purchase: Option<i128>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if min_payout < 0 || max_reward_per_purchase.is_some_and(|max_reward| max_reward <= 0) {
+            return Err(Error::InvalidLimit);
+        }
+        let limits = PayoutLimits { min_payout, max_reward_per_purchase };
+        env.storage().instance().set(&DataKey::PayoutLimits, &limits);
+        
+        env.events().publish((Symbol::new(&env, "LimitsUpdated"),), limits);
+        Self::audit(&env, symbol_short!("config"), None, None);
+        Ok(())
+    }
+
+    /// Get the minimum payout and the per-purchase reward cap (no minimum
+    /// and no cap until `set_payout_limits` is called)
+    pub fn get_payout_limits(env: Env) -> PayoutLimits {
+        env.storage()
+            .instance()
+            .get(&DataKey::PayoutLimits)
+            .unwrap_or_default()
+    }
+
+    /// Withdraw a contributor's claimable balance
+    /// 
+    /// Transfers everything credited to the contributor in
+    /// `PayoutMode::Accrue`, or below the minimum payout, and resets the
+    /// balance.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor claiming (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the USDC transferred
+    /// * `Err(Error::NothingToClaim)` if the balance is zero
+    /// * `Err(Error::TokenNotSet)` if the contract is not initialized
+    pub fn claim(env: Env, contributor: Address) -> Result<i128, Error> {
+        contributor.require_auth();
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        Self::claim_token(&env, &contributor, &usdc_token)
+    }
+
+    /// Withdraw a contributor's claimable balance in a token
+    /// 
+    /// Same as `claim` for payouts made in `token` with
+    /// `payout_weighted_in`. The `EarningsClaimed` event of a token other
+    /// than the configured USDC token carries the token as a third topic.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor claiming (must authorize)
+    /// * `token` - Token to withdraw
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the amount of `token` transferred
+    /// * `Err(Error::NothingToClaim)` if the balance is zero
+    pub fn claim_in(env: Env, contributor: Address, token: Address) -> Result<i128, Error> {
+        contributor.require_auth();
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        
+        Self::claim_token(&env, &contributor, &token)
+    }
+
+    /// Transfer a contributor's claimable balance in `token` and reset it
+    fn claim_token(env: &Env, contributor: &Address, token: &Address) -> Result<i128, Error> {
+        let storage = env.storage().persistent();
+        let key = Self::earnings_key(env, contributor, token);
+        let mut earnings: ContributorEarnings = storage.get(&key)
+            .unwrap_or_default();
+        let amount = earnings.claimable;
+        if amount <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+        
+        earnings.claimable = 0;
+        storage.set(&key, &earnings);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        Self::count_claim(env, contributor, token);
+        token::Client::new(env, token)
+            .transfer(&env.current_contract_address(), contributor, &amount);
+        
+        if Self::is_default_token(env, token) {
+            env.events().publish(
+                (Symbol::new(env, "EarningsClaimed"), contributor.clone()),
+                amount,
+            );
+        } else {
+            env.events().publish(
+                (Symbol::new(env, "EarningsClaimed"), contributor.clone(), token.clone()),
+                amount,
+            );
+        }
+        Ok(amount)
+    }
+
+    /// Withdraw the claimable balances of many contributors
+    /// 
+    /// Lets an operator settle contributors in one transaction. Each
+    /// balance is only ever transferred to its own contributor, so no
+    /// authorization is required. Failures are per contributor: one with
+    /// nothing to claim, or whose transfer fails (e.g. a deauthorized
+    /// trustline), is reported with 0 and keeps the balance for a later
+    /// claim, while the others are paid.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributors` - Contributors to settle (at most
+    ///   `MAX_CONTRIBUTORS_PER_BATCH`)
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<(Address, i128)>)` with the USDC transferred to each
+    ///   contributor, in order
+    /// * `Err(Error::BatchTooLarge)` if too many contributors are given
+    /// * `Err(Error::TokenNotSet)` if the contract is not initialized
+    pub fn claim_many(
+        env: Env,
+        contributors: Vec<Address>,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        if contributors.len() > MAX_CONTRIBUTORS_PER_BATCH {
+            return Err(Error::BatchTooLarge);
+        }
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        let token_client = token::Client::new(&env, &usdc_token);
+        let contract_address = env.current_contract_address();
+        let storage = env.storage().persistent();
+        
+        let mut claimed = Vec::new(&env);
+        for contributor in contributors.iter() {
+            let key = DataKey::Earnings(contributor.clone());
+            let mut earnings: ContributorEarnings = storage.get(&key)
+                .unwrap_or_default();
+            let amount = earnings.claimable;
+            if amount <= 0 {
+                claimed.push_back((contributor, 0));
+                continue;
+            }
+            
+            // Reset the balance first and restore it if the transfer fails
+            earnings.claimable = 0;
+            storage.set(&key, &earnings);
+            if let Ok(Ok(())) = token_client.try_transfer(&contract_address, &contributor, &amount) {
+                storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+                Self::count_claim(&env, &contributor, &usdc_token);
+                env.events().publish(
+                    (Symbol::new(&env, "EarningsClaimed"), contributor.clone()),
+                    amount,
+                );
+                claimed.push_back((contributor, amount));
+            } else {
+                earnings.claimable = amount;
+                storage.set(&key, &earnings);
+                env.events().publish(
+                    (Symbol::new(&env, "ClaimFailed"), contributor.clone()),
+                    amount,
+                );
+                claimed.push_back((contributor, 0));
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Reclaim the unclaimed contributor rewards of a refunded purchase
+    /// 
+    /// Takes back what the purchase's payout, identified by the sequence
+    /// `payout_weighted_in` returned, credited to each contributor, unless
+    /// they have claimed since, and never more than their claimable
+    /// balance. Rewards already transferred, in `PayoutMode::Push` or by a
+    /// claim, are not recovered, and a payout is reclaimed at most once.
+    /// The reclaimed amount is transferred to `recipient` and taken off the
+    /// contributor's lifetime earnings and the payout totals; annual
+    /// earnings records are left unchanged. Requires the authorization of
+    /// the authorized caller (see `set_authorized_caller`).
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Token the purchase was paid out in
+    /// * `dataset_id` - ID of the purchased dataset
+    /// * `sequence` - Sequence of the purchase's payout for the dataset
+    /// * `recipient` - Address receiving the reclaimed amount
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the amount transferred to `recipient`
+    /// * `Err(Error::Unauthorized)` if no authorized caller is set
+    pub fn claw_back(
+        env: Env,
+        token: Address,
+        dataset_id: Bytes,
+        sequence: u64,
+        recipient: Address,
+    ) -> Result<i128, Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        let caller: Address = Self::get_instance(&env, &DataKey::AuthorizedCaller)
+            .ok_or(Error::Unauthorized)?;
+        caller.require_auth();
+        
+        let storage = env.storage().persistent();
+        let credits_key = DataKey::PayoutCredits(dataset_id.clone(), sequence);
+        let credits: Vec<PayoutCredit> = storage.get(&credits_key)
+            .unwrap_or(Vec::new(&env));
+        storage.remove(&credits_key);
+        let mut reclaimed = 0;
+        for PayoutCredit { contributor, amount, claims } in credits.iter() {
+            // A claim since the payout withdrew its reward
+            if Self::claim_count(&env, &contributor, &token) != claims {
+                continue;
+            }
+            let key = Self::earnings_key(&env, &contributor, &token);
+            let mut earnings: ContributorEarnings = storage.get(&key)
+                .unwrap_or_default();
+            let taken = amount.min(earnings.claimable);
+            if taken <= 0 {
+                continue;
+            }
+            earnings.claimable = math::checked_sub(earnings.claimable, taken)?;
+            earnings.total_earned = math::checked_sub(earnings.total_earned, taken)?;
+            storage.set(&key, &earnings);
+            storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            reclaimed = math::checked_add(reclaimed, taken)?;
+            
+            env.events().publish(
+                (Symbol::new(&env, "EarningsClawedBack"), dataset_id.clone(), contributor),
+                taken,
+            );
+        }
+        
+        if reclaimed > 0 {
+            Self::record_totals(&env, &token, -reclaimed, 0)?;
+            token::Client::new(&env, &token)
+                .transfer(&env.current_contract_address(), &recipient, &reclaimed);
+        }
+        Ok(reclaimed)
+    }
+
+    /// Add USDC to the pool `process_next` tips are paid from
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `from` - Funder (must authorize the transfer)
+    /// * `amount` - USDC to add
+    /// 
+    /// # Returns
+    /// * `Ok(i128)` with the new pool balance
+    /// * `Err(Error::InvalidAmount)` if amount is not positive
+    pub fn fund_queue_tips(env: Env, from: Address, amount: i128) -> Result<i128, Error> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)?;
+        token::Client::new(&env, &usdc_token)
+            .transfer(&from, &env.current_contract_address(), &amount);
+        
+        let pool: i128 = Self::get_instance(&env, &DataKey::TipPool).unwrap_or(0);
+        let pool = math::checked_add(pool, amount)?;
+        env.storage().instance().set(&DataKey::TipPool, &pool);
+        Ok(pool)
+    }
+
+    /// Get the running earnings of a contributor
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// 
+    /// Payouts are only recorded under `DataKey::Earnings`, so earnings
+    /// still stored under the pre-schema-3 key are added in.
+    /// 
+    /// # Returns
+    /// * Earnings (all zero if the contributor was never paid)
+    pub fn get_contributor_earnings(
+        env: Env,
+        contributor: Address,
+    ) -> ContributorEarnings {
+        let storage = env.storage().persistent();
+        let key = DataKey::Earnings(contributor);
+        let current: ContributorEarnings = storage.get(&key)
+            .unwrap_or_default();
+        let legacy: ContributorEarnings = storage.get(&key.legacy(&env))
+            .unwrap_or_default();
+        ContributorEarnings {
+            sales: current.sales.saturating_add(legacy.sales),
+            total_earned: current.total_earned.saturating_add(legacy.total_earned),
+            claimable: current.claimable.saturating_add(legacy.claimable),
+        }
+    }
+
+    /// Get the earnings of a contributor in a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// * `token` - Token the payouts were made in
+    /// 
+    /// # Returns
+    /// * Earnings from payouts in `token` (all zero if none were made); for
+    ///   the configured USDC token, the same as `get_contributor_earnings`
+    pub fn get_earnings_in(env: Env, contributor: Address, token: Address) -> ContributorEarnings {
+        if Self::is_default_token(&env, &token) {
+            return Self::get_contributor_earnings(env, contributor);
+        }
+        env.storage().persistent()
+            .get(&DataKey::TokenEarnings(contributor, token))
+            .unwrap_or_default()
+    }
+
+    /// Get a contributor's earnings in a calendar year
+    /// 
+    /// Payouts are bucketed by the UTC calendar year of their ledger
+    /// timestamp.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// * `year` - Calendar year (e.g. 2025)
+    /// 
+    /// # Returns
+    /// * Earnings for the year (all zero if the contributor was not paid)
+    pub fn get_annual_earnings(
+        env: Env,
+        contributor: Address,
+        year: u32,
+    ) -> AnnualEarnings {
+        env.storage().persistent()
+            .get(&DataKey::AnnualEarnings(contributor, year))
+            .unwrap_or(AnnualEarnings {
+                year,
+                sales: 0,
+                gross: 0,
+                earned: 0,
+                withheld: 0,
+            })
+    }
+
+    /// Add a payout to a contributor's running and annual earnings
+    /// 
+    /// `gross` is the contributor share before the institutional royalty,
+    /// `amount` what was actually transferred or, if `accrued`, credited to
+    /// the contributor's claimable balance. Annual earnings are reported in
+    /// USDC, so payouts in other tokens only add to that token's earnings.
+    fn record_earnings(
+        env: &Env,
+        token: &Address,
+        contributor: &Address,
+        gross: i128,
+        amount: i128,
+        accrued: bool,
+    ) -> Result<(), Error> {
+        let storage = env.storage().persistent();
+        let key = Self::earnings_key(env, contributor, token);
+        let mut earnings: ContributorEarnings = storage.get(&key)
+            .unwrap_or_default();
+        earnings.sales = earnings.sales.saturating_add(1);
+        earnings.total_earned = math::checked_add(earnings.total_earned, amount)?;
+        if accrued {
+            earnings.claimable = math::checked_add(earnings.claimable, amount)?;
+        }
+        storage.set(&key, &earnings);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        if !Self::is_default_token(env, token) {
+            return Ok(());
+        }
+        
+        let year = Self::calendar_year(env.ledger().timestamp());
+        let mut annual = Self::get_annual_earnings(env.clone(), contributor.clone(), year);
+        annual.sales = annual.sales.saturating_add(1);
+        annual.gross = math::checked_add(annual.gross, gross)?;
+        annual.earned = math::checked_add(annual.earned, amount)?;
+        if accrued {
+            annual.withheld = math::checked_add(annual.withheld, amount)?;
+        }
+        let key = DataKey::AnnualEarnings(contributor.clone(), year);
+        storage.set(&key, &annual);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        Ok(())
+    }
+
+    /// Append the rewards a batch of a payout credited to its record
+    fn record_credits(env: &Env, dataset_id: &Bytes, sequence: u64, credits: Vec<PayoutCredit>) {
+        let storage = env.storage().persistent();
+        let key = DataKey::PayoutCredits(dataset_id.clone(), sequence);
+        let mut recorded: Vec<PayoutCredit> = storage.get(&key)
+            .unwrap_or(Vec::new(env));
+        recorded.append(&credits);
+        storage.set(&key, &recorded);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Number of claims a contributor has made in a token
+    fn claim_count(env: &Env, contributor: &Address, token: &Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::Claims(contributor.clone(), token.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Count a contributor's claim in a token
+    fn count_claim(env: &Env, contributor: &Address, token: &Address) {
+        let storage = env.storage().persistent();
+        let key = DataKey::Claims(contributor.clone(), token.clone());
+        let claims = Self::claim_count(env, contributor, token);
+        storage.set(&key, &claims.wrapping_add(1));
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// UTC calendar year of a ledger timestamp
+    /// 
+    /// Converts days since the Unix epoch to a proleptic Gregorian date
+    /// (Howard Hinnant's `civil_from_days`), keeping only the year.
+    fn calendar_year(timestamp: u64) -> u32 {
+        let z = timestamp / 86_400 + 719_468;
+        let era = z / 146_097;
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        // Months are counted from March, so January and February belong to
+        // the next year
+        let year = yoe + era * 400 + u64::from(mp >= 10);
+        year as u32
+    }
+
+    /// Get the total treasury share paid out in a token
+    /// 
+    /// The treasury receives the platform share in the settlement token of
+    /// each payout, so it can hold several assets. Royalty, referral and
+    /// insurance carve-outs are not included.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Settlement token
+    /// 
+    /// # Returns
+    /// * Amount sent to the treasury in `token` (0 if none)
+    pub fn get_treasury_received(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::TreasuryReceived(token))
+            .unwrap_or(0)
+    }
+
+    /// Get a contributor's claimable balance
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// 
+    /// # Returns
+    /// * USDC credited in `PayoutMode::Accrue` and not yet claimed
+    pub fn get_pending_balance(env: Env, contributor: Address) -> i128 {
+        Self::get_contributor_earnings(env, contributor).claimable
+    }
+
+    /// Get everything a contributor has earned
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// 
+    /// # Returns
+    /// * USDC paid or credited to the contributor, net of institutional
+    ///   royalty, including any balance not yet claimed
+    pub fn get_lifetime_earnings(env: Env, contributor: Address) -> i128 {
+        Self::get_contributor_earnings(env, contributor).total_earned
+    }
+
+    /// Get the USDC paid or credited to contributors by all payouts
+    /// 
+    /// Amounts are net of institutional royalty and include bounty payouts
+    /// and Merkle claims.
+    pub fn get_total_distributed(env: Env) -> i128 {
+        Self::get_totals(&env).total_distributed
+    }
+
+    /// Get the platform share of all payouts
+    /// 
+    /// Includes the referral shares and insurance premiums carved out of
+    /// it and the rounding remainders paid to the treasury; see
+    /// `get_treasury_received` for what the treasury itself received.
+    pub fn get_platform_accrued(env: Env) -> i128 {
+        Self::get_totals(&env).platform_accrued
+    }
+
+    /// Add a transfer to the treasury to its token's running total
+    fn record_treasury(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Ok(());
+        }
+        let storage = env.storage().persistent();
+        let key = DataKey::TreasuryReceived(token.clone());
+        let total: i128 = storage.get(&key).unwrap_or(0);
+        storage.set(&key, &math::checked_add(total, amount)?);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        Ok(())
+    }
+
+    /// Add to the running payout totals of a token
+    fn record_totals(
+        env: &Env,
+        token: &Address,
+        distributed: i128,
+        platform: i128,
+    ) -> Result<(), Error> {
+        let storage = env.storage().persistent();
+        let key = Self::totals_key(env, token);
+        let mut totals: PayoutTotals = storage.get(&key).unwrap_or_default();
+        totals.total_distributed = math::checked_add(totals.total_distributed, distributed)?;
+        totals.platform_accrued = math::checked_add(totals.platform_accrued, platform)?;
+        storage.set(&key, &totals);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        Ok(())
+    }
+
+    /// Read the running USDC payout totals (all zero before the first payout)
+    fn get_totals(env: &Env) -> PayoutTotals {
+        env.storage().persistent()
+            .get(&DataKey::Totals)
+            .unwrap_or_default()
+    }
+
+    /// Get the running payout totals in a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Token the payouts were made in
+    /// 
+    /// # Returns
+    /// * Totals of payouts in `token` (all zero if none were made); for the
+    ///   configured USDC token, the same as `get_total_distributed` and
+    ///   `get_platform_accrued`
+    pub fn get_payout_totals_in(env: Env, token: Address) -> PayoutTotals {
+        env.storage().persistent()
+            .get(&Self::totals_key(&env, &token))
+            .unwrap_or_default()
+    }
+
+    /// Whether `token` is the configured USDC token, whose payouts keep
+    /// their original storage keys
+    fn is_default_token(env: &Env, token: &Address) -> bool {
+        Self::get_instance::<Address>(env, &DataKey::UsdcToken).as_ref() == Some(token)
+    }
+
+    /// Key of a contributor's earnings in a token
+    fn earnings_key(env: &Env, contributor: &Address, token: &Address) -> DataKey {
+        if Self::is_default_token(env, token) {
+            DataKey::Earnings(contributor.clone())
+        } else {
+            DataKey::TokenEarnings(contributor.clone(), token.clone())
+        }
+    }
+
+    /// Key of the running payout totals in a token
+    fn totals_key(env: &Env, token: &Address) -> DataKey {
+        if Self::is_default_token(env, token) {
+            DataKey::Totals
+        } else {
+            DataKey::TokenTotals(token.clone())
+        }
+    }
+
+    /// Get the number of payout batches made for a dataset
+    /// 
+    /// The next batch for the dataset uses this value as its payout
+    /// sequence, so back-office systems can precompute its payout IDs.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the dataset (or bounty, for `payout_amount`)
+    /// 
+    /// # Returns
+    /// * Number of payouts made so far
+    pub fn get_payout_count(env: Env, dataset_id: Bytes) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::PayoutSeq(dataset_id))
+            .unwrap_or(0)
+    }
+
+    /// Take the next payout sequence for a dataset
+    fn next_payout_seq(env: &Env, dataset_id: &Bytes) -> u64 {
+        let storage = env.storage().persistent();
+        let key = DataKey::PayoutSeq(dataset_id.clone());
+        let sequence: u64 = storage.get(&key).unwrap_or(0);
+        storage.set(&key, &(sequence + 1));
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        sequence
+    }
+
+    /// Derive a payout ID from its `biochain_ids::PayoutId` preimage
+    /// 
+    /// # Returns
+    /// * `Ok(BytesN<32>)` SHA-256 of the preimage
+    /// * `Err(Error::InvalidContributors)` if the recipient has no strkey form
+    /// * `Err(Error::InvalidAmount)` if the amount is negative
+    fn derive_payout_id(
+        env: &Env,
+        dataset_key: [u8; 32],
+        sequence: u64,
+        recipient: &Address,
+        amount: i128,
+    ) -> Result<BytesN<32>, Error> {
+        let strkey = recipient.to_string();
+        if strkey.len() as usize != STRKEY_LEN {
+            return Err(Error::InvalidContributors);
+        }
+        let mut recipient_strkey = [0u8; STRKEY_LEN];
+        strkey.copy_into_slice(&mut recipient_strkey);
+        
+        let preimage = PayoutId::new(dataset_key, sequence, recipient_strkey, amount)
+            .map_err(|err| match err {
+                IdError::InvalidAddress => Error::InvalidContributors,
+                IdError::InvalidAmount => Error::InvalidAmount,
+            })?
+            .encode();
+        Ok(env.crypto().sha256(&Bytes::from_array(env, &preimage)).into())
+    }
+
+    /// Get the configured USDC token address
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` if configured
+    /// * `Err(Error::TokenNotSet)` if not initialized
+    pub fn get_usdc_token(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::UsdcToken)
+            .ok_or(Error::TokenNotSet)
+    }
+
+    /// Get the configured treasury address
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` if configured
+    /// * `Err(Error::TreasuryNotSet)` if not initialized
+    pub fn get_treasury(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Treasury)
+            .ok_or(Error::TreasuryNotSet)
+    }
+
+    /// Set the PlatformStats contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `stats` - Address of the PlatformStats contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_stats_contract(
+        env: Env,
+        stats: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Stats, &stats);
+        Self::audit(&env, symbol_short!("config"), Some(stats), None);
+        Ok(())
+    }
+
+    /// Set the NotificationInbox contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `inbox` - Address of the NotificationInbox contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_notification_inbox(
+        env: Env,
+        inbox: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Inbox, &inbox);
+        Self::audit(&env, symbol_short!("config"), Some(inbox), None);
+        Ok(())
+    }
+
+    /// Get the admin address
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` the current admin
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Admin).ok_or(Error::Unauthorized)
+    }
+
+    /// Propose an address to take over the admin role
+    /// 
+    /// The role only moves once the proposed admin calls `accept_admin`, so
+    /// it cannot be handed to an address nobody controls. A new proposal
+    /// replaces a pending one. Requires the current admin's authorization,
+    /// or the UpgradeCoordinator's on deployments initialized before admins
+    /// were stored. Emits an `AdminProposed` event.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_admin` - Address proposed as admin
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if neither an admin nor a coordinator
+    ///   is set
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let current: Option<Address> = Self::get_instance(&env, &DataKey::Admin);
+        match current.as_ref() {
+            Some(admin) => admin.require_auth(),
+            None => Self::require_upgrader(&env)?,
+        }
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        
+        env.events().publish(
+            (Symbol::new(&env, "AdminProposed"),),
+            (current, new_admin),
+        );
+        Ok(())
+    }
+
+    /// Accept the admin role proposed with `propose_admin`
+    /// 
+    /// Requires the proposed admin's authorization. Emits an `AdminChanged`
+    /// event.
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NoPendingAdmin)` if no admin was proposed
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        let new_admin: Address = storage.get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        new_admin.require_auth();
+        let previous: Option<Address> = Self::get_instance(&env, &DataKey::Admin);
+        storage.set(&DataKey::Admin, &new_admin);
+        storage.remove(&DataKey::PendingAdmin);
+        
+        env.events().publish(
+            (Symbol::new(&env, "AdminChanged"),),
+            (previous, new_admin.clone()),
+        );
+        Self::audit(&env, symbol_short!("admin"), Some(new_admin), None);
+        Ok(())
+    }
+
+    /// Get the admin proposed with `propose_admin`, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        Self::get_instance(&env, &DataKey::PendingAdmin)
+    }
+
+    /// Set the contract allowed to call `payout_for_dataset`
+    /// 
+    /// `payout_for_dataset` pays out of this contract's balance, so it is
+    /// rejected until a caller is set, normally the DatasetMarketplace.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - Address allowed to call `payout_for_dataset`
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_authorized_caller(env: Env, caller: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::AuthorizedCaller, &caller);
+        Self::audit(&env, symbol_short!("config"), Some(caller), None);
+        Ok(())
+    }
+
+    /// Get the contract allowed to call `payout_for_dataset`, if any
+    pub fn get_authorized_caller(env: Env) -> Option<Address> {
+        Self::get_instance(&env, &DataKey::AuthorizedCaller)
+    }
+
+    /// Set the UpgradeCoordinator contract address
+    /// 
+    /// Requires the admin's authorization. The first call sets the
+    /// coordinator; replacing it also requires the current coordinator's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `coordinator` - Address of the UpgradeCoordinator contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_upgrade_coordinator(
+        env: Env,
+        coordinator: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(current) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            current.require_auth();
+        }
+        storage.set(&DataKey::Upgrader, &coordinator);
+        Self::audit(&env, symbol_short!("upgrader"), Some(coordinator), None);
+        Ok(())
+    }
+
+    /// Pause payouts while an upgrade is applied
+    /// 
+    /// This is the UpgradeCoordinator's pause. The admin's
+    /// `emergency_pause` blocks the same calls with the same
+    /// `Error::Paused`, but has its own flag, so that an upgrade finishing
+    /// does not lift an incident halt, and the other way round.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn pause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        Self::audit(&env, symbol_short!("pause"), None, None);
+        Ok(())
+    }
+
+    /// Resume payouts after an upgrade
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &false);
+        Self::audit(&env, symbol_short!("unpause"), None, None);
+        Ok(())
+    }
+
+    /// Halt payouts and claims during an incident
+    /// 
+    /// Unlike `pause`, which the UpgradeCoordinator uses during upgrades,
+    /// the emergency pause is the admin's. While it is on, payouts, queue
+    /// processing, claims and claw-backs fail with `Error::Paused`, as they
+    /// do under `pause`; reads and configuration stay available. Each pause
+    /// is lifted only by its own unpause.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn emergency_pause(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::EmergencyPaused, &true);
+        Self::audit(&env, symbol_short!("pause"), None, None);
+        Ok(())
+    }
+
+    /// Lift the emergency pause
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn emergency_unpause(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().remove(&DataKey::EmergencyPaused);
+        Self::audit(&env, symbol_short!("unpause"), None, None);
+        Ok(())
+    }
+
+    /// Check whether the admin's emergency pause is on
+    pub fn is_emergency_paused(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::EmergencyPaused).unwrap_or(false)
+    }
+
+    /// Check whether the contract is paused
+    /// 
+    /// A tripped CircuitBreaker pauses the contract as well, until its
+    /// guardian resumes it, and so does a pause by the GuardianSet.
+    pub fn is_paused(env: Env) -> bool {
+        if Self::get_instance(&env, &DataKey::Paused).unwrap_or(false) {
+            return true;
+        }
+        if let Some(guardians) = Self::get_instance::<Address>(&env, &DataKey::Guardians) {
+            if let Ok(Ok(true)) = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &guardians,
+                &Symbol::new(&env, "is_paused"),
+                ().into_val(&env),
+            ) {
+                return true;
+            }
+        }
+        match Self::get_instance::<Address>(&env, &DataKey::Breaker) {
+            Some(breaker) => matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &breaker,
+                    &Symbol::new(&env, "is_tripped"),
+                    ().into_val(&env),
+                ),
+                Ok(Ok(true))
+            ),
+            None => false,
+        }
+    }
+
+    /// Get the configuration health of the contract
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Health snapshot; `ready` requires the token and treasury and no
+    ///   pause
+    pub fn health(env: Env) -> Health {
+        let token_set = Self::has_instance(&env, &DataKey::UsdcToken);
+        let treasury_set = Self::has_instance(&env, &DataKey::Treasury);
+        let paused = Self::is_paused(env.clone()) || Self::is_emergency_paused(env.clone());
+        
+        Health {
+            token_set,
+            treasury_set,
+            upgrader_set: Self::has_instance(&env, &DataKey::Upgrader),
+            audit_log_set: Self::has_instance(&env, &DataKey::AuditLog),
+            paused,
+            schema_version: Self::schema_version(env.clone()),
+            ready: token_set && treasury_set && !paused,
+        }
+    }
+
+    /// Replace the contract WASM
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_wasm_hash` - Hash of the uploaded WASM to switch to
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn upgrade(
+        env: Env,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        Self::audit(&env, symbol_short!("upgrade"), None, Some(new_wasm_hash.clone()));
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Run storage migrations for the current code
+    /// 
+    /// Called by the UpgradeCoordinator right after `upgrade`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the interface version after migration
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        Self::require_upgrader(&env)?;
+        
+        // Schema 2 needed no transformation
+        if Self::schema_version(env.clone()) < 3 {
+            Self::migrate_v2_to_v3(&env);
+        }
+        
+        Self::audit(&env, symbol_short!("migrate"), None, None);
+        Ok(INTERFACE_VERSION)
+    }
+
+    /// Get the storage schema version
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Schema version of the stored data (1 if no marker was ever written)
+    pub fn schema_version(env: Env) -> u32 {
+        Self::get_instance(&env, &DataKey::Schema).unwrap_or(1)
+    }
+
+    /// Migrate storage from schema 1 or 2 to schema 3
+    /// 
+    /// Configuration entries are moved to their `DataKey`. Earnings cannot
+    /// be enumerated, so they stay under their legacy keys and are added in
+    /// by `get_contributor_earnings`.
+    fn migrate_v2_to_v3(env: &Env) {
+        let from = Self::schema_version(env.clone());
+        let storage = env.storage().instance();
+        for key in CONFIG_KEYS.iter() {
+            let legacy_key = key.legacy(env);
+            if let Some(value) = storage.get::<_, Val>(&legacy_key) {
+                if !storage.has(key) {
+                    storage.set(key, &value);
+                }
+                storage.remove(&legacy_key);
+            }
+        }
+        storage.set(&DataKey::Schema, &3u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (from, 3u32),
+        );
+    }
+
+    /// Read an instance entry, falling back to its pre-schema-3 key
+    fn get_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let storage = env.storage().instance();
+        storage.get(key).or_else(|| storage.get(&key.legacy(env)))
+    }
+
+    /// Check an instance entry under its `DataKey` or pre-schema-3 key
+    fn has_instance(env: &Env, key: &DataKey) -> bool {
+        let storage = env.storage().instance();
+        storage.has(key) || storage.has(&key.legacy(env))
+    }
+
+    /// Get the crate version of the deployed code
+    pub fn version(env: Env) -> String {
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Get the interface version implemented by the current code
+    pub fn interface_version(_env: Env) -> u32 {
+        INTERFACE_VERSION
+    }
+
+    /// Set the AuditLog contract address
+    /// 
+    /// Requires the admin's authorization, so the audit trail cannot be
+    /// redirected by anyone else.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `audit_log` - Address of the AuditLog contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_audit_log(
+        env: Env,
+        audit_log: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::AuditLog, &audit_log);
+        Self::audit(&env, symbol_short!("audit"), Some(audit_log), None);
+        Ok(())
+    }
+
+    /// Set the CircuitBreaker contract address
+    /// 
+    /// The contract reports the USDC paid out by each payout to the breaker
+    /// and treats a tripped breaker as a pause of payouts. Requires the
+    /// admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `breaker` - Address of the CircuitBreaker contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_circuit_breaker(
+        env: Env,
+        breaker: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Breaker, &breaker);
+        Self::audit(&env, symbol_short!("config"), Some(breaker), None);
+        Ok(())
+    }
+
+    /// Set the GuardianSet contract address
+    /// 
+    /// Any guardian of the set can then pause payouts; resuming takes the
+    /// set's M-of-N approval. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardians` - Address of the GuardianSet contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_guardian_set(
+        env: Env,
+        guardians: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Guardians, &guardians);
+        Self::audit(&env, symbol_short!("config"), Some(guardians), None);
+        Ok(())
+    }
+
+    /// Set the TokenAllowlist contract address
+    /// 
+    /// New payouts are then refused while the allowlist does not accept the
+    /// USDC token; queued payouts and Merkle claims still complete.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `allowlist` - Address of the TokenAllowlist contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_token_allowlist(
+        env: Env,
+        allowlist: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::TokenAllowlist, &allowlist);
+        Self::audit(&env, symbol_short!("config"), Some(allowlist), None);
+        Ok(())
+    }
+
+    /// Set the ContributorOnboarding contract address
+    /// 
+    /// Contributors must then be onboarded before their first payout,
+    /// Merkle claims included. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `onboarding` - Address of the ContributorOnboarding contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_onboarding(
+        env: Env,
+        onboarding: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Onboarding, &onboarding);
+        Self::audit(&env, symbol_short!("config"), Some(onboarding), None);
+        Ok(())
+    }
+
+    /// Report an observation to the CircuitBreaker contract
+    /// 
+    /// Reporting is best-effort like analytics: if no breaker is configured
+    /// or the call fails, the payout still goes through. A trip only blocks
+    /// the calls that follow.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - CircuitBreaker metric symbol ("payout")
+    /// * `amount` - Observed amount
+    fn report_to_breaker(
+        env: &Env,
+        metric: Symbol,
+        amount: i128,
+    ) {
+        if let Some(breaker) = Self::get_instance::<Address>(env, &DataKey::Breaker) {
+            let _ = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &breaker,
+                &symbol_short!("record"),
+                (env.current_contract_address(), metric, amount).into_val(env),
+            );
+        }
+    }
+
+    /// Append a record of a privileged action to the AuditLog contract
+    /// 
+    /// Auditing is best-effort like analytics: if no audit log is configured
+    /// or the call fails, the action still goes through, so a pause during
+    /// an incident can never be blocked by the log.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `action` - AuditLog action symbol ("pause", "upgrade", ...)
+    /// * `subject` - Address the action applied to, if any
+    /// * `data` - Hash the action applied to, if any
+    fn audit(
+        env: &Env,
+        action: Symbol,
+        subject: Option<Address>,
+        data: Option<BytesN<32>>,
+    ) {
+        if let Some(audit_log) = Self::get_instance::<Address>(env, &DataKey::AuditLog) {
+            let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+                &audit_log,
+                &symbol_short!("append"),
+                (
+                    env.current_contract_address(),
+                    action,
+                    subject,
+                    data.map(Bytes::from).unwrap_or(Bytes::new(env)),
+                ).into_val(env),
+            );
+        }
+    }
+
+    /// Contributor share of the configured split, in basis points
+    fn contributor_bps(env: &Env) -> i128 {
+        Self::get_split(env.clone()).0 as i128
+    }
+
+    /// Whether `payer` is the authorized caller, so its payout is a sale
+    /// rather than self-funded
+    fn is_authorized_caller(env: &Env, payer: &Address) -> bool {
+        Self::get_instance::<Address>(env, &DataKey::AuthorizedCaller).as_ref() == Some(payer)
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(env, &DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require the UpgradeCoordinator's authorization
+    fn require_upgrader(env: &Env) -> Result<(), Error> {
+        let coordinator: Address = Self::get_instance(env, &DataKey::Upgrader)
+            .ok_or(Error::Unauthorized)?;
+        coordinator.require_auth();
+        Ok(())
+    }
+
+    /// Reject payouts in a token the TokenAllowlist does not accept
+    /// 
+    /// Without an allowlist configured every token is accepted; a failing
+    /// lookup counts as not accepted.
+    fn require_accepted_token(env: &Env, token: &Address) -> Result<(), Error> {
+        if let Some(allowlist) = Self::get_instance::<Address>(env, &DataKey::TokenAllowlist) {
+            let accepted = matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &allowlist,
+                    &Symbol::new(env, "is_accepted"),
+                    (token.clone(),).into_val(env),
+                ),
+                Ok(Ok(true))
+            );
+            if !accepted {
+                return Err(Error::TokenNotAccepted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject the first payout of contributors without an identity binding
+    /// 
+    /// Contributors already paid before are not checked again. Without an
+    /// onboarding contract configured every contributor is accepted; a
+    /// failing lookup counts as not onboarded.
+    fn require_onboarded(
+        env: &Env,
+        contributors: impl Iterator<Item = Address>,
+    ) -> Result<(), Error> {
+        let Some(onboarding) = Self::get_instance::<Address>(env, &DataKey::Onboarding) else {
+            return Ok(());
+        };
+        for contributor in contributors {
+            if Self::get_contributor_earnings(env.clone(), contributor.clone()).sales > 0 {
+                continue;
+            }
+            let onboarded = matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &onboarding,
+                    &Symbol::new(env, "is_onboarded"),
+                    (contributor,).into_val(env),
+                ),
+                Ok(Ok(true))
+            );
+            if !onboarded {
+                return Err(Error::NotOnboarded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject state-changing calls while paused
+    /// 
+    /// Every such call pays out or claims, so the emergency pause rejects
+    /// them as well.
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_emergency_paused(env.clone()) || Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+        Ok(())
+    }
+
+    /// Append a "payout" notification to a contributor's inbox
+    /// 
+    /// Notifications are best-effort: if no inbox is configured or the call
+    /// fails, the payout still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor that was paid
+    /// * `dataset_id` - ID of the dataset the payout is for
+    /// * `amount` - Amount paid in USDC
+    fn notify(
+        env: &Env,
+        contributor: &Address,
+        dataset_id: &Bytes,
+        amount: i128,
+    ) {
+        if let Some(inbox) = Self::get_instance::<Address>(env, &DataKey::Inbox) {
+            let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+                &inbox,
+                &symbol_short!("append"),
+                (
+                    env.current_contract_address(),
+                    contributor.clone(),
+                    symbol_short!("payout"),
+                    dataset_id.clone(),
+                    amount,
+                ).into_val(env),
+            );
+        }
+    }
+
+    /// Mint contribution credit for a contributor's sale
+    /// 
+    /// Best-effort like notifications: if no credit token is configured or
+    /// the call fails, the payout still goes through.
+    fn mint_credit(env: &Env, contributor: &Address) {
+        if let Some(credit_token) = Self::get_instance::<Address>(env, &DataKey::CreditToken) {
+            let _ = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+                &credit_token,
+                &Symbol::new(env, "record_sale"),
+                (env.current_contract_address(), contributor.clone()).into_val(env),
+            );
+        }
+    }
+
+    /// Count a sale towards a contributor's reputation
+    /// 
+    /// Best-effort like credit: if no reputation contract is configured or
+    /// the call fails, the payout still goes through.
+    fn report_sale(env: &Env, contributor: &Address) {
+        if let Some(reputation) = Self::get_instance::<Address>(env, &DataKey::Reputation) {
+            let _ = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+                &reputation,
+                &Symbol::new(env, "record_sale"),
+                (env.current_contract_address(), contributor.clone()).into_val(env),
+            );
+        }
+    }
+
+    /// Push an analytics increment to the PlatformStats contract
+    /// 
+    /// Analytics are best-effort: if no stats contract is configured or the
+    /// call fails, the payout still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - PlatformStats metric symbol ("payouts")
+    /// * `amount` - Increment to push
+    fn push_stat(
+        env: &Env,
+        metric: Symbol,
+        amount: i128,
+    ) {
+        if let Some(stats) = Self::get_instance::<Address>(env, &DataKey::Stats) {
+            let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &stats,
+                &symbol_short!("record"),
+                (env.current_contract_address(), metric, amount).into_val(env),
+            );
+        }
+    }
+
+    /// Set the RoyaltyRegistry contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `royalty_registry` - Address of the RoyaltyRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_royalty_registry(
+        env: Env,
+        royalty_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Royalty, &royalty_registry);
+        Self::audit(&env, symbol_short!("config"), Some(royalty_registry), None);
+        Ok(())
+    }
+
+    /// Load the royalty terms of a dataset from RoyaltyRegistry
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - ID of the purchased dataset
+    /// 
+    /// # Returns
+    /// * Terms for the dataset (zero terms if no registry is configured)
+    fn load_royalty_terms(
+        env: &Env,
+        dataset_id: &Bytes,
+    ) -> RoyaltyTerms {
+        match Self::get_instance::<Address>(env, &DataKey::Royalty) {
+            Some(royalty_registry) => env.invoke_contract(
+                &royalty_registry,
+                &Symbol::new(env, "dataset_terms_or_default"),
+                (dataset_id.clone(),).into_val(env),
+            ),
+            None => RoyaltyTerms {
+                resale_bps: 0,
+                institutional_bps: 0,
+                institution: None,
+                referral_bps: 0,
+                referrer: None,
+                updated_at: 0,
+            },
+        }
+    }
+
+    /// Set the ReferralNetwork contract address
+    /// 
+    /// Once set, part of the platform share of every payout is routed up
+    /// the contributor's referral chain, as configured in the network.
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `referral_network` - Address of the ReferralNetwork contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_referral_network(
+        env: Env,
+        referral_network: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::ReferralNetwork, &referral_network);
+        Self::audit(&env, symbol_short!("config"), Some(referral_network), None);
+        Ok(())
+    }
+
+    /// Referral amounts owed on a contributor's platform share
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Contributor being paid
+    /// * `platform_amount` - Platform share the carve-out is taken from
+    /// 
+    /// # Returns
+    /// * (referrer, amount) pairs with a positive amount, direct referrer first
+    /// * `Err(Error::Overflow)` if an amount overflows
+    fn referral_chain(
+        env: &Env,
+        contributor: &Address,
+        platform_amount: i128,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        let mut chain = Vec::new(env);
+        let referral_network: Address = match Self::get_instance(env, &DataKey::ReferralNetwork) {
+            Some(network) => network,
+            None => return Ok(chain),
+        };
+        
+        let shares: Vec<ReferralShare> = env.invoke_contract(
+            &referral_network,
+            &Symbol::new(env, "get_payout_chain"),
+            (contributor.clone(),).into_val(env),
+        );
+        for share in shares.iter() {
+            let amount = Self::apply_bps(platform_amount, share.share_bps)?;
+            if amount > 0 {
+                chain.push_back((share.referrer, amount));
+            }
+        }
+        Ok(chain)
+    }
+
+    /// Set the ContributionCredit contract address
+    /// 
+    /// Once set, every payout by the authorized caller mints sale credit to
+    /// each contributor paid; self-funded payouts mint none. This contract
+    /// must be added as a minter in ContributionCredit. Requires the
+    /// admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `credit_token` - Address of the ContributionCredit contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_credit_token(
+        env: Env,
+        credit_token: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::CreditToken, &credit_token);
+        Self::audit(&env, symbol_short!("config"), Some(credit_token), None);
+        Ok(())
+    }
+
+    /// Set the ContributorReputation contract address
+    /// 
+    /// Once set, every payout by the authorized caller counts as a sale
+    /// towards the reputation of each contributor paid; self-funded payouts
+    /// count for nothing. This contract must be added as a reporter in
+    /// ContributorReputation. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reputation` - Address of the ContributorReputation contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_reputation_contract(
+        env: Env,
+        reputation: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Reputation, &reputation);
+        Self::audit(&env, symbol_short!("config"), Some(reputation), None);
+        Ok(())
+    }
+
+    /// Set the InsurancePool contract address
+    /// 
+    /// Once set, part of the platform share of every payout is paid into
+    /// the pool as a premium for the contributor's studies.
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `insurance_pool` - Address of the InsurancePool contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_insurance_pool(
+        env: Env,
+        insurance_pool: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Insurance, &insurance_pool);
+        Self::audit(&env, symbol_short!("config"), Some(insurance_pool), None);
+        Ok(())
+    }
+
+    /// Pay a contributor's insurance premium out of the platform share
+    /// 
+    /// The premium rate comes from InsurancePool.premium_bps() and grows
+    /// with the number of upheld claims against the contributor.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `contributor` - Contributor the premium is charged for
+    /// * `platform_amount` - Platform share of this contributor's reward
+    /// 
+    /// # Returns
+    /// * Premium paid into the pool (0 if no pool is configured)
+    /// * `Err(Error::Overflow)` if the premium overflows
+    fn pay_insurance_premium(
+        env: &Env,
+        usdc_token: &Address,
+        contributor: &Address,
+        platform_amount: i128,
+    ) -> Result<i128, Error> {
+        let Some(insurance_pool) = Self::get_instance::<Address>(env, &DataKey::Insurance) else {
+            return Ok(0);
+        };
+        let premium_amount = Self::insurance_premium(env, contributor, platform_amount)?;
+        
+        if premium_amount > 0 {
+            // The pool pulls the premium from this contract, so authorize
+            // the nested USDC transfer up front
+            let contract_address = env.current_contract_address();
+            env.authorize_as_current_contract(soroban_sdk::vec![
+                env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: usdc_token.clone(),
+                        fn_name: symbol_short!("transfer"),
+                        args: (
+                            contract_address.clone(),
+                            insurance_pool.clone(),
+                            premium_amount,
+                        ).into_val(env),
+                    },
+                    sub_invocations: Vec::new(env),
+                }),
+            ]);
+            
+            env.invoke_contract::<()>(
+                &insurance_pool,
+                &Symbol::new(env, "deposit_premium"),
+                (
+                    env.current_contract_address(),
+                    contributor.clone(),
+                    premium_amount,
+                ).into_val(env),
+            );
+        }
+        
+        Ok(premium_amount)
+    }
+
+    /// Insurance premium owed for a contributor's platform share
+    /// 
+    /// # Returns
+    /// * Premium amount (0 if no pool is configured)
+    /// * `Err(Error::Overflow)` if the premium overflows
+    fn insurance_premium(
+        env: &Env,
+        contributor: &Address,
+        platform_amount: i128,
+    ) -> Result<i128, Error> {
+        let insurance_pool: Address = match Self::get_instance(env, &DataKey::Insurance) {
+            Some(pool) => pool,
+            None => return Ok(0),
+        };
+        
+        let premium_bps: u32 = env.invoke_contract(
+            &insurance_pool,
+            &Symbol::new(env, "premium_bps"),
+            (contributor.clone(),).into_val(env),
+        );
+        Self::apply_bps(platform_amount, premium_bps)
+    }
+
+    /// Take a rate in basis points of an amount, rounded toward zero
+    fn apply_bps(
+        amount: i128,
+        bps: u32,
+    ) -> Result<i128, Error> {
+        Ok(math::checked_mul(amount, bps as i128)? / 10_000)
     }
 }
@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+//! Resource budget benchmarks for the batch settlement paths
+//!
+//! Measures CPU instructions and memory with the host budget across batch
+//! sizes. Each size has a regression budget with headroom over the
+//! measured cost; raise a budget only together with the change that makes
+//! the path more expensive.
+
+use revenue_splitter::{PayoutMode, RevenueSplitter, RevenueSplitterClient, MAX_CONTRIBUTORS_PER_BATCH};
+use soroban_sdk::{testutils::Address as AddressTestUtils, token, Address, Bytes, Env, Vec};
+
+/// Network limits per transaction (CPU instructions / memory bytes)
+const TX_CPU_LIMIT: u64 = 100_000_000;
+const TX_MEM_LIMIT: u64 = 41_943_040;
+
+/// Share of the transaction limits a full batch may use
+///
+/// Batch calls share the transaction with their caller, such as a
+/// marketplace purchase paying its contributors, so a batch of
+/// MAX_CONTRIBUTORS_PER_BATCH must leave most of the transaction to it.
+const FULL_BATCH_LIMIT_DIVISOR: u64 = 4;
+
+/// Regression budgets for payout_batch (contributors, max cpu, max mem)
+///
+/// Each contributor costs a transfer to them and one to the treasury, the
+/// lifetime and annual earnings records and a payout ID hash; the fixed
+/// cost covers the payment pull and the running payout totals.
+const PAYOUT_BATCH_BUDGETS: [(u32, u64, u64); 2] = [
+    (10, 5_400_000, 800_000),
+    (MAX_CONTRIBUTORS_PER_BATCH, 11_600_000, 1_900_000),
+];
+
+/// Regression budgets for claim_many (contributors, max cpu, max mem)
+///
+/// Each contributor costs reading and rewriting their earnings record and
+/// claim count, and one transfer.
+const CLAIM_MANY_BUDGETS: [(u32, u64, u64); 2] = [
+    (10, 3_350_000, 595_000),
+    (MAX_CONTRIBUTORS_PER_BATCH, 8_050_000, 1_650_000),
+];
+
+/// Measured cost of one call
+#[derive(Debug)]
+struct Cost {
+    cpu: u64,
+    mem: u64,
+}
+
+struct Bench<'a> {
+    env: Env,
+    splitter: RevenueSplitterClient<'a>,
+    usdc: Address,
+}
+
+/// Helper: Deploy an initialized RevenueSplitter
+fn setup<'a>() -> Bench<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Setup is not measured; only the calls wrapped in `measure` are
+    env.budget().reset_unlimited();
+
+    let splitter =
+        RevenueSplitterClient::new(&env, &env.register_contract(None, RevenueSplitter));
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc, &Address::generate(&env));
+    splitter.set_upgrade_coordinator(&Address::generate(&env));
+    Bench { env, splitter, usdc }
+}
+
+/// Helper: Fund a payer with `amount` USDC
+fn funded_payer(bench: &Bench, amount: i128) -> Address {
+    let payer = Address::generate(&bench.env);
+    token::StellarAssetClient::new(&bench.env, &bench.usdc).mint(&payer, &amount);
+    payer
+}
+
+/// Helper: `count` distinct contributors with a 10 USDC share each
+fn batch(bench: &Bench, count: u32) -> (Vec<Address>, Vec<i128>) {
+    let mut contributors = Vec::new(&bench.env);
+    let mut amounts = Vec::new(&bench.env);
+    for _ in 0..count {
+        contributors.push_back(Address::generate(&bench.env));
+        amounts.push_back(10_0000000);
+    }
+    (contributors, amounts)
+}
+
+/// Helper: Measure a single contract call with an unlimited budget
+fn measure(env: &Env, call: impl FnOnce()) -> Cost {
+    env.budget().reset_unlimited();
+    call();
+    Cost {
+        cpu: env.budget().cpu_instruction_cost(),
+        mem: env.budget().memory_bytes_cost(),
+    }
+}
+
+#[test]
+fn bench_payout_batch_by_contributor_count() {
+    for (count, max_cpu, max_mem) in PAYOUT_BATCH_BUDGETS {
+        let bench = setup();
+        let (contributors, amounts) = batch(&bench, count);
+        let payer = funded_payer(&bench, 10_0000000 * count as i128);
+        let dataset_id = Bytes::from_slice(&bench.env, b"bench_dataset");
+
+        // Act
+        let cost = measure(&bench.env, || {
+            bench
+                .splitter
+                .payout_batch(&payer, &dataset_id, &contributors, &amounts);
+        });
+
+        // Assert: the whole batch is paid without queueing
+        assert_eq!(bench.splitter.get_queue_length(), 0);
+        assert!(
+            cost.cpu <= max_cpu,
+            "payout_batch cpu regressed for {count} contributors: {cost:?}"
+        );
+        assert!(
+            cost.mem <= max_mem,
+            "payout_batch mem regressed for {count} contributors: {cost:?}"
+        );
+        assert!(cost.cpu <= TX_CPU_LIMIT && cost.mem <= TX_MEM_LIMIT);
+        if count == MAX_CONTRIBUTORS_PER_BATCH {
+            assert!(
+                cost.cpu <= TX_CPU_LIMIT / FULL_BATCH_LIMIT_DIVISOR
+                    && cost.mem <= TX_MEM_LIMIT / FULL_BATCH_LIMIT_DIVISOR,
+                "a full batch uses too much of the transaction: {cost:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn bench_claim_many_by_contributor_count() {
+    for (count, max_cpu, max_mem) in CLAIM_MANY_BUDGETS {
+        let bench = setup();
+        bench.splitter.set_payout_mode(&PayoutMode::Accrue);
+        let (contributors, amounts) = batch(&bench, count);
+        let payer = funded_payer(&bench, 10_0000000 * count as i128);
+        bench.splitter.payout_batch(
+            &payer,
+            &Bytes::from_slice(&bench.env, b"bench_dataset"),
+            &contributors,
+            &amounts,
+        );
+
+        // Act
+        let cost = measure(&bench.env, || {
+            bench.splitter.claim_many(&contributors);
+        });
+
+        // Assert: every balance was withdrawn
+        assert_eq!(bench.splitter.get_total_distributed(), 8_5000000 * count as i128);
+        assert_eq!(
+            token::Client::new(&bench.env, &bench.usdc).balance(&bench.splitter.address),
+            0
+        );
+        assert!(
+            cost.cpu <= max_cpu,
+            "claim_many cpu regressed for {count} contributors: {cost:?}"
+        );
+        assert!(
+            cost.mem <= max_mem,
+            "claim_many mem regressed for {count} contributors: {cost:?}"
+        );
+        assert!(cost.cpu <= TX_CPU_LIMIT && cost.mem <= TX_MEM_LIMIT);
+        if count == MAX_CONTRIBUTORS_PER_BATCH {
+            assert!(
+                cost.cpu <= TX_CPU_LIMIT / FULL_BATCH_LIMIT_DIVISOR
+                    && cost.mem <= TX_MEM_LIMIT / FULL_BATCH_LIMIT_DIVISOR,
+                "a full batch uses too much of the transaction: {cost:?}"
+            );
+        }
+    }
+}
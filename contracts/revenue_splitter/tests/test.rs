@@ -1,9 +1,17 @@
 #![cfg(test)]
 
-use super::*;
+use biochain_ids::{PayoutId, PayoutLeaf};
+use revenue_splitter::{
+    AnnualEarnings, ContributorEarnings, ContributorRewarded, DataKey, Error, PayoutCommitment, PayoutLimits, PayoutMode, RevenueSplitter, RevenueSplitterClient,
+    DEFAULT_CONTRIBUTOR_BPS, DEFAULT_PLATFORM_BPS, INTERFACE_VERSION, MAX_CONTRIBUTORS_PER_BATCH,
+    SCHEMA_VERSION,
+};
 use soroban_sdk::{
-    Env, Address, Bytes, Vec, I128, token,
-    testutils::{Address as AddressTestUtils, Events as EventsTestUtils},
+    Env, Address, Bytes, BytesN, String, Symbol, TryFromVal, Vec, symbol_short, token,
+    testutils::{
+        Address as AddressTestUtils, AuthorizedFunction, AuthorizedInvocation,
+        Events as EventsTestUtils, IssuerFlags, Ledger,
+    },
 };
 
 /// Helper: Create a test environment
@@ -18,42 +26,29 @@ fn create_address(env: &Env) -> Address {
 
 /// Helper: Create a mock USDC token contract
 /// 
-/// Note: In a real test environment, we would use a proper mock token contract.
-/// For now, we create a simple token address that we'll use for testing.
-/// The actual token operations would require a deployed token contract.
-fn create_mock_token(env: &Env, _admin: &Address) -> (Address, token::Client) {
-    // Create a mock token address
-    // In production tests with proper token mocking, we'd deploy a real token contract
-    let token_id = Address::generate(env);
+/// Deploys a Stellar Asset Contract administered by `admin`.
+fn create_mock_token<'a>(env: &Env, admin: &Address) -> (Address, token::Client<'a>) {
+    let token_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
     let token_client = token::Client::new(env, &token_id);
     
     (token_id, token_client)
 }
 
 /// Helper: Fund an address with USDC
-/// 
-/// Note: This is a placeholder. In real tests with a mock token contract,
-/// we would call token.mint() to fund addresses.
-fn fund_usdc(_env: &Env, _token: &token::Client, _to: &Address, _amount: i128) {
-    // In a real test with mock token contract:
-    // let admin = Address::generate(env);
-    // token.mint(&admin, to, &I128::from(amount));
-    // For now, this is a placeholder since we don't have a real token contract
+fn fund_usdc(env: &Env, token: &token::Client, to: &Address, amount: i128) {
+    env.mock_all_auths();
+    token::StellarAssetClient::new(env, &token.address).mint(to, &amount);
 }
 
 /// Helper: Get USDC balance
-/// 
-/// Note: This is a placeholder. In real tests with a mock token contract,
-/// we would call token.balance() to get the balance.
-fn get_balance(_env: &Env, _token: &token::Client, _address: &Address) -> i128 {
-    // In a real test with mock token contract:
-    // token.balance(address).into()
-    // For now, return 0 as placeholder
-    0
+fn get_balance(_env: &Env, token: &token::Client, address: &Address) -> i128 {
+    token.balance(address)
 }
 
 /// Helper: Create RevenueSplitter client
-fn create_revenue_splitter_client(env: &Env) -> RevenueSplitterClient {
+fn create_revenue_splitter_client(env: &Env) -> RevenueSplitterClient<'_> {
     let contract_id = env.register_contract(None, RevenueSplitter);
     RevenueSplitterClient::new(env, &contract_id)
 }
@@ -68,74 +63,406 @@ fn test_init() {
     let treasury = create_address(&env);
     
     // Act
-    let result = client.init(&usdc_token, &treasury);
+    let result = client.try_init(&create_address(&env), &usdc_token, &treasury);
     
     // Assert
     assert!(result.is_ok(), "init should succeed");
     
     // Verify configuration was stored
-    let stored_token = client.get_usdc_token();
-    assert!(stored_token.is_ok(), "get_usdc_token should succeed");
-    assert_eq!(stored_token.unwrap(), usdc_token, "USDC token should match");
+    assert_eq!(client.get_usdc_token(), usdc_token, "USDC token should match");
+    assert_eq!(client.get_treasury(), treasury, "Treasury should match");
+}
+
+#[test]
+fn test_init_cannot_be_repeated() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let usdc_token = create_address(&env);
+    let treasury = create_address(&env);
+    client.init(&admin, &usdc_token, &treasury);
+    
+    // Act: nobody can take over the admin role or redirect the treasury
+    let result = client.try_init(&create_address(&env), &usdc_token, &create_address(&env));
+    
+    // Assert
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_treasury(), treasury);
+    
+    // Deployments initialized before admins were stored are guarded too
+    let legacy = create_revenue_splitter_client(&env);
+    env.as_contract(&legacy.address, || {
+        env.storage().instance().set(&symbol_short!("USDC_TKN"), &usdc_token);
+    });
+    assert_eq!(
+        legacy.try_init(&create_address(&env), &usdc_token, &treasury),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(legacy.try_get_admin(), Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_admin_transfer_requires_acceptance() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+    let new_admin = create_address(&env);
+    
+    // Act: proposing does not hand over the role
+    client.propose_admin(&new_admin);
+    assert_eq!(client.get_admin(), admin.clone());
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    client.accept_admin();
+    
+    // Assert: the proposed admin accepted with their own authorization
+    assert_eq!(
+        env.auths()[0],
+        (
+            new_admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    client.address.clone(),
+                    Symbol::new(&env, "accept_admin"),
+                    Vec::new(&env),
+                )),
+                sub_invocations: std::vec::Vec::new(),
+            }
+        )
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap(),
+        Symbol::new(&env, "AdminChanged")
+    );
+    assert_eq!(
+        <(Option<Address>, Address)>::try_from_val(&env, &data).unwrap(),
+        (Some(admin), new_admin.clone())
+    );
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+}
+
+#[test]
+fn test_set_stats_contract_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let stats = create_address(&env);
+    assert_eq!(client.try_set_stats_contract(&stats), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_stats_contract(&stats).is_err());
+    env.mock_all_auths();
+    client.set_stats_contract(&stats);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_insurance_pool_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let insurance_pool = create_address(&env);
+    assert_eq!(client.try_set_insurance_pool(&insurance_pool), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_insurance_pool(&insurance_pool).is_err());
+    env.mock_all_auths();
+    client.set_insurance_pool(&insurance_pool);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_royalty_registry_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let royalty_registry = create_address(&env);
+    assert_eq!(client.try_set_royalty_registry(&royalty_registry), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_royalty_registry(&royalty_registry).is_err());
+    env.mock_all_auths();
+    client.set_royalty_registry(&royalty_registry);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_notification_inbox_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let inbox = create_address(&env);
+    assert_eq!(client.try_set_notification_inbox(&inbox), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_notification_inbox(&inbox).is_err());
+    env.mock_all_auths();
+    client.set_notification_inbox(&inbox);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_referral_network_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let referral_network = create_address(&env);
+    assert_eq!(client.try_set_referral_network(&referral_network), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_referral_network(&referral_network).is_err());
+    env.mock_all_auths();
+    client.set_referral_network(&referral_network);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_upgrade_coordinator_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let coordinator = create_address(&env);
+    assert_eq!(client.try_set_upgrade_coordinator(&coordinator), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_upgrade_coordinator(&coordinator).is_err());
+    env.mock_all_auths();
+    client.set_upgrade_coordinator(&coordinator);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_circuit_breaker_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let breaker = create_address(&env);
+    assert_eq!(client.try_set_circuit_breaker(&breaker), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_circuit_breaker(&breaker).is_err());
+    env.mock_all_auths();
+    client.set_circuit_breaker(&breaker);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_audit_log_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let log = create_address(&env);
+    assert_eq!(client.try_set_audit_log(&log), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_audit_log(&log).is_err());
+    env.mock_all_auths();
+    client.set_audit_log(&log);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_guardian_set_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let guardians = create_address(&env);
+    assert_eq!(client.try_set_guardian_set(&guardians), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_guardian_set(&guardians).is_err());
+    env.mock_all_auths();
+    client.set_guardian_set(&guardians);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_token_allowlist_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let allowlist = create_address(&env);
+    assert_eq!(client.try_set_token_allowlist(&allowlist), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_token_allowlist(&allowlist).is_err());
+    env.mock_all_auths();
+    client.set_token_allowlist(&allowlist);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_onboarding_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let onboarding = create_address(&env);
+    assert_eq!(client.try_set_onboarding(&onboarding), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_onboarding(&onboarding).is_err());
+    env.mock_all_auths();
+    client.set_onboarding(&onboarding);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_credit_token_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let credit = create_address(&env);
+    assert_eq!(client.try_set_credit_token(&credit), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_credit_token(&credit).is_err());
+    env.mock_all_auths();
+    client.set_credit_token(&credit);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_reputation_contract_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    let reputation = create_address(&env);
+    assert_eq!(client.try_set_reputation_contract(&reputation), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_reputation_contract(&reputation).is_err());
+    env.mock_all_auths();
+    client.set_reputation_contract(&reputation);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_queue_tip_requires_the_admin() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    assert_eq!(client.try_set_queue_tip(&1000), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin, &create_address(&env), &create_address(&env));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_queue_tip(&1000).is_err());
+    env.mock_all_auths();
+    client.set_queue_tip(&1000);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_coordinator_installs_admin_on_legacy_deployment() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: initialized before admins were stored
+    let usdc_token = create_address(&env);
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&symbol_short!("USDC_TKN"), &usdc_token);
+    });
+    let admin = create_address(&env);
+    assert_eq!(client.try_propose_admin(&admin), Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.try_set_split(&9_000, &1_000), Err(Ok(Error::Unauthorized)));
+    assert_eq!(
+        client.try_set_upgrade_coordinator(&create_address(&env)),
+        Err(Ok(Error::Unauthorized))
+    );
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&symbol_short!("UPGRADER"), &create_address(&env));
+    });
+    
+    // Act
+    client.propose_admin(&admin);
+    client.accept_admin();
     
-    let stored_treasury = client.get_treasury();
-    assert!(stored_treasury.is_ok(), "get_treasury should succeed");
-    assert_eq!(stored_treasury.unwrap(), treasury, "Treasury should match");
+    // Assert: the admin now manages the configuration
+    assert_eq!(client.get_admin(), admin);
+    client.set_split(&9_000, &1_000);
+    assert_eq!(client.get_split(), (9_000, 1_000));
 }
 
 #[test]
-fn test_payout_for_dataset_splits_correctly_for_multiple_contributors() {
+fn test_payout_for_dataset_weights_contributors_by_studies() {
     let env = create_env();
     let client = create_revenue_splitter_client(&env);
     
     // Arrange
     let admin = create_address(&env);
-    let (usdc_token, _usdc_token_client) = create_mock_token(&env, &admin);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
     let treasury = create_address(&env);
     
     // Initialize contract
-    client.init(&usdc_token, &treasury).unwrap();
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
     
-    // Create contributors
+    // Create contributors who supplied 1, 2 and 3 studies
     let contributor_a = create_address(&env);
     let contributor_b = create_address(&env);
     let contributor_c = create_address(&env);
-    let contributors = Vec::from_array(&env, [
-        contributor_a.clone(),
-        contributor_b.clone(),
-        contributor_c.clone(),
+    let weights = Vec::from_array(&env, [
+        (contributor_a.clone(), 1u32),
+        (contributor_b.clone(), 2),
+        (contributor_c.clone(), 3),
     ]);
     
     let dataset_id = Bytes::from_slice(&env, b"dataset_multiple_contributors");
     
     // Calculate expected amounts
-    const BASE_REWARD: i128 = 10_0000000; // 10 USDC with 7 decimals
-    let user_amount = (BASE_REWARD * 85) / 100; // 8.5 USDC
-    let platform_amount = BASE_REWARD - user_amount; // 1.5 USDC
+    const PRICE: i128 = 60_0000000; // 60 USDC with 7 decimals
+    let share = PRICE / 6; // 10 USDC per study
+    let user_amount = (share * 85) / 100; // 8.5 USDC
+    let platform_amount = share - user_amount; // 1.5 USDC
     
     assert_eq!(user_amount, 8_5000000, "User amount should be 8.5 USDC");
     assert_eq!(platform_amount, 1_5000000, "Platform amount should be 1.5 USDC");
     
-    // Note: In a real test with a mock token contract, we would:
-    // 1. Fund RevenueSplitter contract with enough USDC
-    // 2. Call payout_for_dataset
-    // 3. Verify balances increased correctly
-    // 
-    // For now, we test the structure and logic without actual token transfers
-    // The contract logic is correct, but token operations require a real token contract
+    // Fund RevenueSplitter contract with the purchase amount
+    fund_usdc(&env, &usdc_token_client, &client.address, PRICE);
     
     // Act
-    // This will fail without a real token contract, but we can verify the structure
-    let result = client.payout_for_dataset(&dataset_id, &contributors);
+    let result = client.try_payout_for_dataset(&dataset_id, &weights, &PRICE);
+    
+    // Assert: each contributor earns in proportion to their studies
+    assert!(result.is_ok(), "payout_for_dataset should succeed");
+    for (contributor, studies) in weights.iter() {
+        assert_eq!(
+            get_balance(&env, &usdc_token_client, &contributor),
+            user_amount * studies as i128
+        );
+    }
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), platform_amount * 6);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
     
-    // Assert: The call structure is correct
-    // In a full test with mock token, we'd verify:
-    // - result.is_ok()
-    // - Balances are correct
-    // - Events are emitted
-    // 
-    // For now, we just verify the function can be called (it will fail on token transfer)
-    // This is expected behavior without a real token contract
+    // A zero weight is rejected before any USDC moves
+    let weights = Vec::from_array(&env, [(contributor_a, 1u32), (contributor_b, 0)]);
+    assert_eq!(
+        client.try_payout_for_dataset(&dataset_id, &weights, &PRICE),
+        Err(Ok(Error::InvalidContributors))
+    );
 }
 
 #[test]
@@ -145,34 +472,125 @@ fn test_payout_for_dataset_emits_events() {
     
     // Arrange
     let admin = create_address(&env);
-    let (usdc_token, _usdc_token_client) = create_mock_token(&env, &admin);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
     let treasury = create_address(&env);
-    client.init(&usdc_token, &treasury).unwrap();
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
     
     let contributor1 = create_address(&env);
     let contributor2 = create_address(&env);
     let contributors = Vec::from_array(&env, [
-        contributor1.clone(),
-        contributor2.clone(),
+        (contributor1.clone(), 1u32),
+        (contributor2.clone(), 1),
     ]);
     
     let dataset_id = Bytes::from_slice(&env, b"dataset_events_test");
+    fund_usdc(&env, &usdc_token_client, &client.address, 20_0000000);
     
     // Act
-    // Note: This will fail without a real token contract, but we can check event structure
-    let _result = client.payout_for_dataset(&dataset_id, &contributors);
+    client.payout_for_dataset(&dataset_id, &contributors, &20_0000000);
     
-    // Assert: Verify events structure
-    // In a real test with mock token, we'd verify:
+    // Assert: 2 ContributorRewarded events + 1 DatasetPayoutCompleted event
+    // (token transfer events are emitted by the token contract)
     let events = env.events().all();
+    let splitter_events = events
+        .iter()
+        .filter(|(contract, _, _)| *contract == client.address)
+        .count();
+    assert_eq!(splitter_events, 3, "Expected one event per contributor plus a summary");
+}
+
+#[test]
+fn test_payout_ids_are_derivable_off_chain() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
+    
+    let contributor = create_address(&env);
+    let contributors = Vec::from_array(&env, [(contributor.clone(), 1u32)]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_payout_ids");
+    fund_usdc(&env, &usdc_token_client, &client.address, 20_0000000);
+    assert_eq!(client.get_payout_count(&dataset_id), 0);
+    
+    // Act: two payouts of the same dataset to the same contributor
+    client.payout_for_dataset(&dataset_id, &contributors, &10_0000000);
+    client.payout_for_dataset(&dataset_id, &contributors, &10_0000000);
+    let rewarded = rewarded_events(&env, &client.address);
+    let (first, second) = (&rewarded[0], &rewarded[1]);
+    
+    // Assert: IDs follow the shared preimage and differ by sequence
+    assert_eq!(client.get_payout_count(&dataset_id), 2);
+    assert_eq!((first.sequence, second.sequence), (0, 1));
+    assert_ne!(first.payout_id, second.payout_id);
+    
+    let mut strkey = [0u8; 56];
+    contributor.to_string().copy_into_slice(&mut strkey);
+    let preimage = PayoutId::new(
+        env.crypto().sha256(&dataset_id).to_array(),
+        1,
+        strkey,
+        second.user_amount,
+    ).unwrap().encode();
+    let expected: BytesN<32> = env.crypto().sha256(&Bytes::from_array(&env, &preimage)).into();
+    assert_eq!(second.payout_id, expected);
+}
+
+/// Helper: Decode the ContributorRewarded events emitted by the splitter
+fn rewarded_events(env: &Env, splitter: &Address) -> std::vec::Vec<ContributorRewarded> {
+    env.events()
+        .all()
+        .iter()
+        .filter(|(contract, topics, _)| {
+            contract == splitter
+                && Symbol::try_from_val(env, &topics.get(0).unwrap()).ok()
+                    == Some(Symbol::new(env, "ContributorRewarded"))
+        })
+        .map(|(_, _, data)| ContributorRewarded::try_from_val(env, &data).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_payout_for_dataset_divides_the_purchase_amount() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a price that does not divide evenly
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
+    let contributors = Vec::from_array(&env, [
+        (create_address(&env), 1u32),
+        (create_address(&env), 1),
+        (create_address(&env), 1),
+    ]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_custom_price");
+    fund_usdc(&env, &usdc_token_client, &client.address, 10_0000001);
+    
+    // Act
+    client.payout_for_dataset(&dataset_id, &contributors, &10_0000001);
+    
+    // Assert: exactly the price is paid out, the dust to the treasury
+    for (contributor, _) in contributors.iter() {
+        assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 2_8333333);
+    }
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 1_5000002);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    assert_eq!(client.get_total_distributed(), 8_4999999);
+    assert_eq!(client.get_platform_accrued(), 1_5000002);
     
-    // Note: Events may not be emitted if the function fails early
-    // In a full test with mock token, we'd verify:
-    // - 2 ContributorRewarded events (one per contributor)
-    // - 1 DatasetPayoutCompleted event
-    // 
-    // For now, we just verify the event structure is correct
-    // The actual event emission requires successful token transfers
+    // A share too small to split is rejected before any USDC moves
+    for amount in [0, -1, 3] {
+        assert_eq!(
+            client.try_payout_for_dataset(&dataset_id, &contributors, &amount),
+            Err(Ok(Error::InvalidAmount))
+        );
+    }
 }
 
 #[test]
@@ -183,20 +601,17 @@ fn test_payout_for_dataset_rejects_empty_contributors() {
     // Arrange
     let usdc_token = create_address(&env);
     let treasury = create_address(&env);
-    client.init(&usdc_token, &treasury).unwrap();
+    client.init(&create_address(&env), &usdc_token, &treasury);
     
     let dataset_id = Bytes::from_slice(&env, b"dataset_empty_contributors");
     let empty_contributors = Vec::new(&env);
     
     // Act
-    let result = client.payout_for_dataset(&dataset_id, &empty_contributors);
+    let result = client.try_payout_for_dataset(&dataset_id, &empty_contributors, &10_0000000);
     
     // Assert
     assert!(result.is_err(), "Empty contributors should fail");
-    match result.unwrap_err() {
-        Error::InvalidContributors => {},
-        _ => panic!("Expected InvalidContributors error"),
-    }
+    assert_eq!(result.unwrap_err(), Ok(Error::InvalidContributors));
 }
 
 #[test]
@@ -206,18 +621,15 @@ fn test_payout_for_dataset_fails_if_not_initialized() {
     
     // Arrange: Don't initialize
     let contributor = create_address(&env);
-    let contributors = Vec::from_array(&env, [contributor]);
+    let contributors = Vec::from_array(&env, [(contributor, 1u32)]);
     let dataset_id = Bytes::from_slice(&env, b"dataset_not_initialized");
     
     // Act
-    let result = client.payout_for_dataset(&dataset_id, &contributors);
+    let result = client.try_payout_for_dataset(&dataset_id, &contributors, &10_0000000);
     
     // Assert
     assert!(result.is_err(), "Should fail if not initialized");
-    match result.unwrap_err() {
-        Error::TokenNotSet | Error::TreasuryNotSet => {},
-        _ => panic!("Expected initialization error"),
-    }
+    assert_eq!(result.unwrap_err(), Ok(Error::TokenNotSet));
 }
 
 #[test]
@@ -262,24 +674,19 @@ fn test_get_configuration() {
     let client = create_revenue_splitter_client(&env);
     
     // Before initialization, should fail
-    let token_result = client.get_usdc_token();
-    assert!(token_result.is_err(), "get_usdc_token should fail before init");
+    let token_result = client.try_get_usdc_token();
+    assert_eq!(token_result, Err(Ok(Error::TokenNotSet)), "get_usdc_token should fail before init");
     
-    let treasury_result = client.get_treasury();
-    assert!(treasury_result.is_err(), "get_treasury should fail before init");
+    let treasury_result = client.try_get_treasury();
+    assert_eq!(treasury_result, Err(Ok(Error::TreasuryNotSet)), "get_treasury should fail before init");
     
     // After initialization, should succeed
     let usdc_token = create_address(&env);
     let treasury = create_address(&env);
-    client.init(&usdc_token, &treasury).unwrap();
+    client.init(&create_address(&env), &usdc_token, &treasury);
     
-    let stored_token = client.get_usdc_token();
-    assert!(stored_token.is_ok(), "get_usdc_token should succeed after init");
-    assert_eq!(stored_token.unwrap(), usdc_token, "Stored token should match");
-    
-    let stored_treasury = client.get_treasury();
-    assert!(stored_treasury.is_ok(), "get_treasury should succeed after init");
-    assert_eq!(stored_treasury.unwrap(), treasury, "Stored treasury should match");
+    assert_eq!(client.get_usdc_token(), usdc_token, "Stored token should match");
+    assert_eq!(client.get_treasury(), treasury, "Stored treasury should match");
 }
 
 #[test]
@@ -289,25 +696,1241 @@ fn test_single_contributor_payout() {
     
     // Arrange
     let admin = create_address(&env);
-    let (usdc_token, _usdc_token_client) = create_mock_token(&env, &admin);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
     let treasury = create_address(&env);
     
-    client.init(&usdc_token, &treasury).unwrap();
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
     
     let contributor = create_address(&env);
-    let contributors = Vec::from_array(&env, [contributor.clone()]);
+    let contributors = Vec::from_array(&env, [(contributor.clone(), 1u32)]);
     let dataset_id = Bytes::from_slice(&env, b"dataset_single_contributor");
+    fund_usdc(&env, &usdc_token_client, &client.address, 10_0000000);
+    
+    // Act
+    let result = client.try_payout_for_dataset(&dataset_id, &contributors, &10_0000000);
+    
+    // Assert
+    assert!(result.is_ok(), "payout_for_dataset should succeed");
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 8_5000000, "Contributor should receive 8.5 USDC");
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 1_5000000, "Treasury should receive 1.5 USDC");
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    // RevenueSplitter owns the 200-299 code space
+    assert_eq!(Error::NotInitialized as u32, 200);
+    assert_eq!(Error::InvalidContributors as u32, 201);
+    assert_eq!(Error::InvalidAmount as u32, 202);
+    assert_eq!(Error::TransferFailed as u32, 203);
+    assert_eq!(Error::TreasuryNotSet as u32, 204);
+    assert_eq!(Error::TokenNotSet as u32, 205);
+    assert_eq!(Error::Paused as u32, 206);
+    assert_eq!(Error::Unauthorized as u32, 207);
+    assert_eq!(Error::Overflow as u32, 208);
+    assert_eq!(Error::RootAlreadyCommitted as u32, 209);
+    assert_eq!(Error::RootNotFound as u32, 210);
+    assert_eq!(Error::AlreadyClaimed as u32, 211);
+    assert_eq!(Error::InvalidProof as u32, 212);
+    assert_eq!(Error::QueueEmpty as u32, 213);
+    assert_eq!(Error::TokenNotAccepted as u32, 214);
+    assert_eq!(Error::NotOnboarded as u32, 215);
+    assert_eq!(Error::InvalidSplit as u32, 216);
+    assert_eq!(Error::NothingToClaim as u32, 217);
+    assert_eq!(Error::BatchTooLarge as u32, 218);
+    assert_eq!(Error::AlreadyInitialized as u32, 219);
+    assert_eq!(Error::NoPendingAdmin as u32, 220);
+    assert_eq!(Error::InvalidLimit as u32, 221);
+}
+
+#[test]
+fn test_migrate_writes_schema_marker() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: legacy deployment without a schema marker
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&symbol_short!("UPGRADER"), &create_address(&env));
+    });
+    assert_eq!(client.schema_version(), 1);
+    
+    // Act
+    let interface_version = client.migrate();
+    
+    // Assert
+    assert_eq!(interface_version, INTERFACE_VERSION);
+    assert_eq!(client.schema_version(), SCHEMA_VERSION);
+}
+
+#[test]
+fn test_migrate_moves_legacy_keys_and_keeps_earnings() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: schema 2 storage with symbol keys
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    let contributor = create_address(&env);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.set(&symbol_short!("USDC_TKN"), &usdc_token);
+        storage.set(&symbol_short!("TREASURY"), &treasury);
+        storage.set(&symbol_short!("SCHEMA"), &2u32);
+        storage.set(&symbol_short!("UPGRADER"), &create_address(&env));
+        env.storage().persistent().set(
+            &(symbol_short!("EARNINGS"), contributor.clone()),
+            &ContributorEarnings {
+                sales: 3,
+                total_earned: 30_0000000,
+                claimable: 0,
+            },
+        );
+    });
+    
+    // Legacy configuration is honored before migration
+    assert_eq!(client.get_usdc_token(), usdc_token);
+    
+    // Act
+    client.migrate();
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 10_0000000);
+    client.payout_purchase(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &Vec::from_array(&env, [contributor.clone()]),
+        &10_0000000,
+    );
+    
+    // Assert
+    assert_eq!(client.schema_version(), SCHEMA_VERSION);
+    assert_eq!(client.get_treasury(), treasury);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        let stored: Address = storage.get(&DataKey::UsdcToken).unwrap();
+        assert_eq!(stored, usdc_token);
+        assert!(!storage.has(&symbol_short!("USDC_TKN")));
+    });
+    let earnings = client.get_contributor_earnings(&contributor);
+    assert_eq!(earnings.sales, 4);
+    assert_eq!(earnings.total_earned, 30_0000000 + 8_5000000);
+}
+
+#[test]
+fn test_version_introspection() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Assert
+    assert_eq!(client.version(), String::from_str(&env, env!("CARGO_PKG_VERSION")));
+    assert_eq!(client.interface_version(), INTERFACE_VERSION);
+}
+
+#[test]
+fn test_payout_purchase_splits_price_and_holds_nothing() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 25_0000001);
+    let contributor_a = create_address(&env);
+    let contributor_b = create_address(&env);
+    let contributors = Vec::from_array(&env, [contributor_a.clone(), contributor_b.clone()]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_purchase_split");
+    
+    // Act: 25.0000001 USDC over 2 contributors leaves a 1-stroop remainder
+    client.payout_purchase(&payer, &dataset_id, &contributors, &25_0000001);
+    
+    // Assert: 12.5 USDC shares split 85/15, remainder to the treasury
+    assert_eq!(get_balance(&env, &usdc_token_client, &payer), 0);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_a), 10_6250000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_b), 10_6250000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 3_7500001);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+}
+
+#[test]
+fn test_payout_purchase_without_contributors_pays_treasury() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 10_0000000);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_no_contributors");
     
     // Act
-    // Note: This will fail without a real token contract, but we can verify the structure
-    let result = client.payout_for_dataset(&dataset_id, &contributors);
-    
-    // Assert: The call structure is correct
-    // In a full test with mock token, we'd verify:
-    // - result.is_ok()
-    // - Contributor balance increased by 8.5 USDC
-    // - Treasury balance increased by 1.5 USDC
-    // 
-    // For now, we just verify the function can be called
-    // This is expected behavior without a real token contract
+    client.payout_purchase(&payer, &dataset_id, &Vec::new(&env), &10_0000000);
+    
+    // Assert
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 10_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    
+    // Non-positive amounts are rejected
+    assert_eq!(
+        client.try_payout_purchase(&payer, &dataset_id, &Vec::new(&env), &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_treasury_received_tracked_per_token() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 20_0000001);
+    let contributors = Vec::from_array(&env, [create_address(&env), create_address(&env)]);
+    
+    // Act: a purchase with a rounding remainder, then an explicit payout
+    client.payout_purchase(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &contributors,
+        &10_0000001,
+    );
+    client.payout_amount(&payer, &Bytes::from_slice(&env, b"bounty"), &contributors, &10_0000000);
+    
+    // Assert: the running total matches what the treasury holds
+    let received = client.get_treasury_received(&usdc_token);
+    assert_eq!(received, 2 * 1_5000000 + 1);
+    assert_eq!(received, get_balance(&env, &usdc_token_client, &treasury));
+    let (other_token, _) = create_mock_token(&env, &admin);
+    assert_eq!(client.get_treasury_received(&other_token), 0);
+}
+
+#[test]
+fn test_payout_amount_pulls_from_the_payer() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: USDC already held by the splitter must stay put
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    fund_usdc(&env, &usdc_token_client, &client.address, 50_0000000);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 10_0000000);
+    let contributors = Vec::from_array(&env, [create_address(&env), create_address(&env)]);
+    let payout_id = Bytes::from_slice(&env, b"bounty");
+    
+    // Act & Assert: without the payer's authorization nothing is paid
+    env.set_auths(&[]);
+    assert!(client.try_payout_amount(&payer, &payout_id, &contributors, &10_0000000).is_err());
+    env.mock_all_auths();
+    let attacker = create_address(&env);
+    assert!(client
+        .try_payout_amount(&attacker, &payout_id, &Vec::from_array(&env, [attacker.clone()]), &50_0000000)
+        .is_err());
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 50_0000000);
+    
+    // The payer's USDC is what gets paid out
+    client.payout_amount(&payer, &payout_id, &contributors, &10_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &payer), 0);
+    for contributor in contributors.iter() {
+        assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 4_2500000);
+    }
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 1_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 50_0000000);
+    assert_eq!(
+        client.try_payout_amount(&payer, &payout_id, &Vec::new(&env), &10_0000000),
+        Err(Ok(Error::InvalidContributors))
+    );
+}
+
+#[test]
+fn test_preview_payout_matches_payout_purchase() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 30_0000002);
+    let contributors = Vec::from_array(&env, [
+        create_address(&env),
+        create_address(&env),
+        create_address(&env),
+    ]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_preview");
+    
+    // Act
+    let preview = client.preview_payout(&dataset_id, &contributors, &30_0000002);
+    client.payout_purchase(&payer, &dataset_id, &contributors, &30_0000002);
+    
+    // Assert
+    assert_eq!(preview.num_contributors, 3);
+    for contributor in contributors.iter() {
+        assert_eq!(get_balance(&env, &usdc_token_client, &contributor), preview.contributor_amount);
+    }
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), preview.treasury_amount);
+    assert_eq!(preview.contributor_amount * 3 + preview.treasury_amount, 30_0000002);
+    
+    // Amounts too small to split are rejected
+    assert_eq!(
+        client.try_preview_payout(&dataset_id, &contributors, &3),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_contributor_earnings_accumulate_across_payouts() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    client.init(&create_address(&env), &usdc_token, &create_address(&env));
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 35_0000000);
+    let contributor_a = create_address(&env);
+    let contributor_b = create_address(&env);
+    
+    // Act: a pays in both purchases, b only in the second
+    client.payout_purchase(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &Vec::from_array(&env, [contributor_a.clone()]),
+        &10_0000000,
+    );
+    client.payout_purchase(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_002"),
+        &Vec::from_array(&env, [contributor_a.clone(), contributor_b.clone()]),
+        &25_0000000,
+    );
+    
+    // Assert
+    let earnings_a = client.get_contributor_earnings(&contributor_a);
+    assert_eq!(earnings_a.sales, 2);
+    assert_eq!(earnings_a.total_earned, 8_5000000 + 10_6250000);
+    assert_eq!(earnings_a.total_earned, get_balance(&env, &usdc_token_client, &contributor_a));
+    assert_eq!(earnings_a.claimable, 0);
+    assert_eq!(client.get_contributor_earnings(&contributor_b).sales, 1);
+    assert_eq!(client.get_contributor_earnings(&create_address(&env)).sales, 0);
+}
+
+#[test]
+fn test_annual_earnings_split_at_calendar_year() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    client.init(&create_address(&env), &usdc_token, &create_address(&env));
+    
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 30_0000000);
+    let contributor = create_address(&env);
+    let contributors = Vec::from_array(&env, [contributor.clone()]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    
+    // Act: last second of 2024, then first second and mid 2025, when
+    // rewards are credited instead of transferred
+    for timestamp in [1_735_689_599, 1_735_689_600, 1_750_000_000] {
+        env.ledger().set_timestamp(timestamp);
+        if timestamp == 1_750_000_000 {
+            client.set_payout_mode(&PayoutMode::Accrue);
+        }
+        client.payout_purchase(&payer, &dataset_id, &contributors, &10_0000000);
+    }
+    
+    // Assert
+    assert_eq!(
+        client.get_annual_earnings(&contributor, &2024),
+        AnnualEarnings {
+            year: 2024,
+            sales: 1,
+            gross: 8_5000000,
+            earned: 8_5000000,
+            withheld: 0,
+        }
+    );
+    let earnings_2025 = client.get_annual_earnings(&contributor, &2025);
+    assert_eq!((earnings_2025.sales, earnings_2025.earned), (2, 17_0000000));
+    assert_eq!(earnings_2025.withheld, client.get_pending_balance(&contributor));
+    assert_eq!(earnings_2025.withheld, 8_5000000);
+    assert_eq!(
+        earnings_2025.earned + client.get_annual_earnings(&contributor, &2024).earned,
+        client.get_contributor_earnings(&contributor).total_earned
+    );
+    assert_eq!(client.get_annual_earnings(&contributor, &2026).sales, 0);
+}
+
+#[test]
+fn test_health_requires_token_and_treasury() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Assert: not ready before init
+    let health = client.health();
+    assert!(!health.token_set && !health.treasury_set && !health.ready);
+    
+    // Act
+    client.init(&create_address(&env), &create_address(&env), &create_address(&env));
+    
+    // Assert
+    let health = client.health();
+    assert!(health.token_set && health.treasury_set && !health.paused);
+    assert_eq!(health.schema_version, 1);
+    assert!(health.ready);
+}
+
+#[test]
+fn test_preview_payout_reports_overflow() {
+    let env = create_env();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let contributors = Vec::from_array(&env, [create_address(&env)]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    
+    // Act: the 85% contributor share of i128::MAX overflows
+    let result = client.try_preview_payout(&dataset_id, &contributors, &i128::MAX);
+    
+    // Assert
+    assert_eq!(result, Err(Ok(Error::Overflow)));
+}
+
+/// Helper: Hash a payout Merkle leaf the way the off-chain builder does
+fn merkle_leaf(env: &Env, dataset_id: &Bytes, contributor: &Address, amount: i128) -> BytesN<32> {
+    let mut strkey = [0u8; 56];
+    contributor.to_string().copy_into_slice(&mut strkey);
+    let dataset_key = env.crypto().sha256(dataset_id).to_array();
+    let preimage = PayoutLeaf::new(dataset_key, strkey, amount).unwrap().encode();
+    env.crypto().sha256(&Bytes::from_array(env, &preimage)).into()
+}
+
+/// Helper: Hash two Merkle nodes, smaller first
+fn merkle_node(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut pair = Bytes::from_array(env, &first.to_array());
+    pair.extend_from_array(&second.to_array());
+    env.crypto().sha256(&pair).into()
+}
+
+#[test]
+fn test_merkle_root_payout_claims() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: three leaves sharing the 85 USDC contributor share of a
+    // 100 USDC purchase
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 100_0000000);
+    
+    let dataset_id = Bytes::from_slice(&env, b"dataset_large");
+    let contributors = [create_address(&env), create_address(&env), create_address(&env)];
+    let amounts = [40_0000000, 30_0000000, 15_0000000];
+    let leaves: std::vec::Vec<BytesN<32>> = contributors
+        .iter()
+        .zip(amounts)
+        .map(|(contributor, amount)| merkle_leaf(&env, &dataset_id, contributor, amount))
+        .collect();
+    let pair = merkle_node(&env, &leaves[0], &leaves[1]);
+    let root = merkle_node(&env, &pair, &leaves[2]);
+    let proofs = [
+        Vec::from_array(&env, [leaves[1].clone(), leaves[2].clone()]),
+        Vec::from_array(&env, [leaves[0].clone(), leaves[2].clone()]),
+        Vec::from_array(&env, [pair]),
+    ];
+    
+    // Act
+    let commitment = client.commit_payout_root(&payer, &dataset_id, &root, &100_0000000);
+    for i in 0..3 {
+        client.claim_from_root(&payer, &root, &contributors[i], &amounts[i], &proofs[i]);
+    }
+    
+    // Assert
+    assert_eq!(commitment.contributor_total, 85_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 15_0000000);
+    for i in 0..3 {
+        assert_eq!(get_balance(&env, &usdc_token_client, &contributors[i]), amounts[i]);
+        assert!(client.is_root_claimed(&payer, &root, &contributors[i]));
+    }
+    assert_eq!(client.get_contributor_earnings(&contributors[0]).total_earned, 40_0000000);
+    assert_eq!(
+        client.get_payout_root(&payer, &root),
+        PayoutCommitment {
+            dataset_id,
+            root: root.clone(),
+            contributor_total: 85_0000000,
+            claimed: 85_0000000,
+            claims: 3,
+        }
+    );
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    
+    // Each contributor claims once per root
+    assert_eq!(
+        client.try_claim_from_root(&payer, &root, &contributors[0], &amounts[0], &proofs[0]),
+        Err(Ok(Error::AlreadyClaimed))
+    );
+}
+
+#[test]
+fn test_front_run_root_does_not_take_over_the_payout() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a single-leaf tree paying 8.5 USDC of a 10 USDC purchase
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    client.init(&create_address(&env), &usdc_token, &create_address(&env));
+    let payer = create_address(&env);
+    let attacker = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 10_0000000);
+    fund_usdc(&env, &usdc_token_client, &attacker, 1_0000000);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_large");
+    let contributor = create_address(&env);
+    let root = merkle_leaf(&env, &dataset_id, &contributor, 8_5000000);
+    let no_proof = Vec::new(&env);
+    
+    // Act: the attacker commits the root seen in the mempool first
+    client.commit_payout_root(&attacker, &dataset_id, &root, &1_0000000);
+    let commitment = client.commit_payout_root(&payer, &dataset_id, &root, &10_0000000);
+    
+    // Assert: the payer's commitment stands on its own and pays the leaf
+    assert_eq!(commitment.contributor_total, 8_5000000);
+    assert_eq!(
+        client.try_claim_from_root(&attacker, &root, &contributor, &8_5000000, &no_proof),
+        Err(Ok(Error::InvalidAmount))
+    );
+    client.claim_from_root(&payer, &root, &contributor, &8_5000000, &no_proof);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 8_5000000);
+    assert!(client.is_root_claimed(&payer, &root, &contributor));
+    assert!(!client.is_root_claimed(&attacker, &root, &contributor));
+}
+
+#[test]
+fn test_merkle_claim_rejects_invalid_proofs() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a single-leaf tree
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    client.init(&create_address(&env), &usdc_token, &create_address(&env));
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 20_0000000);
+    
+    let dataset_id = Bytes::from_slice(&env, b"dataset_large");
+    let contributor = create_address(&env);
+    let root = merkle_leaf(&env, &dataset_id, &contributor, 8_5000000);
+    client.commit_payout_root(&payer, &dataset_id, &root, &10_0000000);
+    let no_proof = Vec::new(&env);
+    
+    // Act & Assert: a different amount or contributor is not in the tree
+    assert_eq!(
+        client.try_claim_from_root(&payer, &root, &contributor, &9_0000000, &no_proof),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_claim_from_root(&payer, &root, &create_address(&env), &8_5000000, &no_proof),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_claim_from_root(&payer, &BytesN::from_array(&env, &[0u8; 32]), &contributor, &8_5000000, &no_proof),
+        Err(Ok(Error::RootNotFound))
+    );
+    assert_eq!(
+        client.try_commit_payout_root(&payer, &dataset_id, &root, &10_0000000),
+        Err(Ok(Error::RootAlreadyCommitted))
+    );
+    client.claim_from_root(&payer, &root, &contributor, &8_5000000, &no_proof);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 8_5000000);
+}
+
+#[test]
+fn test_oversized_payout_is_queued_and_processed() {
+    let env = create_env();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: five contributors more than fit in one batch
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    let num_contributors = MAX_CONTRIBUTORS_PER_BATCH + 5;
+    let amount = 10_0000000 * num_contributors as i128;
+    fund_usdc(&env, &usdc_token_client, &payer, amount + 1_0000000);
+    let mut contributors = Vec::new(&env);
+    for _ in 0..num_contributors {
+        contributors.push_back(create_address(&env));
+    }
+    let last = contributors.last().unwrap();
+    
+    // Act: the purchase pays the first batch and queues the rest
+    client.payout_purchase(&payer, &Bytes::from_slice(&env, b"dataset_huge"), &contributors, &amount);
+    
+    // Assert
+    assert_eq!(client.get_queue_length(), 1);
+    let queued = client.peek_payout_queue().unwrap();
+    assert_eq!(queued.remaining.len(), 5);
+    assert_eq!(queued.num_contributors, num_contributors);
+    assert_eq!(get_balance(&env, &usdc_token_client, &last), 0);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 5 * 10_0000000);
+    
+    // Anyone can finish it, earning the configured tip
+    let keeper = create_address(&env);
+    client.set_queue_tip(&1000);
+    client.fund_queue_tips(&payer, &1_0000000);
+    assert_eq!(client.process_next(&keeper, &3), 3);
+    assert_eq!(client.get_queue_length(), 1);
+    assert_eq!(client.process_next(&keeper, &100), 2);
+    assert_eq!(client.get_queue_length(), 0);
+    assert_eq!(client.peek_payout_queue(), None);
+    
+    assert_eq!(get_balance(&env, &usdc_token_client, &last), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &keeper), 5 * 1000);
+    assert_eq!(
+        get_balance(&env, &usdc_token_client, &client.address),
+        1_0000000 - 5 * 1000
+    );
+    assert_eq!(
+        get_balance(&env, &usdc_token_client, &treasury),
+        1_5000000 * num_contributors as i128
+    );
+    assert_eq!(
+        client.try_process_next(&keeper, &1),
+        Err(Ok(Error::QueueEmpty))
+    );
+}
+
+#[test]
+fn test_payouts_in_other_tokens_are_tracked_per_token() {
+    let env = create_env();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a splitter configured for USDC and a payer holding EURC
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let (eurc_token, eurc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    let num_contributors = MAX_CONTRIBUTORS_PER_BATCH + 1;
+    let amount = 10_0000000 * num_contributors as i128;
+    fund_usdc(&env, &eurc_token_client, &payer, amount + 10_0000000);
+    let mut weights = Vec::new(&env);
+    for _ in 0..num_contributors {
+        weights.push_back((create_address(&env), 1u32));
+    }
+    let (last, _) = weights.last().unwrap();
+    
+    // Act: a purchase paid in EURC, too large for one batch
+    client.payout_weighted_in(
+        &payer,
+        &eurc_token,
+        &Bytes::from_slice(&env, b"dataset_eurc"),
+        &weights,
+        &amount,
+    );
+    client.process_next(&create_address(&env), &1);
+    
+    // Assert: everyone, including the queued contributor, is paid in EURC
+    assert_eq!(client.get_queue_length(), 0);
+    assert_eq!(get_balance(&env, &eurc_token_client, &last), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &last), 0);
+    assert_eq!(
+        get_balance(&env, &eurc_token_client, &treasury),
+        1_5000000 * num_contributors as i128
+    );
+    assert_eq!(get_balance(&env, &eurc_token_client, &client.address), 0);
+    
+    // EURC totals and earnings are kept apart from the USDC ones
+    let totals = client.get_payout_totals_in(&eurc_token);
+    assert_eq!(totals.total_distributed, 8_5000000 * num_contributors as i128);
+    assert_eq!(totals.platform_accrued, 1_5000000 * num_contributors as i128);
+    assert_eq!(client.get_total_distributed(), 0);
+    assert_eq!(client.get_payout_totals_in(&usdc_token).total_distributed, 0);
+    assert_eq!(client.get_earnings_in(&last, &eurc_token).total_earned, 8_5000000);
+    assert_eq!(client.get_lifetime_earnings(&last), 0);
+    assert_eq!(client.get_treasury_received(&eurc_token), 1_5000000 * num_contributors as i128);
+    
+    // Accrued EURC is claimed in EURC
+    client.set_payout_mode(&PayoutMode::Accrue);
+    let contributor = create_address(&env);
+    client.payout_weighted_in(
+        &payer,
+        &eurc_token,
+        &Bytes::from_slice(&env, b"dataset_eurc"),
+        &Vec::from_array(&env, [(contributor.clone(), 1u32)]),
+        &10_0000000,
+    );
+    assert_eq!(client.get_pending_balance(&contributor), 0);
+    assert_eq!(client.try_claim(&contributor), Err(Ok(Error::NothingToClaim)));
+    assert_eq!(client.claim_in(&contributor, &eurc_token), 8_5000000);
+    assert_eq!(get_balance(&env, &eurc_token_client, &contributor), 8_5000000);
+    assert_eq!(
+        client.try_claim_in(&contributor, &eurc_token),
+        Err(Ok(Error::NothingToClaim))
+    );
+}
+
+#[test]
+fn test_weighted_payout_splits_by_weight() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 100_0000001);
+    let light = create_address(&env);
+    let heavy = create_address(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    
+    // Act: weights 1 and 3 split the price 25/75
+    client.payout_weighted(
+        &payer,
+        &dataset_id,
+        &Vec::from_array(&env, [(light.clone(), 1u32), (heavy.clone(), 3u32)]),
+        &100_0000001,
+    );
+    
+    // Assert: the rounding remainder goes to the treasury
+    assert_eq!(get_balance(&env, &usdc_token_client, &light), 21_2500000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &heavy), 63_7500000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 15_0000001);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    
+    // A contributor without weight is rejected
+    assert_eq!(
+        client.try_payout_weighted(
+            &payer,
+            &dataset_id,
+            &Vec::from_array(&env, [(light, 1u32), (heavy, 0u32)]),
+            &1_0000000,
+        ),
+        Err(Ok(Error::InvalidContributors))
+    );
+}
+
+#[test]
+fn test_payout_limits_cap_rewards_and_credit_dust() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 100_0000000);
+    let light = create_address(&env);
+    let heavy = create_address(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    assert_eq!(client.get_payout_limits(), PayoutLimits { min_payout: 0, max_reward_per_purchase: None });
+    assert_eq!(client.try_set_payout_limits(&-1, &None), Err(Ok(Error::InvalidLimit)));
+    assert_eq!(client.try_set_payout_limits(&0, &Some(0)), Err(Ok(Error::InvalidLimit)));
+    let limits = PayoutLimits { min_payout: 10_0000000, max_reward_per_purchase: Some(40_0000000) };
+    client.set_payout_limits(&limits.min_payout, &limits.max_reward_per_purchase);
+    assert_eq!(client.get_payout_limits(), limits);
+    
+    // Act: weights 1 and 3 share 40 of the 100 USDC paid
+    client.payout_weighted(
+        &payer,
+        &dataset_id,
+        &Vec::from_array(&env, [(light.clone(), 1u32), (heavy.clone(), 3u32)]),
+        &100_0000000,
+    );
+    
+    // Assert: the excess goes to the treasury, and the reward below the
+    // minimum is credited instead of transferred
+    assert_eq!(get_balance(&env, &usdc_token_client, &heavy), 25_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &light), 0);
+    assert_eq!(client.get_pending_balance(&light), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 66_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 8_5000000);
+    
+    // The credited reward is claimed like an accrued one
+    assert_eq!(client.claim(&light), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+}
+
+#[test]
+fn test_payout_limits_apply_to_batches_and_roots() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 200_0000000);
+    let light = create_address(&env);
+    let heavy = create_address(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    client.set_payout_limits(&10_0000000, &Some(40_0000000));
+    
+    // Act: explicit amounts of 25 and 75 USDC, above the 40 USDC cap
+    client.payout_batch(
+        &payer,
+        &dataset_id,
+        &Vec::from_array(&env, [light.clone(), heavy.clone()]),
+        &Vec::from_array(&env, [25_0000000i128, 75_0000000]),
+    );
+    
+    // Assert: the amounts are scaled down to 10 and 30 USDC, the excess
+    // goes to the treasury, and the reward below the minimum is credited
+    assert_eq!(get_balance(&env, &usdc_token_client, &heavy), 25_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &light), 0);
+    assert_eq!(client.get_pending_balance(&light), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 66_0000000);
+    
+    // A Merkle commitment holds the contributor share of at most the cap
+    let root = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = client.commit_payout_root(&payer, &dataset_id, &root, &100_0000000);
+    assert_eq!(commitment.contributor_total, 34_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 132_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 42_5000000);
+}
+
+#[test]
+fn test_emergency_pause_blocks_payouts_and_claims() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a contributor with a claimable balance
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    client.init(&admin, &usdc_token, &create_address(&env));
+    client.set_payout_mode(&PayoutMode::Accrue);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 20_0000000);
+    let contributor = create_address(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    let weights = Vec::from_array(&env, [(contributor.clone(), 1u32)]);
+    client.payout_weighted(&payer, &dataset_id, &weights, &10_0000000);
+    
+    // Act
+    client.emergency_pause();
+    
+    // Assert: payouts and claims halt, configuration does not
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(client.is_emergency_paused());
+    assert!(!client.is_paused());
+    assert!(client.health().paused);
+    assert_eq!(
+        client.try_payout_weighted(&payer, &dataset_id, &weights, &10_0000000),
+        Err(Ok(Error::Paused))
+    );
+    assert_eq!(client.try_claim(&contributor), Err(Ok(Error::Paused)));
+    client.set_split(&9_000, &1_000);
+    
+    // Lifting the pause resumes them
+    client.emergency_unpause();
+    assert!(!client.is_emergency_paused());
+    assert_eq!(client.claim(&contributor), 8_5000000);
+    client.payout_weighted(&payer, &dataset_id, &weights, &10_0000000);
+    assert_eq!(client.get_pending_balance(&contributor), 9_0000000);
+}
+
+#[test]
+fn test_set_split_changes_payouts() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
+    assert_eq!(client.get_split(), (DEFAULT_CONTRIBUTOR_BPS, DEFAULT_PLATFORM_BPS));
+    
+    // Act
+    client.set_split(&9_000, &1_000);
+    
+    // Assert: the change is announced and applied to the next payout
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap(),
+        Symbol::new(&env, "SplitUpdated")
+    );
+    assert_eq!(<(u32, u32)>::try_from_val(&env, &data).unwrap(), (9_000, 1_000));
+    assert_eq!(client.get_split(), (9_000, 1_000));
+    
+    let contributor = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &client.address, 10_0000000);
+    client.payout_for_dataset(
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &Vec::from_array(&env, [(contributor.clone(), 1u32)]),
+        &10_0000000,
+    );
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor), 9_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 1_0000000);
+    
+    // Shares must be non-zero and cover the whole amount
+    for (contributor_bps, platform_bps) in [(8_000, 1_000), (10_000, 0), (u32::MAX, 2)] {
+        assert_eq!(
+            client.try_set_split(&contributor_bps, &platform_bps),
+            Err(Ok(Error::InvalidSplit))
+        );
+    }
+    assert_eq!(client.get_split(), (9_000, 1_000));
+}
+
+#[test]
+fn test_accrued_payouts_are_claimed_by_contributors() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
+    assert_eq!(client.get_payout_mode(), PayoutMode::Push);
+    client.set_payout_mode(&PayoutMode::Accrue);
+    
+    let contributor_a = create_address(&env);
+    let contributor_b = create_address(&env);
+    let contributors = Vec::from_array(&env, [(contributor_a.clone(), 1u32), (contributor_b.clone(), 1)]);
+    fund_usdc(&env, &usdc_token_client, &client.address, 40_0000000);
+    
+    // Act: two purchases credit the contributors instead of paying them
+    client.payout_for_dataset(&Bytes::from_slice(&env, b"dataset_001"), &contributors, &20_0000000);
+    client.payout_for_dataset(&Bytes::from_slice(&env, b"dataset_002"), &contributors, &20_0000000);
+    
+    // Assert: the treasury is paid, contributor rewards stay in the contract
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_a), 0);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 6_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 34_0000000);
+    let earnings = client.get_contributor_earnings(&contributor_a);
+    assert_eq!((earnings.sales, earnings.total_earned, earnings.claimable), (2, 17_0000000, 17_0000000));
+    
+    // Act: the contributor withdraws everything credited so far
+    assert_eq!(client.claim(&contributor_a), 17_0000000);
+    
+    // Assert
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_a), 17_0000000);
+    assert_eq!(client.get_contributor_earnings(&contributor_a).claimable, 0);
+    assert_eq!(client.get_contributor_earnings(&contributor_a).total_earned, 17_0000000);
+    assert_eq!(client.try_claim(&contributor_a), Err(Ok(Error::NothingToClaim)));
+    
+    // Balances credited before switching back to push stay claimable
+    client.set_payout_mode(&PayoutMode::Push);
+    assert_eq!(client.claim(&contributor_b), 17_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+}
+
+#[test]
+fn test_claw_back_reclaims_only_what_the_refunded_purchase_credited() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: two 20 USDC purchases paid out by the marketplace; `kept`
+    // has earnings from both, `claimed` claimed the first before the refund
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &create_address(&env));
+    client.init(&create_address(&env), &usdc_token, &create_address(&env));
+    client.set_payout_mode(&PayoutMode::Accrue);
+    let marketplace = create_address(&env);
+    let refunded = Bytes::from_slice(&env, b"dataset_001");
+    let other = Bytes::from_slice(&env, b"dataset_002");
+    let claimed = create_address(&env);
+    let kept = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &marketplace, 40_0000000);
+    let buyer = create_address(&env);
+    assert_eq!(
+        client.try_claw_back(&usdc_token, &refunded, &0, &buyer),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_authorized_caller(&marketplace);
+    let sequence = client.payout_weighted_in(
+        &marketplace,
+        &usdc_token,
+        &refunded,
+        &Vec::from_array(&env, [(claimed.clone(), 1u32), (kept.clone(), 1)]),
+        &20_0000000,
+    ).unwrap();
+    client.claim(&claimed);
+    client.payout_weighted_in(
+        &marketplace,
+        &usdc_token,
+        &other,
+        &Vec::from_array(&env, [(kept.clone(), 1u32), (claimed.clone(), 1)]),
+        &20_0000000,
+    );
+    
+    // Act
+    let reclaimed = client.claw_back(&usdc_token, &refunded, &sequence, &buyer);
+    
+    // Assert: only `kept`'s reward from the refunded purchase comes back;
+    // neither contributor loses what the other purchase credited
+    assert_eq!(reclaimed, 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &buyer), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 17_0000000);
+    let earnings = client.get_contributor_earnings(&kept);
+    assert_eq!((earnings.total_earned, earnings.claimable), (8_5000000, 8_5000000));
+    let earnings = client.get_contributor_earnings(&claimed);
+    assert_eq!((earnings.total_earned, earnings.claimable), (17_0000000, 8_5000000));
+    assert_eq!(client.get_total_distributed(), 25_5000000);
+    assert_eq!(client.claw_back(&usdc_token, &refunded, &sequence, &buyer), 0);
+}
+
+#[test]
+fn test_accounting_totals_track_payouts_and_claims() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init_with_caller(&create_address(&env), &usdc_token, &treasury, &create_address(&env));
+    client.set_upgrade_coordinator(&create_address(&env));
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 100_0000001);
+    let light = create_address(&env);
+    let heavy = create_address(&env);
+    assert_eq!((client.get_total_distributed(), client.get_platform_accrued()), (0, 0));
+    
+    // Act: a pushed payout with a rounding remainder, then an accrued one
+    client.payout_weighted(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &Vec::from_array(&env, [(light.clone(), 1u32), (heavy.clone(), 3u32)]),
+        &100_0000001,
+    );
+    client.set_payout_mode(&PayoutMode::Accrue);
+    fund_usdc(&env, &usdc_token_client, &client.address, 10_0000000);
+    client.payout_for_dataset(
+        &Bytes::from_slice(&env, b"dataset_002"),
+        &Vec::from_array(&env, [(light.clone(), 1u32)]),
+        &10_0000000,
+    );
+    
+    // Assert
+    assert_eq!(client.get_total_distributed(), 93_5000000);
+    assert_eq!(client.get_platform_accrued(), 16_5000001);
+    assert_eq!(client.get_pending_balance(&light), 8_5000000);
+    assert_eq!(client.get_lifetime_earnings(&light), 29_7500000);
+    assert_eq!(client.get_pending_balance(&heavy), 0);
+    assert_eq!(client.get_lifetime_earnings(&heavy), 63_7500000);
+    
+    // Claiming moves the pending balance without changing any total
+    client.claim(&light);
+    assert_eq!(client.get_pending_balance(&light), 0);
+    assert_eq!(client.get_lifetime_earnings(&light), 29_7500000);
+    assert_eq!(client.get_total_distributed(), 93_5000000);
+}
+
+#[test]
+fn test_payout_batch_pays_explicit_amounts() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 50_0000000);
+    let contributor_a = create_address(&env);
+    let contributor_b = create_address(&env);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    
+    // Invalid entries reject the whole batch before any USDC moves
+    let pair = Vec::from_array(&env, [contributor_a.clone(), contributor_b.clone()]);
+    for (contributors, amounts, error) in [
+        (pair.clone(), Vec::from_array(&env, [10_0000000]), Error::InvalidContributors),
+        (
+            Vec::from_array(&env, [contributor_a.clone(), contributor_a.clone()]),
+            Vec::from_array(&env, [10_0000000, 10_0000000]),
+            Error::InvalidContributors,
+        ),
+        (pair.clone(), Vec::from_array(&env, [10_0000000, 1]), Error::InvalidAmount),
+    ] {
+        assert_eq!(
+            client.try_payout_batch(&payer, &dataset_id, &contributors, &amounts),
+            Err(Ok(error))
+        );
+    }
+    assert_eq!(get_balance(&env, &usdc_token_client, &payer), 50_0000000);
+    
+    // Act
+    client.payout_batch(
+        &payer,
+        &dataset_id,
+        &pair,
+        &Vec::from_array(&env, [10_0000000, 30_0000000]),
+    );
+    
+    // Assert: each amount is that contributor's share, split 85/15
+    assert_eq!(get_balance(&env, &usdc_token_client, &payer), 10_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_a), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributor_b), 25_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &treasury), 6_0000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    assert_eq!(rewarded_events(&env, &client.address).len(), 2);
+}
+
+#[test]
+fn test_claim_many_settles_contributors_independently() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: three contributors credited in accrual mode, in a token
+    // whose issuer can deauthorize holders
+    let asset = env.register_stellar_asset_contract_v2(create_address(&env));
+    asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let usdc_token = asset.address();
+    let usdc_token_client = token::Client::new(&env, &usdc_token);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    client.set_upgrade_coordinator(&create_address(&env));
+    client.set_payout_mode(&PayoutMode::Accrue);
+    let payer = create_address(&env);
+    fund_usdc(&env, &usdc_token_client, &payer, 30_0000000);
+    let contributors: std::vec::Vec<Address> = (0..3).map(|_| create_address(&env)).collect();
+    client.payout_batch(
+        &payer,
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &Vec::from_slice(&env, &contributors),
+        &Vec::from_array(&env, [10_0000000; 3]),
+    );
+    
+    // The second contributor cannot receive the token
+    let sac = token::StellarAssetClient::new(&env, &usdc_token);
+    sac.set_authorized(&contributors[1], &false);
+    let never_paid = create_address(&env);
+    
+    // Act
+    let mut batch = Vec::from_slice(&env, &contributors);
+    batch.push_back(never_paid.clone());
+    let claimed = client.claim_many(&batch);
+    
+    // Assert: the failed transfer does not block the others
+    assert_eq!(
+        claimed,
+        Vec::from_array(
+            &env,
+            [
+                (contributors[0].clone(), 8_5000000),
+                (contributors[1].clone(), 0),
+                (contributors[2].clone(), 8_5000000),
+                (never_paid, 0),
+            ]
+        )
+    );
+    assert_eq!(get_balance(&env, &usdc_token_client, &contributors[0]), 8_5000000);
+    assert_eq!(client.get_pending_balance(&contributors[0]), 0);
+    assert_eq!(client.get_pending_balance(&contributors[1]), 8_5000000);
+    
+    // The kept balance can be claimed once the contributor can receive it
+    sac.set_authorized(&contributors[1], &true);
+    assert_eq!(client.claim(&contributors[1]), 8_5000000);
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
+    
+    // Batches are capped like payouts
+    let oversized: std::vec::Vec<Address> = (0..=MAX_CONTRIBUTORS_PER_BATCH)
+        .map(|_| create_address(&env))
+        .collect();
+    assert_eq!(
+        client.try_claim_many(&Vec::from_slice(&env, &oversized)),
+        Err(Ok(Error::BatchTooLarge))
+    );
+}
+
+#[test]
+fn test_payout_for_dataset_requires_authorized_caller() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_revenue_splitter_client(&env);
+    
+    // Arrange: a funded splitter without an authorized caller
+    let admin = create_address(&env);
+    let (usdc_token, usdc_token_client) = create_mock_token(&env, &admin);
+    let treasury = create_address(&env);
+    client.init(&create_address(&env), &usdc_token, &treasury);
+    fund_usdc(&env, &usdc_token_client, &client.address, 10_0000000);
+    let contributors = Vec::from_array(&env, [(create_address(&env), 1u32)]);
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    
+    // Nobody can spend the balance until the admin sets a caller
+    assert_eq!(
+        client.try_payout_for_dataset(&dataset_id, &contributors, &10_0000000),
+        Err(Ok(Error::Unauthorized))
+    );
+    let marketplace = create_address(&env);
+    client.set_authorized_caller(&marketplace);
+    assert_eq!(client.get_authorized_caller(), Some(marketplace.clone()));
+    
+    // Without the marketplace's authorization the call fails
+    env.set_auths(&[]);
+    assert!(client.try_payout_for_dataset(&dataset_id, &contributors, &10_0000000).is_err());
+    env.mock_all_auths();
+    
+    // Act
+    client.payout_for_dataset(&dataset_id, &contributors, &10_0000000);
+    
+    // Assert: the payout was authorized by the marketplace
+    assert!(env
+        .auths()
+        .iter()
+        .any(|(address, _)| *address == marketplace));
+    assert_eq!(get_balance(&env, &usdc_token_client, &client.address), 0);
 }
@@ -0,0 +1,230 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
+    Symbol,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const DATASET_TERMS_KEY: Symbol = symbol_short!("DS_TERMS");
+const STUDY_TERMS_KEY: Symbol = symbol_short!("ST_TERMS");
+
+/// Basis point denominator
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Maximum combined royalty (basis points) a single terms record may carry
+pub const MAX_TOTAL_BPS: u32 = 5_000;
+
+/// RoyaltyTerms structure
+///
+/// Royalty terms set once here and read by DatasetMarketplace and
+/// RevenueSplitter:
+/// - resale_bps: Royalty on secondary license sales (marketplace)
+/// - institutional_bps: Share of the contributor payout owed to the
+///   contributing institution (splitter)
+/// - institution: Institution receiving the institutional royalty
+/// - referral_bps: Share of the platform payout owed to the referrer (splitter)
+/// - referrer: Referrer receiving the referral share
+/// - updated_at: Ledger timestamp of the last update
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyTerms {
+    pub resale_bps: u32,
+    pub institutional_bps: u32,
+    pub institution: Option<Address>,
+    pub referral_bps: u32,
+    pub referrer: Option<Address>,
+    pub updated_at: u64,
+}
+
+/// Subject of a royalty terms update, published in Royalty events
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TermsSubject {
+    Dataset(Bytes),
+    Study(BytesN<32>),
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    RoyaltyTooHigh = 3,
+    MissingRecipient = 4,
+    TermsNotFound = 5,
+}
+
+#[contract]
+pub struct RoyaltyRegistry;
+
+#[contractimpl]
+impl RoyaltyRegistry {
+    /// Initialize the RoyaltyRegistry contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to set royalty terms
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        Ok(())
+    }
+
+    /// Set the royalty terms of a dataset
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_id` - Marketplace dataset ID
+    /// * `terms` - Royalty terms (`updated_at` is overwritten)
+    ///
+    /// # Returns
+    /// * `Ok(RoyaltyTerms)` as stored
+    /// * `Err(Error)` if the terms are invalid
+    pub fn set_dataset_terms(
+        env: Env,
+        dataset_id: Bytes,
+        terms: RoyaltyTerms,
+    ) -> Result<RoyaltyTerms, Error> {
+        Self::require_admin(&env)?;
+        let terms = Self::validate(&env, terms)?;
+        env.storage()
+            .persistent()
+            .set(&(DATASET_TERMS_KEY, dataset_id.clone()), &terms);
+
+        env.events().publish(
+            (symbol_short!("Royalty"),),
+            (TermsSubject::Dataset(dataset_id), terms.clone()),
+        );
+
+        Ok(terms)
+    }
+
+    /// Set the royalty terms of a study
+    ///
+    /// Study terms apply to every dataset the study is included in.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_hash` - StudyRegistry dataset hash
+    /// * `terms` - Royalty terms (`updated_at` is overwritten)
+    ///
+    /// # Returns
+    /// * `Ok(RoyaltyTerms)` as stored
+    /// * `Err(Error)` if the terms are invalid
+    pub fn set_study_terms(
+        env: Env,
+        study_hash: BytesN<32>,
+        terms: RoyaltyTerms,
+    ) -> Result<RoyaltyTerms, Error> {
+        Self::require_admin(&env)?;
+        let terms = Self::validate(&env, terms)?;
+        env.storage()
+            .persistent()
+            .set(&(STUDY_TERMS_KEY, study_hash.clone()), &terms);
+
+        env.events().publish(
+            (symbol_short!("Royalty"),),
+            (TermsSubject::Study(study_hash), terms.clone()),
+        );
+
+        Ok(terms)
+    }
+
+    /// Remove the royalty terms of a dataset
+    pub fn clear_dataset_terms(env: Env, dataset_id: Bytes) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&(DATASET_TERMS_KEY, dataset_id));
+        Ok(())
+    }
+
+    /// Remove the royalty terms of a study
+    pub fn clear_study_terms(env: Env, study_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&(STUDY_TERMS_KEY, study_hash));
+        Ok(())
+    }
+
+    /// Get the royalty terms of a dataset
+    ///
+    /// # Returns
+    /// * `Ok(RoyaltyTerms)` if set
+    /// * `Err(Error::TermsNotFound)` otherwise
+    pub fn get_dataset_terms(env: Env, dataset_id: Bytes) -> Result<RoyaltyTerms, Error> {
+        env.storage()
+            .persistent()
+            .get(&(DATASET_TERMS_KEY, dataset_id))
+            .ok_or(Error::TermsNotFound)
+    }
+
+    /// Get the royalty terms of a study
+    ///
+    /// # Returns
+    /// * `Ok(RoyaltyTerms)` if set
+    /// * `Err(Error::TermsNotFound)` otherwise
+    pub fn get_study_terms(env: Env, study_hash: BytesN<32>) -> Result<RoyaltyTerms, Error> {
+        env.storage()
+            .persistent()
+            .get(&(STUDY_TERMS_KEY, study_hash))
+            .ok_or(Error::TermsNotFound)
+    }
+
+    /// Get the terms of a dataset, or zero terms if none are set
+    ///
+    /// Used by RevenueSplitter and DatasetMarketplace so a missing entry
+    /// never blocks a payout.
+    pub fn dataset_terms_or_default(env: Env, dataset_id: Bytes) -> RoyaltyTerms {
+        Self::get_dataset_terms(env, dataset_id).unwrap_or(RoyaltyTerms {
+            resale_bps: 0,
+            institutional_bps: 0,
+            institution: None,
+            referral_bps: 0,
+            referrer: None,
+            updated_at: 0,
+        })
+    }
+
+    /// Apply a basis-point royalty to an amount (rounds down)
+    pub fn apply_bps(amount: i128, bps: u32) -> i128 {
+        amount * bps as i128 / BPS_DENOMINATOR as i128
+    }
+
+    fn validate(env: &Env, mut terms: RoyaltyTerms) -> Result<RoyaltyTerms, Error> {
+        let total =
+            terms.resale_bps as u64 + terms.institutional_bps as u64 + terms.referral_bps as u64;
+        if total > MAX_TOTAL_BPS as u64 {
+            return Err(Error::RoyaltyTooHigh);
+        }
+        if terms.institutional_bps > 0 && terms.institution.is_none() {
+            return Err(Error::MissingRecipient);
+        }
+        if terms.referral_bps > 0 && terms.referrer.is_none() {
+            return Err(Error::MissingRecipient);
+        }
+        terms.updated_at = env.ledger().timestamp();
+        Ok(terms)
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
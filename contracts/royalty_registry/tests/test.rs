@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use royalty_registry::{Error, RoyaltyRegistry, RoyaltyRegistryClient, RoyaltyTerms};
+use soroban_sdk::{testutils::Address as AddressTestUtils, Address, Bytes, BytesN, Env};
+
+/// Helper: Create an initialized RoyaltyRegistry client
+fn create_registry_client(env: &Env) -> RoyaltyRegistryClient<'_> {
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltyRegistry);
+    let client = RoyaltyRegistryClient::new(env, &contract_id);
+    client.init(&Address::generate(env));
+    client
+}
+
+/// Helper: Terms with an institutional royalty and a referral share
+fn create_terms(env: &Env, institutional_bps: u32, referral_bps: u32) -> RoyaltyTerms {
+    RoyaltyTerms {
+        resale_bps: 500,
+        institutional_bps,
+        institution: Some(Address::generate(env)),
+        referral_bps,
+        referrer: Some(Address::generate(env)),
+        updated_at: 0,
+    }
+}
+
+#[test]
+fn test_set_and_get_dataset_terms() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    // Arrange
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    let terms = create_terms(&env, 1000, 200);
+
+    // Act
+    let stored = client.set_dataset_terms(&dataset_id, &terms);
+
+    // Assert
+    assert_eq!(stored.institutional_bps, 1000);
+    assert_eq!(client.get_dataset_terms(&dataset_id), stored);
+    assert_eq!(client.dataset_terms_or_default(&dataset_id), stored);
+}
+
+#[test]
+fn test_set_and_clear_study_terms() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    // Arrange
+    let study_hash = BytesN::from_array(&env, &[3u8; 32]);
+
+    // Act
+    client.set_study_terms(&study_hash, &create_terms(&env, 100, 0));
+    client.clear_study_terms(&study_hash);
+
+    // Assert
+    assert_eq!(
+        client.try_get_study_terms(&study_hash),
+        Err(Ok(Error::TermsNotFound))
+    );
+}
+
+#[test]
+fn test_missing_dataset_terms_default_to_zero() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    // Act
+    let terms = client.dataset_terms_or_default(&Bytes::from_slice(&env, b"unknown"));
+
+    // Assert
+    assert_eq!(terms.resale_bps, 0);
+    assert_eq!(terms.institutional_bps, 0);
+    assert_eq!(terms.institution, None);
+}
+
+#[test]
+fn test_excessive_royalty_rejected() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    // Act: 500 + 4000 + 1000 > MAX_TOTAL_BPS
+    let result = client.try_set_dataset_terms(
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &create_terms(&env, 4000, 1000),
+    );
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::RoyaltyTooHigh)));
+}
+
+#[test]
+fn test_royalty_without_recipient_rejected() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    // Arrange
+    let mut terms = create_terms(&env, 1000, 0);
+    terms.institution = None;
+
+    // Act
+    let result = client.try_set_dataset_terms(&Bytes::from_slice(&env, b"dataset_001"), &terms);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::MissingRecipient)));
+}
+
+#[test]
+fn test_apply_bps_rounds_down() {
+    let env = Env::default();
+    let client = create_registry_client(&env);
+
+    assert_eq!(client.apply_bps(&8_5000000, &1000), 8500000);
+    assert_eq!(client.apply_bps(&9, &1000), 0);
+}
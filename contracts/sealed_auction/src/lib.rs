@@ -0,0 +1,498 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address,
+    Bytes, BytesN, Env, IntoVal, Symbol, Vec,
+};
+pub use biochain_types::{Dataset, DatasetStatus};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const MARKETPLACE_KEY: Symbol = symbol_short!("MARKET");
+const USDC_TOKEN_KEY: Symbol = symbol_short!("USDC_TKN");
+const AUCTION_COUNT_KEY: Symbol = symbol_short!("AUC_CNT");
+const AUCTION_KEY: Symbol = symbol_short!("AUCTION");
+const BID_KEY: Symbol = symbol_short!("BID");
+
+/// Domain tag of bid commitments, so they cannot be replayed elsewhere
+const BID_DOMAIN: Symbol = symbol_short!("BIO_BID");
+
+/// TTL policy for auctions and sealed bids, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum bidders per auction
+pub const MAX_BIDDERS: u32 = 50;
+
+/// Auction lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Open,
+    Sold,
+    Unsold,
+}
+
+/// Sealed-bid auction of a dataset license
+///
+/// - reserve_price: Lowest bid that can win, at least the listed price
+/// - commit_ends: Ledger timestamp closing the commit phase
+/// - reveal_ends: Ledger timestamp closing the reveal phase
+/// - bidders: Addresses that committed, in commit order
+/// - highest_bidder / highest_bid: Best valid reveal so far; ties go to
+///   the earlier reveal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub seller: Address,
+    pub dataset_id: Bytes,
+    pub reserve_price: i128,
+    pub commit_ends: u64,
+    pub reveal_ends: u64,
+    pub bidders: Vec<Address>,
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128,
+    pub status: AuctionStatus,
+}
+
+/// Sealed bid
+///
+/// - commitment: `commitment_hash` of the bid amount and a secret salt
+/// - deposit: Escrowed USDC, the most the bid can reveal
+/// - revealed: Amount revealed, if the bid was opened in time
+/// - refunded: Whether the deposit (or its unused part) was returned
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    pub deposit: i128,
+    pub revealed: Option<i128>,
+    pub refunded: bool,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidSchedule = 4,
+    AuctionNotFound = 5,
+    DatasetNotFound = 6,
+    NotInCommitPhase = 7,
+    NotInRevealPhase = 8,
+    AlreadyCommitted = 9,
+    TooManyBidders = 10,
+    BidNotFound = 11,
+    InvalidReveal = 12,
+    AlreadyRevealed = 13,
+    AuctionNotEnded = 14,
+    AuctionClosed = 15,
+    NothingToRefund = 16,
+}
+
+/// Commit-reveal auctions for exclusive dataset sales
+///
+/// Open bids leak what buyers are willing to pay. Here bidders first
+/// commit to a hash of their bid and escrow a deposit that caps it, then
+/// open the bid once commits close. After the reveal phase the highest
+/// valid reveal buys the dataset through the DatasetMarketplace with
+/// `purchase_dataset_at`, so contributors are paid the winning bid through
+/// the normal RevenueSplitter payout path. Every other deposit, and the
+/// unused part of the winner's, is refunded.
+#[contract]
+pub struct SealedAuction;
+
+#[contractimpl]
+impl SealedAuction {
+    /// Initialize the SealedAuction contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Deployment admin
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// * `usdc_token` - Address of the USDC token contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        marketplace: Address,
+        usdc_token: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&MARKETPLACE_KEY, &marketplace);
+        storage.set(&USDC_TOKEN_KEY, &usdc_token);
+        storage.set(&AUCTION_COUNT_KEY, &0u64);
+        Ok(())
+    }
+
+    /// Open a sealed-bid auction for a listed dataset
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `seller` - Address opening the auction (must authorize)
+    /// * `dataset_id` - ID of the dataset in the DatasetMarketplace
+    /// * `reserve_price` - Lowest winning bid, at least the listed price
+    /// * `commit_ends` - Ledger timestamp closing the commit phase
+    /// * `reveal_ends` - Ledger timestamp closing the reveal phase
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the auction ID
+    /// * `Err(Error::InvalidAmount)` if the reserve is below the listed price
+    /// * `Err(Error::InvalidSchedule)` unless now < commit_ends < reveal_ends
+    /// * `Err(Error::DatasetNotFound)` if the dataset is not listed
+    pub fn create_auction(
+        env: Env,
+        seller: Address,
+        dataset_id: Bytes,
+        reserve_price: i128,
+        commit_ends: u64,
+        reveal_ends: u64,
+    ) -> Result<u64, Error> {
+        // ============================================
+        // 1. VALIDATE INPUTS
+        // ============================================
+        seller.require_auth();
+        if commit_ends <= env.ledger().timestamp() || reveal_ends <= commit_ends {
+            return Err(Error::InvalidSchedule);
+        }
+        let marketplace: Address = env
+            .storage()
+            .instance()
+            .get(&MARKETPLACE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        let dataset = match env.try_invoke_contract::<Dataset, soroban_sdk::Error>(
+            &marketplace,
+            &Symbol::new(&env, "get_dataset"),
+            (dataset_id.clone(),).into_val(&env),
+        ) {
+            Ok(Ok(dataset)) => dataset,
+            _ => return Err(Error::DatasetNotFound),
+        };
+        if reserve_price <= 0 || reserve_price < dataset.price_usdc {
+            return Err(Error::InvalidAmount);
+        }
+
+        // ============================================
+        // 2. STORE AUCTION
+        // ============================================
+        let storage = env.storage().instance();
+        let auction_id: u64 = storage
+            .get(&AUCTION_COUNT_KEY)
+            .ok_or(Error::NotInitialized)?;
+        storage.set(&AUCTION_COUNT_KEY, &(auction_id + 1));
+
+        let auction = Auction {
+            auction_id,
+            seller: seller.clone(),
+            dataset_id: dataset_id.clone(),
+            reserve_price,
+            commit_ends,
+            reveal_ends,
+            bidders: Vec::new(&env),
+            highest_bidder: None,
+            highest_bid: 0,
+            status: AuctionStatus::Open,
+        };
+        Self::save_auction(&env, &auction);
+
+        // ============================================
+        // 3. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("AucNew"), auction_id),
+            (seller, dataset_id, reserve_price, commit_ends, reveal_ends),
+        );
+
+        Ok(auction_id)
+    }
+
+    /// Commit a sealed bid and escrow its deposit
+    ///
+    /// Only the commitment and deposit are public; the deposit caps the bid
+    /// and may be padded to hide it.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `auction_id` - Auction to bid in
+    /// * `bidder` - Researcher bidding (must authorize)
+    /// * `commitment` - `commitment_hash` of the bid
+    /// * `deposit` - USDC escrowed, at least the reserve price
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::NotInCommitPhase)` after `commit_ends`
+    /// * `Err(Error)` if the bidder already committed, the deposit is below
+    ///   the reserve or the auction is full
+    pub fn commit_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        commitment: BytesN<32>,
+        deposit: i128,
+    ) -> Result<(), Error> {
+        bidder.require_auth();
+        let mut auction = Self::load_open_auction(&env, auction_id)?;
+        if env.ledger().timestamp() > auction.commit_ends {
+            return Err(Error::NotInCommitPhase);
+        }
+        if auction.bidders.contains(&bidder) {
+            return Err(Error::AlreadyCommitted);
+        }
+        if auction.bidders.len() >= MAX_BIDDERS {
+            return Err(Error::TooManyBidders);
+        }
+        if deposit < auction.reserve_price {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::token_client(&env)?.transfer(&bidder, &env.current_contract_address(), &deposit);
+
+        let bid = SealedBid {
+            commitment,
+            deposit,
+            revealed: None,
+            refunded: false,
+        };
+        Self::save_bid(&env, auction_id, &bidder, &bid);
+        auction.bidders.push_back(bidder.clone());
+        Self::save_auction(&env, &auction);
+
+        env.events()
+            .publish((symbol_short!("BidSealed"), auction_id, bidder), deposit);
+        Ok(())
+    }
+
+    /// Reveal a sealed bid
+    ///
+    /// Anyone holding the amount and salt may reveal, e.g. a relayer acting
+    /// for the bidder. Bids left unrevealed cannot win and are refunded.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `auction_id` - Auction the bid was committed in
+    /// * `bidder` - Bidder whose bid is revealed
+    /// * `amount` - Bid amount in USDC
+    /// * `salt` - Secret salt used in the commitment
+    ///
+    /// # Returns
+    /// * `Ok(bool)` whether the bid is now the highest
+    /// * `Err(Error::NotInRevealPhase)` outside the reveal phase
+    /// * `Err(Error::InvalidReveal)` if the amount and salt do not match the
+    ///   commitment, exceed the deposit or are below the reserve
+    pub fn reveal_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<bool, Error> {
+        let mut auction = Self::load_open_auction(&env, auction_id)?;
+        let now = env.ledger().timestamp();
+        if now <= auction.commit_ends || now > auction.reveal_ends {
+            return Err(Error::NotInRevealPhase);
+        }
+        let mut bid = Self::get_bid(env.clone(), auction_id, bidder.clone())?;
+        if bid.revealed.is_some() {
+            return Err(Error::AlreadyRevealed);
+        }
+        let expected =
+            Self::commitment_hash(env.clone(), auction_id, bidder.clone(), amount, salt);
+        if expected != bid.commitment || amount > bid.deposit || amount < auction.reserve_price {
+            return Err(Error::InvalidReveal);
+        }
+
+        bid.revealed = Some(amount);
+        Self::save_bid(&env, auction_id, &bidder, &bid);
+        let leading = amount > auction.highest_bid;
+        if leading {
+            auction.highest_bidder = Some(bidder.clone());
+            auction.highest_bid = amount;
+            Self::save_auction(&env, &auction);
+        }
+
+        env.events()
+            .publish((symbol_short!("BidReveal"), auction_id, bidder), amount);
+        Ok(leading)
+    }
+
+    /// Settle an auction once the reveal phase is over
+    ///
+    /// The highest valid reveal buys the dataset at its bid through the
+    /// marketplace; the purchase carries the winner's data use agreement,
+    /// so the winner authorizes the settlement. The unused part of the
+    /// winner's deposit is refunded. If nobody revealed a valid bid, or the
+    /// purchase fails, the auction closes unsold and every deposit can be
+    /// reclaimed.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `auction_id` - Auction to settle
+    ///
+    /// # Returns
+    /// * `Ok(AuctionStatus)` with the final status
+    /// * `Err(Error::AuctionNotEnded)` before `reveal_ends`
+    /// * `Err(Error::AuctionClosed)` if already settled
+    pub fn settle(env: Env, auction_id: u64) -> Result<AuctionStatus, Error> {
+        // ============================================
+        // 1. VALIDATE AUCTION
+        // ============================================
+        let mut auction = Self::load_open_auction(&env, auction_id)?;
+        if env.ledger().timestamp() <= auction.reveal_ends {
+            return Err(Error::AuctionNotEnded);
+        }
+
+        // ============================================
+        // 2. BUY FOR THE WINNER THROUGH THE MARKETPLACE
+        // ============================================
+        auction.status = AuctionStatus::Unsold;
+        if let Some(winner) = auction.highest_bidder.clone() {
+            winner.require_auth();
+            let marketplace: Address = env
+                .storage()
+                .instance()
+                .get(&MARKETPLACE_KEY)
+                .ok_or(Error::NotInitialized)?;
+            let token = Self::token_client(&env)?;
+            let this = env.current_contract_address();
+            let price = auction.highest_bid;
+            token.approve(&this, &marketplace, &price, &env.ledger().sequence());
+            let purchase = env.try_invoke_contract::<Dataset, soroban_sdk::Error>(
+                &marketplace,
+                &Symbol::new(&env, "purchase_dataset_at"),
+                (auction.dataset_id.clone(), winner.clone(), this.clone(), price)
+                    .into_val(&env),
+            );
+
+            if let Ok(Ok(_)) = purchase {
+                auction.status = AuctionStatus::Sold;
+                let mut bid = Self::get_bid(env.clone(), auction_id, winner.clone())?;
+                let change = bid.deposit - price;
+                if change > 0 {
+                    token.transfer(&this, &winner, &change);
+                }
+                bid.refunded = true;
+                Self::save_bid(&env, auction_id, &winner, &bid);
+            } else {
+                token.approve(&this, &marketplace, &0, &env.ledger().sequence());
+            }
+        }
+        Self::save_auction(&env, &auction);
+
+        // ============================================
+        // 3. EMIT EVENT
+        // ============================================
+        env.events().publish(
+            (symbol_short!("AucSettle"), auction_id),
+            (auction.status, auction.highest_bidder, auction.highest_bid),
+        );
+
+        Ok(auction.status)
+    }
+
+    /// Refund a losing or unrevealed bid's deposit
+    ///
+    /// Available once the reveal phase is over. The leading bidder is
+    /// refunded by `settle` instead, unless the auction closed unsold.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `auction_id` - Auction the bid was committed in
+    /// * `bidder` - Bidder to refund
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the refunded amount
+    /// * `Err(Error::AuctionNotEnded)` before `reveal_ends`
+    /// * `Err(Error::NothingToRefund)` if already refunded or the bid is
+    ///   still leading an unsettled auction
+    pub fn claim_refund(env: Env, auction_id: u64, bidder: Address) -> Result<i128, Error> {
+        let auction = Self::get_auction(env.clone(), auction_id)?;
+        if env.ledger().timestamp() <= auction.reveal_ends {
+            return Err(Error::AuctionNotEnded);
+        }
+        let mut bid = Self::get_bid(env.clone(), auction_id, bidder.clone())?;
+        let leading = auction.highest_bidder.as_ref() == Some(&bidder);
+        if bid.refunded || (leading && auction.status == AuctionStatus::Open) {
+            return Err(Error::NothingToRefund);
+        }
+
+        Self::token_client(&env)?.transfer(&env.current_contract_address(), &bidder, &bid.deposit);
+        bid.refunded = true;
+        Self::save_bid(&env, auction_id, &bidder, &bid);
+
+        env.events()
+            .publish((symbol_short!("BidRefund"), auction_id, bidder), bid.deposit);
+        Ok(bid.deposit)
+    }
+
+    /// Compute the commitment of a bid
+    ///
+    /// Bidders should compute it off-chain; calling this on a public node
+    /// discloses the amount to it.
+    pub fn commitment_hash(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        let preimage = (BID_DOMAIN, auction_id, bidder, amount, salt).to_xdr(&env);
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Get an auction by ID
+    pub fn get_auction(env: Env, auction_id: u64) -> Result<Auction, Error> {
+        env.storage()
+            .persistent()
+            .get(&(AUCTION_KEY, auction_id))
+            .ok_or(Error::AuctionNotFound)
+    }
+
+    /// Get a bidder's sealed bid in an auction
+    pub fn get_bid(env: Env, auction_id: u64, bidder: Address) -> Result<SealedBid, Error> {
+        env.storage()
+            .persistent()
+            .get(&(BID_KEY, auction_id, bidder))
+            .ok_or(Error::BidNotFound)
+    }
+
+    fn load_open_auction(env: &Env, auction_id: u64) -> Result<Auction, Error> {
+        let auction = Self::get_auction(env.clone(), auction_id)?;
+        if auction.status != AuctionStatus::Open {
+            return Err(Error::AuctionClosed);
+        }
+        Ok(auction)
+    }
+
+    fn save_auction(env: &Env, auction: &Auction) {
+        let key = (AUCTION_KEY, auction.auction_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, auction);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn save_bid(env: &Env, auction_id: u64, bidder: &Address, bid: &SealedBid) {
+        let key = (BID_KEY, auction_id, bidder.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, bid);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = env
+            .storage()
+            .instance()
+            .get(&USDC_TOKEN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
@@ -0,0 +1,286 @@
+#![cfg(test)]
+
+use sealed_auction::{AuctionStatus, Error, SealedAuction, SealedAuctionClient};
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+
+/// Listed price of the auctioned dataset (100 USDC)
+const PRICE: i128 = 100_0000000;
+
+/// Phase deadlines used by the tests
+const COMMIT_ENDS: u64 = 1_000;
+const REVEAL_ENDS: u64 = 2_000;
+
+struct Setup<'a> {
+    env: Env,
+    auction: SealedAuctionClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    contributor: Address,
+    dataset_id: Bytes,
+}
+
+/// Helper: Deploy the core contracts and a SealedAuction, and list a
+/// one-study dataset
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let auction = SealedAuctionClient::new(&env, &env.register_contract(None, SealedAuction));
+    auction.init(&Address::generate(&env), &marketplace.address, &usdc_id);
+
+    let contributor = Address::generate(&env);
+    let study_hash = [1u8; 32];
+    let study = BytesN::from_array(&env, &study_hash);
+    let attestation = study_registry::testutils::attestation(&env, &study, &contributor);
+    registry.register_study(
+        &study,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study, &attestation, &contributor),
+        &contributor,
+    );
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from_array(&env, &study_hash)],
+        &PRICE,
+        &None,
+    );
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        auction,
+        marketplace,
+        contributor,
+        dataset_id,
+    }
+}
+
+/// Helper: Open an auction reserved at the listed price
+fn create_auction(s: &Setup) -> u64 {
+    s.auction.create_auction(
+        &Address::generate(&s.env),
+        &s.dataset_id,
+        &PRICE,
+        &COMMIT_ENDS,
+        &REVEAL_ENDS,
+    )
+}
+
+/// Helper: Fund a new bidder and commit `amount` with a deposit of `deposit`
+fn commit(s: &Setup, auction_id: u64, amount: i128, deposit: i128) -> (Address, BytesN<32>) {
+    let bidder = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.usdc.address).mint(&bidder, &deposit);
+    let seed = s.auction.get_auction(&auction_id).bidders.len() as u8 + 1;
+    let salt = BytesN::from_array(&s.env, &[seed; 32]);
+    let commitment = s
+        .auction
+        .commitment_hash(&auction_id, &bidder, &amount, &salt);
+    s.auction
+        .commit_bid(&auction_id, &bidder, &commitment, &deposit);
+    (bidder, salt)
+}
+
+#[test]
+fn test_highest_reveal_wins_and_pays_contributors() {
+    let s = setup();
+    let auction_id = create_auction(&s);
+    let (low, low_salt) = commit(&s, auction_id, 120_0000000, 200_0000000);
+    let (high, high_salt) = commit(&s, auction_id, 150_0000000, 160_0000000);
+    let (silent, _) = commit(&s, auction_id, 300_0000000, 300_0000000);
+
+    // Act
+    s.env.ledger().set_timestamp(COMMIT_ENDS + 1);
+    assert!(s
+        .auction
+        .reveal_bid(&auction_id, &low, &120_0000000, &low_salt));
+    assert!(s
+        .auction
+        .reveal_bid(&auction_id, &high, &150_0000000, &high_salt));
+    s.env.ledger().set_timestamp(REVEAL_ENDS + 1);
+    assert_eq!(s.auction.settle(&auction_id), AuctionStatus::Sold);
+
+    // Assert: the winner paid its bid, not its deposit, through the splitter
+    assert_eq!(
+        s.marketplace.get_purchase(&s.dataset_id, &high).buyer,
+        high
+    );
+    assert_eq!(s.usdc.balance(&high), 10_0000000);
+    assert_eq!(s.usdc.balance(&s.contributor), 150_0000000 * 85 / 100);
+
+    // Everyone else gets their full deposit back, once
+    assert_eq!(s.auction.claim_refund(&auction_id, &low), 200_0000000);
+    assert_eq!(s.auction.claim_refund(&auction_id, &silent), 300_0000000);
+    assert_eq!(
+        s.auction.try_claim_refund(&auction_id, &low),
+        Err(Ok(Error::NothingToRefund))
+    );
+    assert_eq!(
+        s.auction.try_claim_refund(&auction_id, &high),
+        Err(Ok(Error::NothingToRefund))
+    );
+    assert_eq!(s.usdc.balance(&s.auction.address), 0);
+    assert_eq!(
+        s.auction.try_settle(&auction_id),
+        Err(Ok(Error::AuctionClosed))
+    );
+}
+
+#[test]
+fn test_reveal_must_match_commitment_and_deposit() {
+    let s = setup();
+    let auction_id = create_auction(&s);
+    let (bidder, salt) = commit(&s, auction_id, 110_0000000, 110_0000000);
+    let (greedy, greedy_salt) = commit(&s, auction_id, 200_0000000, 120_0000000);
+    s.env.ledger().set_timestamp(COMMIT_ENDS + 1);
+
+    // Act & Assert: a different amount or salt does not open the bid
+    assert_eq!(
+        s.auction
+            .try_reveal_bid(&auction_id, &bidder, &105_0000000, &salt),
+        Err(Ok(Error::InvalidReveal))
+    );
+    assert_eq!(
+        s.auction
+            .try_reveal_bid(&auction_id, &bidder, &110_0000000, &BytesN::from_array(&s.env, &[0u8; 32])),
+        Err(Ok(Error::InvalidReveal))
+    );
+    // Bids above the escrowed deposit are invalid
+    assert_eq!(
+        s.auction
+            .try_reveal_bid(&auction_id, &greedy, &200_0000000, &greedy_salt),
+        Err(Ok(Error::InvalidReveal))
+    );
+    s.auction
+        .reveal_bid(&auction_id, &bidder, &110_0000000, &salt);
+    assert_eq!(
+        s.auction
+            .try_reveal_bid(&auction_id, &bidder, &110_0000000, &salt),
+        Err(Ok(Error::AlreadyRevealed))
+    );
+    let auction = s.auction.get_auction(&auction_id);
+    assert_eq!(
+        (auction.highest_bidder, auction.highest_bid),
+        (Some(bidder.clone()), 110_0000000)
+    );
+    assert_eq!(
+        s.auction.get_bid(&auction_id, &bidder).revealed,
+        Some(110_0000000)
+    );
+}
+
+#[test]
+fn test_phases_are_enforced() {
+    let s = setup();
+    let auction_id = create_auction(&s);
+    let (bidder, salt) = commit(&s, auction_id, PRICE, PRICE);
+
+    // Act & Assert: no reveals while commits are open
+    assert_eq!(
+        s.auction.try_reveal_bid(&auction_id, &bidder, &PRICE, &salt),
+        Err(Ok(Error::NotInRevealPhase))
+    );
+    s.env.ledger().set_timestamp(COMMIT_ENDS + 1);
+    let late = Address::generate(&s.env);
+    assert_eq!(
+        s.auction
+            .try_commit_bid(&auction_id, &late, &BytesN::from_array(&s.env, &[0u8; 32]), &PRICE),
+        Err(Ok(Error::NotInCommitPhase))
+    );
+    assert_eq!(s.auction.try_settle(&auction_id), Err(Ok(Error::AuctionNotEnded)));
+    assert_eq!(
+        s.auction.try_claim_refund(&auction_id, &bidder),
+        Err(Ok(Error::AuctionNotEnded))
+    );
+
+    // Bids revealed too late are refunded without selling
+    s.env.ledger().set_timestamp(REVEAL_ENDS + 1);
+    assert_eq!(
+        s.auction.try_reveal_bid(&auction_id, &bidder, &PRICE, &salt),
+        Err(Ok(Error::NotInRevealPhase))
+    );
+    assert_eq!(s.auction.settle(&auction_id), AuctionStatus::Unsold);
+    assert_eq!(s.auction.claim_refund(&auction_id, &bidder), PRICE);
+    assert_eq!(s.usdc.balance(&s.contributor), 0);
+}
+
+#[test]
+fn test_create_and_commit_validate_amounts() {
+    let s = setup();
+    let seller = Address::generate(&s.env);
+
+    // Act & Assert: the reserve cannot undercut the listing
+    assert_eq!(
+        s.auction.try_create_auction(
+            &seller,
+            &s.dataset_id,
+            &(PRICE - 1),
+            &COMMIT_ENDS,
+            &REVEAL_ENDS,
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.auction.try_create_auction(
+            &seller,
+            &s.dataset_id,
+            &PRICE,
+            &REVEAL_ENDS,
+            &COMMIT_ENDS,
+        ),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        s.auction.try_create_auction(
+            &seller,
+            &Bytes::from_slice(&s.env, b"missing"),
+            &PRICE,
+            &COMMIT_ENDS,
+            &REVEAL_ENDS,
+        ),
+        Err(Ok(Error::DatasetNotFound))
+    );
+
+    // Deposits below the reserve could never win
+    let auction_id = create_auction(&s);
+    let commitment = BytesN::from_array(&s.env, &[0u8; 32]);
+    let bidder = Address::generate(&s.env);
+    assert_eq!(
+        s.auction
+            .try_commit_bid(&auction_id, &bidder, &commitment, &(PRICE - 1)),
+        Err(Ok(Error::InvalidAmount))
+    );
+    let (committed, _) = commit(&s, auction_id, PRICE, PRICE);
+    assert_eq!(
+        s.auction
+            .try_commit_bid(&auction_id, &committed, &commitment, &PRICE),
+        Err(Ok(Error::AlreadyCommitted))
+    );
+}
@@ -0,0 +1,99 @@
+//! NVIDIA CVM attestation reports
+//!
+//! The attestation service of the confidential VM signs a fixed-layout
+//! report (ENCODED_LEN bytes):
+//!
+//! ```text
+//! magic "BCAT" (4) || version (1) || measurement (32) || report_data (32) || signature (64)
+//! ```
+//!
+//! - measurement: Hash of the CVM image the study was processed in (the
+//!   MRENCLAVE equivalent)
+//! - report_data: Nonce of the registration, SHA-256(dataset_hash ||
+//!   contributor strkey) (see `StudyRegistry::attestation_nonce`)
+//! - signature: ECDSA P-256 signature (r || s, low-S) over the SHA-256 of
+//!   everything before it, by the attestation root key
+
+/// Magic bytes identifying a report
+pub const MAGIC: [u8; 4] = *b"BCAT";
+
+/// Report layout version
+pub const VERSION: u8 = 1;
+
+/// Length of the signed part of a report
+pub const BODY_LEN: usize = 4 + 1 + 32 + 32;
+
+/// Length of an encoded report
+pub const ENCODED_LEN: usize = BODY_LEN + 64;
+
+/// First byte of a SEC-1 uncompressed public key
+pub const UNCOMPRESSED_KEY_TAG: u8 = 0x04;
+
+/// Order of the P-256 group, big-endian
+const ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63, 0x25, 0x51,
+];
+
+/// Largest low-S value, ORDER / 2, big-endian
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0x80, 0x00, 0x00, 0x00, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xde, 0x73, 0x7d, 0x56, 0xd3, 0x8b, 0xcf, 0x42, 0x79, 0xdc, 0xe5, 0x61, 0x7e, 0x31, 0x92, 0xa8,
+];
+
+/// Decoded attestation report
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    pub measurement: [u8; 32],
+    pub report_data: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl Report {
+    /// Decode a report, checking its magic and version
+    ///
+    /// # Returns
+    /// * `Some(Report)` if the layout is valid (the signature is not
+    ///   checked)
+    /// * `None` otherwise
+    pub fn decode(bytes: &[u8; ENCODED_LEN]) -> Option<Report> {
+        if bytes[..4] != MAGIC || bytes[4] != VERSION {
+            return None;
+        }
+        Some(Report {
+            measurement: bytes[5..37].try_into().ok()?,
+            report_data: bytes[37..BODY_LEN].try_into().ok()?,
+            signature: bytes[BODY_LEN..].try_into().ok()?,
+        })
+    }
+
+    /// Whether the signature has the form the host accepts
+    ///
+    /// r and s must be non-zero and below the group order, and s must be
+    /// low. The host traps on any other signature, so checking first turns
+    /// those into an error; a well-formed signature that does not verify
+    /// still traps.
+    pub fn has_canonical_signature(&self) -> bool {
+        let (r, s) = self.signature.split_at(32);
+        let zero = [0u8; 32];
+        r != zero && s != zero && r < &ORDER[..] && s <= &HALF_ORDER[..]
+    }
+
+    /// Signed part of the report
+    pub fn body(&self) -> [u8; BODY_LEN] {
+        let mut body = [0u8; BODY_LEN];
+        body[..4].copy_from_slice(&MAGIC);
+        body[4] = VERSION;
+        body[5..37].copy_from_slice(&self.measurement);
+        body[37..].copy_from_slice(&self.report_data);
+        body
+    }
+
+    /// Encode the report with its signature
+    pub fn encode(&self) -> [u8; ENCODED_LEN] {
+        let mut out = [0u8; ENCODED_LEN];
+        out[..BODY_LEN].copy_from_slice(&self.body());
+        out[BODY_LEN..].copy_from_slice(&self.signature);
+        out
+    }
+}
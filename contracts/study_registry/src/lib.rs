@@ -1,31 +1,399 @@
 #![no_std]
+use biochain_groth16::PROOF_LEN;
+use biochain_zk_schema::{PublicInputs, ENCODED_LEN, SIGNAL_COUNT, STRKEY_LEN};
+pub use biochain_types::StudyRecord;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Env, Address, 
-    Bytes, BytesN,
+    contract, contracterror, contractimpl, contractmeta, contracttype, symbol_short, Env, Symbol, Address, 
+    Bytes, BytesN, Map, String, Vec, IntoVal, TryFromVal, Val,
 };
 
-/// StudyRecord struct
+pub mod attestation;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+
+/// Storage keys used before schema 3
+/// 
+/// Only read through `DataKey::legacy` so entries written by older code
+/// stay reachable until they are migrated. Study records were keyed by
+/// their raw dataset hash.
+const STATS_KEY: Symbol = symbol_short!("STATS");
+const UPGRADER_KEY: Symbol = symbol_short!("UPGRADER");
+const PAUSED_KEY: Symbol = symbol_short!("PAUSED");
+const SCHEMA_KEY: Symbol = symbol_short!("SCHEMA");
+const AUDIT_KEY: Symbol = symbol_short!("AUDIT");
+const CONTRIB_KEY: Symbol = symbol_short!("CONTRIB");
+const RELAY_KEY: Symbol = symbol_short!("RELAY");
+
+/// Interface version checked by the UpgradeCoordinator after an upgrade
+/// 
+/// - 2: Adds `revoke_study`
+/// - 3: Verifies Groth16 proofs; adds `set_verifying_key`
+/// - 4: Verifying keys per circuit and version; adds `set_study_circuit`
+///   and `get_study_verification`
+/// - 5: `register_study` takes a `ProofKind` and accepts RISC Zero
+///   receipts; adds `set_image_id` and `set_receipt_verifier`
+/// - 6: Verifies signed CVM attestations; adds `set_attestation_root` and
+///   `set_measurement_allowed`
+/// - 7: Attestations embed a nonce of the registration; adds
+///   `attestation_nonce`
+/// - 8: `register_study` requires the contributor's authorization
+/// - 9: Adds `list_studies`, `study_count` and `get_studies_by_contributor`
+/// - 10: Adds `register_study_with_metadata` and `get_study_metadata`
+/// - 11: `revoke_study` takes the caller and a reason and is open to the
+///   UpgradeCoordinator; adds `get_revoked_studies`
+/// - 12: Adds `register_study_with_contributors` and
+///   `get_study_contributors`
+/// - 13: Adds `init` and `get_admin`; `set_stats_contract` and
+///   `set_upgrade_coordinator` require the admin
+/// - 14: `set_verifying_key`, `set_study_circuit`, `set_attestation_root`,
+///   `set_measurement_allowed`, `set_image_id`, `set_receipt_verifier`,
+///   `set_audit_log`, `set_guardian_set`, `set_onboarding`,
+///   `set_credit_token`, `set_relayer_registry` and `set_marketplace`
+///   require the admin
+/// - 15: Groth16 proofs are checked by the proof verifier contract instead
+///   of in `register_study`; adds `set_proof_verifier`
+/// - 16: Adds `set_reputation_contract`
+/// - 17: `set_attestation_root` rejects keys that are not SEC-1
+///   uncompressed and attestations with a malformed signature fail with
+///   `Error::InvalidAttestation`
+pub const INTERFACE_VERSION: u32 = 17;
+
+/// Circuit recorded for studies verified with a RISC Zero receipt; the
+/// key version is the version of the guest image ID
+pub const RISC_ZERO_CIRCUIT: Symbol = symbol_short!("risc0");
+
+/// Maximum studies returned by a single `list_studies` or
+/// `get_studies_by_contributor` call
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Study hashes per StudyIndex entry, so the index adds one ledger entry
+/// per chunk rather than per study
+const STUDY_INDEX_CHUNK: u32 = 50;
+
+/// Total of a study's contributor shares, in basis points
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Maximum contributors of a single study
+pub const MAX_STUDY_CONTRIBUTORS: u32 = 10;
+
+/// Maximum length of a study's off-chain metadata URI, enough for an
+/// `ipfs://` URI of a CIDv1 with a path
+pub const MAX_METADATA_URI_LEN: u32 = 128;
+
+/// Data-handling policy version committed to in the ZK public inputs
+pub const POLICY_VERSION: u32 = 1;
+
+// Contract metadata embedded in the WASM custom section
+contractmeta!(key = "Description", val = "Registers anonymized medical studies with attestation hashes and Groth16 proofs");
+
+/// TTL policy for records kept alive by the TtlKeeper, in ledgers
+/// 
+/// Entries are extended to ~30 days once they drop below ~7 days.
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Storage schema version written by this code
+/// 
+/// - 1: StudyRecord stored raw (no marker in storage)
+/// - 2: StudyRecord stored wrapped in VersionedStudy
+/// - 3: All entries keyed by `DataKey` instead of raw hashes and symbols
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Storage keys
 /// 
-/// Stores essential study information on-chain:
-/// - dataset_hash: Unique hash of the processed dataset
-/// - contributor: Address of the study contributor
-/// - timestamp: Ledger timestamp when the study was registered
+/// Each record type has its own variant, so keys of different records
+/// cannot collide:
+/// - Study / Revocation / StudyContributors: Study records, their
+///   revocations and the shares of studies with several contributors in
+///   instance storage
+/// - ContributorStudies: Per-contributor index in persistent storage
+/// - StudyCount / StudyIndex: Number of registered studies and their
+///   hashes by registration order in chunks of STUDY_INDEX_CHUNK, in
+///   persistent storage (added with schema 3 or later, so there is no
+///   older key)
+/// - CreditToken / Guardians / Marketplace / Onboarding / Reputation:
+///   ContributionCredit, GuardianSet, DatasetMarketplace,
+///   ContributorOnboarding and ContributorReputation addresses in instance
+///   storage (added with schema 3 or later, so there is no older key)
+/// - VerifyingKey / ImageId: Groth16 verifying keys by circuit and RISC
+///   Zero image IDs by proof kind, each by version, in persistent storage
+///   so they do not grow the instance entry loaded by every call
+/// - KeyVersion / StudyCircuit / ImageIdVersion / ReceiptVerifier /
+///   ProofVerifier: Latest key version per circuit, the circuit
+///   registrations are verified against, latest image ID version per proof
+///   kind and the RISC Zero and Groth16 verifier contracts, in instance
+///   storage (added with schema 3 or later, so there is no older key)
+/// - AttestationRoot / Measurement: Attestation root public key and the
+///   allowed CVM measurements, in instance storage (added with schema 3 or
+///   later, so there is no older key)
+/// - Admin: Address allowed to change the configuration, in instance
+///   storage (added with schema 3, so there is no older key)
+/// - Remaining variants: Configuration in instance storage
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct StudyRecord {
-    pub dataset_hash: BytesN<32>,
-    pub contributor: Address,
-    pub timestamp: u64,
+pub enum DataKey {
+    Study(BytesN<32>),
+    ContributorStudies(Address),
+    Stats,
+    Upgrader,
+    Paused,
+    Schema,
+    AuditLog,
+    RelayerRegistry,
+    CreditToken,
+    Guardians,
+    Revocation(BytesN<32>),
+    Marketplace,
+    Onboarding,
+    VerifyingKey(Symbol, u32),
+    KeyVersion(Symbol),
+    StudyCircuit,
+    ImageId(ProofKind, u32),
+    ImageIdVersion(ProofKind),
+    ReceiptVerifier,
+    ProofVerifier,
+    AttestationRoot,
+    Measurement(BytesN<32>),
+    StudyCount,
+    StudyIndex(u32),
+    StudyContributors(BytesN<32>),
+    Admin,
+    Reputation,
 }
 
-/// Error types for the contract
+impl DataKey {
+    /// Key the same entry was stored under before schema 3
+    fn legacy(&self, env: &Env) -> Val {
+        match self {
+            DataKey::Study(dataset_hash) => dataset_hash.into_val(env),
+            DataKey::ContributorStudies(contributor) => (CONTRIB_KEY, contributor.clone()).into_val(env),
+            DataKey::Stats => STATS_KEY.into_val(env),
+            DataKey::Upgrader => UPGRADER_KEY.into_val(env),
+            DataKey::Paused => PAUSED_KEY.into_val(env),
+            DataKey::Schema => SCHEMA_KEY.into_val(env),
+            DataKey::AuditLog => AUDIT_KEY.into_val(env),
+            DataKey::RelayerRegistry => RELAY_KEY.into_val(env),
+            DataKey::CreditToken
+            | DataKey::Guardians
+            | DataKey::Revocation(_)
+            | DataKey::Marketplace
+            | DataKey::Onboarding
+            | DataKey::VerifyingKey(..)
+            | DataKey::KeyVersion(_)
+            | DataKey::StudyCircuit
+            | DataKey::ImageId(..)
+            | DataKey::ImageIdVersion(_)
+            | DataKey::ReceiptVerifier
+            | DataKey::ProofVerifier
+            | DataKey::AttestationRoot
+            | DataKey::Measurement(_)
+            | DataKey::StudyCount
+            | DataKey::StudyIndex(_)
+            | DataKey::StudyContributors(_)
+            | DataKey::Admin
+            | DataKey::Reputation => self.into_val(env),
+        }
+    }
+}
+
+/// Configuration entries moved to `DataKey` by the schema 3 migration
+const CONFIG_KEYS: [DataKey; 6] = [
+    DataKey::Stats,
+    DataKey::Upgrader,
+    DataKey::Paused,
+    DataKey::Schema,
+    DataKey::AuditLog,
+    DataKey::RelayerRegistry,
+];
+
+/// Why a study was revoked
+/// 
+/// Unspecified is reported for revocations recorded before reasons were.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RevocationReason {
+    Unspecified,
+    ConsentWithdrawn,
+    DataQuality,
+    Misconduct,
+    Other,
+}
+
+/// Revocation of a study
+/// 
+/// - revoked_at: Ledger timestamp of the revocation
+/// - erased: Whether the study record was erased too (it is kept, marked
+///   revoked, otherwise)
+/// - reason: Why the study was revoked
+/// - by_admin: Whether the UpgradeCoordinator revoked it rather than the
+///   contributor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyRevocation {
+    pub revoked_at: u64,
+    pub erased: bool,
+    pub reason: RevocationReason,
+    pub by_admin: bool,
+}
+
+/// Revocation layout written before reasons were recorded
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct LegacyRevocation {
+    revoked_at: u64,
+    erased: bool,
+}
+
+/// Versioned wrapper for stored study records
+/// 
+/// New record layouts are added as new variants so that entries written by
+/// older code can still be decoded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionedStudy {
+    V1(StudyRecord),
+    /// Record with the verifying key its proof was checked against
+    V2(StudyRecord, StudyVerification),
+    /// V2 with the metadata declared at registration
+    V3(StudyRecord, StudyVerification, StudyMetadata),
+}
+
+/// Groth16 verifying key of a study validity circuit
+/// 
+/// Every circuit exposes the SIGNAL_COUNT public signals of
+/// biochain-zk-schema, so keys have SIGNAL_COUNT + 1 commitments.
+type StudyVerifyingKey = biochain_groth16::VerifyingKey<{ SIGNAL_COUNT + 1 }>;
+
+/// Length of an encoded verifying key
+pub const VERIFYING_KEY_LEN: usize = StudyVerifyingKey::ENCODED_LEN;
+
+/// Proof system of a registration's `zk_proof`
+/// 
+/// - Groth16: Groth16 proof (A || B || C) for the selected study circuit
+/// - RiscZero: RISC Zero receipt, journal || seal, where the journal is the
+///   registration's encoded public inputs (ENCODED_LEN bytes) committed by
+///   the guest and the seal is checked by the receipt verifier contract
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProofKind {
+    Groth16,
+    RiscZero,
+}
+
+/// Key a study's proof was verified with
+/// 
+/// - circuit_id: Circuit the proof was made for (RISC_ZERO_CIRCUIT for
+///   receipts)
+/// - key_version: Version of the circuit's verifying key, or of the image
+///   ID for receipts, at registration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyVerification {
+    pub circuit_id: Symbol,
+    pub key_version: u32,
+}
+
+/// Kind of data a study holds
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StudyType {
+    Imaging,
+    Genomics,
+    Labs,
+    Clinical,
+    Wearables,
+    Other,
+}
+
+/// How a study was anonymized in the CVM
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AnonymizationMethod {
+    Deidentification,
+    Pseudonymization,
+    KAnonymity,
+    DifferentialPrivacy,
+    Other,
+}
+
+/// Descriptive metadata of a study, for marketplace listings
+/// 
+/// Declared by the contributor at registration; unlike the dataset hash it
+/// is not covered by the attestation or the proof.
+/// - study_type: Kind of data in the study
+/// - anonymization: Anonymization method applied
+/// - record_count: Number of records in the dataset
+/// - schema_hash: Hash of the dataset's data schema
+/// - metadata_uri: Off-chain metadata document, e.g. `ipfs://<CID>`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StudyMetadata {
+    pub study_type: StudyType,
+    pub anonymization: AnonymizationMethod,
+    pub record_count: u32,
+    pub schema_hash: BytesN<32>,
+    pub metadata_uri: String,
+}
+
+/// Configuration health of the contract
+/// 
+/// Returned by `health()` so deploy pipelines and monitoring can check the
+/// wiring in a single read:
+/// - stats_set / upgrader_set / audit_log_set / relayer_registry_set:
+///   Whether the corresponding contract address is configured
+/// - verifying_key_set: Whether a study circuit with a verifying key is
+///   selected and a proof verifier is set
+/// - attestation_root_set: Whether the attestation root key is set
+/// - paused: Whether registrations are paused
+/// - schema_version: Schema version of the stored data
+/// - ready: Whether registrations can be served
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Health {
+    pub stats_set: bool,
+    pub upgrader_set: bool,
+    pub audit_log_set: bool,
+    pub relayer_registry_set: bool,
+    pub verifying_key_set: bool,
+    pub attestation_root_set: bool,
+    pub paused: bool,
+    pub schema_version: u32,
+    pub ready: bool,
+}
+
+/// Error types for the contract
+/// 
+/// StudyRegistry owns codes 100-199 so errors stay distinguishable when
+/// they surface through DatasetMarketplace or RevenueSplitter calls.
+/// Codes are stable: never renumber, only append.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum Error {
-    DuplicateStudy,
-    InvalidAttestation,
-    InvalidZKProof,
-    StudyNotFound,
+    DuplicateStudy = 100,
+    InvalidAttestation = 101,
+    InvalidZKProof = 102,
+    StudyNotFound = 103,
+    Paused = 104,
+    Unauthorized = 105,
+    RelayerNotAllowed = 106,
+    InvalidNonce = 107,
+    StudyRevoked = 108,
+    NotOnboarded = 109,
+    VerifyingKeyNotSet = 110,
+    InvalidVerifyingKey = 111,
+    ImageIdNotSet = 112,
+    ReceiptVerifierNotSet = 113,
+    InvalidJournal = 114,
+    UnsupportedProofKind = 115,
+    AttestationRootNotSet = 116,
+    MeasurementNotAllowed = 117,
+    AttestationReplay = 118,
+    InvalidMetadata = 119,
+    InvalidContributors = 120,
+    AlreadyInitialized = 121,
+    ProofVerifierNotSet = 122,
+    InvalidAttestationRoot = 123,
 }
 
 #[contract]
@@ -33,6 +401,37 @@ pub struct StudyRegistry;
 
 #[contractimpl]
 impl StudyRegistry {
+    /// Initialize the StudyRegistry contract
+    /// 
+    /// Admin-only setters fail with `Error::Unauthorized` until an admin is
+    /// set, so nobody can point the registry at their own contracts before
+    /// the deployer does.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to change the configuration
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if an admin is already set
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
+    /// Get the admin address
+    /// 
+    /// # Returns
+    /// * `Ok(Address)` the current admin
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Admin).ok_or(Error::Unauthorized)
+    }
+
     /// Register a medical study on-chain
     /// 
     /// This function validates and stores a study record after processing through:
@@ -40,13 +439,21 @@ impl StudyRegistry {
     /// 2. ZK-Prover - zero-knowledge proof
     /// 
     /// Requirements:
-    /// - attestation must be non-empty (TEE attestation proof)
-    /// - zk_proof must be non-empty (ZK proof of validity)
+    /// - contributor must authorize the call, so studies (and the payouts
+    ///   that follow them) cannot be attributed to someone else
+    /// - attestation must be a report embedding the nonce of dataset_hash
+    ///   and contributor, signed by the attestation root key, from an
+    ///   allowed CVM measurement
+    /// - zk_proof must be a Groth16 proof that the proof verifier accepts
+    ///   under the stored verifying key for the public inputs of this call,
+    ///   or a RISC Zero receipt of the expected image whose journal is those
+    ///   public inputs
     /// - dataset_hash must be unique (no duplicates allowed)
     /// 
     /// Storage:
-    /// - Key: dataset_hash (BytesN<32>)
-    /// - Value: StudyRecord { dataset_hash, contributor, timestamp }
+    /// - Key: DataKey::Study(dataset_hash)
+    /// - Value: StudyRecord { dataset_hash, contributor, timestamp } and the
+    ///   key that verified the proof
     /// 
     /// Events:
     /// - Emits StudyRegistered event with dataset_hash, contributor, timestamp
@@ -55,6 +462,7 @@ impl StudyRegistry {
     /// * `env` - The Soroban environment
     /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
     /// * `attestation` - TEE attestation proof from NVIDIA CVM
+    /// * `proof_kind` - Proof system of `zk_proof`
     /// * `zk_proof` - Zero-knowledge proof of study validity
     /// * `contributor` - Address of the study contributor
     /// 
@@ -65,47 +473,153 @@ impl StudyRegistry {
         env: Env,
         dataset_hash: BytesN<32>,
         attestation: Bytes,
+        proof_kind: ProofKind,
+        zk_proof: Bytes,
+        contributor: Address,
+    ) -> Result<(), Error> {
+        contributor.require_auth();
+        Self::register(env, dataset_hash, attestation, proof_kind, zk_proof, contributor, None, None)
+    }
+
+    /// Register a study with several contributors sharing its revenue
+    /// 
+    /// Same as `register_study`, for studies produced jointly, e.g. by a
+    /// hospital, a lab and a patient cohort steward. `contributor` submits
+    /// the study as before (the attestation and proof are bound to it and
+    /// it may revoke the study); `contributors` lists everyone paid for it,
+    /// `contributor` included, with their share in basis points. Every
+    /// listed contributor must authorize the call and be onboarded.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
+    /// * `attestation` - TEE attestation proof from NVIDIA CVM
+    /// * `proof_kind` - Proof system of `zk_proof`
+    /// * `zk_proof` - Zero-knowledge proof of study validity
+    /// * `contributor` - Address submitting the study (must authorize)
+    /// * `contributors` - Distinct contributors and their shares
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidContributors)` if `contributors` is empty, has
+    ///   more than MAX_STUDY_CONTRIBUTORS entries or duplicates, leaves out
+    ///   `contributor`, has a zero share or shares not summing to
+    ///   BPS_DENOMINATOR
+    /// * `Err(Error)` if validation fails as in `register_study`
+    pub fn register_study_with_contributors(
+        env: Env,
+        dataset_hash: BytesN<32>,
+        attestation: Bytes,
+        proof_kind: ProofKind,
+        zk_proof: Bytes,
+        contributor: Address,
+        contributors: Vec<(Address, u32)>,
+    ) -> Result<(), Error> {
+        contributor.require_auth();
+        Self::register(env, dataset_hash, attestation, proof_kind, zk_proof, contributor, None, Some(contributors))
+    }
+
+    /// Register a study with descriptive metadata for listings
+    /// 
+    /// Same as `register_study`; the metadata is stored with the study
+    /// record and returned by `get_study_metadata`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
+    /// * `attestation` - TEE attestation proof from NVIDIA CVM
+    /// * `proof_kind` - Proof system of `zk_proof`
+    /// * `zk_proof` - Zero-knowledge proof of study validity
+    /// * `contributor` - Address of the study contributor (must authorize)
+    /// * `metadata` - Metadata of the study
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidMetadata)` if the record count is zero or the
+    ///   URI is empty or longer than MAX_METADATA_URI_LEN
+    /// * `Err(Error)` if validation fails as in `register_study`
+    pub fn register_study_with_metadata(
+        env: Env,
+        dataset_hash: BytesN<32>,
+        attestation: Bytes,
+        proof_kind: ProofKind,
+        zk_proof: Bytes,
+        contributor: Address,
+        metadata: StudyMetadata,
+    ) -> Result<(), Error> {
+        contributor.require_auth();
+        Self::register(env, dataset_hash, attestation, proof_kind, zk_proof, contributor, Some(metadata), None)
+    }
+
+    /// Validate and store a registration its contributor authorized
+    /// 
+    /// Shared by the `register_study*` entry points, which each require the
+    /// contributor's authorization for their own call.
+    #[allow(clippy::too_many_arguments)]
+    fn register(
+        env: Env,
+        dataset_hash: BytesN<32>,
+        attestation: Bytes,
+        proof_kind: ProofKind,
         zk_proof: Bytes,
         contributor: Address,
+        metadata: Option<StudyMetadata>,
+        contributors: Option<Vec<(Address, u32)>>,
     ) -> Result<(), Error> {
+        // Reject calls while an upgrade is in progress
+        Self::require_not_paused(&env)?;
+        if let Some(metadata) = &metadata {
+            let uri_len = metadata.metadata_uri.len();
+            if metadata.record_count == 0 || uri_len == 0 || uri_len > MAX_METADATA_URI_LEN {
+                return Err(Error::InvalidMetadata);
+            }
+        }
+        
         // ============================================
         // 1. CHECK UNIQUENESS (Prevent duplicates)
         // ============================================
-        if Self::dataset_exists(&env, &dataset_hash) {
+        // Revoked studies, erased ones included, cannot come back
+        if Self::has_instance(&env, &DataKey::Study(dataset_hash.clone()))
+            || Self::get_revocation(env.clone(), dataset_hash.clone()).is_some()
+        {
             return Err(Error::DuplicateStudy);
         }
+        Self::require_onboarded(&env, &contributor)?;
+        if let Some(contributors) = &contributors {
+            Self::validate_contributors(&contributor, contributors)?;
+            for (co_contributor, _) in contributors.iter() {
+                if co_contributor != contributor {
+                    co_contributor.require_auth();
+                    Self::require_onboarded(&env, &co_contributor)?;
+                }
+            }
+        }
 
         // ============================================
         // 2. VALIDATE ATTESTATION (TEE Proof)
         // ============================================
-        // Verify attestation is present and non-empty
-        // In production, this would verify the cryptographic signature
-        // from the NVIDIA TEE attestation service
-        if attestation.len() == 0 {
-            return Err(Error::InvalidAttestation);
-        }
+        // The CVM must attest to producing exactly this dataset for this
+        // contributor from an allowed image
+        Self::verify_attestation(&env, &attestation, &dataset_hash, &contributor)?;
 
         // ============================================
-        // 3. VALIDATE ZK PROOF
+        // 3. VERIFY ZK PROOF (Groth16 over BN254 or RISC Zero receipt)
         // ============================================
-        // Verify zk_proof is present and non-empty
-        // In production, this would verify the proof using RISC Zero verifier
-        // or a custom SNARK verifier (BN254 curve)
-        if zk_proof.len() == 0 {
-            return Err(Error::InvalidZKProof);
-        }
-
-        // Mock verification: In production, this would:
-        // 1. Deserialize the ZK proof
-        // 2. Verify with RISC Zero verifier or SNARK verifier
-        // 3. Validate public inputs (dataset_hash, attestation)
-        // 4. Ensure proof certifies:
-        //    - Processing in TEE
-        //    - No PII in dataset
-        //    - Valid dataset_hash
-        if !Self::verify_zk_proof_mock(&zk_proof, &dataset_hash, &attestation) {
-            return Err(Error::InvalidZKProof);
-        }
+        // The proof certifies processing in the TEE and the absence of PII
+        // for exactly this dataset_hash, attestation and contributor: its
+        // public signals are the digest of the public inputs of this call,
+        // and a receipt's journal is those public inputs
+        let verification = match proof_kind {
+            ProofKind::Groth16 => {
+                let signals = Self::proof_signals(&env, &dataset_hash, &attestation, &contributor)?;
+                Self::verify_zk_proof(&env, &zk_proof, &signals)?
+            }
+            ProofKind::RiscZero => {
+                let public_inputs =
+                    Self::encode_public_inputs(&env, &dataset_hash, &attestation, &contributor)?;
+                Self::verify_receipt(&env, &zk_proof, &dataset_hash, &public_inputs)?
+            }
+        };
 
         // ============================================
         // 4. GET LEDGER TIMESTAMP
@@ -127,32 +641,331 @@ impl StudyRegistry {
         // Use dataset_hash as the key for direct lookup
         // This ensures uniqueness and efficient access
         let storage = env.storage().instance();
-        storage.set(&dataset_hash, &study_record);
+        // together with the key that verified the proof, so studies stay
+        // attributable after the key is rotated
+        let versioned = match metadata {
+            Some(metadata) => VersionedStudy::V3(study_record, verification, metadata),
+            None => VersionedStudy::V2(study_record, verification),
+        };
+        storage.set(&DataKey::Study(dataset_hash.clone()), &versioned);
+        
+        // Index the study under each of its contributors for dashboards
+        // (persistent, so the index does not grow the instance entry;
+        // entries under the legacy key are merged in on read)
+        let persistent = env.storage().persistent();
+        let mut indexed = Vec::from_array(&env, [contributor.clone()]);
+        if let Some(contributors) = &contributors {
+            storage.set(&DataKey::StudyContributors(dataset_hash.clone()), contributors);
+            for (co_contributor, _) in contributors.iter() {
+                if co_contributor != contributor {
+                    indexed.push_back(co_contributor);
+                }
+            }
+        }
+        for indexed_contributor in indexed.iter() {
+            let contributor_key = DataKey::ContributorStudies(indexed_contributor);
+            let mut contributor_studies: Vec<BytesN<32>> = persistent.get(&contributor_key)
+                .unwrap_or(Vec::new(&env));
+            contributor_studies.push_back(dataset_hash.clone());
+            persistent.set(&contributor_key, &contributor_studies);
+            persistent.extend_ttl(&contributor_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+        
+        // Append the study to the registration-order index for paging
+        let count = Self::study_count(env.clone());
+        let index_key = DataKey::StudyIndex(count / STUDY_INDEX_CHUNK);
+        let mut chunk: Vec<BytesN<32>> = persistent.get(&index_key)
+            .unwrap_or(Vec::new(&env));
+        chunk.push_back(dataset_hash.clone());
+        persistent.set(&index_key, &chunk);
+        persistent.extend_ttl(&index_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        persistent.set(&DataKey::StudyCount, &(count + 1));
+        persistent.extend_ttl(&DataKey::StudyCount, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        // ============================================
+        // 7. PUSH ANALYTICS
+        // ============================================
+        Self::push_stat(&env, symbol_short!("regs"), 1);
+        Self::mint_credit(&env, &contributor);
+        for indexed_contributor in indexed.iter() {
+            Self::report_reputation(&env, "record_study_verified", &indexed_contributor);
+        }
 
         // ============================================
-        // 7. EMIT EVENT
+        // 8. EMIT EVENT
         // ============================================
         // Emit StudyRegistered event for indexing and monitoring
         // Event structure: (event_name, (dataset_hash, contributor, timestamp))
         env.events().publish(
-            (symbol_short!("StudyRegistered"),),
+            (Symbol::new(&env, "StudyRegistered"),),
             (dataset_hash.clone(), contributor.clone(), timestamp),
         );
+        if let Some(contributors) = contributors {
+            env.events().publish(
+                (Symbol::new(&env, "StudyContributors"), dataset_hash.clone()),
+                contributors,
+            );
+        }
 
         Ok(())
     }
 
-    /// Check if a dataset_hash already exists (uniqueness check)
+    /// Register a study through a fee-sponsoring relayer
+    /// 
+    /// The contributor signs an auth entry for this call and an allowlisted
+    /// relayer submits it, paying the fees. The nonce is consumed in the
+    /// RelayerRegistry so the signed call cannot be replayed.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `relayer` - Allowlisted relayer submitting the call (must authorize)
+    /// * `nonce` - Contributor's next RelayerRegistry nonce
+    /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
+    /// * `attestation` - TEE attestation proof from NVIDIA CVM
+    /// * `proof_kind` - Proof system of `zk_proof`
+    /// * `zk_proof` - Zero-knowledge proof of study validity
+    /// * `contributor` - Address of the study contributor (must authorize)
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error)` if the relay is rejected or validation fails
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_study_sponsored(
+        env: Env,
+        relayer: Address,
+        nonce: u64,
+        dataset_hash: BytesN<32>,
+        attestation: Bytes,
+        proof_kind: ProofKind,
+        zk_proof: Bytes,
+        contributor: Address,
+    ) -> Result<(), Error> {
+        relayer.require_auth();
+        contributor.require_auth();
+        Self::use_relay_nonce(&env, &relayer, &contributor, nonce)?;
+        Self::register(env, dataset_hash, attestation, proof_kind, zk_proof, contributor, None, None)
+    }
+
+    /// Build the canonical ZK public inputs of a registration
+    /// 
+    /// Encoded with the biochain-zk-schema crate, which the prover pipeline
+    /// uses too, so the proof and the verifier commit to the same bytes.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
+    /// * `attestation` - TEE attestation proof (hashed with SHA256)
+    /// * `contributor` - Address of the study contributor
+    /// 
+    /// # Returns
+    /// * `Ok(Bytes)` with the encoded public inputs
+    /// * `Err(Error::InvalidZKProof)` if the inputs cannot be encoded
+    pub fn public_inputs(
+        env: Env,
+        dataset_hash: BytesN<32>,
+        attestation: Bytes,
+        contributor: Address,
+    ) -> Result<Bytes, Error> {
+        let encoded = Self::encode_public_inputs(&env, &dataset_hash, &attestation, &contributor)?;
+        Ok(Bytes::from_array(&env, &encoded))
+    }
+
+    /// Nonce the attestation of a registration must embed
+    /// 
+    /// SHA-256(dataset_hash || contributor strkey), passed to the CVM as
+    /// its report data so a report cannot be replayed for another study or
+    /// contributor.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hash` - SHA256 hash of the processed dataset (32 bytes)
+    /// * `contributor` - Address of the study contributor
+    /// 
+    /// # Returns
+    /// * `Ok(BytesN<32>)` with the nonce
+    /// * `Err(Error::AttestationReplay)` if the contributor has no strkey
+    pub fn attestation_nonce(
+        env: Env,
+        dataset_hash: BytesN<32>,
+        contributor: Address,
+    ) -> Result<BytesN<32>, Error> {
+        let strkey = Self::contributor_strkey(&contributor).ok_or(Error::AttestationReplay)?;
+        let mut preimage = Bytes::from(dataset_hash);
+        preimage.extend_from_array(&strkey);
+        Ok(env.crypto().sha256(&preimage).into())
+    }
+
+    /// Check if a dataset_hash exists and can still be used
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
     /// * `dataset_hash` - The dataset hash to check
     /// 
     /// # Returns
-    /// * `true` if the dataset_hash exists, `false` otherwise
-    pub fn dataset_exists(env: &Env, dataset_hash: &BytesN<32>) -> bool {
+    /// * `true` if the dataset_hash exists and is not revoked, `false` otherwise
+    pub fn dataset_exists(env: Env, dataset_hash: BytesN<32>) -> bool {
+        Self::has_instance(&env, &DataKey::Study(dataset_hash.clone()))
+            && Self::get_revocation(env, dataset_hash).is_none()
+    }
+
+    /// Revoke a study, e.g. when its contributor withdraws consent
+    /// 
+    /// Callable by the study's contributor or by the UpgradeCoordinator,
+    /// for instance when a study turns out to be fraudulent. A revoked
+    /// study is tombstoned: it no longer counts as existing, its hash
+    /// cannot be registered again and `get_revoked_studies` reports it.
+    /// With `erase` the study record is deleted as well and the study
+    /// leaves its contributor's index; otherwise the record stays readable
+    /// through `get_study`. The DatasetMarketplace, once set, is notified
+    /// in the same call so licenses for datasets containing the study are
+    /// curtailed; unlike analytics this is not best-effort, a revocation
+    /// never goes through unnoticed.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - The contributor or the UpgradeCoordinator (must
+    ///   authorize)
+    /// * `dataset_hash` - Hash of the study to revoke
+    /// * `reason` - Why the study is revoked
+    /// * `erase` - Whether to delete the study record
+    /// 
+    /// # Returns
+    /// * `Ok(StudyRevocation)` if successful
+    /// * `Err(Error::StudyNotFound)` if the study does not exist
+    /// * `Err(Error::StudyRevoked)` if the study was already revoked
+    /// * `Err(Error::Unauthorized)` if the caller is neither the
+    ///   contributor nor the UpgradeCoordinator
+    pub fn revoke_study(
+        env: Env,
+        caller: Address,
+        dataset_hash: BytesN<32>,
+        reason: RevocationReason,
+        erase: bool,
+    ) -> Result<StudyRevocation, Error> {
+        // ============================================
+        // 1. VALIDATE STUDY
+        // ============================================
+        if Self::get_revocation(env.clone(), dataset_hash.clone()).is_some() {
+            return Err(Error::StudyRevoked);
+        }
+        let study = Self::load_study(&env, &dataset_hash)
+            .ok_or(Error::StudyNotFound)?;
+        let by_admin = caller != study.contributor;
+        if by_admin && Self::get_instance::<Address>(&env, &DataKey::Upgrader) != Some(caller.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        caller.require_auth();
+
+        // ============================================
+        // 2. RECORD REVOCATION
+        // ============================================
+        let revocation = StudyRevocation {
+            revoked_at: env.ledger().timestamp(),
+            erased: erase,
+            reason,
+            by_admin,
+        };
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Revocation(dataset_hash.clone()), &revocation);
+
+        // ============================================
+        // 3. ERASE RECORD
+        // ============================================
+        let contributors = Self::study_contributors(&env, &study);
+        if erase {
+            let study_key = DataKey::Study(dataset_hash.clone());
+            storage.remove(&study_key);
+            storage.remove(&study_key.legacy(&env));
+            storage.remove(&DataKey::StudyContributors(dataset_hash.clone()));
+            
+            let persistent = env.storage().persistent();
+            for (contributor, _) in contributors.iter() {
+                let index_key = DataKey::ContributorStudies(contributor);
+                for key in [index_key.legacy(&env), index_key.into_val(&env)] {
+                    let studies: Option<Vec<BytesN<32>>> = persistent.get(&key);
+                    if let Some(mut studies) = studies {
+                        if let Some(index) = studies.first_index_of(&dataset_hash) {
+                            studies.remove(index);
+                            persistent.set(&key, &studies);
+                        }
+                    }
+                }
+            }
+        }
+
+        // ============================================
+        // 4. NOTIFY MARKETPLACE
+        // ============================================
+        if let Some(marketplace) = Self::get_instance::<Address>(&env, &DataKey::Marketplace) {
+            let _flagged: u32 = env.invoke_contract(
+                &marketplace,
+                &Symbol::new(&env, "on_study_revoked"),
+                (Bytes::from(dataset_hash.clone()), erase).into_val(&env),
+            );
+        }
+
+        // A withdrawal of consent is not held against the contributors
+        if reason != RevocationReason::ConsentWithdrawn {
+            for (contributor, _) in contributors.iter() {
+                Self::report_reputation(&env, "record_revocation", &contributor);
+            }
+        }
+
+        // ============================================
+        // 5. EMIT EVENT
+        // ============================================
+        if by_admin {
+            Self::audit(&env, symbol_short!("revoke"), Some(study.contributor.clone()), Some(dataset_hash.clone()));
+        }
+        env.events().publish(
+            (Symbol::new(&env, "StudyRevoked"),),
+            (dataset_hash, study.contributor, revocation.revoked_at, erase, reason),
+        );
+
+        Ok(revocation)
+    }
+
+    /// Get the revocation of a study, if it was revoked
+    /// 
+    /// Revocations recorded before reasons were are reported with
+    /// `RevocationReason::Unspecified` by the contributor.
+    pub fn get_revocation(env: Env, dataset_hash: BytesN<32>) -> Option<StudyRevocation> {
+        let raw: Val = env.storage()
+            .instance()
+            .get(&DataKey::Revocation(dataset_hash))?;
+        // Decoding a struct from a map with other fields traps, so pick the
+        // layout by its fields first
+        let fields = Map::<Symbol, Val>::try_from_val(&env, &raw).ok()?;
+        if fields.contains_key(symbol_short!("reason")) {
+            return StudyRevocation::try_from_val(&env, &raw).ok();
+        }
+        LegacyRevocation::try_from_val(&env, &raw).ok().map(|legacy| StudyRevocation {
+            revoked_at: legacy.revoked_at,
+            erased: legacy.erased,
+            reason: RevocationReason::Unspecified,
+            by_admin: false,
+        })
+    }
+
+    /// Get the revoked studies among the given ones
+    /// 
+    /// Lets the DatasetMarketplace check a whole dataset in one call.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hashes` - Studies to check
+    /// 
+    /// # Returns
+    /// * The revoked studies, erased ones included, in the given order
+    pub fn get_revoked_studies(env: Env, dataset_hashes: Vec<BytesN<32>>) -> Vec<BytesN<32>> {
         let storage = env.storage().instance();
-        storage.has(&dataset_hash)
+        let mut revoked = Vec::new(&env);
+        for dataset_hash in dataset_hashes.iter() {
+            if storage.has(&DataKey::Revocation(dataset_hash.clone())) {
+                revoked.push_back(dataset_hash);
+            }
+        }
+        revoked
     }
 
     /// Get a study record by dataset_hash
@@ -168,36 +981,1364 @@ impl StudyRegistry {
         env: Env,
         dataset_hash: BytesN<32>,
     ) -> Result<StudyRecord, Error> {
-        let storage = env.storage().instance();
-        storage.get(&dataset_hash)
+        Self::load_study(&env, &dataset_hash)
             .ok_or(Error::StudyNotFound)
     }
 
-    /// Verify ZK proof (mock implementation)
+    /// Get the contributors of a study with their shares
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hash` - The dataset hash to lookup
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<(Address, u32)>)` with each contributor's share in basis
+    ///   points, summing to BPS_DENOMINATOR (the whole of it for the
+    ///   submitting contributor of single-contributor studies)
+    /// * `Err(Error::StudyNotFound)` if not found
+    pub fn get_study_contributors(
+        env: Env,
+        dataset_hash: BytesN<32>,
+    ) -> Result<Vec<(Address, u32)>, Error> {
+        let study = Self::load_study(&env, &dataset_hash)
+            .ok_or(Error::StudyNotFound)?;
+        Ok(Self::study_contributors(&env, &study))
+    }
+
+    /// Get the studies registered by a contributor
     /// 
-    /// In production, this would:
-    /// 1. Deserialize the ZK proof
-    /// 2. Call RISC Zero verifier or SNARK verifier (BN254)
-    /// 3. Validate public inputs match (dataset_hash, attestation)
-    /// 4. Verify proof structure and cryptographic validity
+    /// Lists studies the address submitted or shares in. Studies registered
+    /// before the contributor index existed are not listed.
     /// 
     /// # Arguments
-    /// * `zk_proof` - The zero-knowledge proof to verify
-    /// * `dataset_hash` - The dataset hash (public input)
-    /// * `attestation` - The TEE attestation (public input)
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
     /// 
     /// # Returns
-    /// * `true` if proof is valid (mock: checks non-empty and structure)
-    /// * `false` otherwise
-    fn verify_zk_proof_mock(
-        zk_proof: &Bytes,
-        dataset_hash: &BytesN<32>,
+    /// * Study hashes in registration order (empty if none)
+    pub fn get_contributor_studies(
+        env: Env,
+        contributor: Address,
+    ) -> Vec<BytesN<32>> {
+        // The index is only appended to under its DataKey, so studies still
+        // listed under the legacy key come first
+        let storage = env.storage().persistent();
+        let key = DataKey::ContributorStudies(contributor);
+        let mut studies: Vec<BytesN<32>> = storage.get(&key.legacy(&env))
+            .unwrap_or(Vec::new(&env));
+        studies.append(&storage.get(&key).unwrap_or(Vec::new(&env)));
+        studies
+    }
+
+    /// Get the number of studies registered since the study index existed
+    /// 
+    /// Erased studies keep their position, so they are still counted.
+    pub fn study_count(env: Env) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::StudyCount)
+            .unwrap_or(0)
+    }
+
+    /// Page through studies in registration order
+    /// 
+    /// Revoked studies are included (check `get_revocation`); erased ones
+    /// are skipped, so a page can hold fewer than `limit` studies.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `start` - Position of the first study (0-based)
+    /// * `limit` - Maximum number of studies (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * Studies in registration order (empty past the end)
+    pub fn list_studies(env: Env, start: u32, limit: u32) -> Vec<StudyRecord> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::study_count(env.clone()));
+        let persistent = env.storage().persistent();
+        
+        let mut studies = Vec::new(&env);
+        for index in start / STUDY_INDEX_CHUNK..end.div_ceil(STUDY_INDEX_CHUNK) {
+            let chunk: Vec<BytesN<32>> = persistent.get(&DataKey::StudyIndex(index))
+                .unwrap_or(Vec::new(&env));
+            let first = index * STUDY_INDEX_CHUNK;
+            for (offset, dataset_hash) in chunk.iter().enumerate() {
+                let position = first + offset as u32;
+                if position < start || position >= end {
+                    continue;
+                }
+                if let Some(study) = Self::load_study(&env, &dataset_hash) {
+                    studies.push_back(study);
+                }
+            }
+        }
+        studies
+    }
+
+    /// Page through the studies registered by a contributor
+    /// 
+    /// Pages over `get_contributor_studies`, so erased studies are not
+    /// listed and revoked ones are.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `contributor` - Address of the contributor
+    /// * `start` - Position of the first study (0-based)
+    /// * `limit` - Maximum number of studies (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * The contributor's studies in registration order (empty past the
+    ///   end)
+    pub fn get_studies_by_contributor(
+        env: Env,
+        contributor: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<StudyRecord> {
+        let study_ids = Self::get_contributor_studies(env.clone(), contributor);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(study_ids.len());
+        
+        let mut studies = Vec::new(&env);
+        for position in start..end {
+            let study = study_ids.get(position)
+                .and_then(|dataset_hash| Self::load_study(&env, &dataset_hash));
+            if let Some(study) = study {
+                studies.push_back(study);
+            }
+        }
+        studies
+    }
+
+    /// Extend the TTL of study records
+    /// 
+    /// Permissionless so the TtlKeeper (or anyone) can keep records alive.
+    /// Studies live in instance storage, so extending the instance covers
+    /// every record. The current verifying key is extended too, so
+    /// registrations keep working through quiet periods.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `study_ids` - Study hashes to keep alive
+    /// 
+    /// # Returns
+    /// * Number of the given studies that exist
+    pub fn extend_study_ttl(
+        env: Env,
+        study_ids: Vec<BytesN<32>>,
+    ) -> u32 {
+        let storage = env.storage().instance();
+        let found = study_ids.iter()
+            .filter(|id| Self::has_instance(&env, &DataKey::Study(id.clone())))
+            .count() as u32;
+        
+        if found > 0 {
+            storage.extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+            let persistent = env.storage().persistent();
+            if let Some(current) = Self::current_key(&env) {
+                let key = DataKey::VerifyingKey(current.circuit_id, current.key_version);
+                persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            }
+        }
+        found
+    }
+
+    /// Get the storage schema version
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Schema version of the stored data (1 if no marker was ever written)
+    pub fn schema_version(env: Env) -> u32 {
+        Self::get_instance(&env, &DataKey::Schema).unwrap_or(1)
+    }
+
+    /// Rewrite legacy study records in the current layout
+    /// 
+    /// Records are wrapped in `VersionedStudy` and moved from their raw
+    /// hash key to `DataKey::Study`. Storage cannot be enumerated on-chain,
+    /// so records are migrated in batches by hash. Records are rewritten
+    /// with identical content, so anyone can call this.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_hashes` - Studies to migrate
+    /// 
+    /// # Returns
+    /// * Number of records that were rewritten
+    pub fn migrate_studies(
+        env: Env,
+        dataset_hashes: Vec<BytesN<32>>,
+    ) -> u32 {
+        let storage = env.storage().instance();
+        let mut migrated = 0u32;
+        
+        for dataset_hash in dataset_hashes.iter() {
+            let storage_key = DataKey::Study(dataset_hash);
+            let legacy_key = storage_key.legacy(&env);
+            let record = storage.get::<_, Val>(&legacy_key)
+                .and_then(|raw| Self::decode_study(&env, &raw));
+            if let Some(record) = record {
+                storage.set(&storage_key, &VersionedStudy::V1(record));
+                storage.remove(&legacy_key);
+                migrated += 1;
+            }
+        }
+        
+        migrated
+    }
+
+    /// Set the PlatformStats contract address
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `stats` - Address of the PlatformStats contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_stats_contract(
+        env: Env,
+        stats: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Stats, &stats);
+        Self::audit(&env, symbol_short!("config"), Some(stats), None);
+        Ok(())
+    }
+
+    /// Set the UpgradeCoordinator contract address
+    /// 
+    /// Requires the admin's authorization. The first call sets the
+    /// coordinator; replacing it also requires the current coordinator's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `coordinator` - Address of the UpgradeCoordinator contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_upgrade_coordinator(
+        env: Env,
+        coordinator: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if let Some(current) = Self::get_instance::<Address>(&env, &DataKey::Upgrader) {
+            current.require_auth();
+        }
+        storage.set(&DataKey::Upgrader, &coordinator);
+        Self::audit(&env, symbol_short!("upgrader"), Some(coordinator), None);
+        Ok(())
+    }
+
+    /// Pause registrations while an upgrade is applied
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn pause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        Self::audit(&env, symbol_short!("pause"), None, None);
+        Ok(())
+    }
+
+    /// Resume registrations after an upgrade
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        env.storage().instance().set(&DataKey::Paused, &false);
+        Self::audit(&env, symbol_short!("unpause"), None, None);
+        Ok(())
+    }
+
+    /// Check whether the contract is paused
+    /// 
+    /// A pause by the GuardianSet pauses the contract as well.
+    pub fn is_paused(env: Env) -> bool {
+        if Self::get_instance(&env, &DataKey::Paused).unwrap_or(false) {
+            return true;
+        }
+        match Self::get_instance::<Address>(&env, &DataKey::Guardians) {
+            Some(guardians) => matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &guardians,
+                    &Symbol::new(&env, "is_paused"),
+                    ().into_val(&env),
+                ),
+                Ok(Ok(true))
+            ),
+            None => false,
+        }
+    }
+
+    /// Get the configuration health of the contract
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * Health snapshot; `ready` requires the verifying key and no pause
+    pub fn health(env: Env) -> Health {
+        let paused = Self::is_paused(env.clone());
+        let verifying_key_set = Self::has_instance(&env, &DataKey::StudyCircuit)
+            && Self::has_instance(&env, &DataKey::ProofVerifier);
+        let attestation_root_set = Self::has_instance(&env, &DataKey::AttestationRoot);
+        
+        Health {
+            stats_set: Self::has_instance(&env, &DataKey::Stats),
+            upgrader_set: Self::has_instance(&env, &DataKey::Upgrader),
+            audit_log_set: Self::has_instance(&env, &DataKey::AuditLog),
+            relayer_registry_set: Self::has_instance(&env, &DataKey::RelayerRegistry),
+            verifying_key_set,
+            attestation_root_set,
+            paused,
+            schema_version: Self::schema_version(env.clone()),
+            ready: verifying_key_set && attestation_root_set && !paused,
+        }
+    }
+
+    /// Replace the contract WASM
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_wasm_hash` - Hash of the uploaded WASM to switch to
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn upgrade(
+        env: Env,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_upgrader(&env)?;
+        Self::audit(&env, symbol_short!("upgrade"), None, Some(new_wasm_hash.clone()));
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Run storage migrations for the current code
+    /// 
+    /// Called by the UpgradeCoordinator right after `upgrade`.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the interface version after migration
+    /// * `Err(Error::Unauthorized)` if no coordinator is set
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        Self::require_upgrader(&env)?;
+        
+        if Self::schema_version(env.clone()) < 2 {
+            Self::migrate_v1_to_v2(&env);
+        }
+        if Self::schema_version(env.clone()) < 3 {
+            Self::migrate_v2_to_v3(&env);
+        }
+        
+        Self::audit(&env, symbol_short!("migrate"), None, None);
+        Ok(INTERFACE_VERSION)
+    }
+
+    /// Get the crate version of the deployed code
+    pub fn version(env: Env) -> String {
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Get the interface version implemented by the current code
+    pub fn interface_version(_env: Env) -> u32 {
+        INTERFACE_VERSION
+    }
+
+    /// Migrate storage from schema 1 to schema 2
+    /// 
+    /// Study records cannot be enumerated, so they
+    /// keep decoding through `load_study` until rewritten by
+    /// `migrate_studies`.
+    fn migrate_v1_to_v2(env: &Env) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Schema, &2u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (1u32, 2u32),
+        );
+    }
+
+    /// Migrate storage from schema 2 to schema 3
+    /// 
+    /// Configuration entries are moved to their `DataKey`. Study records
+    /// cannot be enumerated, so they stay readable under their raw hash
+    /// until rewritten by `migrate_studies`.
+    fn migrate_v2_to_v3(env: &Env) {
+        let storage = env.storage().instance();
+        for key in CONFIG_KEYS.iter() {
+            let legacy_key = key.legacy(env);
+            if let Some(value) = storage.get::<_, Val>(&legacy_key) {
+                if !storage.has(key) {
+                    storage.set(key, &value);
+                }
+                storage.remove(&legacy_key);
+            }
+        }
+        storage.set(&DataKey::Schema, &3u32);
+        
+        env.events().publish(
+            (symbol_short!("Migrated"),),
+            (2u32, 3u32),
+        );
+    }
+
+    /// Load a study record written by any schema version
+    fn load_study(
+        env: &Env,
+        dataset_hash: &BytesN<32>,
+    ) -> Option<StudyRecord> {
+        let raw: Val = Self::get_instance(env, &DataKey::Study(dataset_hash.clone()))?;
+        Self::decode_study(env, &raw)
+    }
+
+    /// Contributors of a loaded study with their shares
+    fn study_contributors(env: &Env, study: &StudyRecord) -> Vec<(Address, u32)> {
+        env.storage()
+            .instance()
+            .get(&DataKey::StudyContributors(study.dataset_hash.clone()))
+            .unwrap_or(Vec::from_array(env, [(study.contributor.clone(), BPS_DENOMINATOR)]))
+    }
+
+    /// Check the contributor shares of a registration
+    fn validate_contributors(
+        contributor: &Address,
+        contributors: &Vec<(Address, u32)>,
+    ) -> Result<(), Error> {
+        if contributors.is_empty() || contributors.len() > MAX_STUDY_CONTRIBUTORS {
+            return Err(Error::InvalidContributors);
+        }
+        let mut total = 0u32;
+        let mut includes_contributor = false;
+        for (index, (address, share)) in contributors.iter().enumerate() {
+            if share == 0 || contributors.iter().skip(index + 1).any(|(other, _)| other == address) {
+                return Err(Error::InvalidContributors);
+            }
+            includes_contributor |= address == *contributor;
+            total = total.saturating_add(share);
+        }
+        if !includes_contributor || total != BPS_DENOMINATOR {
+            return Err(Error::InvalidContributors);
+        }
+        Ok(())
+    }
+
+    /// Decode a stored study record in any record layout
+    fn decode_study(env: &Env, raw: &Val) -> Option<StudyRecord> {
+        match VersionedStudy::try_from_val(env, raw) {
+            Ok(VersionedStudy::V1(record))
+            | Ok(VersionedStudy::V2(record, _))
+            | Ok(VersionedStudy::V3(record, ..)) => Some(record),
+            // Schema 1 stored the record unwrapped
+            Err(_) => StudyRecord::try_from_val(env, raw).ok(),
+        }
+    }
+
+    /// Read an instance entry, falling back to its pre-schema-3 key
+    fn get_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let storage = env.storage().instance();
+        storage.get(key).or_else(|| storage.get(&key.legacy(env)))
+    }
+
+    /// Check an instance entry under its `DataKey` or pre-schema-3 key
+    fn has_instance(env: &Env, key: &DataKey) -> bool {
+        let storage = env.storage().instance();
+        storage.has(key) || storage.has(&key.legacy(env))
+    }
+
+    /// Set the AuditLog contract address
+    /// 
+    /// Requires the admin's authorization, so the audit trail cannot be
+    /// redirected by anyone else.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `audit_log` - Address of the AuditLog contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_audit_log(
+        env: Env,
+        audit_log: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::AuditLog, &audit_log);
+        Self::audit(&env, symbol_short!("audit"), Some(audit_log), None);
+        Ok(())
+    }
+
+    /// Set the RelayerRegistry contract address
+    /// 
+    /// Requires the admin's authorization, as the registry decides who may
+    /// submit calls for contributors.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `relayer_registry` - Address of the RelayerRegistry contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_relayer_registry(
+        env: Env,
+        relayer_registry: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::RelayerRegistry, &relayer_registry);
+        Self::audit(&env, symbol_short!("config"), Some(relayer_registry), None);
+        Ok(())
+    }
+
+    /// Set the ContributionCredit contract address
+    /// 
+    /// Once set, every verified study mints credit to its contributor. This
+    /// contract must be added as a minter in ContributionCredit. Requires
+    /// the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `credit_token` - Address of the ContributionCredit contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_credit_token(
+        env: Env,
+        credit_token: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::CreditToken, &credit_token);
+        Self::audit(&env, symbol_short!("config"), Some(credit_token), None);
+        Ok(())
+    }
+
+    /// Set the ContributorReputation contract address
+    /// 
+    /// Once set, every verified study counts towards the reputation of each
+    /// of its contributors and every revocation, other than for withdrawn
+    /// consent, against it. This contract must be added as a reporter in
+    /// ContributorReputation. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `reputation` - Address of the ContributorReputation contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_reputation_contract(
+        env: Env,
+        reputation: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Reputation, &reputation);
+        Self::audit(&env, symbol_short!("config"), Some(reputation), None);
+        Ok(())
+    }
+
+    /// Set the GuardianSet contract address
+    /// 
+    /// Any guardian of the set can then pause registrations; resuming takes
+    /// the set's M-of-N approval. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `guardians` - Address of the GuardianSet contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_guardian_set(
+        env: Env,
+        guardians: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Guardians, &guardians);
+        Self::audit(&env, symbol_short!("config"), Some(guardians), None);
+        Ok(())
+    }
+
+    /// Set the DatasetMarketplace contract address
+    /// 
+    /// Once set, every revocation is reported to the marketplace through
+    /// `on_study_revoked`. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if no admin is set
+    pub fn set_marketplace(
+        env: Env,
+        marketplace: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Marketplace, &marketplace);
+        Self::audit(&env, symbol_short!("config"), Some(marketplace), None);
+        Ok(())
+    }
+
+    /// Set the public key attestation reports are signed with
+    /// 
+    /// The key of the NVIDIA attestation root certificate (or of the
+    /// service signing on its behalf). Only the encoding is checked: a key
+    /// off the curve makes every registration trap in the host. Requires
+    /// the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `public_key` - SEC-1 uncompressed P-256 public key
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::InvalidAttestationRoot)` if the key is not SEC-1
+    ///   uncompressed
+    pub fn set_attestation_root(
+        env: Env,
+        public_key: BytesN<65>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if public_key.to_array()[0] != attestation::UNCOMPRESSED_KEY_TAG {
+            return Err(Error::InvalidAttestationRoot);
+        }
+        env.storage().instance().set(&DataKey::AttestationRoot, &public_key);
+        let fingerprint = env.crypto().sha256(&public_key.into()).into();
+        Self::audit(&env, symbol_short!("config"), None, Some(fingerprint));
+        Ok(())
+    }
+
+    /// Get the attestation root public key, if set
+    pub fn get_attestation_root(env: Env) -> Option<BytesN<65>> {
+        Self::get_instance(&env, &DataKey::AttestationRoot)
+    }
+
+    /// Allow or disallow a CVM image measurement
+    /// 
+    /// Only attestations from allowed measurements are accepted, so
+    /// disallowing a measurement retires its image for new registrations.
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `measurement` - Measurement of the CVM image
+    /// * `allowed` - Whether attestations from it are accepted
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_measurement_allowed(
+        env: Env,
+        measurement: BytesN<32>,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        let key = DataKey::Measurement(measurement.clone());
+        if allowed {
+            storage.set(&key, &true);
+        } else {
+            storage.remove(&key);
+        }
+        Self::audit(&env, symbol_short!("config"), None, Some(measurement.clone()));
+        env.events().publish(
+            (Symbol::new(&env, "MeasurementSet"), measurement),
+            allowed,
+        );
+        Ok(())
+    }
+
+    /// Check if attestations from a CVM image measurement are accepted
+    pub fn is_measurement_allowed(env: Env, measurement: BytesN<32>) -> bool {
+        Self::has_instance(&env, &DataKey::Measurement(measurement))
+    }
+
+    /// Set the ContributorOnboarding contract address
+    /// 
+    /// Once set, only onboarded contributors can register studies. Requires
+    /// the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `onboarding` - Address of the ContributorOnboarding contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_onboarding(
+        env: Env,
+        onboarding: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Onboarding, &onboarding);
+        Self::audit(&env, symbol_short!("config"), Some(onboarding), None);
+        Ok(())
+    }
+
+    /// Add a new version of a circuit's Groth16 verifying key
+    /// 
+    /// Keys are never overwritten: each call stores the next version, and
+    /// studies record the version that verified them. Registrations use the
+    /// latest version of the selected study circuit (see `set_study_circuit`),
+    /// so rotating a key takes effect immediately. Every point is checked
+    /// to be on its curve here; the G2 subgroup checks cost more than a
+    /// transaction allows in WASM and are left to the proof verifier, like
+    /// the pairing. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `circuit_id` - Circuit the key belongs to
+    /// * `vk` - Encoded verifying key (VERIFYING_KEY_LEN bytes, see
+    ///   biochain-groth16)
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the new key version (starting at 1)
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::InvalidVerifyingKey)` if the key does not decode
+    pub fn set_verifying_key(
+        env: Env,
+        circuit_id: Symbol,
+        vk: Bytes,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+        if vk.len() as usize != VERIFYING_KEY_LEN {
+            return Err(Error::InvalidVerifyingKey);
+        }
+        let mut encoded = [0u8; VERIFYING_KEY_LEN];
+        vk.copy_into_slice(&mut encoded);
+        StudyVerifyingKey::from_trusted_bytes(&encoded).map_err(|_| Error::InvalidVerifyingKey)?;
+        
+        let version = Self::get_verifying_key_version(env.clone(), circuit_id.clone()) + 1;
+        let key = DataKey::VerifyingKey(circuit_id.clone(), version);
+        let persistent = env.storage().persistent();
+        persistent.set(&key, &vk);
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        env.storage().instance().set(&DataKey::KeyVersion(circuit_id.clone()), &version);
+        
+        Self::audit(&env, symbol_short!("config"), None, Some(env.crypto().sha256(&vk).into()));
+        env.events().publish(
+            (Symbol::new(&env, "VerifyingKeySet"), circuit_id),
+            version,
+        );
+        Ok(version)
+    }
+
+    /// Get a version of a circuit's verifying key
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `circuit_id` - Circuit the key belongs to
+    /// * `version` - Key version, or `None` for the latest
+    /// 
+    /// # Returns
+    /// * The encoded key, or `None` if there is no such version
+    pub fn get_verifying_key(
+        env: Env,
+        circuit_id: Symbol,
+        version: Option<u32>,
+    ) -> Option<Bytes> {
+        let version = version
+            .unwrap_or_else(|| Self::get_verifying_key_version(env.clone(), circuit_id.clone()));
+        env.storage().persistent().get(&DataKey::VerifyingKey(circuit_id, version))
+    }
+
+    /// Get the latest key version of a circuit (0 if it has no key)
+    pub fn get_verifying_key_version(env: Env, circuit_id: Symbol) -> u32 {
+        Self::get_instance(&env, &DataKey::KeyVersion(circuit_id)).unwrap_or(0)
+    }
+
+    /// Select the circuit registrations are verified against
+    /// 
+    /// Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `circuit_id` - Circuit to verify registrations with
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::VerifyingKeyNotSet)` if the circuit has no key
+    pub fn set_study_circuit(
+        env: Env,
+        circuit_id: Symbol,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if Self::get_verifying_key_version(env.clone(), circuit_id.clone()) == 0 {
+            return Err(Error::VerifyingKeyNotSet);
+        }
+        env.storage().instance().set(&DataKey::StudyCircuit, &circuit_id);
+        Self::audit(&env, symbol_short!("config"), None, None);
+        Ok(())
+    }
+
+    /// Get the circuit registrations are verified against, if selected
+    pub fn get_study_circuit(env: Env) -> Option<Symbol> {
+        Self::get_instance(&env, &DataKey::StudyCircuit)
+    }
+
+    /// Add a new version of the guest image ID expected for a proof kind
+    /// 
+    /// Like verifying keys, image IDs are never overwritten and receipts
+    /// are checked against the latest version. Only RISC Zero receipts
+    /// commit to an image; SNARK circuits are configured with
+    /// `set_verifying_key`. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `proof_kind` - Proof kind the image ID applies to
+    /// * `image_id` - Image ID of the guest program
+    /// 
+    /// # Returns
+    /// * `Ok(u32)` with the new image ID version (starting at 1)
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    /// * `Err(Error::UnsupportedProofKind)` for proof kinds without images
+    pub fn set_image_id(
+        env: Env,
+        proof_kind: ProofKind,
+        image_id: BytesN<32>,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+        if proof_kind != ProofKind::RiscZero {
+            return Err(Error::UnsupportedProofKind);
+        }
+        
+        let version = Self::get_image_id_version(env.clone(), proof_kind) + 1;
+        let key = DataKey::ImageId(proof_kind, version);
+        let persistent = env.storage().persistent();
+        persistent.set(&key, &image_id);
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        env.storage().instance().set(&DataKey::ImageIdVersion(proof_kind), &version);
+        
+        Self::audit(&env, symbol_short!("config"), None, Some(image_id.clone()));
+        env.events().publish(
+            (Symbol::new(&env, "ImageIdSet"), proof_kind),
+            (version, image_id),
+        );
+        Ok(version)
+    }
+
+    /// Get a version of the image ID expected for a proof kind
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `proof_kind` - Proof kind the image ID applies to
+    /// * `version` - Image ID version, or `None` for the latest
+    /// 
+    /// # Returns
+    /// * The image ID, or `None` if there is no such version
+    pub fn get_image_id(
+        env: Env,
+        proof_kind: ProofKind,
+        version: Option<u32>,
+    ) -> Option<BytesN<32>> {
+        let version = version
+            .unwrap_or_else(|| Self::get_image_id_version(env.clone(), proof_kind));
+        env.storage().persistent().get(&DataKey::ImageId(proof_kind, version))
+    }
+
+    /// Get the latest image ID version of a proof kind (0 if it has none)
+    pub fn get_image_id_version(env: Env, proof_kind: ProofKind) -> u32 {
+        Self::get_instance(&env, &DataKey::ImageIdVersion(proof_kind)).unwrap_or(0)
+    }
+
+    /// Set the contract that verifies RISC Zero receipt seals
+    /// 
+    /// The verifier exposes `verify(seal, image_id, journal_digest)` and
+    /// fails unless the seal proves an execution of the image that
+    /// committed a journal with that SHA-256 digest. Requires the admin's
+    /// authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `verifier` - Address of the receipt verifier contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_receipt_verifier(
+        env: Env,
+        verifier: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::ReceiptVerifier, &verifier);
+        Self::audit(&env, symbol_short!("config"), Some(verifier), None);
+        Ok(())
+    }
+
+    /// Get the RISC Zero receipt verifier contract, if set
+    pub fn get_receipt_verifier(env: Env) -> Option<Address> {
+        Self::get_instance(&env, &DataKey::ReceiptVerifier)
+    }
+
+    /// Set the contract that verifies Groth16 proofs
+    /// 
+    /// The verifier exposes `verify(vk, proof, signals)` and fails unless
+    /// the proof verifies against the encoded key for those public signals,
+    /// e.g. the ProofVerifier contract, which accepts proofs its off-chain
+    /// verification service approved. Requires the admin's authorization.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `verifier` - Address of the proof verifier contract
+    /// 
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::Unauthorized)` if the contract is not initialized
+    pub fn set_proof_verifier(
+        env: Env,
+        verifier: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::ProofVerifier, &verifier);
+        Self::audit(&env, symbol_short!("config"), Some(verifier), None);
+        Ok(())
+    }
+
+    /// Get the Groth16 proof verifier contract, if set
+    pub fn get_proof_verifier(env: Env) -> Option<Address> {
+        Self::get_instance(&env, &DataKey::ProofVerifier)
+    }
+
+    /// Get the circuit and key version a study was verified with
+    /// 
+    /// # Returns
+    /// * The verification, or `None` for unknown studies and studies
+    ///   registered before keys were versioned
+    pub fn get_study_verification(
+        env: Env,
+        dataset_hash: BytesN<32>,
+    ) -> Option<StudyVerification> {
+        let raw: Val = Self::get_instance(&env, &DataKey::Study(dataset_hash))?;
+        match VersionedStudy::try_from_val(&env, &raw) {
+            Ok(VersionedStudy::V2(_, verification))
+            | Ok(VersionedStudy::V3(_, verification, _)) => Some(verification),
+            _ => None,
+        }
+    }
+
+    /// Get the metadata a study was registered with
+    /// 
+    /// # Returns
+    /// * The metadata, or `None` for unknown studies and studies registered
+    ///   without metadata
+    pub fn get_study_metadata(
+        env: Env,
+        dataset_hash: BytesN<32>,
+    ) -> Option<StudyMetadata> {
+        let raw: Val = Self::get_instance(&env, &DataKey::Study(dataset_hash))?;
+        match VersionedStudy::try_from_val(&env, &raw) {
+            Ok(VersionedStudy::V3(_, _, metadata)) => Some(metadata),
+            _ => None,
+        }
+    }
+
+    /// Verify a CVM attestation report for a dataset
+    /// 
+    /// Fails closed: without a root key nothing can be registered. A
+    /// signature that is not canonical is rejected, but a well-formed one
+    /// that does not verify traps in the host and aborts the call with a
+    /// host error rather than an `Error`: `secp256r1_verify` has no
+    /// fallible variant.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `attestation` - Encoded report (see the `attestation` module)
+    /// * `dataset_hash` - Dataset the report must attest to
+    /// * `contributor` - Contributor the report must attest to
+    /// 
+    /// # Returns
+    /// * `Ok(())` if the report is valid
+    /// * `Err(Error::InvalidAttestation)` if it is malformed or its
+    ///   signature is not canonical
+    /// * `Err(Error::AttestationReplay)` if it embeds the nonce of another
+    ///   registration
+    /// * `Err(Error::AttestationRootNotSet)` if no root key is set
+    /// * `Err(Error::MeasurementNotAllowed)` if the measurement is not
+    ///   allowed
+    fn verify_attestation(
+        env: &Env,
+        attestation: &Bytes,
+        dataset_hash: &BytesN<32>,
+        contributor: &Address,
+    ) -> Result<(), Error> {
+        if attestation.len() as usize != attestation::ENCODED_LEN {
+            return Err(Error::InvalidAttestation);
+        }
+        let mut encoded = [0u8; attestation::ENCODED_LEN];
+        attestation.copy_into_slice(&mut encoded);
+        let report = attestation::Report::decode(&encoded)
+            .filter(attestation::Report::has_canonical_signature)
+            .ok_or(Error::InvalidAttestation)?;
+        let nonce = Self::attestation_nonce(env.clone(), dataset_hash.clone(), contributor.clone())?;
+        if report.report_data != nonce.to_array() {
+            return Err(Error::AttestationReplay);
+        }
+        
+        let root: BytesN<65> = Self::get_instance(env, &DataKey::AttestationRoot)
+            .ok_or(Error::AttestationRootNotSet)?;
+        if !Self::is_measurement_allowed(env.clone(), BytesN::from_array(env, &report.measurement)) {
+            return Err(Error::MeasurementNotAllowed);
+        }
+        
+        let digest = env.crypto().sha256(&Bytes::from_array(env, &report.body()));
+        env.crypto().secp256r1_verify(&root, &digest, &BytesN::from_array(env, &report.signature));
+        Ok(())
+    }
+
+    /// Reject contributors without an identity binding
+    /// 
+    /// Without an onboarding contract configured every contributor is
+    /// accepted; a failing lookup counts as not onboarded.
+    fn require_onboarded(env: &Env, contributor: &Address) -> Result<(), Error> {
+        if let Some(onboarding) = Self::get_instance::<Address>(env, &DataKey::Onboarding) {
+            let onboarded = matches!(
+                env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                    &onboarding,
+                    &Symbol::new(env, "is_onboarded"),
+                    (contributor.clone(),).into_val(env),
+                ),
+                Ok(Ok(true))
+            );
+            if !onboarded {
+                return Err(Error::NotOnboarded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mint contribution credit for a verified study
+    /// 
+    /// Best-effort like analytics: if no credit token is configured or the
+    /// call fails, the registration still goes through.
+    fn mint_credit(env: &Env, contributor: &Address) {
+        if let Some(credit_token) = Self::get_instance::<Address>(env, &DataKey::CreditToken) {
+            let _ = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+                &credit_token,
+                &Symbol::new(env, "record_study_verified"),
+                (env.current_contract_address(), contributor.clone()).into_val(env),
+            );
+        }
+    }
+
+    /// Report a reputation event for a contributor to ContributorReputation
+    /// 
+    /// Best-effort like credit: if no reputation contract is configured or
+    /// the call fails, the registration or revocation still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `method` - ContributorReputation method ("record_study_verified",
+    ///   "record_revocation")
+    /// * `contributor` - Contributor the event applies to
+    fn report_reputation(env: &Env, method: &str, contributor: &Address) {
+        if let Some(reputation) = Self::get_instance::<Address>(env, &DataKey::Reputation) {
+            let _ = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+                &reputation,
+                &Symbol::new(env, method),
+                (env.current_contract_address(), contributor.clone()).into_val(env),
+            );
+        }
+    }
+
+    /// Consume the signer's nonce for a sponsored call in the RelayerRegistry
+    /// 
+    /// Unlike analytics this is not best-effort: without a configured
+    /// registry no relayer is allowed.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `relayer` - Relayer submitting the call
+    /// * `signer` - Contributor the call acts for
+    /// * `nonce` - Nonce carried by the signed call
+    fn use_relay_nonce(
+        env: &Env,
+        relayer: &Address,
+        signer: &Address,
+        nonce: u64,
+    ) -> Result<(), Error> {
+        let relayer_registry: Address = Self::get_instance(env, &DataKey::RelayerRegistry)
+            .ok_or(Error::RelayerNotAllowed)?;
+        
+        match env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &relayer_registry,
+            &Symbol::new(env, "use_nonce"),
+            (relayer.clone(), signer.clone(), nonce).into_val(env),
+        ) {
+            Ok(Ok(())) => Ok(()),
+            // RelayerRegistry Error::InvalidNonce
+            Err(Ok(error)) if error == soroban_sdk::Error::from_contract_error(4) => {
+                Err(Error::InvalidNonce)
+            }
+            _ => Err(Error::RelayerNotAllowed),
+        }
+    }
+
+    /// Append a record of a privileged action to the AuditLog contract
+    /// 
+    /// Auditing is best-effort like analytics: if no audit log is configured
+    /// or the call fails, the action still goes through, so a pause during
+    /// an incident can never be blocked by the log.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `action` - AuditLog action symbol ("pause", "upgrade", ...)
+    /// * `subject` - Address the action applied to, if any
+    /// * `data` - Hash the action applied to, if any
+    fn audit(
+        env: &Env,
+        action: Symbol,
+        subject: Option<Address>,
+        data: Option<BytesN<32>>,
+    ) {
+        if let Some(audit_log) = Self::get_instance::<Address>(env, &DataKey::AuditLog) {
+            let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+                &audit_log,
+                &symbol_short!("append"),
+                (
+                    env.current_contract_address(),
+                    action,
+                    subject,
+                    data.map(Bytes::from).unwrap_or(Bytes::new(env)),
+                ).into_val(env),
+            );
+        }
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(env, &DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require the UpgradeCoordinator's authorization
+    fn require_upgrader(env: &Env) -> Result<(), Error> {
+        let coordinator: Address = Self::get_instance(env, &DataKey::Upgrader)
+            .ok_or(Error::Unauthorized)?;
+        coordinator.require_auth();
+        Ok(())
+    }
+
+    /// Reject state-changing calls while paused
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+        Ok(())
+    }
+
+    /// Push an analytics increment to the PlatformStats contract
+    /// 
+    /// Analytics are best-effort: if no stats contract is configured or the
+    /// call fails, the registration still goes through.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `metric` - PlatformStats metric symbol ("regs")
+    /// * `amount` - Increment to push
+    fn push_stat(
+        env: &Env,
+        metric: Symbol,
+        amount: i128,
+    ) {
+        if let Some(stats) = Self::get_instance::<Address>(env, &DataKey::Stats) {
+            let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &stats,
+                &symbol_short!("record"),
+                (env.current_contract_address(), metric, amount).into_val(env),
+            );
+        }
+    }
+
+    /// Encode the canonical ZK public inputs of a registration
+    fn encode_public_inputs(
+        env: &Env,
+        dataset_hash: &BytesN<32>,
+        attestation: &Bytes,
+        contributor: &Address,
+    ) -> Result<[u8; ENCODED_LEN], Error> {
+        let contributor_strkey = Self::contributor_strkey(contributor).ok_or(Error::InvalidZKProof)?;
+        
+        let inputs = PublicInputs::new(
+            dataset_hash.to_array(),
+            contributor_strkey,
+            env.crypto().sha256(attestation).to_array(),
+            POLICY_VERSION,
+        ).map_err(|_| Error::InvalidZKProof)?;
+        
+        Ok(inputs.encode())
+    }
+
+    /// Strkey of an address as ASCII bytes
+    fn contributor_strkey(contributor: &Address) -> Option<[u8; STRKEY_LEN]> {
+        let strkey = contributor.to_string();
+        if strkey.len() as usize != STRKEY_LEN {
+            return None;
+        }
+        let mut contributor_strkey = [0u8; STRKEY_LEN];
+        strkey.copy_into_slice(&mut contributor_strkey);
+        Some(contributor_strkey)
+    }
+
+    /// Public signals a registration's proof must be valid for
+    /// 
+    /// The SHA-256 digest of the encoded public inputs, split as the
+    /// circuit expects (see `biochain_zk_schema::signals`).
+    fn proof_signals(
+        env: &Env,
+        dataset_hash: &BytesN<32>,
         attestation: &Bytes,
-    ) -> bool {
-        // Mock verification: Check basic structure
-        // In production, this would perform full cryptographic verification
-        zk_proof.len() > 0 && 
-        dataset_hash.len() == 32 && 
-        attestation.len() > 0
+        contributor: &Address,
+    ) -> Result<[[u8; 32]; SIGNAL_COUNT], Error> {
+        let encoded = Self::encode_public_inputs(env, dataset_hash, attestation, contributor)?;
+        let digest = env.crypto().sha256(&Bytes::from_array(env, &encoded)).to_array();
+        Ok(biochain_zk_schema::signals(&digest))
+    }
+
+    /// Study circuit and its latest key version, if a circuit is selected
+    fn current_key(env: &Env) -> Option<StudyVerification> {
+        let circuit_id: Symbol = Self::get_instance(env, &DataKey::StudyCircuit)?;
+        let key_version = Self::get_verifying_key_version(env.clone(), circuit_id.clone());
+        Some(StudyVerification { circuit_id, key_version })
+    }
+
+    /// Verify a Groth16 proof against the study circuit's latest key
+    /// 
+    /// The BN254 pairing costs about 38 times a transaction's instruction
+    /// limit in WASM, and the network has no host function for it, so the
+    /// proof verifier contract checks the proof instead. Fails closed:
+    /// without a study circuit or a verifier nothing can be registered.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `zk_proof` - Encoded proof, A || B || C (PROOF_LEN bytes)
+    /// * `signals` - Public signals of the registration
+    /// 
+    /// # Returns
+    /// * `Ok(StudyVerification)` with the key that verified the proof
+    /// * `Err(Error::VerifyingKeyNotSet)` if no study circuit is selected
+    /// * `Err(Error::ProofVerifierNotSet)` if no verifier is set
+    /// * `Err(Error::InvalidZKProof)` if the proof is malformed or invalid
+    fn verify_zk_proof(
+        env: &Env,
+        zk_proof: &Bytes,
+        signals: &[[u8; 32]; SIGNAL_COUNT],
+    ) -> Result<StudyVerification, Error> {
+        let current = Self::current_key(env).ok_or(Error::VerifyingKeyNotSet)?;
+        let key = DataKey::VerifyingKey(current.circuit_id.clone(), current.key_version);
+        let persistent = env.storage().persistent();
+        let verifying_key: Bytes = persistent.get(&key).ok_or(Error::VerifyingKeyNotSet)?;
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        let verifier: Address = Self::get_instance(env, &DataKey::ProofVerifier)
+            .ok_or(Error::ProofVerifierNotSet)?;
+        
+        if zk_proof.len() as usize != PROOF_LEN {
+            return Err(Error::InvalidZKProof);
+        }
+        let mut signal_values = Vec::new(env);
+        for signal in signals {
+            signal_values.push_back(BytesN::from_array(env, signal));
+        }
+        match env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &verifier,
+            &symbol_short!("verify"),
+            (verifying_key, zk_proof.clone(), signal_values).into_val(env),
+        ) {
+            Ok(Ok(())) => Ok(current),
+            _ => Err(Error::InvalidZKProof),
+        }
+    }
+
+    /// Verify a RISC Zero receipt against the latest image ID
+    /// 
+    /// Fails closed: without an image ID or a receipt verifier nothing can
+    /// be registered with a receipt.
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `receipt` - Encoded receipt, journal || seal
+    /// * `dataset_hash` - Dataset the journal must commit to
+    /// * `public_inputs` - Encoded public inputs of the registration
+    /// 
+    /// # Returns
+    /// * `Ok(StudyVerification)` with the image ID version that was proven
+    /// * `Err(Error::ImageIdNotSet)` if no image ID is set
+    /// * `Err(Error::ReceiptVerifierNotSet)` if no verifier is set
+    /// * `Err(Error::InvalidJournal)` if the journal is not for this
+    ///   registration
+    /// * `Err(Error::InvalidZKProof)` if the seal does not verify
+    fn verify_receipt(
+        env: &Env,
+        receipt: &Bytes,
+        dataset_hash: &BytesN<32>,
+        public_inputs: &[u8; ENCODED_LEN],
+    ) -> Result<StudyVerification, Error> {
+        let key_version = Self::get_image_id_version(env.clone(), ProofKind::RiscZero);
+        let key = DataKey::ImageId(ProofKind::RiscZero, key_version);
+        let persistent = env.storage().persistent();
+        let image_id: BytesN<32> = persistent.get(&key).ok_or(Error::ImageIdNotSet)?;
+        persistent.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        let verifier: Address = Self::get_instance(env, &DataKey::ReceiptVerifier)
+            .ok_or(Error::ReceiptVerifierNotSet)?;
+        
+        // The journal must commit to this dataset, and to the rest of the
+        // registration like the Groth16 signals do
+        if receipt.len() as usize <= ENCODED_LEN {
+            return Err(Error::InvalidJournal);
+        }
+        let journal = receipt.slice(..ENCODED_LEN as u32);
+        let mut journal_bytes = [0u8; ENCODED_LEN];
+        journal.copy_into_slice(&mut journal_bytes);
+        let committed = PublicInputs::decode(&journal_bytes).map_err(|_| Error::InvalidJournal)?;
+        if committed.dataset_hash != dataset_hash.to_array() || journal_bytes != *public_inputs {
+            return Err(Error::InvalidJournal);
+        }
+        
+        let seal = receipt.slice(ENCODED_LEN as u32..);
+        let journal_digest: BytesN<32> = env.crypto().sha256(&journal).into();
+        match env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &verifier,
+            &symbol_short!("verify"),
+            (seal, image_id, journal_digest).into_val(env),
+        ) {
+            Ok(Ok(())) => Ok(StudyVerification { circuit_id: RISC_ZERO_CIRCUIT, key_version }),
+            _ => Err(Error::InvalidZKProof),
+        }
     }
 }
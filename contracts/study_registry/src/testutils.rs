@@ -0,0 +1,223 @@
+//! Helpers for tests that register studies
+//!
+//! `register_study` only accepts proofs that verify against the study
+//! circuit's verifying key. These helpers install the biochain-groth16
+//! test setup and forge proofs for it, so tests of dependent contracts can
+//! register studies without the prover pipeline. Never deploy
+//! `verifying_key()`: anyone can forge proofs for it.
+//!
+//! Groth16 proofs are checked by `MockProofVerifier`, which runs the
+//! pairing natively in place of the off-chain verification service.
+//! RISC Zero receipts are checked by `MockReceiptVerifier`, which accepts
+//! the seals built by `receipt()`. Attestations are signed with a fixed
+//! test root key, which must never be installed outside tests either.
+//!
+//! The install helpers initialize the registry with the test
+//! transaction's source account as admin if it has none, and authorize
+//! their own calls, so they work whether or not the test mocks
+//! authorizations.
+
+use biochain_groth16::{testutils as setup, Proof, PROOF_LEN};
+use biochain_zk_schema::SIGNAL_COUNT;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, Address, Bytes, BytesN, Env, String, Symbol, Vec,
+};
+
+use crate::attestation::Report;
+use crate::{ProofKind, StudyRegistry, StudyRegistryClient, StudyVerifyingKey, VERIFYING_KEY_LEN};
+
+/// Circuit the test verifying key is installed under
+pub const CIRCUIT: Symbol = symbol_short!("test");
+
+/// Encoded verifying key of the test setup
+pub fn verifying_key(env: &Env) -> Bytes {
+    let mut encoded = [0u8; VERIFYING_KEY_LEN];
+    setup::verifying_key::<{ SIGNAL_COUNT + 1 }>().write_bytes(&mut encoded);
+    Bytes::from_array(env, &encoded)
+}
+
+/// Source account of every test transaction (the all-zero ed25519 key)
+///
+/// Authorizations by the source account consume no nonce, so admin calls
+/// made during setup leave no auth entries in the storage that later
+/// invocations snapshot.
+pub const SOURCE_ACCOUNT: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+/// Initialize the registry with SOURCE_ACCOUNT as admin unless it has one
+///
+/// # Returns
+/// * The registry's admin
+pub fn ensure_admin(registry: &StudyRegistryClient) -> Address {
+    if let Ok(Ok(admin)) = registry.try_get_admin() {
+        return admin;
+    }
+    let admin = Address::from_string(&String::from_str(&registry.env, SOURCE_ACCOUNT));
+    registry.init(&admin);
+    admin
+}
+
+pub use groth16::MockProofVerifier;
+
+// A module of its own, since contract functions of the same name collide
+// within a module
+mod groth16 {
+    use super::*;
+
+    /// Groth16 verifier that checks proofs with the pairing, natively
+    #[contract]
+    pub struct MockProofVerifier;
+
+    #[contractimpl]
+    impl MockProofVerifier {
+        /// Panic unless the proof verifies against the key for the signals
+        pub fn verify(_env: Env, vk: Bytes, proof: Bytes, signals: Vec<BytesN<32>>) {
+            let mut encoded = [0u8; VERIFYING_KEY_LEN];
+            vk.copy_into_slice(&mut encoded);
+            let key = StudyVerifyingKey::from_bytes(&encoded).expect("valid key");
+            let mut proof_bytes = [0u8; PROOF_LEN];
+            proof.copy_into_slice(&mut proof_bytes);
+            let proof = Proof::from_bytes(&proof_bytes).expect("malformed proof");
+            let signals: [[u8; 32]; SIGNAL_COUNT] =
+                core::array::from_fn(|i| signals.get(i as u32).expect("signal").to_array());
+            assert_eq!(key.verify(&proof, &signals), Ok(true), "invalid proof");
+        }
+    }
+}
+
+/// Install the test verifying key under CIRCUIT, select it for
+/// registrations and deploy a MockProofVerifier to check proofs
+pub fn install_verifying_key(registry: &StudyRegistryClient) {
+    ensure_admin(registry);
+    let registry = registry.mock_all_auths();
+    registry.set_verifying_key(&CIRCUIT, &verifying_key(&registry.env));
+    registry.set_study_circuit(&CIRCUIT);
+    registry.set_proof_verifier(&registry.env.register_contract(None, MockProofVerifier));
+}
+
+/// Proof accepted by `register_study` under `verifying_key()` for these
+/// arguments
+///
+/// Computed without a contract call, so it does not show up in
+/// `env.auths()` or the budget of the next invocation.
+pub fn zk_proof(
+    env: &Env,
+    dataset_hash: &BytesN<32>,
+    attestation: &Bytes,
+    contributor: &Address,
+) -> Bytes {
+    let signals = StudyRegistry::proof_signals(env, dataset_hash, attestation, contributor)
+        .expect("contributor has a strkey");
+    Bytes::from_array(env, &setup::prove(&signals).to_bytes())
+}
+
+/// Public signals of a registration, as the registry passes them to its
+/// proof verifier
+pub fn signals(
+    env: &Env,
+    dataset_hash: &BytesN<32>,
+    attestation: &Bytes,
+    contributor: &Address,
+) -> Vec<BytesN<32>> {
+    let signals = StudyRegistry::proof_signals(env, dataset_hash, attestation, contributor)
+        .expect("contributor has a strkey");
+    let mut values = Vec::new(env);
+    for signal in signals.iter() {
+        values.push_back(BytesN::from_array(env, signal));
+    }
+    values
+}
+
+/// Guest image ID installed by `install_receipt_verifier`
+pub const IMAGE_ID: [u8; 32] = [0x52; 32];
+
+/// Receipt verifier whose seal is SHA-256(image_id || journal_digest)
+#[contract]
+pub struct MockReceiptVerifier;
+
+#[contractimpl]
+impl MockReceiptVerifier {
+    /// Panic unless the seal matches the image and journal digest
+    pub fn verify(env: Env, seal: Bytes, image_id: BytesN<32>, journal: BytesN<32>) {
+        assert_eq!(seal, mock_seal(&env, &image_id, &journal), "invalid seal");
+    }
+}
+
+fn mock_seal(env: &Env, image_id: &BytesN<32>, journal_digest: &BytesN<32>) -> Bytes {
+    let mut preimage = Bytes::from(image_id.clone());
+    preimage.append(&journal_digest.clone().into());
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Deploy a MockReceiptVerifier and install it with IMAGE_ID for RISC
+/// Zero receipts
+pub fn install_receipt_verifier(registry: &StudyRegistryClient) {
+    ensure_admin(registry);
+    let registry = registry.mock_all_auths();
+    let env = &registry.env;
+    registry.set_receipt_verifier(&env.register_contract(None, MockReceiptVerifier));
+    registry.set_image_id(&ProofKind::RiscZero, &BytesN::from_array(env, &IMAGE_ID));
+}
+
+/// Receipt of IMAGE_ID accepted by `register_study` under
+/// `install_receipt_verifier()` for these arguments
+pub fn receipt(
+    env: &Env,
+    dataset_hash: &BytesN<32>,
+    attestation: &Bytes,
+    contributor: &Address,
+) -> Bytes {
+    let journal = Bytes::from_array(
+        env,
+        &StudyRegistry::encode_public_inputs(env, dataset_hash, attestation, contributor)
+            .expect("contributor has a strkey"),
+    );
+    let journal_digest = env.crypto().sha256(&journal).into();
+    let mut receipt = journal;
+    receipt.append(&mock_seal(env, &BytesN::from_array(env, &IMAGE_ID), &journal_digest));
+    receipt
+}
+
+/// CVM measurement allowed by `install_attestation_root`
+pub const MEASUREMENT: [u8; 32] = [0x4d; 32];
+
+fn attestation_key() -> SigningKey {
+    SigningKey::from_bytes(&[0x11; 32].into()).expect("valid scalar")
+}
+
+/// Public key of the test attestation root
+pub fn attestation_root(env: &Env) -> BytesN<65> {
+    let point = attestation_key().verifying_key().to_encoded_point(false);
+    BytesN::from_array(env, point.as_bytes().try_into().expect("uncompressed point"))
+}
+
+/// Install the test attestation root and allow MEASUREMENT
+pub fn install_attestation_root(registry: &StudyRegistryClient) {
+    ensure_admin(registry);
+    let registry = registry.mock_all_auths();
+    let env = &registry.env;
+    registry.set_attestation_root(&attestation_root(env));
+    registry.set_measurement_allowed(&BytesN::from_array(env, &MEASUREMENT), &true);
+}
+
+/// Attestation from MEASUREMENT for a registration, signed by the test
+/// root
+pub fn attestation(env: &Env, dataset_hash: &BytesN<32>, contributor: &Address) -> Bytes {
+    let nonce = StudyRegistry::attestation_nonce(env.clone(), dataset_hash.clone(), contributor.clone())
+        .expect("contributor has a strkey");
+    attestation_from(env, &MEASUREMENT, &nonce.to_array())
+}
+
+/// Attestation from any measurement with any report data, signed by the
+/// test root
+pub fn attestation_from(env: &Env, measurement: &[u8; 32], report_data: &[u8; 32]) -> Bytes {
+    let mut report = Report {
+        measurement: *measurement,
+        report_data: *report_data,
+        signature: [0; 64],
+    };
+    let signature: Signature = attestation_key().sign(&report.body());
+    let signature = signature.normalize_s().unwrap_or(signature);
+    report.signature = signature.to_bytes().into();
+    Bytes::from_array(env, &report.encode())
+}
@@ -1,8 +1,15 @@
 #![cfg(test)]
 
-use super::*;
+use study_registry::{
+    testutils, AnonymizationMethod, DataKey, Error, ProofKind, RevocationReason, StudyMetadata,
+    StudyRecord, StudyRegistry, StudyRegistryClient, StudyRevocation, StudyType,
+    StudyVerification, VersionedStudy,
+    BPS_DENOMINATOR, INTERFACE_VERSION, MAX_METADATA_URI_LEN, MAX_STUDY_CONTRIBUTORS, MAX_PAGE_SIZE, RISC_ZERO_CIRCUIT, SCHEMA_VERSION,
+    VERIFYING_KEY_LEN,
+};
 use soroban_sdk::{
-    Env, Address, Bytes, BytesN, testutils::{Address as AddressTestUtils, Events as EventsTestUtils},
+    contracttype, Env, Address, Bytes, BytesN, IntoVal, String, Vec, symbol_short,
+    testutils::{Address as AddressTestUtils, Events as EventsTestUtils, Ledger, MockAuth, MockAuthInvoke},
 };
 
 /// Helper: Create a test environment
@@ -22,37 +29,49 @@ fn create_dataset_hash(env: &Env, seed: u8) -> BytesN<32> {
     BytesN::from_array(env, &hash_bytes)
 }
 
-/// Helper: Create a test attestation
-fn create_attestation(env: &Env) -> Bytes {
-    Bytes::from_slice(env, b"mock_attestation_proof_from_tee")
+/// Helper: Create a test attestation for a registration
+fn create_attestation(env: &Env, dataset_hash: &BytesN<32>, contributor: &Address) -> Bytes {
+    testutils::attestation(env, dataset_hash, contributor)
 }
 
-/// Helper: Create a test ZK proof
-fn create_zk_proof(env: &Env) -> Bytes {
-    Bytes::from_slice(env, b"mock_zk_proof_bn254_1234567890")
+/// Helper: Create a ZK proof valid for these registration arguments
+fn create_zk_proof(
+    env: &Env,
+    dataset_hash: &BytesN<32>,
+    attestation: &Bytes,
+    contributor: &Address,
+) -> Bytes {
+    testutils::zk_proof(env, dataset_hash, attestation, contributor)
 }
 
-/// Helper: Create StudyRegistry client
-fn create_study_registry_client(env: &Env) -> StudyRegistryClient {
+/// Helper: Create StudyRegistry client with the test verifying key and
+/// attestation root
+fn create_study_registry_client(env: &Env) -> StudyRegistryClient<'_> {
     let contract_id = env.register_contract(None, StudyRegistry);
-    StudyRegistryClient::new(env, &contract_id)
+    let client = StudyRegistryClient::new(env, &contract_id);
+    testutils::install_verifying_key(&client);
+    testutils::install_attestation_root(&client);
+    client
 }
 
 #[test]
 fn test_register_study_success() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
+    env.ledger().with_mut(|l| l.timestamp = 1_700_000_000);
     let contributor = create_address(&env);
     let dataset_hash = create_dataset_hash(&env, 0);
-    let attestation = create_attestation(&env);
-    let zk_proof = create_zk_proof(&env);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
     
     // Act
-    let result = client.register_study(
+    let result = client.try_register_study(
         &dataset_hash,
         &attestation,
+        &ProofKind::Groth16,
         &zk_proof,
         &contributor,
     );
@@ -61,120 +80,117 @@ fn test_register_study_success() {
     assert!(result.is_ok(), "register_study should succeed");
     
     // Verify StudyRecord is stored
-    let study = client.get_study(&dataset_hash);
+    let study = client.try_get_study(&dataset_hash);
     assert!(study.is_ok(), "get_study should succeed");
     
-    let study_record = study.unwrap();
+    let study_record = study.unwrap().unwrap();
     assert_eq!(study_record.dataset_hash, dataset_hash, "dataset_hash should match");
     assert_eq!(study_record.contributor, contributor, "contributor should match");
     assert!(study_record.timestamp > 0, "timestamp should be set");
     
     // Verify StudyRegistered event was emitted
     let events = env.events().all();
-    assert!(events.len() > 0, "Events should be emitted");
+    assert!(!events.is_empty(), "Events should be emitted");
 }
 
 #[test]
 fn test_register_study_duplicate_hash_fails() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
     let contributor1 = create_address(&env);
     let contributor2 = create_address(&env);
     let dataset_hash = create_dataset_hash(&env, 1);
-    let attestation = create_attestation(&env);
-    let zk_proof = create_zk_proof(&env);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor1);
     
     // Act: Register first study
-    let result1 = client.register_study(
+    let result1 = client.try_register_study(
         &dataset_hash,
         &attestation,
-        &zk_proof,
+        &ProofKind::Groth16,
+        &create_zk_proof(&env, &dataset_hash, &attestation, &contributor1),
         &contributor1,
     );
     assert!(result1.is_ok(), "First registration should succeed");
     
     // Act: Try to register duplicate
-    let result2 = client.register_study(
+    let result2 = client.try_register_study(
         &dataset_hash,
         &attestation,
-        &zk_proof,
+        &ProofKind::Groth16,
+        &create_zk_proof(&env, &dataset_hash, &attestation, &contributor2),
         &contributor2,
     );
     
     // Assert: Should fail with DuplicateStudy error
     assert!(result2.is_err(), "Duplicate registration should fail");
-    match result2.unwrap_err() {
-        Error::DuplicateStudy => {},
-        _ => panic!("Expected DuplicateStudy error"),
-    }
+    assert_eq!(result2.unwrap_err(), Ok(Error::DuplicateStudy));
     
     // Verify only one study record exists
-    let study = client.get_study(&dataset_hash);
+    let study = client.try_get_study(&dataset_hash);
     assert!(study.is_ok());
-    assert_eq!(study.unwrap().contributor, contributor1, "Original contributor should be preserved");
+    assert_eq!(study.unwrap().unwrap().contributor, contributor1, "Original contributor should be preserved");
 }
 
 #[test]
 fn test_register_study_invalid_attestation_fails() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
     let contributor = create_address(&env);
     let dataset_hash = create_dataset_hash(&env, 2);
     let empty_attestation = Bytes::new(&env); // Empty attestation
-    let zk_proof = create_zk_proof(&env);
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &empty_attestation, &contributor);
     
     // Act
-    let result = client.register_study(
+    let result = client.try_register_study(
         &dataset_hash,
         &empty_attestation,
+        &ProofKind::Groth16,
         &zk_proof,
         &contributor,
     );
     
     // Assert
     assert!(result.is_err(), "Empty attestation should fail");
-    match result.unwrap_err() {
-        Error::InvalidAttestation => {},
-        _ => panic!("Expected InvalidAttestation error"),
-    }
+    assert_eq!(result.unwrap_err(), Ok(Error::InvalidAttestation));
     
     // Verify study was not stored
-    let study = client.get_study(&dataset_hash);
+    let study = client.try_get_study(&dataset_hash);
     assert!(study.is_err(), "Study should not be stored");
 }
 
 #[test]
 fn test_register_study_invalid_zk_proof_fails() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
     let contributor = create_address(&env);
     let dataset_hash = create_dataset_hash(&env, 3);
-    let attestation = create_attestation(&env);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
     let empty_zk_proof = Bytes::new(&env); // Empty ZK proof
     
     // Act
-    let result = client.register_study(
+    let result = client.try_register_study(
         &dataset_hash,
         &attestation,
+        &ProofKind::Groth16,
         &empty_zk_proof,
         &contributor,
     );
     
     // Assert
     assert!(result.is_err(), "Empty ZK proof should fail");
-    match result.unwrap_err() {
-        Error::InvalidZKProof => {},
-        _ => panic!("Expected InvalidZKProof error"),
-    }
+    assert_eq!(result.unwrap_err(), Ok(Error::InvalidZKProof));
     
     // Verify study was not stored
-    let study = client.get_study(&dataset_hash);
+    let study = client.try_get_study(&dataset_hash);
     assert!(study.is_err(), "Study should not be stored");
 }
 
@@ -187,35 +203,34 @@ fn test_get_nonexistent_study() {
     let nonexistent_hash = create_dataset_hash(&env, 99);
     
     // Act
-    let result = client.get_study(&nonexistent_hash);
+    let result = client.try_get_study(&nonexistent_hash);
     
     // Assert
     assert!(result.is_err(), "Getting nonexistent study should fail");
-    match result.unwrap_err() {
-        Error::StudyNotFound => {},
-        _ => panic!("Expected StudyNotFound error"),
-    }
+    assert_eq!(result.unwrap_err(), Ok(Error::StudyNotFound));
 }
 
 #[test]
 fn test_dataset_exists() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
     let contributor = create_address(&env);
     let dataset_hash = create_dataset_hash(&env, 4);
-    let attestation = create_attestation(&env);
-    let zk_proof = create_zk_proof(&env);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
     
     // Before registration, dataset should not exist
     let exists_before = client.dataset_exists(&dataset_hash);
     assert!(!exists_before, "Dataset should not exist before registration");
     
     // Register study
-    let result = client.register_study(
+    let result = client.try_register_study(
         &dataset_hash,
         &attestation,
+        &ProofKind::Groth16,
         &zk_proof,
         &contributor,
     );
@@ -229,20 +244,21 @@ fn test_dataset_exists() {
 #[test]
 fn test_multiple_studies_different_hashes() {
     let env = create_env();
+    env.mock_all_auths();
     let client = create_study_registry_client(&env);
     
     // Arrange
     let contributor = create_address(&env);
-    let attestation = create_attestation(&env);
-    let zk_proof = create_zk_proof(&env);
     
     // Register multiple studies with different hashes
     for i in 0..5 {
         let dataset_hash = create_dataset_hash(&env, i + 10);
-        let result = client.register_study(
+        let attestation = create_attestation(&env, &dataset_hash, &contributor);
+        let result = client.try_register_study(
             &dataset_hash,
             &attestation,
-            &zk_proof,
+            &ProofKind::Groth16,
+            &create_zk_proof(&env, &dataset_hash, &attestation, &contributor),
             &contributor,
         );
         assert!(result.is_ok(), "Registration {} should succeed", i);
@@ -252,3 +268,1122 @@ fn test_multiple_studies_different_hashes() {
         assert!(exists, "Dataset {} should exist", i);
     }
 }
+
+#[test]
+fn test_migrate_v1_to_v2_with_legacy_records() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: pre-populate schema 1 storage (raw StudyRecord, no marker)
+    let contributor = create_address(&env);
+    let legacy_hash = create_dataset_hash(&env, 20);
+    let legacy_record = StudyRecord {
+        dataset_hash: legacy_hash.clone(),
+        contributor: contributor.clone(),
+        timestamp: 1_700_000_000,
+    };
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&legacy_hash, &legacy_record);
+        env.storage().persistent().set(
+            &(symbol_short!("CONTRIB"), contributor.clone()),
+            &soroban_sdk::vec![&env, legacy_hash.clone()],
+        );
+        env.storage().instance().set(&symbol_short!("UPGRADER"), &create_address(&env));
+    });
+    assert_eq!(client.schema_version(), 1);
+    
+    // Legacy records stay readable before migration
+    assert_eq!(client.get_study(&legacy_hash), legacy_record);
+    assert!(client.dataset_exists(&legacy_hash));
+    assert_eq!(
+        client.get_contributor_studies(&contributor),
+        soroban_sdk::vec![&env, legacy_hash.clone()],
+    );
+    
+    // Act
+    client.migrate();
+    let migrated = client.migrate_studies(&soroban_sdk::vec![&env, legacy_hash.clone()]);
+    
+    // Assert
+    assert_eq!(client.schema_version(), SCHEMA_VERSION);
+    assert_eq!(migrated, 1);
+    assert_eq!(client.get_study(&legacy_hash), legacy_record);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        let stored: VersionedStudy = storage.get(&DataKey::Study(legacy_hash.clone())).unwrap();
+        assert_eq!(stored, VersionedStudy::V1(legacy_record.clone()));
+        assert!(!storage.has(&legacy_hash));
+    });
+    
+    // Migrating again is a no-op
+    assert_eq!(client.migrate_studies(&soroban_sdk::vec![&env, legacy_hash]), 0);
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    // StudyRegistry owns the 100-199 code space
+    assert_eq!(Error::DuplicateStudy as u32, 100);
+    assert_eq!(Error::InvalidAttestation as u32, 101);
+    assert_eq!(Error::InvalidZKProof as u32, 102);
+    assert_eq!(Error::StudyNotFound as u32, 103);
+    assert_eq!(Error::Paused as u32, 104);
+    assert_eq!(Error::Unauthorized as u32, 105);
+    assert_eq!(Error::RelayerNotAllowed as u32, 106);
+    assert_eq!(Error::InvalidNonce as u32, 107);
+    assert_eq!(Error::StudyRevoked as u32, 108);
+    assert_eq!(Error::NotOnboarded as u32, 109);
+    assert_eq!(Error::VerifyingKeyNotSet as u32, 110);
+    assert_eq!(Error::InvalidVerifyingKey as u32, 111);
+    assert_eq!(Error::ImageIdNotSet as u32, 112);
+    assert_eq!(Error::ReceiptVerifierNotSet as u32, 113);
+    assert_eq!(Error::InvalidJournal as u32, 114);
+    assert_eq!(Error::UnsupportedProofKind as u32, 115);
+    assert_eq!(Error::AttestationRootNotSet as u32, 116);
+    assert_eq!(Error::MeasurementNotAllowed as u32, 117);
+    assert_eq!(Error::AttestationReplay as u32, 118);
+    assert_eq!(Error::InvalidMetadata as u32, 119);
+    assert_eq!(Error::InvalidContributors as u32, 120);
+    assert_eq!(Error::AlreadyInitialized as u32, 121);
+    assert_eq!(Error::ProofVerifierNotSet as u32, 122);
+    assert_eq!(Error::InvalidAttestationRoot as u32, 123);
+}
+
+#[test]
+fn test_set_stats_contract_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let stats = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_stats_contract(&stats), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.try_init(&create_address(&env)), Err(Ok(Error::AlreadyInitialized)));
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_stats_contract(&stats).is_err());
+    env.mock_all_auths();
+    client.set_stats_contract(&stats);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(client.health().stats_set);
+}
+
+#[test]
+fn test_set_onboarding_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let onboarding = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_onboarding(&onboarding), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_onboarding(&onboarding).is_err());
+    env.mock_all_auths();
+    client.set_onboarding(&onboarding);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_credit_token_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let credit = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_credit_token(&credit), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_credit_token(&credit).is_err());
+    env.mock_all_auths();
+    client.set_credit_token(&credit);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_reputation_contract_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let reputation = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_reputation_contract(&reputation), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_reputation_contract(&reputation).is_err());
+    env.mock_all_auths();
+    client.set_reputation_contract(&reputation);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_guardian_set_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let guardians = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_guardian_set(&guardians), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_guardian_set(&guardians).is_err());
+    env.mock_all_auths();
+    client.set_guardian_set(&guardians);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_audit_log_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let log = create_address(&env);
+    
+    // Nobody can set it before the admin is
+    assert_eq!(client.try_set_audit_log(&log), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_audit_log(&log).is_err());
+    env.mock_all_auths();
+    client.set_audit_log(&log);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_relayer_registry_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let relayer_registry = create_address(&env);
+    assert_eq!(client.try_set_relayer_registry(&relayer_registry), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_relayer_registry(&relayer_registry).is_err());
+    env.mock_all_auths();
+    client.set_relayer_registry(&relayer_registry);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_marketplace_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let marketplace = create_address(&env);
+    assert_eq!(client.try_set_marketplace(&marketplace), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_marketplace(&marketplace).is_err());
+    env.mock_all_auths();
+    client.set_marketplace(&marketplace);
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_set_upgrade_coordinator_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let coordinator = create_address(&env);
+    assert_eq!(client.try_set_upgrade_coordinator(&coordinator), Err(Ok(Error::Unauthorized)));
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: only with the admin's authorization
+    assert!(client.try_set_upgrade_coordinator(&coordinator).is_err());
+    env.mock_all_auths();
+    client.set_upgrade_coordinator(&coordinator);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(client.health().upgrader_set);
+}
+
+#[test]
+fn test_version_introspection() {
+    let env = create_env();
+    let client = create_study_registry_client(&env);
+    
+    // Assert
+    assert_eq!(client.version(), String::from_str(&env, env!("CARGO_PKG_VERSION")));
+    assert_eq!(client.interface_version(), INTERFACE_VERSION);
+}
+
+#[test]
+fn test_get_contributor_studies() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange
+    let contributor = create_address(&env);
+    let other = create_address(&env);
+    
+    // Act
+    for (seed, registrant) in [(1, &contributor), (2, &other), (3, &contributor)] {
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, registrant);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, registrant);
+        client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, registrant);
+    }
+    
+    // Assert: studies are listed per contributor in registration order
+    assert_eq!(
+        client.get_contributor_studies(&contributor),
+        Vec::from_array(&env, [create_dataset_hash(&env, 1), create_dataset_hash(&env, 3)])
+    );
+    assert_eq!(client.get_contributor_studies(&other).len(), 1);
+    assert!(client.get_contributor_studies(&create_address(&env)).is_empty());
+}
+
+#[test]
+fn test_list_studies_pages_in_registration_order() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: alternate two contributors over five studies
+    let contributor = create_address(&env);
+    let other = create_address(&env);
+    for seed in 1..=5u8 {
+        let registrant = if seed % 2 == 1 { &contributor } else { &other };
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, registrant);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, registrant);
+        client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, registrant);
+    }
+    let hashes = |studies: Vec<StudyRecord>| -> std::vec::Vec<u8> {
+        studies.iter().map(|study| study.dataset_hash.get(0).unwrap()).collect()
+    };
+    
+    // Act + Assert
+    assert_eq!(client.study_count(), 5);
+    assert_eq!(hashes(client.list_studies(&0, &2)), [1, 2]);
+    assert_eq!(hashes(client.list_studies(&2, &10)), [3, 4, 5]);
+    assert!(client.list_studies(&5, &10).is_empty());
+    assert_eq!(hashes(client.get_studies_by_contributor(&contributor, &1, &10)), [3, 5]);
+    assert_eq!(hashes(client.get_studies_by_contributor(&other, &0, &1)), [2]);
+    assert!(client.get_studies_by_contributor(&create_address(&env), &0, &10).is_empty());
+    
+    // Erased studies keep their position but are no longer listed
+    client.revoke_study(&contributor, &create_dataset_hash(&env, 3), &RevocationReason::ConsentWithdrawn, &true);
+    assert_eq!(client.study_count(), 5);
+    assert_eq!(hashes(client.list_studies(&2, &10)), [4, 5]);
+    assert_eq!(hashes(client.get_studies_by_contributor(&contributor, &0, &10)), [1, 5]);
+}
+
+#[test]
+fn test_list_studies_spans_index_chunks() {
+    let env = create_env();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: more studies than fit in one index chunk
+    let contributor = create_address(&env);
+    for seed in 1..=60u8 {
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, &contributor);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+        client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor);
+    }
+    
+    // Act
+    let page = client.list_studies(&45, &100);
+    
+    // Assert: the page continues across the chunk boundary
+    assert_eq!(client.study_count(), 60);
+    assert_eq!(page.len(), 15);
+    for (offset, study) in page.iter().enumerate() {
+        assert_eq!(study.dataset_hash, create_dataset_hash(&env, 46 + offset as u8));
+    }
+    assert_eq!(client.list_studies(&0, &100).len(), MAX_PAGE_SIZE);
+}
+
+#[test]
+fn test_revoke_study_and_erase() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange
+    let contributor = create_address(&env);
+    let revoked = create_dataset_hash(&env, 1);
+    let erased = create_dataset_hash(&env, 2);
+    let revoked_attestation = create_attestation(&env, &revoked, &contributor);
+    let attestation = create_attestation(&env, &erased, &contributor);
+    let erased_proof = create_zk_proof(&env, &erased, &attestation, &contributor);
+    client.register_study(
+        &revoked,
+        &revoked_attestation,
+        &ProofKind::Groth16,
+        &create_zk_proof(&env, &revoked, &revoked_attestation, &contributor),
+        &contributor,
+    );
+    client.register_study(&erased, &attestation, &ProofKind::Groth16, &erased_proof, &contributor);
+    
+    // Act
+    let reason = RevocationReason::ConsentWithdrawn;
+    client.revoke_study(&contributor, &revoked, &reason, &false);
+    let revocation = client.revoke_study(&contributor, &erased, &reason, &true);
+    
+    // Assert: a revoked study keeps its record, an erased one does not
+    assert!(!client.dataset_exists(&revoked));
+    assert_eq!(client.get_study(&revoked).contributor, contributor);
+    assert!(revocation.erased);
+    assert_eq!(revocation.reason, reason);
+    assert!(!revocation.by_admin);
+    assert_eq!(client.get_revocation(&erased), Some(revocation));
+    assert_eq!(client.try_get_study(&erased), Err(Ok(Error::StudyNotFound)));
+    assert_eq!(
+        client.get_contributor_studies(&contributor),
+        Vec::from_array(&env, [revoked.clone()])
+    );
+    
+    // Revoked hashes cannot be revoked or registered again
+    assert_eq!(
+        client.try_revoke_study(&contributor, &revoked, &reason, &true),
+        Err(Ok(Error::StudyRevoked))
+    );
+    assert_eq!(
+        client.try_register_study(&erased, &attestation, &ProofKind::Groth16, &erased_proof, &contributor),
+        Err(Ok(Error::DuplicateStudy))
+    );
+    assert_eq!(
+        client.try_revoke_study(&contributor, &create_dataset_hash(&env, 3), &reason, &false),
+        Err(Ok(Error::StudyNotFound))
+    );
+}
+
+#[test]
+fn test_admin_revocation_and_revoked_study_lookup() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange
+    let coordinator = create_address(&env);
+    client.set_upgrade_coordinator(&coordinator);
+    let contributor = create_address(&env);
+    let dataset_hashes = Vec::from_array(
+        &env,
+        [create_dataset_hash(&env, 1), create_dataset_hash(&env, 2), create_dataset_hash(&env, 3)],
+    );
+    for dataset_hash in dataset_hashes.iter() {
+        let attestation = create_attestation(&env, &dataset_hash, &contributor);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+        client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor);
+    }
+    let first = dataset_hashes.get_unchecked(0);
+    let third = dataset_hashes.get_unchecked(2);
+    
+    // Act: only the contributor and the coordinator may revoke
+    assert_eq!(
+        client.try_revoke_study(&create_address(&env), &first, &RevocationReason::Other, &false),
+        Err(Ok(Error::Unauthorized))
+    );
+    let revocation = client.revoke_study(&coordinator, &first, &RevocationReason::Misconduct, &false);
+    
+    // Assert: the study is tombstoned, not deleted
+    assert!(revocation.by_admin);
+    assert_eq!(revocation.reason, RevocationReason::Misconduct);
+    assert_eq!(client.get_study(&first).contributor, contributor);
+    assert!(!client.dataset_exists(&first));
+    
+    // Revoked studies are reported in the given order
+    client.revoke_study(&contributor, &third, &RevocationReason::DataQuality, &true);
+    assert_eq!(client.get_revoked_studies(&dataset_hashes), Vec::from_array(&env, [first, third]));
+}
+
+/// Revocation as stored before reasons were recorded
+#[contracttype]
+struct OldRevocation {
+    revoked_at: u64,
+    erased: bool,
+}
+
+#[test]
+fn test_legacy_revocation_has_unspecified_reason() {
+    let env = create_env();
+    let client = create_study_registry_client(&env);
+    let dataset_hash = create_dataset_hash(&env, 1);
+    
+    // Arrange: a revocation in the old layout
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(
+            &DataKey::Revocation(dataset_hash.clone()),
+            &OldRevocation { revoked_at: 42, erased: true },
+        );
+    });
+    
+    // Act
+    let revocation = client.get_revocation(&dataset_hash);
+    
+    // Assert
+    assert_eq!(
+        revocation,
+        Some(StudyRevocation {
+            revoked_at: 42,
+            erased: true,
+            reason: RevocationReason::Unspecified,
+            by_admin: false,
+        })
+    );
+}
+
+#[test]
+fn test_public_inputs_match_schema_fixture() {
+    let env = create_env();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: the inputs encoded in the biochain-zk-schema fixture
+    let fixture = include_str!("../../biochain_zk_schema/tests/fixtures/public_inputs_v1.hex").trim();
+    let contributor = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    
+    // Act
+    let attestation = Bytes::from_slice(&env, b"mock_attestation");
+    let encoded = client.public_inputs(&create_dataset_hash(&env, 1), &attestation, &contributor);
+    
+    // Assert: the registry encodes byte-for-byte what the prover commits to
+    let mut hex = [0u8; 2 * biochain_zk_schema::ENCODED_LEN];
+    for (i, byte) in encoded.iter().enumerate() {
+        let digits = b"0123456789abcdef";
+        hex[2 * i] = digits[(byte >> 4) as usize];
+        hex[2 * i + 1] = digits[(byte & 0x0f) as usize];
+    }
+    assert_eq!(&hex[..], fixture.as_bytes());
+}
+
+#[test]
+fn test_health_reports_wiring_and_pause() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    
+    // Assert: a fresh registry needs a verifying key, a proof verifier and
+    // an attestation root to serve registrations
+    let health = client.health();
+    assert!(!health.ready && !health.verifying_key_set && !health.attestation_root_set);
+    assert!(!health.paused && !health.stats_set && !health.upgrader_set);
+    assert_eq!(health.schema_version, 1);
+    client.init(&create_address(&env));
+    client.set_verifying_key(&testutils::CIRCUIT, &testutils::verifying_key(&env));
+    assert!(!client.health().ready);
+    client.set_study_circuit(&testutils::CIRCUIT);
+    assert!(!client.health().ready);
+    client.set_attestation_root(&testutils::attestation_root(&env));
+    assert!(!client.health().ready);
+    client.set_proof_verifier(&create_address(&env));
+    assert!(client.health().ready);
+    
+    // Act
+    client.set_stats_contract(&create_address(&env));
+    client.set_upgrade_coordinator(&create_address(&env));
+    client.pause();
+    
+    // Assert
+    let health = client.health();
+    assert!(health.stats_set && health.upgrader_set && health.paused);
+    assert!(!health.audit_log_set && !health.relayer_registry_set);
+    assert!(!health.ready);
+}
+
+#[test]
+fn test_register_study_rejects_proof_for_other_inputs() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange
+    let contributor = create_address(&env);
+    let dataset_hash = create_dataset_hash(&env, 5);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let other_hash = create_dataset_hash(&env, 6);
+    let other_attestation = Bytes::from_slice(&env, b"other_attestation");
+    
+    // Act + Assert: a valid proof only covers its own hash, attestation and
+    // contributor
+    for (hash, proof_attestation, registrant) in [
+        (&other_hash, &attestation, &contributor),
+        (&dataset_hash, &other_attestation, &contributor),
+        (&dataset_hash, &attestation, &create_address(&env)),
+    ] {
+        let zk_proof = create_zk_proof(&env, hash, proof_attestation, registrant);
+        assert_eq!(
+            client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor),
+            Err(Ok(Error::InvalidZKProof))
+        );
+    }
+    
+    // Malformed proofs are rejected the same way
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+    let mut tampered = zk_proof.clone();
+    tampered.set(63, tampered.get(63).unwrap() ^ 1);
+    let truncated = zk_proof.slice(..255);
+    for bad_proof in [tampered, truncated, Bytes::from_array(&env, &[0u8; 256])] {
+        assert_eq!(
+            client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &bad_proof, &contributor),
+            Err(Ok(Error::InvalidZKProof))
+        );
+    }
+    assert!(!client.dataset_exists(&dataset_hash));
+    
+    client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor);
+    assert!(client.dataset_exists(&dataset_hash));
+}
+
+#[test]
+fn test_register_study_fails_closed_without_verifying_key() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    testutils::install_attestation_root(&client);
+    
+    // Arrange
+    let contributor = create_address(&env);
+    let dataset_hash = create_dataset_hash(&env, 7);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+    
+    // Act + Assert: neither a key alone nor a circuit without a key is enough
+    assert_eq!(client.get_study_circuit(), None);
+    client.set_verifying_key(&testutils::CIRCUIT, &testutils::verifying_key(&env));
+    assert_eq!(
+        client.try_set_study_circuit(&symbol_short!("missing")),
+        Err(Ok(Error::VerifyingKeyNotSet))
+    );
+    assert_eq!(
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor),
+        Err(Ok(Error::VerifyingKeyNotSet))
+    );
+    
+    // With a circuit selected, the proof still needs a verifier
+    client.set_study_circuit(&testutils::CIRCUIT);
+    assert_eq!(
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor),
+        Err(Ok(Error::ProofVerifierNotSet))
+    );
+    client.set_proof_verifier(&env.register_contract(None, testutils::MockProofVerifier));
+    client.register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor);
+    assert!(client.dataset_exists(&dataset_hash));
+}
+
+#[test]
+fn test_set_verifying_key_validates_points() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let verifying_key = testutils::verifying_key(&env);
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // A point off the curve
+    let mut off_curve = verifying_key.clone();
+    off_curve.set(63, off_curve.get(63).unwrap() ^ 1);
+    
+    // A commitment missing
+    let short = verifying_key.slice(..VERIFYING_KEY_LEN as u32 - 64);
+    
+    for bad_key in [off_curve, short] {
+        assert_eq!(
+            client.try_set_verifying_key(&testutils::CIRCUIT, &bad_key),
+            Err(Ok(Error::InvalidVerifyingKey))
+        );
+    }
+    assert_eq!(client.get_verifying_key_version(&testutils::CIRCUIT), 0);
+    assert_eq!(client.get_verifying_key(&testutils::CIRCUIT, &None), None);
+    
+    // The admin authorizes key changes, even once a coordinator is set
+    client.set_upgrade_coordinator(&create_address(&env));
+    assert_eq!(client.set_verifying_key(&testutils::CIRCUIT, &verifying_key), 1);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_verifying_key(&testutils::CIRCUIT, &None), Some(verifying_key));
+}
+
+#[test]
+fn test_verifying_key_setters_require_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let verifying_key = testutils::verifying_key(&env);
+    
+    // Nobody can install a key before the admin is set
+    assert_eq!(
+        client.try_set_verifying_key(&testutils::CIRCUIT, &verifying_key),
+        Err(Ok(Error::Unauthorized))
+    );
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: without the admin's authorization nothing changes
+    assert!(client.try_set_verifying_key(&testutils::CIRCUIT, &verifying_key).is_err());
+    assert!(client.try_set_study_circuit(&testutils::CIRCUIT).is_err());
+    assert_eq!(client.get_verifying_key_version(&testutils::CIRCUIT), 0);
+    
+    // The admin installs and rotates keys and selects the circuit
+    env.mock_all_auths();
+    client.set_verifying_key(&testutils::CIRCUIT, &verifying_key);
+    client.set_study_circuit(&testutils::CIRCUIT);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.set_verifying_key(&testutils::CIRCUIT, &verifying_key), 2);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_verifying_key_version(&testutils::CIRCUIT), 2);
+    assert_eq!(client.get_study_circuit(), Some(testutils::CIRCUIT));
+}
+
+#[test]
+fn test_attestation_setters_require_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let root = testutils::attestation_root(&env);
+    let measurement = BytesN::from_array(&env, &testutils::MEASUREMENT);
+    
+    // Nobody can install a root before the admin is set
+    assert_eq!(client.try_set_attestation_root(&root), Err(Ok(Error::Unauthorized)));
+    assert_eq!(
+        client.try_set_measurement_allowed(&measurement, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: without the admin's authorization nothing changes
+    assert!(client.try_set_attestation_root(&root).is_err());
+    assert!(client.try_set_measurement_allowed(&measurement, &true).is_err());
+    assert_eq!(client.get_attestation_root(), None);
+    assert!(!client.is_measurement_allowed(&measurement));
+    
+    // The admin installs the root, which must be SEC-1 uncompressed, and
+    // allows the measurement
+    env.mock_all_auths();
+    let mut compressed = root.to_array();
+    compressed[0] = 0x02;
+    assert_eq!(
+        client.try_set_attestation_root(&BytesN::from_array(&env, &compressed)),
+        Err(Ok(Error::InvalidAttestationRoot))
+    );
+    client.set_attestation_root(&root);
+    assert_eq!(env.auths()[0].0, admin);
+    client.set_measurement_allowed(&measurement, &true);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_attestation_root(), Some(root));
+    assert!(client.is_measurement_allowed(&measurement));
+}
+
+#[test]
+fn test_receipt_setters_require_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let image_id = BytesN::from_array(&env, &testutils::IMAGE_ID);
+    let verifier = create_address(&env);
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert: without the admin's authorization nothing changes
+    assert!(client.try_set_image_id(&ProofKind::RiscZero, &image_id).is_err());
+    assert!(client.try_set_receipt_verifier(&verifier).is_err());
+    assert_eq!(client.get_image_id_version(&ProofKind::RiscZero), 0);
+    assert_eq!(client.get_receipt_verifier(), None);
+    
+    // The admin installs both
+    env.mock_all_auths();
+    assert_eq!(client.set_image_id(&ProofKind::RiscZero, &image_id), 1);
+    assert_eq!(env.auths()[0].0, admin);
+    client.set_receipt_verifier(&verifier);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_receipt_verifier(), Some(verifier));
+}
+
+#[test]
+fn test_set_proof_verifier_requires_the_admin() {
+    let env = create_env();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    let verifier = create_address(&env);
+    let admin = create_address(&env);
+    client.init(&admin);
+    
+    // Act & Assert
+    assert!(client.try_set_proof_verifier(&verifier).is_err());
+    assert_eq!(client.get_proof_verifier(), None);
+    env.mock_all_auths();
+    client.set_proof_verifier(&verifier);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_proof_verifier(), Some(verifier));
+}
+
+#[test]
+fn test_register_study_with_metadata() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange
+    let contributor = create_address(&env);
+    let metadata = StudyMetadata {
+        study_type: StudyType::Genomics,
+        anonymization: AnonymizationMethod::KAnonymity,
+        record_count: 1_200,
+        schema_hash: BytesN::from_array(&env, &[7u8; 32]),
+        metadata_uri: String::from_str(&env, "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"),
+    };
+    let register = |seed: u8, metadata: &StudyMetadata| {
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, &contributor);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+        client.try_register_study_with_metadata(
+            &dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor, metadata,
+        )
+    };
+    
+    // Act
+    assert_eq!(register(1, &metadata), Ok(Ok(())));
+    
+    // Assert: the record reads as before, with its metadata alongside
+    let dataset_hash = create_dataset_hash(&env, 1);
+    assert_eq!(client.get_study(&dataset_hash).contributor, contributor);
+    assert_eq!(client.get_study_metadata(&dataset_hash), Some(metadata.clone()));
+    assert_eq!(client.get_study_verification(&dataset_hash).unwrap().key_version, 1);
+    assert!(client.dataset_exists(&dataset_hash));
+    
+    // Studies registered without metadata have none
+    let plain_hash = create_dataset_hash(&env, 2);
+    let attestation = create_attestation(&env, &plain_hash, &contributor);
+    let zk_proof = create_zk_proof(&env, &plain_hash, &attestation, &contributor);
+    client.register_study(&plain_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor);
+    assert_eq!(client.get_study_metadata(&plain_hash), None);
+    
+    // Empty, oversized and zero-record metadata is rejected
+    let empty_uri = StudyMetadata { metadata_uri: String::from_str(&env, ""), ..metadata.clone() };
+    let long_uri = [b'a'; MAX_METADATA_URI_LEN as usize + 1];
+    let long_uri = StudyMetadata {
+        metadata_uri: String::from_bytes(&env, &long_uri),
+        ..metadata.clone()
+    };
+    let no_records = StudyMetadata { record_count: 0, ..metadata };
+    for invalid in [empty_uri, long_uri, no_records] {
+        assert_eq!(register(3, &invalid), Err(Ok(Error::InvalidMetadata)));
+    }
+    assert!(!client.dataset_exists(&create_dataset_hash(&env, 3)));
+}
+
+#[test]
+fn test_register_study_with_contributors() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: a hospital submits a study shared with a lab and a steward
+    let hospital = create_address(&env);
+    let lab = create_address(&env);
+    let steward = create_address(&env);
+    let shares = Vec::from_array(
+        &env,
+        [(hospital.clone(), 6_000u32), (lab.clone(), 3_000), (steward.clone(), 1_000)],
+    );
+    let register = |seed: u8, contributors: &Vec<(Address, u32)>| {
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, &hospital);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &hospital);
+        client.try_register_study_with_contributors(
+            &dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &hospital, contributors,
+        )
+    };
+    
+    // Act
+    assert_eq!(register(1, &shares), Ok(Ok(())));
+    
+    // Assert: every contributor authorized and is indexed
+    let dataset_hash = create_dataset_hash(&env, 1);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(signer, _)| signer).collect();
+    assert_eq!(signers, [hospital.clone(), lab.clone(), steward.clone()]);
+    assert_eq!(client.get_study_contributors(&dataset_hash), shares);
+    assert_eq!(client.get_study(&dataset_hash).contributor, hospital);
+    for contributor in [&hospital, &lab, &steward] {
+        assert_eq!(client.get_contributor_studies(contributor), Vec::from_array(&env, [dataset_hash.clone()]));
+    }
+    
+    // Single-contributor studies give their contributor the whole share
+    let plain_hash = create_dataset_hash(&env, 2);
+    let attestation = create_attestation(&env, &plain_hash, &lab);
+    let zk_proof = create_zk_proof(&env, &plain_hash, &attestation, &lab);
+    client.register_study(&plain_hash, &attestation, &ProofKind::Groth16, &zk_proof, &lab);
+    assert_eq!(
+        client.get_study_contributors(&plain_hash),
+        Vec::from_array(&env, [(lab.clone(), BPS_DENOMINATOR)])
+    );
+    
+    // Shares must be distinct, non-zero, include the submitter and sum to
+    // the denominator
+    let mut too_many = Vec::from_array(&env, [(hospital.clone(), BPS_DENOMINATOR - MAX_STUDY_CONTRIBUTORS)]);
+    for _ in 0..MAX_STUDY_CONTRIBUTORS {
+        too_many.push_back((create_address(&env), 1));
+    }
+    for invalid in [
+        Vec::new(&env),
+        Vec::from_array(&env, [(hospital.clone(), 5_000u32), (lab.clone(), 4_000)]),
+        Vec::from_array(&env, [(hospital.clone(), 5_000u32), (hospital.clone(), 5_000)]),
+        Vec::from_array(&env, [(hospital.clone(), BPS_DENOMINATOR), (lab.clone(), 0)]),
+        Vec::from_array(&env, [(lab.clone(), BPS_DENOMINATOR)]),
+        too_many,
+    ] {
+        assert_eq!(register(3, &invalid), Err(Ok(Error::InvalidContributors)));
+    }
+    
+    // Erasing the study drops it from every contributor's index
+    client.revoke_study(&hospital, &dataset_hash, &RevocationReason::ConsentWithdrawn, &true);
+    assert_eq!(client.try_get_study_contributors(&dataset_hash), Err(Ok(Error::StudyNotFound)));
+    assert!(client.get_contributor_studies(&steward).is_empty());
+    assert_eq!(client.get_contributor_studies(&lab), Vec::from_array(&env, [plain_hash]));
+}
+
+#[test]
+fn test_rotated_keys_are_versioned_per_study() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    
+    // Arrange: a valid key of another setup, which the test proofs do not
+    // satisfy
+    let fixture = include_str!("../../biochain_groth16/tests/fixtures/verifying_key.hex").trim();
+    let nibble = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+    let other_bytes: std::vec::Vec<u8> = fixture.as_bytes()
+        .chunks(2)
+        .map(|pair| nibble(pair[0]) << 4 | nibble(pair[1]))
+        .collect();
+    let other_key = Bytes::from_slice(&env, &other_bytes);
+    let test_key = testutils::verifying_key(&env);
+    let contributor = create_address(&env);
+    let register = |seed: u8| {
+        let dataset_hash = create_dataset_hash(&env, seed);
+        let attestation = create_attestation(&env, &dataset_hash, &contributor);
+        let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor)
+    };
+    assert_eq!(register(1), Ok(Ok(())));
+    
+    // Act: rotate to the other key, then back
+    assert_eq!(client.set_verifying_key(&testutils::CIRCUIT, &other_key), 2);
+    assert_eq!(register(2), Err(Ok(Error::InvalidZKProof)));
+    assert_eq!(client.set_verifying_key(&testutils::CIRCUIT, &test_key), 3);
+    assert_eq!(register(2), Ok(Ok(())));
+    
+    // Assert: every version stays readable and studies record theirs
+    assert_eq!(client.get_verifying_key_version(&testutils::CIRCUIT), 3);
+    assert_eq!(client.get_verifying_key(&testutils::CIRCUIT, &Some(2)), Some(other_key.clone()));
+    assert_eq!(client.get_verifying_key(&testutils::CIRCUIT, &None), Some(test_key));
+    let verified_with = |seed: u8| {
+        client.get_study_verification(&create_dataset_hash(&env, seed)).unwrap().key_version
+    };
+    assert_eq!(verified_with(1), 1);
+    assert_eq!(verified_with(2), 3);
+    
+    // Circuits are versioned independently
+    assert_eq!(client.set_verifying_key(&symbol_short!("other"), &other_key), 1);
+    client.set_study_circuit(&symbol_short!("other"));
+    assert_eq!(
+        client.get_study_verification(&create_dataset_hash(&env, 1)),
+        Some(StudyVerification { circuit_id: testutils::CIRCUIT, key_version: 1 })
+    );
+    assert_eq!(register(3), Err(Ok(Error::InvalidZKProof)));
+}
+
+#[test]
+fn test_register_study_with_risc_zero_receipt() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = create_study_registry_client(&env);
+    let contributor = create_address(&env);
+    let dataset_hash = create_dataset_hash(&env, 1);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let receipt = testutils::receipt(&env, &dataset_hash, &attestation, &contributor);
+    let register = |receipt: &Bytes| {
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::RiscZero, receipt, &contributor)
+    };
+    
+    // Fails closed until an image ID and a verifier are set
+    assert_eq!(register(&receipt), Err(Ok(Error::ImageIdNotSet)));
+    assert_eq!(
+        client.try_set_image_id(&ProofKind::Groth16, &BytesN::from_array(&env, &testutils::IMAGE_ID)),
+        Err(Ok(Error::UnsupportedProofKind))
+    );
+    client.set_image_id(&ProofKind::RiscZero, &BytesN::from_array(&env, &testutils::IMAGE_ID));
+    assert_eq!(register(&receipt), Err(Ok(Error::ReceiptVerifierNotSet)));
+    testutils::install_receipt_verifier(&client);
+    assert_eq!(client.get_image_id_version(&ProofKind::RiscZero), 2);
+    
+    // A receipt is not a Groth16 proof, and the journal must be this
+    // registration's
+    assert_eq!(
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &receipt, &contributor),
+        Err(Ok(Error::InvalidZKProof))
+    );
+    let other_dataset = create_dataset_hash(&env, 2);
+    let other_receipt = testutils::receipt(&env, &other_dataset, &attestation, &contributor);
+    assert_eq!(register(&other_receipt), Err(Ok(Error::InvalidJournal)));
+    let other_contributor = create_address(&env);
+    let other_receipt = testutils::receipt(&env, &dataset_hash, &attestation, &other_contributor);
+    assert_eq!(register(&other_receipt), Err(Ok(Error::InvalidJournal)));
+    assert_eq!(register(&receipt.slice(..10)), Err(Ok(Error::InvalidJournal)));
+    
+    // The seal must prove the expected image
+    let mut forged = receipt.clone();
+    let last = forged.len() - 1;
+    forged.set(last, forged.get(last).unwrap() ^ 1);
+    assert_eq!(register(&forged), Err(Ok(Error::InvalidZKProof)));
+    
+    // Act
+    assert_eq!(register(&receipt), Ok(Ok(())));
+    
+    // Assert: the study records the image ID version that was proven
+    assert!(client.dataset_exists(&dataset_hash));
+    assert_eq!(
+        client.get_study_verification(&dataset_hash),
+        Some(StudyVerification { circuit_id: RISC_ZERO_CIRCUIT, key_version: 2 })
+    );
+    
+    // Rotating the image ID rejects receipts of the old image
+    let rotated = BytesN::from_array(&env, &[0x77; 32]);
+    assert_eq!(client.set_image_id(&ProofKind::RiscZero, &rotated), 3);
+    assert_eq!(client.get_image_id(&ProofKind::RiscZero, &None), Some(rotated));
+    let next_dataset = create_dataset_hash(&env, 3);
+    let next_attestation = create_attestation(&env, &next_dataset, &contributor);
+    let stale = testutils::receipt(&env, &next_dataset, &next_attestation, &contributor);
+    assert_eq!(
+        client.try_register_study(&next_dataset, &next_attestation, &ProofKind::RiscZero, &stale, &contributor),
+        Err(Ok(Error::InvalidZKProof))
+    );
+}
+
+#[test]
+fn test_register_study_verifies_attestation() {
+    let env = create_env();
+    env.mock_all_auths();
+    let client = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    testutils::install_verifying_key(&client);
+    let contributor = create_address(&env);
+    let dataset_hash = create_dataset_hash(&env, 1);
+    let register = |attestation: &Bytes| {
+        let zk_proof = create_zk_proof(&env, &dataset_hash, attestation, &contributor);
+        client.try_register_study(&dataset_hash, attestation, &ProofKind::Groth16, &zk_proof, &contributor)
+    };
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    
+    // Fails closed until a root key is set
+    assert_eq!(register(&attestation), Err(Ok(Error::AttestationRootNotSet)));
+    testutils::install_attestation_root(&client);
+    
+    // Malformed reports are rejected
+    let mut bad_magic = attestation.clone();
+    bad_magic.set(0, b'X');
+    for bad in [bad_magic, attestation.slice(1..)] {
+        assert_eq!(register(&bad), Err(Ok(Error::InvalidAttestation)));
+    }
+    
+    // Reports for another dataset or contributor cannot be replayed
+    let other_dataset = create_attestation(&env, &create_dataset_hash(&env, 2), &contributor);
+    let other_contributor = create_attestation(&env, &dataset_hash, &create_address(&env));
+    let unbound = testutils::attestation_from(&env, &testutils::MEASUREMENT, &dataset_hash.to_array());
+    for replayed in [other_dataset, other_contributor, unbound] {
+        assert_eq!(register(&replayed), Err(Ok(Error::AttestationReplay)));
+    }
+    
+    // Only allowed measurements are accepted
+    let measurement = [0x4e; 32];
+    let nonce = client.attestation_nonce(&dataset_hash, &contributor);
+    let other_image = testutils::attestation_from(&env, &measurement, &nonce.to_array());
+    assert_eq!(register(&other_image), Err(Ok(Error::MeasurementNotAllowed)));
+    
+    // Signatures the host cannot parse are rejected: r or s zero, s not
+    // below the group order or high
+    let signature_at = attestation.len() - 64;
+    let with_signature = |r: [u8; 32], s: [u8; 32]| {
+        let mut report = attestation.slice(..signature_at);
+        report.extend_from_array(&r);
+        report.extend_from_array(&s);
+        report
+    };
+    let mut r = [0u8; 32];
+    attestation.slice(signature_at..signature_at + 32).copy_into_slice(&mut r);
+    let mut high_s = [0u8; 32];
+    high_s[0] = 0x80;
+    for malformed in [
+        with_signature([0; 32], [1; 32]),
+        with_signature(r, [0; 32]),
+        with_signature(r, [0xff; 32]),
+        with_signature(r, high_s),
+    ] {
+        assert_eq!(register(&malformed), Err(Ok(Error::InvalidAttestation)));
+    }
+    
+    // A well-formed signature that does not verify traps in the host
+    let mut forged = attestation.clone();
+    let last = forged.len() - 1;
+    forged.set(last, forged.get(last).unwrap() ^ 1);
+    assert!(register(&forged).is_err());
+    assert!(!matches!(register(&forged), Err(Ok(_))));
+    
+    // Act
+    client.set_measurement_allowed(&BytesN::from_array(&env, &measurement), &true);
+    client.set_measurement_allowed(&BytesN::from_array(&env, &testutils::MEASUREMENT), &false);
+    
+    // Assert
+    assert!(!client.is_measurement_allowed(&BytesN::from_array(&env, &testutils::MEASUREMENT)));
+    assert_eq!(register(&attestation), Err(Ok(Error::MeasurementNotAllowed)));
+    assert_eq!(register(&other_image), Ok(Ok(())));
+    assert_eq!(client.get_attestation_root(), Some(testutils::attestation_root(&env)));
+}
+
+#[test]
+fn test_register_study_requires_contributor_auth() {
+    let env = create_env();
+    let client = create_study_registry_client(&env);
+    let contributor = create_address(&env);
+    let impostor = create_address(&env);
+    let dataset_hash = create_dataset_hash(&env, 1);
+    let attestation = create_attestation(&env, &dataset_hash, &contributor);
+    let zk_proof = create_zk_proof(&env, &dataset_hash, &attestation, &contributor);
+    let args = (
+        dataset_hash.clone(),
+        attestation.clone(),
+        ProofKind::Groth16,
+        zk_proof.clone(),
+        contributor.clone(),
+    );
+    let authorize = |signer: &Address| {
+        env.mock_auths(&[MockAuth {
+            address: signer,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "register_study",
+                args: args.clone().into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+    };
+    let register = || {
+        client.try_register_study(&dataset_hash, &attestation, &ProofKind::Groth16, &zk_proof, &contributor)
+    };
+    
+    // Act + Assert: without the contributor's signature the study cannot
+    // be attributed to them, even with a valid proof
+    assert!(matches!(register(), Err(Err(_))));
+    authorize(&impostor);
+    assert!(matches!(register(), Err(Err(_))));
+    assert!(!client.dataset_exists(&dataset_hash));
+    
+    authorize(&contributor);
+    assert_eq!(register(), Ok(Ok(())));
+    assert_eq!(client.get_study(&dataset_hash).contributor, contributor);
+}
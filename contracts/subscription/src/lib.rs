@@ -0,0 +1,600 @@
+#![no_std]
+use biochain_interfaces::{
+    DatasetMarketplaceClient, DatasetMarketplaceError, StudyRegistryClient, StudyRegistryError,
+};
+use biochain_pricing as pricing;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+/// TTL policy for plans and subscriptions, in ledgers
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Maximum number of datasets in a single plan, so a distribution stays
+/// within one transaction's registry lookups
+pub const MAX_PLAN_DATASETS: u32 = 10;
+
+/// Storage keys
+///
+/// - Plan / Subscription: Records in persistent storage
+/// - Remaining variants: Configuration in instance storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    UsdcToken,
+    RevenueSplitter,
+    Marketplace,
+    StudyRegistry,
+    PlanCount,
+    Plan(u32),
+    Subscription(Address),
+}
+
+/// Blanket access to a curated collection of datasets
+///
+/// - dataset_ids: Marketplace datasets subscribers can access
+/// - price: USDC paid per period
+/// - period: Length of a paid period, in seconds
+/// - active: Whether new subscriptions and renewals are accepted
+/// - accrued: Subscription fees earned so far, as periods elapse
+/// - distributed: Part of `accrued` paid out to contributors
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub plan_id: u32,
+    pub dataset_ids: Vec<Bytes>,
+    pub price: i128,
+    pub period: u64,
+    pub active: bool,
+    pub accrued: i128,
+    pub distributed: i128,
+}
+
+/// A researcher's subscription to a plan
+///
+/// - expires_at: Ledger timestamp access ends at
+/// - settled_until: Ledger timestamp fees have been accrued to the plan up
+///   to
+/// - balance: Fees paid for the time between `settled_until` and
+///   `expires_at`, refunded on cancellation
+/// - cancelled: Whether the subscriber cancelled before `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub subscriber: Address,
+    pub plan_id: u32,
+    pub started_at: u64,
+    pub expires_at: u64,
+    pub settled_until: u64,
+    pub balance: i128,
+    pub cancelled: bool,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidPeriod = 4,
+    InvalidPlan = 5,
+    DatasetNotFound = 6,
+    PlanNotFound = 7,
+    PlanClosed = 8,
+    AlreadySubscribed = 9,
+    NotSubscribed = 10,
+    NothingToDistribute = 11,
+    StudyLookupFailed = 12,
+    Overflow = 13,
+}
+
+/// Recurring subscriptions to curated dataset collections
+///
+/// Researchers pay a USDC fee per period for access to every dataset in a
+/// plan. Fees are held here and accrue to the plan linearly as the paid
+/// time elapses, so a cancellation refunds the unused time. Accrued fees
+/// are paid out through the RevenueSplitter to the contributors of the
+/// plan's datasets, each study weighing the same and split among its
+/// contributors by share, as in marketplace purchases.
+#[contract]
+pub struct Subscriptions;
+
+#[contractimpl]
+impl Subscriptions {
+    /// Initialize the Subscriptions contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to create and close plans
+    /// * `usdc_token` - Address of the USDC token contract
+    /// * `revenue_splitter` - Address of the RevenueSplitter contract
+    /// * `marketplace` - Address of the DatasetMarketplace contract
+    /// * `study_registry` - Address of the StudyRegistry contract
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        revenue_splitter: Address,
+        marketplace: Address,
+        study_registry: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::UsdcToken, &usdc_token);
+        storage.set(&DataKey::RevenueSplitter, &revenue_splitter);
+        storage.set(&DataKey::Marketplace, &marketplace);
+        storage.set(&DataKey::StudyRegistry, &study_registry);
+        storage.set(&DataKey::PlanCount, &0u32);
+        Ok(())
+    }
+
+    /// Create a plan over a collection of marketplace datasets
+    ///
+    /// Requires the admin's authorization.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dataset_ids` - Datasets included, listed in the marketplace
+    /// * `price` - USDC paid per period
+    /// * `period` - Length of a paid period, in seconds
+    ///
+    /// # Returns
+    /// * `Ok(u32)` with the plan ID
+    /// * `Err(Error::InvalidPlan)` if there are no datasets, more than
+    ///   `MAX_PLAN_DATASETS` or duplicates
+    /// * `Err(Error::DatasetNotFound)` if a dataset is not listed
+    /// * `Err(Error)` if the price or period is not positive
+    pub fn create_plan(
+        env: Env,
+        dataset_ids: Vec<Bytes>,
+        price: i128,
+        period: u64,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+        if price <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if period == 0 {
+            return Err(Error::InvalidPeriod);
+        }
+        if dataset_ids.is_empty() || dataset_ids.len() > MAX_PLAN_DATASETS {
+            return Err(Error::InvalidPlan);
+        }
+        let marketplace = Self::marketplace(&env)?;
+        for (index, dataset_id) in dataset_ids.iter().enumerate() {
+            if dataset_ids.first_index_of(&dataset_id) != Some(index as u32) {
+                return Err(Error::InvalidPlan);
+            }
+            if marketplace.try_get_dataset(&dataset_id).is_err() {
+                return Err(Error::DatasetNotFound);
+            }
+        }
+
+        let storage = env.storage().instance();
+        let plan_id: u32 = storage
+            .get(&DataKey::PlanCount)
+            .ok_or(Error::NotInitialized)?;
+        storage.set(&DataKey::PlanCount, &(plan_id + 1));
+        let plan = Plan {
+            plan_id,
+            dataset_ids,
+            price,
+            period,
+            active: true,
+            accrued: 0,
+            distributed: 0,
+        };
+        Self::save_plan(&env, &plan);
+
+        env.events().publish(
+            (symbol_short!("PlanNew"), plan_id),
+            (plan.dataset_ids.len(), price, period),
+        );
+        Ok(plan_id)
+    }
+
+    /// Stop accepting subscriptions and renewals to a plan
+    ///
+    /// Existing subscriptions run until they expire and their fees still
+    /// accrue and are distributed. Requires the admin's authorization.
+    pub fn close_plan(env: Env, plan_id: u32) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut plan = Self::get_plan(env.clone(), plan_id)?;
+        plan.active = false;
+        Self::save_plan(&env, &plan);
+
+        env.events().publish((symbol_short!("PlanClose"), plan_id), ());
+        Ok(())
+    }
+
+    /// Subscribe to a plan for one period
+    ///
+    /// The period's price is pulled from the subscriber. A subscriber has
+    /// one subscription at a time; once it expires or is cancelled they
+    /// can subscribe to any plan again.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `subscriber` - Researcher subscribing (must authorize)
+    /// * `plan_id` - Plan to subscribe to
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the ledger timestamp access ends at
+    /// * `Err(Error::AlreadySubscribed)` if the subscriber has access
+    /// * `Err(Error::PlanClosed)` if the plan no longer accepts subscribers
+    /// * `Err(Error)` if the plan does not exist
+    pub fn subscribe(env: Env, subscriber: Address, plan_id: u32) -> Result<u64, Error> {
+        subscriber.require_auth();
+        if Self::is_subscribed(env.clone(), subscriber.clone()) {
+            return Err(Error::AlreadySubscribed);
+        }
+        // Fees left from an expired subscription accrue to its plan first
+        if let Some(previous) = Self::get_subscription(env.clone(), subscriber.clone()) {
+            Self::accrue_subscription(&env, previous)?;
+        }
+        let plan = Self::load_active_plan(&env, plan_id)?;
+        Self::token_client(&env)?.transfer(
+            &subscriber,
+            &env.current_contract_address(),
+            &plan.price,
+        );
+
+        let now = env.ledger().timestamp();
+        let expires_at = now.checked_add(plan.period).ok_or(Error::Overflow)?;
+        Self::save_subscription(
+            &env,
+            &Subscription {
+                subscriber: subscriber.clone(),
+                plan_id,
+                started_at: now,
+                expires_at,
+                settled_until: now,
+                balance: plan.price,
+                cancelled: false,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("SubNew"), plan_id, subscriber),
+            (plan.price, expires_at),
+        );
+        Ok(expires_at)
+    }
+
+    /// Pay for another period of the subscriber's plan
+    ///
+    /// The period is added to the current one, or starts now if the
+    /// subscription expired.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `subscriber` - Subscriber renewing (must authorize)
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the ledger timestamp access ends at
+    /// * `Err(Error::NotSubscribed)` if there is no subscription or it was
+    ///   cancelled
+    /// * `Err(Error::PlanClosed)` if the plan no longer accepts renewals
+    pub fn renew(env: Env, subscriber: Address) -> Result<u64, Error> {
+        subscriber.require_auth();
+        let subscription = Self::get_subscription(env.clone(), subscriber.clone())
+            .filter(|subscription| !subscription.cancelled)
+            .ok_or(Error::NotSubscribed)?;
+        let mut subscription = Self::accrue_subscription(&env, subscription)?;
+        let plan = Self::load_active_plan(&env, subscription.plan_id)?;
+        Self::token_client(&env)?.transfer(
+            &subscriber,
+            &env.current_contract_address(),
+            &plan.price,
+        );
+
+        let now = env.ledger().timestamp();
+        if subscription.expires_at <= now {
+            subscription.settled_until = now;
+            subscription.expires_at = now;
+        }
+        subscription.expires_at = subscription
+            .expires_at
+            .checked_add(plan.period)
+            .ok_or(Error::Overflow)?;
+        subscription.balance = pricing::math::checked_add(subscription.balance, plan.price)
+            .map_err(|_| Error::Overflow)?;
+        Self::save_subscription(&env, &subscription);
+
+        env.events().publish(
+            (symbol_short!("SubRenew"), subscription.plan_id, subscriber),
+            (plan.price, subscription.expires_at),
+        );
+        Ok(subscription.expires_at)
+    }
+
+    /// Cancel a subscription and refund its unused time
+    ///
+    /// Fees for the time already elapsed accrue to the plan; the rest is
+    /// returned to the subscriber and access ends at once.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `subscriber` - Subscriber cancelling (must authorize)
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the refunded USDC
+    /// * `Err(Error::NotSubscribed)` if the subscriber has no access
+    pub fn cancel(env: Env, subscriber: Address) -> Result<i128, Error> {
+        subscriber.require_auth();
+        if !Self::is_subscribed(env.clone(), subscriber.clone()) {
+            return Err(Error::NotSubscribed);
+        }
+        let subscription = Self::get_subscription(env.clone(), subscriber.clone())
+            .ok_or(Error::NotSubscribed)?;
+        let mut subscription = Self::accrue_subscription(&env, subscription)?;
+        let refund = subscription.balance;
+        if refund > 0 {
+            Self::token_client(&env)?.transfer(
+                &env.current_contract_address(),
+                &subscriber,
+                &refund,
+            );
+        }
+        subscription.balance = 0;
+        subscription.expires_at = subscription.settled_until;
+        subscription.cancelled = true;
+        Self::save_subscription(&env, &subscription);
+
+        env.events().publish(
+            (symbol_short!("SubCancel"), subscription.plan_id, subscriber),
+            refund,
+        );
+        Ok(refund)
+    }
+
+    /// Check whether a researcher currently has access through a
+    /// subscription
+    pub fn is_subscribed(env: Env, subscriber: Address) -> bool {
+        Self::get_subscription(env.clone(), subscriber).is_some_and(|subscription| {
+            !subscription.cancelled && env.ledger().timestamp() < subscription.expires_at
+        })
+    }
+
+    /// Accrue a subscription's fees for the time elapsed to its plan
+    ///
+    /// Anyone can call this, e.g. before `distribute`.
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the fees accrued by this call
+    /// * `Err(Error::NotSubscribed)` if there is no subscription
+    pub fn accrue(env: Env, subscriber: Address) -> Result<i128, Error> {
+        let subscription = Self::get_subscription(env.clone(), subscriber)
+            .ok_or(Error::NotSubscribed)?;
+        let balance = subscription.balance;
+        let subscription = Self::accrue_subscription(&env, subscription)?;
+        Ok(balance - subscription.balance)
+    }
+
+    /// Pay a plan's accrued fees out to the contributors of its datasets
+    ///
+    /// Anyone can call this. Every study of the plan's datasets weighs the
+    /// same (once per dataset it is in) and is split among its
+    /// contributors by share; revoked and unregistered studies are
+    /// skipped. The RevenueSplitter applies its split and sends the
+    /// rounding remainder, or everything if no contributor is found, to
+    /// the treasury.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `plan_id` - Plan to distribute
+    ///
+    /// # Returns
+    /// * `Ok(i128)` with the USDC paid out
+    /// * `Err(Error::NothingToDistribute)` if no fees accrued since the
+    ///   last distribution
+    /// * `Err(Error::StudyLookupFailed)` if the registry lookup fails
+    pub fn distribute(env: Env, plan_id: u32) -> Result<i128, Error> {
+        let mut plan = Self::get_plan(env.clone(), plan_id)?;
+        let amount = plan.accrued - plan.distributed;
+        if amount <= 0 {
+            return Err(Error::NothingToDistribute);
+        }
+        let weights = Self::contributor_weights(&env, &plan.dataset_ids)?;
+
+        plan.distributed = plan.accrued;
+        Self::save_plan(&env, &plan);
+
+        // ============================================
+        // PAY THROUGH REVENUE SPLITTER
+        // ============================================
+        let usdc_token: Address = Self::get_instance(&env, &DataKey::UsdcToken)?;
+        let revenue_splitter: Address = Self::get_instance(&env, &DataKey::RevenueSplitter)?;
+        let contract_address = env.current_contract_address();
+        env.authorize_as_current_contract(soroban_sdk::vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: usdc_token,
+                    fn_name: symbol_short!("transfer"),
+                    args: (contract_address.clone(), revenue_splitter.clone(), amount)
+                        .into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        env.invoke_contract::<()>(
+            &revenue_splitter,
+            &Symbol::new(&env, "payout_weighted"),
+            (contract_address, Self::payout_id(&env, plan_id), weights, amount).into_val(&env),
+        );
+
+        env.events().publish((symbol_short!("PlanPaid"), plan_id), amount);
+        Ok(amount)
+    }
+
+    /// Get a plan by ID
+    pub fn get_plan(env: Env, plan_id: u32) -> Result<Plan, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Plan(plan_id))
+            .ok_or(Error::PlanNotFound)
+    }
+
+    /// Get a researcher's latest subscription, if any
+    pub fn get_subscription(env: Env, subscriber: Address) -> Option<Subscription> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscriber))
+    }
+
+    /// Get the admin address
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Self::get_instance(&env, &DataKey::Admin)
+    }
+
+    /// Move the fees for the time elapsed since the last accrual from the
+    /// subscription's balance to its plan
+    fn accrue_subscription(env: &Env, mut subscription: Subscription) -> Result<Subscription, Error> {
+        let end = env.ledger().timestamp().min(subscription.expires_at);
+        if end <= subscription.settled_until || subscription.balance == 0 {
+            return Ok(subscription);
+        }
+        // The whole balance once the paid time is over, so no rounding
+        // dust stays behind
+        let earned = if end == subscription.expires_at {
+            subscription.balance
+        } else {
+            pricing::prorate(
+                subscription.balance,
+                end - subscription.settled_until,
+                subscription.expires_at - subscription.settled_until,
+                pricing::Rounding::Down,
+            )
+            .map_err(|_| Error::Overflow)?
+        };
+
+        let mut plan = Self::get_plan(env.clone(), subscription.plan_id)?;
+        plan.accrued = pricing::math::checked_add(plan.accrued, earned)
+            .map_err(|_| Error::Overflow)?;
+        Self::save_plan(env, &plan);
+        subscription.balance -= earned;
+        subscription.settled_until = end;
+        Self::save_subscription(env, &subscription);
+        Ok(subscription)
+    }
+
+    /// Contributors of the studies in `dataset_ids` with their weights
+    ///
+    /// Same weighting as DatasetMarketplace purchases, over the studies of
+    /// all the datasets.
+    fn contributor_weights(env: &Env, dataset_ids: &Vec<Bytes>) -> Result<Vec<(Address, u32)>, Error> {
+        let marketplace = Self::marketplace(env)?;
+        let mut study_hashes = Vec::new(env);
+        for dataset_id in dataset_ids.iter() {
+            let dataset = match marketplace.try_get_dataset(&dataset_id) {
+                Ok(Ok(dataset)) => dataset,
+                Err(Ok(DatasetMarketplaceError::DatasetNotFound)) => continue,
+                _ => return Err(Error::StudyLookupFailed),
+            };
+            for study_id in dataset.study_ids.iter() {
+                // Only 32-byte hashes can be registry studies
+                if study_id.len() != 32 {
+                    continue;
+                }
+                let mut hash_bytes = [0u8; 32];
+                study_id.copy_into_slice(&mut hash_bytes);
+                study_hashes.push_back(BytesN::from_array(env, &hash_bytes));
+            }
+        }
+
+        let study_registry: Address = Self::get_instance(env, &DataKey::StudyRegistry)?;
+        let study_registry = StudyRegistryClient::new(env, &study_registry);
+        let revoked = match study_registry.try_get_revoked_studies(&study_hashes) {
+            Ok(Ok(revoked)) => revoked,
+            _ => return Err(Error::StudyLookupFailed),
+        };
+        let mut weights: Vec<(Address, u32)> = Vec::new(env);
+        for study_hash in study_hashes.iter() {
+            if revoked.contains(&study_hash) {
+                continue;
+            }
+            let contributors = match study_registry.try_get_study_contributors(&study_hash) {
+                Ok(Ok(contributors)) => contributors,
+                Err(Ok(StudyRegistryError::StudyNotFound)) => continue,
+                _ => return Err(Error::StudyLookupFailed),
+            };
+            for (contributor, share) in contributors.iter() {
+                match weights.iter().position(|(address, _)| address == contributor) {
+                    Some(index) => {
+                        let (address, weight) = weights.get_unchecked(index as u32);
+                        weights.set(index as u32, (address, weight + share));
+                    }
+                    None => weights.push_back((contributor, share)),
+                }
+            }
+        }
+        Ok(weights)
+    }
+
+    fn load_active_plan(env: &Env, plan_id: u32) -> Result<Plan, Error> {
+        let plan = Self::get_plan(env.clone(), plan_id)?;
+        if !plan.active {
+            return Err(Error::PlanClosed);
+        }
+        Ok(plan)
+    }
+
+    fn save_plan(env: &Env, plan: &Plan) {
+        let key = DataKey::Plan(plan.plan_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, plan);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn save_subscription(env: &Env, subscription: &Subscription) {
+        let key = DataKey::Subscription(subscription.subscriber.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, subscription);
+        storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = Self::get_instance(env, &DataKey::Admin)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn get_instance<V: soroban_sdk::TryFromVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        key: &DataKey,
+    ) -> Result<V, Error> {
+        env.storage().instance().get(key).ok_or(Error::NotInitialized)
+    }
+
+    fn marketplace(env: &Env) -> Result<DatasetMarketplaceClient<'_>, Error> {
+        let marketplace: Address = Self::get_instance(env, &DataKey::Marketplace)?;
+        Ok(DatasetMarketplaceClient::new(env, &marketplace))
+    }
+
+    /// Payout ID used in RevenueSplitter events: "subscription" +
+    /// big-endian plan ID
+    fn payout_id(env: &Env, plan_id: u32) -> Bytes {
+        let mut payout_id = Bytes::from_slice(env, b"subscription");
+        payout_id.append(&Bytes::from_slice(env, &plan_id.to_be_bytes()));
+        payout_id
+    }
+
+    fn token_client(env: &Env) -> Result<token::Client<'_>, Error> {
+        let usdc_token: Address = Self::get_instance(env, &DataKey::UsdcToken)?;
+        Ok(token::Client::new(env, &usdc_token))
+    }
+}
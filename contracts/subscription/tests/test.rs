@@ -0,0 +1,240 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Ledger},
+    token, vec, Address, Bytes, BytesN, Env, Vec,
+};
+use subscription::{Error, Subscriptions, SubscriptionsClient};
+
+/// Price of one period (30 USDC)
+const PRICE: i128 = 30_0000000;
+
+/// Length of one period (30 days)
+const PERIOD: u64 = 30 * 86_400;
+
+struct Setup<'a> {
+    env: Env,
+    subscriptions: SubscriptionsClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    usdc: token::Client<'a>,
+    treasury: Address,
+}
+
+/// Helper: Deploy the core contracts and a Subscriptions contract
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+    splitter.init(&Address::generate(&env), &usdc_id, &treasury);
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let subscriptions = SubscriptionsClient::new(&env, &env.register_contract(None, Subscriptions));
+    subscriptions.init(
+        &Address::generate(&env),
+        &usdc_id,
+        &splitter.address,
+        &marketplace.address,
+        &registry.address,
+    );
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        subscriptions,
+        registry,
+        marketplace,
+        treasury,
+    }
+}
+
+/// Helper: Register a study with hash `[seed; 32]` for `contributor`
+fn register_study(s: &Setup, contributor: &Address, seed: u8) -> Bytes {
+    let study_hash = BytesN::from_array(&s.env, &[seed; 32]);
+    let attestation = study_registry::testutils::attestation(&s.env, &study_hash, contributor);
+    s.registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&s.env, &study_hash, &attestation, contributor),
+        contributor,
+    );
+    study_hash.into()
+}
+
+/// Helper: List a dataset of the given studies in the marketplace
+fn list_dataset(s: &Setup, name: &[u8], study_ids: Vec<Bytes>) -> Bytes {
+    let dataset_id = Bytes::from_slice(&s.env, name);
+    s.marketplace.register_dataset(
+        &Address::generate(&s.env),
+        &dataset_id,
+        &study_ids,
+        &10_0000000,
+        &None,
+    );
+    dataset_id
+}
+
+/// Helper: A researcher holding `periods` periods' worth of USDC
+fn funded_subscriber(s: &Setup, periods: i128) -> Address {
+    let subscriber = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.usdc.address).mint(&subscriber, &(periods * PRICE));
+    subscriber
+}
+
+#[test]
+fn test_fees_accrue_over_time_and_pay_plan_contributors() {
+    let s = setup();
+    // Arrange: a plan over one study of `alice` and two studies of `bob`
+    let alice = Address::generate(&s.env);
+    let bob = Address::generate(&s.env);
+    let first = list_dataset(&s, b"dataset_a", vec![&s.env, register_study(&s, &alice, 1)]);
+    let second = list_dataset(
+        &s,
+        b"dataset_b",
+        vec![&s.env, register_study(&s, &bob, 2), register_study(&s, &bob, 3)],
+    );
+    let plan_id = s.subscriptions.create_plan(&vec![&s.env, first, second], &PRICE, &PERIOD);
+    let subscriber = funded_subscriber(&s, 1);
+
+    // Act: a third of the period elapses
+    assert_eq!(s.subscriptions.subscribe(&subscriber, &plan_id), 1_000 + PERIOD);
+    assert!(s.subscriptions.is_subscribed(&subscriber));
+    s.env.ledger().set_timestamp(1_000 + PERIOD / 3);
+    assert_eq!(s.subscriptions.accrue(&subscriber), PRICE / 3);
+    let paid = s.subscriptions.distribute(&plan_id);
+
+    // Assert: each study earns a third, 85% of it to its contributor
+    assert_eq!(paid, PRICE / 3);
+    assert_eq!(s.usdc.balance(&alice), 2_8333333);
+    assert_eq!(s.usdc.balance(&bob), 5_6666666);
+    assert_eq!(s.usdc.balance(&s.treasury), PRICE / 3 - 2_8333333 - 5_6666666);
+    assert_eq!(
+        s.subscriptions.try_distribute(&plan_id),
+        Err(Ok(Error::NothingToDistribute))
+    );
+
+    // Cancelling refunds the unused two thirds and ends access
+    assert_eq!(s.subscriptions.cancel(&subscriber), 2 * PRICE / 3);
+    assert_eq!(s.usdc.balance(&subscriber), 2 * PRICE / 3);
+    assert!(!s.subscriptions.is_subscribed(&subscriber));
+    assert_eq!(s.usdc.balance(&s.subscriptions.address), 0);
+    let plan = s.subscriptions.get_plan(&plan_id);
+    assert_eq!((plan.accrued, plan.distributed), (PRICE / 3, PRICE / 3));
+    assert_eq!(s.subscriptions.try_cancel(&subscriber), Err(Ok(Error::NotSubscribed)));
+}
+
+#[test]
+fn test_renewals_extend_access_until_the_plan_closes() {
+    let s = setup();
+    let dataset_id = list_dataset(
+        &s,
+        b"dataset_a",
+        vec![&s.env, register_study(&s, &Address::generate(&s.env), 1)],
+    );
+    let plan_id = s.subscriptions.create_plan(&vec![&s.env, dataset_id], &PRICE, &PERIOD);
+    let subscriber = funded_subscriber(&s, 4);
+    s.subscriptions.subscribe(&subscriber, &plan_id);
+
+    // Act: renew before expiry, then let both periods run out
+    assert_eq!(
+        s.subscriptions.try_subscribe(&subscriber, &plan_id),
+        Err(Ok(Error::AlreadySubscribed))
+    );
+    assert_eq!(s.subscriptions.renew(&subscriber), 1_000 + 2 * PERIOD);
+    s.env.ledger().set_timestamp(1_000 + 2 * PERIOD);
+
+    // Assert: access ended and both periods accrued in full
+    assert!(!s.subscriptions.is_subscribed(&subscriber));
+    assert_eq!(s.subscriptions.accrue(&subscriber), 2 * PRICE);
+    assert_eq!(s.subscriptions.get_subscription(&subscriber).unwrap().balance, 0);
+
+    // A renewal after expiry starts a new period now
+    s.env.ledger().set_timestamp(1_000 + 3 * PERIOD);
+    assert_eq!(s.subscriptions.renew(&subscriber), 1_000 + 4 * PERIOD);
+    assert!(s.subscriptions.is_subscribed(&subscriber));
+
+    // A closed plan takes no renewals or new subscribers
+    s.subscriptions.close_plan(&plan_id);
+    assert_eq!(s.subscriptions.try_renew(&subscriber), Err(Ok(Error::PlanClosed)));
+    assert_eq!(
+        s.subscriptions
+            .try_subscribe(&funded_subscriber(&s, 1), &plan_id),
+        Err(Ok(Error::PlanClosed))
+    );
+    assert!(s.subscriptions.is_subscribed(&subscriber));
+    assert_eq!(s.subscriptions.distribute(&plan_id), 2 * PRICE);
+}
+
+#[test]
+fn test_create_plan_validates_datasets() {
+    let s = setup();
+    let dataset_id = list_dataset(
+        &s,
+        b"dataset_a",
+        vec![&s.env, register_study(&s, &Address::generate(&s.env), 1)],
+    );
+    let datasets = vec![&s.env, dataset_id.clone()];
+
+    // Act & Assert
+    assert_eq!(
+        s.subscriptions.try_create_plan(&datasets, &0, &PERIOD),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.subscriptions.try_create_plan(&datasets, &PRICE, &0),
+        Err(Ok(Error::InvalidPeriod))
+    );
+    assert_eq!(
+        s.subscriptions.try_create_plan(&Vec::new(&s.env), &PRICE, &PERIOD),
+        Err(Ok(Error::InvalidPlan))
+    );
+    assert_eq!(
+        s.subscriptions
+            .try_create_plan(&vec![&s.env, dataset_id.clone(), dataset_id], &PRICE, &PERIOD),
+        Err(Ok(Error::InvalidPlan))
+    );
+    assert_eq!(
+        s.subscriptions.try_create_plan(
+            &vec![&s.env, Bytes::from_slice(&s.env, b"unknown")],
+            &PRICE,
+            &PERIOD
+        ),
+        Err(Ok(Error::DatasetNotFound))
+    );
+    assert_eq!(
+        s.subscriptions.try_subscribe(&funded_subscriber(&s, 1), &7),
+        Err(Ok(Error::PlanNotFound))
+    );
+    assert_eq!(
+        s.subscriptions.try_init(
+            &Address::generate(&s.env),
+            &s.usdc.address,
+            &s.usdc.address,
+            &s.marketplace.address,
+            &s.registry.address,
+        ),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
@@ -0,0 +1,208 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, IntoVal,
+    Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const TOKENS_KEY: Symbol = symbol_short!("TOKENS");
+const TOKEN_KEY: Symbol = symbol_short!("TOKEN");
+
+/// Maximum number of listed tokens
+pub const MAX_TOKENS: u32 = 20;
+
+/// Highest decimals accepted for a token
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Listed token and its metadata
+///
+/// - decimals: Decimals amounts of the token are expressed in
+/// - oracle: Price feed for the token, if any
+/// - paused: Whether payment paths currently refuse the token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub decimals: u32,
+    pub oracle: Option<Address>,
+    pub paused: bool,
+    pub added_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    TokenNotListed = 3,
+    TokenAlreadyListed = 4,
+    InvalidDecimals = 5,
+    TooManyTokens = 6,
+}
+
+/// Tokens accepted for payment across the platform
+///
+/// Governance lists each token with its metadata and can pause or delist
+/// it; the marketplace and splitter refuse payments in tokens for which
+/// `is_accepted` is false.
+#[contract]
+pub struct TokenAllowlist;
+
+#[contractimpl]
+impl TokenAllowlist {
+    /// Initialize the TokenAllowlist contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Governance address allowed to manage the list
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&TOKENS_KEY, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    /// List a token as accepted for payment
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Token contract address
+    /// * `decimals` - Decimals of the token
+    /// * `oracle` - Price feed for the token, if any
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidDecimals)` if above `MAX_DECIMALS` or different
+    ///   from what the token contract reports
+    /// * `Err(Error)` if already listed or the list is full
+    pub fn add_token(
+        env: Env,
+        token: Address,
+        decimals: u32,
+        oracle: Option<Address>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let storage = env.storage().instance();
+        if storage.has(&(TOKEN_KEY, token.clone())) {
+            return Err(Error::TokenAlreadyListed);
+        }
+        let mut tokens = Self::get_tokens(env.clone());
+        if tokens.len() >= MAX_TOKENS {
+            return Err(Error::TooManyTokens);
+        }
+        if decimals > MAX_DECIMALS {
+            return Err(Error::InvalidDecimals);
+        }
+        // Catch a mistyped value when the token reports its decimals
+        if let Ok(Ok(reported)) = env.try_invoke_contract::<u32, soroban_sdk::Error>(
+            &token,
+            &symbol_short!("decimals"),
+            ().into_val(&env),
+        ) {
+            if reported != decimals {
+                return Err(Error::InvalidDecimals);
+            }
+        }
+
+        let info = TokenInfo {
+            decimals,
+            oracle,
+            paused: false,
+            added_at: env.ledger().timestamp(),
+        };
+        storage.set(&(TOKEN_KEY, token.clone()), &info);
+        tokens.push_back(token.clone());
+        storage.set(&TOKENS_KEY, &tokens);
+
+        env.events().publish((symbol_short!("Listed"), token), decimals);
+        Ok(())
+    }
+
+    /// Delist a token
+    pub fn remove_token(env: Env, token: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::get_token(env.clone(), token.clone())?;
+        let storage = env.storage().instance();
+        storage.remove(&(TOKEN_KEY, token.clone()));
+        let mut tokens = Self::get_tokens(env.clone());
+        if let Some(index) = tokens.first_index_of(&token) {
+            tokens.remove(index);
+        }
+        storage.set(&TOKENS_KEY, &tokens);
+
+        env.events().publish((symbol_short!("Delisted"), token), ());
+        Ok(())
+    }
+
+    /// Pause or resume payments in a listed token
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Token contract address
+    /// * `paused` - Whether payment paths refuse the token
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::TokenNotListed)` if the token is not listed
+    pub fn set_token_paused(env: Env, token: Address, paused: bool) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut info = Self::get_token(env.clone(), token.clone())?;
+        info.paused = paused;
+        env.storage().instance().set(&(TOKEN_KEY, token.clone()), &info);
+
+        env.events().publish((symbol_short!("Paused"), token), paused);
+        Ok(())
+    }
+
+    /// Replace the price feed of a listed token
+    pub fn set_oracle(env: Env, token: Address, oracle: Option<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut info = Self::get_token(env.clone(), token.clone())?;
+        info.oracle = oracle;
+        env.storage().instance().set(&(TOKEN_KEY, token), &info);
+        Ok(())
+    }
+
+    /// Check whether payments in a token are accepted
+    ///
+    /// # Returns
+    /// * `true` if the token is listed and not paused
+    pub fn is_accepted(env: Env, token: Address) -> bool {
+        Self::get_token(env, token).is_ok_and(|info| !info.paused)
+    }
+
+    /// Get a listed token's metadata
+    pub fn get_token(env: Env, token: Address) -> Result<TokenInfo, Error> {
+        env.storage()
+            .instance()
+            .get(&(TOKEN_KEY, token))
+            .ok_or(Error::TokenNotListed)
+    }
+
+    /// Get the listed tokens, paused ones included
+    pub fn get_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&TOKENS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::Address as AddressTestUtils, token, vec, Address, Bytes, BytesN, Env,
+};
+use token_allowlist::{Error, TokenAllowlist, TokenAllowlistClient};
+
+/// Dataset price used by the tests (20 USDC)
+const PRICE: i128 = 20_0000000;
+
+/// Decimals of the Stellar Asset Contract used as USDC
+const USDC_DECIMALS: u32 = 7;
+
+struct Setup<'a> {
+    env: Env,
+    allowlist: TokenAllowlistClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: revenue_splitter::RevenueSplitterClient<'a>,
+    usdc: token::Client<'a>,
+    buyer: Address,
+    dataset_id: Bytes,
+}
+
+/// Helper: Deploy the core contracts checking a TokenAllowlist, list a
+/// one-study dataset and fund a buyer
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = revenue_splitter::RevenueSplitterClient::new(
+        &env,
+        &env.register_contract(None, revenue_splitter::RevenueSplitter),
+    );
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    splitter.init(&Address::generate(&env), &usdc_id, &Address::generate(&env));
+    marketplace.set_revenue_splitter(&splitter.address);
+    marketplace.set_study_registry(&registry.address);
+
+    let allowlist = TokenAllowlistClient::new(&env, &env.register_contract(None, TokenAllowlist));
+    allowlist.init(&Address::generate(&env));
+    marketplace.set_token_allowlist(&allowlist.address);
+    splitter.set_token_allowlist(&allowlist.address);
+
+    let study_hash = [1u8; 32];
+    let study = BytesN::from_array(&env, &study_hash);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study, &contributor);
+    registry.register_study(
+        &study,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study, &attestation, &contributor),
+        &contributor,
+    );
+    let dataset_id = Bytes::from_slice(&env, b"dataset_001");
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &dataset_id,
+        &vec![&env, Bytes::from_array(&env, &study_hash)],
+        &PRICE,
+        &None,
+    );
+
+    let buyer = Address::generate(&env);
+    let usdc = token::Client::new(&env, &usdc_id);
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&buyer, &(2 * PRICE));
+    usdc.approve(&buyer, &marketplace.address, &(2 * PRICE), &1_000);
+
+    Setup {
+        env,
+        allowlist,
+        marketplace,
+        splitter,
+        usdc,
+        buyer,
+        dataset_id,
+    }
+}
+
+#[test]
+fn test_purchases_need_a_listed_token() {
+    let s = setup();
+
+    // Act & Assert: nothing is listed yet
+    assert!(!s.allowlist.is_accepted(&s.usdc.address));
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&s.dataset_id, &s.buyer, &None),
+        Err(Ok(dataset_marketplace::Error::TokenNotAccepted))
+    );
+    assert!(!s.marketplace.simulate_purchase(&s.dataset_id, &s.buyer).can_purchase);
+
+    s.allowlist.add_token(&s.usdc.address, &USDC_DECIMALS, &None);
+    s.marketplace.purchase_dataset(&s.dataset_id, &s.buyer, &None);
+    assert_eq!(s.usdc.balance(&s.buyer), PRICE);
+}
+
+#[test]
+fn test_paused_token_is_refused_until_resumed() {
+    let s = setup();
+    s.allowlist.add_token(&s.usdc.address, &USDC_DECIMALS, &None);
+
+    // Act
+    s.allowlist.set_token_paused(&s.usdc.address, &true);
+
+    // Assert: the splitter refuses direct payouts as well
+    assert!(!s.allowlist.is_accepted(&s.usdc.address));
+    assert!(s.allowlist.get_token(&s.usdc.address).paused);
+    assert_eq!(
+        s.splitter.try_payout_purchase(
+            &s.buyer,
+            &s.dataset_id,
+            &vec![&s.env, Address::generate(&s.env)],
+            &PRICE,
+        ),
+        Err(Ok(revenue_splitter::Error::TokenNotAccepted))
+    );
+    assert_eq!(
+        s.marketplace.try_purchase_dataset(&s.dataset_id, &s.buyer, &None),
+        Err(Ok(dataset_marketplace::Error::TokenNotAccepted))
+    );
+
+    s.allowlist.set_token_paused(&s.usdc.address, &false);
+    s.marketplace.purchase_dataset(&s.dataset_id, &s.buyer, &None);
+}
+
+#[test]
+fn test_token_metadata_is_validated_and_managed() {
+    let s = setup();
+    let token = s.usdc.address.clone();
+
+    // Act & Assert: decimals must match what the token reports
+    assert_eq!(
+        s.allowlist.try_add_token(&token, &6, &None),
+        Err(Ok(Error::InvalidDecimals))
+    );
+    assert_eq!(
+        s.allowlist.try_add_token(&Address::generate(&s.env), &19, &None),
+        Err(Ok(Error::InvalidDecimals))
+    );
+    let oracle = Address::generate(&s.env);
+    s.allowlist.add_token(&token, &USDC_DECIMALS, &Some(oracle.clone()));
+    assert_eq!(
+        s.allowlist.try_add_token(&token, &USDC_DECIMALS, &None),
+        Err(Ok(Error::TokenAlreadyListed))
+    );
+    let info = s.allowlist.get_token(&token);
+    assert_eq!((info.decimals, info.oracle), (USDC_DECIMALS, Some(oracle)));
+
+    s.allowlist.set_oracle(&token, &None);
+    assert_eq!(s.allowlist.get_token(&token).oracle, None);
+    assert_eq!(s.allowlist.get_tokens(), vec![&s.env, token.clone()]);
+
+    // Delisted tokens are no longer accepted
+    s.allowlist.remove_token(&token);
+    assert!(!s.allowlist.is_accepted(&token));
+    assert!(s.allowlist.get_tokens().is_empty());
+    assert_eq!(
+        s.allowlist.try_remove_token(&token),
+        Err(Ok(Error::TokenNotListed))
+    );
+}
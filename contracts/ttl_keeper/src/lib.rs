@@ -0,0 +1,343 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+/// Storage keys
+const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const TARGETS_KEY: Symbol = symbol_short!("TARGETS");
+const REWARD_KEY: Symbol = symbol_short!("REWARD");
+const LAST_PAID_KEY: Symbol = symbol_short!("LASTPAID");
+const KEY_COUNT_KEY: Symbol = symbol_short!("KEY_CNT");
+const KEY_KEY: Symbol = symbol_short!("KEY");
+const KNOWN_KEY: Symbol = symbol_short!("KNOWN");
+
+/// TTL policy, in ledgers
+///
+/// Matches the core contracts: entries are extended to ~30 days once they
+/// drop below ~7 days.
+pub const TTL_THRESHOLD: u32 = 120_960;
+pub const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Minimum ledgers between two rewarded runs (~1 day)
+pub const REWARD_INTERVAL: u32 = 17_280;
+
+/// Maximum keys processed by a single `run` call
+pub const MAX_BATCH_SIZE: u32 = 100;
+
+/// Contracts whose storage is kept alive
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Targets {
+    pub registry: Address,
+    pub marketplace: Address,
+    pub splitter: Address,
+}
+
+/// Record the keeper extends
+///
+/// - Study: StudyRegistry study by hash
+/// - Dataset: DatasetMarketplace dataset by ID
+/// - Purchase: DatasetMarketplace purchase by (dataset_id, buyer)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CriticalKey {
+    Study(BytesN<32>),
+    Dataset(Bytes),
+    Purchase(Bytes, Address),
+}
+
+/// Reward configuration
+///
+/// - token: Token the reward is paid in (USDC)
+/// - amount: Paid to the caller of a run, at most once per REWARD_INTERVAL
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reward {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Outcome of a `run` call
+///
+/// - processed: Registered keys visited
+/// - found: Keys whose record still exists and was extended
+/// - reward: Reward paid to the caller (0 if none was due)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunReport {
+    pub processed: u32,
+    pub found: u32,
+    pub reward: i128,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidAmount = 3,
+    InvalidBatch = 4,
+}
+
+#[contract]
+pub struct TtlKeeper;
+
+#[contractimpl]
+impl TtlKeeper {
+    /// Initialize the TtlKeeper contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to configure the reward
+    /// * `targets` - Registry, marketplace and splitter to keep alive
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, admin: Address, targets: Targets) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&ADMIN_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&ADMIN_KEY, &admin);
+        storage.set(&TARGETS_KEY, &targets);
+        storage.set(&KEY_COUNT_KEY, &0u32);
+        Ok(())
+    }
+
+    /// Set the reward paid to callers of `run`
+    ///
+    /// The reward is paid from this contract's balance of `token`; fund it
+    /// with a plain token transfer. Runs stay possible (unrewarded) when the
+    /// balance is too low.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - Token the reward is paid in
+    /// * `amount` - Reward per run (0 disables rewards)
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::InvalidAmount)` if `amount` is negative
+    pub fn set_reward(env: Env, token: Address, amount: i128) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&REWARD_KEY, &Reward { token, amount });
+        Ok(())
+    }
+
+    /// Get the reward configuration, if any
+    pub fn get_reward(env: Env) -> Option<Reward> {
+        env.storage().instance().get(&REWARD_KEY)
+    }
+
+    /// Register records to keep alive
+    ///
+    /// Requires the admin's authorization: every registered key is visited
+    /// by rewarded runs, so the list is curated. Keys that are already
+    /// registered are skipped.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `keys` - Records to register
+    ///
+    /// # Returns
+    /// * `Ok(u32)` with the number of newly registered keys
+    /// * `Err(Error::InvalidBatch)` if more than MAX_BATCH_SIZE keys are given
+    pub fn register_keys(env: Env, keys: Vec<CriticalKey>) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+        if keys.len() > MAX_BATCH_SIZE {
+            return Err(Error::InvalidBatch);
+        }
+
+        let instance = env.storage().instance();
+        let persistent = env.storage().persistent();
+        let mut count: u32 = instance.get(&KEY_COUNT_KEY).ok_or(Error::NotInitialized)?;
+        let mut added = 0u32;
+
+        for key in keys.iter() {
+            let known_key = (KNOWN_KEY, key.clone());
+            if persistent.has(&known_key) {
+                continue;
+            }
+            persistent.set(&known_key, &true);
+            persistent.set(&(KEY_KEY, count), &key);
+            count += 1;
+            added += 1;
+        }
+
+        instance.set(&KEY_COUNT_KEY, &count);
+        env.events()
+            .publish((symbol_short!("KeysAdded"),), (added, count));
+        Ok(added)
+    }
+
+    /// Get the number of registered keys
+    pub fn key_count(env: Env) -> u32 {
+        env.storage().instance().get(&KEY_COUNT_KEY).unwrap_or(0)
+    }
+
+    /// Get a registered key by index
+    pub fn get_key(env: Env, index: u32) -> Option<CriticalKey> {
+        env.storage().persistent().get(&(KEY_KEY, index))
+    }
+
+    /// Extend the TTL of a batch of registered keys
+    ///
+    /// Callable by anyone. Flow:
+    /// 1. Extend the instance and code of the three core contracts
+    /// 2. Batch the keys in `start..start + limit` by kind and call the
+    ///    owning contract's extend_*_ttl entry point once per kind
+    /// 3. Extend this contract's own entries
+    /// 4. Pay the reward if one is configured, funded and due, and the run
+    ///    visited at least one registered key
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - Address receiving the reward (must authorize the call)
+    /// * `start` - Index of the first key to process
+    /// * `limit` - Maximum number of keys to process
+    ///
+    /// # Returns
+    /// * `Ok(RunReport)` describing the run
+    /// * `Err(Error)` if not initialized or the batch is too large
+    pub fn run(env: Env, caller: Address, start: u32, limit: u32) -> Result<RunReport, Error> {
+        caller.require_auth();
+        if limit > MAX_BATCH_SIZE {
+            return Err(Error::InvalidBatch);
+        }
+
+        // ============================================
+        // 1. EXTEND CORE CONTRACT INSTANCES AND CODE
+        // ============================================
+        let instance = env.storage().instance();
+        let targets: Targets = instance.get(&TARGETS_KEY).ok_or(Error::NotInitialized)?;
+        let count: u32 = instance.get(&KEY_COUNT_KEY).unwrap_or(0);
+
+        for contract in [&targets.registry, &targets.marketplace, &targets.splitter] {
+            env.deployer()
+                .extend_ttl(contract.clone(), TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+
+        // ============================================
+        // 2. EXTEND REGISTERED KEYS
+        // ============================================
+        let persistent = env.storage().persistent();
+        let end = start.saturating_add(limit).min(count);
+        let mut studies = Vec::new(&env);
+        let mut datasets = Vec::new(&env);
+        let mut purchases = Vec::new(&env);
+
+        for index in start..end {
+            let key: CriticalKey = match persistent.get(&(KEY_KEY, index)) {
+                Some(key) => key,
+                None => continue,
+            };
+            persistent.extend_ttl(&(KEY_KEY, index), TTL_THRESHOLD, TTL_EXTEND_TO);
+            persistent.extend_ttl(&(KNOWN_KEY, key.clone()), TTL_THRESHOLD, TTL_EXTEND_TO);
+            match key {
+                CriticalKey::Study(study_id) => studies.push_back(study_id),
+                CriticalKey::Dataset(dataset_id) => datasets.push_back(dataset_id),
+                CriticalKey::Purchase(dataset_id, buyer) => {
+                    purchases.push_back((dataset_id, buyer))
+                }
+            }
+        }
+
+        let mut found = 0u32;
+        if !studies.is_empty() {
+            found += env.invoke_contract::<u32>(
+                &targets.registry,
+                &Symbol::new(&env, "extend_study_ttl"),
+                (studies,).into_val(&env),
+            );
+        }
+        if !datasets.is_empty() {
+            found += env.invoke_contract::<u32>(
+                &targets.marketplace,
+                &Symbol::new(&env, "extend_dataset_ttl"),
+                (datasets,).into_val(&env),
+            );
+        }
+        if !purchases.is_empty() {
+            found += env.invoke_contract::<u32>(
+                &targets.marketplace,
+                &Symbol::new(&env, "extend_purchase_ttl"),
+                (purchases,).into_val(&env),
+            );
+        }
+
+        // ============================================
+        // 3. EXTEND OWN ENTRIES
+        // ============================================
+        instance.extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        // ============================================
+        // 4. PAY REWARD
+        // ============================================
+        // A run outside the registered keys does no work worth paying for
+        let processed = end.saturating_sub(start);
+        let reward = if processed > 0 {
+            Self::pay_reward(&env, &caller)
+        } else {
+            0
+        };
+
+        let report = RunReport {
+            processed,
+            found,
+            reward,
+        };
+        env.events()
+            .publish((symbol_short!("Kept"), caller), report.clone());
+        Ok(report)
+    }
+
+    /// Pay the configured reward to `caller` if it is funded and due
+    ///
+    /// # Returns
+    /// * Reward paid (0 if none)
+    fn pay_reward(env: &Env, caller: &Address) -> i128 {
+        let storage = env.storage().instance();
+        let reward: Reward = match storage.get(&REWARD_KEY) {
+            Some(reward) => reward,
+            None => return 0,
+        };
+
+        let now = env.ledger().sequence();
+        if let Some(last_paid) = storage.get::<_, u32>(&LAST_PAID_KEY) {
+            if now < last_paid.saturating_add(REWARD_INTERVAL) {
+                return 0;
+            }
+        }
+
+        let token_client = token::Client::new(env, &reward.token);
+        let contract_address = env.current_contract_address();
+        if reward.amount == 0 || token_client.balance(&contract_address) < reward.amount {
+            return 0;
+        }
+
+        token_client.transfer(&contract_address, caller, &reward.amount);
+        storage.set(&LAST_PAID_KEY, &now);
+        reward.amount
+    }
+
+    /// Require the admin's authorization
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+}
@@ -0,0 +1,232 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as AddressTestUtils, Deployer as _, Ledger},
+    token, vec, Address, Bytes, BytesN, Env,
+};
+use ttl_keeper::{
+    CriticalKey, Error, Targets, TtlKeeper, TtlKeeperClient, REWARD_INTERVAL, TTL_EXTEND_TO,
+};
+
+/// Reward paid per run in the tests (0.1 USDC)
+const REWARD: i128 = 1_000_000;
+
+struct Setup<'a> {
+    env: Env,
+    keeper: TtlKeeperClient<'a>,
+    registry: study_registry::StudyRegistryClient<'a>,
+    marketplace: dataset_marketplace::DatasetMarketplaceClient<'a>,
+    splitter: Address,
+    usdc: token::Client<'a>,
+}
+
+/// Helper: Deploy the core contracts with one study and one dataset, and
+/// an initialized TtlKeeper funded for two rewards
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 100;
+        li.max_entry_ttl = 2 * TTL_EXTEND_TO;
+    });
+
+    let registry = study_registry::StudyRegistryClient::new(
+        &env,
+        &env.register_contract(None, study_registry::StudyRegistry),
+    );
+    study_registry::testutils::install_verifying_key(&registry);
+    study_registry::testutils::install_attestation_root(&registry);
+    let marketplace = dataset_marketplace::DatasetMarketplaceClient::new(
+        &env,
+        &env.register_contract(None, dataset_marketplace::DatasetMarketplace),
+    );
+    marketplace.init(&Address::generate(&env));
+    let splitter = env.register_contract(None, revenue_splitter::RevenueSplitter);
+
+    let study_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let contributor = Address::generate(&env);
+    let attestation = study_registry::testutils::attestation(&env, &study_hash, &contributor);
+    registry.register_study(
+        &study_hash,
+        &attestation,
+        &study_registry::ProofKind::Groth16,
+        &study_registry::testutils::zk_proof(&env, &study_hash, &attestation, &contributor),
+        &contributor,
+    );
+    marketplace.register_dataset(
+        &Address::generate(&env),
+        &Bytes::from_slice(&env, b"dataset_001"),
+        &vec![&env, Bytes::from_array(&env, &[1u8; 32])],
+        &10_0000000,
+        &None,
+    );
+
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let keeper = TtlKeeperClient::new(&env, &env.register_contract(None, TtlKeeper));
+    keeper.init(
+        &Address::generate(&env),
+        &Targets {
+            registry: registry.address.clone(),
+            marketplace: marketplace.address.clone(),
+            splitter: splitter.clone(),
+        },
+    );
+    keeper.set_reward(&usdc_id, &REWARD);
+    token::StellarAssetClient::new(&env, &usdc_id).mint(&keeper.address, &(2 * REWARD));
+
+    Setup {
+        usdc: token::Client::new(&env, &usdc_id),
+        env,
+        keeper,
+        registry,
+        marketplace,
+        splitter,
+    }
+}
+
+/// Helper: Critical keys for the study and dataset created in `setup`, plus
+/// a study that was never registered
+fn critical_keys(env: &Env) -> soroban_sdk::Vec<CriticalKey> {
+    vec![
+        env,
+        CriticalKey::Study(BytesN::from_array(env, &[1u8; 32])),
+        CriticalKey::Dataset(Bytes::from_slice(env, b"dataset_001")),
+        CriticalKey::Study(BytesN::from_array(env, &[9u8; 32])),
+    ]
+}
+
+#[test]
+fn test_register_keys_skips_duplicates() {
+    let s = setup();
+
+    // Act
+    let added = s.keeper.register_keys(&critical_keys(&s.env));
+    let added_again = s.keeper.register_keys(&critical_keys(&s.env));
+
+    // Assert
+    assert_eq!((added, added_again), (3, 0));
+    assert_eq!(s.keeper.key_count(), 3);
+    assert_eq!(
+        s.keeper.get_key(&1),
+        Some(CriticalKey::Dataset(Bytes::from_slice(
+            &s.env,
+            b"dataset_001"
+        )))
+    );
+}
+
+#[test]
+fn test_run_extends_core_contracts() {
+    let s = setup();
+    s.keeper.register_keys(&critical_keys(&s.env));
+    let caller = Address::generate(&s.env);
+
+    // Act
+    let report = s.keeper.run(&caller, &0, &10);
+
+    // Assert: the missing study is visited but not found
+    assert_eq!(report.processed, 3);
+    assert_eq!(report.found, 2);
+    for contract in [&s.registry.address, &s.marketplace.address, &s.splitter] {
+        assert_eq!(
+            s.env.deployer().get_contract_instance_ttl(contract),
+            TTL_EXTEND_TO
+        );
+        assert_eq!(
+            s.env.deployer().get_contract_code_ttl(contract),
+            TTL_EXTEND_TO
+        );
+    }
+}
+
+#[test]
+fn test_run_processes_a_window_of_keys() {
+    let s = setup();
+    s.keeper.register_keys(&critical_keys(&s.env));
+    let caller = Address::generate(&s.env);
+
+    // Act
+    let report = s.keeper.run(&caller, &1, &10);
+
+    // Assert
+    assert_eq!(report.processed, 2);
+    assert_eq!(report.found, 1);
+    assert_eq!(
+        s.keeper.try_run(&caller, &0, &101),
+        Err(Ok(Error::InvalidBatch))
+    );
+}
+
+#[test]
+fn test_register_keys_requires_the_admin() {
+    let s = setup();
+
+    // Act & Assert: without the admin's authorization nothing is registered
+    s.env.set_auths(&[]);
+    assert!(s.keeper.try_register_keys(&critical_keys(&s.env)).is_err());
+    assert_eq!(s.keeper.key_count(), 0);
+}
+
+#[test]
+fn test_reward_is_paid_at_most_once_per_interval() {
+    let s = setup();
+    s.keeper.register_keys(&critical_keys(&s.env));
+    let caller = Address::generate(&s.env);
+
+    // Act
+    let first = s.keeper.run(&caller, &0, &10);
+    let second = s.keeper.run(&caller, &0, &10);
+    s.env
+        .ledger()
+        .with_mut(|li| li.sequence_number += REWARD_INTERVAL);
+    let third = s.keeper.run(&caller, &0, &10);
+    s.env
+        .ledger()
+        .with_mut(|li| li.sequence_number += REWARD_INTERVAL);
+    let unfunded = s.keeper.run(&caller, &0, &10);
+
+    // Assert: rewards stop once the keeper runs out of funds
+    assert_eq!(
+        (first.reward, second.reward, third.reward, unfunded.reward),
+        (REWARD, 0, REWARD, 0)
+    );
+    assert_eq!(s.usdc.balance(&caller), 2 * REWARD);
+}
+
+#[test]
+fn test_run_without_keys_is_not_rewarded() {
+    let s = setup();
+    s.keeper.register_keys(&critical_keys(&s.env));
+    let caller = Address::generate(&s.env);
+
+    // Act: a window past the registered keys visits nothing
+    let empty = s.keeper.run(&caller, &3, &10);
+    let useful = s.keeper.run(&caller, &0, &10);
+
+    // Assert: only the run that kept keys alive is paid
+    assert_eq!((empty.processed, empty.reward), (0, 0));
+    assert_eq!((useful.processed, useful.reward), (3, REWARD));
+    assert_eq!(s.usdc.balance(&caller), REWARD);
+}
+
+#[test]
+fn test_init_and_reward_validation() {
+    let s = setup();
+
+    // Act & Assert
+    let targets = Targets {
+        registry: s.registry.address.clone(),
+        marketplace: s.marketplace.address.clone(),
+        splitter: s.splitter.clone(),
+    };
+    assert_eq!(
+        s.keeper.try_init(&Address::generate(&s.env), &targets),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(
+        s.keeper.try_set_reward(&s.usdc.address, &-1),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
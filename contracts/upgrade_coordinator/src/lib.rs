@@ -0,0 +1,226 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    IntoVal, Symbol, Vec,
+};
+
+/// Storage keys
+const GOVERNANCE_KEY: Symbol = symbol_short!("GOV");
+const TARGETS_KEY: Symbol = symbol_short!("TARGETS");
+const LAST_KEY: Symbol = symbol_short!("LAST");
+
+/// Core contracts upgraded together
+///
+/// Order matters: contracts are paused and upgraded registry first, then
+/// splitter, then marketplace, and unpaused in reverse order.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Targets {
+    pub registry: Address,
+    pub splitter: Address,
+    pub marketplace: Address,
+}
+
+/// Upgrade plan
+///
+/// - registry_wasm / splitter_wasm / marketplace_wasm: Uploaded WASM hashes
+/// - registry_version / splitter_version / marketplace_version: Interface
+///   version each contract must report after migrate(); the contracts
+///   version their interfaces independently
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradePlan {
+    pub registry_wasm: BytesN<32>,
+    pub splitter_wasm: BytesN<32>,
+    pub marketplace_wasm: BytesN<32>,
+    pub registry_version: u32,
+    pub splitter_version: u32,
+    pub marketplace_version: u32,
+}
+
+/// Record of the last applied upgrade
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeRecord {
+    pub plan: UpgradePlan,
+    pub applied_at: u64,
+}
+
+/// Error types for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    TargetsNotSet = 3,
+    VersionMismatch = 4,
+    VersionNotIncreasing = 5,
+}
+
+#[contract]
+pub struct UpgradeCoordinator;
+
+#[contractimpl]
+impl UpgradeCoordinator {
+    /// Initialize the UpgradeCoordinator contract
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `governance` - Address allowed to configure and run upgrades
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(Error::AlreadyInitialized)` if called twice
+    pub fn init(env: Env, governance: Address) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&GOVERNANCE_KEY) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&GOVERNANCE_KEY, &governance);
+        Ok(())
+    }
+
+    /// Set the contracts upgraded together
+    ///
+    /// Each target must have this contract set as its upgrade coordinator.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `targets` - StudyRegistry, RevenueSplitter and DatasetMarketplace
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    pub fn set_targets(env: Env, targets: Targets) -> Result<(), Error> {
+        Self::require_governance(&env)?;
+        env.storage().instance().set(&TARGETS_KEY, &targets);
+        Ok(())
+    }
+
+    /// Get the contracts upgraded together
+    pub fn get_targets(env: Env) -> Result<Targets, Error> {
+        env.storage()
+            .instance()
+            .get(&TARGETS_KEY)
+            .ok_or(Error::TargetsNotSet)
+    }
+
+    /// Upgrade all core contracts atomically
+    ///
+    /// Pauses every target, swaps in the new WASM, runs each contract's
+    /// migrate(), checks the reported interface versions and unpauses. Any
+    /// failure reverts the whole transaction, so contracts are never left
+    /// running mismatched code. No contract's version may go back, and at
+    /// least one must move forward.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `plan` - WASM hashes and expected interface version of each contract
+    ///
+    /// # Returns
+    /// * `Ok(UpgradeRecord)` if every contract was upgraded
+    /// * `Err(Error)` if a version check fails
+    pub fn execute_upgrade(env: Env, plan: UpgradePlan) -> Result<UpgradeRecord, Error> {
+        Self::require_governance(&env)?;
+        let targets = Self::get_targets(env.clone())?;
+        if let Some(last) = Self::last_upgrade(env.clone()) {
+            let versions = [
+                (plan.registry_version, last.plan.registry_version),
+                (plan.splitter_version, last.plan.splitter_version),
+                (plan.marketplace_version, last.plan.marketplace_version),
+            ];
+            if versions.iter().any(|(next, prev)| next < prev)
+                || versions.iter().all(|(next, prev)| next == prev)
+            {
+                return Err(Error::VersionNotIncreasing);
+            }
+        }
+
+        let contracts = Vec::from_array(
+            &env,
+            [
+                (targets.registry, plan.registry_wasm.clone(), plan.registry_version),
+                (targets.splitter, plan.splitter_wasm.clone(), plan.splitter_version),
+                (targets.marketplace, plan.marketplace_wasm.clone(), plan.marketplace_version),
+            ],
+        );
+
+        // ============================================
+        // 1. PAUSE ALL TARGETS
+        // ============================================
+        for (contract, _, _) in contracts.iter() {
+            Self::call(&env, &contract, "pause", ());
+        }
+
+        // ============================================
+        // 2. APPLY NEW WASM AND MIGRATE
+        // ============================================
+        for (contract, wasm_hash, expected) in contracts.iter() {
+            Self::call(&env, &contract, "upgrade", (wasm_hash,));
+            let migrated: u32 =
+                env.invoke_contract(&contract, &Symbol::new(&env, "migrate"), ().into_val(&env));
+            if migrated != expected {
+                return Err(Error::VersionMismatch);
+            }
+        }
+
+        // ============================================
+        // 3. CHECK INTERFACE VERSIONS
+        // ============================================
+        for (contract, _, expected) in contracts.iter() {
+            let version: u32 = env.invoke_contract(
+                &contract,
+                &Symbol::new(&env, "interface_version"),
+                ().into_val(&env),
+            );
+            if version != expected {
+                return Err(Error::VersionMismatch);
+            }
+        }
+
+        // ============================================
+        // 4. UNPAUSE IN REVERSE ORDER
+        // ============================================
+        for i in (0..contracts.len()).rev() {
+            let (contract, _, _) = contracts.get_unchecked(i);
+            Self::call(&env, &contract, "unpause", ());
+        }
+
+        let record = UpgradeRecord {
+            plan,
+            applied_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&LAST_KEY, &record);
+
+        env.events().publish(
+            (symbol_short!("Upgraded"),),
+            record.clone(),
+        );
+
+        Ok(record)
+    }
+
+    /// Get the last applied upgrade, if any
+    pub fn last_upgrade(env: Env) -> Option<UpgradeRecord> {
+        env.storage().instance().get(&LAST_KEY)
+    }
+
+    fn call<A: IntoVal<Env, Vec<soroban_sdk::Val>>>(
+        env: &Env,
+        contract: &Address,
+        func: &str,
+        args: A,
+    ) {
+        env.invoke_contract::<()>(contract, &Symbol::new(env, func), args.into_val(env));
+    }
+
+    fn require_governance(env: &Env) -> Result<Address, Error> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&GOVERNANCE_KEY)
+            .ok_or(Error::NotInitialized)?;
+        governance.require_auth();
+        Ok(governance)
+    }
+}
@@ -0,0 +1,264 @@
+#![cfg(test)]
+
+use dataset_marketplace::{DatasetMarketplace, DatasetMarketplaceClient};
+use revenue_splitter::{RevenueSplitter, RevenueSplitterClient};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, testutils::Address as AddressTestUtils, Address, Bytes,
+    BytesN, Env,
+};
+use study_registry::{StudyRegistry, StudyRegistryClient};
+use upgrade_coordinator::{
+    Error, Targets, UpgradeCoordinator, UpgradeCoordinatorClient, UpgradePlan,
+};
+
+/// Core contract stand-in exposing the upgrade hooks
+///
+/// The "upgrade" stores the WASM hash; the first byte of the hash is used
+/// as the interface version reported after migration.
+#[contract]
+pub struct MockCore;
+
+#[contractimpl]
+impl MockCore {
+    pub fn pause(env: Env) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &true);
+    }
+
+    pub fn unpause(env: Env) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &false);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WASM"), &new_wasm_hash);
+    }
+
+    pub fn migrate(env: Env) -> u32 {
+        Self::interface_version(env)
+    }
+
+    pub fn interface_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get::<_, BytesN<32>>(&symbol_short!("WASM"))
+            .map(|hash| hash.to_array()[0] as u32)
+            .unwrap_or(1)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    coordinator: UpgradeCoordinatorClient<'a>,
+    registry: MockCoreClient<'a>,
+    splitter: MockCoreClient<'a>,
+    marketplace: MockCoreClient<'a>,
+}
+
+/// Helper: Deploy three mock core contracts and a configured coordinator
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = env.register_contract(None, MockCore);
+    let splitter = env.register_contract(None, MockCore);
+    let marketplace = env.register_contract(None, MockCore);
+
+    let coordinator_id = env.register_contract(None, UpgradeCoordinator);
+    let coordinator = UpgradeCoordinatorClient::new(&env, &coordinator_id);
+    coordinator.init(&Address::generate(&env));
+    coordinator.set_targets(&Targets {
+        registry: registry.clone(),
+        splitter: splitter.clone(),
+        marketplace: marketplace.clone(),
+    });
+
+    Setup {
+        registry: MockCoreClient::new(&env, &registry),
+        splitter: MockCoreClient::new(&env, &splitter),
+        marketplace: MockCoreClient::new(&env, &marketplace),
+        coordinator,
+        env,
+    }
+}
+
+/// Helper: A WASM hash whose mock interface version is `version`
+fn wasm(env: &Env, version: u8) -> BytesN<32> {
+    let mut bytes = [0u8; 32];
+    bytes[0] = version;
+    BytesN::from_array(env, &bytes)
+}
+
+/// Helper: Plan upgrading every contract to `version`
+fn plan(env: &Env, version: u8) -> UpgradePlan {
+    UpgradePlan {
+        registry_wasm: wasm(env, version),
+        splitter_wasm: wasm(env, version),
+        marketplace_wasm: wasm(env, version),
+        registry_version: version as u32,
+        splitter_version: version as u32,
+        marketplace_version: version as u32,
+    }
+}
+
+#[test]
+fn test_execute_upgrade_upgrades_all_and_unpauses() {
+    let s = setup();
+
+    // Act
+    let record = s.coordinator.execute_upgrade(&plan(&s.env, 2));
+
+    // Assert
+    assert_eq!(record.plan, plan(&s.env, 2));
+    for core in [&s.registry, &s.splitter, &s.marketplace] {
+        assert_eq!(core.interface_version(), 2);
+        assert!(!core.is_paused());
+    }
+    assert_eq!(s.coordinator.last_upgrade(), Some(record));
+}
+
+#[test]
+fn test_version_mismatch_reverts_everything() {
+    let s = setup();
+
+    // Arrange: splitter build reports an older interface
+    let mut mismatched = plan(&s.env, 2);
+    mismatched.splitter_wasm = wasm(&s.env, 1);
+
+    // Act
+    let result = s.coordinator.try_execute_upgrade(&mismatched);
+
+    // Assert: registry upgrade and pauses were rolled back
+    assert_eq!(result, Err(Ok(Error::VersionMismatch)));
+    assert_eq!(s.registry.interface_version(), 1);
+    assert!(!s.registry.is_paused());
+    assert_eq!(s.coordinator.last_upgrade(), None);
+}
+
+#[test]
+fn test_version_must_increase() {
+    let s = setup();
+
+    // Arrange
+    s.coordinator.execute_upgrade(&plan(&s.env, 3));
+
+    // Act
+    let result = s.coordinator.try_execute_upgrade(&plan(&s.env, 2));
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::VersionNotIncreasing)));
+}
+
+#[test]
+fn test_targets_report_their_own_versions() {
+    let s = setup();
+
+    // Arrange: only the marketplace moves to a new interface
+    let mut next = plan(&s.env, 2);
+    next.marketplace_wasm = wasm(&s.env, 3);
+    next.marketplace_version = 3;
+
+    // Act
+    s.coordinator.execute_upgrade(&next);
+
+    // Assert
+    assert_eq!(s.registry.interface_version(), 2);
+    assert_eq!(s.marketplace.interface_version(), 3);
+
+    // Act: redeploying the same versions is not an upgrade, and no
+    // contract may go back
+    let mut back = plan(&s.env, 4);
+    back.marketplace_wasm = wasm(&s.env, 2);
+    back.marketplace_version = 2;
+
+    // Assert
+    assert_eq!(
+        s.coordinator.try_execute_upgrade(&next),
+        Err(Ok(Error::VersionNotIncreasing))
+    );
+    assert_eq!(
+        s.coordinator.try_execute_upgrade(&back),
+        Err(Ok(Error::VersionNotIncreasing))
+    );
+}
+
+#[test]
+fn test_execute_upgrade_on_core_contracts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // Arrange: the real core contracts, coordinated by one coordinator
+    let admin = Address::generate(&env);
+    let coordinator = UpgradeCoordinatorClient::new(
+        &env,
+        &env.register_contract(None, UpgradeCoordinator),
+    );
+    coordinator.init(&admin);
+    let registry = StudyRegistryClient::new(&env, &env.register_contract(None, StudyRegistry));
+    registry.init(&admin);
+    registry.set_upgrade_coordinator(&coordinator.address);
+    let splitter =
+        RevenueSplitterClient::new(&env, &env.register_contract(None, RevenueSplitter));
+    splitter.init(&admin, &Address::generate(&env), &Address::generate(&env));
+    splitter.set_upgrade_coordinator(&coordinator.address);
+    let marketplace =
+        DatasetMarketplaceClient::new(&env, &env.register_contract(None, DatasetMarketplace));
+    marketplace.init(&admin);
+    marketplace.set_upgrade_coordinator(&coordinator.address);
+    coordinator.set_targets(&Targets {
+        registry: registry.address.clone(),
+        splitter: splitter.address.clone(),
+        marketplace: marketplace.address.clone(),
+    });
+
+    // The test host keeps running native contracts after an upgrade to
+    // empty code, so the contracts report their current versions
+    let code = env.deployer().upload_contract_wasm(Bytes::new(&env));
+    let upgrade = UpgradePlan {
+        registry_wasm: code.clone(),
+        splitter_wasm: code.clone(),
+        marketplace_wasm: code,
+        registry_version: registry.interface_version(),
+        splitter_version: splitter.interface_version(),
+        marketplace_version: marketplace.interface_version(),
+    };
+
+    // Act: a single version for every contract does not match
+    let mut shared = upgrade.clone();
+    shared.registry_version = upgrade.marketplace_version;
+    shared.splitter_version = upgrade.marketplace_version;
+    let result = coordinator.try_execute_upgrade(&shared);
+
+    // Assert
+    assert_eq!(result, Err(Ok(Error::VersionMismatch)));
+
+    // Act
+    let record = coordinator.execute_upgrade(&upgrade);
+
+    // Assert
+    assert_eq!(record.plan, upgrade);
+    assert!(!registry.is_paused());
+    assert!(!splitter.is_paused());
+    assert!(!marketplace.is_paused());
+}
+
+#[test]
+fn test_init_twice_fails() {
+    let s = setup();
+
+    assert_eq!(
+        s.coordinator.try_init(&Address::generate(&s.env)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
@@ -100,7 +100,8 @@ const buildPurchaseTransaction = async (
       contract.call(
         'purchase_dataset',
         xdr.ScVal.scvBytes(datasetIdBytes),
-        xdr.ScVal.scvAddress(buyerScAddress)
+        xdr.ScVal.scvAddress(buyerScAddress),
+        xdr.ScVal.scvVoid() // promo_code: none
       )
     )
     .setTimeout(30)